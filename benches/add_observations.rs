@@ -0,0 +1,70 @@
+//! Batch vs per-entity `add_observations`
+//!
+//! Run with `cargo bench --bench add_observations [-- <entities>]`
+//! (default 2000 entities, three observations each).
+
+use std::time::{Duration, Instant};
+
+use memory_graph::types::{Entity, Observation};
+use memory_graph::KnowledgeBase;
+
+fn setup(dir: &tempfile::TempDir, entities: usize) -> KnowledgeBase {
+    let path = dir.path().join("memory.jsonl");
+    let kb = KnowledgeBase::with_file_path(path.to_string_lossy().to_string());
+    kb.create_entities(
+        (0..entities)
+            .map(|i| Entity::new(format!("Module{}", i), "Module".to_string()))
+            .collect(),
+    )
+    .unwrap();
+    kb
+}
+
+fn observations(entities: usize) -> Vec<Observation> {
+    (0..entities)
+        .map(|i| Observation {
+            entity_name: format!("Module{}", i),
+            contents: vec![
+                format!("Owner: team-{}", i % 7),
+                format!("Lines: {}", i * 13),
+                "Reviewed".to_string(),
+            ],
+        })
+        .collect()
+}
+
+fn time(run: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    run();
+    start.elapsed()
+}
+
+fn main() {
+    let entities: usize = std::env::args()
+        .skip(1)
+        .find(|arg| !arg.starts_with("--"))
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(2000);
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let kb = setup(&dir, entities);
+    let naive = time(|| {
+        for obs in observations(entities) {
+            kb.add_observations(vec![obs]).unwrap();
+        }
+    });
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let kb = setup(&dir, entities);
+    let batch = time(|| {
+        kb.add_observations_batch(observations(entities)).unwrap();
+    });
+
+    println!("add_observations on {} entities", entities);
+    println!("  per-entity loop: {:>10.1?}", naive);
+    println!("  batch:           {:>10.1?}", batch);
+    println!(
+        "  speedup:         {:>9.1}x",
+        naive.as_secs_f64() / batch.as_secs_f64()
+    );
+}
@@ -1,20 +1,34 @@
 //! HTTP server setup with Axum
+//!
+//! All routes can be moved under a base path with `MEMORY_API_PREFIX`
+//! (e.g. `/memory/team-a` serves `/memory/team-a/api/graph`), so several
+//! instances can run behind one gateway. By default there is no prefix.
+//!
+//! `/ready` answers 503 until the graph is loaded at startup (with the replay
+//! progress while the event log is being replayed), and 200 after that.
 
+use std::env;
 use std::sync::Arc;
 use axum::{
+    http::StatusCode,
+    middleware,
+    response::IntoResponse,
     routing::{get, post},
-    Router,
+    Json, Router,
 };
+use serde_json::json;
 use tower_http::cors::{Any, CorsLayer};
 
+use crate::event_store::replay_status;
 use crate::knowledge_base::KnowledgeBase;
-use super::rest::{entities, graph, relations, search};
+use super::rest::{admin_only, with_caller, backup, changes, entities, event_store, graph, relations, search, tools};
 use super::sse::handler::{
     login_handler, me_handler, mcp_request_handler, refresh_handler,
     server_info_handler, sse_handler, SseState,
 };
 use super::sse::JwtAuth;
-use super::websocket::{handler::ws_handler, state::AppState};
+use super::websocket::handler::{ws_auth, ws_handler, WsAuth};
+use super::websocket::state::AppState;
 
 /// Create the Axum router with all endpoints
 ///
@@ -24,13 +38,44 @@ pub fn create_router(state: Arc<AppState>, kb_sync: Arc<KnowledgeBase>) -> Route
     create_router_with_auth(state, kb_sync, None, false)
 }
 
-/// Create router with optional JWT authentication
+/// Create router with optional JWT authentication, under `MEMORY_API_PREFIX`
 pub fn create_router_with_auth(
     state: Arc<AppState>,
     kb_sync: Arc<KnowledgeBase>,
     jwt_auth: Option<Arc<JwtAuth>>,
     require_auth: bool,
 ) -> Router {
+    let prefix = api_prefix_from_env();
+    create_router_with_prefix(state, kb_sync, jwt_auth, require_auth, &prefix)
+}
+
+/// Base path from `MEMORY_API_PREFIX`, normalized (empty for none)
+pub fn api_prefix_from_env() -> String {
+    env::var("MEMORY_API_PREFIX")
+        .map(|prefix| normalize_prefix(&prefix))
+        .unwrap_or_default()
+}
+
+/// `"gateway/a/"` -> `"/gateway/a"`; `""` and `"/"` -> `""`
+fn normalize_prefix(prefix: &str) -> String {
+    let trimmed = prefix.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", trimmed)
+    }
+}
+
+/// Create router with optional JWT authentication and every route under `prefix`
+pub fn create_router_with_prefix(
+    state: Arc<AppState>,
+    kb_sync: Arc<KnowledgeBase>,
+    jwt_auth: Option<Arc<JwtAuth>>,
+    require_auth: bool,
+    prefix: &str,
+) -> Router {
+    let prefix = normalize_prefix(prefix);
+
     // CORS configuration - allow all origins for development
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -42,29 +87,68 @@ pub fn create_router_with_auth(
         kb_sync,
         state.event_tx.clone(),
         Arc::clone(&state.sequence_counter),
-    );
+    )
+    .with_slow_client_policy(state.slow_client_policy);
+
+    // The WebSocket upgrade checks the same tokens as SSE/MCP
+    let ws_auth_state = WsAuth::new(jwt_auth.clone(), require_auth);
+    let admin_auth_state = ws_auth_state.clone();
 
     // Add JWT auth if configured
     if let Some(auth) = jwt_auth {
         sse_state = sse_state.with_jwt_auth(auth, require_auth);
     }
 
-    let sse_state = Arc::new(sse_state);
+    // The SSE endpoint event advertises the POST path, prefix included
+    let sse_state = Arc::new(sse_state.with_base_path(prefix.clone()));
 
     // Build main router with AppState
     let main_router = Router::new()
-        // WebSocket endpoint
-        .route("/ws", get(ws_handler))
-        // Health check
-        .route("/health", get(health_check))
         // REST API endpoints
         .route("/api/graph", get(graph::get_graph))
         .route("/api/graph/stats", get(graph::get_stats))
+        .route("/api/graph/stream", get(graph::stream_graph))
+        .route("/api/graph/subgraph", get(graph::get_subgraph))
+        .route(
+            "/api/graph/reload",
+            post(graph::reload_graph)
+                .route_layer(middleware::from_fn_with_state(admin_auth_state.clone(), admin_only)),
+        )
         .route("/api/events/replay", get(graph::get_events_replay))
         .route("/api/entities", get(entities::list_entities))
         .route("/api/entities/:name", get(entities::get_entity))
         .route("/api/relations", get(relations::list_relations))
         .route("/api/search", get(search::search_nodes))
+        .route("/api/changes", get(changes::list_changes))
+        .route("/api/event-store/archives", get(event_store::list_archives))
+        .route(
+            "/api/event-store/archives/cleanup",
+            post(event_store::cleanup_archives)
+                .route_layer(middleware::from_fn_with_state(admin_auth_state.clone(), admin_only)),
+        )
+        .route("/api/tools", get(tools::list_tools))
+        .route("/api/tool-stats", get(tools::tool_stats))
+        .route(
+            "/api/backup",
+            get(backup::download_backup)
+                .route_layer(middleware::from_fn_with_state(admin_auth_state.clone(), admin_only)),
+        )
+        .route(
+            "/api/restore",
+            post(backup::restore_backup)
+                .layer(backup::restore_body_limit())
+                .route_layer(middleware::from_fn_with_state(admin_auth_state, admin_only)),
+        )
+        // Every REST request runs as its caller, for entity ACLs
+        .route_layer(middleware::from_fn_with_state(ws_auth_state.clone(), with_caller))
+        // WebSocket endpoint
+        .route(
+            "/ws",
+            get(ws_handler).route_layer(middleware::from_fn_with_state(ws_auth_state, ws_auth)),
+        )
+        // Health check
+        .route("/health", get(health_check))
+        .route("/ready", get(ready_check))
         .with_state(state);
 
     // Build SSE router with SseState
@@ -79,8 +163,31 @@ pub fn create_router_with_auth(
         .route("/auth/me", get(me_handler))
         .with_state(sse_state);
 
-    // Merge routers and apply CORS
-    main_router.merge(sse_router).layer(cors)
+    // Merge routers, relocate under the prefix and apply CORS
+    let router = main_router.merge(sse_router);
+    let router = if prefix.is_empty() {
+        router
+    } else {
+        Router::new().nest(&prefix, router)
+    };
+    router.layer(cors)
+}
+
+/// Router served while the knowledge base is still loading
+///
+/// Only `/health` and `/ready` answer, so orchestrators can watch a long
+/// replay instead of seeing connection errors. `/ready` stays 503 for as long
+/// as this router serves, covering the legacy load, snapshot load and seeding
+/// as well as the replay itself.
+pub fn startup_router(prefix: &str) -> Router {
+    let router = Router::new()
+        .route("/health", get(health_check))
+        .route("/ready", get(loading_check));
+    if prefix.is_empty() {
+        router
+    } else {
+        Router::new().nest(prefix, router)
+    }
 }
 
 /// Health check endpoint
@@ -88,18 +195,49 @@ async fn health_check() -> &'static str {
     "OK"
 }
 
+/// Readiness endpoint while loading: 503, with progress during a replay
+async fn loading_check() -> impl IntoResponse {
+    let status = replay_status();
+    let body = if status.is_replaying() {
+        let progress = status.progress();
+        json!({
+            "status": "replaying",
+            "replayed": progress.replayed,
+            "total": progress.total
+        })
+    } else {
+        json!({ "status": "loading" })
+    };
+    (StatusCode::SERVICE_UNAVAILABLE, Json(body))
+}
+
+/// Readiness endpoint once the knowledge base is loaded
+async fn ready_check() -> impl IntoResponse {
+    (StatusCode::OK, Json(json!({ "status": "ready" })))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::knowledge_base::KnowledgeBase;
+    use crate::api::websocket::events::GraphEvent;
     use axum::body::Body;
     use axum::http::Request;
+    use tempfile::TempDir;
     use tower::util::ServiceExt;
 
+    /// Knowledge base stored in a fresh temporary directory
+    fn temp_kb() -> (TempDir, Arc<KnowledgeBase>) {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("memory.jsonl");
+        let kb = KnowledgeBase::for_testing(path.display().to_string(), "server".to_string());
+        (dir, Arc::new(kb))
+    }
+
     #[tokio::test]
     async fn test_health_check() {
         // Single KB instance - shared by both AppState and router
-        let kb = Arc::new(KnowledgeBase::new());
+        let (_dir, kb) = temp_kb();
         let state = Arc::new(AppState::new(Arc::clone(&kb)));
         let app = create_router(state, kb);
 
@@ -110,4 +248,656 @@ mod tests {
 
         assert_eq!(response.status(), 200);
     }
+
+    #[tokio::test]
+    async fn test_ready_check() {
+        let ready = |app: Router| async move {
+            let response = app
+                .oneshot(Request::builder().uri("/memory/ready").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            (status, json["status"].as_str().unwrap().to_string())
+        };
+
+        // Not ready while loading, whether or not an event log is replaying
+        let (status, state) = ready(startup_router("/memory")).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(state == "loading" || state == "replaying", "{}", state);
+
+        let (_dir, kb) = temp_kb();
+        let state = Arc::new(AppState::new(Arc::clone(&kb)));
+        let app = create_router_with_prefix(state, kb, None, false, "/memory");
+        assert_eq!(ready(app).await, (StatusCode::OK, "ready".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_list_archives() {
+        let (_dir, kb) = temp_kb();
+        let state = Arc::new(AppState::new(Arc::clone(&kb)));
+        let app = create_router(state, kb);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/event-store/archives")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_list_tools_catalog() {
+        let (_dir, kb) = temp_kb();
+        let state = Arc::new(AppState::new(Arc::clone(&kb)));
+        let app = create_router(state, kb);
+
+        let response = app
+            .oneshot(Request::builder().uri("/api/tools").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let tools = json["data"]["tools"].as_array().unwrap();
+        let access = |name: &str| {
+            tools
+                .iter()
+                .find(|t| t["name"] == name)
+                .map(|t| t["access"].clone())
+                .unwrap()
+        };
+        assert_eq!(access("read_graph"), "read");
+        assert_eq!(access("create_entities"), "write");
+        // Only admins may reload, through POST /api/graph/reload
+        assert!(tools.iter().all(|t| t["name"] != "reload_graph"));
+    }
+
+    #[tokio::test]
+    async fn test_routes_under_prefix() {
+        use futures::StreamExt;
+
+        let (_dir, kb) = temp_kb();
+        let state = Arc::new(AppState::new(Arc::clone(&kb)));
+        let app = create_router_with_prefix(state, kb, None, false, "team-a/");
+        let get = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        let response = app.clone().oneshot(get("/team-a/health")).await.unwrap();
+        assert_eq!(response.status(), 200);
+        let response = app.clone().oneshot(get("/health")).await.unwrap();
+        assert_eq!(response.status(), 404);
+
+        // The endpoint event points clients at the prefixed POST path
+        let response = app.oneshot(get("/team-a/mcp/sse")).await.unwrap();
+        assert_eq!(response.status(), 200);
+        let mut body = response.into_body().into_data_stream();
+        let first = body.next().await.unwrap().unwrap();
+        let first = String::from_utf8_lossy(&first);
+        assert!(first.contains("data: /team-a/mcp/sse"), "{}", first);
+
+        assert_eq!(normalize_prefix("/"), "");
+        assert_eq!(normalize_prefix(" /a/b/ "), "/a/b");
+    }
+
+    #[tokio::test]
+    async fn test_responses_carry_graph_version() {
+        let path = format!("target/test_graph_version_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(path.clone(), "tester".to_string()));
+        let state = Arc::new(AppState::new(Arc::clone(&kb)));
+        let app = create_router(state, Arc::clone(&kb));
+
+        let graph_version = |app: Router| async move {
+            let response = app
+                .oneshot(Request::builder().uri("/api/graph").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            json["graph_version"].as_u64().unwrap()
+        };
+
+        let before = graph_version(app.clone()).await;
+        assert_eq!(before, kb.version());
+
+        kb.create_entities(vec![crate::types::Entity::new(
+            "Alice".to_string(),
+            "Person".to_string(),
+        )])
+        .unwrap();
+        let after = graph_version(app).await;
+        assert!(after > before);
+        assert_eq!(after, kb.version());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_graph_stream_is_reloadable() {
+        use crate::types::{Entity, Relation};
+
+        let path = format!("target/test_graph_stream_{}.jsonl", std::process::id());
+        let copy_path = format!("target/test_graph_stream_copy_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(path.clone(), "tester".to_string()));
+        // Long enough that the stream outgrows its channel buffer
+        let observation = "Owned by the platform team ".repeat(8);
+        kb.create_entities(
+            (0..2000)
+                .map(|i| {
+                    let observations = vec![observation.clone()];
+                    Entity::with_observations(format!("E{}", i), "Module".to_string(), observations)
+                })
+                .collect(),
+        )
+        .unwrap();
+        kb.create_relations(vec![Relation::new(
+            "E0".to_string(),
+            "E1".to_string(),
+            "depends_on".to_string(),
+        )])
+        .unwrap();
+        let state = Arc::new(AppState::new(Arc::clone(&kb)));
+        let app = create_router(state, Arc::clone(&kb));
+
+        let response = app
+            .oneshot(Request::builder().uri("/api/graph/stream").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.headers()["x-graph-version"],
+            kb.version().to_string().as_str()
+        );
+
+        // A client that has not read the body yet does not block writers
+        let writer = Arc::clone(&kb);
+        let write = tokio::task::spawn_blocking(move || {
+            writer.create_entities(vec![Entity::new("Late".to_string(), "Module".to_string())])
+        });
+        let written = tokio::time::timeout(std::time::Duration::from_secs(5), write).await;
+        assert!(written.expect("write blocked by the stream").unwrap().is_ok());
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(body.len() > 8 * 64 * 1024, "expected more chunks than the channel holds");
+        assert_eq!(body.iter().filter(|&&b| b == b'\n').count(), 2001);
+
+        std::fs::write(&copy_path, &body).unwrap();
+        let copy = KnowledgeBase::for_testing(copy_path.clone(), "tester".to_string());
+        assert_eq!(copy.reload_from_disk().unwrap(), (2000, 1));
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&copy_path);
+    }
+
+    #[tokio::test]
+    async fn test_websocket_requires_token() {
+        let mut auth = JwtAuth::new("test-secret-key-that-is-at-least-32-characters-long");
+        auth.add_user("alice", "password123", vec!["read".to_string()])
+            .unwrap();
+        let user = auth.authenticate("alice", "password123").unwrap().clone();
+        let token = auth.generate_tokens(&user).unwrap().access_token;
+
+        let path = format!("target/test_websocket_token_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(path.clone(), "tester".to_string()));
+        let state = Arc::new(AppState::new(Arc::clone(&kb)));
+        let app = create_router_with_prefix(state, kb, Some(Arc::new(auth)), true, "");
+        let upgrade = |uri: &str, bearer: Option<&str>| {
+            let mut request = Request::builder()
+                .uri(uri)
+                .header("Connection", "upgrade")
+                .header("Upgrade", "websocket")
+                .header("Sec-WebSocket-Version", "13")
+                .header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==");
+            if let Some(bearer) = bearer {
+                request = request.header("Authorization", format!("Bearer {}", bearer));
+            }
+            request.body(Body::empty()).unwrap()
+        };
+
+        let response = app.clone().oneshot(upgrade("/ws", None)).await.unwrap();
+        assert_eq!(response.status(), 401);
+        let response = app
+            .clone()
+            .oneshot(upgrade("/ws?token=not-a-jwt", None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 401);
+
+        // A valid token gets past auth; the upgrade itself needs a real connection
+        let response = app
+            .clone()
+            .oneshot(upgrade(&format!("/ws?token={}", token), None))
+            .await
+            .unwrap();
+        assert_ne!(response.status(), 401);
+        let response = app.oneshot(upgrade("/ws", Some(&token))).await.unwrap();
+        assert_ne!(response.status(), 401);
+
+        let _ = std::fs::remove_file(&path);
+    }
+    #[tokio::test]
+    async fn test_backup_is_admin_only() {
+        let mut auth = JwtAuth::new("test-secret-key-that-is-at-least-32-characters-long");
+        auth.add_user("alice", "password123", vec!["write".to_string()])
+            .unwrap();
+        auth.add_user("admin", "admin", vec!["*".to_string()]).unwrap();
+        let token = |name: &str, password: &str| {
+            let user = auth.authenticate(name, password).unwrap().clone();
+            auth.generate_tokens(&user).unwrap().access_token
+        };
+        let (alice, admin) = (token("alice", "password123"), token("admin", "admin"));
+
+        let path = format!("target/test_backup_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(path.clone(), "server".to_string()));
+        kb.create_entities(vec![crate::types::Entity::new(
+            "Auth".to_string(),
+            "Module".to_string(),
+        )])
+        .unwrap();
+        let state = Arc::new(AppState::new(Arc::clone(&kb)));
+        let app = create_router_with_prefix(
+            Arc::clone(&state),
+            Arc::clone(&kb),
+            Some(Arc::new(auth)),
+            false,
+            "",
+        );
+        let backup = |bearer: Option<&str>| {
+            let mut request = Request::builder().uri("/api/backup");
+            if let Some(bearer) = bearer {
+                request = request.header("Authorization", format!("Bearer {}", bearer));
+            }
+            request.body(Body::empty()).unwrap()
+        };
+
+        assert_eq!(app.clone().oneshot(backup(None)).await.unwrap().status(), 401);
+        assert_eq!(app.clone().oneshot(backup(Some(&alice))).await.unwrap().status(), 403);
+        let response = app.clone().oneshot(backup(Some(&admin))).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["x-backup-mode"], "legacy");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, std::fs::read(&path).unwrap());
+
+        let restore = |bearer: &str, query: &str| {
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/restore{}", query))
+                .header("Authorization", format!("Bearer {}", bearer))
+                .body(Body::from(body.clone()))
+                .unwrap()
+        };
+        kb.delete_entities(vec!["Auth".to_string()]).unwrap();
+        let response = app.clone().oneshot(restore(&alice, "?confirm=true")).await.unwrap();
+        assert_eq!(response.status(), 403);
+        assert_eq!(app.clone().oneshot(restore(&admin, "")).await.unwrap().status(), 400);
+        let response = app.clone().oneshot(restore(&admin, "?confirm=true")).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(kb.graph.read().unwrap().entities.len(), 1);
+        let response = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&response).unwrap();
+        let _ = std::fs::remove_file(report["data"]["safetyBackup"].as_str().unwrap());
+
+        // Without JWT auth there is no admin, so no backups
+        let app = create_router(state, kb);
+        assert_eq!(app.oneshot(backup(None)).await.unwrap().status(), 403);
+
+        let _ = std::fs::remove_file(&path);
+    }
+    #[tokio::test]
+    async fn test_maintenance_endpoints_are_admin_only() {
+        let mut auth = JwtAuth::new("test-secret-key-that-is-at-least-32-characters-long");
+        auth.add_user("alice", "password123", vec!["write".to_string()])
+            .unwrap();
+        auth.add_user("admin", "admin", vec!["*".to_string()]).unwrap();
+        let token = |name: &str, password: &str| {
+            let user = auth.authenticate(name, password).unwrap().clone();
+            auth.generate_tokens(&user).unwrap().access_token
+        };
+        let (alice, admin) = (token("alice", "password123"), token("admin", "admin"));
+
+        let path = format!("target/test_archive_cleanup_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(path.clone(), "server".to_string()));
+        let state = Arc::new(AppState::new(Arc::clone(&kb)));
+        let app = create_router_with_prefix(
+            Arc::clone(&state),
+            Arc::clone(&kb),
+            Some(Arc::new(auth)),
+            false,
+            "",
+        );
+        let cleanup = |bearer: Option<&str>| {
+            let mut request = Request::builder()
+                .method("POST")
+                .uri("/api/event-store/archives/cleanup?keep=1");
+            if let Some(bearer) = bearer {
+                request = request.header("Authorization", format!("Bearer {}", bearer));
+            }
+            request.body(Body::empty()).unwrap()
+        };
+
+        assert_eq!(app.clone().oneshot(cleanup(None)).await.unwrap().status(), 401);
+        assert_eq!(app.clone().oneshot(cleanup(Some(&alice))).await.unwrap().status(), 403);
+        assert_eq!(app.clone().oneshot(cleanup(Some(&admin))).await.unwrap().status(), 200);
+
+        let reload = |bearer: Option<&str>| {
+            let mut request = Request::builder().method("POST").uri("/api/graph/reload");
+            if let Some(bearer) = bearer {
+                request = request.header("Authorization", format!("Bearer {}", bearer));
+            }
+            request.body(Body::empty()).unwrap()
+        };
+        assert_eq!(app.clone().oneshot(reload(None)).await.unwrap().status(), 401);
+        assert_eq!(app.clone().oneshot(reload(Some(&alice))).await.unwrap().status(), 403);
+        let mut events = crate::api::websocket::init_broadcaster(1024).subscribe();
+        assert_eq!(app.oneshot(reload(Some(&admin))).await.unwrap().status(), 200);
+
+        // Clients are told to drop their view of the graph
+        let mut resynced = false;
+        while let Ok(msg) = events.try_recv() {
+            resynced |= matches!(msg.event, GraphEvent::Resync { ref reason, .. } if reason == "reload");
+        }
+        assert!(resynced);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_mcp_tool_calls_attributed_to_token_user() {
+        let mut auth = JwtAuth::new("test-secret-key-that-is-at-least-32-characters-long");
+        auth.add_user("alice", "password123", vec!["write".to_string()])
+            .unwrap();
+        let user = auth.authenticate("alice", "password123").unwrap().clone();
+        let token = auth.generate_tokens(&user).unwrap().access_token;
+
+        let path = format!("target/test_mcp_attribution_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(path.clone(), "server".to_string()));
+        let state = Arc::new(AppState::new(Arc::clone(&kb)));
+        let app =
+            create_router_with_prefix(state, Arc::clone(&kb), Some(Arc::new(auth)), false, "");
+        let create = |name: &str, bearer: Option<&str>| {
+            let body = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {
+                    "name": "create_entities",
+                    "arguments": { "entities": [{ "name": name, "entityType": "Module" }] }
+                }
+            });
+            let mut request = Request::builder()
+                .method("POST")
+                .uri("/mcp")
+                .header("Content-Type", "application/json");
+            if let Some(bearer) = bearer {
+                request = request.header("Authorization", format!("Bearer {}", bearer));
+            }
+            request.body(Body::from(body.to_string())).unwrap()
+        };
+
+        let response = app.clone().oneshot(create("Auth", Some(&token))).await.unwrap();
+        assert_eq!(response.status(), 200);
+        let response = app.oneshot(create("Billing", None)).await.unwrap();
+        assert_eq!(response.status(), 200);
+
+        let graph = kb.read_graph(None, None).unwrap();
+        let created_by: Vec<&str> = graph.entities.iter().map(|e| e.created_by.as_str()).collect();
+        assert_eq!(created_by, vec!["alice", "server"]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+    #[tokio::test]
+    async fn test_iso_timestamps_on_request() {
+        let path = format!("target/test_iso_timestamps_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(path.clone(), "tester".to_string()));
+        kb.create_entities(vec![crate::types::Entity::new(
+            "Auth".to_string(),
+            "Module".to_string(),
+        )])
+        .unwrap();
+        kb.graph.write().unwrap().entities[0].created_at = 1_709_287_200;
+        let state = Arc::new(AppState::new(Arc::clone(&kb)));
+        let app = create_router(state, Arc::clone(&kb));
+
+        let entity = |app: Router, uri: &'static str| async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            json["data"][0].clone()
+        };
+
+        let plain = entity(app.clone(), "/api/entities").await;
+        assert_eq!(plain["createdAt"], 1_709_287_200);
+        assert!(plain.get("createdAtIso").is_none());
+
+        let iso = entity(app, "/api/entities?iso_timestamps=true").await;
+        assert_eq!(iso["createdAt"], 1_709_287_200);
+        assert_eq!(iso["createdAtIso"], "2024-03-01T10:00:00Z");
+        assert!(iso["updatedAtIso"].as_str().unwrap().ends_with('Z'));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_acls_apply_to_anonymous_http_callers() {
+        let mut auth = JwtAuth::new("test-secret-key-that-is-at-least-32-characters-long");
+        auth.add_user("carol", "password123", vec!["finance".to_string()])
+            .unwrap();
+        let user = auth.authenticate("carol", "password123").unwrap().clone();
+        let token = auth.generate_tokens(&user).unwrap().access_token;
+        let auth = Arc::new(auth);
+
+        let path = format!("target/test_http_acl_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(path.clone(), "server".to_string()));
+        let mut payroll = crate::types::Entity::new("Payroll".to_string(), "Module".to_string());
+        payroll.acl = vec!["finance".to_string()];
+        kb.create_entities(vec![payroll]).unwrap();
+        let state = Arc::new(AppState::new(Arc::clone(&kb)));
+        let app = create_router_with_prefix(
+            Arc::clone(&state),
+            Arc::clone(&kb),
+            Some(Arc::clone(&auth)),
+            false,
+            "",
+        );
+        let get = |uri: &str, bearer: Option<&str>| {
+            let mut request = Request::builder().uri(uri);
+            if let Some(bearer) = bearer {
+                request = request.header("Authorization", format!("Bearer {}", bearer));
+            }
+            request.body(Body::empty()).unwrap()
+        };
+        let entities = |app: Router, uri: &'static str, bearer: Option<String>| async move {
+            let response = app.oneshot(get(uri, bearer.as_deref())).await.unwrap();
+            assert_eq!(response.status(), 200);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            json["total"].as_u64().unwrap()
+        };
+
+        // Without a token the restricted entity is left out
+        assert_eq!(entities(app.clone(), "/api/entities", None).await, 0);
+        assert_eq!(entities(app.clone(), "/api/graph", None).await, 0);
+        assert_eq!(entities(app.clone(), "/api/search?q=Payroll", None).await, 0);
+        let response = app.clone().oneshot(get("/api/entities/Payroll", None)).await.unwrap();
+        assert_eq!(response.status(), 404);
+        let response = app.clone().oneshot(get("/api/graph/stream", None)).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(body.is_empty());
+
+        // The token's permission satisfies the ACL; a bad token is refused
+        assert_eq!(entities(app.clone(), "/api/entities", Some(token.clone())).await, 1);
+        let response = app.clone().oneshot(get("/api/graph", Some("not-a-jwt"))).await.unwrap();
+        assert_eq!(response.status(), 401);
+
+        let read_graph = |bearer: Option<&str>| {
+            let body = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": { "name": "read_graph", "arguments": {} }
+            });
+            let mut request = Request::builder()
+                .method("POST")
+                .uri("/mcp")
+                .header("Content-Type", "application/json");
+            if let Some(bearer) = bearer {
+                request = request.header("Authorization", format!("Bearer {}", bearer));
+            }
+            request.body(Body::from(body.to_string())).unwrap()
+        };
+        let response = app.clone().oneshot(read_graph(None)).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(!String::from_utf8_lossy(&body).contains("Payroll"));
+        let response = app.oneshot(read_graph(Some(&token))).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("Payroll"));
+
+        // With auth required, requests without a token are refused
+        let app = create_router_with_prefix(state, kb, Some(auth), true, "");
+        let response = app.clone().oneshot(get("/api/graph", None)).await.unwrap();
+        assert_eq!(response.status(), 401);
+        assert_eq!(app.oneshot(read_graph(None)).await.unwrap().status(), 401);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_promote_runs_as_caller() {
+        use futures::StreamExt;
+
+        let mut auth = JwtAuth::new("test-secret-key-that-is-at-least-32-characters-long");
+        auth.add_user("bob", "password123", vec!["write".to_string()])
+            .unwrap();
+        auth.add_user("carol", "password123", vec!["finance".to_string()])
+            .unwrap();
+        let token = |name: &str| {
+            let user = auth.authenticate(name, "password123").unwrap().clone();
+            auth.generate_tokens(&user).unwrap().access_token
+        };
+        let (bob, carol) = (token("bob"), token("carol"));
+
+        let path = format!("target/test_promote_caller_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(path.clone(), "server".to_string()));
+        let mut payroll = crate::types::Entity::new("Payroll".to_string(), "Module".to_string());
+        payroll.acl = vec!["finance".to_string()];
+        kb.create_entities(vec![payroll]).unwrap();
+        let state = Arc::new(AppState::new(Arc::clone(&kb)));
+        let app =
+            create_router_with_prefix(state, Arc::clone(&kb), Some(Arc::new(auth)), false, "");
+
+        // Open a scoped session and read its id from the welcome event
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/mcp/sse?scoped=true")
+                    .header("Authorization", format!("Bearer {}", carol))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let mut body = response.into_body().into_data_stream();
+        let mut received = String::new();
+        while !received.contains("\"session_id\":\"") {
+            received.push_str(&String::from_utf8_lossy(&body.next().await.unwrap().unwrap()));
+        }
+        let session_id = received
+            .split("\"session_id\":\"")
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+            .unwrap()
+            .to_string();
+
+        let call = |method: &str, params: serde_json::Value, bearer: &str| {
+            let body = serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "method": method, "params": params
+            });
+            Request::builder()
+                .method("POST")
+                .uri("/mcp")
+                .header("Content-Type", "application/json")
+                .header("Mcp-Session-Id", session_id.as_str())
+                .header("Authorization", format!("Bearer {}", bearer))
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let rpc = |app: Router, request: Request<Body>| async move {
+            let response = app.oneshot(request).await.unwrap();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+
+        let observe = serde_json::json!({
+            "name": "add_observations",
+            "arguments": { "observations": [
+                { "entityName": "Payroll", "contents": ["Runs monthly"] }
+            ]}
+        });
+        let added = rpc(app.clone(), call("tools/call", observe, &carol)).await;
+        assert!(added.get("error").is_none(), "{}", added);
+
+        // Bob may not change Payroll, so he may not promote changes to it either
+        let promote = serde_json::json!({});
+        let denied = rpc(app.clone(), call("session/promote_to_shared", promote.clone(), &bob)).await;
+        assert!(denied["error"].to_string().contains("Access denied"), "{}", denied);
+        assert!(kb.graph.read().unwrap().entities[0].observations.is_empty());
+
+        let promoted = rpc(app, call("session/promote_to_shared", promote, &carol)).await;
+        assert!(promoted.get("error").is_none(), "{}", promoted);
+        let graph = kb.graph.read().unwrap();
+        assert_eq!(graph.entities[0].observations, vec!["Runs monthly"]);
+        assert_eq!(graph.entities[0].updated_by, "carol");
+        drop(graph);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_session_is_rejected() {
+        let path = format!("target/test_unknown_session_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(path.clone(), "server".to_string()));
+        let state = Arc::new(AppState::new(Arc::clone(&kb)));
+        let app = create_router_with_prefix(state, Arc::clone(&kb), None, false, "");
+
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "tools/call",
+            "params": { "name": "create_entities", "arguments": { "entities": [
+                { "name": "Auth", "entityType": "Module", "observations": [] }
+            ]}}
+        });
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/mcp")
+                    .header("Content-Type", "application/json")
+                    .header("Mcp-Session-Id", "sess_gone")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error["id"], 7);
+        assert!(error["error"].to_string().contains("sess_gone"), "{}", error);
+
+        // The write was not redirected to the shared graph
+        assert!(kb.graph.read().unwrap().entities.is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
 }
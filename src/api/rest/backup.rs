@@ -0,0 +1,123 @@
+//! Backup download and restore endpoints
+
+use std::sync::Arc;
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{DefaultBodyLimit, Query, State},
+    http::{header, HeaderName, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+
+use super::{ApiError, ApiResponse};
+use crate::api::websocket::state::AppState;
+use crate::types::InvalidParams;
+use crate::utils::time::current_timestamp;
+
+/// GET /api/backup - Download the persisted state as one file (admin only)
+///
+/// The `memory.jsonl` file in legacy mode; in Event Sourcing mode a full
+/// snapshot of the current state followed by the event log (see
+/// `KnowledgeBase::write_backup`). The backup is written to memory on a
+/// blocking thread while the graph read lock is held, so it is consistent,
+/// and only sent once the lock is released, so a slow client never holds up
+/// writers. The mode is reported in the `x-backup-mode` header.
+pub async fn download_backup(State(state): State<Arc<AppState>>) -> Response {
+    let written = tokio::task::spawn_blocking(move || {
+        let mut backup = Vec::new();
+        state.kb.write_backup(&mut backup).map(|mode| (mode, backup))
+    })
+    .await;
+
+    let (mode, backup) = match written {
+        Ok(Ok(written)) => written,
+        Ok(Err(e)) => {
+            let error = ApiError::internal(e.to_string());
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response();
+        }
+        Err(e) => {
+            let error = ApiError::internal(e.to_string());
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response();
+        }
+    };
+
+    let file_name = format!("memory-backup-{}-{}.jsonl", mode.as_str(), current_timestamp());
+    (
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name),
+            ),
+            (
+                HeaderName::from_static("x-backup-mode"),
+                mode.as_str().to_string(),
+            ),
+        ],
+        Body::from(backup),
+    )
+        .into_response()
+}
+
+/// Largest backup file `POST /api/restore` accepts
+const MAX_RESTORE_BYTES: usize = 1024 * 1024 * 1024;
+
+/// Query parameters for restore
+#[derive(Debug, Default, Deserialize)]
+pub struct RestoreParams {
+    /// Must be true: the current graph is replaced
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// POST /api/restore?confirm=true - Replace the graph with a backup file (admin only)
+///
+/// The request body is a file from `GET /api/backup`, of either mode. It is
+/// validated before anything changes and the current state is saved to a
+/// `pre-restore` file first (see `KnowledgeBase::restore_backup`), whose path
+/// is returned. Connected clients receive a `resync` event.
+pub async fn restore_backup(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<RestoreParams>,
+    body: Bytes,
+) -> Response {
+    if !params.confirm {
+        let error = ApiError::bad_request(
+            "Restoring replaces the whole graph; pass confirm=true to proceed".to_string(),
+        );
+        return (StatusCode::BAD_REQUEST, Json(error)).into_response();
+    }
+    let Ok(content) = String::from_utf8(body.to_vec()) else {
+        let error = ApiError::bad_request("Backup is not valid UTF-8".to_string());
+        return (StatusCode::BAD_REQUEST, Json(error)).into_response();
+    };
+
+    let kb = Arc::clone(&state.kb);
+    let restored = tokio::task::spawn_blocking(move || kb.restore_backup(&content)).await;
+
+    match restored {
+        Ok(Ok(report)) => {
+            let version = state.response_version();
+            (StatusCode::OK, Json(ApiResponse::new(report, version))).into_response()
+        }
+        Ok(Err(e)) if e.is::<InvalidParams>() => {
+            let error = ApiError::bad_request(e.to_string());
+            (StatusCode::BAD_REQUEST, Json(error)).into_response()
+        }
+        Ok(Err(e)) => {
+            let error = ApiError::internal(e.to_string());
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
+        }
+        Err(e) => {
+            let error = ApiError::internal(e.to_string());
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
+        }
+    }
+}
+
+/// Body size limit of the restore endpoint
+pub fn restore_body_limit() -> DefaultBodyLimit {
+    DefaultBodyLimit::max(MAX_RESTORE_BYTES)
+}
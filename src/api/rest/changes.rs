@@ -0,0 +1,45 @@
+//! Recent changes endpoint
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+
+use super::ApiResponse;
+use crate::api::websocket::state::AppState;
+use crate::knowledge_base::KnowledgeBase;
+use crate::types::Caller;
+
+/// Query parameters for listing recent changes
+#[derive(Debug, Deserialize)]
+pub struct RecentChangesParams {
+    /// Maximum number of changes to return
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+    /// Only changes to this entity or its relations
+    pub entity: Option<String>,
+}
+
+fn default_limit() -> usize {
+    50
+}
+
+/// GET /api/changes - Latest changes kept in memory, newest first
+pub async fn list_changes(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+    Query(params): Query<RecentChangesParams>,
+) -> impl IntoResponse {
+    let version = state.response_version();
+    let changes = KnowledgeBase::run_as_caller(Some(caller), || {
+        state
+            .kb
+            .recent_changes(params.limit, params.entity.as_deref())
+    });
+    let total = changes.len();
+    Json(ApiResponse::with_total(changes, version, total))
+}
@@ -3,16 +3,17 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Extension, Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json,
 };
 use serde::{Deserialize, Serialize};
 
-use super::{ApiError, ApiResponse};
+use super::{ApiError, ApiResponse, TimestampParams};
 use crate::api::websocket::state::AppState;
-use crate::types::{Entity, Relation};
+use crate::knowledge_base::KnowledgeBase;
+use crate::types::{Caller, Entity, KnowledgeGraph, Relation};
 
 /// Query parameters for listing entities
 #[derive(Debug, Deserialize)]
@@ -46,24 +47,35 @@ fn default_order() -> String {
     "asc".to_string()
 }
 
+/// Run `read` on the graph as `caller` may see it, under the read lock
+pub(super) fn read_visible<T>(
+    state: &AppState,
+    caller: Caller,
+    read: impl FnOnce(&KnowledgeGraph) -> T,
+) -> T {
+    KnowledgeBase::run_as_caller(Some(caller), || state.kb.with_visible_graph(read))
+}
+
 /// GET /api/entities - List entities with pagination
 pub async fn list_entities(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
     Query(params): Query<ListEntitiesParams>,
+    Query(timestamps): Query<TimestampParams>,
 ) -> impl IntoResponse {
-    let graph = state.kb.graph.read().unwrap();
-
     // Filter by type if specified
-    let mut entities: Vec<Entity> = if let Some(ref entity_type) = params.entity_type {
-        graph
-            .entities
-            .iter()
-            .filter(|e| e.entity_type.eq_ignore_ascii_case(entity_type))
-            .cloned()
-            .collect()
-    } else {
-        graph.entities.clone()
-    };
+    let mut entities: Vec<Entity> = read_visible(&state, caller, |graph| {
+        if let Some(ref entity_type) = params.entity_type {
+            graph
+                .entities
+                .iter()
+                .filter(|e| e.entity_type.eq_ignore_ascii_case(entity_type))
+                .cloned()
+                .collect()
+        } else {
+            graph.entities.clone()
+        }
+    });
 
     let total = entities.len();
 
@@ -71,16 +83,16 @@ pub async fn list_entities(
     match params.sort.as_str() {
         "created_at" => {
             if params.order == "desc" {
-                entities.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+                entities.sort_by_key(|e| std::cmp::Reverse(e.created_at));
             } else {
-                entities.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+                entities.sort_by_key(|e| e.created_at);
             }
         }
         "updated_at" => {
             if params.order == "desc" {
-                entities.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+                entities.sort_by_key(|e| std::cmp::Reverse(e.updated_at));
             } else {
-                entities.sort_by(|a, b| a.updated_at.cmp(&b.updated_at));
+                entities.sort_by_key(|e| e.updated_at);
             }
         }
         _ => {
@@ -97,8 +109,8 @@ pub async fn list_entities(
     let limit = params.limit.min(1000);
     let entities: Vec<Entity> = entities.into_iter().skip(params.offset).take(limit).collect();
 
-    let sequence_id = state.current_sequence_id();
-    Json(ApiResponse::with_total(entities, sequence_id, total))
+    let version = state.response_version();
+    ApiResponse::with_total(entities, version, total).into_json(&timestamps)
 }
 
 /// Response for single entity with relations
@@ -112,35 +124,33 @@ pub struct EntityDetail {
     pub incoming_relations: Vec<Relation>,
 }
 
-/// GET /api/entities/:name - Get single entity with relations
+/// GET /api/entities/:name - Get single entity (by name or id) with relations
 pub async fn get_entity(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
     Path(name): Path<String>,
+    Query(timestamps): Query<TimestampParams>,
 ) -> impl IntoResponse {
-    let graph = state.kb.graph.read().unwrap();
-
     // URL decode the name (handles spaces and special chars)
     let decoded_name = urlencoding::decode(&name)
         .unwrap_or_else(|_| name.clone().into())
         .into_owned();
 
-    // Find entity
-    let entity = graph.entities.iter().find(|e| e.name == decoded_name);
-
-    match entity {
+    read_visible(&state, caller, |graph| match graph.resolve_entity(&decoded_name) {
         Some(entity) => {
+            let decoded_name = &entity.name;
             // Get related relations
             let outgoing_relations: Vec<Relation> = graph
                 .relations
                 .iter()
-                .filter(|r| r.from == decoded_name)
+                .filter(|r| &r.from == decoded_name)
                 .cloned()
                 .collect();
 
             let incoming_relations: Vec<Relation> = graph
                 .relations
                 .iter()
-                .filter(|r| r.to == decoded_name)
+                .filter(|r| &r.to == decoded_name)
                 .cloned()
                 .collect();
 
@@ -150,12 +160,13 @@ pub async fn get_entity(
                 incoming_relations,
             };
 
-            let sequence_id = state.current_sequence_id();
-            (StatusCode::OK, Json(ApiResponse::new(detail, sequence_id))).into_response()
+            let version = state.response_version();
+            let body = ApiResponse::new(detail, version).into_json(&timestamps);
+            (StatusCode::OK, body).into_response()
         }
         None => {
             let error = ApiError::not_found(format!("Entity '{}' not found", decoded_name));
             (StatusCode::NOT_FOUND, Json(error)).into_response()
         }
-    }
+    })
 }
@@ -0,0 +1,83 @@
+//! Event store endpoints - Archive state and maintenance
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{ApiError, ApiResponse};
+use crate::api::websocket::state::AppState;
+use crate::event_store::ArchiveInfo;
+
+/// Response for GET /api/event-store/archives
+#[derive(Debug, Serialize)]
+pub struct ArchivesResponse {
+    pub archives: Vec<ArchiveInfo>,
+    /// Combined size of all archives in bytes
+    pub total_size: u64,
+}
+
+/// GET /api/event-store/archives - List archived event log segments
+///
+/// Empty when Event Sourcing is disabled.
+pub async fn list_archives(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match state.kb.archive_info() {
+        Ok(archives) => {
+            let total = archives.len();
+            let total_size = archives.iter().map(|a| a.size).sum();
+            let response = ArchivesResponse {
+                archives,
+                total_size,
+            };
+            let version = state.response_version();
+            (
+                StatusCode::OK,
+                Json(ApiResponse::with_total(response, version, total)),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            let error = ApiError::internal(e.to_string());
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
+        }
+    }
+}
+
+/// Query parameters for archive cleanup
+#[derive(Debug, Deserialize)]
+pub struct CleanupParams {
+    /// Number of most recent archives to keep (required, no default)
+    pub keep: usize,
+}
+
+/// Response for POST /api/event-store/archives/cleanup
+#[derive(Debug, Serialize)]
+pub struct CleanupResponse {
+    pub deleted: usize,
+}
+
+/// POST /api/event-store/archives/cleanup?keep=N - Delete all but the newest N archives
+pub async fn cleanup_archives(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<CleanupParams>,
+) -> impl IntoResponse {
+    match state.kb.cleanup_archives(params.keep) {
+        Ok(deleted) => {
+            let version = state.response_version();
+            (
+                StatusCode::OK,
+                Json(ApiResponse::new(CleanupResponse { deleted }, version)),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            let error = ApiError::internal(e.to_string());
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
+        }
+    }
+}
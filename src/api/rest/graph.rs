@@ -3,15 +3,21 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Query, State},
-    response::IntoResponse,
+    body::Body,
+    extract::{Extension, Query, State},
+    http::{header, HeaderName, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::ReceiverStream;
 
-use super::ApiResponse;
+use super::{ApiError, ApiResponse, TimestampParams};
+use crate::api::websocket::events::WsMessage;
 use crate::api::websocket::state::AppState;
-use crate::types::{Entity, Relation};
+use crate::knowledge_base::KnowledgeBase;
+use crate::types::{Caller, Entity, KnowledgeGraph, Relation};
 
 /// Response for GET /api/graph
 #[derive(Debug, Serialize)]
@@ -45,14 +51,21 @@ fn default_true() -> bool {
 /// GET /api/graph - Get full graph snapshot
 ///
 /// Returns entities and relations for client recovery after WebSocket reconnection.
-/// Includes sequence_id so client knows the snapshot version.
+/// Includes sequence_id and graph_version so client knows the snapshot version.
 pub async fn get_graph(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
     Query(params): Query<GraphParams>,
+    Query(timestamps): Query<TimestampParams>,
 ) -> impl IntoResponse {
-    // Get all entities with pagination
-    let all_entities: Vec<Entity> = state.kb.graph.read().unwrap().entities.clone();
-    let all_relations: Vec<Relation> = state.kb.graph.read().unwrap().relations.clone();
+    // Copy entities and relations under one read lock, so they match the version
+    let (version, all_entities, all_relations): (_, Vec<Entity>, Vec<Relation>) =
+        KnowledgeBase::run_as_caller(Some(caller), || {
+            state.kb.with_visible_graph(|graph| {
+                let version = state.response_version();
+                (version, graph.entities.clone(), graph.relations.clone())
+            })
+        });
 
     let total_entities = all_entities.len();
 
@@ -79,11 +92,158 @@ pub async fn get_graph(
     };
 
     let graph = GraphResponse { entities, relations };
-    let sequence_id = state.current_sequence_id();
+    let response = ApiResponse::with_total(graph, version, total_entities);
+
+    response.into_json(&timestamps)
+}
+
+/// Query parameters for subgraph export
+#[derive(Debug, Deserialize)]
+pub struct SubgraphParams {
+    /// Entity at the center of the subgraph
+    pub center: String,
+    /// Maximum number of hops from the center (max 5)
+    #[serde(default = "default_depth")]
+    pub depth: usize,
+    /// `json` (default) or `jsonl` (memory.jsonl lines)
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+fn default_depth() -> usize {
+    2
+}
+
+/// GET /api/graph/subgraph - Export everything within `depth` hops of `center`
+///
+/// With `format=jsonl` the body is plain memory.jsonl content that can be
+/// loaded by another server.
+pub async fn get_subgraph(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+    Query(params): Query<SubgraphParams>,
+    Query(timestamps): Query<TimestampParams>,
+) -> Response {
+    let version = state.response_version();
+    let subgraph = KnowledgeBase::run_as_caller(Some(caller), || {
+        state.kb.get_subgraph(&params.center, params.depth.min(5))
+    });
+    let subgraph = match subgraph {
+        Ok(subgraph) => subgraph,
+        Err(e) => {
+            let error = ApiError::not_found(e.to_string());
+            return (StatusCode::NOT_FOUND, Json(error)).into_response();
+        }
+    };
 
-    let response = ApiResponse::with_total(graph, sequence_id, total_entities);
+    match params.format.as_deref() {
+        Some("jsonl") => match subgraph.to_jsonl() {
+            Ok(body) => ([(header::CONTENT_TYPE, "application/x-ndjson")], body).into_response(),
+            Err(e) => {
+                let error = ApiError::internal(e.to_string());
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
+            }
+        },
+        None | Some("json") => {
+            let graph = GraphResponse {
+                entities: subgraph.entities,
+                relations: subgraph.relations,
+            };
+            ApiResponse::new(graph, version)
+                .into_json(&timestamps)
+                .into_response()
+        }
+        Some(other) => {
+            let error = ApiError::bad_request(format!("Unknown format: {}", other));
+            (StatusCode::BAD_REQUEST, Json(error)).into_response()
+        }
+    }
+}
+
+/// Bytes of NDJSON buffered before a chunk is sent to the client
+const STREAM_CHUNK_BYTES: usize = 64 * 1024;
+
+/// GET /api/graph/stream - Stream the whole graph as NDJSON
+///
+/// Entities then relations, one per line, in the memory.jsonl format so the
+/// body can be loaded by another server. The graph is copied under the read
+/// lock, then serialized in chunks on a blocking thread, so a slow client
+/// never holds up writers and the response body is never built in full. The
+/// version copied is reported in the `x-sequence-id` and `x-graph-version`
+/// headers.
+pub async fn stream_graph(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+) -> Response {
+    let (version_tx, version_rx) = oneshot::channel();
+    let (chunk_tx, chunk_rx) = mpsc::channel(4);
+
+    tokio::task::spawn_blocking(move || {
+        let (graph, version) = KnowledgeBase::run_as_caller(Some(caller), || {
+            state
+                .kb
+                .with_visible_graph(|graph| (graph.clone(), state.response_version()))
+        });
+        if version_tx.send(version).is_ok() {
+            stream_lines(&graph, &chunk_tx);
+        }
+    });
 
-    Json(response)
+    let Ok(version) = version_rx.await else {
+        let error = ApiError::internal("Graph stream failed to start".to_string());
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response();
+    };
+
+    (
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (
+                HeaderName::from_static("x-sequence-id"),
+                version.sequence_id.to_string(),
+            ),
+            (
+                HeaderName::from_static("x-graph-version"),
+                version.graph_version.to_string(),
+            ),
+        ],
+        Body::from_stream(ReceiverStream::new(chunk_rx)),
+    )
+        .into_response()
+}
+
+/// One memory.jsonl line: an entity or a relation, serialized as is
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Line<'a> {
+    Entity(&'a Entity),
+    Relation(&'a Relation),
+}
+
+/// Send the graph as NDJSON chunks, stopping early if the client goes away
+fn stream_lines(graph: &KnowledgeGraph, chunks: &mpsc::Sender<serde_json::Result<Vec<u8>>>) {
+    let lines = graph
+        .entities
+        .iter()
+        .map(Line::Entity)
+        .chain(graph.relations.iter().map(Line::Relation));
+    let mut chunk = Vec::with_capacity(STREAM_CHUNK_BYTES);
+
+    for line in lines {
+        if let Err(e) = serde_json::to_writer(&mut chunk, &line) {
+            let _ = chunks.blocking_send(Err(e));
+            return;
+        }
+        chunk.push(b'\n');
+        if chunk.len() >= STREAM_CHUNK_BYTES {
+            let full = std::mem::replace(&mut chunk, Vec::with_capacity(STREAM_CHUNK_BYTES));
+            if chunks.blocking_send(Ok(full)).is_err() {
+                return;
+            }
+        }
+    }
+    if !chunk.is_empty() {
+        let _ = chunks.blocking_send(Ok(chunk));
+    }
 }
 
 /// GET /api/graph/stats - Get graph statistics
@@ -107,9 +267,20 @@ pub struct RelationTypeCount {
     pub count: usize,
 }
 
-pub async fn get_stats(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let graph = state.kb.graph.read().unwrap();
+pub async fn get_stats(
+    State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
+) -> impl IntoResponse {
+    let stats = KnowledgeBase::run_as_caller(Some(caller), || {
+        state.kb.with_visible_graph(graph_stats)
+    });
 
+    let version = state.response_version();
+    Json(ApiResponse::new(stats, version))
+}
+
+/// Entity and relation type counts of `graph`
+fn graph_stats(graph: &KnowledgeGraph) -> GraphStats {
     // Count entity types
     let mut entity_type_counts: std::collections::HashMap<String, usize> =
         std::collections::HashMap::new();
@@ -128,7 +299,7 @@ pub async fn get_stats(State(state): State<Arc<AppState>>) -> impl IntoResponse
             .or_insert(0) += 1;
     }
 
-    let stats = GraphStats {
+    GraphStats {
         entity_count: graph.entities.len(),
         relation_count: graph.relations.len(),
         entity_types: entity_type_counts
@@ -142,10 +313,7 @@ pub async fn get_stats(State(state): State<Arc<AppState>>) -> impl IntoResponse
                 count,
             })
             .collect(),
-    };
-
-    let sequence_id = state.current_sequence_id();
-    Json(ApiResponse::new(stats, sequence_id))
+    }
 }
 
 /// Query parameters for event replay
@@ -159,7 +327,7 @@ pub struct EventReplayParams {
 #[derive(Debug, Serialize)]
 pub struct EventReplayResponse {
     /// Events since the requested sequence ID
-    pub events: Vec<crate::api::websocket::events::WsMessage>,
+    pub events: Vec<WsMessage>,
     /// Whether a full refresh is needed (events too old)
     pub needs_full_refresh: bool,
     /// Oldest available sequence ID in history
@@ -175,9 +343,11 @@ pub struct EventReplayResponse {
 /// `needs_full_refresh` will be true and client should fetch full graph.
 pub async fn get_events_replay(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
     Query(params): Query<EventReplayParams>,
 ) -> impl IntoResponse {
-    let current_sequence_id = state.current_sequence_id();
+    let version = state.response_version();
+    let current_sequence_id = version.sequence_id;
 
     // Get broadcaster if available
     let broadcaster = crate::api::websocket::get_broadcaster();
@@ -196,6 +366,15 @@ pub async fn get_events_replay(
         }
     };
 
+    // Left out like on the WebSocket: events about entities the caller may not see
+    let events: Vec<WsMessage> = events
+        .into_iter()
+        .filter_map(|msg| {
+            let event = state.kb.visible_event(&msg.event, &caller)?;
+            Some(WsMessage { event, ..msg })
+        })
+        .collect();
+
     let response = EventReplayResponse {
         events,
         needs_full_refresh,
@@ -203,5 +382,30 @@ pub async fn get_events_replay(
         current_sequence_id,
     };
 
-    Json(ApiResponse::new(response, current_sequence_id))
+    Json(ApiResponse::new(response, version))
+}
+
+/// Response for POST /api/graph/reload
+#[derive(Debug, Serialize)]
+pub struct ReloadResponse {
+    pub entities: usize,
+    pub relations: usize,
+}
+
+/// POST /api/graph/reload - Re-read the graph from disk after out-of-band edits
+pub async fn reload_graph(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match state.kb.reload_from_disk() {
+        Ok((entities, relations)) => {
+            let version = state.response_version();
+            let response = ReloadResponse {
+                entities,
+                relations,
+            };
+            (StatusCode::OK, Json(ApiResponse::new(response, version))).into_response()
+        }
+        Err(e) => {
+            let error = ApiError::internal(e.to_string());
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
+        }
+    }
 }
@@ -2,17 +2,56 @@
 //!
 //! Provides REST endpoints for client recovery and data access:
 //! - `GET /api/graph` - Full graph snapshot
+//! - `GET /api/graph/subgraph` - Export the neighborhood of an entity
+//! - `GET /api/graph/stream` - Stream the whole graph as NDJSON
 //! - `GET /api/entities` - List entities with pagination
 //! - `GET /api/entities/:name` - Get single entity
 //! - `GET /api/relations` - List relations
 //! - `GET /api/search` - Search nodes
+//! - `GET /api/changes` - Recent changes kept in memory (lost on restart)
+//! - `GET /api/event-store/archives` - List event log archives
+//! - `POST /api/event-store/archives/cleanup` - Delete old archives (admin)
+//! - `GET /api/tools` - Tool catalog with read/write classification
+//! - `GET /api/tool-stats` - Per-tool usage since startup (lost on restart)
+//! - `GET /api/backup` - Download the persisted state as one file (admin)
+//! - `POST /api/restore?confirm=true` - Replace the graph with a backup (admin)
+//!
+//! Every endpoint goes through [`with_caller`], which rejects an invalid
+//! token (or a missing one when auth is required) and otherwise runs the
+//! request as the token's caller or the anonymous one. Entities the caller
+//! may not see under their ACLs are left out of every response, as are the
+//! relations and changes touching them.
+//!
+//! Admin endpoints also go through [`admin_only`]: they need JWT
+//! authentication and a token with the `*` permission.
+//!
+//! Timestamps are Unix seconds. The graph, entity, relation and search
+//! endpoints also accept `iso_timestamps=true` to add an ISO 8601 copy of each
+//! (`createdAtIso`, `updatedAtIso`, `validFromIso`, `validToIso`).
 
+pub mod backup;
+pub mod changes;
 pub mod entities;
+pub mod event_store;
 pub mod graph;
 pub mod relations;
 pub mod search;
+pub mod tools;
 
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::api::sse::handler::AuthErrorResponse;
+use crate::api::sse::AuthError;
+use crate::api::websocket::handler::WsAuth;
+use crate::utils::add_iso_timestamps;
 
 /// Common pagination parameters
 #[derive(Debug, Deserialize)]
@@ -36,6 +75,24 @@ impl PaginationParams {
     }
 }
 
+/// `iso_timestamps` query parameter
+#[derive(Debug, Default, Deserialize)]
+pub struct TimestampParams {
+    /// Add ISO 8601 strings next to Unix-second timestamps (default: false)
+    #[serde(default)]
+    pub iso_timestamps: bool,
+}
+
+/// Sequence ID and graph version a response reflects
+///
+/// Handlers take this before reading the graph, so a concurrent mutation can
+/// only make the data newer than the version reported, never older.
+#[derive(Debug, Clone, Copy)]
+pub struct ResponseVersion {
+    pub sequence_id: u64,
+    pub graph_version: u64,
+}
+
 /// Standard API response wrapper
 #[derive(Debug, Serialize)]
 pub struct ApiResponse<T> {
@@ -43,29 +100,42 @@ pub struct ApiResponse<T> {
     pub data: T,
     /// Current sequence ID for cache invalidation
     pub sequence_id: u64,
+    /// Graph version the data was read at (see `KnowledgeBase::version`)
+    pub graph_version: u64,
     /// Total count (for paginated responses)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub total: Option<usize>,
 }
 
 impl<T> ApiResponse<T> {
-    pub fn new(data: T, sequence_id: u64) -> Self {
+    pub fn new(data: T, version: ResponseVersion) -> Self {
         Self {
             data,
-            sequence_id,
+            sequence_id: version.sequence_id,
+            graph_version: version.graph_version,
             total: None,
         }
     }
 
-    pub fn with_total(data: T, sequence_id: u64, total: usize) -> Self {
+    pub fn with_total(data: T, version: ResponseVersion, total: usize) -> Self {
         Self {
-            data,
-            sequence_id,
             total: Some(total),
+            ..Self::new(data, version)
         }
     }
 }
 
+impl<T: Serialize> ApiResponse<T> {
+    /// JSON body, with ISO 8601 timestamps added if the client asked for them
+    pub fn into_json(self, timestamps: &TimestampParams) -> Json<Value> {
+        let mut body = serde_json::to_value(self).unwrap_or_default();
+        if timestamps.iso_timestamps {
+            add_iso_timestamps(&mut body);
+        }
+        Json(body)
+    }
+}
+
 /// API error response
 #[derive(Debug, Serialize)]
 pub struct ApiError {
@@ -95,3 +165,51 @@ impl ApiError {
         }
     }
 }
+
+/// Resolve the caller of the request for entity ACLs (see the module docs)
+///
+/// Handlers read it with `Extension<Caller>`.
+pub async fn with_caller(
+    State(auth): State<WsAuth>,
+    headers: HeaderMap,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    match auth.caller(&headers, None) {
+        Ok(caller) => {
+            request.extensions_mut().insert(caller);
+            next.run(request).await
+        }
+        Err(e) => (
+            StatusCode::UNAUTHORIZED,
+            Json(AuthErrorResponse::from_auth_error(&e)),
+        )
+            .into_response(),
+    }
+}
+
+/// Let the request through only with a token holding the `*` permission
+///
+/// Without JWT authentication configured, admin endpoints are disabled.
+pub async fn admin_only(
+    State(auth): State<WsAuth>,
+    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Response {
+    if auth.jwt_auth.is_none() {
+        let error = AuthErrorResponse {
+            error: "Admin endpoints need JWT authentication".to_string(),
+            error_code: "auth_not_configured".to_string(),
+        };
+        return (StatusCode::FORBIDDEN, Json(error)).into_response();
+    }
+
+    let (status, error) = match auth.validate(&headers, None) {
+        Ok(Some(claims)) if claims.has_permission("*") => return next.run(request).await,
+        Ok(Some(_)) => (StatusCode::FORBIDDEN, AuthError::InsufficientPermissions),
+        Ok(None) => (StatusCode::UNAUTHORIZED, AuthError::MissingToken),
+        Err(e) => (StatusCode::UNAUTHORIZED, e),
+    };
+    (status, Json(AuthErrorResponse::from_auth_error(&error))).into_response()
+}
@@ -3,15 +3,15 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Query, State},
+    extract::{Extension, Query, State},
     response::IntoResponse,
-    Json,
 };
 use serde::Deserialize;
 
-use super::ApiResponse;
+use super::entities::read_visible;
+use super::{ApiResponse, TimestampParams};
 use crate::api::websocket::state::AppState;
-use crate::types::Relation;
+use crate::types::{Caller, Relation};
 
 /// Query parameters for listing relations
 #[derive(Debug, Deserialize)]
@@ -38,37 +38,39 @@ fn default_limit() -> usize {
 /// GET /api/relations - List relations with pagination and filters
 pub async fn list_relations(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
     Query(params): Query<ListRelationsParams>,
+    Query(timestamps): Query<TimestampParams>,
 ) -> impl IntoResponse {
-    let graph = state.kb.graph.read().unwrap();
-
     // Apply filters
-    let mut relations: Vec<Relation> = graph
-        .relations
-        .iter()
-        .filter(|r| {
-            // Filter by type
-            if let Some(ref relation_type) = params.relation_type {
-                if !r.relation_type.eq_ignore_ascii_case(relation_type) {
-                    return false;
+    let mut relations: Vec<Relation> = read_visible(&state, caller, |graph| {
+        graph
+            .relations
+            .iter()
+            .filter(|r| {
+                // Filter by type
+                if let Some(ref relation_type) = params.relation_type {
+                    if !r.relation_type.eq_ignore_ascii_case(relation_type) {
+                        return false;
+                    }
                 }
-            }
-            // Filter by source
-            if let Some(ref from) = params.from {
-                if !r.from.eq_ignore_ascii_case(from) {
-                    return false;
+                // Filter by source
+                if let Some(ref from) = params.from {
+                    if !r.from.eq_ignore_ascii_case(from) {
+                        return false;
+                    }
                 }
-            }
-            // Filter by target
-            if let Some(ref to) = params.to {
-                if !r.to.eq_ignore_ascii_case(to) {
-                    return false;
+                // Filter by target
+                if let Some(ref to) = params.to {
+                    if !r.to.eq_ignore_ascii_case(to) {
+                        return false;
+                    }
                 }
-            }
-            true
-        })
-        .cloned()
-        .collect();
+                true
+            })
+            .cloned()
+            .collect()
+    });
 
     let total = relations.len();
 
@@ -84,6 +86,6 @@ pub async fn list_relations(
     let limit = params.limit.min(1000);
     let relations: Vec<Relation> = relations.into_iter().skip(params.offset).take(limit).collect();
 
-    let sequence_id = state.current_sequence_id();
-    Json(ApiResponse::with_total(relations, sequence_id, total))
+    let version = state.response_version();
+    ApiResponse::with_total(relations, version, total).into_json(&timestamps)
 }
@@ -3,27 +3,39 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Query, State},
+    extract::{Extension, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json,
 };
 use serde::Deserialize;
 
-use super::{ApiError, ApiResponse};
+use super::{ApiError, ApiResponse, TimestampParams};
 use crate::api::websocket::state::AppState;
+use crate::knowledge_base::KnowledgeBase;
+use crate::types::{Caller, SearchField, SearchOptions};
 
 /// Query parameters for search
 #[derive(Debug, Deserialize)]
 pub struct SearchParams {
     /// Search query string
+    #[serde(alias = "query")]
     pub q: String,
     /// Maximum number of results
     #[serde(default = "default_limit")]
     pub limit: usize,
+    /// Number of ranked results to skip
+    #[serde(default)]
+    pub offset: usize,
     /// Include relations connected to matching entities
-    #[serde(default = "default_true")]
+    #[serde(default = "default_true", alias = "includeRelations")]
     pub include_relations: bool,
+    /// Comma-separated fields to match against (name, entityType, observations)
+    #[serde(default)]
+    pub fields: Option<String>,
+    /// Comma-separated entity types to restrict results to
+    #[serde(default, alias = "entityTypes")]
+    pub entity_types: Option<String>,
 }
 
 fn default_limit() -> usize {
@@ -34,35 +46,61 @@ fn default_true() -> bool {
     true
 }
 
+/// Split a comma-separated query value, dropping empty items
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
 /// GET /api/search - Search nodes in the knowledge graph
 ///
-/// Searches entity names, types, and observations using the existing
-/// search logic with synonym matching.
+/// Uses the same ranked search as the `search_nodes` MCP tool. Entities carry
+/// a relevance `score` and `total` counts all matches before pagination.
 pub async fn search_nodes(
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
     Query(params): Query<SearchParams>,
+    Query(timestamps): Query<TimestampParams>,
 ) -> impl IntoResponse {
     if params.q.trim().is_empty() {
         let error = ApiError::bad_request("Query parameter 'q' is required");
         return (StatusCode::BAD_REQUEST, Json(error)).into_response();
     }
 
+    let fields = match params
+        .fields
+        .as_deref()
+        .map(|f| SearchField::parse_list(split_list(f)))
+        .transpose()
+    {
+        Ok(fields) => fields,
+        Err(e) => {
+            let error = ApiError::bad_request(e);
+            return (StatusCode::BAD_REQUEST, Json(error)).into_response();
+        }
+    };
+
     // Use existing search_nodes functionality (KnowledgeBase has internal RwLock)
-    let limit = if params.limit > 0 {
-        Some(params.limit.min(1000))
-    } else {
-        None
+    let options = SearchOptions {
+        limit: if params.limit > 0 {
+            Some(params.limit.min(1000))
+        } else {
+            None
+        },
+        offset: params.offset,
+        include_relations: params.include_relations,
+        fields,
+        entity_types: params
+            .entity_types
+            .as_deref()
+            .map(|t| split_list(t).map(String::from).collect()),
     };
 
-    match state.kb.search_nodes(&params.q, limit, params.include_relations) {
+    let version = state.response_version();
+    match KnowledgeBase::run_as_caller(Some(caller), || state.kb.search_nodes(&params.q, &options)) {
         Ok(result) => {
-            let sequence_id = state.current_sequence_id();
-            let total = result.entities.len();
-            (
-                StatusCode::OK,
-                Json(ApiResponse::with_total(result, sequence_id, total)),
-            )
-                .into_response()
+            let total = result.total;
+            let body = ApiResponse::with_total(result, version, total).into_json(&timestamps);
+            (StatusCode::OK, body).into_response()
         }
         Err(e) => {
             let error = ApiError::internal(e.to_string());
@@ -0,0 +1,27 @@
+//! Tool catalog and usage endpoints
+
+use std::sync::Arc;
+
+use axum::{extract::State, response::IntoResponse, Json};
+
+use super::ApiResponse;
+use crate::api::websocket::state::AppState;
+use crate::server::tool_catalog;
+use crate::tools::{get_all_tools, tool_stats as usage};
+
+/// GET /api/tools - Tool schemas with read/write classification
+///
+/// Same body as the `server/capabilities` JSON-RPC method; see
+/// [`tool_catalog`] for the layout.
+pub async fn list_tools(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let tools = get_all_tools(Arc::clone(&state.kb));
+    let catalog = tool_catalog(tools.iter().map(|t| t.as_ref()));
+    Json(ApiResponse::new(catalog, state.response_version()))
+}
+
+/// GET /api/tool-stats - Invocations, errors and latency per tool
+///
+/// Same data as the `tool_stats` tool; counters start at zero on restart.
+pub async fn tool_stats(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    Json(ApiResponse::new(usage().snapshot(), state.response_version()))
+}
@@ -349,11 +349,7 @@ impl JwtAuth {
     /// Validate token from Authorization header
     /// Supports: "Bearer <token>" or just "<token>"
     pub fn validate_authorization(&self, auth_header: &str) -> Result<Claims, AuthError> {
-        let token = if auth_header.starts_with("Bearer ") {
-            &auth_header[7..]
-        } else {
-            auth_header
-        };
+        let token = auth_header.strip_prefix("Bearer ").unwrap_or(auth_header);
 
         self.validate_token(token)
     }
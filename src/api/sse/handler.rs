@@ -20,8 +20,22 @@ use tokio::sync::broadcast;
 
 use super::auth::{AuthError, Claims, JwtAuth};
 use super::{session::SessionManager, SseEvent};
-use crate::api::websocket::events::WsMessage;
-use crate::protocol::{JsonRpcError, JsonRpcRequest, JsonRpcResponse, McpTool, Tool};
+use crate::api::websocket::backpressure::{Catchup, ClientCursor, SlowClientPolicy};
+use crate::api::websocket::events::{GraphEvent, WsMessage};
+use crate::api::websocket::{get_broadcaster, InferredSubscription, SequenceCounter};
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{
+    ErrorObject, JsonRpcError, JsonRpcRequest, JsonRpcResponse, McpTool, Tool,
+};
+use crate::server::{execute_batch, resources_list, resources_read, tool_catalog};
+use crate::tools::{execute_tool, track};
+use crate::types::Caller;
+
+/// How often an open SSE stream marks its session as used
+const SESSION_HEARTBEAT: Duration = Duration::from_secs(30);
+
+/// Registered MCP tools by name
+type ToolMap = HashMap<String, Arc<dyn Tool>>;
 
 /// Shared state for SSE endpoints
 pub struct SseState {
@@ -30,25 +44,29 @@ pub struct SseState {
     /// Session manager
     pub sessions: SessionManager,
     /// Registered MCP tools
-    pub tools: HashMap<String, Arc<dyn Tool>>,
+    pub tools: ToolMap,
     /// Server info
     pub server_name: String,
     pub server_version: String,
     /// Broadcast channel for graph events (shared with WebSocket)
     pub event_rx: broadcast::Sender<WsMessage>,
     /// Sequence counter
-    pub sequence_counter: Arc<std::sync::atomic::AtomicU64>,
+    pub sequence_counter: Arc<SequenceCounter>,
     /// JWT authentication (optional - None means auth disabled)
     pub jwt_auth: Option<Arc<JwtAuth>>,
     /// Whether authentication is required
     pub require_auth: bool,
+    /// Prefix the routes are mounted under (empty for none)
+    pub base_path: String,
+    /// What to do with SSE clients that lag past the replay history
+    pub slow_client_policy: SlowClientPolicy,
 }
 
 impl SseState {
     pub fn new(
         kb: Arc<crate::knowledge_base::KnowledgeBase>,
         event_tx: broadcast::Sender<WsMessage>,
-        sequence_counter: Arc<std::sync::atomic::AtomicU64>,
+        sequence_counter: Arc<SequenceCounter>,
     ) -> Self {
         // Register all tools
         let tools_vec = crate::tools::get_all_tools(kb.clone());
@@ -68,9 +86,23 @@ impl SseState {
             sequence_counter,
             jwt_auth: None,
             require_auth: false,
+            base_path: String::new(),
+            slow_client_policy: SlowClientPolicy::default(),
         }
     }
 
+    /// Set the prefix the routes are mounted under
+    pub fn with_base_path(mut self, base_path: String) -> Self {
+        self.base_path = base_path;
+        self
+    }
+
+    /// Set the policy for clients that lag past the replay history
+    pub fn with_slow_client_policy(mut self, policy: SlowClientPolicy) -> Self {
+        self.slow_client_policy = policy;
+        self
+    }
+
     /// Set JWT authentication
     pub fn with_jwt_auth(mut self, jwt_auth: Arc<JwtAuth>, require_auth: bool) -> Self {
         self.jwt_auth = Some(jwt_auth);
@@ -86,7 +118,7 @@ impl SseState {
 
     /// Get current sequence ID
     pub fn current_sequence_id(&self) -> u64 {
-        self.sequence_counter.load(std::sync::atomic::Ordering::SeqCst)
+        self.sequence_counter.current()
     }
 
     /// Validate token from headers
@@ -119,6 +151,26 @@ impl SseState {
             Ok(None)
         }
     }
+
+    /// Caller the token belongs to, or [`Caller::anonymous`] without one
+    ///
+    /// Fails like [`SseState::validate_auth`]: a token that is given must be
+    /// valid, and one is required when auth is.
+    pub fn caller(&self, headers: &HeaderMap) -> Result<Caller, AuthError> {
+        Ok(match self.validate_auth(headers)? {
+            Some(claims) => Caller::new(claims.sub, claims.permissions),
+            None => Caller::anonymous(),
+        })
+    }
+}
+
+/// 401 response for a failed token check
+fn unauthorized(error: &AuthError) -> axum::response::Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(AuthErrorResponse::from_auth_error(error)),
+    )
+        .into_response()
 }
 
 /// Query parameters for SSE connection
@@ -126,6 +178,12 @@ impl SseState {
 pub struct SseParams {
     /// API key for authentication
     pub api_key: Option<String>,
+    /// Also stream inferred (non-persistent) relations after mutations
+    #[serde(default)]
+    pub show_inferred: bool,
+    /// Work on a private scratch graph instead of the shared one
+    #[serde(default)]
+    pub scoped: bool,
 }
 
 /// Extract user from API key header or query param
@@ -147,25 +205,44 @@ fn extract_user(headers: &HeaderMap, params: &SseParams) -> Option<String> {
 }
 
 /// GET /mcp/sse - SSE stream for server→client events
+///
+/// Graph events about entities the token's caller may not see are left out.
 pub async fn sse_handler(
     State(state): State<Arc<SseState>>,
     headers: HeaderMap,
     Query(params): Query<SseParams>,
-) -> impl IntoResponse {
+) -> axum::response::Response {
+    let caller = match state.caller(&headers) {
+        Ok(caller) => caller,
+        Err(e) => return unauthorized(&e),
+    };
     let user = extract_user(&headers, &params).unwrap_or_else(|| "anonymous".to_string());
 
     // Create session
-    let session = state
-        .sessions
-        .create_session(user, params.api_key.clone())
-        .await;
+    let session = if params.scoped {
+        state
+            .sessions
+            .create_scoped_session(user, params.api_key.clone(), &state.kb)
+            .await
+    } else {
+        state
+            .sessions
+            .create_session(user, params.api_key.clone())
+            .await
+    };
+    let scoped = session.scope.is_some();
 
     // Subscribe to graph events
     let mut event_rx = state.event_rx.subscribe();
     let session_id = session.session_id.clone();
     let server_name = state.server_name.clone();
     let server_version = state.server_version.clone();
+    let endpoint = format!("{}/mcp/sse", state.base_path);
     let sequence_id = state.current_sequence_id();
+    let graph_version = state.kb.version();
+    let inferred = params.show_inferred.then(InferredSubscription::new);
+    let mut cursor = ClientCursor::new(state.slow_client_policy, sequence_id);
+    let stream_state = Arc::clone(&state);
 
     // Create SSE stream
     let stream = async_stream::stream! {
@@ -173,7 +250,7 @@ pub async fn sse_handler(
         // This tells the client where to POST messages
         yield Ok::<_, Infallible>(Event::default()
             .event("endpoint")
-            .data("/mcp/sse"));
+            .data(endpoint));
 
         // Send welcome message
         let welcome = SseEvent::Welcome {
@@ -181,38 +258,79 @@ pub async fn sse_handler(
             server_name,
             server_version,
             sequence_id,
+            graph_version,
+            scoped,
         };
         yield Ok::<_, Infallible>(Event::default()
             .event("welcome")
             .data(serde_json::to_string(&welcome).unwrap_or_default()));
 
-        // Stream graph events
+        // Stream graph events, keeping the session alive while connected
         loop {
-            match event_rx.recv().await {
+            let received =
+                tokio::time::timeout(SESSION_HEARTBEAT, event_rx.recv()).await;
+            stream_state.sessions.touch(&session_id).await;
+            let Ok(received) = received else {
+                continue;
+            };
+            let messages = match received {
                 Ok(msg) => {
-                    let event = SseEvent::GraphEvent { event: msg };
-                    yield Ok(Event::default()
-                        .event("graph_event")
-                        .data(serde_json::to_string(&event).unwrap_or_default()));
+                    if !cursor.advance(&msg) {
+                        continue; // Already replayed after a lag
+                    }
+                    vec![msg]
                 }
                 Err(broadcast::error::RecvError::Lagged(n)) => {
-                    // Client is too slow
-                    let error = SseEvent::Error {
-                        code: "lagged".to_string(),
-                        message: format!("Missed {} events, please reconnect", n),
-                    };
-                    yield Ok(Event::default()
-                        .event("error")
-                        .data(serde_json::to_string(&error).unwrap_or_default()));
+                    // Client is too slow; resume from the replay history if it
+                    // still covers the gap
+                    match cursor.catch_up(get_broadcaster()) {
+                        Catchup::Replayed(messages) => messages,
+                        lost => {
+                            let error = SseEvent::Error {
+                                code: "lagged".to_string(),
+                                message: format!("Missed {} events, please reconnect", n),
+                            };
+                            yield Ok(Event::default()
+                                .event("error")
+                                .data(serde_json::to_string(&error).unwrap_or_default()));
+                            if matches!(lost, Catchup::Disconnect) {
+                                break;
+                            }
+                            continue;
+                        }
+                    }
                 }
                 Err(broadcast::error::RecvError::Closed) => {
                     break;
                 }
+            };
+
+            for msg in messages {
+                if inferred.is_none() && matches!(msg.event, GraphEvent::Inferred { .. }) {
+                    continue;
+                }
+                let Some(event) = stream_state.kb.visible_event(&msg.event, &caller) else {
+                    continue;
+                };
+                let msg = WsMessage { event, ..msg };
+                let event = SseEvent::GraphEvent { event: msg };
+                yield Ok(Event::default()
+                    .event("graph_event")
+                    .data(serde_json::to_string(&event).unwrap_or_default()));
             }
         }
     };
 
-    Sse::new(stream).keep_alive(KeepAlive::default().interval(Duration::from_secs(30)))
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default().interval(Duration::from_secs(30)))
+        .into_response()
+}
+
+/// Query parameters for POST /mcp
+#[derive(Debug, Default, Deserialize)]
+pub struct McpParams {
+    /// Session to run the request in (alternative to the `Mcp-Session-Id` header)
+    pub session_id: Option<String>,
 }
 
 /// Request body for POST /mcp
@@ -231,17 +349,48 @@ pub enum McpResponse {
 }
 
 /// POST /mcp - Handle JSON-RPC requests
+///
+/// Requests naming a scoped session run against its scratch graph; requests
+/// naming no session, or an unscoped one, use the shared graph. Naming an
+/// unknown or expired session is answered with 404 and a JSON-RPC error.
 pub async fn mcp_request_handler(
     State(state): State<Arc<SseState>>,
     headers: HeaderMap,
+    Query(params): Query<McpParams>,
     Json(request): Json<JsonRpcRequest>,
 ) -> impl IntoResponse {
-    // Validate API key
-    let user = extract_user(&headers, &SseParams { api_key: None })
-        .unwrap_or_else(|| "anonymous".to_string());
+    // Attribute writes to the JWT-authenticated caller (the startup user otherwise)
+    // and check entity ACLs against their permissions; requests without a
+    // token run as the anonymous caller, which satisfies no ACL
+    let caller = match state.caller(&headers) {
+        Ok(caller) => Some(caller),
+        Err(e) => return unauthorized(&e),
+    };
 
+    let session_id = headers
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from)
+        .or(params.session_id);
     let id = request.id.clone().unwrap_or(Value::Null);
 
+    // Falling back to the shared graph would silently apply writes meant for
+    // a scratch graph, so a stale session id is an error
+    let scope = match session_id {
+        Some(ref session_id) => match state.sessions.touch(session_id).await {
+            Some(session) => session.scope,
+            None => {
+                let error = JsonRpcError::invalid_request(
+                    id,
+                    format!("Unknown or expired session '{}'", session_id),
+                );
+                return (StatusCode::NOT_FOUND, Json(error)).into_response();
+            }
+        },
+        None => None,
+    };
+    let tools = scope.as_ref().map_or(&state.tools, |scope| &scope.tools);
+
     // Validate JSON-RPC version
     if request.jsonrpc != "2.0" {
         let error = JsonRpcError::invalid_request(id, "jsonrpc must be '2.0'".to_string());
@@ -251,8 +400,24 @@ pub async fn mcp_request_handler(
     // Handle methods
     let result = match request.method.as_str() {
         "initialize" => handle_initialize(&state, id.clone()),
-        "tools/list" => handle_tools_list(&state, id.clone()),
-        "tools/call" => handle_tool_call(&state, id.clone(), request.params, &user),
+        "tools/list" => handle_tools_list(tools, id.clone()),
+        "tools/call" => handle_tool_call(tools, id.clone(), request.params, caller),
+        "tools/call_batch" => {
+            handle_tool_call_batch(tools, id.clone(), request.params, caller)
+        }
+        "server/capabilities" => handle_capabilities(tools, id.clone()),
+        // Stored results are only listed and read by the caller who stored them
+        "resources/list" => KnowledgeBase::run_as_caller(caller, || {
+            Ok(JsonRpcResponse::new(id.clone(), resources_list(&state.kb)))
+        }),
+        "resources/read" => KnowledgeBase::run_as_caller(caller, || {
+            handle_resources_read(&state.kb, id.clone(), request.params)
+        }),
+        // Promoted writes are checked against and attributed to the caller
+        "session/promote_to_shared" => {
+            let session_id = session_id.as_deref();
+            handle_promote(&state, id.clone(), session_id, request.params, caller).await
+        }
         "ping" => Ok(JsonRpcResponse::new(id.clone(), json!({}))),
         _ => {
             let error = JsonRpcError::method_not_found(id, request.method);
@@ -270,7 +435,8 @@ fn handle_initialize(state: &SseState, id: Value) -> Result<JsonRpcResponse, Jso
     let result = json!({
         "protocolVersion": "2024-11-05",
         "capabilities": {
-            "tools": {}
+            "tools": {},
+            "resources": {}
         },
         "serverInfo": {
             "name": state.server_name,
@@ -280,17 +446,70 @@ fn handle_initialize(state: &SseState, id: Value) -> Result<JsonRpcResponse, Jso
     Ok(JsonRpcResponse::new(id, result))
 }
 
-fn handle_tools_list(state: &SseState, id: Value) -> Result<JsonRpcResponse, JsonRpcError> {
-    let tools: Vec<McpTool> = state.tools.values().map(|t| t.definition()).collect();
+fn handle_tools_list(tools: &ToolMap, id: Value) -> Result<JsonRpcResponse, JsonRpcError> {
+    let tools: Vec<McpTool> = tools.values().map(|t| t.definition()).collect();
     let result = json!({ "tools": tools });
     Ok(JsonRpcResponse::new(id, result))
 }
 
-fn handle_tool_call(
+fn handle_capabilities(tools: &ToolMap, id: Value) -> Result<JsonRpcResponse, JsonRpcError> {
+    let result = tool_catalog(tools.values().map(|t| t.as_ref()));
+    Ok(JsonRpcResponse::new(id, result))
+}
+
+/// Handle resources/read (large tool results; see `server::resources_read`)
+fn handle_resources_read(
+    kb: &KnowledgeBase,
+    id: Value,
+    params: Option<Value>,
+) -> Result<JsonRpcResponse, JsonRpcError> {
+    let params = params.unwrap_or(json!({}));
+    resources_read(kb, &params)
+        .map(|result| JsonRpcResponse::new(id.clone(), result))
+        .map_err(|e| JsonRpcError::new(id, e.code, e.message, e.data))
+}
+
+/// Handle session/promote_to_shared: merge a scoped session into the shared graph
+///
+/// `entityNames` limits promotion to those entities and relations touching them.
+async fn handle_promote(
     state: &SseState,
     id: Value,
+    session_id: Option<&str>,
     params: Option<Value>,
-    _user: &str,
+    caller: Option<Caller>,
+) -> Result<JsonRpcResponse, JsonRpcError> {
+    let session_id = session_id.ok_or_else(|| {
+        JsonRpcError::invalid_params(id.clone(), "Missing session id".to_string())
+    })?;
+    let entity_names: Option<Vec<String>> = params
+        .as_ref()
+        .and_then(|p| p.get("entityNames"))
+        .map(|names| serde_json::from_value(names.clone()))
+        .transpose()
+        .map_err(|e| JsonRpcError::invalid_params(id.clone(), e.to_string()))?;
+
+    match state
+        .sessions
+        .promote_to_shared(session_id, &state.kb, entity_names.as_deref(), caller)
+        .await
+    {
+        Ok((report, warnings)) => {
+            let mut result = serde_json::to_value(&report).unwrap_or_default();
+            if !warnings.is_empty() {
+                result["warnings"] = json!(warnings);
+            }
+            Ok(JsonRpcResponse::new(id, result))
+        }
+        Err(e) => Err(JsonRpcError::invalid_params(id, e.to_string())),
+    }
+}
+
+fn handle_tool_call(
+    tools: &ToolMap,
+    id: Value,
+    params: Option<Value>,
+    caller: Option<Caller>,
 ) -> Result<JsonRpcResponse, JsonRpcError> {
     let params = params.ok_or_else(|| {
         JsonRpcError::invalid_params(id.clone(), "Missing parameters".to_string())
@@ -303,7 +522,7 @@ fn handle_tool_call(
             JsonRpcError::invalid_params(id.clone(), "Missing tool name".to_string())
         })?;
 
-    let tool = state.tools.get(tool_name).ok_or_else(|| {
+    let tool = tools.get(tool_name).ok_or_else(|| {
         JsonRpcError::new(
             id.clone(),
             -32602,
@@ -314,17 +533,33 @@ fn handle_tool_call(
 
     let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
 
-    match tool.execute(arguments) {
+    match KnowledgeBase::run_as_caller(caller, || {
+        track(tool_name, || execute_tool(tool.as_ref(), arguments))
+    }) {
         Ok(result) => Ok(JsonRpcResponse::new(id, result)),
-        Err(e) => Err(JsonRpcError::new(
-            id,
-            -32603,
-            "Tool execution error".to_string(),
-            Some(json!({"details": e.to_string()})),
-        )),
+        Err(e) => {
+            let error = ErrorObject::tool_error(e.as_ref());
+            Err(JsonRpcError::new(id, error.code, error.message, error.data))
+        }
     }
 }
 
+/// Handle tools/call_batch (sequential, non-atomic; see `server::execute_batch`)
+fn handle_tool_call_batch(
+    tools: &ToolMap,
+    id: Value,
+    params: Option<Value>,
+    caller: Option<Caller>,
+) -> Result<JsonRpcResponse, JsonRpcError> {
+    let params = params.unwrap_or(json!({}));
+
+    KnowledgeBase::run_as_caller(caller, || {
+        execute_batch(&params, |name| tools.get(name).map(|t| t.as_ref()))
+    })
+    .map(|result| JsonRpcResponse::new(id.clone(), result))
+    .map_err(|e| JsonRpcError::invalid_params(id, e))
+}
+
 /// GET /mcp/info - Get server info
 #[derive(Debug, Serialize)]
 pub struct ServerInfoResponse {
@@ -333,6 +568,10 @@ pub struct ServerInfoResponse {
     pub protocol_version: String,
     pub tool_count: usize,
     pub active_sessions: usize,
+    /// Current graph version, bumped by every mutation
+    pub graph_version: u64,
+    /// Fingerprint of the graph content (see the `graph_hash` tool)
+    pub content_hash: String,
 }
 
 pub async fn server_info_handler(State(state): State<Arc<SseState>>) -> impl IntoResponse {
@@ -342,6 +581,8 @@ pub async fn server_info_handler(State(state): State<Arc<SseState>>) -> impl Int
         protocol_version: "2024-11-05".to_string(),
         tool_count: state.tools.len(),
         active_sessions: state.sessions.session_count().await,
+        graph_version: state.kb.version(),
+        content_hash: state.kb.content_hash().unwrap_or_default(),
     };
     Json(info)
 }
@@ -371,7 +612,7 @@ pub struct AuthErrorResponse {
 }
 
 impl AuthErrorResponse {
-    fn from_auth_error(err: &AuthError) -> Self {
+    pub(crate) fn from_auth_error(err: &AuthError) -> Self {
         let (error, error_code) = match err {
             AuthError::InvalidCredentials => {
                 ("Invalid username or password".to_string(), "invalid_credentials".to_string())
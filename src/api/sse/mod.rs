@@ -9,12 +9,23 @@
 //! - `GET /mcp/info` - Server info and capabilities
 //! - `POST /auth/token` - Login and get JWT tokens
 //! - `POST /auth/refresh` - Refresh access token
+//!
+//! Connecting with `?scoped=true` gives the session a private scratch graph
+//! (see [`SessionScope`]); send its id as `Mcp-Session-Id` (or `?session_id=`)
+//! on `POST /mcp` to route tool calls there.
+//!
+//! Sessions idle for `MEMORY_SSE_SESSION_IDLE_SECS` (default 1800, 0 = never)
+//! are dropped. An open SSE stream keeps its session alive. `POST /mcp`
+//! naming a dropped or unknown session gets a 404 JSON-RPC error.
 
 pub mod auth;
 pub mod handler;
 pub mod session;
 
 pub use auth::{AuthError, Claims, JwtAuth, SharedJwtAuth, TokenPair};
+pub use session::SessionScope;
+
+use std::sync::Arc;
 
 use serde::Serialize;
 
@@ -42,6 +53,11 @@ pub enum SseEvent {
         server_name: String,
         server_version: String,
         sequence_id: u64,
+        /// Version of the shared graph (see `KnowledgeBase::version`)
+        graph_version: u64,
+        /// Whether tool calls go to a private scratch graph
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        scoped: bool,
     },
     /// Error notification
     Error {
@@ -57,4 +73,8 @@ pub struct ClientSession {
     pub user: String,
     pub api_key: Option<String>,
     pub connected_at: i64,
+    /// Last time the session was used (Unix seconds); see `SessionManager`
+    pub last_seen: i64,
+    /// Private scratch graph; None means the session uses the shared graph
+    pub scope: Option<Arc<SessionScope>>,
 }
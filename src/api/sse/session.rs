@@ -1,20 +1,113 @@
 //! Session management for SSE connections
+//!
+//! Every use of a session (a request naming it, or its SSE stream being
+//! alive) updates its `last_seen`. Sessions unused for longer than the idle
+//! timeout are evicted whenever sessions are looked up or counted, so
+//! `active_sessions` does not grow with every reconnect.
 
 use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 use super::ClientSession;
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::Tool;
+use crate::types::{Caller, ImportReport, McpResult};
+use crate::validation::ValidationWarning;
+
+/// Private scratch graph of a scoped session, with tools bound to it
+///
+/// Entities created through these tools stay invisible to other sessions
+/// until promoted with [`SessionManager::promote_to_shared`].
+pub struct SessionScope {
+    pub kb: Arc<KnowledgeBase>,
+    pub tools: HashMap<String, Arc<dyn Tool>>,
+}
+
+impl SessionScope {
+    /// Start a scratch graph from the current state of `shared`
+    pub fn new(shared: &KnowledgeBase) -> Self {
+        let kb = Arc::new(shared.scratch_copy());
+        let tools = crate::tools::get_all_tools(Arc::clone(&kb))
+            .into_iter()
+            .map(|tool| (tool.definition().name, tool))
+            .collect();
+        Self { kb, tools }
+    }
+}
+
+impl fmt::Debug for SessionScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionScope")
+            .field("tools", &self.tools.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Idle timeout used when `MEMORY_SSE_SESSION_IDLE_SECS` is unset
+pub const DEFAULT_SESSION_IDLE_SECS: u64 = 1800;
+
+/// Idle timeout from `MEMORY_SSE_SESSION_IDLE_SECS` (None when 0)
+pub fn idle_timeout_from_env() -> Option<Duration> {
+    let secs = env::var("MEMORY_SSE_SESSION_IDLE_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_SESSION_IDLE_SECS);
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
 
 /// Session manager for tracking connected clients
 pub struct SessionManager {
     sessions: RwLock<HashMap<String, ClientSession>>,
+    /// Sessions unused for longer are evicted (None keeps them forever)
+    idle_timeout: Option<Duration>,
 }
 
 impl SessionManager {
     pub fn new() -> Self {
+        Self::with_idle_timeout(idle_timeout_from_env())
+    }
+
+    /// Session manager evicting sessions idle for longer than `idle_timeout`
+    pub fn with_idle_timeout(idle_timeout: Option<Duration>) -> Self {
         Self {
             sessions: RwLock::new(HashMap::new()),
+            idle_timeout,
+        }
+    }
+
+    /// Whether a session last seen at `last_seen` has been idle too long
+    fn is_expired(&self, last_seen: i64, now: i64) -> bool {
+        self.idle_timeout
+            .is_some_and(|timeout| now - last_seen > timeout.as_secs() as i64)
+    }
+
+    /// Remove sessions idle for longer than the timeout, returning how many
+    pub async fn evict_idle(&self) -> usize {
+        if self.idle_timeout.is_none() {
+            return 0;
         }
+        let now = chrono::Utc::now().timestamp();
+        let mut sessions = self.sessions.write().await;
+        let before = sessions.len();
+        sessions.retain(|_, session| !self.is_expired(session.last_seen, now));
+        before - sessions.len()
+    }
+
+    /// Mark a session as used now, returning it (None if unknown or expired)
+    pub async fn touch(&self, session_id: &str) -> Option<ClientSession> {
+        let now = chrono::Utc::now().timestamp();
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.get_mut(session_id)?;
+        if self.is_expired(session.last_seen, now) {
+            sessions.remove(session_id);
+            return None;
+        }
+        session.last_seen = now;
+        Some(session.clone())
     }
 
     /// Generate a new session ID
@@ -29,30 +122,81 @@ impl SessionManager {
 
     /// Create a new session
     pub async fn create_session(&self, user: String, api_key: Option<String>) -> ClientSession {
+        self.evict_idle().await;
+
         let session_id = Self::generate_session_id();
+        let now = chrono::Utc::now().timestamp();
         let session = ClientSession {
             session_id: session_id.clone(),
             user,
             api_key,
-            connected_at: chrono::Utc::now().timestamp(),
+            connected_at: now,
+            last_seen: now,
+            scope: None,
         };
 
         self.sessions.write().await.insert(session_id, session.clone());
         session
     }
 
+    /// Create a session working on a private copy of `shared`
+    pub async fn create_scoped_session(
+        &self,
+        user: String,
+        api_key: Option<String>,
+        shared: &KnowledgeBase,
+    ) -> ClientSession {
+        let mut session = self.create_session(user, api_key).await;
+        session.scope = Some(Arc::new(SessionScope::new(shared)));
+        self.sessions
+            .write()
+            .await
+            .insert(session.session_id.clone(), session.clone());
+        session
+    }
+
+    /// Merge a scoped session's additions into `shared`
+    ///
+    /// With `entity_names`, only those entities (and relations touching them)
+    /// are promoted. The scratch graph is kept, so the session can go on.
+    /// The merge runs as `caller`, who must be allowed to change every
+    /// promoted entity and is recorded as the writer.
+    pub async fn promote_to_shared(
+        &self,
+        session_id: &str,
+        shared: &KnowledgeBase,
+        entity_names: Option<&[String]>,
+        caller: Option<Caller>,
+    ) -> McpResult<(ImportReport, Vec<ValidationWarning>)> {
+        let session = self
+            .get_session(session_id)
+            .await
+            .ok_or_else(|| format!("Unknown session: {}", session_id))?;
+        let scope = session
+            .scope
+            .ok_or_else(|| format!("Session {} is not scoped", session_id))?;
+        KnowledgeBase::run_as_caller(caller, || shared.promote_from(&scope.kb, entity_names))
+    }
+
     /// Remove a session
     pub async fn remove_session(&self, session_id: &str) {
         self.sessions.write().await.remove(session_id);
     }
 
-    /// Get session by ID
+    /// Get session by ID (None once it has been idle too long)
     pub async fn get_session(&self, session_id: &str) -> Option<ClientSession> {
-        self.sessions.read().await.get(session_id).cloned()
+        let now = chrono::Utc::now().timestamp();
+        self.sessions
+            .read()
+            .await
+            .get(session_id)
+            .filter(|session| !self.is_expired(session.last_seen, now))
+            .cloned()
     }
 
-    /// Get active session count
+    /// Get active session count, after evicting idle sessions
     pub async fn session_count(&self) -> usize {
+        self.evict_idle().await;
         self.sessions.read().await.len()
     }
 
@@ -126,4 +270,88 @@ mod tests {
         manager.remove_session(&session.session_id).await;
         assert_eq!(manager.session_count().await, 0);
     }
+
+    #[tokio::test]
+    async fn test_idle_session_is_evicted() {
+        let manager = SessionManager::with_idle_timeout(Some(Duration::from_secs(60)));
+        let idle = manager.create_session("alice".to_string(), None).await;
+        let active = manager.create_session("bob".to_string(), None).await;
+
+        // Pretend one was last used two minutes ago
+        manager
+            .sessions
+            .write()
+            .await
+            .get_mut(&idle.session_id)
+            .unwrap()
+            .last_seen -= 120;
+        assert!(manager.get_session(&idle.session_id).await.is_none());
+        assert!(manager.touch(&active.session_id).await.is_some());
+
+        assert_eq!(manager.session_count().await, 1);
+        assert!(manager.touch(&idle.session_id).await.is_none());
+        assert!(manager.get_session(&active.session_id).await.is_some());
+
+        // Without a timeout nothing expires
+        let manager = SessionManager::with_idle_timeout(None);
+        let session = manager.create_session("carol".to_string(), None).await;
+        manager.sessions.write().await.get_mut(&session.session_id).unwrap().last_seen = 0;
+        assert_eq!(manager.evict_idle().await, 0);
+        assert_eq!(manager.session_count().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_scoped_session_promotes_to_shared() {
+        use crate::types::Entity;
+        use serde_json::json;
+
+        let path = format!("target/test_session_scope_{}.jsonl", std::process::id());
+        let shared = KnowledgeBase::with_file_path(path.clone());
+        shared
+            .create_entities(vec![Entity::new("Api".to_string(), "Module".to_string())])
+            .unwrap();
+        let manager = SessionManager::new();
+        let session = manager
+            .create_scoped_session("alice".to_string(), None, &shared)
+            .await;
+        let scope = session.scope.clone().unwrap();
+
+        let create = &scope.tools["create_entities"];
+        create
+            .execute(json!({ "entities": [
+                { "name": "Draft", "entityType": "Idea" },
+                { "name": "Rejected", "entityType": "Idea" }
+            ]}))
+            .unwrap();
+        scope
+            .kb
+            .add_observations(vec![crate::types::Observation::new(
+                "Api".to_string(),
+                vec!["Rate limited".to_string()],
+            )])
+            .unwrap();
+
+        // Nothing leaks into the shared graph before promotion
+        assert_eq!(shared.read_graph(None, None).unwrap().entities.len(), 1);
+        assert!(manager.promote_to_shared("sess_missing", &shared, None, None).await.is_err());
+
+        let names = vec!["Draft".to_string(), "Api".to_string()];
+        let (report, _) = manager
+            .promote_to_shared(&session.session_id, &shared, Some(&names), None)
+            .await
+            .unwrap();
+        assert_eq!(report.entities_created, 1);
+        assert_eq!(report.entities_updated, 1);
+
+        let graph = shared.read_graph(None, None).unwrap();
+        let mut names: Vec<&str> = graph.entities.iter().map(|e| e.name.as_str()).collect();
+        names.sort_unstable();
+        assert_eq!(names, vec!["Api", "Draft"]);
+        assert!(graph.entities[0].observations.contains(&"Rate limited".to_string()));
+
+        let plain = manager.create_session("bob".to_string(), None).await;
+        assert!(manager.promote_to_shared(&plain.session_id, &shared, None, None).await.is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
 }
@@ -0,0 +1,239 @@
+//! Slow client handling for WebSocket and SSE streams
+//!
+//! Every connection reads the shared broadcast channel through its own
+//! receiver, which holds up to `MEMORY_WS_CLIENT_BUFFER` unread messages
+//! (16-65536, default 1024). A client that falls further behind loses the
+//! oldest messages and its receiver reports `Lagged`.
+//!
+//! On lag the stream first tries to resume: the broadcaster's replay history
+//! (the buffer behind `GET /api/events/replay`) is asked for everything after
+//! the last message the client received, and duplicates still queued in the
+//! receiver are skipped afterwards. Only when the history no longer reaches
+//! back that far does `MEMORY_WS_SLOW_CLIENT_POLICY` decide:
+//!
+//! - `drop-oldest` (default): send a `lagged` error and keep streaming. The
+//!   connection survives, but the client holds a graph with holes in it until
+//!   it refreshes; clients doing gap detection refresh on their own.
+//! - `disconnect`: send a `lagged` error and close the stream. The client has
+//!   to reconnect and reload the snapshot, but never keeps running on a graph
+//!   it knows is incomplete.
+//!
+//! A deeper buffer absorbs longer stalls at the cost of memory: the channel
+//! keeps every message until the slowest receiver has read it.
+
+use std::env;
+use std::ops::RangeInclusive;
+
+use super::batcher::parse_bounded;
+use super::broadcaster::EventBroadcaster;
+use super::events::WsMessage;
+
+/// Default number of unread messages buffered per client
+pub const DEFAULT_CLIENT_BUFFER: usize = 1024;
+
+const CLIENT_BUFFER_RANGE: RangeInclusive<usize> = 16..=65_536;
+
+/// What to do with a client that lagged past the replay history
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SlowClientPolicy {
+    /// Skip the lost messages, tell the client and keep streaming
+    #[default]
+    DropOldest,
+    /// Tell the client and close the stream
+    Disconnect,
+}
+
+impl SlowClientPolicy {
+    /// Parse `drop-oldest` or `disconnect`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "drop-oldest" | "drop_oldest" => Some(Self::DropOldest),
+            "disconnect" => Some(Self::Disconnect),
+            _ => None,
+        }
+    }
+}
+
+/// Buffering parameters for streaming clients
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackpressureConfig {
+    /// Unread messages each receiver holds before it lags
+    pub client_buffer: usize,
+    pub policy: SlowClientPolicy,
+}
+
+impl Default for BackpressureConfig {
+    fn default() -> Self {
+        Self {
+            client_buffer: DEFAULT_CLIENT_BUFFER,
+            policy: SlowClientPolicy::default(),
+        }
+    }
+}
+
+impl BackpressureConfig {
+    /// Read buffering parameters from the environment
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| env::var(key).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let policy = match lookup("MEMORY_WS_SLOW_CLIENT_POLICY") {
+            Some(raw) => SlowClientPolicy::parse(&raw).unwrap_or_else(|| {
+                eprintln!(
+                    "[WebSocket] Ignoring MEMORY_WS_SLOW_CLIENT_POLICY={}: expected drop-oldest or disconnect",
+                    raw
+                );
+                SlowClientPolicy::default()
+            }),
+            None => SlowClientPolicy::default(),
+        };
+
+        Self {
+            client_buffer: parse_bounded(
+                "MEMORY_WS_CLIENT_BUFFER",
+                lookup("MEMORY_WS_CLIENT_BUFFER"),
+                CLIENT_BUFFER_RANGE,
+                DEFAULT_CLIENT_BUFFER,
+            ),
+            policy,
+        }
+    }
+}
+
+/// How a stream recovers from `Lagged`
+#[derive(Debug)]
+pub enum Catchup {
+    /// Missed messages recovered from the replay history, oldest first
+    Replayed(Vec<WsMessage>),
+    /// Messages are lost; notify the client and keep streaming
+    Dropped,
+    /// Messages are lost; notify the client and close the stream
+    Disconnect,
+}
+
+/// Position of one client in the message stream
+pub struct ClientCursor {
+    policy: SlowClientPolicy,
+    /// Sequence id of the first message the client has not received
+    next_sequence_id: u64,
+    /// Whether any message was delivered (before that, duplicates are impossible)
+    started: bool,
+}
+
+impl ClientCursor {
+    /// Cursor for a client whose welcome message carried `sequence_id`
+    pub fn new(policy: SlowClientPolicy, sequence_id: u64) -> Self {
+        Self {
+            policy,
+            next_sequence_id: sequence_id,
+            started: false,
+        }
+    }
+
+    /// Record `msg` as delivered; false if the client already received it
+    pub fn advance(&mut self, msg: &WsMessage) -> bool {
+        if self.started && msg.sequence_id < self.next_sequence_id {
+            return false;
+        }
+        self.started = true;
+        self.next_sequence_id = msg.sequence_id + 1;
+        true
+    }
+
+    /// Recover from a lag, replaying from `history` when it reaches back far enough
+    ///
+    /// Replayed messages are already recorded as delivered.
+    pub fn catch_up(&mut self, history: Option<&EventBroadcaster>) -> Catchup {
+        let replay = history.and_then(|h| h.get_events_from(self.next_sequence_id));
+        match replay {
+            Some(messages) => {
+                let messages: Vec<WsMessage> =
+                    messages.into_iter().filter(|m| self.advance(m)).collect();
+                Catchup::Replayed(messages)
+            }
+            None => match self.policy {
+                SlowClientPolicy::DropOldest => Catchup::Dropped,
+                SlowClientPolicy::Disconnect => Catchup::Disconnect,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::websocket::broadcaster::EVENT_HISTORY_SIZE;
+    use crate::api::websocket::events::GraphEvent;
+    use tokio::sync::broadcast::error::TryRecvError;
+
+    fn deleted(name: &str) -> GraphEvent {
+        GraphEvent::EntityDeleted {
+            name: name.to_string(),
+            user: None,
+        }
+    }
+
+    #[test]
+    fn test_config_from_lookup() {
+        let config = BackpressureConfig::from_lookup(|key| match key {
+            "MEMORY_WS_SLOW_CLIENT_POLICY" => Some("disconnect".to_string()),
+            "MEMORY_WS_CLIENT_BUFFER" => Some("64".to_string()),
+            _ => None,
+        });
+        assert_eq!(config.policy, SlowClientPolicy::Disconnect);
+        assert_eq!(config.client_buffer, 64);
+
+        let config = BackpressureConfig::from_lookup(|key| match key {
+            "MEMORY_WS_SLOW_CLIENT_POLICY" => Some("block".to_string()),
+            "MEMORY_WS_CLIENT_BUFFER" => Some("1".to_string()),
+            _ => None,
+        });
+        assert_eq!(config, BackpressureConfig::default());
+    }
+
+    #[test]
+    fn test_lagged_client_resumes_from_history() {
+        let broadcaster = EventBroadcaster::new(2);
+        let mut rx = broadcaster.subscribe();
+        let mut cursor = ClientCursor::new(SlowClientPolicy::Disconnect, 0);
+
+        for name in ["A", "B", "C", "D", "E"] {
+            broadcaster.broadcast(deleted(name));
+        }
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Lagged(3))));
+
+        let Catchup::Replayed(messages) = cursor.catch_up(Some(&broadcaster)) else {
+            panic!("expected a replay");
+        };
+        let ids: Vec<u64> = messages.iter().map(|m| m.sequence_id).collect();
+        assert_eq!(ids, vec![0, 1, 2, 3, 4]);
+
+        // What the receiver still buffers was part of the replay
+        while let Ok(msg) = rx.try_recv() {
+            assert!(!cursor.advance(&msg));
+        }
+        broadcaster.broadcast(deleted("F"));
+        assert!(cursor.advance(&rx.try_recv().unwrap()));
+    }
+
+    #[test]
+    fn test_policy_applies_without_history() {
+        let mut cursor = ClientCursor::new(SlowClientPolicy::Disconnect, 7);
+        assert!(matches!(cursor.catch_up(None), Catchup::Disconnect));
+
+        let mut cursor = ClientCursor::new(SlowClientPolicy::DropOldest, 7);
+        assert!(matches!(cursor.catch_up(None), Catchup::Dropped));
+
+        // History that no longer reaches back to the cursor cannot resume it
+        let broadcaster = EventBroadcaster::new(2);
+        for i in 0..=EVENT_HISTORY_SIZE {
+            broadcaster.broadcast(deleted(&i.to_string()));
+        }
+        let mut cursor = ClientCursor::new(SlowClientPolicy::DropOldest, 0);
+        assert!(matches!(
+            cursor.catch_up(Some(&broadcaster)),
+            Catchup::Dropped
+        ));
+    }
+}
@@ -1,12 +1,93 @@
 //! Event batcher for debouncing high-frequency updates
+//!
+//! Batching is tuned through the environment (out-of-range values fall back to
+//! the default with a warning):
+//! - `MEMORY_WS_DEBOUNCE_MS`: flush interval, 1-10000 (default 50)
+//! - `MEMORY_WS_MAX_BATCH`: events per batch before a forced flush, 1-10000 (default 100)
 
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::env;
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{broadcast, mpsc};
 use tokio::time::interval;
 
 use super::events::{GraphEvent, WsMessage};
+use super::sequence::SequenceCounter;
+
+/// Default flush interval in milliseconds
+pub const DEFAULT_DEBOUNCE_MS: u64 = 50;
+
+/// Default maximum number of events per batch
+pub const DEFAULT_MAX_BATCH: usize = 100;
+
+const DEBOUNCE_MS_RANGE: RangeInclusive<u64> = 1..=10_000;
+const MAX_BATCH_RANGE: RangeInclusive<usize> = 1..=10_000;
+
+/// Batching parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchConfig {
+    pub debounce_ms: u64,
+    pub max_batch_size: usize,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            debounce_ms: DEFAULT_DEBOUNCE_MS,
+            max_batch_size: DEFAULT_MAX_BATCH,
+        }
+    }
+}
+
+impl BatchConfig {
+    /// Read batching parameters from the environment
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| env::var(key).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        Self {
+            debounce_ms: parse_bounded(
+                "MEMORY_WS_DEBOUNCE_MS",
+                lookup("MEMORY_WS_DEBOUNCE_MS"),
+                DEBOUNCE_MS_RANGE,
+                DEFAULT_DEBOUNCE_MS,
+            ),
+            max_batch_size: parse_bounded(
+                "MEMORY_WS_MAX_BATCH",
+                lookup("MEMORY_WS_MAX_BATCH"),
+                MAX_BATCH_RANGE,
+                DEFAULT_MAX_BATCH,
+            ),
+        }
+    }
+}
+
+/// Parse `value` if it lies in `range`, otherwise warn and use `default`
+pub(super) fn parse_bounded<T>(key: &str, value: Option<String>, range: RangeInclusive<T>, default: T) -> T
+where
+    T: std::str::FromStr + PartialOrd + std::fmt::Display,
+{
+    let Some(raw) = value else {
+        return default;
+    };
+
+    match raw.trim().parse::<T>() {
+        Ok(v) if range.contains(&v) => v,
+        _ => {
+            eprintln!(
+                "[WebSocket] Ignoring {}={}: expected {}..={}, using {}",
+                key,
+                raw,
+                range.start(),
+                range.end(),
+                default
+            );
+            default
+        }
+    }
+}
 
 /// Event batcher that collects events and sends them in batches
 pub struct EventBatcher {
@@ -23,28 +104,31 @@ pub struct EventBatcher {
     tx: broadcast::Sender<WsMessage>,
 
     /// Sequence counter for message IDs
-    sequence_counter: Arc<AtomicU64>,
+    sequence_counter: Arc<SequenceCounter>,
 }
 
 impl EventBatcher {
-    /// Create a new event batcher
+    /// Create a new event batcher configured from the environment
     pub fn new(
         tx: broadcast::Sender<WsMessage>,
-        sequence_counter: Arc<AtomicU64>,
+        sequence_counter: Arc<SequenceCounter>,
     ) -> Self {
-        Self {
-            buffer: Vec::new(),
-            flush_interval: Duration::from_millis(50),
-            max_batch_size: 100,
-            tx,
-            sequence_counter,
-        }
+        Self::from_config(tx, sequence_counter, BatchConfig::from_env())
+    }
+
+    /// Create a new event batcher from a [`BatchConfig`]
+    pub fn from_config(
+        tx: broadcast::Sender<WsMessage>,
+        sequence_counter: Arc<SequenceCounter>,
+        config: BatchConfig,
+    ) -> Self {
+        Self::with_config(tx, sequence_counter, config.debounce_ms, config.max_batch_size)
     }
 
     /// Create a new event batcher with custom settings
     pub fn with_config(
         tx: broadcast::Sender<WsMessage>,
-        sequence_counter: Arc<AtomicU64>,
+        sequence_counter: Arc<SequenceCounter>,
         flush_interval_ms: u64,
         max_batch_size: usize,
     ) -> Self {
@@ -73,7 +157,7 @@ impl EventBatcher {
             return;
         }
 
-        let seq = self.sequence_counter.fetch_add(1, Ordering::SeqCst);
+        let seq = self.sequence_counter.next_id();
 
         // If only one event, send it directly without wrapping in BatchUpdate
         let event = if self.buffer.len() == 1 {
@@ -127,12 +211,33 @@ impl EventBatcher {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::Entity;
+
+    #[test]
+    fn test_batch_config_parsing() {
+        let config = BatchConfig::from_lookup(|_| None);
+        assert_eq!(config, BatchConfig::default());
+
+        let config = BatchConfig::from_lookup(|key| match key {
+            "MEMORY_WS_DEBOUNCE_MS" => Some("200".to_string()),
+            "MEMORY_WS_MAX_BATCH" => Some("500".to_string()),
+            _ => None,
+        });
+        assert_eq!(config.debounce_ms, 200);
+        assert_eq!(config.max_batch_size, 500);
+
+        // Out of range or unparsable values keep the defaults
+        let config = BatchConfig::from_lookup(|key| match key {
+            "MEMORY_WS_DEBOUNCE_MS" => Some("0".to_string()),
+            "MEMORY_WS_MAX_BATCH" => Some("lots".to_string()),
+            _ => None,
+        });
+        assert_eq!(config, BatchConfig::default());
+    }
 
     #[tokio::test]
     async fn test_batcher_flushes_on_timer() {
         let (tx, mut rx) = broadcast::channel(100);
-        let counter = Arc::new(AtomicU64::new(0));
+        let counter = Arc::new(SequenceCounter::new());
         let mut batcher = EventBatcher::with_config(tx, counter, 10, 100);
 
         batcher.push(GraphEvent::EntityDeleted {
@@ -150,7 +255,7 @@ mod tests {
     #[tokio::test]
     async fn test_batcher_batches_multiple_events() {
         let (tx, mut rx) = broadcast::channel(100);
-        let counter = Arc::new(AtomicU64::new(0));
+        let counter = Arc::new(SequenceCounter::new());
         let mut batcher = EventBatcher::with_config(tx, counter, 50, 100);
 
         // Push multiple events
@@ -170,7 +275,7 @@ mod tests {
     #[tokio::test]
     async fn test_batcher_force_flush_on_max_size() {
         let (tx, mut rx) = broadcast::channel(100);
-        let counter = Arc::new(AtomicU64::new(0));
+        let counter = Arc::new(SequenceCounter::new());
         let mut batcher = EventBatcher::with_config(tx, counter, 1000, 5);
 
         // Push more than max_batch_size events
@@ -19,18 +19,23 @@ use std::sync::{OnceLock, RwLock};
 use tokio::sync::broadcast;
 
 use super::events::{GraphEvent, WsMessage};
-use std::sync::atomic::{AtomicU64, Ordering};
+use super::sequence::SequenceCounter;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 /// Global broadcaster instance (initialized once when HTTP server starts)
 static BROADCASTER: OnceLock<EventBroadcaster> = OnceLock::new();
 
+/// Number of live connections subscribed to inferred relations
+static INFERRED_SUBSCRIBERS: AtomicUsize = AtomicUsize::new(0);
+
 /// Maximum number of events to keep in history for replay
-const EVENT_HISTORY_SIZE: usize = 1000;
+pub(crate) const EVENT_HISTORY_SIZE: usize = 1000;
 
 /// Event broadcaster for WebSocket notifications
 pub struct EventBroadcaster {
     tx: broadcast::Sender<WsMessage>,
-    sequence_counter: AtomicU64,
+    sequence_counter: Arc<SequenceCounter>,
     /// Circular buffer of recent events for replay on reconnect
     event_history: RwLock<VecDeque<WsMessage>>,
 }
@@ -38,17 +43,30 @@ pub struct EventBroadcaster {
 impl EventBroadcaster {
     /// Create a new broadcaster with the given capacity
     pub fn new(capacity: usize) -> Self {
+        Self::with_sequence(capacity, Arc::new(SequenceCounter::new()))
+    }
+
+    /// Create a new broadcaster that numbers messages with an existing counter
+    pub fn with_sequence(capacity: usize, sequence_counter: Arc<SequenceCounter>) -> Self {
         let (tx, _) = broadcast::channel(capacity);
         Self {
             tx,
-            sequence_counter: AtomicU64::new(0),
+            sequence_counter,
             event_history: RwLock::new(VecDeque::with_capacity(EVENT_HISTORY_SIZE)),
         }
     }
 
     /// Broadcast an event to all connected WebSocket clients
+    ///
+    /// Inferred relations are opt-in, so they reuse the sequence ID of the
+    /// latest event instead of taking a new one; clients that never receive
+    /// them would otherwise see gaps and refresh.
     pub fn broadcast(&self, event: GraphEvent) {
-        let seq = self.sequence_counter.fetch_add(1, Ordering::SeqCst);
+        let seq = if matches!(event, GraphEvent::Inferred { .. }) {
+            self.sequence_counter.current().saturating_sub(1)
+        } else {
+            self.sequence_counter.next_id()
+        };
         let msg = WsMessage {
             event,
             sequence_id: seq,
@@ -70,7 +88,7 @@ impl EventBroadcaster {
 
     /// Get the current sequence ID
     pub fn current_sequence_id(&self) -> u64 {
-        self.sequence_counter.load(Ordering::SeqCst)
+        self.sequence_counter.current()
     }
 
     /// Subscribe to receive broadcast events
@@ -114,6 +132,26 @@ impl EventBroadcaster {
         Some(events)
     }
 
+    /// Get the events numbered `first_sequence_id` and later, for resuming a stream
+    ///
+    /// Returns None if history does not reach back to `first_sequence_id`
+    /// (or is empty), so some of the requested events are gone.
+    pub fn get_events_from(&self, first_sequence_id: u64) -> Option<Vec<WsMessage>> {
+        let history = self.event_history.read().ok()?;
+        let oldest_seq = history.front().map(|m| m.sequence_id)?;
+        if first_sequence_id < oldest_seq {
+            return None;
+        }
+
+        Some(
+            history
+                .iter()
+                .filter(|m| m.sequence_id >= first_sequence_id)
+                .cloned()
+                .collect(),
+        )
+    }
+
     /// Get the oldest sequence ID still in history
     pub fn oldest_sequence_id(&self) -> Option<u64> {
         self.event_history
@@ -133,6 +171,14 @@ pub fn init_broadcaster(capacity: usize) -> &'static EventBroadcaster {
     BROADCASTER.get_or_init(|| EventBroadcaster::new(capacity))
 }
 
+/// Initialize the global broadcaster sharing a sequence counter with AppState
+pub fn init_broadcaster_with_sequence(
+    capacity: usize,
+    sequence_counter: Arc<SequenceCounter>,
+) -> &'static EventBroadcaster {
+    BROADCASTER.get_or_init(|| EventBroadcaster::with_sequence(capacity, sequence_counter))
+}
+
 /// Get the global broadcaster (returns None if not initialized)
 pub fn get_broadcaster() -> Option<&'static EventBroadcaster> {
     BROADCASTER.get()
@@ -146,10 +192,43 @@ pub fn broadcast_event(event: GraphEvent) {
     }
 }
 
+/// Check whether any connection wants inferred relations
+///
+/// Mutations skip recomputing inferences entirely while this is false.
+pub fn has_inferred_subscribers() -> bool {
+    INFERRED_SUBSCRIBERS.load(Ordering::SeqCst) > 0
+}
+
+/// Registration of a connection on the `inferred` channel
+///
+/// Held for as long as the connection wants inferred events; dropping it
+/// unregisters the connection.
+pub struct InferredSubscription(());
+
+impl InferredSubscription {
+    /// Register a new inferred subscriber
+    pub fn new() -> Self {
+        INFERRED_SUBSCRIBERS.fetch_add(1, Ordering::SeqCst);
+        Self(())
+    }
+}
+
+impl Default for InferredSubscription {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for InferredSubscription {
+    fn drop(&mut self) {
+        INFERRED_SUBSCRIBERS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 /// Helper functions for common events
 pub mod helpers {
     use super::*;
-    use crate::types::{Entity, Relation};
+    use crate::types::{Entity, InferredRelation, Relation};
 
     /// Broadcast entity created event
     pub fn entity_created(entity: &Entity, user: Option<String>) {
@@ -193,6 +272,15 @@ pub mod helpers {
             user,
         });
     }
+
+    /// Broadcast relations inferred for an entity (never persisted)
+    pub fn inferred(target: &str, relations: Vec<InferredRelation>) {
+        broadcast_event(GraphEvent::Inferred {
+            target: target.to_string(),
+            relations,
+            persistent: false,
+        });
+    }
 }
 
 #[cfg(test)]
@@ -212,6 +300,34 @@ mod tests {
         assert_eq!(broadcaster.current_sequence_id(), 1);
     }
 
+    #[test]
+    fn test_inferred_events_reuse_sequence_id() {
+        let broadcaster = EventBroadcaster::new(100);
+        let mut rx = broadcaster.subscribe();
+        let deleted = |name: &str| GraphEvent::EntityDeleted {
+            name: name.to_string(),
+            user: None,
+        };
+
+        broadcaster.broadcast(deleted("A"));
+        broadcaster.broadcast(GraphEvent::Inferred {
+            target: "B".to_string(),
+            relations: Vec::new(),
+            persistent: false,
+        });
+        broadcaster.broadcast(deleted("C"));
+
+        let ids: Vec<u64> = (0..3).map(|_| rx.try_recv().unwrap().sequence_id).collect();
+        assert_eq!(ids, vec![0, 0, 1]);
+    }
+
+    #[test]
+    fn test_inferred_subscription_guard() {
+        let subscription = InferredSubscription::new();
+        assert!(has_inferred_subscribers());
+        drop(subscription);
+    }
+
     #[test]
     fn test_event_history_storage() {
         let broadcaster = EventBroadcaster::new(100);
@@ -1,7 +1,10 @@
 //! WebSocket event types for real-time graph updates
 
 use serde::{Deserialize, Serialize};
-use crate::types::{Entity, Relation};
+use crate::types::{Entity, InferredRelation, Relation};
+
+/// Subscription channel for inferred (non-persistent) relations
+pub const INFERRED_CHANNEL: &str = "inferred";
 
 /// Graph events that can be broadcast to WebSocket clients
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -36,6 +39,13 @@ pub enum GraphEvent {
         user: Option<String>,
     },
 
+    /// A relation's validity window was changed
+    RelationUpdated {
+        payload: Relation,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        user: Option<String>,
+    },
+
     /// A relation was deleted
     RelationDeleted {
         from: String,
@@ -49,6 +59,46 @@ pub enum GraphEvent {
     BatchUpdate {
         events: Vec<GraphEvent>,
     },
+
+    /// Relations derived for an entity after a mutation touched it
+    ///
+    /// Inferred relations are never stored; `persistent` is always false.
+    /// Only sent to clients subscribed to the `inferred` channel.
+    Inferred {
+        target: String,
+        relations: Vec<InferredRelation>,
+        persistent: bool,
+    },
+
+    /// The whole graph was replaced (e.g. restored from a backup)
+    ///
+    /// Clients should discard their copy of the graph and reload it.
+    Resync {
+        reason: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        user: Option<String>,
+    },
+}
+
+impl GraphEvent {
+    /// Names of the entities whose inferences may change because of this event
+    pub fn affected_entities(&self) -> Vec<&str> {
+        match self {
+            GraphEvent::EntityCreated { payload, .. } => vec![payload.name.as_str()],
+            GraphEvent::EntityUpdated { name, .. } | GraphEvent::EntityDeleted { name, .. } => {
+                vec![name.as_str()]
+            }
+            GraphEvent::RelationCreated { payload, .. }
+            | GraphEvent::RelationUpdated { payload, .. } => {
+                vec![payload.from.as_str(), payload.to.as_str()]
+            }
+            GraphEvent::RelationDeleted { from, to, .. } => vec![from.as_str(), to.as_str()],
+            GraphEvent::BatchUpdate { events } => {
+                events.iter().flat_map(|e| e.affected_entities()).collect()
+            }
+            GraphEvent::Inferred { .. } | GraphEvent::Resync { .. } => Vec::new(),
+        }
+    }
 }
 
 /// WebSocket message wrapper with metadata
@@ -105,13 +155,16 @@ pub struct WelcomeMessage {
     #[serde(rename = "type")]
     pub msg_type: String,
     pub current_sequence_id: u64,
+    /// Graph version at connect time (see `KnowledgeBase::version`)
+    pub graph_version: u64,
 }
 
 impl WelcomeMessage {
-    pub fn new(current_sequence_id: u64) -> Self {
+    pub fn new(current_sequence_id: u64, graph_version: u64) -> Self {
         Self {
             msg_type: "connected".to_string(),
             current_sequence_id,
+            graph_version,
         }
     }
 }
@@ -146,6 +199,9 @@ mod tests {
                 updated_by: String::new(),
                 created_at: 0,
                 updated_at: 0,
+                id: String::new(),
+                protected: false,
+                acl: Vec::new(),
             },
             user: Some("test_user".to_string()),
         };
@@ -171,6 +227,31 @@ mod tests {
         assert!(json.contains("42"));
     }
 
+    #[test]
+    fn test_inferred_event_serialization() {
+        let event = GraphEvent::Inferred {
+            target: "A".to_string(),
+            relations: vec![],
+            persistent: false,
+        };
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains(r#""type":"inferred""#));
+        assert!(json.contains(r#""persistent":false"#));
+        assert!(event.affected_entities().is_empty());
+    }
+
+    #[test]
+    fn test_affected_entities() {
+        let event = GraphEvent::RelationDeleted {
+            from: "A".to_string(),
+            to: "B".to_string(),
+            relation_type: "depends_on".to_string(),
+            user: None,
+        };
+        assert_eq!(event.affected_entities(), vec!["A", "B"]);
+    }
+
     #[test]
     fn test_client_message_parsing() {
         let json = r#"{"type":"ping"}"#;
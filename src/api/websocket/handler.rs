@@ -1,13 +1,28 @@
 //! WebSocket connection handler
+//!
+//! With JWT auth configured, the upgrade is checked by [`ws_auth`] before it
+//! reaches [`ws_handler`]. Browsers cannot set headers on a WebSocket, so the
+//! token may also be passed as `?token=`. Clients only receive events about
+//! entities their caller may see (see `KnowledgeBase::visible_event`).
 
 use std::sync::Arc;
 use axum::{
-    extract::{ws::{Message, WebSocket, WebSocketUpgrade}, Query, State},
-    response::Response,
+    extract::{ws::{Message, WebSocket, WebSocketUpgrade}, Extension, Query, Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
 };
 use serde::Deserialize;
 
-use super::events::{ClientMessage, PongMessage, WelcomeMessage};
+use crate::api::sse::handler::AuthErrorResponse;
+use crate::api::sse::{AuthError, Claims, JwtAuth};
+use crate::types::Caller;
+use super::backpressure::{Catchup, ClientCursor};
+use super::broadcaster::{get_broadcaster, InferredSubscription};
+use super::events::{
+    ClientMessage, GraphEvent, PongMessage, WelcomeMessage, WsMessage, INFERRED_CHANNEL,
+};
 use super::state::AppState;
 
 /// Query parameters for WebSocket connection
@@ -17,60 +32,157 @@ pub struct WsParams {
     pub token: Option<String>,
 }
 
+/// JWT settings for the WebSocket upgrade, the same as the SSE endpoints use
+#[derive(Clone, Default)]
+pub struct WsAuth {
+    pub jwt_auth: Option<Arc<JwtAuth>>,
+    pub require_auth: bool,
+}
+
+impl WsAuth {
+    pub fn new(jwt_auth: Option<Arc<JwtAuth>>, require_auth: bool) -> Self {
+        Self {
+            jwt_auth,
+            require_auth,
+        }
+    }
+
+    /// Validate the token from the `Authorization` header or the `token` param
+    ///
+    /// A token that is given must be valid; no token is only an error when
+    /// auth is required. Always `Ok(None)` without JWT auth.
+    pub fn validate(
+        &self,
+        headers: &HeaderMap,
+        token: Option<&str>,
+    ) -> Result<Option<Claims>, AuthError> {
+        let Some(jwt_auth) = &self.jwt_auth else {
+            return Ok(None);
+        };
+
+        if let Some(header) = headers.get("Authorization").and_then(|h| h.to_str().ok()) {
+            return jwt_auth.validate_authorization(header).map(Some);
+        }
+        if let Some(token) = token {
+            return jwt_auth.validate_token(token).map(Some);
+        }
+
+        if self.require_auth {
+            Err(AuthError::MissingToken)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Caller the token belongs to, or [`Caller::anonymous`] without one
+    ///
+    /// Fails like [`WsAuth::validate`].
+    pub fn caller(&self, headers: &HeaderMap, token: Option<&str>) -> Result<Caller, AuthError> {
+        Ok(match self.validate(headers, token)? {
+            Some(claims) => Caller::new(claims.sub, claims.permissions),
+            None => Caller::anonymous(),
+        })
+    }
+}
+
+/// Reject the WebSocket upgrade with 401 unless the token checks out
+///
+/// The caller is passed on to [`ws_handler`], so events about entities they
+/// may not see are not sent to them.
+pub async fn ws_auth(
+    State(auth): State<WsAuth>,
+    Query(params): Query<WsParams>,
+    headers: HeaderMap,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    match auth.caller(&headers, params.token.as_deref()) {
+        Ok(caller) => {
+            request.extensions_mut().insert(caller);
+            next.run(request).await
+        }
+        Err(e) => (
+            StatusCode::UNAUTHORIZED,
+            Json(AuthErrorResponse::from_auth_error(&e)),
+        )
+            .into_response(),
+    }
+}
+
 /// WebSocket upgrade handler
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
-    Query(_params): Query<WsParams>,
     State(state): State<Arc<AppState>>,
+    Extension(caller): Extension<Caller>,
 ) -> Response {
-    // TODO: Validate token if provided
-    // if let Some(token) = params.token {
-    //     if !validate_token(&token) {
-    //         return (StatusCode::UNAUTHORIZED, "Invalid token").into_response();
-    //     }
-    // }
-
-    ws.on_upgrade(move |socket| handle_socket(socket, state))
+    ws.on_upgrade(move |socket| handle_socket(socket, state, caller))
 }
 
 /// Handle an individual WebSocket connection
-async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
+async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>, caller: Caller) {
     // Subscribe to broadcast events
     let mut rx = state.subscribe();
 
+    // Set while the client is subscribed to the `inferred` channel
+    let mut inferred: Option<InferredSubscription> = None;
+
     // Send welcome message with current sequence ID
-    let welcome = WelcomeMessage::new(state.current_sequence_id());
+    let sequence_id = state.current_sequence_id();
+    let welcome = WelcomeMessage::new(sequence_id, state.kb.version());
     if let Ok(json) = serde_json::to_string(&welcome) {
         if socket.send(Message::Text(json)).await.is_err() {
             return; // Client disconnected immediately
         }
     }
 
+    // Delivered position, for resuming after a lag
+    let mut cursor = ClientCursor::new(state.slow_client_policy, sequence_id);
+
     loop {
         tokio::select! {
             // Broadcast events to client
             result = rx.recv() => {
-                match result {
+                let messages = match result {
                     Ok(msg) => {
-                        if let Ok(json) = serde_json::to_string(&msg) {
-                            if socket.send(Message::Text(json)).await.is_err() {
-                                break; // Client disconnected
-                            }
+                        if !cursor.advance(&msg) {
+                            continue; // Already replayed after a lag
                         }
+                        vec![msg]
                     }
                     Err(broadcast::error::RecvError::Lagged(n)) => {
-                        // Client is too slow, they missed events
-                        // Send an error message so they know to refresh
-                        let error_msg = serde_json::json!({
-                            "type": "error",
-                            "code": "lagged",
-                            "message": format!("Missed {} events, please refresh", n)
-                        });
-                        let _ = socket.send(Message::Text(error_msg.to_string())).await;
+                        // Client is too slow; resume from the replay history
+                        // if it still covers the gap
+                        match cursor.catch_up(get_broadcaster()) {
+                            Catchup::Replayed(messages) => messages,
+                            lost => {
+                                // Send an error message so they know to refresh
+                                let error_msg = serde_json::json!({
+                                    "type": "error",
+                                    "code": "lagged",
+                                    "message": format!("Missed {} events, please refresh", n)
+                                });
+                                let _ = socket.send(Message::Text(error_msg.to_string())).await;
+                                if matches!(lost, Catchup::Disconnect) {
+                                    let _ = socket.send(Message::Close(None)).await;
+                                    break;
+                                }
+                                continue;
+                            }
+                        }
                     }
                     Err(broadcast::error::RecvError::Closed) => {
                         break; // Channel closed
                     }
+                };
+                let messages = messages
+                    .into_iter()
+                    .filter_map(|msg| {
+                        let event = state.kb.visible_event(&msg.event, &caller)?;
+                        Some(WsMessage { event, ..msg })
+                    })
+                    .collect();
+                if !send_messages(&mut socket, messages, inferred.is_some()).await {
+                    break; // Client disconnected
                 }
             }
 
@@ -78,7 +190,7 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
             result = socket.recv() => {
                 match result {
                     Some(Ok(msg)) => {
-                        if !handle_client_message(msg, &mut socket).await {
+                        if !handle_client_message(msg, &mut socket, &mut inferred).await {
                             break; // Client requested close or error
                         }
                     }
@@ -90,9 +202,29 @@ async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>) {
     }
 }
 
+/// Send broadcast messages to the client, skipping inferred ones unless subscribed
+/// Returns false if the client disconnected
+async fn send_messages(socket: &mut WebSocket, messages: Vec<WsMessage>, inferred: bool) -> bool {
+    for msg in messages {
+        if !inferred && matches!(msg.event, GraphEvent::Inferred { .. }) {
+            continue;
+        }
+        if let Ok(json) = serde_json::to_string(&msg) {
+            if socket.send(Message::Text(json)).await.is_err() {
+                return false;
+            }
+        }
+    }
+    true
+}
+
 /// Handle a message from the client
 /// Returns false if the connection should be closed
-async fn handle_client_message(msg: Message, socket: &mut WebSocket) -> bool {
+async fn handle_client_message(
+    msg: Message,
+    socket: &mut WebSocket,
+    inferred: &mut Option<InferredSubscription>,
+) -> bool {
     match msg {
         Message::Text(text) => {
             if let Ok(client_msg) = serde_json::from_str::<ClientMessage>(&text) {
@@ -105,12 +237,18 @@ async fn handle_client_message(msg: Message, socket: &mut WebSocket) -> bool {
                     }
                     ClientMessage::Subscribe { channel, filter } => {
                         // TODO: Implement channel filtering
-                        // For now, all clients receive all events
-                        let _ = (channel, filter);
+                        // For now, all clients receive all graph events;
+                        // inferred relations are opt-in
+                        if channel == INFERRED_CHANNEL && inferred.is_none() {
+                            *inferred = Some(InferredSubscription::new());
+                        }
+                        let _ = filter;
                     }
                     ClientMessage::Unsubscribe { channel } => {
                         // TODO: Implement channel filtering
-                        let _ = channel;
+                        if channel == INFERRED_CHANNEL {
+                            *inferred = None;
+                        }
                     }
                 }
             }
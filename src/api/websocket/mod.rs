@@ -4,15 +4,26 @@
 //!
 //! ## Features
 //! - Real-time entity/relation updates
-//! - Event batching (debounce 50ms, max 100 events)
-//! - Sequence ID tracking for gap detection
+//! - Event batching (debounce 50ms, max 100 events; tunable via
+//!   `MEMORY_WS_DEBOUNCE_MS` / `MEMORY_WS_MAX_BATCH`)
+//! - Sequence ID tracking for gap detection, monotonic across restarts
 //! - Reconnection support with "Snapshot then Subscribe" strategy
+//! - Optional `inferred` channel streaming derived (non-persistent) relations
+//! - Per-client buffering; lagging clients resume from the replay history
+//!   (`MEMORY_WS_CLIENT_BUFFER` / `MEMORY_WS_SLOW_CLIENT_POLICY`)
+//! - JWT auth on the upgrade when configured (`Authorization` header or `?token=`)
 
 pub mod events;
 pub mod handler;
 pub mod state;
+pub mod backpressure;
 pub mod batcher;
 pub mod broadcaster;
+pub mod sequence;
 
 // Re-export commonly used items
-pub use broadcaster::{broadcast_event, get_broadcaster, init_broadcaster, helpers as ws_helpers};
+pub use broadcaster::{
+    broadcast_event, get_broadcaster, has_inferred_subscribers, helpers as ws_helpers,
+    init_broadcaster, init_broadcaster_with_sequence, InferredSubscription,
+};
+pub use sequence::SequenceCounter;
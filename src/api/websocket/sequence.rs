@@ -0,0 +1,162 @@
+//! Sequence counter for WebSocket/SSE messages
+//!
+//! Sequence ids must stay monotonic across restarts, otherwise clients doing
+//! gap detection see ids lower than the last one they stored.
+//!
+//! # Persistence
+//!
+//! Ids are handed out from memory, but the counter keeps a reserved ceiling on
+//! disk: a value above every id issued so far, rewritten once every
+//! [`SEQUENCE_RESERVE_BLOCK`] ids. On startup the counter resumes at that
+//! ceiling, so a restart (or crash) only ever skips ids. Clients treat the
+//! skip like any other gap and do a full refresh, which they need after a
+//! restart anyway because the replay history is gone.
+//!
+//! # Event Sourcing
+//!
+//! Sequence ids are not event ids: a batch is one message for many events, and
+//! inferred broadcasts have no event at all. With Event Sourcing on, the counter
+//! additionally resumes no lower than the store's `next_event_id`, so a restored
+//! sequence id is never below the id of an event that was already persisted.
+//!
+//! # Graph version
+//!
+//! Sequence ids order messages; they do not say which graph state a client
+//! holds. For that, REST responses and welcome messages also carry
+//! `graph_version` ([`KnowledgeBase::version`]), bumped by every mutation
+//! whether or not anything is broadcast. Unlike sequence ids it is not
+//! persisted, so compare it for equality only, within one server run.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::knowledge_base::KnowledgeBase;
+
+/// Number of ids reserved per write of the sequence file
+pub const SEQUENCE_RESERVE_BLOCK: u64 = 1000;
+
+/// Monotonic message sequence counter, optionally persisted
+pub struct SequenceCounter {
+    next: AtomicU64,
+    file: Option<PathBuf>,
+    /// Ceiling currently recorded in `file`
+    reserved: Mutex<u64>,
+}
+
+impl SequenceCounter {
+    /// Create an in-memory counter starting at zero
+    pub fn new() -> Self {
+        Self {
+            next: AtomicU64::new(0),
+            file: None,
+            reserved: Mutex::new(0),
+        }
+    }
+
+    /// Create a counter restored from `path`, starting no lower than `floor`
+    pub fn persistent(path: impl Into<PathBuf>, floor: u64) -> Self {
+        let path = path.into();
+        let stored = fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .unwrap_or(0);
+        let start = stored.max(floor);
+
+        let counter = Self {
+            next: AtomicU64::new(start),
+            file: Some(path),
+            reserved: Mutex::new(start),
+        };
+        counter.reserve(start);
+        counter
+    }
+
+    /// Create a persistent counter stored next to the knowledge base's memory file
+    pub fn for_knowledge_base(kb: &KnowledgeBase) -> Self {
+        let path = PathBuf::from(format!("{}.seq", kb.memory_file_path));
+        Self::persistent(path, kb.next_event_id().unwrap_or(0))
+    }
+
+    /// Take the next sequence id
+    pub fn next_id(&self) -> u64 {
+        let seq = self.next.fetch_add(1, Ordering::SeqCst);
+        self.reserve(seq);
+        seq
+    }
+
+    /// Get the current sequence ID (the id the next message will get)
+    pub fn current(&self) -> u64 {
+        self.next.load(Ordering::SeqCst)
+    }
+
+    /// Make sure the persisted ceiling covers `seq`
+    fn reserve(&self, seq: u64) {
+        let Some(ref path) = self.file else {
+            return;
+        };
+
+        let mut reserved = self.reserved.lock().unwrap();
+        if seq < *reserved {
+            return;
+        }
+
+        let ceiling = seq + SEQUENCE_RESERVE_BLOCK;
+        // Write-then-rename so a crash never leaves a truncated file behind
+        let tmp = path.with_extension("seq.tmp");
+        let result = fs::write(&tmp, ceiling.to_string()).and_then(|_| fs::rename(&tmp, path));
+        if let Err(e) = result {
+            eprintln!("[Sequence] Failed to persist sequence ceiling: {}", e);
+        }
+        *reserved = ceiling;
+    }
+}
+
+impl Default for SequenceCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "memory_graph_seq_{}_{}.seq",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn test_sequence_survives_restart() {
+        let path = temp_path("restart");
+        let _ = fs::remove_file(&path);
+
+        let counter = SequenceCounter::persistent(&path, 0);
+        assert_eq!(counter.next_id(), 0);
+        assert_eq!(counter.next_id(), 1);
+        drop(counter);
+
+        // A restart resumes above every id issued before
+        let counter = SequenceCounter::persistent(&path, 0);
+        assert!(counter.next_id() > 1);
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_sequence_respects_floor() {
+        let path = temp_path("floor");
+        let _ = fs::remove_file(&path);
+
+        let counter = SequenceCounter::persistent(&path, 5000);
+        assert_eq!(counter.current(), 5000);
+        assert_eq!(counter.next_id(), 5000);
+
+        let _ = fs::remove_file(&path);
+    }
+}
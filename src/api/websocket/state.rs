@@ -1,11 +1,13 @@
 //! WebSocket application state
 
-use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::broadcast;
 
+use crate::api::rest::ResponseVersion;
 use crate::knowledge_base::KnowledgeBase;
+use super::backpressure::{BackpressureConfig, SlowClientPolicy};
 use super::events::{GraphEvent, WsMessage};
+use super::sequence::SequenceCounter;
 
 /// Shared application state for WebSocket connections
 ///
@@ -19,26 +21,45 @@ pub struct AppState {
     pub event_tx: broadcast::Sender<WsMessage>,
 
     /// Monotonically increasing sequence counter
-    pub sequence_counter: Arc<AtomicU64>,
+    pub sequence_counter: Arc<SequenceCounter>,
+
+    /// What to do with clients that lag past the replay history
+    pub slow_client_policy: SlowClientPolicy,
 }
 
 impl AppState {
     /// Create a new AppState with the given knowledge base
     pub fn new(kb: Arc<KnowledgeBase>) -> Self {
-        // Buffer 1024 events - if clients are too slow, they'll miss events
-        // and need to do a full refresh
-        let (event_tx, _) = broadcast::channel(1024);
+        Self::with_sequence(kb, Arc::new(SequenceCounter::new()))
+    }
+
+    /// Create a new AppState that numbers messages with an existing counter
+    pub fn with_sequence(kb: Arc<KnowledgeBase>, sequence_counter: Arc<SequenceCounter>) -> Self {
+        // Each client buffers this many messages before it lags
+        let config = BackpressureConfig::from_env();
+        let (event_tx, _) = broadcast::channel(config.client_buffer);
+        Self::with_sender(kb, sequence_counter, event_tx, config.policy)
+    }
 
+    /// Create a new AppState streaming an existing channel, such as the
+    /// global broadcaster's, so lagging clients can resume from its history
+    pub fn with_sender(
+        kb: Arc<KnowledgeBase>,
+        sequence_counter: Arc<SequenceCounter>,
+        event_tx: broadcast::Sender<WsMessage>,
+        slow_client_policy: SlowClientPolicy,
+    ) -> Self {
         Self {
             kb,
             event_tx,
-            sequence_counter: Arc::new(AtomicU64::new(0)),
+            sequence_counter,
+            slow_client_policy,
         }
     }
 
     /// Broadcast a graph event to all connected WebSocket clients
     pub fn broadcast(&self, event: GraphEvent) {
-        let seq = self.sequence_counter.fetch_add(1, Ordering::SeqCst);
+        let seq = self.sequence_counter.next_id();
         let msg = WsMessage {
             event,
             sequence_id: seq,
@@ -51,7 +72,15 @@ impl AppState {
 
     /// Get the current sequence ID
     pub fn current_sequence_id(&self) -> u64 {
-        self.sequence_counter.load(Ordering::SeqCst)
+        self.sequence_counter.current()
+    }
+
+    /// Sequence ID and graph version to stamp a REST response with
+    pub fn response_version(&self) -> ResponseVersion {
+        ResponseVersion {
+            sequence_id: self.current_sequence_id(),
+            graph_version: self.kb.version(),
+        }
     }
 
     /// Subscribe to receive broadcast events
@@ -95,6 +124,9 @@ mod tests {
                 updated_by: String::new(),
                 created_at: 0,
                 updated_at: 0,
+                id: String::new(),
+                protected: false,
+                acl: Vec::new(),
             },
             user: Some("tester".to_string()),
         });
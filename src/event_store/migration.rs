@@ -159,6 +159,9 @@ impl MigrationTool {
                 name: entity.name.clone(),
                 entity_type: entity.entity_type.clone(),
                 observations: entity.observations.clone(),
+                id: entity.id.clone(),
+                protected: entity.protected,
+                acl: entity.acl.clone(),
             };
 
             let user = if entity.created_by.is_empty() {
@@ -189,6 +192,8 @@ impl MigrationTool {
                 relation_type: relation.relation_type.clone(),
                 valid_from: relation.valid_from.map(|v| v as i64),
                 valid_to: relation.valid_to.map(|v| v as i64),
+                created_at: (relation.created_at > 0).then_some(relation.created_at as i64),
+                properties: relation.properties.clone(),
             };
 
             let user = if relation.created_by.is_empty() {
@@ -306,6 +311,9 @@ mod tests {
                 updated_by: String::new(),
                 created_at: 0,
                 updated_at: 0,
+                id: String::new(),
+                protected: false,
+                acl: Vec::new(),
             },
         ];
 
@@ -316,8 +324,10 @@ mod tests {
                 relation_type: "test".to_string(),
                 created_by: "tester".to_string(),
                 created_at: 0,
+                updated_at: 0,
                 valid_from: None,
                 valid_to: None,
+                properties: Default::default(),
             },
         ];
 
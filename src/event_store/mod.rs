@@ -2,9 +2,13 @@
 //!
 //! This module provides the core event sourcing infrastructure:
 //! - `EventStore`: Manages append-only event log and state replay
-//! - `SnapshotManager`: Handles snapshot creation and loading
-//! - `LogRotation`: Archives old events after snapshots
+//! - `SnapshotManager`: Handles snapshot creation and loading, full or delta
+//! - `LogRotation`: Archives old events after snapshots and rolls the live
+//!   log into numbered segments (`EventStoreConfig::segment_size`,
+//!   `MEMORY_SEGMENT_BYTES=N`)
 //! - `StatsCollector`: Collects metrics and statistics
+//! - `ReplayStatus`: Startup replay progress, for logs and the `/ready`
+//!   endpoint (`MEMORY_REPLAY_PROGRESS_EVERY=N`)
 //!
 //! # Architecture
 //!
@@ -21,15 +25,48 @@
 //! │ (latest.jsonl)│    │ after snapshot  │
 //! └───────────────┘    └─────────────────┘
 //! ```
+//!
+//! # Multiple Writers
+//!
+//! Each process is the single writer for the event IDs in its
+//! `EventStoreConfig::id_range`; giving every writer a disjoint range keeps
+//! IDs unique in a shared log. The server defaults to IDs below
+//! `EXTERNAL_ID_BASE`, leaving everything above it to external producers. A server in tail mode (`MEMORY_EVENT_TAIL=true`)
+//! reads events outside its own range with `EventStore::tail` and applies
+//! them as they arrive. Log rotation rewrites `events.jsonl`, so external
+//! writers should pause while it runs.
+//!
+//! Snapshots record the log position they reflect
+//! (`SnapshotMeta::log_position`): the number of log lines before them,
+//! archived ones included, which `data/log_state.json` keeps count of.
+//! Startup replays the lines after it and rotation archives the lines
+//! before it, whichever range their IDs are in.
+//!
+//! Writers can instead share one range by reserving blocks of it
+//! (`EventStoreConfig::id_block_size`, `MEMORY_EVENT_ID_BLOCK=N`): each
+//! reservation advances a high-water mark in `data/id_reservations.json`
+//! under a file lock, see `EventStore::reserve_id_block`. IDs from different
+//! writers then interleave in the log, which is why snapshots and rotation
+//! go by log position, and archives are listed in the order they were
+//! written rather than by the IDs in their names. Reservations are only held
+//! in memory: a restarted writer reserves a fresh block, and the rest of its
+//! old one stays unused.
 
 mod migration;
+mod progress;
 mod rotation;
 mod snapshot;
 mod stats;
 mod store;
 
 pub use migration::{MigrationResult, MigrationTool};
+pub use progress::{
+    replay_status, ReplayProgress, ReplayStatus, DEFAULT_REPLAY_PROGRESS_EVERY,
+};
 pub use rotation::{ArchiveInfo, LogRotation};
 pub use snapshot::SnapshotManager;
 pub use stats::{EventStoreStats, ReplayBenchmark, StatsCollector};
-pub use store::{EventStore, EventStoreConfig, EventStoreError, EventStoreResult};
+pub use store::{
+    EventStore, EventStoreConfig, EventStoreError, EventStoreResult, SnapshotData, TailCursor,
+    TailedEvent, EXTERNAL_ID_BASE,
+};
@@ -0,0 +1,76 @@
+//! Replay progress reporting
+//!
+//! Replaying a large event log at startup can take a while. `EventStore`
+//! reports its progress every `EventStoreConfig::replay_progress_every`
+//! events (`MEMORY_REPLAY_PROGRESS_EVERY=N`, default 10000): to stderr, and
+//! to the process-wide [`replay_status`] that the HTTP `/ready` endpoint
+//! serves. Library callers can pass their own callback to
+//! `EventStore::replay_all_with_progress` / `replay_after_with_progress`.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use serde::Serialize;
+
+/// Default number of events between progress reports
+pub const DEFAULT_REPLAY_PROGRESS_EVERY: usize = 10_000;
+
+/// How far a replay has got
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ReplayProgress {
+    /// Events applied so far
+    pub replayed: u64,
+    /// Events to apply in this replay
+    pub total: u64,
+}
+
+/// Replay state shared with the readiness endpoint
+#[derive(Debug, Default)]
+pub struct ReplayStatus {
+    replaying: AtomicBool,
+    replayed: AtomicU64,
+    total: AtomicU64,
+}
+
+static REPLAY_STATUS: ReplayStatus = ReplayStatus {
+    replaying: AtomicBool::new(false),
+    replayed: AtomicU64::new(0),
+    total: AtomicU64::new(0),
+};
+
+/// Replay state of this process
+pub fn replay_status() -> &'static ReplayStatus {
+    &REPLAY_STATUS
+}
+
+impl ReplayStatus {
+    /// Mark a replay as started
+    pub fn start(&self) {
+        self.replayed.store(0, Ordering::Relaxed);
+        self.total.store(0, Ordering::Relaxed);
+        self.replaying.store(true, Ordering::Release);
+    }
+
+    /// Record progress of the running replay
+    pub fn update(&self, progress: ReplayProgress) {
+        self.total.store(progress.total, Ordering::Relaxed);
+        self.replayed.store(progress.replayed, Ordering::Relaxed);
+    }
+
+    /// Mark the replay as done (whether it succeeded or not)
+    pub fn finish(&self) {
+        self.replaying.store(false, Ordering::Release);
+    }
+
+    /// Whether a replay is running
+    pub fn is_replaying(&self) -> bool {
+        self.replaying.load(Ordering::Acquire)
+    }
+
+    /// Progress of the running (or last) replay
+    pub fn progress(&self) -> ReplayProgress {
+        ReplayProgress {
+            replayed: self.replayed.load(Ordering::Relaxed),
+            total: self.total.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -4,13 +4,40 @@
 //! - Rotating event logs after snapshot
 //! - Archiving old events with timestamps
 //! - Cleaning up old archives
+//! - Rolling the live log into numbered segments
 //! - Compression (future)
+//!
+//! With `EventStoreConfig::segment_size` set, `events.jsonl` is renamed to
+//! `segments/events-NNNNNN.jsonl` whenever it reaches that size, and a new
+//! live log starts. The log is then the closed segments in number order
+//! followed by `events.jsonl` (see [`LogRotation::log_files`]). Rotation
+//! archives across all of them. Rolling renames the live log, so external
+//! writers should pause while it runs, as for rotation.
 
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
 
-use super::store::{EventStoreConfig, EventStoreResult};
+use serde::{Deserialize, Serialize};
+
+use super::store::{EventStoreConfig, EventStoreError, EventStoreResult};
+use crate::types::Event;
+use crate::utils::{atomic_write_with, current_timestamp};
+
+/// Contents of the log state file
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LogState {
+    /// Log lines moved to archives so far
+    archived_lines: u64,
+    /// Archive file names in log order, oldest first
+    ///
+    /// IDs from several writers interleave, so archive names do not sort in
+    /// log order; archives from before this list existed sort first.
+    #[serde(default)]
+    archives: Vec<String>,
+    /// Unix timestamp (seconds) of the last rotation
+    updated_at: u64,
+}
 
 /// Log rotation manager for event archives
 pub struct LogRotation {
@@ -26,7 +53,13 @@ impl LogRotation {
     /// Rotate the current event log after a snapshot
     ///
     /// This moves events up to the snapshot point to an archive file,
-    /// keeping only events after the snapshot in the active log.
+    /// keeping only events after the snapshot in the active log. Closed
+    /// segments are read too and removed once their events are archived or
+    /// moved to the active log.
+    ///
+    /// Only for snapshots without a log position: event IDs from several
+    /// writers interleave, so `rotate_to_position` is used whenever the
+    /// snapshot has one.
     ///
     /// # Arguments
     /// * `snapshot_event_id` - The last event ID included in the snapshot
@@ -35,35 +68,62 @@ impl LogRotation {
     /// * `Ok(Some(path))` - Path to the archive file if rotation occurred
     /// * `Ok(None)` - No rotation needed (no events to archive)
     pub fn rotate_after_snapshot(&self, snapshot_event_id: u64) -> EventStoreResult<Option<PathBuf>> {
+        // If we can't parse an event ID, keep the line in the active log
+        self.rotate_lines(|_, line| {
+            self.extract_event_id(line)
+                .is_some_and(|event_id| event_id <= snapshot_event_id)
+        })
+        .map(|archive| archive.map(|(path, _)| path))
+    }
+
+    /// Rotate the event log up to a snapshot's log position
+    ///
+    /// Archives the lines before `log_position` (see
+    /// `SnapshotMeta::log_position`), whoever wrote them, and keeps the rest
+    /// in the active log. The count of archived lines is kept in
+    /// `data/log_state.json`, so positions stay valid across rotations; that
+    /// file must not be deleted while snapshots refer to positions.
+    pub fn rotate_to_position(&self, log_position: u64) -> EventStoreResult<Option<PathBuf>> {
+        let cut = log_position.saturating_sub(self.archived_lines()?);
+        let archive = self.rotate_lines(|index, _| index < cut)?;
+        Ok(archive.map(|(path, _)| path))
+    }
+
+    /// Move the log lines `archive` selects (by index and content) to a new
+    /// archive file, keeping the rest in the active log
+    ///
+    /// The new count of archived lines is recorded in the log state after
+    /// the archive is written and before the active log is replaced.
+    fn rotate_lines(
+        &self,
+        mut archive: impl FnMut(u64, &str) -> bool,
+    ) -> EventStoreResult<Option<(PathBuf, usize)>> {
         let events_path = self.config.events_path();
+        let segments = self.list_segments()?;
 
-        if !events_path.exists() {
+        if !events_path.exists() && segments.is_empty() {
             return Ok(None);
         }
 
-        // Read all events
-        let file = File::open(&events_path)?;
-        let reader = BufReader::new(file);
-
+        // Read all events, oldest segment first
         let mut archive_lines = Vec::new();
         let mut keep_lines = Vec::new();
+        let mut index = 0;
+
+        for path in self.log_files()? {
+            let reader = BufReader::new(File::open(&path)?);
+            for line_result in reader.lines() {
+                let line = line_result?;
+                if line.trim().is_empty() {
+                    continue;
+                }
 
-        for line_result in reader.lines() {
-            let line = line_result?;
-            if line.trim().is_empty() {
-                continue;
-            }
-
-            // Parse event ID from the line
-            if let Some(event_id) = self.extract_event_id(&line) {
-                if event_id <= snapshot_event_id {
+                if archive(index, &line) {
                     archive_lines.push(line);
                 } else {
                     keep_lines.push(line);
                 }
-            } else {
-                // If we can't parse, keep it in the active log
-                keep_lines.push(line);
+                index += 1;
             }
         }
 
@@ -77,9 +137,14 @@ impl LogRotation {
         fs::create_dir_all(&archive_dir)?;
 
         // Generate archive filename with event range
-        let archive_filename = format!("events_{}_to_{}.jsonl",
-            self.extract_event_id(&archive_lines[0]).unwrap_or(0),
-            snapshot_event_id
+        let ids: Vec<u64> = archive_lines
+            .iter()
+            .filter_map(|line| self.extract_event_id(line))
+            .collect();
+        let archive_filename = format!(
+            "events_{}_to_{}.jsonl",
+            ids.iter().min().copied().unwrap_or(0),
+            ids.iter().max().copied().unwrap_or(0)
         );
         let archive_path = archive_dir.join(&archive_filename);
 
@@ -92,6 +157,11 @@ impl LogRotation {
             archive_file.sync_all()?;
         }
 
+        let mut state = self.load_log_state()?;
+        state.archived_lines += archive_lines.len() as u64;
+        state.archives.push(archive_filename);
+        self.save_log_state(state)?;
+
         // Write remaining events back to active log
         {
             let temp_path = events_path.with_extension("tmp");
@@ -105,13 +175,94 @@ impl LogRotation {
             fs::rename(&temp_path, &events_path)?;
         }
 
+        // Every segment's events are now in the archive or the active log
+        for segment in &segments {
+            fs::remove_file(segment)?;
+        }
+
         println!(
             "Rotated {} events to archive: {}",
             archive_lines.len(),
             archive_path.display()
         );
 
-        Ok(Some(archive_path))
+        Ok(Some((archive_path, archive_lines.len())))
+    }
+
+    /// Number of log lines moved to archives so far
+    ///
+    /// Log positions count these before the lines still in the log.
+    pub fn archived_lines(&self) -> EventStoreResult<u64> {
+        Ok(self.load_log_state()?.archived_lines)
+    }
+
+    fn load_log_state(&self) -> EventStoreResult<LogState> {
+        let path = self.config.log_state_path();
+        if !path.exists() {
+            return Ok(LogState::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    fn save_log_state(&self, mut state: LogState) -> EventStoreResult<()> {
+        state.updated_at = current_timestamp();
+        atomic_write_with(self.config.log_state_path(), |file| {
+            let json = serde_json::to_string(&state)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            writeln!(file, "{}", json)
+        })
+        .map_err(|e| EventStoreError::Io(std::io::Error::other(e)))
+    }
+
+    /// Close the live log as the next numbered segment
+    ///
+    /// Returns the segment path, or `None` if the live log is missing or
+    /// empty. The next append starts a new `events.jsonl`.
+    pub fn roll_segment(&self) -> EventStoreResult<Option<PathBuf>> {
+        let events_path = self.config.events_path();
+        if !events_path.exists() || fs::metadata(&events_path)?.len() == 0 {
+            return Ok(None);
+        }
+
+        let number = self
+            .list_segments()?
+            .last()
+            .and_then(|path| parse_segment_number(path))
+            .map_or(1, |n| n + 1);
+        let segment_path = self.config.segment_path(number);
+        fs::create_dir_all(self.config.segments_dir())?;
+        fs::rename(&events_path, &segment_path)?;
+
+        Ok(Some(segment_path))
+    }
+
+    /// Closed segments of the event log, oldest first
+    pub fn list_segments(&self) -> EventStoreResult<Vec<PathBuf>> {
+        let segments_dir = self.config.segments_dir();
+        if !segments_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut segments: Vec<(u64, PathBuf)> = Vec::new();
+        for entry in fs::read_dir(&segments_dir)? {
+            let path = entry?.path();
+            if let Some(number) = parse_segment_number(&path) {
+                segments.push((number, path));
+            }
+        }
+        segments.sort();
+
+        Ok(segments.into_iter().map(|(_, path)| path).collect())
+    }
+
+    /// Every file of the event log in order: closed segments, then the live log
+    pub fn log_files(&self) -> EventStoreResult<Vec<PathBuf>> {
+        let mut files = self.list_segments()?;
+        let events_path = self.config.events_path();
+        if events_path.exists() {
+            files.push(events_path);
+        }
+        Ok(files)
     }
 
     /// Extract event ID from a JSON line
@@ -141,20 +292,50 @@ impl LogRotation {
                 // Count events in archive
                 let event_count = self.count_events(&path)?;
 
+                let (first_event_id, last_event_id) = parse_archive_range(&path).unzip();
+
                 archives.push(ArchiveInfo {
                     path,
                     size,
                     event_count,
+                    first_event_id,
+                    last_event_id,
+                    compressed: false,
                 });
             }
         }
 
-        // Sort by filename (which includes event IDs)
-        archives.sort_by(|a, b| a.path.file_name().cmp(&b.path.file_name()));
+        // Oldest first: in rotation order, then by the event IDs in the file names
+        let order = self.load_log_state()?.archives;
+        let rank = |archive: &ArchiveInfo| {
+            let name = archive.path.file_name().and_then(|n| n.to_str());
+            order.iter().position(|listed| Some(listed.as_str()) == name)
+        };
+        archives.sort_by(|a, b| {
+            (rank(a), a.first_event_id, a.last_event_id, a.path.file_name())
+                .cmp(&(rank(b), b.first_event_id, b.last_event_id, b.path.file_name()))
+        });
 
         Ok(archives)
     }
 
+    /// Load every event from all archives, oldest archive first
+    ///
+    /// Lines that fail to parse are skipped, as in `EventStore::load_events`.
+    pub fn load_archived_events(&self) -> EventStoreResult<Vec<Event>> {
+        let mut events = Vec::new();
+        for archive in self.list_archives()? {
+            let reader = BufReader::new(File::open(&archive.path)?);
+            for line in reader.lines() {
+                let line = line?;
+                if let Ok(event) = Event::from_json_line(&line) {
+                    events.push(event);
+                }
+            }
+        }
+        Ok(events)
+    }
+
     /// Count events in a file
     fn count_events(&self, path: &Path) -> EventStoreResult<usize> {
         let file = File::open(path)?;
@@ -171,16 +352,14 @@ impl LogRotation {
     /// # Returns
     /// * Number of archives deleted
     pub fn cleanup_old_archives(&self, keep_count: usize) -> EventStoreResult<usize> {
-        let mut archives = self.list_archives()?;
+        let archives = self.list_archives()?;
 
         if archives.len() <= keep_count {
             return Ok(0);
         }
 
-        // Sort by filename descending (newest first)
-        archives.sort_by(|a, b| b.path.file_name().cmp(&a.path.file_name()));
-
-        let to_delete = &archives[keep_count..];
+        // Archives are listed oldest first
+        let to_delete = &archives[..archives.len() - keep_count];
         let delete_count = to_delete.len();
 
         for archive in to_delete {
@@ -198,8 +377,27 @@ impl LogRotation {
     }
 }
 
+/// Read the event ID range from an `events_{first}_to_{last}.jsonl` name
+fn parse_archive_range(path: &Path) -> Option<(u64, u64)> {
+    let stem = path.file_stem()?.to_str()?.strip_prefix("events_")?;
+    let (first, last) = stem.split_once("_to_")?;
+    Some((first.parse().ok()?, last.parse().ok()?))
+}
+
+/// Read the segment number from an `events-{number}.jsonl` name
+fn parse_segment_number(path: &Path) -> Option<u64> {
+    if path.extension().and_then(|s| s.to_str()) != Some("jsonl") {
+        return None;
+    }
+    path.file_stem()?
+        .to_str()?
+        .strip_prefix("events-")?
+        .parse()
+        .ok()
+}
+
 /// Information about an archive file
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ArchiveInfo {
     /// Path to the archive file
     pub path: PathBuf,
@@ -207,6 +405,12 @@ pub struct ArchiveInfo {
     pub size: u64,
     /// Number of events in the archive
     pub event_count: usize,
+    /// First event ID in the archive (from the file name)
+    pub first_event_id: Option<u64>,
+    /// Last event ID in the archive (from the file name)
+    pub last_event_id: Option<u64>,
+    /// Whether the archive is compressed (compression is not implemented yet)
+    pub compressed: bool,
 }
 
 #[cfg(test)]
@@ -252,6 +456,119 @@ mod tests {
         assert_eq!(active_count, 2);
     }
 
+    #[test]
+    fn test_rotate_to_position() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = EventStoreConfig::new(temp_dir.path().join("data"));
+        let external = EventStoreConfig {
+            id_range: 1000..2000,
+            ..config.clone()
+        };
+
+        // Two writers interleave: IDs 1, 1000, 2, 1001, ...
+        let mut store = EventStore::with_config(config.clone());
+        let mut sidecar = EventStore::with_config(external);
+        sidecar.initialize().unwrap();
+        for i in 1..=3 {
+            for writer in [&mut store, &mut sidecar] {
+                writer.create_and_append_event(
+                    EventType::EntityCreated,
+                    "user".to_string(),
+                    serde_json::json!({"name": format!("Entity{}", i), "entity_type": "Test"}),
+                ).unwrap();
+            }
+        }
+
+        let rotation = LogRotation::new(config.clone());
+        assert_eq!(rotation.archived_lines().unwrap(), 0);
+        let archive_path = rotation.rotate_to_position(3).unwrap().unwrap();
+        assert!(archive_path.ends_with("events_1_to_1000.jsonl"));
+        assert_eq!(rotation.count_events(&archive_path).unwrap(), 3);
+        assert_eq!(rotation.archived_lines().unwrap(), 3);
+
+        // Positions keep counting the archived lines
+        let (events, end) = store.load_positioned_events().unwrap();
+        let positioned: Vec<(u64, u64)> = events.iter().map(|(p, e)| (*p, e.event_id)).collect();
+        assert_eq!(positioned, vec![(3, 1001), (4, 3), (5, 1002)]);
+        assert_eq!(end, 6);
+        assert!(rotation.rotate_to_position(3).unwrap().is_none());
+
+        // Archives list in log order, not by the IDs in their names
+        rotation.rotate_to_position(4).unwrap().unwrap();
+        rotation.rotate_to_position(5).unwrap().unwrap();
+        let names: Vec<String> = rotation
+            .list_archives()
+            .unwrap()
+            .iter()
+            .map(|a| a.path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["events_1_to_1000.jsonl", "events_1001_to_1001.jsonl", "events_3_to_3.jsonl"]
+        );
+        assert_eq!(rotation.cleanup_old_archives(1).unwrap(), 2);
+        assert!(config.archive_dir().join("events_3_to_3.jsonl").exists());
+    }
+
+    #[test]
+    fn test_segments_replay_in_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = EventStoreConfig {
+            segment_size: Some(600),
+            ..EventStoreConfig::new(temp_dir.path().join("data"))
+        };
+        let external = EventStoreConfig {
+            id_range: 1000..2000,
+            ..config.clone()
+        };
+
+        let mut store = EventStore::with_config(config.clone());
+        let mut sidecar = EventStore::with_config(external);
+        let mut tailed = Vec::new();
+        for i in 1..=20 {
+            store.create_and_append_event(
+                EventType::EntityCreated,
+                "user".to_string(),
+                serde_json::json!({
+                    "name": format!("Entity{}", i),
+                    "entity_type": "Test",
+                    "observations": []
+                }),
+            ).unwrap();
+            if i % 3 == 0 {
+                tailed.extend(sidecar.tail().unwrap());
+            }
+        }
+
+        let rotation = LogRotation::new(config.clone());
+        let segments = rotation.list_segments().unwrap();
+        assert!(segments.len() >= 3, "log was rolled into segments");
+        // Each segment was closed by the append that took it past the size
+        for segment in &segments {
+            assert!(fs::metadata(segment).unwrap().len() >= 600);
+        }
+        assert_eq!(parse_segment_number(&segments[0]), Some(1));
+
+        // Replay reads every segment, then the live log
+        let events = store.load_events().unwrap();
+        let ids: Vec<u64> = events.iter().map(|e| e.event_id).collect();
+        assert_eq!(ids, (1..=20).collect::<Vec<u64>>());
+        let (entities, _) = EventStore::with_config(config.clone()).initialize().unwrap();
+        assert_eq!(entities.len(), 20);
+        assert_eq!(entities[19].name, "Entity20");
+
+        // Tailing picks up events written before a roll exactly once
+        tailed.extend(sidecar.tail().unwrap());
+        assert_eq!(tailed.len(), 20);
+
+        // Rotation archives across segments and drops them
+        let archive_path = rotation.rotate_after_snapshot(15).unwrap().unwrap();
+        assert_eq!(rotation.count_events(&archive_path).unwrap(), 15);
+        assert!(rotation.list_segments().unwrap().is_empty());
+        let ids: Vec<u64> = store.load_events().unwrap().iter().map(|e| e.event_id).collect();
+        assert_eq!(ids, vec![16, 17, 18, 19, 20]);
+    }
+
     #[test]
     fn test_list_archives() {
         let temp_dir = TempDir::new().unwrap();
@@ -268,6 +585,10 @@ mod tests {
         let archives = rotation.list_archives().unwrap();
 
         assert_eq!(archives.len(), 2);
+        let first = archives.iter().find(|a| a.first_event_id == Some(1)).unwrap();
+        assert_eq!(first.last_event_id, Some(100));
+        assert_eq!(first.event_count, 2);
+        assert!(!first.compressed);
     }
 
     #[test]
@@ -3,14 +3,52 @@
 //! Handles creation, loading, and management of state snapshots.
 //! Snapshots are point-in-time captures of the materialized state
 //! that allow fast startup without replaying all events.
-
+//!
+//! # Delta snapshots
+//!
+//! With `EventStoreConfig::max_delta_chain` set, most snapshots are deltas:
+//! `delta-<last_event_id>.jsonl` files holding only the entities and
+//! relations touched since the previous snapshot, plus removal markers for
+//! those that are gone. Loading applies each delta in turn onto the full
+//! snapshot in `latest.jsonl`; a delta whose `base_event_id` does not match
+//! the state loaded so far ends the chain, and the event log replays the
+//! rest. The next full snapshot removes the deltas.
+
+use std::collections::BTreeSet;
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
 
-use crate::types::{Entity, Relation, SnapshotMeta};
+use crate::types::{
+    Entity, EntityCreatedData, EntityDeletedData, EntityUpdatedData, Event, EventType,
+    ObservationAddedData, ObservationEditedData, ObservationRemovedData, Relation,
+    RelationCreatedData, RelationDeletedData, RelationUpdatedData, SnapshotMeta,
+};
 use crate::utils::atomic_write_with;
 
-use super::store::{EventStoreConfig, EventStoreError, EventStoreResult};
+use super::store::{EventStore, EventStoreConfig, EventStoreError, EventStoreResult, SnapshotData};
+
+/// Relation identity: (from, to, relation type)
+type RelationKey = (String, String, String);
+
+/// Contents of a delta snapshot file
+struct Delta {
+    meta: SnapshotMeta,
+    entities: Vec<Entity>,
+    relations: Vec<Relation>,
+    removed_entities: Vec<String>,
+    removed_relations: Vec<RelationKey>,
+}
+
+fn relation_key(relation: &Relation) -> RelationKey {
+    (
+        relation.from.clone(),
+        relation.to.clone(),
+        relation.relation_type.clone(),
+    )
+}
 
 /// Snapshot Manager handles creating and loading snapshots
 pub struct SnapshotManager {
@@ -33,6 +71,15 @@ impl SnapshotManager {
         self.config.previous_snapshot_path()
     }
 
+    /// Get path to the file a snapshot was written to
+    pub fn path_for(&self, meta: &SnapshotMeta) -> PathBuf {
+        if meta.is_delta {
+            self.config.delta_snapshot_path(meta.last_event_id)
+        } else {
+            self.config.latest_snapshot_path()
+        }
+    }
+
     /// Check if a snapshot exists
     pub fn snapshot_exists(&self) -> bool {
         self.config.latest_snapshot_path().exists()
@@ -49,11 +96,14 @@ impl SnapshotManager {
     /// # Arguments
     ///
     /// * `last_event_id` - The ID of the last event included in this snapshot
+    /// * `log_position` - The log lines included in this snapshot, if known
+    ///   (see `SnapshotMeta::log_position`)
     /// * `entities` - Current entities to snapshot
     /// * `relations` - Current relations to snapshot
     pub fn create_snapshot(
         &self,
         last_event_id: u64,
+        log_position: Option<u64>,
         entities: &[Entity],
         relations: &[Relation],
     ) -> EventStoreResult<SnapshotMeta> {
@@ -64,7 +114,8 @@ impl SnapshotManager {
         fs::create_dir_all(self.config.snapshots_dir())?;
 
         // Create metadata
-        let meta = SnapshotMeta::new(last_event_id, entities.len(), relations.len());
+        let meta = SnapshotMeta::new(last_event_id, entities.len(), relations.len())
+            .with_log_position(log_position);
 
         // Write snapshot atomically
         let meta_clone = meta.clone();
@@ -90,7 +141,7 @@ impl SnapshotManager {
 
             Ok(())
         })
-        .map_err(|e| EventStoreError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+        .map_err(|e| EventStoreError::Io(std::io::Error::other(e)))?;
 
         // Backup is handled by atomic_write_with's rename
         // But we need to manually handle previous backup
@@ -117,6 +168,22 @@ impl SnapshotManager {
         entities: &[Entity],
         relations: &[Relation],
     ) -> EventStoreResult<SnapshotMeta> {
+        self.write_full_snapshot(
+            SnapshotMeta::new(last_event_id, entities.len(), relations.len()),
+            entities,
+            relations,
+        )
+    }
+
+    /// Write `meta`, entities and relations as the full snapshot, keeping
+    /// the previous one as a backup
+    fn write_full_snapshot(
+        &self,
+        meta: SnapshotMeta,
+        entities: &[Entity],
+        relations: &[Relation],
+    ) -> EventStoreResult<SnapshotMeta> {
+        let last_event_id = meta.last_event_id;
         let latest_path = self.config.latest_snapshot_path();
         let previous_path = self.config.previous_snapshot_path();
         let temp_path = latest_path.with_extension("tmp");
@@ -124,9 +191,6 @@ impl SnapshotManager {
         // Ensure snapshots directory exists
         fs::create_dir_all(self.config.snapshots_dir())?;
 
-        // Create metadata
-        let meta = SnapshotMeta::new(last_event_id, entities.len(), relations.len());
-
         // Step 1: Write to temp file
         {
             let mut file = File::create(&temp_path)?;
@@ -160,6 +224,9 @@ impl SnapshotManager {
         // Step 3: Atomic rename temp to latest
         fs::rename(&temp_path, &latest_path)?;
 
+        // Deltas on top of the old snapshot are superseded
+        self.remove_deltas()?;
+
         println!(
             "Created snapshot with backup: {} entities, {} relations (event_id: {})",
             entities.len(),
@@ -170,7 +237,246 @@ impl SnapshotManager {
         Ok(meta)
     }
 
+    /// Snapshot the state as a delta if the config allows, or in full
+    ///
+    /// A delta is written when a full snapshot exists and fewer than
+    /// `max_delta_chain` deltas sit on top of it; otherwise this is
+    /// `create_snapshot_with_backup`. A delta also needs a new event of this
+    /// store since the base, and the base to carry a log position if
+    /// `log_position` is given.
+    pub fn create_incremental_snapshot(
+        &self,
+        last_event_id: u64,
+        log_position: Option<u64>,
+        entities: &[Entity],
+        relations: &[Relation],
+    ) -> EventStoreResult<SnapshotMeta> {
+        if self.snapshot_exists() && self.delta_paths()?.len() < self.config.max_delta_chain {
+            if let Some(base) = self.load_meta()? {
+                if base.last_event_id < last_event_id
+                    && base.log_position.is_some() == log_position.is_some()
+                {
+                    return self.create_delta_snapshot(
+                        &base,
+                        last_event_id,
+                        log_position,
+                        entities,
+                        relations,
+                    );
+                }
+            }
+        }
+        self.write_full_snapshot(
+            SnapshotMeta::new(last_event_id, entities.len(), relations.len())
+                .with_log_position(log_position),
+            entities,
+            relations,
+        )
+    }
+
+    /// Write a delta holding what the events since `base` touched, taken
+    /// from the current state
+    ///
+    /// The events are those between the two log positions, whoever wrote
+    /// them, or without positions `base.last_event_id + 1 ..= last_event_id`.
+    ///
+    /// Entities and relations that no longer exist are written as removal
+    /// markers. An entity deleted and created again in the range gets both a
+    /// marker and its current state, so relations dropped with the old
+    /// entity do not survive the delta.
+    pub fn create_delta_snapshot(
+        &self,
+        base: &SnapshotMeta,
+        last_event_id: u64,
+        log_position: Option<u64>,
+        entities: &[Entity],
+        relations: &[Relation],
+    ) -> EventStoreResult<SnapshotMeta> {
+        let base_event_id = base.last_event_id;
+        let mut touched_entities: BTreeSet<String> = BTreeSet::new();
+        let mut deleted_entities: BTreeSet<String> = BTreeSet::new();
+        let mut touched_relations: BTreeSet<RelationKey> = BTreeSet::new();
+
+        let store = EventStore::with_config(self.config.clone());
+        let events: Vec<Event> = match (base.log_position, log_position) {
+            (Some(from), Some(to)) => store
+                .load_positioned_events()?
+                .0
+                .into_iter()
+                .filter(|(position, _)| (from..to).contains(position))
+                .map(|(_, event)| event)
+                .collect(),
+            _ => store
+                .load_events_after(base_event_id)?
+                .into_iter()
+                .filter(|e| e.event_id <= last_event_id)
+                .collect(),
+        };
+        for event in &events {
+            match event.event_type {
+                EventType::EntityCreated => {
+                    touched_entities.insert(event.parse_data::<EntityCreatedData>()?.name);
+                }
+                EventType::EntityUpdated => {
+                    touched_entities.insert(event.parse_data::<EntityUpdatedData>()?.name);
+                }
+                EventType::EntityDeleted => {
+                    let name = event.parse_data::<EntityDeletedData>()?.name;
+                    touched_entities.insert(name.clone());
+                    deleted_entities.insert(name);
+                }
+                EventType::ObservationAdded => {
+                    touched_entities.insert(event.parse_data::<ObservationAddedData>()?.entity);
+                }
+                EventType::ObservationRemoved => {
+                    touched_entities.insert(event.parse_data::<ObservationRemovedData>()?.entity);
+                }
+                EventType::ObservationEdited => {
+                    touched_entities.insert(event.parse_data::<ObservationEditedData>()?.entity);
+                }
+                EventType::RelationCreated => {
+                    let data: RelationCreatedData = event.parse_data()?;
+                    touched_relations.insert((data.from, data.to, data.relation_type));
+                }
+                EventType::RelationDeleted => {
+                    let data: RelationDeletedData = event.parse_data()?;
+                    touched_relations.insert((data.from, data.to, data.relation_type));
+                }
+                EventType::RelationUpdated => {
+                    let data: RelationUpdatedData = event.parse_data()?;
+                    touched_relations.insert((data.from, data.to, data.relation_type));
+                }
+            }
+        }
+
+        let changed_entities: Vec<&Entity> = entities
+            .iter()
+            .filter(|e| touched_entities.contains(&e.name))
+            .collect();
+        let changed_relations: Vec<&Relation> = relations
+            .iter()
+            .filter(|r| touched_relations.contains(&relation_key(r)))
+            .collect();
+
+        let present: BTreeSet<&str> = changed_entities.iter().map(|e| e.name.as_str()).collect();
+        let removed_entities: Vec<&String> = touched_entities
+            .iter()
+            .filter(|name| deleted_entities.contains(*name) || !present.contains(name.as_str()))
+            .collect();
+        let present: BTreeSet<RelationKey> =
+            changed_relations.iter().map(|r| relation_key(r)).collect();
+        let removed_relations: Vec<&RelationKey> = touched_relations.difference(&present).collect();
+
+        fs::create_dir_all(self.config.snapshots_dir())?;
+        let meta = SnapshotMeta::delta(
+            base_event_id,
+            last_event_id,
+            changed_entities.len(),
+            changed_relations.len(),
+        )
+        .with_log_position(log_position);
+
+        let meta_clone = meta.clone();
+        atomic_write_with(self.config.delta_snapshot_path(last_event_id), |file| {
+            let mut line = |value: serde_json::Result<String>| -> std::io::Result<()> {
+                let json =
+                    value.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+                writeln!(file, "{}", json)
+            };
+            line(serde_json::to_string(&meta_clone))?;
+            for name in &removed_entities {
+                line(serde_json::to_string(
+                    &json!({"type": "entity_removed", "name": name}),
+                ))?;
+            }
+            for (from, to, relation_type) in &removed_relations {
+                line(serde_json::to_string(&json!({
+                    "type": "relation_removed",
+                    "from": from,
+                    "to": to,
+                    "relationType": relation_type
+                })))?;
+            }
+            for entity in &changed_entities {
+                line(serde_json::to_string(entity))?;
+            }
+            for relation in &changed_relations {
+                line(serde_json::to_string(relation))?;
+            }
+            Ok(())
+        })
+        .map_err(|e| EventStoreError::Io(std::io::Error::other(e)))?;
+
+        println!(
+            "Created delta snapshot: {} entities, {} relations changed, {} removed (event_id: {} -> {})",
+            changed_entities.len(),
+            changed_relations.len(),
+            removed_entities.len() + removed_relations.len(),
+            base_event_id,
+            last_event_id
+        );
+
+        Ok(meta)
+    }
+
+    /// Delta snapshot files, oldest first
+    pub fn delta_paths(&self) -> EventStoreResult<Vec<PathBuf>> {
+        let dir = self.config.snapshots_dir();
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut paths: Vec<PathBuf> = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with("delta-") && n.ends_with(".jsonl"))
+            })
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Delete all delta snapshots
+    fn remove_deltas(&self) -> EventStoreResult<()> {
+        for path in self.delta_paths()? {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Metadata of the deltas that continue the chain from `last_event_id`
+    fn delta_chain(
+        &self,
+        mut last_event_id: u64,
+    ) -> EventStoreResult<Vec<(SnapshotMeta, PathBuf)>> {
+        let mut chain = Vec::new();
+        for path in self.delta_paths()? {
+            let Some(meta) = read_meta(&path)? else {
+                continue;
+            };
+            if meta.last_event_id <= last_event_id {
+                // Left over from before the current base
+                continue;
+            }
+            if meta.base_event_id != Some(last_event_id) {
+                eprintln!(
+                    "Warning: Delta snapshot {} does not follow event {}; replaying events instead",
+                    path.display(),
+                    last_event_id
+                );
+                break;
+            }
+            last_event_id = meta.last_event_id;
+            chain.push((meta, path));
+        }
+        Ok(chain)
+    }
+
     /// Load snapshot metadata only (fast, for checking state)
+    ///
+    /// With deltas this is the metadata of the last delta in the chain.
     pub fn load_meta(&self) -> EventStoreResult<Option<SnapshotMeta>> {
         let path = self.config.latest_snapshot_path();
 
@@ -178,29 +484,46 @@ impl SnapshotManager {
             return Ok(None);
         }
 
-        let file = File::open(&path)?;
-        let reader = BufReader::new(file);
-
-        if let Some(first_line) = reader.lines().next() {
-            let line = first_line?;
-            let meta = SnapshotMeta::from_json_line(&line)?;
-            Ok(Some(meta))
-        } else {
-            Err(EventStoreError::SnapshotCorrupted(
-                "Empty snapshot file".to_string(),
-            ))
-        }
+        let base = read_meta(&path)?
+            .ok_or_else(|| EventStoreError::SnapshotCorrupted("Empty snapshot file".to_string()))?;
+        let head = self
+            .delta_chain(base.last_event_id)?
+            .pop()
+            .map(|(meta, _)| meta);
+        Ok(Some(head.unwrap_or(base)))
     }
 
     /// Load full snapshot (metadata + entities + relations)
-    pub fn load_full(&self) -> EventStoreResult<Option<(SnapshotMeta, Vec<Entity>, Vec<Relation>)>> {
+    ///
+    /// Deltas on top of the full snapshot are applied in order. The returned
+    /// metadata is that of the last one applied, with the counts of the
+    /// reconstructed state.
+    pub fn load_full(&self) -> EventStoreResult<Option<SnapshotData>> {
         let path = self.config.latest_snapshot_path();
 
         if !path.exists() {
             return Ok(None);
         }
 
-        let file = File::open(&path)?;
+        let (mut meta, mut entities, mut relations) = self.read_full(&path)?;
+        let chain = self.delta_chain(meta.last_event_id)?;
+        if chain.is_empty() {
+            return Ok(Some((meta, entities, relations)));
+        }
+
+        for (_, delta_path) in chain {
+            let delta = read_delta(&delta_path)?;
+            apply_delta(&mut entities, &mut relations, &delta);
+            meta = delta.meta;
+        }
+        meta.entity_count = entities.len();
+        meta.relation_count = relations.len();
+        Ok(Some((meta, entities, relations)))
+    }
+
+    /// Read a full snapshot file
+    fn read_full(&self, path: &Path) -> EventStoreResult<SnapshotData> {
+        let file = File::open(path)?;
         let reader = BufReader::new(file);
         let mut lines = reader.lines();
 
@@ -252,11 +575,11 @@ impl SnapshotManager {
             );
         }
 
-        Ok(Some((meta, entities, relations)))
+        Ok((meta, entities, relations))
     }
 
     /// Try to recover from backup snapshot if primary is corrupted
-    pub fn recover_from_backup(&self) -> EventStoreResult<Option<(SnapshotMeta, Vec<Entity>, Vec<Relation>)>> {
+    pub fn recover_from_backup(&self) -> EventStoreResult<Option<SnapshotData>> {
         let previous_path = self.config.previous_snapshot_path();
 
         if !previous_path.exists() {
@@ -315,11 +638,94 @@ impl SnapshotManager {
         if previous.exists() {
             fs::remove_file(&previous)?;
         }
+        self.remove_deltas()?;
 
         Ok(())
     }
 }
 
+/// Read the metadata line of a snapshot or delta file
+fn read_meta(path: &Path) -> EventStoreResult<Option<SnapshotMeta>> {
+    let reader = BufReader::new(File::open(path)?);
+    match reader.lines().next() {
+        Some(line) => Ok(Some(SnapshotMeta::from_json_line(&line?)?)),
+        None => Ok(None),
+    }
+}
+
+/// Read a delta snapshot file
+fn read_delta(path: &Path) -> EventStoreResult<Delta> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut lines = reader.lines();
+
+    let meta_line = lines
+        .next()
+        .ok_or_else(|| EventStoreError::SnapshotCorrupted("Empty delta snapshot".to_string()))??;
+    let mut delta = Delta {
+        meta: SnapshotMeta::from_json_line(&meta_line)?,
+        entities: Vec::new(),
+        relations: Vec::new(),
+        removed_entities: Vec::new(),
+        removed_relations: Vec::new(),
+    };
+
+    for (line_num, line_result) in lines.enumerate() {
+        let line = line_result?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let value: serde_json::Value = serde_json::from_str(&line).map_err(|e| {
+            EventStoreError::SnapshotCorrupted(format!("Line {}: {}", line_num + 2, e))
+        })?;
+        let text = |key: &str| value.get(key).and_then(|v| v.as_str()).map(String::from);
+
+        match value.get("type").and_then(|v| v.as_str()) {
+            Some("entity_removed") => delta.removed_entities.extend(text("name")),
+            Some("relation_removed") => {
+                if let (Some(from), Some(to), Some(relation_type)) =
+                    (text("from"), text("to"), text("relationType"))
+                {
+                    delta.removed_relations.push((from, to, relation_type));
+                }
+            }
+            _ if value.get("entityType").is_some() && value.get("name").is_some() => {
+                delta.entities.push(serde_json::from_value(value)?);
+            }
+            _ if value.get("relationType").is_some() => {
+                delta.relations.push(serde_json::from_value(value)?);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(delta)
+}
+
+/// Apply a delta: removals first (dropping a removed entity's relations, as
+/// replaying its deletion would), then the changed entities and relations
+fn apply_delta(entities: &mut Vec<Entity>, relations: &mut Vec<Relation>, delta: &Delta) {
+    for name in &delta.removed_entities {
+        entities.retain(|e| &e.name != name);
+        relations.retain(|r| &r.from != name && &r.to != name);
+    }
+    relations.retain(|r| !delta.removed_relations.contains(&relation_key(r)));
+
+    for entity in &delta.entities {
+        match entities.iter_mut().find(|e| e.name == entity.name) {
+            Some(existing) => *existing = entity.clone(),
+            None => entities.push(entity.clone()),
+        }
+    }
+    for relation in &delta.relations {
+        let key = relation_key(relation);
+        match relations.iter_mut().find(|r| relation_key(r) == key) {
+            Some(existing) => *existing = relation.clone(),
+            None => relations.push(relation.clone()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -14,6 +14,7 @@ use std::time::Instant;
 use crate::types::EventType;
 
 use super::rotation::LogRotation;
+use super::snapshot::SnapshotManager;
 use super::store::{EventStoreConfig, EventStoreResult};
 
 /// Statistics about the Event Store
@@ -85,18 +86,19 @@ impl StatsCollector {
     pub fn collect(&self) -> EventStoreResult<EventStoreStats> {
         let mut stats = EventStoreStats::default();
 
-        // Active log stats
-        let events_path = self.config.events_path();
-        if events_path.exists() {
-            let (count, size, by_type, last_id) = self.analyze_event_file(&events_path)?;
-            stats.active_event_count = count;
-            stats.active_log_size = size;
-            stats.events_by_type = by_type;
-            stats.last_event_id = last_id;
+        // Active log stats (closed segments included)
+        let rotation = LogRotation::new(self.config.clone());
+        for path in rotation.log_files()? {
+            let (count, size, by_type, last_id) = self.analyze_event_file(&path)?;
+            stats.active_event_count += count;
+            stats.active_log_size += size;
+            for (event_type, n) in by_type {
+                *stats.events_by_type.entry(event_type).or_default() += n;
+            }
+            stats.last_event_id = stats.last_event_id.max(last_id);
         }
 
         // Archive stats
-        let rotation = LogRotation::new(self.config.clone());
         let archives = rotation.list_archives()?;
         stats.archive_file_count = archives.len();
         stats.archived_event_count = archives.iter().map(|a| a.event_count).sum();
@@ -106,16 +108,20 @@ impl StatsCollector {
         let snapshot_path = self.config.latest_snapshot_path();
         if snapshot_path.exists() {
             stats.snapshot_size = fs::metadata(&snapshot_path)?.len();
-
-            // Parse snapshot to get last_snapshot_event_id
-            if let Some(meta) = self.parse_snapshot_meta(&snapshot_path)? {
-                stats.last_snapshot_event_id = meta;
-            }
         }
 
-        // Calculate events since snapshot
-        if stats.last_event_id > stats.last_snapshot_event_id {
-            stats.events_since_snapshot = (stats.last_event_id - stats.last_snapshot_event_id) as usize;
+        // Calculate events since snapshot, by log position when it has one
+        if let Some(meta) = SnapshotManager::new(self.config.clone()).load_meta()? {
+            stats.last_snapshot_event_id = meta.last_event_id;
+            stats.events_since_snapshot = match meta.log_position {
+                Some(log_position) => {
+                    let end = rotation.archived_lines()? + stats.active_event_count as u64;
+                    end.saturating_sub(log_position) as usize
+                }
+                None => stats.last_event_id.saturating_sub(meta.last_event_id) as usize,
+            };
+        } else {
+            stats.events_since_snapshot = stats.active_event_count;
         }
 
         Ok(stats)
@@ -163,22 +169,6 @@ impl StatsCollector {
         Ok((count, size, by_type, last_id))
     }
 
-    /// Parse snapshot metadata to get last event ID
-    fn parse_snapshot_meta(&self, path: &Path) -> EventStoreResult<Option<u64>> {
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-
-        if let Some(Ok(line)) = reader.lines().next() {
-            if let Ok(value) = serde_json::from_str::<serde_json::Value>(&line) {
-                if let Some(id) = value.get("lastEventId").and_then(|v| v.as_u64()) {
-                    return Ok(Some(id));
-                }
-            }
-        }
-
-        Ok(None)
-    }
-
     /// Benchmark replay performance
     pub fn benchmark_replay(&self, iterations: usize) -> EventStoreResult<ReplayBenchmark> {
         let events_path = self.config.events_path();
@@ -4,14 +4,28 @@
 //! functionality for replaying events to rebuild state.
 
 use std::fs::{File, OpenOptions};
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 
+use serde::{Deserialize, Serialize};
+
 use crate::types::{
     Entity, EntityCreatedData, EntityDeletedData, EntityUpdatedData, Event, EventType,
-    ObservationAddedData, ObservationRemovedData, Relation, RelationCreatedData,
-    RelationDeletedData, SnapshotMeta,
+    ObservationAddedData, ObservationEditedData, ObservationRemovedData, Relation,
+    RelationCreatedData, RelationDeletedData, RelationUpdatedData, SnapshotMeta,
 };
+use crate::utils::current_timestamp;
+
+use super::progress::{replay_status, ReplayProgress, DEFAULT_REPLAY_PROGRESS_EVERY};
+use super::rotation::LogRotation;
+use super::snapshot::SnapshotManager;
+
+/// First event ID reserved for external producers
+///
+/// By default the server assigns IDs below this value; sidecars appending to
+/// the same log should each pick a disjoint range at or above it.
+pub const EXTERNAL_ID_BASE: u64 = 1 << 48;
 
 /// Configuration for the EventStore
 #[derive(Debug, Clone)]
@@ -20,10 +34,37 @@ pub struct EventStoreConfig {
     pub data_dir: PathBuf,
     /// Threshold for creating snapshots (number of events)
     pub snapshot_threshold: usize,
+    /// Also snapshot once this many seconds have passed since the last one
+    /// (if any events arrived in between)
+    pub snapshot_interval_secs: Option<u64>,
     /// Whether to archive old event logs
     pub archive_old_events: bool,
     /// Whether to compress archived events
     pub compress_archive: bool,
+    /// Write up to this many delta snapshots on top of a full one before
+    /// writing the next full snapshot (0 = full snapshots only)
+    pub max_delta_chain: usize,
+    /// Event IDs this process may assign
+    ///
+    /// When several processes append to one log, each must own a disjoint
+    /// range so their IDs never collide. Events outside the range were
+    /// written by someone else and are picked up by `tail`.
+    pub id_range: Range<u64>,
+    /// Reserve IDs from `id_range` in blocks of this size, shared with other
+    /// writers through the reservation file (see `EventStore::reserve_id_block`)
+    ///
+    /// `None` assigns IDs straight from `id_range`, which must then be
+    /// disjoint from every other writer's.
+    pub id_block_size: Option<u64>,
+    /// Roll the live log into a new numbered segment once it reaches this
+    /// many bytes (`None` = a single `events.jsonl`)
+    ///
+    /// Closed segments are read before the live log, in number order, by
+    /// `load_events` and replay; see `LogRotation::roll_segment`.
+    pub segment_size: Option<u64>,
+    /// Report replay progress every this many events (0 = never; see
+    /// the `progress` module)
+    pub replay_progress_every: usize,
 }
 
 impl Default for EventStoreConfig {
@@ -31,8 +72,14 @@ impl Default for EventStoreConfig {
         Self {
             data_dir: PathBuf::from("data"),
             snapshot_threshold: 1000,
+            snapshot_interval_secs: None,
             archive_old_events: true,
             compress_archive: false, // TODO: implement compression
+            max_delta_chain: 0,
+            id_range: 1..EXTERNAL_ID_BASE,
+            id_block_size: None,
+            segment_size: None,
+            replay_progress_every: DEFAULT_REPLAY_PROGRESS_EVERY,
         }
     }
 }
@@ -76,15 +123,61 @@ impl EventStoreConfig {
         self.snapshots_dir().join("previous.jsonl")
     }
 
+    /// Get path to the delta snapshot ending at `last_event_id`
+    ///
+    /// Ids are zero-padded so deltas sort by name in chain order.
+    pub fn delta_snapshot_path(&self, last_event_id: u64) -> PathBuf {
+        self.snapshots_dir()
+            .join(format!("delta-{:020}.jsonl", last_event_id))
+    }
+
+    /// Get path to the directory of closed event log segments
+    pub fn segments_dir(&self) -> PathBuf {
+        self.data_dir.join("segments")
+    }
+
+    /// Get path to closed segment number `number`
+    ///
+    /// Numbers are zero-padded so segments sort by name in log order.
+    pub fn segment_path(&self, number: u64) -> PathBuf {
+        self.segments_dir()
+            .join(format!("events-{:06}.jsonl", number))
+    }
+
+    /// Get path to the ID reservation file shared by block-reserving writers
+    pub fn id_reservation_path(&self) -> PathBuf {
+        self.data_dir.join("id_reservations.json")
+    }
+
+    /// Get path to the file counting the log lines moved to archives
+    pub fn log_state_path(&self) -> PathBuf {
+        self.data_dir.join("log_state.json")
+    }
+
+    /// Get path to the graph metadata file
+    pub fn graph_metadata_path(&self) -> PathBuf {
+        self.data_dir.join("graph_metadata.json")
+    }
+
     /// Get path to archive directory
     pub fn archive_dir(&self) -> PathBuf {
         self.data_dir.join("archive")
     }
+
+    /// Parse an ID range written as `START..END` (end exclusive)
+    pub fn parse_id_range(value: &str) -> Option<Range<u64>> {
+        let (start, end) = value.split_once("..")?;
+        let range = start.trim().parse().ok()?..end.trim().parse().ok()?;
+        (range.start > 0 && !range.is_empty()).then_some(range)
+    }
 }
 
 /// Result type for EventStore operations
 pub type EventStoreResult<T> = Result<T, EventStoreError>;
 
+/// Snapshot contents: metadata plus the materialized entities and relations
+pub type SnapshotData = (SnapshotMeta, Vec<Entity>, Vec<Relation>);
+
 /// Errors that can occur in EventStore operations
 #[derive(Debug)]
 pub enum EventStoreError {
@@ -124,10 +217,47 @@ pub struct EventStore {
     config: EventStoreConfig,
     /// Next event ID to assign
     next_event_id: u64,
-    /// Number of events since last snapshot
-    events_since_snapshot: usize,
+    /// Log lines applied so far, archived ones included (see
+    /// `SnapshotMeta::log_position`)
+    log_position: u64,
+    /// `log_position` of the most recent snapshot
+    snapshot_position: u64,
     /// Last event ID included in most recent snapshot
     last_snapshot_event_id: u64,
+    /// Position in the log up to which `tail` has consumed events
+    tail_cursor: TailCursor,
+    /// Unix timestamp (seconds) of the most recent snapshot, or of startup
+    last_snapshot_time: u64,
+    /// ID blocks reserved by this store, the current one last
+    reserved_blocks: Vec<Range<u64>>,
+}
+
+/// Position in the log up to which `EventStore::tail` has consumed lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TailCursor {
+    /// Index of the file being read: a closed segment, or the live log once
+    /// every segment has been read
+    segment: usize,
+    /// Byte offset in that file
+    offset: u64,
+    /// Lines of other writers consumed so far, for `log_position`
+    lines: u64,
+}
+
+/// An event read by `EventStore::read_tail`, with the cursor just past it
+#[derive(Debug, Clone)]
+pub struct TailedEvent {
+    pub event: Event,
+    pub cursor: TailCursor,
+}
+
+/// Contents of the ID reservation file
+#[derive(Debug, Serialize, Deserialize)]
+struct IdReservation {
+    /// First ID not yet reserved by any writer
+    high_water_mark: u64,
+    /// Unix timestamp (seconds) of the last reservation
+    updated_at: u64,
 }
 
 impl EventStore {
@@ -141,8 +271,12 @@ impl EventStore {
         Self {
             config,
             next_event_id: 1,
-            events_since_snapshot: 0,
+            log_position: 0,
+            snapshot_position: 0,
             last_snapshot_event_id: 0,
+            tail_cursor: TailCursor::default(),
+            last_snapshot_time: current_timestamp(),
+            reserved_blocks: Vec::new(),
         }
     }
 
@@ -158,18 +292,45 @@ impl EventStore {
 
     /// Get events since last snapshot
     pub fn events_since_snapshot(&self) -> usize {
-        self.events_since_snapshot
+        self.log_position.saturating_sub(self.snapshot_position) as usize
+    }
+
+    /// Number of log lines applied so far, archived ones included
+    ///
+    /// Lines other writers appended count once `consume_tail` covers them.
+    pub fn log_position(&self) -> u64 {
+        self.log_position
+    }
+
+    /// Get the time of the most recent snapshot (or startup, if none yet)
+    pub fn last_snapshot_time(&self) -> u64 {
+        self.last_snapshot_time
     }
 
     /// Check if snapshot should be created
+    ///
+    /// Fires on whichever comes first: `snapshot_threshold` events, or
+    /// `snapshot_interval_secs` elapsed with at least one new event.
     pub fn should_snapshot(&self) -> bool {
-        self.events_since_snapshot >= self.config.snapshot_threshold
+        let events_since_snapshot = self.events_since_snapshot();
+        if events_since_snapshot >= self.config.snapshot_threshold {
+            return true;
+        }
+
+        match self.config.snapshot_interval_secs {
+            Some(interval) => {
+                events_since_snapshot > 0
+                    && current_timestamp().saturating_sub(self.last_snapshot_time) >= interval
+            }
+            None => false,
+        }
     }
 
     /// Append an event to the event log
     ///
     /// This is the core write operation. Events are appended atomically
-    /// with fsync to ensure durability.
+    /// with fsync to ensure durability. With `segment_size` set, the live log
+    /// is rolled into a segment once the append makes it that large.
     pub fn append_event(&mut self, event: Event) -> EventStoreResult<u64> {
         let events_path = self.config.events_path();
 
@@ -191,12 +352,19 @@ impl EventStore {
         // Sync to disk for durability
         file.sync_all()?;
 
+        if let Some(segment_size) = self.config.segment_size {
+            if file.metadata()?.len() >= segment_size {
+                drop(file);
+                LogRotation::new(self.config.clone()).roll_segment()?;
+            }
+        }
+
         // Update internal state
         let event_id = event.event_id;
-        if event_id >= self.next_event_id {
+        if event_id >= self.next_event_id && self.owns(event_id) {
             self.next_event_id = event_id + 1;
         }
-        self.events_since_snapshot += 1;
+        self.log_position += 1;
 
         Ok(event_id)
     }
@@ -208,7 +376,23 @@ impl EventStore {
         user: String,
         data: serde_json::Value,
     ) -> EventStoreResult<Event> {
+        if let Some(size) = self.config.id_block_size {
+            let in_block = self
+                .reserved_blocks
+                .last()
+                .is_some_and(|block| block.contains(&self.next_event_id));
+            if !in_block {
+                self.reserve_id_block(size)?;
+            }
+        }
+
         let event_id = self.next_event_id;
+        if !self.owns(event_id) {
+            return Err(EventStoreError::InvalidEvent(format!(
+                "Event ID range {:?} exhausted",
+                self.config.id_range
+            )));
+        }
         self.next_event_id += 1;
 
         let event = Event::new(event_type, event_id, user, data);
@@ -217,38 +401,278 @@ impl EventStore {
         Ok(event)
     }
 
-    /// Load all events from the event log
-    pub fn load_events(&self) -> EventStoreResult<Vec<Event>> {
-        let events_path = self.config.events_path();
+    /// Whether `event_id` falls in the range this process assigns from
+    ///
+    /// With `id_block_size` set, only the blocks this store reserved count.
+    pub fn owns(&self, event_id: u64) -> bool {
+        if self.config.id_block_size.is_some() {
+            self.reserved_blocks.iter().any(|b| b.contains(&event_id))
+        } else {
+            self.config.id_range.contains(&event_id)
+        }
+    }
+
+    /// Reserve the next `size` unclaimed IDs of `id_range` for this store
+    ///
+    /// Returns the block as `(start, end)`, end exclusive, and makes it the
+    /// block new events draw from. Writers sharing a log coordinate through
+    /// `id_reservations.json` in the data directory, a single JSON object:
+    ///
+    /// ```text
+    /// {"high_water_mark":2001,"updated_at":1700000000}
+    /// ```
+    ///
+    /// `high_water_mark` is the first ID no writer has reserved. The file is
+    /// held under an exclusive lock while it is read and rewritten, and
+    /// synced before the block is handed out, so no two writers ever receive
+    /// overlapping blocks.
+    ///
+    /// After a crash, the unused rest of a block is simply never assigned:
+    /// IDs stay unique, with a gap. If the file is missing or unreadable, the
+    /// mark is recovered as one past the highest `id_range` ID in the active
+    /// log or the snapshot. That is only safe while no other writer holds a
+    /// block, so the file should not be deleted under running writers.
+    pub fn reserve_id_block(&mut self, size: u64) -> EventStoreResult<(u64, u64)> {
+        if size == 0 {
+            return Err(EventStoreError::InvalidEvent(
+                "ID block size must be positive".to_string(),
+            ));
+        }
+        std::fs::create_dir_all(&self.config.data_dir)?;
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(self.config.id_reservation_path())?;
+        file.lock()?;
+
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        let high_water_mark = match serde_json::from_str::<IdReservation>(&contents) {
+            Ok(reservation) => reservation.high_water_mark,
+            Err(_) => self.recover_high_water_mark()?,
+        };
+
+        let start = high_water_mark.max(self.config.id_range.start);
+        let end = start
+            .checked_add(size)
+            .filter(|end| *end <= self.config.id_range.end)
+            .ok_or_else(|| {
+                EventStoreError::InvalidEvent(format!(
+                    "Event ID range {:?} exhausted",
+                    self.config.id_range
+                ))
+            })?;
+
+        let reservation = IdReservation {
+            high_water_mark: end,
+            updated_at: current_timestamp(),
+        };
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        writeln!(file, "{}", serde_json::to_string(&reservation)?)?;
+        file.sync_all()?;
+        file.unlock()?;
+
+        self.reserved_blocks.push(start..end);
+        self.next_event_id = start;
+        Ok((start, end))
+    }
+
+    /// One past the highest `id_range` ID in the active log and snapshot
+    fn recover_high_water_mark(&self) -> EventStoreResult<u64> {
+        let in_range = |id: &u64| self.config.id_range.contains(id);
+        let logged = self
+            .load_events()?
+            .iter()
+            .map(|e| e.event_id)
+            .filter(in_range)
+            .max();
+        let snapshot = self
+            .load_snapshot_meta()
+            .ok()
+            .flatten()
+            .map(|meta| meta.last_event_id)
+            .filter(in_range);
+        Ok(logged
+            .max(snapshot)
+            .map_or(self.config.id_range.start, |id| id + 1))
+    }
+
+    /// Read events appended to the log by other processes since the last call
+    ///
+    /// Same as `read_tail`, consuming everything read at once.
+    pub fn tail(&mut self) -> EventStoreResult<Vec<Event>> {
+        let (tailed, end) = self.read_tail()?;
+        self.consume_tail(end);
+        Ok(tailed.into_iter().map(|tailed| tailed.event).collect())
+    }
 
+    /// Read events appended to the log by other processes, without consuming them
+    ///
+    /// Only complete lines are read; a line still being written is picked up
+    /// next time. Events in this store's own ID range are skipped, since this
+    /// process wrote (and applied) them itself. If the live log was rolled
+    /// into a segment meanwhile, the rest of that segment is read first. If
+    /// the log shrank otherwise, it was rotated and is re-read from the start;
+    /// re-applying the surviving events is harmless because creates and
+    /// observation adds are idempotent.
+    ///
+    /// Each event comes with the cursor just past it, to hand to
+    /// `consume_tail` once the event is applied: an event that fails to
+    /// apply is read again next time. The returned cursor is the end of
+    /// everything read.
+    pub fn read_tail(&self) -> EventStoreResult<(Vec<TailedEvent>, TailCursor)> {
+        let mut tailed = Vec::new();
+        let mut cursor = self.tail_cursor;
+        let segments = LogRotation::new(self.config.clone()).list_segments()?;
+        cursor.segment = cursor.segment.min(segments.len());
+        while cursor.segment < segments.len() {
+            self.tail_file(&segments[cursor.segment], &mut cursor, &mut tailed)?;
+            cursor.segment += 1;
+            cursor.offset = 0;
+        }
+
+        let events_path = self.config.events_path();
         if !events_path.exists() {
-            return Ok(Vec::new());
+            cursor.offset = 0;
+        } else {
+            if std::fs::metadata(&events_path)?.len() < cursor.offset {
+                cursor.offset = 0;
+            }
+            self.tail_file(&events_path, &mut cursor, &mut tailed)?;
         }
 
-        let file = File::open(&events_path)?;
-        let reader = BufReader::new(file);
-        let mut events = Vec::new();
+        Ok((tailed, cursor))
+    }
 
-        for (line_num, line_result) in reader.lines().enumerate() {
-            let line = line_result?;
-            if line.trim().is_empty() {
+    /// Mark the log as consumed up to `cursor`, from `read_tail`
+    pub fn consume_tail(&mut self, cursor: TailCursor) {
+        self.log_position += cursor.lines.saturating_sub(self.tail_cursor.lines);
+        self.tail_cursor = cursor;
+    }
+
+    /// Consume what other writers appended, if none of it is an event
+    ///
+    /// Returns whether `log_position` now counts every complete line of the
+    /// log. If other writers' events are waiting, nothing is consumed: they
+    /// have to be applied first (see `knowledge_base::tail`).
+    pub fn catch_up_tail(&mut self) -> EventStoreResult<bool> {
+        let (tailed, end) = self.read_tail()?;
+        if !tailed.is_empty() {
+            return Ok(false);
+        }
+        self.consume_tail(end);
+        Ok(true)
+    }
+
+    /// Collect the other writers' events in complete lines of `path` after
+    /// `cursor`, advancing it past each line
+    fn tail_file(
+        &self,
+        path: &Path,
+        cursor: &mut TailCursor,
+        tailed: &mut Vec<TailedEvent>,
+    ) -> EventStoreResult<()> {
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(cursor.offset))?;
+
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+        let complete = match buf.iter().rposition(|b| *b == b'\n') {
+            Some(pos) => &buf[..=pos],
+            None => return Ok(()),
+        };
+
+        for line in complete.split_inclusive(|b| *b == b'\n') {
+            cursor.offset += line.len() as u64;
+            let text = String::from_utf8_lossy(line);
+            if text.trim().is_empty() {
                 continue;
             }
-
-            match Event::from_json_line(&line) {
-                Ok(event) => events.push(event),
+            match Event::from_json_line(text.trim()) {
+                Ok(event) if self.owns(event.event_id) => {}
+                Ok(event) => {
+                    cursor.lines += 1;
+                    tailed.push(TailedEvent {
+                        event,
+                        cursor: *cursor,
+                    });
+                }
                 Err(e) => {
-                    eprintln!(
-                        "Warning: Failed to parse event at line {}: {}",
+                    cursor.lines += 1;
+                    eprintln!("Warning: Failed to parse tailed event: {}", e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Load all events from the event log, closed segments first
+    pub fn load_events(&self) -> EventStoreResult<Vec<Event>> {
+        let mut events = Vec::new();
+
+        for path in LogRotation::new(self.config.clone()).log_files()? {
+            let reader = BufReader::new(File::open(&path)?);
+            for (line_num, line_result) in reader.lines().enumerate() {
+                let line = line_result?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                match Event::from_json_line(&line) {
+                    Ok(event) => events.push(event),
+                    Err(e) => {
+                        eprintln!(
+                            "Warning: Failed to parse event at {}:{}: {}",
+                            path.display(),
+                            line_num + 1,
+                            e
+                        );
+                        // Continue loading other events
+                    }
+                }
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Load all events with their log positions, and the position just past
+    /// the end of the log
+    ///
+    /// Positions count non-empty lines, the archived ones first (see
+    /// `SnapshotMeta::log_position`). Lines that fail to parse are skipped
+    /// but still take up a position.
+    pub fn load_positioned_events(&self) -> EventStoreResult<(Vec<(u64, Event)>, u64)> {
+        let rotation = LogRotation::new(self.config.clone());
+        let mut position = rotation.archived_lines()?;
+        let mut events = Vec::new();
+
+        for path in rotation.log_files()? {
+            let reader = BufReader::new(File::open(&path)?);
+            for (line_num, line_result) in reader.lines().enumerate() {
+                let line = line_result?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                match Event::from_json_line(&line) {
+                    Ok(event) => events.push((position, event)),
+                    Err(e) => eprintln!(
+                        "Warning: Failed to parse event at {}:{}: {}",
+                        path.display(),
                         line_num + 1,
                         e
-                    );
-                    // Continue loading other events
+                    ),
                 }
+                position += 1;
             }
         }
 
-        Ok(events)
+        Ok((events, position))
     }
 
     /// Load events after a specific event ID
@@ -262,72 +686,14 @@ impl EventStore {
             .collect())
     }
 
-    /// Load snapshot metadata from a snapshot file
+    /// Load metadata of the most recent snapshot, the last delta if any
     pub fn load_snapshot_meta(&self) -> EventStoreResult<Option<SnapshotMeta>> {
-        let snapshot_path = self.config.latest_snapshot_path();
-
-        if !snapshot_path.exists() {
-            return Ok(None);
-        }
-
-        let file = File::open(&snapshot_path)?;
-        let reader = BufReader::new(file);
-
-        // First line should be metadata
-        if let Some(first_line) = reader.lines().next() {
-            let line = first_line?;
-            let meta = SnapshotMeta::from_json_line(&line)?;
-            Ok(Some(meta))
-        } else {
-            Err(EventStoreError::SnapshotCorrupted(
-                "Empty snapshot file".to_string(),
-            ))
-        }
+        SnapshotManager::new(self.config.clone()).load_meta()
     }
 
-    /// Load entities and relations from snapshot
-    pub fn load_snapshot(&self) -> EventStoreResult<Option<(SnapshotMeta, Vec<Entity>, Vec<Relation>)>> {
-        let snapshot_path = self.config.latest_snapshot_path();
-
-        if !snapshot_path.exists() {
-            return Ok(None);
-        }
-
-        let file = File::open(&snapshot_path)?;
-        let reader = BufReader::new(file);
-        let mut lines = reader.lines();
-
-        // First line is metadata
-        let meta_line = lines
-            .next()
-            .ok_or_else(|| EventStoreError::SnapshotCorrupted("Empty snapshot".to_string()))??;
-        let meta = SnapshotMeta::from_json_line(&meta_line)?;
-
-        let mut entities = Vec::new();
-        let mut relations = Vec::new();
-
-        // Parse remaining lines
-        for line_result in lines {
-            let line = line_result?;
-            if line.trim().is_empty() {
-                continue;
-            }
-
-            // Try to parse as entity or relation based on structure
-            let value: serde_json::Value = serde_json::from_str(&line)?;
-
-            if value.get("entityType").is_some() && value.get("name").is_some() {
-                // It's an entity
-                let entity: Entity = serde_json::from_value(value)?;
-                entities.push(entity);
-            } else if value.get("relationType").is_some() {
-                // It's a relation
-                let relation: Relation = serde_json::from_value(value)?;
-                relations.push(relation);
-            }
-        }
-
-        Ok(Some((meta, entities, relations)))
+    /// Load entities and relations from the snapshot and its deltas
+    pub fn load_snapshot(&self) -> EventStoreResult<Option<SnapshotData>> {
+        SnapshotManager::new(self.config.clone()).load_full()
     }
 
     /// Apply a single event to the state
@@ -357,6 +723,9 @@ impl EventStore {
                     updated_by: event.user.clone(),
                     created_at: event.timestamp as u64,
                     updated_at: event.timestamp as u64,
+                    id: data.id,
+                    protected: data.protected,
+                    acl: data.acl,
                 };
                 entities.push(entity);
             }
@@ -368,6 +737,9 @@ impl EventStore {
                     if let Some(new_type) = data.entity_type {
                         entity.entity_type = new_type;
                     }
+                    if let Some(protected) = data.protected {
+                        entity.protected = protected;
+                    }
                     entity.updated_by = event.user.clone();
                     entity.updated_at = event.timestamp as u64;
                 }
@@ -405,6 +777,23 @@ impl EventStore {
                 }
             }
 
+            EventType::ObservationEdited => {
+                let data: ObservationEditedData = event.parse_data()?;
+
+                if let Some(entity) = entities.iter_mut().find(|e| e.name == data.entity) {
+                    let observations = &mut entity.observations;
+                    if observations.contains(&data.after) {
+                        observations.retain(|o| o != &data.before);
+                    } else if let Some(slot) = observations.iter_mut().find(|o| **o == data.before) {
+                        *slot = data.after;
+                    } else {
+                        observations.push(data.after);
+                    }
+                    entity.updated_by = event.user.clone();
+                    entity.updated_at = event.timestamp as u64;
+                }
+            }
+
             EventType::RelationCreated => {
                 let data: RelationCreatedData = event.parse_data()?;
 
@@ -414,14 +803,17 @@ impl EventStore {
                 });
 
                 if !exists {
+                    let created_at = data.created_at.unwrap_or(event.timestamp) as u64;
                     let relation = Relation {
                         from: data.from,
                         to: data.to,
                         relation_type: data.relation_type,
                         created_by: event.user.clone(),
-                        created_at: event.timestamp as u64,
+                        created_at,
+                        updated_at: created_at,
                         valid_from: data.valid_from.map(|v| v as u64),
                         valid_to: data.valid_to.map(|v| v as u64),
+                        properties: data.properties,
                     };
                     relations.push(relation);
                 }
@@ -434,6 +826,22 @@ impl EventStore {
                     !(r.from == data.from && r.to == data.to && r.relation_type == data.relation_type)
                 });
             }
+
+            EventType::RelationUpdated => {
+                let data: RelationUpdatedData = event.parse_data()?;
+
+                if let Some(relation) = relations.iter_mut().find(|r| {
+                    r.from == data.from && r.to == data.to && r.relation_type == data.relation_type
+                }) {
+                    if let Some(valid_from) = data.valid_from {
+                        relation.valid_from = Some(valid_from as u64);
+                    }
+                    if let Some(valid_to) = data.valid_to {
+                        relation.valid_to = Some(valid_to as u64);
+                    }
+                    relation.updated_at = event.timestamp as u64;
+                }
+            }
         }
 
         Ok(())
@@ -444,17 +852,25 @@ impl EventStore {
     /// This loads and applies all events in order to reconstruct
     /// the current state from scratch.
     pub fn replay_all(&self) -> EventStoreResult<(Vec<Entity>, Vec<Relation>, u64)> {
-        let events = self.load_events()?;
+        self.replay_all_with_progress(0, &mut |_| {})
+    }
+
+    /// Replay all events, calling `on_progress` every `every` events and
+    /// once at the end (`every` = 0 only calls it at the end)
+    pub fn replay_all_with_progress(
+        &self,
+        every: usize,
+        on_progress: &mut dyn FnMut(ReplayProgress),
+    ) -> EventStoreResult<(Vec<Entity>, Vec<Relation>, u64)> {
         let mut entities = Vec::new();
         let mut relations = Vec::new();
-        let mut max_event_id = 0u64;
-
-        for event in &events {
-            Self::apply_event(&mut entities, &mut relations, event)?;
-            if event.event_id > max_event_id {
-                max_event_id = event.event_id;
-            }
-        }
+        let max_event_id = self.replay_after_with_progress(
+            &mut entities,
+            &mut relations,
+            0,
+            every,
+            on_progress,
+        )?;
 
         Ok((entities, relations, max_event_id))
     }
@@ -465,72 +881,233 @@ impl EventStore {
         entities: &mut Vec<Entity>,
         relations: &mut Vec<Relation>,
         after_event_id: u64,
+    ) -> EventStoreResult<u64> {
+        self.replay_after_with_progress(entities, relations, after_event_id, 0, &mut |_| {})
+    }
+
+    /// Replay events after a specific event ID, reporting progress like
+    /// `replay_all_with_progress`
+    pub fn replay_after_with_progress(
+        &self,
+        entities: &mut Vec<Entity>,
+        relations: &mut Vec<Relation>,
+        after_event_id: u64,
+        every: usize,
+        on_progress: &mut dyn FnMut(ReplayProgress),
     ) -> EventStoreResult<u64> {
         let events = self.load_events_after(after_event_id)?;
-        let mut max_event_id = after_event_id;
+        let max_event_id = Self::replay_events(entities, relations, &events, every, on_progress)?;
+        Ok(max_event_id.max(after_event_id))
+    }
 
-        for event in &events {
+    /// Apply `events` in order, reporting progress like
+    /// `replay_all_with_progress`, and return the highest event ID among them
+    fn replay_events(
+        entities: &mut Vec<Entity>,
+        relations: &mut Vec<Relation>,
+        events: &[Event],
+        every: usize,
+        on_progress: &mut dyn FnMut(ReplayProgress),
+    ) -> EventStoreResult<u64> {
+        let total = events.len() as u64;
+        let mut max_event_id = 0;
+
+        for (index, event) in events.iter().enumerate() {
             Self::apply_event(entities, relations, event)?;
             if event.event_id > max_event_id {
                 max_event_id = event.event_id;
             }
+            if every > 0 && (index + 1) % every == 0 && index + 1 < events.len() {
+                on_progress(ReplayProgress {
+                    replayed: index as u64 + 1,
+                    total,
+                });
+            }
         }
+        on_progress(ReplayProgress {
+            replayed: total,
+            total,
+        });
 
         Ok(max_event_id)
     }
 
+    /// Replay for `initialize`, reporting to stderr and `replay_status`
+    fn replay_from(
+        &self,
+        entities: &mut Vec<Entity>,
+        relations: &mut Vec<Relation>,
+        events: &[Event],
+    ) -> EventStoreResult<u64> {
+        let every = self.config.replay_progress_every;
+        let status = replay_status();
+        status.start();
+        let result = Self::replay_events(
+            entities,
+            relations,
+            events,
+            every,
+            &mut |progress| {
+                status.update(progress);
+                if every > 0 && progress.total as usize >= every {
+                    eprintln!(
+                        "[Replay] Replayed {}/{} events",
+                        progress.replayed, progress.total
+                    );
+                }
+            },
+        );
+        status.finish();
+        result
+    }
+
     /// Initialize from storage (snapshot + replay)
     ///
     /// This is the main startup path:
     /// 1. Try to load latest snapshot
-    /// 2. Replay any events after the snapshot
+    /// 2. Replay the log lines after the snapshot's log position, in log
+    ///    order (after its `last_event_id`, for snapshots without one)
     /// 3. Return the reconstructed state
     pub fn initialize(&mut self) -> EventStoreResult<(Vec<Entity>, Vec<Relation>)> {
+        let (positioned, end) = self.load_positioned_events()?;
+        self.log_position = end;
+
         // Try to load snapshot first
         if let Some((meta, mut entities, mut relations)) = self.load_snapshot()? {
             self.last_snapshot_event_id = meta.last_event_id;
+            self.last_snapshot_time = meta.created_at.max(0) as u64;
             self.next_event_id = meta.last_event_id + 1;
 
             // Replay events after snapshot
-            let max_event_id = self.replay_after(&mut entities, &mut relations, meta.last_event_id)?;
+            let events: Vec<Event> = positioned
+                .into_iter()
+                .filter(|(position, event)| match meta.log_position {
+                    Some(snapshot_position) => *position >= snapshot_position,
+                    None => event.event_id > meta.last_event_id,
+                })
+                .map(|(_, event)| event)
+                .collect();
+            let max_event_id = self.replay_from(&mut entities, &mut relations, &events)?;
 
             if max_event_id > self.next_event_id {
                 self.next_event_id = max_event_id + 1;
             }
 
-            self.events_since_snapshot = (max_event_id - meta.last_event_id) as usize;
+            self.snapshot_position = meta
+                .log_position
+                .unwrap_or_else(|| end.saturating_sub(events.len() as u64));
 
             println!(
                 "Loaded snapshot (event_id: {}) + replayed {} events. Total: {} entities, {} relations.",
                 meta.last_event_id,
-                self.events_since_snapshot,
+                events.len(),
                 entities.len(),
                 relations.len()
             );
 
+            self.resume_after_initialize()?;
             Ok((entities, relations))
         } else {
             // No snapshot, replay all events
-            let (entities, relations, max_event_id) = self.replay_all()?;
+            let (mut entities, mut relations) = (Vec::new(), Vec::new());
+            let events: Vec<Event> = positioned.into_iter().map(|(_, event)| event).collect();
+            let max_event_id = self.replay_from(&mut entities, &mut relations, &events)?;
 
             if max_event_id > 0 {
                 self.next_event_id = max_event_id + 1;
-                self.events_since_snapshot = max_event_id as usize;
             }
 
             println!(
                 "No snapshot found. Replayed {} events. Total: {} entities, {} relations.",
-                max_event_id, entities.len(), relations.len()
+                events.len(),
+                entities.len(),
+                relations.len()
             );
 
+            self.resume_after_initialize()?;
             Ok((entities, relations))
         }
     }
 
+    /// Resume ID assignment within this store's range and start tailing at
+    /// the current end of the log
+    fn resume_after_initialize(&mut self) -> EventStoreResult<()> {
+        // The highest ID in the log may belong to another writer. Block
+        // writers keep it: their first event reserves a block above it.
+        if self.config.id_block_size.is_none() && !self.owns(self.next_event_id) {
+            // Owned events may all be archived; the snapshot still names one
+            let max_owned = self
+                .load_events()?
+                .iter()
+                .map(|e| e.event_id)
+                .chain(Some(self.last_snapshot_event_id))
+                .filter(|id| *id > 0 && self.owns(*id))
+                .max();
+            self.next_event_id = max_owned.map_or(self.config.id_range.start, |id| id + 1);
+        }
+
+        self.resume_tail_at_end()
+    }
+
+    /// Move the tail cursor to the current end of the log
+    fn resume_tail_at_end(&mut self) -> EventStoreResult<()> {
+        let events_path = self.config.events_path();
+        self.tail_cursor.segment = LogRotation::new(self.config.clone()).list_segments()?.len();
+        self.tail_cursor.offset = if events_path.exists() {
+            std::fs::metadata(&events_path)?.len()
+        } else {
+            0
+        };
+        Ok(())
+    }
+
+    /// Archive the log up to `log_position` (see `LogRotation::rotate_to_position`)
+    ///
+    /// Rotation rewrites the live log under the tail cursor, so it waits
+    /// until every event other writers appended is applied: with events
+    /// pending, nothing is rotated.
+    pub fn rotate_to_position(&mut self, log_position: u64) -> EventStoreResult<Option<PathBuf>> {
+        self.rotate_with(|rotation| rotation.rotate_to_position(log_position))
+    }
+
+    /// Archive events up to `snapshot_event_id`, for snapshots without a log
+    /// position (see `LogRotation::rotate_after_snapshot`)
+    ///
+    /// Waits for pending events like `rotate_to_position`.
+    pub fn rotate_after_snapshot(
+        &mut self,
+        snapshot_event_id: u64,
+    ) -> EventStoreResult<Option<PathBuf>> {
+        self.rotate_with(|rotation| rotation.rotate_after_snapshot(snapshot_event_id))
+    }
+
+    fn rotate_with(
+        &mut self,
+        rotate: impl FnOnce(&LogRotation) -> EventStoreResult<Option<PathBuf>>,
+    ) -> EventStoreResult<Option<PathBuf>> {
+        if !self.catch_up_tail()? {
+            return Ok(None);
+        }
+        let archive = rotate(&LogRotation::new(self.config.clone()))?;
+        if archive.is_some() {
+            self.resume_tail_at_end()?;
+        }
+        Ok(archive)
+    }
+
     /// Reset snapshot counter (called after snapshot creation)
     pub fn snapshot_created(&mut self, last_event_id: u64) {
-        self.last_snapshot_event_id = last_event_id;
-        self.events_since_snapshot = 0;
+        self.snapshot_created_at(last_event_id, self.log_position);
+    }
+
+    /// Record a snapshot of the log up to `log_position`
+    ///
+    /// Used when events may have been appended while the snapshot was being
+    /// written: those keep counting towards the next snapshot.
+    pub fn snapshot_created_at(&mut self, last_event_id: u64, log_position: u64) {
+        self.last_snapshot_event_id = self.last_snapshot_event_id.max(last_event_id);
+        self.snapshot_position = self.snapshot_position.max(log_position);
+        self.last_snapshot_time = current_timestamp();
     }
 }
 
@@ -598,6 +1175,38 @@ mod tests {
         assert_eq!(events[1].event_type, EventType::ObservationAdded);
     }
 
+    #[test]
+    fn test_should_snapshot_on_interval() {
+        let (mut store, _temp_dir) = create_test_store();
+        store.config.snapshot_interval_secs = Some(3600);
+
+        store
+            .create_and_append_event(
+                EventType::EntityCreated,
+                "test".to_string(),
+                json!({"name": "A", "entity_type": "Module"}),
+            )
+            .unwrap();
+        assert!(!store.should_snapshot());
+
+        // Pretend the last snapshot was two hours ago
+        store.last_snapshot_time -= 7200;
+        assert!(store.should_snapshot());
+
+        // Nothing new since the snapshot, so the interval alone does not fire
+        store.snapshot_created(1);
+        store.last_snapshot_time -= 7200;
+        assert!(!store.should_snapshot());
+    }
+
+    #[test]
+    fn test_parse_id_range() {
+        assert_eq!(EventStoreConfig::parse_id_range("100..200"), Some(100..200));
+        assert_eq!(EventStoreConfig::parse_id_range("0..10"), None);
+        assert_eq!(EventStoreConfig::parse_id_range("20..10"), None);
+        assert_eq!(EventStoreConfig::parse_id_range("abc"), None);
+    }
+
     #[test]
     fn test_load_events_after() {
         let (mut store, _temp_dir) = create_test_store();
@@ -623,6 +1232,52 @@ mod tests {
         assert_eq!(events[1].event_id, 5);
     }
 
+    #[test]
+    fn test_id_blocks_shared_between_writers() {
+        let (_store, temp_dir) = create_test_store();
+        let config = EventStoreConfig {
+            id_block_size: Some(10),
+            ..EventStoreConfig::with_data_dir(temp_dir.path())
+        };
+        let mut writers = [
+            EventStore::with_config(config.clone()),
+            EventStore::with_config(config.clone()),
+        ];
+
+        let mut ids = Vec::new();
+        for i in 0..30 {
+            let event = writers[i % 2]
+                .create_and_append_event(
+                    EventType::EntityCreated,
+                    "user".to_string(),
+                    json!({"name": format!("Entity:{}", i), "entity_type": "Test"}),
+                )
+                .unwrap();
+            ids.push(event.event_id);
+        }
+        assert_eq!(&ids[..4], &[1, 11, 2, 12]);
+        assert!(writers[0].owns(1) && !writers[0].owns(11));
+
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 30);
+        let reservation: IdReservation =
+            serde_json::from_str(&std::fs::read_to_string(config.id_reservation_path()).unwrap())
+                .unwrap();
+        assert_eq!(reservation.high_water_mark, 41);
+
+        // Losing the file recovers the mark from the log
+        std::fs::remove_file(config.id_reservation_path()).unwrap();
+        let mut writer = EventStore::with_config(config.clone());
+        assert_eq!(writer.reserve_id_block(5).unwrap(), (36, 41));
+
+        let config = EventStoreConfig {
+            id_range: 1..45,
+            ..config
+        };
+        assert!(EventStore::with_config(config).reserve_id_block(5).is_err());
+    }
+
     #[test]
     fn test_apply_entity_created() {
         let mut entities = Vec::new();
@@ -719,6 +1374,39 @@ mod tests {
         assert_eq!(relations[0].relation_type, "affects");
     }
 
+    #[test]
+    fn test_apply_relation_created_timestamps() {
+        let mut entities = Vec::new();
+        let mut relations = Vec::new();
+
+        // Recorded creation time wins over the event timestamp
+        let event = Event::new(
+            EventType::RelationCreated,
+            1,
+            "user".to_string(),
+            json!({
+                "from": "A",
+                "to": "B",
+                "relation_type": "depends_on",
+                "created_at": 1700000000
+            }),
+        );
+        EventStore::apply_event(&mut entities, &mut relations, &event).unwrap();
+        assert_eq!(relations[0].created_at, 1700000000);
+        assert_eq!(relations[0].updated_at, 1700000000);
+
+        // Older events without it fall back to the event timestamp
+        let event = Event::new(
+            EventType::RelationCreated,
+            2,
+            "user".to_string(),
+            json!({"from": "B", "to": "A", "relation_type": "depends_on"}),
+        );
+        EventStore::apply_event(&mut entities, &mut relations, &event).unwrap();
+        assert_eq!(relations[1].created_at, event.timestamp as u64);
+        assert_eq!(relations[1].updated_at, relations[1].created_at);
+    }
+
     #[test]
     fn test_replay_all() {
         let (mut store, _temp_dir) = create_test_store();
@@ -756,6 +1444,28 @@ mod tests {
         assert_eq!(max_id, 3);
     }
 
+    #[test]
+    fn test_replay_all_with_progress() {
+        let (mut store, _temp_dir) = create_test_store();
+        for name in ["A", "B", "C", "D", "E"] {
+            store
+                .create_and_append_event(
+                    EventType::EntityCreated,
+                    "user".to_string(),
+                    json!({"name": name, "entity_type": "Test"}),
+                )
+                .unwrap();
+        }
+
+        let mut reports = Vec::new();
+        let (entities, _, _) = store
+            .replay_all_with_progress(2, &mut |progress| reports.push(progress.replayed))
+            .unwrap();
+
+        assert_eq!(entities.len(), 5);
+        assert_eq!(reports, vec![2, 4, 5]);
+    }
+
     #[test]
     fn test_idempotent_entity_created() {
         let mut entities = Vec::new();
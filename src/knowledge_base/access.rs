@@ -0,0 +1,116 @@
+//! In-memory entity access tracking
+//!
+//! Counts how often each entity is returned by read queries so callers can
+//! prioritize frequently used entities when building context. Stats are never
+//! persisted and reset on restart. Set `MEMORY_ACCESS_TRACKING=false` to disable.
+
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::sync::Mutex;
+
+use crate::types::HotEntity;
+use crate::utils::time::current_timestamp;
+
+use super::{acl, KnowledgeBase};
+
+/// Per-entity access statistics
+#[derive(Debug, Clone, Copy, Default)]
+struct AccessStats {
+    count: u64,
+    last_accessed: u64,
+}
+
+/// Thread-safe access counter keyed by entity name
+#[derive(Debug)]
+pub(crate) struct AccessTracker {
+    enabled: bool,
+    stats: Mutex<HashMap<String, AccessStats>>,
+}
+
+impl AccessTracker {
+    /// Create a tracker, optionally disabled
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            stats: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create a tracker configured from `MEMORY_ACCESS_TRACKING` (enabled by default)
+    pub(crate) fn from_env() -> Self {
+        let enabled = env::var("MEMORY_ACCESS_TRACKING")
+            .map(|v| v != "false" && v != "0")
+            .unwrap_or(true);
+        Self::new(enabled)
+    }
+
+    /// Record one access for each distinct entity name
+    pub(crate) fn record<'a>(&self, names: impl IntoIterator<Item = &'a str>) {
+        if !self.enabled {
+            return;
+        }
+
+        let now = current_timestamp();
+        let unique: HashSet<&str> = names.into_iter().collect();
+        let mut stats = self.stats.lock().unwrap();
+        for name in unique {
+            let entry = stats.entry(name.to_string()).or_default();
+            entry.count += 1;
+            entry.last_accessed = now;
+        }
+    }
+}
+
+/// Get the most accessed entities that still exist in the graph
+///
+/// Ordered by access count, then by most recent access.
+pub fn hot_entities(kb: &KnowledgeBase, limit: usize) -> Vec<HotEntity> {
+    let stats = kb.access_tracker.stats.lock().unwrap().clone();
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+
+    let mut hot: Vec<HotEntity> = graph
+        .entities
+        .iter()
+        .filter_map(|e| {
+            stats.get(&e.name).map(|s| HotEntity {
+                name: e.name.clone(),
+                entity_type: e.entity_type.clone(),
+                access_count: s.count,
+                last_accessed: s.last_accessed,
+            })
+        })
+        .collect();
+
+    hot.sort_by(|a, b| {
+        b.access_count
+            .cmp(&a.access_count)
+            .then(b.last_accessed.cmp(&a.last_accessed))
+    });
+    hot.truncate(limit);
+    hot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_counts_distinct_names() {
+        let tracker = AccessTracker::new(true);
+        tracker.record(["A", "A", "B"]);
+        tracker.record(["A"]);
+
+        let stats = tracker.stats.lock().unwrap();
+        assert_eq!(stats["A"].count, 2);
+        assert_eq!(stats["B"].count, 1);
+    }
+
+    #[test]
+    fn test_disabled_tracker_records_nothing() {
+        let tracker = AccessTracker::new(false);
+        tracker.record(["A"]);
+
+        assert!(tracker.stats.lock().unwrap().is_empty());
+    }
+}
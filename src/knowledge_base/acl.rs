@@ -0,0 +1,405 @@
+//! Entity access-control lists
+//!
+//! An entity may carry an `acl`: usernames or permissions allowed to see and
+//! change it. Entities without one are public. ACLs apply to every call that
+//! runs as a [`Caller`] (see `user.rs`): HTTP requests run as the token's user
+//! or, without a valid token, as [`Caller::anonymous`], which satisfies no
+//! ACL. Only calls without a caller (stdio, library use) see everything. A
+//! caller satisfies an ACL when their username or one of their permissions is
+//! listed, or when they hold the `*` permission.
+//!
+//! Read queries work on [`view`] of the graph, which leaves out entities the
+//! caller may not see together with the relations touching them, so hidden
+//! entities are reported as not found. The REST endpoints and the WebSocket
+//! and SSE streams filter the same way ([`event_visible`]). Mutations naming
+//! such an entity (observations, deletions, relations, protection and the
+//! same operations in transactions) fail with an access error, as do imports,
+//! scratch promotions and bulk relation chunks naming one, and graph-wide
+//! rewrites, renames, normalization and historization that would change one.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use crate::api::websocket::events::GraphEvent;
+use crate::types::{Caller, Entity, KnowledgeGraph, McpResult};
+
+use super::user;
+
+/// Whether `caller` may see and change `entity` (`None` = no caller, sees all)
+pub(super) fn allows(entity: &Entity, caller: Option<&Caller>) -> bool {
+    let Some(caller) = caller else {
+        return true;
+    };
+    if entity.acl.is_empty() {
+        return true;
+    }
+    caller.authenticated
+        && (caller.permissions.iter().any(|p| p == "*")
+            || entity
+                .acl
+                .iter()
+                .any(|entry| *entry == caller.user || caller.permissions.contains(entry)))
+}
+
+/// Names of the entities `caller` may not see
+fn hidden_names(graph: &KnowledgeGraph, caller: Option<&Caller>) -> HashSet<String> {
+    graph
+        .entities
+        .iter()
+        .filter(|e| !allows(e, caller))
+        .map(|e| e.name.clone())
+        .collect()
+}
+
+/// Names of the entities the current caller may not see
+pub(super) fn hidden(graph: &KnowledgeGraph) -> HashSet<String> {
+    hidden_names(graph, user::caller().as_ref())
+}
+
+/// The graph as the current caller may see it
+///
+/// Borrows `graph` when nothing is hidden, so unrestricted reads copy nothing.
+pub(super) fn view(graph: &KnowledgeGraph) -> Cow<'_, KnowledgeGraph> {
+    let hidden = hidden_names(graph, user::caller().as_ref());
+    if hidden.is_empty() {
+        return Cow::Borrowed(graph);
+    }
+    Cow::Owned(KnowledgeGraph {
+        entities: graph
+            .entities
+            .iter()
+            .filter(|e| !hidden.contains(&e.name))
+            .cloned()
+            .collect(),
+        relations: graph
+            .relations
+            .iter()
+            .filter(|r| !hidden.contains(&r.from) && !hidden.contains(&r.to))
+            .cloned()
+            .collect(),
+    })
+}
+
+/// Drop the entities the current caller may not see, and relations touching them
+pub(super) fn visible(mut graph: KnowledgeGraph) -> KnowledgeGraph {
+    let hidden = hidden_names(&graph, user::caller().as_ref());
+    if hidden.is_empty() {
+        return graph;
+    }
+    graph.entities.retain(|e| !hidden.contains(&e.name));
+    graph
+        .relations
+        .retain(|r| !hidden.contains(&r.from) && !hidden.contains(&r.to));
+    graph
+}
+
+/// `event` as `caller` may see it in `graph`, or `None` if nothing is left
+///
+/// Created entities are checked against their own ACL; other events against
+/// the entities they name. A deleted entity is gone from the graph, so its
+/// deletion is always passed on.
+pub(super) fn event_visible(
+    graph: &KnowledgeGraph,
+    event: &GraphEvent,
+    caller: &Caller,
+) -> Option<GraphEvent> {
+    let hidden = |name: &str| {
+        graph
+            .entities
+            .iter()
+            .find(|e| e.name == name)
+            .is_some_and(|e| !allows(e, Some(caller)))
+    };
+    let shown = match event {
+        GraphEvent::EntityCreated { payload, .. } => allows(payload, Some(caller)),
+        GraphEvent::EntityUpdated { name, .. } => !hidden(name),
+        GraphEvent::RelationCreated { payload, .. } | GraphEvent::RelationUpdated { payload, .. } => {
+            !hidden(&payload.from) && !hidden(&payload.to)
+        }
+        GraphEvent::RelationDeleted { from, to, .. } => !hidden(from) && !hidden(to),
+        GraphEvent::BatchUpdate { events } => {
+            let events: Vec<GraphEvent> = events
+                .iter()
+                .filter_map(|e| event_visible(graph, e, caller))
+                .collect();
+            return (!events.is_empty()).then_some(GraphEvent::BatchUpdate { events });
+        }
+        GraphEvent::Inferred {
+            target,
+            relations,
+            persistent,
+        } => {
+            if hidden(target) {
+                return None;
+            }
+            let relations = relations
+                .iter()
+                .filter(|r| !hidden(&r.relation.from) && !hidden(&r.relation.to))
+                .cloned()
+                .collect();
+            return Some(GraphEvent::Inferred {
+                target: target.clone(),
+                relations,
+                persistent: *persistent,
+            });
+        }
+        GraphEvent::EntityDeleted { .. } | GraphEvent::Resync { .. } => true,
+    };
+    shown.then(|| event.clone())
+}
+
+/// Fail if the current caller may not change one of the named entities
+///
+/// Names of missing entities pass, so callers keep their own not-found handling.
+pub(super) fn check_writable<'a>(
+    graph: &KnowledgeGraph,
+    names: impl IntoIterator<Item = &'a str>,
+) -> McpResult<()> {
+    let Some(caller) = user::caller() else {
+        return Ok(());
+    };
+    for name in names {
+        if let Some(entity) = graph.entities.iter().find(|e| e.name == name) {
+            if !allows(entity, Some(&caller)) {
+                return Err(format!("Access denied to entity '{}'", name).into());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::knowledge_base::KnowledgeBase;
+    use crate::types::{Observation, Relation, SearchOptions};
+
+    #[test]
+    fn test_acl_hides_and_protects_entities() {
+        let path = format!("target/test_acl_{}.jsonl", std::process::id());
+        let kb = KnowledgeBase::for_testing(path.clone(), "server".to_string());
+        let mut payroll = Entity::new("Payroll".to_string(), "Module".to_string());
+        payroll.acl = vec!["alice".to_string(), "finance".to_string()];
+        kb.create_entities(vec![
+            payroll,
+            Entity::new("Auth".to_string(), "Module".to_string()),
+        ])
+        .unwrap();
+        kb.create_relations(vec![Relation::new(
+            "Payroll".to_string(),
+            "Auth".to_string(),
+            "depends_on".to_string(),
+        )])
+        .unwrap();
+
+        let names = |graph: KnowledgeGraph| -> (Vec<String>, usize) {
+            let names = graph.entities.into_iter().map(|e| e.name).collect();
+            (names, graph.relations.len())
+        };
+        let bob = Caller::new("bob".to_string(), vec!["read".to_string()]);
+        let cfo = Caller::new("carol".to_string(), vec!["finance".to_string()]);
+
+        // Without an authenticated caller everything is visible
+        assert_eq!(names(kb.read_graph(None, None).unwrap()).0.len(), 2);
+        KnowledgeBase::run_as_caller(Some(bob), || {
+            assert_eq!(
+                names(kb.read_graph(None, None).unwrap()),
+                (vec!["Auth".to_string()], 0)
+            );
+            let opened = kb.open_nodes(vec!["Payroll".to_string(), "Auth".to_string()]);
+            assert_eq!(names(opened.unwrap()), (vec!["Auth".to_string()], 0));
+            let options = SearchOptions::default();
+            assert!(kb
+                .search_nodes("Payroll", &options)
+                .unwrap()
+                .entities
+                .is_empty());
+
+            let denied = kb.add_observations(vec![Observation {
+                entity_name: "Payroll".to_string(),
+                contents: vec!["Owner: bob".to_string()],
+            }]);
+            assert!(denied.unwrap_err().to_string().contains("Access denied"));
+            assert!(kb.delete_entities(vec!["Payroll".to_string()]).is_err());
+        });
+        KnowledgeBase::run_as(Some("alice"), || {
+            assert_eq!(names(kb.read_graph(None, None).unwrap()).1, 1);
+        });
+        KnowledgeBase::run_as_caller(Some(cfo), || {
+            kb.delete_entities(vec!["Payroll".to_string()]).unwrap();
+        });
+        assert_eq!(names(kb.read_graph(None, None).unwrap()).0, vec!["Auth"]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_acl_guards_graph_wide_writes() {
+        let path = format!("target/test_acl_graph_wide_{}.jsonl", std::process::id());
+        let kb = KnowledgeBase::for_testing(path.clone(), "server".to_string());
+        let mut payroll = Entity::with_observations(
+            "Payroll".to_string(),
+            "Module".to_string(),
+            vec!["Runs monthly".to_string()],
+        );
+        payroll.acl = vec!["finance".to_string()];
+        kb.create_entities(vec![
+            payroll,
+            Entity::new("Auth".to_string(), "Module".to_string()),
+        ])
+        .unwrap();
+        kb.create_relations(vec![Relation::new(
+            "Payroll".to_string(),
+            "Auth".to_string(),
+            "depends_on".to_string(),
+        )])
+        .unwrap();
+        let json = |kb: &KnowledgeBase| serde_json::to_value(kb.read_graph(None, None).unwrap());
+        let before = json(&kb).unwrap();
+
+        // Changes made in a scratch copy before the caller promotes it
+        let scratch = kb.scratch_copy();
+        scratch
+            .add_observations(vec![Observation {
+                entity_name: "Payroll".to_string(),
+                contents: vec!["Owner: bob".to_string()],
+            }])
+            .unwrap();
+
+        fn denied<T>(result: McpResult<T>) {
+            let message = result.err().expect("access was denied").to_string();
+            assert!(message.contains("Access denied"), "{}", message);
+        }
+        let bob = Caller::new("bob".to_string(), vec!["read".to_string()]);
+        KnowledgeBase::run_as_caller(Some(bob), || {
+            let incoming = KnowledgeGraph {
+                entities: vec![Entity::with_observations(
+                    "Payroll".to_string(),
+                    "Module".to_string(),
+                    vec!["Owner: bob".to_string()],
+                )],
+                relations: Vec::new(),
+            };
+            denied(kb.import_graph(incoming));
+            denied(kb.promote_from(&scratch, None));
+            denied(kb.rename_relation_type("depends_on", "uses"));
+            denied(kb.rename_entity_type("Module", "Service"));
+            denied(kb.replace_in_observations("monthly", "weekly", false));
+        });
+        assert_eq!(json(&kb).unwrap(), before);
+
+        let cfo = Caller::new("carol".to_string(), vec!["finance".to_string()]);
+        KnowledgeBase::run_as_caller(Some(cfo), || {
+            assert_eq!(kb.rename_relation_type("depends_on", "uses").unwrap(), 1);
+        });
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_acl_checks_relation_endpoints_given_by_id() {
+        let path = format!("target/test_acl_endpoint_ids_{}.jsonl", std::process::id());
+        let kb = KnowledgeBase::for_testing(path.clone(), "server".to_string());
+        let mut payroll = Entity::new("Payroll".to_string(), "Module".to_string());
+        payroll.acl = vec!["finance".to_string()];
+        let created = kb
+            .create_entities(vec![
+                payroll,
+                Entity::new("Auth".to_string(), "Module".to_string()),
+            ])
+            .unwrap();
+        let payroll_id = created[0].id.clone();
+        assert!(!payroll_id.is_empty());
+        let by_id = || {
+            vec![Relation::new(
+                "Auth".to_string(),
+                payroll_id.clone(),
+                "depends_on".to_string(),
+            )]
+        };
+
+        let bob = Caller::new("bob".to_string(), vec!["read".to_string()]);
+        KnowledgeBase::run_as_caller(Some(bob), || {
+            let message = kb.create_relations(by_id()).unwrap_err().to_string();
+            assert!(message.contains("Access denied"), "{}", message);
+            let (report, _) = kb.bulk_create(Vec::new(), by_id(), 10).unwrap();
+            assert_eq!(report.relations_created, 0);
+            assert!(report.error.unwrap().contains("Access denied"));
+            let incoming = KnowledgeGraph {
+                entities: Vec::new(),
+                relations: by_id(),
+            };
+            assert!(kb.import_graph(incoming).is_err());
+        });
+        assert!(kb.read_graph(None, None).unwrap().relations.is_empty());
+
+        let cfo = Caller::new("carol".to_string(), vec!["finance".to_string()]);
+        KnowledgeBase::run_as_caller(Some(cfo), || {
+            let created = kb.create_relations(by_id()).unwrap();
+            assert_eq!(created[0].to, "Payroll");
+        });
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_acl_filters_every_read_path() {
+        use crate::types::{PathStep, RelatedOptions, UserField};
+
+        let path = format!("target/test_acl_reads_{}.jsonl", std::process::id());
+        let kb = KnowledgeBase::for_testing(path.clone(), "server".to_string());
+        let mut payroll = Entity::with_observations(
+            "Payroll".to_string(),
+            "Module".to_string(),
+            vec!["Handles salaries".to_string()],
+        );
+        payroll.acl = vec!["finance".to_string()];
+        kb.create_entities(vec![
+            payroll,
+            Entity::with_observations(
+                "Auth".to_string(),
+                "Module".to_string(),
+                vec!["Handles salaries too".to_string()],
+            ),
+        ])
+        .unwrap();
+        kb.create_relations(vec![Relation::new(
+            "Auth".to_string(),
+            "Payroll".to_string(),
+            "depends_on".to_string(),
+        )])
+        .unwrap();
+
+        // A request without a token holds no grants, even for an ACL naming it
+        let anonymous = Caller::anonymous();
+        KnowledgeBase::run_as_caller(Some(anonymous), || {
+            assert_eq!(kb.read_graph(None, None).unwrap().entities.len(), 1);
+            let related = kb
+                .get_related("Auth", &RelatedOptions::default())
+                .unwrap();
+            assert_eq!(related.total, 0);
+            let step = PathStep::new("depends_on".to_string(), "outgoing".to_string());
+            assert!(kb.traverse("Auth", vec![step], 10).unwrap().paths.is_empty());
+            assert_eq!(kb.get_subgraph("Auth", 2).unwrap().entities.len(), 1);
+            assert!(kb.entity_to_markdown("Payroll").is_err());
+            assert!(kb.get_attributes("Payroll").is_err());
+            assert!(kb.diff_entities("Auth", "Payroll").is_err());
+            assert!(kb.references_to("Payroll").is_err());
+            assert!(kb.entity_timeline("Payroll").is_err());
+            assert!(kb.suggest_relations("Auth", 5).unwrap().is_empty());
+            assert_eq!(kb.entities_by_user("server", UserField::CreatedBy).len(), 1);
+            assert!(kb.recent_changes(10, Some("Payroll")).is_empty());
+            // Writes still go to the startup user
+            assert_eq!(kb.acting_user(), "server");
+        });
+
+        // Tokens holding a listed permission see it everywhere
+        let cfo = Caller::new("carol".to_string(), vec!["finance".to_string()]);
+        KnowledgeBase::run_as_caller(Some(cfo), || {
+            assert!(kb.entity_to_markdown("Payroll").is_ok());
+            assert_eq!(kb.get_subgraph("Auth", 2).unwrap().relations.len(), 1);
+        });
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,103 @@
+//! One-file backups of the persisted state
+//!
+//! In legacy mode a backup is the `memory.jsonl` file as stored. In Event
+//! Sourcing mode it is a full snapshot of the current state (the snapshot
+//! metadata line, the graph metadata line if there is any, entities, then
+//! relations) followed by every event of the active log, archives excluded.
+//! Read as a snapshot, the file yields the state and skips the events.
+//!
+//! The graph read lock (and in Event Sourcing mode the event store lock) is
+//! held until the backup is written, so it reflects a single point between
+//! mutations; writers wait meanwhile. Callers serving a backup over the
+//! network should write it to a buffer first rather than to the client.
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::types::{BackupMode, KnowledgeGraph, McpResult, SnapshotMeta};
+
+use super::KnowledgeBase;
+
+/// Mode of backups of this knowledge base
+pub fn backup_mode(kb: &KnowledgeBase) -> BackupMode {
+    if kb.is_event_sourcing_enabled() {
+        BackupMode::EventSourcing
+    } else {
+        BackupMode::Legacy
+    }
+}
+
+/// Write a backup of the persisted state to `out`
+pub fn write_backup(kb: &KnowledgeBase, out: &mut dyn Write) -> McpResult<BackupMode> {
+    let graph = kb.graph.read().unwrap();
+    write_backup_of(kb, &graph, out)
+}
+
+/// Write a backup of `graph`, which the caller holds locked
+pub(super) fn write_backup_of(
+    kb: &KnowledgeBase,
+    graph: &KnowledgeGraph,
+    out: &mut dyn Write,
+) -> McpResult<BackupMode> {
+    let Some(ref event_store) = kb.event_store else {
+        let path = Path::new(&kb.memory_file_path);
+        if path.exists() {
+            io::copy(&mut File::open(path)?, out)?;
+        }
+        return Ok(BackupMode::Legacy);
+    };
+
+    let store = event_store.lock().unwrap();
+    let last_event_id = store.next_event_id().saturating_sub(1);
+    let meta = SnapshotMeta::new(last_event_id, graph.entities.len(), graph.relations.len());
+    writeln!(out, "{}", serde_json::to_string(&meta)?)?;
+
+    let metadata = kb.graph_metadata.read().unwrap();
+    if !metadata.is_empty() {
+        writeln!(out, "{}", metadata.to_header_line()?)?;
+    }
+    for entity in &graph.entities {
+        writeln!(out, "{}", serde_json::to_string(entity)?)?;
+    }
+    for relation in &graph.relations {
+        writeln!(out, "{}", serde_json::to_string(relation)?)?;
+    }
+    for event in store.load_events()? {
+        writeln!(out, "{}", serde_json::to_string(&event)?)?;
+    }
+
+    Ok(BackupMode::EventSourcing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Entity, Event};
+
+    #[test]
+    fn test_event_sourcing_backup_is_a_snapshot_plus_events() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        kb.create_entities(vec![
+            Entity::new("Api".to_string(), "Module".to_string()),
+            Entity::new("Auth".to_string(), "Module".to_string()),
+        ])
+        .unwrap();
+        kb.delete_entities(vec!["Auth".to_string()]).unwrap();
+
+        let mut backup = Vec::new();
+        assert_eq!(kb.write_backup(&mut backup).unwrap(), BackupMode::EventSourcing);
+        let backup = String::from_utf8(backup).unwrap();
+        let lines: Vec<&str> = backup.lines().collect();
+
+        let meta = SnapshotMeta::from_json_line(lines[0]).unwrap();
+        assert_eq!((meta.last_event_id, meta.entity_count), (3, 1));
+        assert!(lines[1].contains("\"name\":\"Api\""));
+        let events: Vec<Event> = lines[2..]
+            .iter()
+            .map(|line| Event::from_json_line(line).unwrap())
+            .collect();
+        assert_eq!(events.len(), 3);
+    }
+}
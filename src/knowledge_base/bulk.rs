@@ -0,0 +1,204 @@
+//! Chunked bulk creation of entities and relations
+//!
+//! A single `create_entities` call with tens of thousands of entities holds
+//! the write lock for the whole batch and saves nothing if it fails partway.
+//! `bulk_create` commits the input in chunks instead: each chunk takes the
+//! write lock, is applied with the same rules as `create_entities` /
+//! `create_relations`, and is persisted (or its events appended in Event
+//! Sourcing mode) before the lock is released, so readers get in between
+//! chunks and committed chunks survive a later failure.
+//!
+//! All entity chunks run before the relation chunks, so relations may refer
+//! to entities from any chunk. In file mode every chunk rewrites the memory
+//! file, so larger chunks mean fewer rewrites.
+
+use crate::types::{BulkChunk, BulkReport, Entity, McpResult, Relation};
+use crate::validation::ValidationWarning;
+
+use super::crud;
+use super::{acl, KnowledgeBase};
+
+/// Create `entities` then `relations`, committing `chunk_size` items at a time
+///
+/// `on_chunk` runs after each committed chunk; returning an error stops the
+/// import there, as a failed commit does. Either way the committed chunks
+/// are kept and the failure is reported in [`BulkReport::error`].
+pub fn bulk_create(
+    kb: &KnowledgeBase,
+    entities: Vec<Entity>,
+    relations: Vec<Relation>,
+    chunk_size: usize,
+    mut on_chunk: impl FnMut(&BulkChunk, &BulkReport) -> McpResult<()>,
+) -> McpResult<(BulkReport, Vec<ValidationWarning>)> {
+    if chunk_size == 0 {
+        return Err("chunkSize must be at least 1".into());
+    }
+
+    let mut report = BulkReport {
+        chunk_size,
+        total_chunks: entities.len().div_ceil(chunk_size) + relations.len().div_ceil(chunk_size),
+        ..Default::default()
+    };
+    let mut warnings = Vec::new();
+
+    let mut entities = entities.into_iter();
+    let mut relations = relations.into_iter();
+    for index in 0..report.total_chunks {
+        let entity_chunk: Vec<Entity> = entities.by_ref().take(chunk_size).collect();
+        let outcome = if entity_chunk.is_empty() {
+            let mut relation_chunk: Vec<Relation> =
+                relations.by_ref().take(chunk_size).collect();
+            let submitted = relation_chunk.len();
+            crud::commit_with_warnings(kb, |graph, pending| {
+                crud::resolve_relation_endpoints(kb, graph, &mut relation_chunk);
+                acl::check_writable(graph, crud::relation_endpoints(&relation_chunk))?;
+                Ok(crud::apply_create_relations(kb, graph, relation_chunk, pending).len())
+            })
+            .map(|(created, chunk_warnings)| {
+                let chunk = BulkChunk {
+                    index,
+                    submitted,
+                    relations_created: created,
+                    ..Default::default()
+                };
+                (chunk, chunk_warnings)
+            })
+        } else {
+            let submitted = entity_chunk.len();
+            crud::commit_with_warnings(kb, |graph, pending| {
+                Ok(crud::apply_create_entities(kb, graph, entity_chunk, pending).len())
+            })
+            .map(|(created, chunk_warnings)| {
+                let chunk = BulkChunk {
+                    index,
+                    submitted,
+                    entities_created: created,
+                    ..Default::default()
+                };
+                (chunk, chunk_warnings)
+            })
+        };
+
+        let (chunk, chunk_warnings) = match outcome {
+            Ok(committed) => committed,
+            Err(e) => {
+                report.failed_chunk = Some(index);
+                report.error = Some(e.to_string());
+                break;
+            }
+        };
+        warnings.extend(chunk_warnings);
+        report.entities_created += chunk.entities_created;
+        report.relations_created += chunk.relations_created;
+        report.chunks.push(chunk);
+
+        let chunk = report.chunks.last().expect("chunk was just pushed");
+        if let Err(e) = on_chunk(chunk, &report) {
+            if index + 1 < report.total_chunks {
+                report.failed_chunk = Some(index + 1);
+            }
+            report.error = Some(e.to_string());
+            break;
+        }
+    }
+
+    Ok((report, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_store::EventStore;
+    use crate::knowledge_base::load;
+
+    fn modules(count: usize) -> (Vec<Entity>, Vec<Relation>) {
+        let entities = (0..count)
+            .map(|i| Entity::new(format!("M{}", i), "Module".to_string()))
+            .collect();
+        let relations = (1..count)
+            .map(|i| {
+                Relation::new(
+                    format!("M{}", i),
+                    format!("M{}", i - 1),
+                    "depends_on".to_string(),
+                )
+            })
+            .collect();
+        (entities, relations)
+    }
+
+    #[test]
+    fn test_bulk_create_keeps_committed_chunks() {
+        let path = format!("target/test_bulk_create_{}.jsonl", std::process::id());
+        let kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        let (entities, relations) = modules(5000);
+
+        // Fail after the third chunk: the first 3,000 entities are saved
+        let (report, _) = bulk_create(&kb, entities, relations, 1000, |chunk, _| {
+            assert!(
+                kb.graph.try_read().is_ok(),
+                "lock is released between chunks"
+            );
+            match chunk.index {
+                2 => Err("disk full".into()),
+                _ => Ok(()),
+            }
+        })
+        .unwrap();
+        assert_eq!(report.total_chunks, 10);
+        assert_eq!(report.chunks.len(), 3);
+        assert_eq!(report.entities_created, 3000);
+        assert_eq!(report.failed_chunk, Some(3));
+        assert_eq!(report.error.as_deref(), Some("disk full"));
+        assert!(!report.is_complete());
+        assert_eq!(
+            load::load_graph_from_file(&path).unwrap().entities.len(),
+            3000
+        );
+
+        // Re-running the full batch only creates what is missing
+        let (entities, relations) = modules(5000);
+        let (report, _) = bulk_create(&kb, entities, relations, 1000, |_, _| Ok(())).unwrap();
+        assert!(report.is_complete());
+        assert_eq!(report.entities_created, 2000);
+        assert_eq!(report.relations_created, 4999);
+        let saved = load::load_graph_from_file(&path).unwrap();
+        assert_eq!((saved.entities.len(), saved.relations.len()), (5000, 4999));
+
+        assert!(bulk_create(&kb, Vec::new(), Vec::new(), 0, |_, _| Ok(())).is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_bulk_create_events_are_durable_per_chunk() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        let (entities, relations) = modules(250);
+
+        let (report, _) = bulk_create(&kb, entities, relations, 100, |chunk, _| {
+            if chunk.index == 3 {
+                return Err("client went away".into());
+            }
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(report.entities_created, 250);
+        assert_eq!(report.relations_created, 100);
+        assert_eq!(report.failed_chunk, Some(4));
+
+        let events = kb
+            .event_store
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .load_events()
+            .unwrap();
+        let (mut entities, mut relations) = (Vec::new(), Vec::new());
+        for event in &events {
+            EventStore::apply_event(&mut entities, &mut relations, event).unwrap();
+        }
+        assert_eq!((entities.len(), relations.len()), (250, 100));
+    }
+}
@@ -0,0 +1,134 @@
+//! Entities and events attributed to a user
+//!
+//! In collaborative (HTTP) mode every write records the acting user (see
+//! `user.rs`). [`entities_by_user`] filters the current graph on an
+//! entity's `createdBy` or `updatedBy`, so it works in both storage modes
+//! but only sees entities that still exist, and only their latest updater.
+//! With Event Sourcing, [`events_by_user`] reads the event log (archives
+//! included) for the fuller picture: every change the user made, deletions
+//! and relation changes too.
+
+use std::collections::BTreeMap;
+
+use crate::types::{Entity, Event, McpResult, RecentChange, UserField};
+
+use super::{acl, changes, temporal};
+use super::KnowledgeBase;
+
+/// Entities whose `field` is `user`, in graph order
+pub fn entities_by_user(kb: &KnowledgeBase, user: &str, field: UserField) -> Vec<Entity> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    graph
+        .entities
+        .iter()
+        .filter(|e| field.of(e) == user)
+        .cloned()
+        .collect()
+}
+
+/// Up to `limit` changes `user` made, newest first, read from the event log
+///
+/// Returns `None` without Event Sourcing.
+pub fn events_by_user(
+    kb: &KnowledgeBase,
+    user: &str,
+    limit: usize,
+) -> McpResult<Option<Vec<RecentChange>>> {
+    let (Some(event_store), Some(rotation)) = (&kb.event_store, &kb.log_rotation) else {
+        return Ok(None);
+    };
+
+    // Keyed by id: an event can be in both an archive and the active log
+    let mut events: BTreeMap<u64, Event> = BTreeMap::new();
+    for event in rotation.load_archived_events()? {
+        events.insert(event.event_id, event);
+    }
+    for event in event_store.lock().unwrap().load_events()? {
+        events.insert(event.event_id, event);
+    }
+
+    // Changes to entities the caller may not see are left out
+    let hidden = acl::hidden(&kb.graph.read().unwrap());
+    let changes = events
+        .into_values()
+        .rev()
+        .filter(|event| event.user == user)
+        .filter(|event| !hidden.iter().any(|name| temporal::event_touches(event, name)))
+        .take(limit)
+        .map(|event| RecentChange {
+            timestamp: event.timestamp.max(0) as u64,
+            ..changes::describe(event.event_type, &event.data, event.user)
+        })
+        .collect();
+    Ok(Some(changes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EventType, Observation};
+
+    #[test]
+    fn test_by_user_in_both_modes() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = format!("target/test_by_user_{}.jsonl", std::process::id());
+        let legacy = KnowledgeBase::for_testing(path.clone(), "server".to_string());
+        let sourced =
+            KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "server".to_string());
+
+        for kb in [&legacy, &sourced] {
+            KnowledgeBase::run_as(Some("alice"), || {
+                kb.create_entities(vec![
+                    Entity::new("Auth".to_string(), "Module".to_string()),
+                    Entity::new("Billing".to_string(), "Module".to_string()),
+                ])
+            })
+            .unwrap();
+            KnowledgeBase::run_as(Some("bob"), || {
+                kb.add_observations(vec![Observation {
+                    entity_name: "Auth".to_string(),
+                    contents: vec!["Owner: bob".to_string()],
+                }])
+            })
+            .unwrap();
+            KnowledgeBase::run_as(Some("alice"), || {
+                kb.delete_entities(vec!["Billing".to_string()])
+            })
+            .unwrap();
+
+            let names = |user: &str, field: UserField| -> Vec<String> {
+                entities_by_user(kb, user, field)
+                    .into_iter()
+                    .map(|e| e.name)
+                    .collect()
+            };
+            assert_eq!(names("alice", UserField::CreatedBy), vec!["Auth"]);
+            assert!(names("alice", UserField::UpdatedBy).is_empty());
+            assert_eq!(names("bob", UserField::UpdatedBy), vec!["Auth"]);
+        }
+
+        assert!(events_by_user(&legacy, "alice", 10).unwrap().is_none());
+        let changes = events_by_user(&sourced, "alice", 10).unwrap().unwrap();
+        let summary: Vec<(EventType, &str)> = changes
+            .iter()
+            .map(|c| (c.change_type, c.subject.as_str()))
+            .collect();
+        // The deletion is only visible in the event log
+        assert_eq!(
+            summary,
+            vec![
+                (EventType::EntityDeleted, "Billing"),
+                (EventType::EntityCreated, "Billing"),
+                (EventType::EntityCreated, "Auth"),
+            ]
+        );
+        assert!(changes.iter().all(|c| c.user == "alice" && c.timestamp > 0));
+        assert_eq!(
+            events_by_user(&sourced, "alice", 1).unwrap().unwrap().len(),
+            1
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
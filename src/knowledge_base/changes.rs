@@ -0,0 +1,167 @@
+//! In-memory buffer of recent changes
+//!
+//! Without Event Sourcing nothing records who changed what. Every committed
+//! mutation is therefore also kept in a bounded ring buffer (type, subject,
+//! user, time) for debugging. The buffer is never persisted and starts empty
+//! on restart; it is not a substitute for the event log.
+//! `MEMORY_RECENT_CHANGES` sets how many changes are kept (default 256,
+//! `0` disables it).
+
+use std::collections::VecDeque;
+use std::env;
+use std::sync::Mutex;
+
+use serde_json::Value;
+
+use crate::types::{EventType, RecentChange};
+use crate::utils::time::current_timestamp;
+
+use super::{acl, KnowledgeBase};
+
+/// Changes kept when `MEMORY_RECENT_CHANGES` is not set
+const DEFAULT_CAPACITY: usize = 256;
+
+/// Bounded buffer of the latest committed changes, oldest first
+#[derive(Debug)]
+pub(crate) struct ChangeLog {
+    capacity: usize,
+    changes: Mutex<VecDeque<RecentChange>>,
+}
+
+impl ChangeLog {
+    /// Create a buffer keeping the last `capacity` changes (0 = disabled)
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            changes: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Create a buffer sized by `MEMORY_RECENT_CHANGES`
+    pub(crate) fn from_env() -> Self {
+        let capacity = env::var("MEMORY_RECENT_CHANGES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_CAPACITY);
+        Self::new(capacity)
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Append committed changes, dropping the oldest beyond capacity
+    pub(crate) fn record(&self, committed: Vec<RecentChange>) {
+        if committed.is_empty() || !self.is_enabled() {
+            return;
+        }
+        let mut changes = self.changes.lock().unwrap();
+        changes.extend(committed);
+        let excess = changes.len().saturating_sub(self.capacity);
+        changes.drain(..excess);
+    }
+}
+
+/// Describe a queued event as a change by `user`
+pub(super) fn describe(event_type: EventType, data: &Value, user: String) -> RecentChange {
+    let text = |key: &str| data.get(key).and_then(Value::as_str).unwrap_or_default();
+    let subject = match data.get("name").or_else(|| data.get("entity")) {
+        Some(name) => name.as_str().unwrap_or_default().to_string(),
+        None => format!(
+            "{} -[{}]-> {}",
+            text("from"),
+            text("relation_type"),
+            text("to")
+        ),
+    };
+    RecentChange {
+        change_type: event_type,
+        subject,
+        user,
+        timestamp: current_timestamp(),
+    }
+}
+
+/// Whether `change` is to the entity `name` or a relation touching it
+fn names(change: &RecentChange, name: &str) -> bool {
+    change.subject == name
+        || change.subject.starts_with(&format!("{} -[", name))
+        || change.subject.ends_with(&format!("]-> {}", name))
+}
+
+/// Latest changes, newest first, optionally only those naming `subject`
+///
+/// A relation change matches either of its endpoints. Changes to entities the
+/// current caller may not see are left out.
+pub fn recent_changes(
+    kb: &KnowledgeBase,
+    limit: usize,
+    subject: Option<&str>,
+) -> Vec<RecentChange> {
+    let hidden = acl::hidden(&kb.graph.read().unwrap());
+    let changes = kb.recent_changes.changes.lock().unwrap();
+    changes
+        .iter()
+        .rev()
+        .filter(|change| subject.is_none_or(|subject| names(change, subject)))
+        .filter(|change| !hidden.iter().any(|name| names(change, name)))
+        .take(limit)
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Entity, Relation};
+
+    #[test]
+    fn test_recent_changes_are_bounded() {
+        let path = format!("target/test_recent_changes_{}.jsonl", std::process::id());
+        let mut kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        kb.recent_changes = ChangeLog::new(3);
+        kb.create_entities(vec![
+            Entity::new("Auth".to_string(), "Module".to_string()),
+            Entity::new("Billing".to_string(), "Module".to_string()),
+        ])
+        .unwrap();
+        kb.create_relations(vec![Relation::new(
+            "Billing".to_string(),
+            "Auth".to_string(),
+            "depends_on".to_string(),
+        )])
+        .unwrap();
+        KnowledgeBase::run_as(Some("alice"), || {
+            kb.delete_entities(vec!["Billing".to_string()])
+        })
+        .unwrap();
+
+        let changes = recent_changes(&kb, 10, None);
+        let summary: Vec<(EventType, &str)> = changes
+            .iter()
+            .map(|c| (c.change_type, c.subject.as_str()))
+            .collect();
+        // The oldest change (creating Auth) fell out of the buffer
+        assert_eq!(summary.len(), 3);
+        assert_eq!(summary[0], (EventType::EntityDeleted, "Billing"));
+        assert_eq!(changes[0].user, "alice");
+        assert_eq!(
+            summary[1],
+            (EventType::RelationCreated, "Billing -[depends_on]-> Auth")
+        );
+        assert_eq!(summary[2], (EventType::EntityCreated, "Billing"));
+
+        assert_eq!(recent_changes(&kb, 10, Some("Auth")).len(), 1);
+        assert_eq!(recent_changes(&kb, 1, None).len(), 1);
+
+        kb.recent_changes = ChangeLog::new(0);
+        kb.create_entities(vec![Entity::new(
+            "Search".to_string(),
+            "Module".to_string(),
+        )])
+        .unwrap();
+        assert!(recent_changes(&kb, 10, None).is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
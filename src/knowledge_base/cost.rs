@@ -0,0 +1,212 @@
+//! Query cost estimates and the visit budget
+//!
+//! `traverse` and `infer` can touch most of a large graph, and callers cannot
+//! tell beforehand. Their cost is estimated from the graph size and the
+//! requested depth and breadth, without walking the graph:
+//! - `traverse`: each hop multiplies the frontier by the average degree of
+//!   the step's relation type, the frontier being capped at `maxResults`
+//! - `infer`: each rule walks outgoing relations up to `maxDepth` hops,
+//!   visiting every entity at most once
+//!
+//! With `MEMORY_MAX_QUERY_VISITS` set, queries estimated above it are refused
+//! before they run. Cached inference results cost nothing and are always
+//! returned.
+
+use std::env;
+
+use crate::types::{KnowledgeGraph, McpResult, PathStep, QueryCost};
+use crate::validation::RelationAliases;
+
+use super::inference::InferenceEngine;
+use super::KnowledgeBase;
+
+/// Maximum estimated node visits per query (`None` = unlimited)
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct QueryBudget {
+    max_visits: Option<u64>,
+}
+
+impl QueryBudget {
+    /// Read the budget from `MEMORY_MAX_QUERY_VISITS` (unset or 0 = unlimited)
+    pub(crate) fn from_env() -> Self {
+        Self {
+            max_visits: env::var("MEMORY_MAX_QUERY_VISITS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&v| v > 0),
+        }
+    }
+
+    fn cost(&self, operation: &str, estimated_visits: u64) -> QueryCost {
+        QueryCost {
+            operation: operation.to_string(),
+            estimated_visits,
+            budget: self.max_visits,
+            allowed: self.max_visits.is_none_or(|max| estimated_visits <= max),
+        }
+    }
+}
+
+/// Estimate the cost of `traverse` with this path and result limit
+pub fn estimate_traverse(kb: &KnowledgeBase, path: &[PathStep], max_results: usize) -> QueryCost {
+    let graph = kb.graph.read().unwrap();
+    let visits = traverse_visits(&graph, &kb.relation_aliases, path, max_results);
+    kb.query_budget.cost("traverse", visits)
+}
+
+/// Estimate the cost of `infer` with this depth and rule selection
+pub fn estimate_infer(
+    kb: &KnowledgeBase,
+    max_depth: usize,
+    rules: Option<&[String]>,
+) -> McpResult<QueryCost> {
+    let mut engine = InferenceEngine::with_max_depth(max_depth);
+    if let Some(rules) = rules {
+        engine.select_rules(rules)?;
+    }
+    let graph = kb.graph.read().unwrap();
+    let visits = infer_visits(&graph, max_depth, &engine);
+    Ok(kb.query_budget.cost("infer", visits))
+}
+
+/// Refuse a `traverse` estimated over the budget
+pub(super) fn check_traverse(
+    kb: &KnowledgeBase,
+    graph: &KnowledgeGraph,
+    path: &[PathStep],
+    max_results: usize,
+) -> McpResult<()> {
+    let visits = traverse_visits(graph, &kb.relation_aliases, path, max_results);
+    enforce(
+        &kb.query_budget.cost("traverse", visits),
+        "lower maxResults or maxHops, or split the path into several queries",
+    )
+}
+
+/// Refuse an `infer` estimated over the budget
+pub(super) fn check_infer(
+    kb: &KnowledgeBase,
+    graph: &KnowledgeGraph,
+    max_depth: usize,
+    engine: &InferenceEngine,
+) -> McpResult<()> {
+    let visits = infer_visits(graph, max_depth, engine);
+    enforce(
+        &kb.query_budget.cost("infer", visits),
+        "lower maxDepth or select fewer rules",
+    )
+}
+
+fn enforce(cost: &QueryCost, suggestion: &str) -> McpResult<()> {
+    match cost.budget {
+        Some(budget) if !cost.allowed => Err(format!(
+            "{} would visit about {} nodes, over the budget of {} (MEMORY_MAX_QUERY_VISITS); {}",
+            cost.operation, cost.estimated_visits, budget, suggestion
+        )
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+fn traverse_visits(
+    graph: &KnowledgeGraph,
+    aliases: &RelationAliases,
+    path: &[PathStep],
+    max_results: usize,
+) -> u64 {
+    let entities = graph.entities.len().max(1) as f64;
+    let cap = max_results.max(1) as f64;
+    let mut frontier = 1.0_f64;
+    let mut visits = 0.0;
+
+    for step in path {
+        let step_type = aliases.canonical(&step.relation_type);
+        let matching = graph
+            .relations
+            .iter()
+            .filter(|r| aliases.canonical(&r.relation_type) == step_type)
+            .count() as f64;
+        let sides = if step.direction == "both" { 2.0 } else { 1.0 };
+        let degree = matching * sides / entities;
+
+        let (min_hops, max_hops) = step.hop_range();
+        let mut reached = if min_hops == 0 { frontier } else { 0.0 };
+        let mut hop_frontier = frontier;
+        for _ in 0..max_hops {
+            hop_frontier *= degree;
+            visits += hop_frontier;
+            reached += hop_frontier;
+            if hop_frontier < 1.0 {
+                break;
+            }
+            hop_frontier = hop_frontier.min(cap);
+        }
+        frontier = reached.min(cap);
+    }
+
+    visits.ceil() as u64
+}
+
+fn infer_visits(graph: &KnowledgeGraph, max_depth: usize, engine: &InferenceEngine) -> u64 {
+    let rule_count = engine.rules().filter(|(_, _, enabled)| *enabled).count();
+    let entities = graph.entities.len() as f64;
+    let degree = graph.relations.len() as f64 / entities.max(1.0);
+    let mut per_rule = 0.0;
+    let mut level = 1.0;
+    for _ in 0..max_depth {
+        level *= degree;
+        per_rule += level;
+        if per_rule >= entities {
+            break;
+        }
+    }
+
+    (per_rule.min(entities) * rule_count as f64).ceil() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Entity, Relation};
+
+    #[test]
+    fn test_budget_refuses_broad_queries() {
+        let path = format!("target/test_query_cost_{}.jsonl", std::process::id());
+        let mut kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        let names: Vec<String> = (0..20).map(|i| format!("M{}", i)).collect();
+        kb.create_entities(
+            names
+                .iter()
+                .map(|n| Entity::new(n.clone(), "Module".to_string()))
+                .collect(),
+        )
+        .unwrap();
+        // Every module depends on the next three
+        let relations = (0..20)
+            .flat_map(|i| (1..=3).map(move |j| (i, (i + j) % 20)))
+            .map(|(i, j)| {
+                Relation::new(names[i].clone(), names[j].clone(), "depends_on".to_string())
+            })
+            .collect();
+        kb.create_relations(relations).unwrap();
+
+        let step = PathStep::new("depends_on".to_string(), "out".to_string()).with_hops(1, 4);
+        let narrow = estimate_traverse(&kb, std::slice::from_ref(&step), 5);
+        let broad = estimate_traverse(&kb, std::slice::from_ref(&step), 500);
+        assert_eq!(narrow.estimated_visits, 3 + 9 + 15 + 15);
+        assert!(broad.estimated_visits > narrow.estimated_visits);
+        assert!(narrow.allowed && narrow.budget.is_none());
+        assert_eq!(estimate_infer(&kb, 3, None).unwrap().estimated_visits, 20);
+        assert!(estimate_infer(&kb, 3, Some(&["NoSuchRule".to_string()])).is_err());
+
+        kb.query_budget = QueryBudget {
+            max_visits: Some(100),
+        };
+        assert!(kb.traverse("M0", vec![step.clone()], 5).is_ok());
+        let err = kb.traverse("M0", vec![step], 500).unwrap_err().to_string();
+        assert!(err.contains("over the budget of 100"), "{}", err);
+        assert!(kb.infer("M0", 0.5, 3, None).is_ok());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,73 +1,359 @@
 //! CRUD operations for the knowledge base
+//!
+//! Each mutation is split into an `apply_*` step that edits a graph and queues
+//! its side effects, and a commit step that emits events, broadcasts and
+//! persists. Transactions reuse the `apply_*` steps on a cloned graph.
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::atomic::Ordering;
 
-use serde_json::json;
+use serde_json::{json, Value};
 
-use crate::api::websocket::ws_helpers;
-use crate::types::{Entity, EventType, McpResult, Observation, ObservationDeletion, Relation};
+use crate::api::websocket::events::GraphEvent;
+use crate::api::websocket::{broadcast_event, has_inferred_subscribers, ws_helpers};
+use crate::types::{
+    Entity, EventType, KnowledgeGraph, McpResult, Observation, ObservationDeletion,
+    ObservationReplacement, ObservationReplacementResult, RecentChange, Relation,
+};
+use crate::utils::entity_id;
 use crate::utils::time::current_timestamp;
+use crate::validation::{CardinalityMode, ValidationWarning, ValidationWarningKind};
 
+use super::acl;
+use super::changes;
+use super::inference::InferenceEngine;
+use super::snapshot;
 use super::KnowledgeBase;
 
+/// Minimum confidence for inferred relations pushed to subscribers
+const BROADCAST_MIN_CONFIDENCE: f32 = 0.5;
+
+/// Side effects of a mutation, flushed only once the graph change is committed
+#[derive(Default)]
+pub(super) struct PendingEffects {
+    events: Vec<(EventType, Value)>,
+    broadcasts: Vec<GraphEvent>,
+    warnings: Vec<ValidationWarning>,
+    changes: Vec<RecentChange>,
+}
+
+impl PendingEffects {
+    /// Queue an event (ignored unless Event Sourcing is enabled)
+    ///
+    /// Also queues the change for the recent changes buffer.
+    pub(super) fn event(&mut self, kb: &KnowledgeBase, event_type: EventType, data: Value) {
+        if kb.recent_changes.is_enabled() {
+            self.changes
+                .push(changes::describe(event_type, &data, kb.acting_user()));
+        }
+        if kb.event_sourcing_enabled {
+            self.events.push((event_type, data));
+        }
+    }
+
+    /// Queue a WebSocket broadcast
+    pub(super) fn broadcast(&mut self, event: GraphEvent) {
+        self.broadcasts.push(event);
+    }
+
+    /// Record a warning for the caller
+    fn warn(&mut self, warning: ValidationWarning) {
+        self.warnings.push(warning);
+    }
+
+    /// Warnings recorded so far
+    pub(super) fn warnings(&self) -> &[ValidationWarning] {
+        &self.warnings
+    }
+
+    /// Run an observation through the configured limits
+    ///
+    /// Returns the content to store, or `None` if it was rejected.
+    fn admit(
+        &mut self,
+        kb: &KnowledgeBase,
+        entity_name: &str,
+        current_count: usize,
+        content: &str,
+    ) -> Option<String> {
+        let (admitted, warning) = kb
+            .observation_limits
+            .admit(entity_name, current_count, content);
+        self.warnings.extend(warning);
+        admitted
+    }
+
+    /// Append queued events to the store
+    fn append_events(&mut self, kb: &KnowledgeBase) -> McpResult<()> {
+        for (event_type, data) in self.events.drain(..) {
+            kb.emit_event(event_type, data)?;
+        }
+        Ok(())
+    }
+
+    /// Notify WebSocket clients of the committed change
+    ///
+    /// Returns the entities touched by the mutation if a client subscribed to
+    /// inferred relations, for `broadcast_inferences` once the lock is released.
+    fn notify(self) -> BTreeSet<String> {
+        let targets: BTreeSet<String> = if has_inferred_subscribers() {
+            self.broadcasts
+                .iter()
+                .flat_map(|e| e.affected_entities())
+                .map(String::from)
+                .collect()
+        } else {
+            BTreeSet::new()
+        };
+
+        for event in self.broadcasts {
+            broadcast_event(event);
+        }
+        targets
+    }
+}
+
+/// Recompute and broadcast inferred relations for `targets`
+///
+/// Runs under the read lock after the mutation's write lock is released, so
+/// inference never holds up other writers.
+fn broadcast_inferences(kb: &KnowledgeBase, targets: BTreeSet<String>) {
+    if targets.is_empty() {
+        return;
+    }
+    let graph = kb.graph.read().unwrap();
+    let engine = InferenceEngine::new();
+    for target in targets {
+        if graph.entities.iter().any(|e| e.name == target) {
+            let (relations, _) = engine.infer(&graph, &target, BROADCAST_MIN_CONFIDENCE);
+            ws_helpers::inferred(&target, relations);
+        }
+    }
+}
+
+/// Run a mutation under the write lock and commit it
+///
+/// The write lock is held while the graph is changed and its events are
+/// appended, so readers never see a graph state without its backing events or
+/// the other way around. If `mutate` returns an error nothing is emitted or
+/// persisted; it is up to `mutate` to leave the graph untouched in that case.
+/// If appending an event fails, the graph is rebuilt from the log before the
+/// lock is released.
+pub(super) fn commit<T>(
+    kb: &KnowledgeBase,
+    mutate: impl FnOnce(&mut KnowledgeGraph, &mut PendingEffects) -> McpResult<T>,
+) -> McpResult<T> {
+    commit_with_warnings(kb, mutate).map(|(result, _)| result)
+}
+
+/// Same as `commit`, also returning warnings raised while applying the mutation
+pub(super) fn commit_with_warnings<T>(
+    kb: &KnowledgeBase,
+    mutate: impl FnOnce(&mut KnowledgeGraph, &mut PendingEffects) -> McpResult<T>,
+) -> McpResult<(T, Vec<ValidationWarning>)> {
+    let mut graph = kb.graph.write().unwrap();
+    // Invalidates cached reads (e.g. inference) even if the mutation fails
+    kb.graph_version.fetch_add(1, Ordering::Release);
+    let mut pending = PendingEffects::default();
+
+    let result = mutate(&mut graph, &mut pending)?;
+    let warnings = std::mem::take(&mut pending.warnings);
+    if let Err(e) = pending.append_events(kb) {
+        kb.rebuild_from_log(&mut graph);
+        return Err(e);
+    }
+    // Scratch graphs are private to their session and never written out
+    if kb.scratch {
+        return Ok((result, warnings));
+    }
+    let changes = std::mem::take(&mut pending.changes);
+    let inference_targets = pending.notify();
+
+    // Persist based on mode
+    if !kb.event_sourcing_enabled {
+        kb.persist_to_file(&graph)?;
+    }
+    kb.recent_changes.record(changes);
+
+    drop(graph);
+    broadcast_inferences(kb, inference_targets);
+    snapshot::request_snapshot(kb)?;
+
+    Ok((result, warnings))
+}
+
 /// Create new entities (thread-safe: holds write lock during entire operation)
 pub fn create_entities(kb: &KnowledgeBase, entities: Vec<Entity>) -> McpResult<Vec<Entity>> {
-    let mut graph = kb.graph.write().unwrap();
-    let existing_names: HashSet<String> = graph.entities.iter().map(|e| e.name.clone()).collect();
+    create_entities_with_warnings(kb, entities).map(|(created, _)| created)
+}
+
+/// Create new entities, reporting observations truncated or rejected by limits
+pub fn create_entities_with_warnings(
+    kb: &KnowledgeBase,
+    entities: Vec<Entity>,
+) -> McpResult<(Vec<Entity>, Vec<ValidationWarning>)> {
+    commit_with_warnings(kb, |graph, pending| {
+        Ok(apply_create_entities(kb, graph, entities, pending))
+    })
+}
+
+pub(super) fn apply_create_entities(
+    kb: &KnowledgeBase,
+    graph: &mut KnowledgeGraph,
+    entities: Vec<Entity>,
+    pending: &mut PendingEffects,
+) -> Vec<Entity> {
+    let mut existing_names: HashSet<String> = graph.entities.iter().map(|e| e.name.clone()).collect();
+    let mut existing_ids: HashSet<String> = graph
+        .entities
+        .iter()
+        .filter(|e| !e.id.is_empty())
+        .map(|e| e.id.clone())
+        .collect();
     let now = current_timestamp();
 
     let mut created = Vec::new();
     for mut entity in entities {
+        let (name, warning) = kb.entity_names.admit(&entity.name);
+        if let Some(warning) = warning {
+            pending.warn(warning);
+        }
+        let Some(name) = name else {
+            continue;
+        };
+        entity.name = name;
+
         if !existing_names.contains(&entity.name) {
+            // Keep a client-supplied id only if no other entity uses it
+            if entity.id.is_empty() || existing_ids.contains(&entity.id) {
+                entity.id = (0..)
+                    .map(|salt| entity_id(&entity.name, &entity.entity_type, now, salt))
+                    .find(|id| !existing_ids.contains(id))
+                    .expect("id space exhausted");
+            }
+            existing_ids.insert(entity.id.clone());
+
             // Auto-fill user info if not provided
             if entity.created_by.is_empty() || entity.created_by == "system" {
-                entity.created_by = kb.current_user.clone();
+                entity.created_by = kb.acting_user();
             }
             if entity.updated_by.is_empty() || entity.updated_by == "system" {
-                entity.updated_by = kb.current_user.clone();
+                entity.updated_by = kb.acting_user();
             }
             entity.created_at = now;
             entity.updated_at = now;
 
-            // Emit event if Event Sourcing is enabled
-            if kb.event_sourcing_enabled {
-                kb.emit_event(
-                    EventType::EntityCreated,
-                    json!({
-                        "name": entity.name,
-                        "entity_type": entity.entity_type,
-                        "observations": entity.observations
-                    }),
-                )?;
+            for warning in kb.entity_templates.apply(
+                &entity.name,
+                &entity.entity_type,
+                &mut entity.observations,
+            ) {
+                pending.warn(warning);
             }
 
-            // Broadcast to WebSocket clients
-            ws_helpers::entity_created(&entity, Some(kb.current_user.clone()));
+            let mut observations = Vec::with_capacity(entity.observations.len());
+            for content in &entity.observations {
+                if let Some(content) = pending.admit(kb, &entity.name, observations.len(), content)
+                {
+                    observations.push(content);
+                }
+            }
+            entity.observations = observations;
+
+            let mut data = json!({
+                "name": entity.name,
+                "entity_type": entity.entity_type,
+                "observations": entity.observations,
+                "id": entity.id
+            });
+            if entity.protected {
+                data["protected"] = json!(true);
+            }
+            if !entity.acl.is_empty() {
+                data["acl"] = json!(entity.acl);
+            }
+            pending.event(kb, EventType::EntityCreated, data);
+            pending.broadcast(GraphEvent::EntityCreated {
+                payload: entity.clone(),
+                user: Some(kb.acting_user()),
+            });
 
+            existing_names.insert(entity.name.clone());
             created.push(entity.clone());
             graph.entities.push(entity);
         }
     }
 
-    // Persist based on mode
-    if !kb.event_sourcing_enabled {
-        kb.persist_to_file(&graph)?;
-    }
-
-    drop(graph);
-    kb.maybe_create_snapshot()?;
-
-    Ok(created)
+    created
 }
 
 /// Create new relations (thread-safe: holds write lock during entire operation)
+///
+/// Self-loops (`from == to`) are allowed; see `traversal.rs` for how they are followed.
 pub fn create_relations(kb: &KnowledgeBase, relations: Vec<Relation>) -> McpResult<Vec<Relation>> {
-    let mut graph = kb.graph.write().unwrap();
+    create_relations_with_warnings(kb, relations).map(|(created, _)| created)
+}
+
+/// Create new relations, reporting those that break cardinality constraints
+pub fn create_relations_with_warnings(
+    kb: &KnowledgeBase,
+    relations: Vec<Relation>,
+) -> McpResult<(Vec<Relation>, Vec<ValidationWarning>)> {
+    let mut relations = relations;
+    commit_with_warnings(kb, |graph, pending| {
+        resolve_relation_endpoints(kb, graph, &mut relations);
+        acl::check_writable(graph, relation_endpoints(&relations))?;
+        Ok(apply_create_relations(kb, graph, relations, pending))
+    })
+}
+
+/// Rewrite endpoints given by id or in unnormalized form to entity names
+///
+/// Relations are always stored by name, and ACL checks must see the entity
+/// an endpoint actually resolves to. Endpoints that match nothing are left
+/// untouched.
+pub(super) fn resolve_relation_endpoints(
+    kb: &KnowledgeBase,
+    graph: &KnowledgeGraph,
+    relations: &mut [Relation],
+) {
+    let entity_names: HashSet<&str> = graph.entities.iter().map(|e| e.name.as_str()).collect();
+    let names_by_id: HashMap<&str, &str> = graph
+        .entities
+        .iter()
+        .filter(|e| !e.id.is_empty())
+        .map(|e| (e.id.as_str(), e.name.as_str()))
+        .collect();
+
+    for relation in relations {
+        for endpoint in [&mut relation.from, &mut relation.to] {
+            if entity_names.contains(endpoint.as_str()) {
+                continue;
+            }
+            if let Some(name) = names_by_id.get(endpoint.as_str()) {
+                *endpoint = name.to_string();
+            } else {
+                let normalized = kb.entity_names.normalize(endpoint).into_owned();
+                if entity_names.contains(normalized.as_str()) {
+                    *endpoint = normalized;
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn apply_create_relations(
+    kb: &KnowledgeBase,
+    graph: &mut KnowledgeGraph,
+    mut relations: Vec<Relation>,
+    pending: &mut PendingEffects,
+) -> Vec<Relation> {
+    resolve_relation_endpoints(kb, graph, &mut relations);
     let entity_names: HashSet<String> = graph.entities.iter().map(|e| e.name.clone()).collect();
     let now = current_timestamp();
 
     // Use tuple of owned Strings to avoid borrow issues
-    let existing_relations: HashSet<(String, String, String)> = graph
+    let mut existing_relations: HashSet<(String, String, String)> = graph
         .relations
         .iter()
         .map(|r| (r.from.clone(), r.to.clone(), r.relation_type.clone()))
@@ -75,35 +361,51 @@ pub fn create_relations(kb: &KnowledgeBase, relations: Vec<Relation>) -> McpResu
 
     let mut created = Vec::new();
     for mut relation in relations {
+        if !kb.relation_aliases.is_empty() {
+            relation.relation_type = kb.relation_aliases.normalize(&relation.relation_type);
+        }
         if entity_names.contains(&relation.from) && entity_names.contains(&relation.to) {
             let key = (
                 relation.from.clone(),
                 relation.to.clone(),
                 relation.relation_type.clone(),
             );
-            if !existing_relations.contains(&key) {
+            // `insert` also skips duplicates within the same batch
+            if existing_relations.insert(key) {
+                if !admit_functional(
+                    kb,
+                    graph,
+                    &relation,
+                    &mut existing_relations,
+                    &mut created,
+                    pending,
+                ) {
+                    continue;
+                }
+
                 // Auto-fill user info if not provided
                 if relation.created_by.is_empty() || relation.created_by == "system" {
-                    relation.created_by = kb.current_user.clone();
+                    relation.created_by = kb.acting_user();
                 }
                 relation.created_at = now;
+                relation.updated_at = now;
 
-                // Emit event if Event Sourcing is enabled
-                if kb.event_sourcing_enabled {
-                    kb.emit_event(
-                        EventType::RelationCreated,
-                        json!({
-                            "from": relation.from,
-                            "to": relation.to,
-                            "relation_type": relation.relation_type,
-                            "valid_from": relation.valid_from,
-                            "valid_to": relation.valid_to
-                        }),
-                    )?;
+                let mut data = json!({
+                    "from": relation.from,
+                    "to": relation.to,
+                    "relation_type": relation.relation_type,
+                    "valid_from": relation.valid_from,
+                    "valid_to": relation.valid_to,
+                    "created_at": relation.created_at
+                });
+                if !relation.properties.is_empty() {
+                    data["properties"] = json!(relation.properties);
                 }
-
-                // Broadcast to WebSocket clients
-                ws_helpers::relation_created(&relation, Some(kb.current_user.clone()));
+                pending.event(kb, EventType::RelationCreated, data);
+                pending.broadcast(GraphEvent::RelationCreated {
+                    payload: relation.clone(),
+                    user: Some(kb.acting_user()),
+                });
 
                 created.push(relation.clone());
                 graph.relations.push(relation);
@@ -111,15 +413,86 @@ pub fn create_relations(kb: &KnowledgeBase, relations: Vec<Relation>) -> McpResu
         }
     }
 
-    // Persist based on mode
-    if !kb.event_sourcing_enabled {
-        kb.persist_to_file(&graph)?;
+    created
+}
+
+/// Enforce a functional constraint on `relation` before it is created
+///
+/// Returns false if the relation must be dropped. In replace mode the
+/// conflicting relations are deleted, including any created earlier in the
+/// same batch.
+fn admit_functional(
+    kb: &KnowledgeBase,
+    graph: &mut KnowledgeGraph,
+    relation: &Relation,
+    existing_relations: &mut HashSet<(String, String, String)>,
+    created: &mut Vec<Relation>,
+    pending: &mut PendingEffects,
+) -> bool {
+    let Some(mode) = kb.cardinality.functional_mode(&relation.relation_type) else {
+        return true;
+    };
+
+    let conflicting: Vec<Relation> = graph
+        .relations
+        .iter()
+        .filter(|r| r.from == relation.from && r.relation_type == relation.relation_type)
+        .cloned()
+        .collect();
+    if conflicting.is_empty() {
+        return true;
     }
 
-    drop(graph);
-    kb.maybe_create_snapshot()?;
+    let subject = format!(
+        "{} -[{}]-> {}",
+        relation.from, relation.relation_type, relation.to
+    );
+    let current = conflicting
+        .iter()
+        .map(|r| r.to.as_str())
+        .collect::<Vec<_>>()
+        .join("', '");
 
-    Ok(created)
+    match mode {
+        CardinalityMode::Reject => {
+            existing_relations.remove(&(
+                relation.from.clone(),
+                relation.to.clone(),
+                relation.relation_type.clone(),
+            ));
+            pending.warn(ValidationWarning::new(
+                ValidationWarningKind::CardinalityViolation,
+                subject,
+                format!(
+                    "'{}' allows one relation per entity and '{}' already points to '{}'; relation rejected",
+                    relation.relation_type, relation.from, current
+                ),
+            ));
+            false
+        }
+        CardinalityMode::Replace => {
+            pending.warn(ValidationWarning::new(
+                ValidationWarningKind::CardinalityViolation,
+                subject,
+                format!(
+                    "'{}' allows one relation per entity; replaced relation to '{}'",
+                    relation.relation_type, current
+                ),
+            ));
+            for old in &conflicting {
+                existing_relations.remove(&(
+                    old.from.clone(),
+                    old.to.clone(),
+                    old.relation_type.clone(),
+                ));
+            }
+            created.retain(|c| {
+                !(c.from == relation.from && c.relation_type == relation.relation_type)
+            });
+            apply_delete_relations(kb, graph, conflicting, pending);
+            true
+        }
+    }
 }
 
 /// Add observations to entities (thread-safe: holds write lock during entire operation)
@@ -127,79 +500,319 @@ pub fn add_observations(
     kb: &KnowledgeBase,
     observations: Vec<Observation>,
 ) -> McpResult<Vec<Observation>> {
-    let mut graph = kb.graph.write().unwrap();
+    add_observations_with_warnings(kb, observations).map(|(added, _)| added)
+}
+
+/// Add observations, reporting those truncated or rejected by limits
+pub fn add_observations_with_warnings(
+    kb: &KnowledgeBase,
+    observations: Vec<Observation>,
+) -> McpResult<(Vec<Observation>, Vec<ValidationWarning>)> {
+    commit_with_warnings(kb, |graph, pending| {
+        acl::check_writable(graph, observations.iter().map(|o| o.entity_name.as_str()))?;
+        Ok(apply_add_observations(kb, graph, observations, pending))
+    })
+}
+
+pub(super) fn apply_add_observations(
+    kb: &KnowledgeBase,
+    graph: &mut KnowledgeGraph,
+    observations: Vec<Observation>,
+    pending: &mut PendingEffects,
+) -> Vec<Observation> {
     let mut added = Vec::new();
     let now = current_timestamp();
 
     for obs in observations {
-        if let Some(entity) = graph.entities.iter_mut().find(|e| e.name == obs.entity_name) {
-            let existing: HashSet<String> = entity.observations.iter().cloned().collect();
-            let mut new_contents = Vec::new();
-
-            for content in &obs.contents {
-                if !existing.contains(content) {
-                    // Emit event if Event Sourcing is enabled
-                    if kb.event_sourcing_enabled {
-                        kb.emit_event(
-                            EventType::ObservationAdded,
-                            json!({
-                                "entity": obs.entity_name,
-                                "observation": content
-                            }),
-                        )?;
+        if let Some(entity) = graph
+            .entities
+            .iter_mut()
+            .find(|e| e.name == obs.entity_name)
+        {
+            let new_contents = add_to_entity(kb, entity, &obs.contents, now, pending);
+            if !new_contents.is_empty() {
+                added.push(Observation {
+                    entity_name: obs.entity_name,
+                    contents: new_contents,
+                });
+            }
+        }
+    }
+
+    added
+}
+
+/// Add observations to many entities in one pass (thread-safe: holds write lock during entire operation)
+///
+/// Same rules as [`add_observations_with_warnings`] (dedup, limits, one event
+/// per added observation, one persist), but the input is grouped by entity
+/// and every entity is found through a name index built once, instead of a
+/// linear search per item. Items for the same entity are merged into one
+/// result, in first-seen order.
+pub fn add_observations_batch(
+    kb: &KnowledgeBase,
+    observations: Vec<Observation>,
+) -> McpResult<(Vec<Observation>, Vec<ValidationWarning>)> {
+    commit_with_warnings(kb, |graph, pending| {
+        acl::check_writable(graph, observations.iter().map(|o| o.entity_name.as_str()))?;
+        Ok(apply_add_observations_batch(kb, graph, observations, pending))
+    })
+}
+
+pub(super) fn apply_add_observations_batch(
+    kb: &KnowledgeBase,
+    graph: &mut KnowledgeGraph,
+    observations: Vec<Observation>,
+    pending: &mut PendingEffects,
+) -> Vec<Observation> {
+    let mut grouped: Vec<Observation> = Vec::new();
+    let mut group_of: HashMap<String, usize> = HashMap::new();
+    for obs in observations {
+        match group_of.get(&obs.entity_name) {
+            Some(&group) => grouped[group].contents.extend(obs.contents),
+            None => {
+                group_of.insert(obs.entity_name.clone(), grouped.len());
+                grouped.push(obs);
+            }
+        }
+    }
+
+    let index: HashMap<&str, usize> = graph
+        .entities
+        .iter()
+        .enumerate()
+        .map(|(i, e)| (e.name.as_str(), i))
+        .rev()
+        .collect();
+    let targets: Vec<Option<usize>> = grouped
+        .iter()
+        .map(|obs| index.get(obs.entity_name.as_str()).copied())
+        .collect();
+
+    let mut added = Vec::new();
+    let now = current_timestamp();
+    for (obs, target) in grouped.into_iter().zip(targets) {
+        let Some(i) = target else {
+            continue;
+        };
+        let new_contents = add_to_entity(kb, &mut graph.entities[i], &obs.contents, now, pending);
+        if !new_contents.is_empty() {
+            added.push(Observation {
+                entity_name: obs.entity_name,
+                contents: new_contents,
+            });
+        }
+    }
+
+    added
+}
+
+/// Add `contents` missing from `entity`, queueing events and the broadcast
+///
+/// Returns the contents actually added, after limits and truncation.
+fn add_to_entity(
+    kb: &KnowledgeBase,
+    entity: &mut Entity,
+    contents: &[String],
+    now: u64,
+    pending: &mut PendingEffects,
+) -> Vec<String> {
+    let dedup = kb.observation_dedup;
+    let mut existing: HashSet<String> = entity
+        .observations
+        .iter()
+        .map(|o| dedup.key(o).into_owned())
+        .collect();
+    let mut new_contents = Vec::new();
+
+    for content in contents {
+        if !existing.contains(dedup.key(content).as_ref()) {
+            let Some(content) = pending.admit(kb, &entity.name, entity.observations.len(), content)
+            else {
+                continue;
+            };
+            // A truncated observation may collide with an existing one
+            if !existing.insert(dedup.key(&content).into_owned()) {
+                continue;
+            }
+
+            pending.event(
+                kb,
+                EventType::ObservationAdded,
+                json!({
+                    "entity": entity.name,
+                    "observation": content
+                }),
+            );
+
+            entity.observations.push(content.clone());
+            new_contents.push(content);
+        }
+    }
+
+    if !new_contents.is_empty() {
+        entity.updated_at = now;
+        entity.updated_by = kb.acting_user();
+
+        pending.broadcast(GraphEvent::EntityUpdated {
+            name: entity.name.clone(),
+            new_observations: new_contents.clone(),
+            user: Some(kb.acting_user()),
+        });
+    }
+
+    new_contents
+}
+
+/// Replace observations on entities (thread-safe: holds write lock during entire operation)
+///
+/// Observations already present in the new contents are kept as-is, so only real
+/// changes produce `ObservationRemoved` / `ObservationAdded` events.
+pub fn replace_observations(
+    kb: &KnowledgeBase,
+    replacements: Vec<ObservationReplacement>,
+) -> McpResult<Vec<ObservationReplacementResult>> {
+    commit(kb, |graph, pending| {
+        acl::check_writable(graph, replacements.iter().map(|r| r.entity_name.as_str()))?;
+        Ok(apply_replace_observations(kb, graph, replacements, pending))
+    })
+}
+
+pub(super) fn apply_replace_observations(
+    kb: &KnowledgeBase,
+    graph: &mut KnowledgeGraph,
+    replacements: Vec<ObservationReplacement>,
+    pending: &mut PendingEffects,
+) -> Vec<ObservationReplacementResult> {
+    let mut results = Vec::new();
+    let now = current_timestamp();
+
+    for replacement in replacements {
+        if let Some(entity) = graph
+            .entities
+            .iter_mut()
+            .find(|e| e.name == replacement.entity_name)
+        {
+            let new_contents: HashSet<&String> = replacement.contents.iter().collect();
+            let matches = |o: &String| match &replacement.replace_prefix {
+                Some(prefix) => o.starts_with(prefix.as_str()),
+                None => true,
+            };
+
+            let removed: Vec<String> = entity
+                .observations
+                .iter()
+                .filter(|o| matches(o) && !new_contents.contains(o))
+                .cloned()
+                .collect();
+
+            // Queue removals first so replay ends in the same state
+            for obs in &removed {
+                pending.event(
+                    kb,
+                    EventType::ObservationRemoved,
+                    json!({
+                        "entity": replacement.entity_name,
+                        "observation": obs
+                    }),
+                );
+            }
+
+            let to_remove: HashSet<&String> = removed.iter().collect();
+            entity.observations.retain(|o| !to_remove.contains(o));
+
+            let mut added = Vec::new();
+            for content in &replacement.contents {
+                if !entity.observations.contains(content) {
+                    let Some(content) = pending.admit(
+                        kb,
+                        &replacement.entity_name,
+                        entity.observations.len(),
+                        content,
+                    ) else {
+                        continue;
+                    };
+                    if entity.observations.contains(&content) {
+                        continue;
                     }
 
+                    pending.event(
+                        kb,
+                        EventType::ObservationAdded,
+                        json!({
+                            "entity": replacement.entity_name,
+                            "observation": content
+                        }),
+                    );
+
                     entity.observations.push(content.clone());
-                    new_contents.push(content.clone());
+                    added.push(content.clone());
                 }
             }
 
-            if !new_contents.is_empty() {
+            if !removed.is_empty() || !added.is_empty() {
                 entity.updated_at = now;
-                entity.updated_by = kb.current_user.clone();
+                entity.updated_by = kb.acting_user();
 
-                // Broadcast to WebSocket clients
-                ws_helpers::entity_updated(
-                    &obs.entity_name,
-                    new_contents.clone(),
-                    Some(kb.current_user.clone()),
-                );
+                pending.broadcast(GraphEvent::EntityUpdated {
+                    name: replacement.entity_name.clone(),
+                    new_observations: added.clone(),
+                    user: Some(kb.acting_user()),
+                });
 
-                added.push(Observation {
-                    entity_name: obs.entity_name.clone(),
-                    contents: new_contents,
+                results.push(ObservationReplacementResult {
+                    entity_name: replacement.entity_name.clone(),
+                    removed,
+                    added,
                 });
             }
         }
     }
 
-    // Persist based on mode
-    if !kb.event_sourcing_enabled {
-        kb.persist_to_file(&graph)?;
-    }
-
-    drop(graph);
-    kb.maybe_create_snapshot()?;
-
-    Ok(added)
+    results
 }
 
 /// Delete entities (thread-safe: holds write lock during entire operation)
-pub fn delete_entities(kb: &KnowledgeBase, entity_names: Vec<String>) -> McpResult<()> {
-    let mut graph = kb.graph.write().unwrap();
+///
+/// Protected entities are kept unless `force` is set; their names are
+/// returned.
+pub fn delete_entities(
+    kb: &KnowledgeBase,
+    entity_names: Vec<String>,
+    force: bool,
+) -> McpResult<Vec<String>> {
+    commit(kb, |graph, pending| {
+        acl::check_writable(graph, entity_names.iter().map(String::as_str))?;
+        Ok(apply_delete_entities(kb, graph, entity_names, force, pending))
+    })
+}
+
+/// Delete entities, returning the protected ones skipped (none when forced)
+pub(super) fn apply_delete_entities(
+    kb: &KnowledgeBase,
+    graph: &mut KnowledgeGraph,
+    entity_names: Vec<String>,
+    force: bool,
+    pending: &mut PendingEffects,
+) -> Vec<String> {
+    let (entity_names, skipped): (Vec<String>, Vec<String>) =
+        entity_names.into_iter().partition(|name| {
+            force
+                || !graph
+                    .entities
+                    .iter()
+                    .any(|e| &e.name == name && e.protected)
+        });
     let names_to_delete: HashSet<String> = entity_names.iter().cloned().collect();
 
-    // Emit events and broadcast for each entity being deleted
+    // Queue events and broadcasts for each entity being deleted
     for name in &entity_names {
         if graph.entities.iter().any(|e| &e.name == name) {
-            if kb.event_sourcing_enabled {
-                kb.emit_event(
-                    EventType::EntityDeleted,
-                    json!({ "name": name }),
-                )?;
-            }
-            // Broadcast to WebSocket clients
-            ws_helpers::entity_deleted(name, Some(kb.current_user.clone()));
+            pending.event(kb, EventType::EntityDeleted, json!({ "name": name }));
+            pending.broadcast(GraphEvent::EntityDeleted {
+                name: name.clone(),
+                user: Some(kb.acting_user()),
+            });
         }
     }
 
@@ -210,15 +823,7 @@ pub fn delete_entities(kb: &KnowledgeBase, entity_names: Vec<String>) -> McpResu
         .relations
         .retain(|r| !names_to_delete.contains(&r.from) && !names_to_delete.contains(&r.to));
 
-    // Persist based on mode
-    if !kb.event_sourcing_enabled {
-        kb.persist_to_file(&graph)?;
-    }
-
-    drop(graph);
-    kb.maybe_create_snapshot()?;
-
-    Ok(())
+    skipped
 }
 
 /// Delete observations from entities (thread-safe: holds write lock during entire operation)
@@ -226,26 +831,36 @@ pub fn delete_observations(
     kb: &KnowledgeBase,
     deletions: Vec<ObservationDeletion>,
 ) -> McpResult<()> {
-    let mut graph = kb.graph.write().unwrap();
+    commit(kb, |graph, pending| {
+        acl::check_writable(graph, deletions.iter().map(|d| d.entity_name.as_str()))?;
+        apply_delete_observations(kb, graph, deletions, pending);
+        Ok(())
+    })
+}
 
+pub(super) fn apply_delete_observations(
+    kb: &KnowledgeBase,
+    graph: &mut KnowledgeGraph,
+    deletions: Vec<ObservationDeletion>,
+    pending: &mut PendingEffects,
+) {
     for deletion in deletions {
         if let Some(entity) = graph
             .entities
             .iter_mut()
             .find(|e| e.name == deletion.entity_name)
         {
-            // Emit events for each observation being deleted
-            if kb.event_sourcing_enabled {
-                for obs in &deletion.observations {
-                    if entity.observations.contains(obs) {
-                        kb.emit_event(
-                            EventType::ObservationRemoved,
-                            json!({
-                                "entity": deletion.entity_name,
-                                "observation": obs
-                            }),
-                        )?;
-                    }
+            // Queue events for each observation being deleted
+            for obs in &deletion.observations {
+                if entity.observations.contains(obs) {
+                    pending.event(
+                        kb,
+                        EventType::ObservationRemoved,
+                        json!({
+                            "entity": deletion.entity_name,
+                            "observation": obs
+                        }),
+                    );
                 }
             }
 
@@ -253,23 +868,65 @@ pub fn delete_observations(
             entity.observations.retain(|o| !to_remove.contains(o));
         }
     }
+}
 
-    // Persist based on mode
-    if !kb.event_sourcing_enabled {
-        kb.persist_to_file(&graph)?;
+/// Delete relations (thread-safe: holds write lock during entire operation)
+pub fn delete_relations(kb: &KnowledgeBase, relations: Vec<Relation>) -> McpResult<()> {
+    commit(kb, |graph, pending| {
+        acl::check_writable(graph, relation_endpoints(&relations))?;
+        apply_delete_relations(kb, graph, relations, pending);
+        Ok(())
+    })
+}
+
+/// Delete every relation matching all given filters (thread-safe: holds write lock during entire operation)
+///
+/// At least one filter is required so a missing argument can never wipe all
+/// relations. Returns the deleted relations.
+pub fn delete_relations_by_filter(
+    kb: &KnowledgeBase,
+    from: Option<String>,
+    to: Option<String>,
+    relation_type: Option<String>,
+) -> McpResult<Vec<Relation>> {
+    if from.is_none() && to.is_none() && relation_type.is_none() {
+        return Err("At least one of from, to or relationType is required".into());
     }
 
-    drop(graph);
-    kb.maybe_create_snapshot()?;
+    commit(kb, |graph, pending| {
+        let matched: Vec<Relation> = graph
+            .relations
+            .iter()
+            .filter(|r| {
+                from.as_ref().is_none_or(|f| &r.from == f)
+                    && to.as_ref().is_none_or(|t| &r.to == t)
+                    && relation_type
+                        .as_ref()
+                        .is_none_or(|rt| &r.relation_type == rt)
+            })
+            .cloned()
+            .collect();
 
-    Ok(())
+        acl::check_writable(graph, relation_endpoints(&matched))?;
+        apply_delete_relations(kb, graph, matched.clone(), pending);
+        Ok(matched)
+    })
 }
 
-/// Delete relations (thread-safe: holds write lock during entire operation)
-pub fn delete_relations(kb: &KnowledgeBase, relations: Vec<Relation>) -> McpResult<()> {
-    let mut graph = kb.graph.write().unwrap();
+/// Names of both endpoints of each relation
+pub(super) fn relation_endpoints(relations: &[Relation]) -> impl Iterator<Item = &str> {
+    relations
+        .iter()
+        .flat_map(|r| [r.from.as_str(), r.to.as_str()])
+}
 
-    // Emit events and broadcast for each relation being deleted
+pub(super) fn apply_delete_relations(
+    kb: &KnowledgeBase,
+    graph: &mut KnowledgeGraph,
+    relations: Vec<Relation>,
+    pending: &mut PendingEffects,
+) {
+    // Queue events and broadcasts for each relation being deleted
     for relation in &relations {
         let exists = graph.relations.iter().any(|r| {
             r.from == relation.from
@@ -277,23 +934,21 @@ pub fn delete_relations(kb: &KnowledgeBase, relations: Vec<Relation>) -> McpResu
                 && r.relation_type == relation.relation_type
         });
         if exists {
-            if kb.event_sourcing_enabled {
-                kb.emit_event(
-                    EventType::RelationDeleted,
-                    json!({
-                        "from": relation.from,
-                        "to": relation.to,
-                        "relation_type": relation.relation_type
-                    }),
-                )?;
-            }
-            // Broadcast to WebSocket clients
-            ws_helpers::relation_deleted(
-                &relation.from,
-                &relation.to,
-                &relation.relation_type,
-                Some(kb.current_user.clone()),
+            pending.event(
+                kb,
+                EventType::RelationDeleted,
+                json!({
+                    "from": relation.from,
+                    "to": relation.to,
+                    "relation_type": relation.relation_type
+                }),
             );
+            pending.broadcast(GraphEvent::RelationDeleted {
+                from: relation.from.clone(),
+                to: relation.to.clone(),
+                relation_type: relation.relation_type.clone(),
+                user: Some(kb.acting_user()),
+            });
         }
     }
 
@@ -307,14 +962,461 @@ pub fn delete_relations(kb: &KnowledgeBase, relations: Vec<Relation>) -> McpResu
         let key = (r.from.clone(), r.to.clone(), r.relation_type.clone());
         !to_delete.contains(&key)
     });
+}
 
-    // Persist based on mode
-    if !kb.event_sourcing_enabled {
-        kb.persist_to_file(&graph)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PathStep, RelatedOptions};
+    use crate::validation::{CardinalityConstraints, ObservationLimits, RelationAliases};
+
+    #[test]
+    fn test_observation_limits_reported() {
+        let path = format!("target/test_crud_limits_{}.jsonl", std::process::id());
+        let mut kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        kb.observation_limits = ObservationLimits {
+            max_len: Some(10),
+            max_per_entity: Some(2),
+            ..Default::default()
+        };
+
+        let (created, warnings) = create_entities_with_warnings(
+            &kb,
+            vec![Entity::with_observations(
+                "Log".to_string(),
+                "Bug".to_string(),
+                vec!["short".to_string(), "x".repeat(50)],
+            )],
+        )
+        .unwrap();
+        assert_eq!(created[0].observations, vec!["short", &"x".repeat(10)]);
+        assert_eq!(
+            warnings[0].kind,
+            ValidationWarningKind::ObservationTruncated
+        );
+
+        let (added, warnings) = add_observations_with_warnings(
+            &kb,
+            vec![Observation::new(
+                "Log".to_string(),
+                vec!["third".to_string()],
+            )],
+        )
+        .unwrap();
+        assert!(added.is_empty());
+        assert_eq!(warnings[0].kind, ValidationWarningKind::ObservationRejected);
+
+        let _ = std::fs::remove_file(&path);
     }
 
-    drop(graph);
-    kb.maybe_create_snapshot()?;
+    #[test]
+    fn test_functional_relations_enforced() {
+        let path = format!("target/test_crud_cardinality_{}.jsonl", std::process::id());
+        let mut kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        kb.cardinality = CardinalityConstraints::unconstrained()
+            .with_functional("assigned_to", CardinalityMode::Replace)
+            .with_functional("reports_to", CardinalityMode::Reject);
+
+        let names = ["Task", "Alice", "Bob"];
+        create_entities(
+            &kb,
+            names
+                .iter()
+                .map(|n| Entity::new(n.to_string(), "Person".to_string()))
+                .collect(),
+        )
+        .unwrap();
+        let relation = |from: &str, to: &str, rt: &str| {
+            Relation::new(from.to_string(), to.to_string(), rt.to_string())
+        };
+
+        create_relations(&kb, vec![relation("Task", "Alice", "assigned_to")]).unwrap();
+        let (created, warnings) =
+            create_relations_with_warnings(&kb, vec![relation("Task", "Bob", "assigned_to")])
+                .unwrap();
+        assert_eq!(created.len(), 1);
+        assert_eq!(warnings[0].kind, ValidationWarningKind::CardinalityViolation);
+        let targets: Vec<String> = kb
+            .graph
+            .read()
+            .unwrap()
+            .relations
+            .iter()
+            .filter(|r| r.relation_type == "assigned_to")
+            .map(|r| r.to.clone())
+            .collect();
+        assert_eq!(targets, vec!["Bob"]);
+
+        // Reject mode keeps the first relation, even within one batch
+        let (created, warnings) = create_relations_with_warnings(
+            &kb,
+            vec![
+                relation("Alice", "Bob", "reports_to"),
+                relation("Alice", "Task", "reports_to"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].to, "Bob");
+        assert_eq!(warnings.len(), 1);
+
+        // Unconstrained types stay many-to-many
+        let created = create_relations(
+            &kb,
+            vec![
+                relation("Alice", "Bob", "knows"),
+                relation("Alice", "Task", "knows"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(created.len(), 2);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_normalized_observation_dedup() {
+        let path = format!("target/test_crud_dedup_{}.jsonl", std::process::id());
+        let mut kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        create_entities(
+            &kb,
+            vec![Entity::with_observations(
+                "Task".to_string(),
+                "Task".to_string(),
+                vec!["Status: done".to_string()],
+            )],
+        )
+        .unwrap();
+        let variants = || {
+            vec![Observation::new(
+                "Task".to_string(),
+                vec!["Status:done ".to_string(), "status:  DONE".to_string()],
+            )]
+        };
+
+        // Exact matching (the default) stores both variants
+        assert_eq!(add_observations(&kb, variants()).unwrap()[0].contents.len(), 2);
+
+        kb.observation_dedup = crate::validation::DedupMode::Normalized;
+        kb.graph.write().unwrap().entities[0].observations = vec!["Status: done".to_string()];
+        assert!(add_observations(&kb, variants()).unwrap().is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_add_observations_batch_matches_per_entity() {
+        let path = format!("target/test_crud_batch_obs_{}.jsonl", std::process::id());
+        let kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        let entities = || {
+            vec![
+                Entity::with_observations(
+                    "Auth".to_string(),
+                    "Module".to_string(),
+                    vec!["Owner: core".to_string()],
+                ),
+                Entity::new("Billing".to_string(), "Module".to_string()),
+            ]
+        };
+        let input = || {
+            vec![
+                Observation::new("Auth".to_string(), vec!["Owner: core".to_string()]),
+                Observation::new("Billing".to_string(), vec!["Lang: Rust".to_string()]),
+                Observation::new("Missing".to_string(), vec!["Ignored".to_string()]),
+                Observation::new(
+                    "Auth".to_string(),
+                    vec!["Lang: Rust".to_string(), "Lang: Rust".to_string()],
+                ),
+            ]
+        };
+
+        create_entities(&kb, entities()).unwrap();
+        let looped: Vec<Observation> = input()
+            .into_iter()
+            .flat_map(|obs| add_observations(&kb, vec![obs]).unwrap())
+            .collect();
+        let expected = kb.graph.read().unwrap().clone();
+
+        *kb.graph.write().unwrap() = KnowledgeGraph::default();
+        create_entities(&kb, entities()).unwrap();
+        let (batched, _) = add_observations_batch(&kb, input()).unwrap();
+
+        // Same graph; items for one entity are merged into one result
+        let graph = kb.graph.read().unwrap();
+        for (got, want) in graph.entities.iter().zip(&expected.entities) {
+            assert_eq!(got.observations, want.observations);
+            assert_eq!(got.updated_by, "tester");
+            assert!(got.updated_at >= want.created_at);
+        }
+        assert_eq!(looped.len(), 2);
+        assert_eq!(batched.len(), 2);
+        assert_eq!(batched[0].entity_name, "Auth");
+        assert_eq!(batched[0].contents, vec!["Lang: Rust"]);
+        assert_eq!(batched[1].contents, vec!["Lang: Rust"]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_entity_templates_add_defaults() {
+        let path = format!("target/test_crud_templates_{}.jsonl", std::process::id());
+        let mut kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        kb.entity_templates = crate::validation::EntityTemplates::none().with_template(
+            "Bug",
+            vec!["Status: todo".to_string(), "Priority: medium".to_string()],
+        );
+
+        let (created, warnings) = create_entities_with_warnings(
+            &kb,
+            vec![
+                Entity::with_observations(
+                    "Crash".to_string(),
+                    "Bug".to_string(),
+                    vec!["Priority: high".to_string()],
+                ),
+                Entity::new("Payments".to_string(), "Module".to_string()),
+            ],
+        )
+        .unwrap();
+        assert_eq!(created[0].observations, vec!["Priority: high", "Status: todo"]);
+        assert!(created[1].observations.is_empty());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, ValidationWarningKind::TemplateDefaultAdded);
+        assert_eq!(warnings[0].subject, "Crash");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_entity_name_policy() {
+        let path = format!("target/test_crud_names_{}.jsonl", std::process::id());
+        let mut kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        let (created, warnings) = create_entities_with_warnings(
+            &kb,
+            vec![
+                Entity::new(" Alice ".to_string(), "Person".to_string()),
+                Entity::new("Alice".to_string(), "Person".to_string()),
+                Entity::new("Bob".to_string(), "Person".to_string()),
+                Entity::new("  ".to_string(), "Person".to_string()),
+            ],
+        )
+        .unwrap();
+        let names: Vec<&str> = created.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["Alice", "Bob"]);
+        let kinds: Vec<ValidationWarningKind> = warnings.iter().map(|w| w.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ValidationWarningKind::EntityNameNormalized,
+                ValidationWarningKind::EntityNameRejected
+            ]
+        );
+
+        // Unnormalized names still find the entity
+        let relations = create_relations(
+            &kb,
+            vec![Relation::new(" Alice".to_string(), "Bob ".to_string(), "knows".to_string())],
+        )
+        .unwrap();
+        assert_eq!(relations[0].from, "Alice");
+        assert_eq!(kb.open_nodes(vec![" Alice ".to_string()]).unwrap().entities.len(), 1);
+
+        kb.entity_names = crate::validation::NamePolicy::verbatim();
+        let created =
+            create_entities(&kb, vec![Entity::new(" Carol ".to_string(), "Person".to_string())])
+                .unwrap();
+        assert_eq!(created[0].name, " Carol ");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_relation_aliases_normalized() {
+        let path = format!("target/test_crud_aliases_{}.jsonl", std::process::id());
+        let mut kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        create_entities(
+            &kb,
+            vec![
+                Entity::new("Api".to_string(), "Module".to_string()),
+                Entity::new("Auth".to_string(), "Module".to_string()),
+                Entity::new("Db".to_string(), "Module".to_string()),
+            ],
+        )
+        .unwrap();
+        // Stored before the alias existed
+        create_relations(
+            &kb,
+            vec![Relation::new("Auth".to_string(), "Db".to_string(), "dependsOn".to_string())],
+        )
+        .unwrap();
+
+        kb.relation_aliases = RelationAliases::parse("dependsOn=depends_on,depends on=depends_on");
+        let created = create_relations(
+            &kb,
+            vec![Relation::new("Api".to_string(), "Auth".to_string(), "depends on".to_string())],
+        )
+        .unwrap();
+        assert_eq!(created[0].relation_type, "depends_on");
+
+        // Filters match every spelling, old and new
+        let related = kb
+            .get_related(
+                "Auth",
+                &RelatedOptions {
+                    relation_type: Some("dependsOn".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(related.relations.len(), 2);
+        let result = kb
+            .traverse(
+                "Api",
+                vec![PathStep::new("depends_on".to_string(), "out".to_string()).with_hops(1, 2)],
+                10,
+            )
+            .unwrap();
+        assert!(result.end_nodes.iter().any(|e| e.name == "Db"));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_entity_ids_survive_replay() {
+        use crate::event_store::EventStore;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        let mut supplied = Entity::new("Auth".to_string(), "Module".to_string());
+        supplied.id = "auth".to_string();
+        let mut taken = Entity::new("Db".to_string(), "Module".to_string());
+        taken.id = "auth".to_string();
+        let created = create_entities(&kb, vec![supplied, taken]).unwrap();
+        assert_eq!(created[0].id, "auth");
+        assert_eq!(created[1].id.len(), 16);
+
+        // Endpoints given by id are stored by name
+        let relations = create_relations(
+            &kb,
+            vec![Relation::new("auth".to_string(), created[1].id.clone(), "uses".to_string())],
+        )
+        .unwrap();
+        assert_eq!((relations[0].from.as_str(), relations[0].to.as_str()), ("Auth", "Db"));
 
-    Ok(())
+        let config = kb.event_store.as_ref().unwrap().lock().unwrap().config().clone();
+        let (entities, _) = EventStore::with_config(config).initialize().unwrap();
+        let ids: Vec<&str> = entities.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["auth", created[1].id.as_str()]);
+    }
+
+    #[test]
+    fn test_relation_properties_survive_replay() {
+        use crate::event_store::EventStore;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        create_entities(
+            &kb,
+            vec![
+                Entity::new("Api".to_string(), "Module".to_string()),
+                Entity::new("Auth".to_string(), "Module".to_string()),
+            ],
+        )
+        .unwrap();
+        let mut relation = Relation::new("Api".to_string(), "Auth".to_string(), "uses".to_string());
+        relation.properties.insert("critical".to_string(), json!(true));
+        create_relations(&kb, vec![relation]).unwrap();
+
+        let config = kb.event_store.as_ref().unwrap().lock().unwrap().config().clone();
+        let (_, relations) = EventStore::with_config(config).initialize().unwrap();
+        assert_eq!(relations[0].properties.get("critical"), Some(&json!(true)));
+    }
+
+    #[test]
+    fn test_concurrent_writers_match_replay() {
+        use crate::event_store::{EventStore, EventStoreConfig, LogRotation, SnapshotManager};
+        use std::sync::Mutex;
+
+        const WRITERS: usize = 8;
+        const OPS: usize = 40;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = EventStoreConfig::with_data_dir(temp_dir.path());
+        // Snapshot and rotate repeatedly while writers are running
+        config.snapshot_threshold = 25;
+
+        let mut kb =
+            KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        kb.event_store = Some(Mutex::new(EventStore::with_config(config.clone())));
+        kb.snapshot_manager = Some(SnapshotManager::new(config.clone()));
+        kb.log_rotation = Some(LogRotation::new(config.clone()));
+
+        let names: Vec<String> = (0..WRITERS).map(|w| format!("W{}", w)).collect();
+        create_entities(
+            &kb,
+            names
+                .iter()
+                .map(|n| Entity::new(n.clone(), "Worker".to_string()))
+                .collect(),
+        )
+        .unwrap();
+
+        std::thread::scope(|scope| {
+            for (w, name) in names.iter().enumerate() {
+                let kb = &kb;
+                let next = names[(w + 1) % WRITERS].clone();
+                scope.spawn(move || {
+                    for i in 0..OPS {
+                        add_observations(
+                            kb,
+                            vec![Observation::new(name.clone(), vec![format!("step {}", i)])],
+                        )
+                        .unwrap();
+                        if i % 4 == 0 {
+                            let relation_type = format!("handoff_{}", i);
+                            create_relations(
+                                kb,
+                                vec![Relation::new(name.clone(), next.clone(), relation_type)],
+                            )
+                            .unwrap();
+                        }
+                        if i % 10 == 9 {
+                            delete_observations(
+                                kb,
+                                vec![ObservationDeletion {
+                                    entity_name: name.clone(),
+                                    observations: vec![format!("step {}", i - 1)],
+                                }],
+                            )
+                            .unwrap();
+                        }
+                    }
+                });
+            }
+        });
+
+        let (mut entities, mut relations) =
+            EventStore::with_config(config).initialize().unwrap();
+        let graph = kb.graph.read().unwrap();
+        let mut expected_entities = graph.entities.clone();
+        let mut expected_relations = graph.relations.clone();
+
+        entities.sort_by(|a, b| a.name.cmp(&b.name));
+        expected_entities.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(entities.len(), WRITERS);
+        for (replayed, live) in entities.iter().zip(&expected_entities) {
+            assert_eq!(replayed.name, live.name);
+            assert_eq!(replayed.observations, live.observations);
+            assert_eq!(live.observations.len(), OPS - OPS / 10);
+        }
+
+        let key = |r: &Relation| (r.from.clone(), r.to.clone(), r.relation_type.clone());
+        relations.sort_by_key(key);
+        expected_relations.sort_by_key(key);
+        assert_eq!(relations.len(), WRITERS * OPS / 4);
+        assert_eq!(
+            relations.iter().map(key).collect::<Vec<_>>(),
+            expected_relations.iter().map(key).collect::<Vec<_>>()
+        );
+    }
 }
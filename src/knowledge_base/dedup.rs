@@ -0,0 +1,284 @@
+//! Duplicate entity detection and comparison
+//!
+//! Candidates are bucketed by entity type and normalized name prefix, and only
+//! compared within a bucket, so the cost grows with bucket size rather than
+//! with the square of the whole graph.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use crate::types::{Entity, EntityDiff, KnowledgeGraph, McpResult, NeighborEdge, SetDiff};
+
+use super::{acl, KnowledgeBase};
+
+/// Number of leading name characters used to bucket candidates
+const NAME_PREFIX_LEN: usize = 2;
+
+/// Find pairs of entities that are likely duplicates
+///
+/// The score for a pair is the highest of its name similarity (edit distance
+/// on lowercased alphanumeric names), observation overlap and neighbor
+/// overlap (Jaccard). Pairs scoring at least `threshold` are returned, best
+/// first.
+pub fn find_duplicates(kb: &KnowledgeBase, threshold: f32) -> Vec<(String, String, f32)> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+
+    let mut neighbors: HashMap<&str, HashSet<(&str, &str)>> = HashMap::new();
+    for relation in &graph.relations {
+        neighbors
+            .entry(relation.from.as_str())
+            .or_default()
+            .insert((relation.relation_type.as_str(), relation.to.as_str()));
+        neighbors
+            .entry(relation.to.as_str())
+            .or_default()
+            .insert((relation.relation_type.as_str(), relation.from.as_str()));
+    }
+
+    let mut buckets: BTreeMap<(String, String), Vec<(&Entity, String)>> = BTreeMap::new();
+    for entity in &graph.entities {
+        let normalized = normalize_name(&entity.name);
+        let prefix: String = normalized.chars().take(NAME_PREFIX_LEN).collect();
+        buckets
+            .entry((entity.entity_type.to_lowercase(), prefix))
+            .or_default()
+            .push((entity, normalized));
+    }
+
+    let empty = HashSet::new();
+    let mut pairs = Vec::new();
+    for members in buckets.values() {
+        for (i, (a, a_name)) in members.iter().enumerate() {
+            for (b, b_name) in &members[i + 1..] {
+                let a_neighbors = neighbors.get(a.name.as_str()).unwrap_or(&empty);
+                let b_neighbors = neighbors.get(b.name.as_str()).unwrap_or(&empty);
+
+                let a_obs: HashSet<&str> = a.observations.iter().map(String::as_str).collect();
+                let b_obs: HashSet<&str> = b.observations.iter().map(String::as_str).collect();
+
+                let score = name_similarity(a_name, b_name)
+                    .max(jaccard(&a_obs, &b_obs))
+                    .max(jaccard(a_neighbors, b_neighbors));
+
+                if score >= threshold {
+                    pairs.push((a.name.clone(), b.name.clone(), score));
+                }
+            }
+        }
+    }
+
+    pairs.sort_by(|x, y| {
+        y.2.total_cmp(&x.2)
+            .then_with(|| x.0.cmp(&y.0))
+            .then_with(|| x.1.cmp(&y.1))
+    });
+    pairs
+}
+
+/// Compare two entities (by name or id) before merging them
+///
+/// Observations and relations are split into the parts both entities share
+/// and the parts unique to each. Relations are compared by type, direction
+/// and other endpoint.
+pub fn diff_entities(kb: &KnowledgeBase, a: &str, b: &str) -> McpResult<EntityDiff> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    let resolve = |key: &str| {
+        graph
+            .resolve_entity(key)
+            .ok_or_else(|| format!("Entity '{}' not found", key))
+    };
+    let (a, b) = (resolve(a)?, resolve(b)?);
+    if a.name == b.name {
+        return Err(format!("Cannot diff '{}' with itself", a.name).into());
+    }
+
+    let a_obs: HashSet<&str> = a.observations.iter().map(String::as_str).collect();
+    let b_obs: HashSet<&str> = b.observations.iter().map(String::as_str).collect();
+    let only = |observations: &[String], other: &HashSet<&str>| -> Vec<String> {
+        observations
+            .iter()
+            .filter(|o| !other.contains(o.as_str()))
+            .cloned()
+            .collect()
+    };
+    let observations = SetDiff {
+        common: a
+            .observations
+            .iter()
+            .filter(|o| b_obs.contains(o.as_str()))
+            .cloned()
+            .collect(),
+        only_a: only(&a.observations, &b_obs),
+        only_b: only(&b.observations, &a_obs),
+    };
+
+    let a_edges = neighbor_edges(&graph, &a.name);
+    let b_edges = neighbor_edges(&graph, &b.name);
+    let relations = SetDiff {
+        common: a_edges.intersection(&b_edges).cloned().collect(),
+        only_a: a_edges.difference(&b_edges).cloned().collect(),
+        only_b: b_edges.difference(&a_edges).cloned().collect(),
+    };
+
+    // Scored like find_duplicates, where neighbors ignore direction
+    let undirected = |edges: &BTreeSet<NeighborEdge>| -> HashSet<(String, String)> {
+        edges
+            .iter()
+            .map(|e| (e.relation_type.clone(), e.entity.clone()))
+            .collect()
+    };
+    let similarity = name_similarity(&normalize_name(&a.name), &normalize_name(&b.name))
+        .max(jaccard(&a_obs, &b_obs))
+        .max(jaccard(&undirected(&a_edges), &undirected(&b_edges)));
+
+    Ok(EntityDiff {
+        a: a.name.clone(),
+        b: b.name.clone(),
+        type_a: a.entity_type.clone(),
+        type_b: b.entity_type.clone(),
+        same_type: a.entity_type == b.entity_type,
+        similarity,
+        observations,
+        relations,
+    })
+}
+
+/// Every relation touching `name`, seen from `name`
+fn neighbor_edges(graph: &KnowledgeGraph, name: &str) -> BTreeSet<NeighborEdge> {
+    graph
+        .relations
+        .iter()
+        .filter_map(|r| {
+            let (direction, entity) = if r.from == name {
+                ("outgoing", &r.to)
+            } else if r.to == name {
+                ("incoming", &r.from)
+            } else {
+                return None;
+            };
+            Some(NeighborEdge {
+                relation_type: r.relation_type.clone(),
+                direction: direction.to_string(),
+                entity: entity.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Lowercase and drop everything but letters and digits
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// `1 - distance / longest`, so identical names score 1.0
+fn name_similarity(a: &str, b: &str) -> f32 {
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 0.0;
+    }
+    1.0 - levenshtein(a, b) as f32 / longest as f32
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Overlap of two sets; two empty sets share nothing
+fn jaccard<T: Eq + std::hash::Hash>(a: &HashSet<T>, b: &HashSet<T>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("same", "same"), 0);
+    }
+
+    #[test]
+    fn test_name_similarity_ignores_case_and_punctuation() {
+        let a = normalize_name("User-Service");
+        let b = normalize_name("user service");
+        assert_eq!(name_similarity(&a, &b), 1.0);
+        assert!(name_similarity("userservice", "usrservice") > 0.9);
+    }
+
+    #[test]
+    fn test_diff_entities() {
+        use crate::types::Relation;
+
+        let path = format!("target/test_diff_entities_{}.jsonl", std::process::id());
+        let kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        kb.create_entities(vec![
+            Entity::with_observations(
+                "Auth".to_string(),
+                "Module".to_string(),
+                vec!["Handles login".to_string(), "Uses JWT".to_string()],
+            ),
+            Entity::with_observations(
+                "AuthService".to_string(),
+                "Feature".to_string(),
+                vec!["Uses JWT".to_string(), "Owned by core".to_string()],
+            ),
+            Entity::new("Db".to_string(), "Module".to_string()),
+            Entity::new("Api".to_string(), "Module".to_string()),
+        ])
+        .unwrap();
+        let relation = |from: &str, to: &str, relation_type: &str| {
+            Relation::new(from.to_string(), to.to_string(), relation_type.to_string())
+        };
+        kb.create_relations(vec![
+            relation("Auth", "Db", "depends_on"),
+            relation("AuthService", "Db", "depends_on"),
+            relation("Api", "Auth", "depends_on"),
+            relation("AuthService", "Api", "depends_on"),
+        ])
+        .unwrap();
+
+        let diff = diff_entities(&kb, "Auth", "AuthService").unwrap();
+        assert!(!diff.same_type);
+        assert_eq!(diff.observations.common, vec!["Uses JWT"]);
+        assert_eq!(diff.observations.only_a, vec!["Handles login"]);
+        assert_eq!(diff.observations.only_b, vec!["Owned by core"]);
+        let edge = |direction: &str, entity: &str| NeighborEdge {
+            relation_type: "depends_on".to_string(),
+            direction: direction.to_string(),
+            entity: entity.to_string(),
+        };
+        assert_eq!(diff.relations.common, vec![edge("outgoing", "Db")]);
+        assert_eq!(diff.relations.only_a, vec![edge("incoming", "Api")]);
+        assert_eq!(diff.relations.only_b, vec![edge("outgoing", "Api")]);
+        // Undirected, both share Db and Api
+        assert_eq!(diff.similarity, 1.0);
+
+        assert!(diff_entities(&kb, "Auth", "Missing").is_err());
+        assert!(diff_entities(&kb, "Auth", "Auth").is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,173 @@
+//! Archiving of expired temporal relations
+//!
+//! A relation whose `valid_to` has passed stays in the graph, and every query
+//! on the current state has to look past it. [`archive_expired_relations`]
+//! moves relations that ended before a cutoff out of the graph into an
+//! archive file next to the memory file (`memory.expired.jsonl` for
+//! `memory.jsonl`), one relation per line. The removal itself is an ordinary
+//! relation deletion (a `RelationDeleted` event with Event Sourcing), so a
+//! replay arrives at the same graph.
+//!
+//! Archived relations are out of scope for queries on the current graph, but
+//! `get_relation_history` and `get_relations_at_time` read the archive too,
+//! so past states still include them. Scratch graphs never write the archive.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::types::{McpResult, Relation};
+
+use super::acl;
+use super::crud;
+use super::KnowledgeBase;
+
+/// Archive file for relations pruned from `kb`
+fn archive_path(kb: &KnowledgeBase) -> PathBuf {
+    Path::new(&kb.memory_file_path).with_extension("expired.jsonl")
+}
+
+fn is_expired(relation: &Relation, before: u64) -> bool {
+    relation.valid_to.is_some_and(|valid_to| valid_to < before)
+}
+
+/// Relations whose `valid_to` is before `before` (dry run)
+pub fn expired_relations(kb: &KnowledgeBase, before: u64) -> Vec<Relation> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    graph
+        .relations
+        .iter()
+        .filter(|r| is_expired(r, before))
+        .cloned()
+        .collect()
+}
+
+/// Move relations whose `valid_to` is before `before` to the archive (thread-safe: holds write lock during entire operation)
+///
+/// Returns the number of relations archived. Fails without changing anything
+/// if one of them touches an entity the caller may not modify.
+pub fn archive_expired_relations(kb: &KnowledgeBase, before: u64) -> McpResult<usize> {
+    crud::commit(kb, |graph, pending| {
+        let expired: Vec<Relation> = graph
+            .relations
+            .iter()
+            .filter(|r| is_expired(r, before))
+            .cloned()
+            .collect();
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
+        acl::check_writable(graph, crud::relation_endpoints(&expired))?;
+        // Written before the graph changes, so a failed write loses nothing
+        if !kb.scratch {
+            append_archive(kb, &expired)?;
+        }
+
+        let count = expired.len();
+        crud::apply_delete_relations(kb, graph, expired, pending);
+        Ok(count)
+    })
+}
+
+fn append_archive(kb: &KnowledgeBase, relations: &[Relation]) -> McpResult<()> {
+    let path = archive_path(kb);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut lines = String::new();
+    for relation in relations {
+        lines.push_str(&serde_json::to_string(relation)?);
+        lines.push('\n');
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    file.write_all(lines.as_bytes())?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Every relation archived so far, oldest archive first
+///
+/// Lines that do not parse are skipped with a warning.
+pub fn load_archived_relations(kb: &KnowledgeBase) -> McpResult<Vec<Relation>> {
+    let path = archive_path(kb);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)?;
+    let mut relations = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Relation>(line) {
+            Ok(relation) => relations.push(relation),
+            Err(e) => eprintln!(
+                "[Expire] Skipping line {} of {}: {}",
+                index + 1,
+                path.display(),
+                e
+            ),
+        }
+    }
+    Ok(relations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Entity;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_archived_relations_stay_in_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        kb.create_entities(
+            ["Alice", "Acme", "Initech"]
+                .map(|n| Entity::new(n.to_string(), "Org".to_string()))
+                .to_vec(),
+        )
+        .unwrap();
+        kb.create_relations(vec![
+            Relation::with_validity(
+                "Alice".to_string(),
+                "Initech".to_string(),
+                "works_at".to_string(),
+                Some(100),
+                Some(1000),
+            ),
+            Relation::with_validity(
+                "Alice".to_string(),
+                "Acme".to_string(),
+                "works_at".to_string(),
+                Some(1000),
+                None,
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(kb.expired_relations(1000).len(), 0);
+        assert_eq!(kb.expired_relations(1001).len(), 1);
+        assert_eq!(kb.archive_expired_relations(2000).unwrap(), 1);
+        assert_eq!(kb.archive_expired_relations(2000).unwrap(), 0);
+
+        // Gone from the current graph, and from a replay of the log
+        let graph = kb.graph.read().unwrap().clone();
+        assert_eq!(graph.relations.len(), 1);
+        assert_eq!(graph.relations[0].to, "Acme");
+        assert!(kb.verify_replay().unwrap().is_none());
+
+        // Still part of the entity's history and of past states
+        let history = kb.get_relation_history("Initech").unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].valid_to, Some(1000));
+        let at_500 = kb.get_relations_at_time(Some(500), Some("Alice")).unwrap();
+        assert_eq!(at_500.len(), 1);
+        assert_eq!(at_500[0].to, "Initech");
+        assert_eq!(kb.get_relation_history("Alice").unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,203 @@
+//! Content hash of the whole graph
+//!
+//! [`content_hash`] fingerprints the graph content so replicas, caches and
+//! replays can be compared without shipping the graph. Two graphs with the
+//! same content hash the same however their entities, relations and
+//! observations were inserted. Canonical form:
+//!
+//! - Entity: `name`, `entityType`, `observations` (sorted), `id`,
+//!   `protected` and `acl` (sorted).
+//! - Relation: `from`, `to`, `relationType`, `validFrom`, `validTo` and
+//!   `properties`.
+//! - Timestamps and authorship (`createdAt`, `updatedAt`, `createdBy`,
+//!   `updatedBy`) are left out: live mutations stamp them at apply time while
+//!   replays take them from the event (see `verify.rs`), so including them
+//!   would report drift between identical graphs.
+//! - Each item is written as compact JSON with keys sorted, every field
+//!   present (`null` / empty when unset), one item per line. Entities come
+//!   first, sorted by name, then a `--` line, then relations sorted by their
+//!   canonical line.
+//! - The hash is 64-bit FNV-1a (see [`Fnv1a`]) over those lines, as 16 hex
+//!   digits. It detects drift; it is not cryptographic.
+//!
+//! The hash is computed on demand and costs a sort of the graph, so the last
+//! one is kept until the graph version changes.
+
+use serde_json::json;
+
+use crate::types::{Entity, KnowledgeGraph, McpResult, Relation};
+use crate::utils::Fnv1a;
+
+use super::KnowledgeBase;
+
+/// Canonical line for an entity
+fn entity_line(entity: &Entity) -> McpResult<String> {
+    let mut observations = entity.observations.clone();
+    observations.sort();
+    let mut acl = entity.acl.clone();
+    acl.sort();
+    Ok(serde_json::to_string(&json!({
+        "name": entity.name,
+        "entityType": entity.entity_type,
+        "observations": observations,
+        "id": entity.id,
+        "protected": entity.protected,
+        "acl": acl
+    }))?)
+}
+
+/// Canonical line for a relation
+fn relation_line(relation: &Relation) -> McpResult<String> {
+    Ok(serde_json::to_string(&json!({
+        "from": relation.from,
+        "to": relation.to,
+        "relationType": relation.relation_type,
+        "validFrom": relation.valid_from,
+        "validTo": relation.valid_to,
+        "properties": relation.properties
+    }))?)
+}
+
+/// Fingerprint of `graph` (16 hex digits)
+fn graph_hash(graph: &KnowledgeGraph) -> McpResult<String> {
+    let mut entities = graph.entities.iter().collect::<Vec<_>>();
+    entities.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut relations = graph
+        .relations
+        .iter()
+        .map(relation_line)
+        .collect::<McpResult<Vec<_>>>()?;
+    relations.sort();
+
+    let mut hash = Fnv1a::new();
+    for entity in entities {
+        hash.write(entity_line(entity)?.as_bytes());
+        hash.write(b"\n");
+    }
+    hash.write(b"--\n");
+    for relation in relations {
+        hash.write(relation.as_bytes());
+        hash.write(b"\n");
+    }
+    Ok(hash.to_hex())
+}
+
+/// Fingerprint of the knowledge base's current graph
+pub fn content_hash(kb: &KnowledgeBase) -> McpResult<String> {
+    let graph = kb.graph.read().unwrap();
+    // Mutations bump the version under the write lock, so it matches `graph`
+    let version = kb.version();
+    if let Some((cached_version, hash)) = kb.content_hash_cache.lock().unwrap().as_ref() {
+        if *cached_version == version {
+            return Ok(hash.clone());
+        }
+    }
+    let hash = graph_hash(&graph)?;
+    *kb.content_hash_cache.lock().unwrap() = Some((version, hash.clone()));
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_store::EventStore;
+
+    #[test]
+    fn test_content_hash_ignores_insertion_order() {
+        let auth = Entity::with_observations(
+            "Auth".to_string(),
+            "Module".to_string(),
+            vec!["Owner: core".to_string(), "Lang: Rust".to_string()],
+        );
+        let billing = Entity::new("Billing".to_string(), "Module".to_string());
+        let uses = Relation::new(
+            "Billing".to_string(),
+            "Auth".to_string(),
+            "uses".to_string(),
+        );
+        let owns = Relation::new(
+            "Auth".to_string(),
+            "Billing".to_string(),
+            "owns".to_string(),
+        );
+
+        let graph = KnowledgeGraph::with_data(
+            vec![auth.clone(), billing.clone()],
+            vec![uses.clone(), owns.clone()],
+        );
+        let mut reordered_auth = auth.clone();
+        reordered_auth.observations.reverse();
+        let reordered = KnowledgeGraph::with_data(vec![billing, reordered_auth], vec![owns, uses]);
+        let hash = graph_hash(&graph).unwrap();
+        assert_eq!(hash.len(), 16);
+        assert_eq!(hash, graph_hash(&reordered).unwrap());
+
+        let mut stamped = graph.clone();
+        stamped.entities[0].updated_at = 1_700_000_000;
+        stamped.entities[0].updated_by = "bob".to_string();
+        assert_eq!(hash, graph_hash(&stamped).unwrap());
+
+        let mut changed = graph.clone();
+        changed.entities[1].acl = vec!["alice".to_string()];
+        assert_ne!(hash, graph_hash(&changed).unwrap());
+        changed = graph.clone();
+        changed.relations.pop();
+        assert_ne!(hash, graph_hash(&changed).unwrap());
+    }
+
+    #[test]
+    fn test_content_hash_matches_replayed_state() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        kb.create_entities(vec![
+            Entity::new("Auth".to_string(), "Module".to_string()),
+            Entity::new("Billing".to_string(), "Module".to_string()),
+        ])
+        .unwrap();
+        kb.create_relations(vec![Relation::new(
+            "Billing".to_string(),
+            "Auth".to_string(),
+            "uses".to_string(),
+        )])
+        .unwrap();
+
+        let events = kb
+            .event_store
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .load_events()
+            .unwrap();
+        let (mut entities, mut relations) = (Vec::new(), Vec::new());
+        for event in &events {
+            EventStore::apply_event(&mut entities, &mut relations, event).unwrap();
+        }
+        let replayed = KnowledgeGraph::with_data(entities, relations);
+        assert_eq!(content_hash(&kb).unwrap(), graph_hash(&replayed).unwrap());
+    }
+
+    #[test]
+    fn test_content_hash_cached_per_version() {
+        let path = format!("target/test_fingerprint_cache_{}.jsonl", std::process::id());
+        let kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        kb.create_entities(vec![Entity::new("Auth".to_string(), "Module".to_string())])
+            .unwrap();
+
+        let hash = content_hash(&kb).unwrap();
+        let version = kb.version();
+        assert_eq!(
+            kb.content_hash_cache.lock().unwrap().clone(),
+            Some((version, hash.clone()))
+        );
+        assert_eq!(content_hash(&kb).unwrap(), hash);
+
+        kb.create_entities(vec![Entity::new("Billing".to_string(), "Module".to_string())])
+            .unwrap();
+        let changed = content_hash(&kb).unwrap();
+        assert_ne!(changed, hash);
+        assert_eq!(changed, graph_hash(&kb.graph.read().unwrap()).unwrap());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
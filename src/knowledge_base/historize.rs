@@ -0,0 +1,257 @@
+//! Bulk-set relation validity windows from creation times or attributes
+
+use std::collections::HashMap;
+
+use serde_json::json;
+
+use crate::api::websocket::events::GraphEvent;
+use crate::types::{
+    EventType, HistorizeReport, HistorizeRule, KnowledgeGraph, McpResult, TimeSource,
+    ValidityChange,
+};
+use crate::utils::time::current_timestamp;
+
+use super::crud::{self, PendingEffects};
+use super::query::relation_matcher;
+use super::{acl, KnowledgeBase};
+
+/// Compute every validity change without touching the graph
+fn collect_changes(
+    kb: &KnowledgeBase,
+    graph: &KnowledgeGraph,
+    rule: &HistorizeRule,
+) -> McpResult<HistorizeReport> {
+    if rule.valid_from.is_none() && rule.valid_to.is_none() {
+        return Err("Rule must set validFrom, validTo or both".into());
+    }
+
+    let entities: HashMap<&str, _> = graph
+        .entities
+        .iter()
+        .map(|e| (e.name.as_str(), e))
+        .collect();
+    let matches = relation_matcher(kb, graph, &rule.query);
+    let mut report = HistorizeReport::default();
+
+    for relation in graph.relations.iter().filter(|r| matches(r)) {
+        report.matched += 1;
+        let subject = format!(
+            "{} -[{}]-> {}",
+            relation.from, relation.relation_type, relation.to
+        );
+        let (Some(source), Some(target)) = (
+            entities.get(relation.from.as_str()),
+            entities.get(relation.to.as_str()),
+        ) else {
+            report
+                .skipped
+                .push(format!("{}: dangling relation", subject));
+            continue;
+        };
+
+        let bound = |source_rule: &Option<TimeSource>, current: Option<u64>| match source_rule {
+            Some(time_source) if rule.overwrite || current.is_none() => {
+                time_source.resolve(relation, source, target).map(Some)
+            }
+            _ => Ok(current),
+        };
+        let window = bound(&rule.valid_from, relation.valid_from)
+            .and_then(|from| Ok((from, bound(&rule.valid_to, relation.valid_to)?)));
+        let (valid_from, valid_to) = match window {
+            Ok(window) => window,
+            Err(reason) => {
+                report.skipped.push(format!("{}: {}", subject, reason));
+                continue;
+            }
+        };
+
+        if let (Some(from), Some(to)) = (valid_from, valid_to) {
+            if from > to {
+                report.skipped.push(format!(
+                    "{}: validFrom {} would be after validTo {}",
+                    subject, from, to
+                ));
+                continue;
+            }
+        }
+        if (valid_from, valid_to) == (relation.valid_from, relation.valid_to) {
+            continue;
+        }
+
+        report.changes.push(ValidityChange {
+            from: relation.from.clone(),
+            to: relation.to.clone(),
+            relation_type: relation.relation_type.clone(),
+            valid_from,
+            valid_to,
+            previous_valid_from: relation.valid_from,
+            previous_valid_to: relation.valid_to,
+        });
+    }
+
+    Ok(report)
+}
+
+/// Preview the windows a rule would set (dry run)
+pub fn preview_historize_relations(
+    kb: &KnowledgeBase,
+    rule: &HistorizeRule,
+) -> McpResult<HistorizeReport> {
+    let graph = kb.graph.read().unwrap();
+    collect_changes(kb, &graph, rule)
+}
+
+/// Set validity windows on every relation matching `rule` (thread-safe: holds write lock during entire operation)
+///
+/// Each changed relation is recorded as a `RelationUpdated` event carrying
+/// the bounds that changed. Relations whose time source cannot be resolved,
+/// or whose window would end before it starts, are skipped and reported.
+/// Nothing changes if the current caller may not change an endpoint of a
+/// changed relation.
+pub fn historize_relations(kb: &KnowledgeBase, rule: &HistorizeRule) -> McpResult<HistorizeReport> {
+    crud::commit(kb, |graph, pending| {
+        let report = collect_changes(kb, graph, rule)?;
+        let endpoints = report
+            .changes
+            .iter()
+            .flat_map(|c| [c.from.as_str(), c.to.as_str()]);
+        acl::check_writable(graph, endpoints)?;
+        apply_changes(kb, graph, &report.changes, pending);
+        Ok(report)
+    })
+}
+
+fn apply_changes(
+    kb: &KnowledgeBase,
+    graph: &mut KnowledgeGraph,
+    changes: &[ValidityChange],
+    pending: &mut PendingEffects,
+) {
+    let now = current_timestamp();
+
+    for change in changes {
+        let Some(relation) = graph.relations.iter_mut().find(|r| {
+            r.from == change.from && r.to == change.to && r.relation_type == change.relation_type
+        }) else {
+            continue;
+        };
+
+        let mut data = json!({
+            "from": relation.from,
+            "to": relation.to,
+            "relation_type": relation.relation_type
+        });
+        if change.valid_from != relation.valid_from {
+            data["valid_from"] = json!(change.valid_from);
+        }
+        if change.valid_to != relation.valid_to {
+            data["valid_to"] = json!(change.valid_to);
+        }
+        pending.event(kb, EventType::RelationUpdated, data);
+
+        relation.valid_from = change.valid_from;
+        relation.valid_to = change.valid_to;
+        relation.updated_at = now;
+
+        pending.broadcast(GraphEvent::RelationUpdated {
+            payload: relation.clone(),
+            user: Some(kb.acting_user()),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_store::EventStore;
+    use crate::types::{Entity, Observation, Relation, RelationQuery};
+
+    #[test]
+    fn test_historize_relations_replays() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        kb.create_entities(
+            ["Alice", "Acme", "Initech"]
+                .map(|n| Entity::new(n.to_string(), "Org".to_string()))
+                .to_vec(),
+        )
+        .unwrap();
+        kb.add_observations(vec![Observation::new(
+            "Initech".to_string(),
+            vec!["Founded: 2020-01-01".to_string()],
+        )])
+        .unwrap();
+        kb.create_relations(vec![
+            Relation::new(
+                "Alice".to_string(),
+                "Acme".to_string(),
+                "works_at".to_string(),
+            ),
+            Relation::with_validity(
+                "Alice".to_string(),
+                "Initech".to_string(),
+                "works_at".to_string(),
+                None,
+                Some(1000),
+            ),
+        ])
+        .unwrap();
+
+        let rule = HistorizeRule {
+            query: RelationQuery {
+                relation_type: Some("works_at".to_string()),
+                ..Default::default()
+            },
+            valid_from: Some(TimeSource::parse("target_attribute:Founded").unwrap()),
+            ..Default::default()
+        };
+        let preview = kb.preview_historize_relations(&rule).unwrap();
+        assert_eq!(preview.matched, 2);
+        assert!(preview.changes.is_empty());
+        assert_eq!(preview.skipped.len(), 2);
+        assert!(preview.skipped[0].contains("no 'Founded' attribute on 'Acme'"));
+        assert!(preview.skipped[1].contains("would be after validTo"));
+
+        let rule = HistorizeRule {
+            valid_to: None,
+            overwrite: true,
+            valid_from: Some(TimeSource::RelationCreatedAt),
+            ..rule
+        };
+        let report = kb.historize_relations(&rule).unwrap();
+        assert_eq!(report.changes.len(), 1);
+        assert_eq!(report.skipped.len(), 1);
+        assert!(kb
+            .preview_historize_relations(&rule)
+            .unwrap()
+            .changes
+            .is_empty());
+
+        let events = kb
+            .event_store
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .load_events()
+            .unwrap();
+        let (mut entities, mut relations) = (Vec::new(), Vec::new());
+        for event in &events {
+            EventStore::apply_event(&mut entities, &mut relations, event).unwrap();
+        }
+        assert_eq!(
+            events.last().unwrap().event_type,
+            EventType::RelationUpdated
+        );
+        let graph = kb.graph.read().unwrap();
+        let acme = |relations: &[Relation]| {
+            relations
+                .iter()
+                .find(|r| r.to == "Acme")
+                .unwrap()
+                .valid_from
+        };
+        assert!(acme(&graph.relations).is_some());
+        assert_eq!(acme(&relations), acme(&graph.relations));
+    }
+}
@@ -0,0 +1,103 @@
+//! Merge an external graph into the knowledge base
+
+use std::collections::HashMap;
+
+use crate::types::{
+    Entity, EntityTypeConflict, ImportReport, KnowledgeGraph, McpResult, Observation,
+};
+use crate::validation::ValidationWarning;
+
+use super::crud::{self, PendingEffects};
+use super::{acl, KnowledgeBase};
+
+/// Upsert every entity and relation of `incoming` in a single commit
+///
+/// New entities are created, observations of existing entities are unioned, and
+/// duplicate relations are skipped. An entity whose type differs from the stored
+/// one is reported as a conflict and left untouched. Nothing is imported if
+/// the current caller may not change one of the existing entities it names
+/// (see acl.rs).
+pub fn import_graph(
+    kb: &KnowledgeBase,
+    incoming: KnowledgeGraph,
+) -> McpResult<(ImportReport, Vec<ValidationWarning>)> {
+    crud::commit_with_warnings(kb, |graph, pending| {
+        apply_import(kb, graph, incoming, pending)
+    })
+}
+
+/// Upsert `incoming` into `graph`, queueing side effects on `pending`
+pub(super) fn apply_import(
+    kb: &KnowledgeBase,
+    graph: &mut KnowledgeGraph,
+    mut incoming: KnowledgeGraph,
+    pending: &mut PendingEffects,
+) -> McpResult<ImportReport> {
+    crud::resolve_relation_endpoints(kb, graph, &mut incoming.relations);
+    let names = incoming.entities.iter().map(|e| e.name.as_str());
+    acl::check_writable(graph, names.chain(crud::relation_endpoints(&incoming.relations)))?;
+
+    let existing: HashMap<String, String> = graph
+        .entities
+        .iter()
+        .map(|e| (e.name.clone(), e.entity_type.clone()))
+        .collect();
+
+    let mut report = ImportReport::default();
+    let mut to_create: Vec<Entity> = Vec::new();
+    let mut created_index: HashMap<String, usize> = HashMap::new();
+    let mut to_merge: Vec<Observation> = Vec::new();
+    let mut merge_index: HashMap<String, usize> = HashMap::new();
+
+    for entity in incoming.entities {
+        let known_type = existing.get(&entity.name).or_else(|| {
+            created_index
+                .get(&entity.name)
+                .map(|&i| &to_create[i].entity_type)
+        });
+
+        match known_type {
+            Some(known) if *known != entity.entity_type => {
+                report.conflicts.push(EntityTypeConflict {
+                    name: entity.name,
+                    existing_type: known.clone(),
+                    imported_type: entity.entity_type,
+                });
+            }
+            Some(_) => {
+                // Names repeated within the payload fold into one create or merge
+                let contents = match created_index.get(&entity.name) {
+                    Some(&i) => &mut to_create[i].observations,
+                    None => {
+                        let i = *merge_index.entry(entity.name.clone()).or_insert_with(|| {
+                            to_merge.push(Observation::new(entity.name.clone(), Vec::new()));
+                            to_merge.len() - 1
+                        });
+                        &mut to_merge[i].contents
+                    }
+                };
+                for observation in entity.observations {
+                    if !contents.contains(&observation) {
+                        contents.push(observation);
+                    }
+                }
+            }
+            None => {
+                created_index.insert(entity.name.clone(), to_create.len());
+                to_create.push(entity);
+            }
+        }
+    }
+
+    let merging = to_merge.len();
+    report.entities_created = crud::apply_create_entities(kb, graph, to_create, pending).len();
+    report.entities_updated = crud::apply_add_observations(kb, graph, to_merge, pending).len();
+    report.entities_skipped = merging - report.entities_updated;
+
+    let relation_count = incoming.relations.len();
+    report.relations_created =
+        crud::apply_create_relations(kb, graph, incoming.relations, pending).len();
+    report.relations_skipped = relation_count - report.relations_created;
+
+    Ok(report)
+}
@@ -0,0 +1,231 @@
+//! Cache of inference results
+//!
+//! Results are keyed by the request (target, minimum confidence, depth and
+//! rule selection) and tagged with the graph version, a counter bumped under
+//! the write lock by every mutation. A lookup at a newer version drops every
+//! entry, so a cached result never outlives the graph it was computed from.
+//!
+//! The cache holds at most `MEMORY_INFERENCE_CACHE_SIZE` results (default
+//! 128), evicting the least recently used one; `0` disables it.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::env;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+use crate::types::{InferStats, InferredRelation, McpResult};
+
+use super::InferenceEngine;
+use crate::knowledge_base::{acl, cost};
+use crate::knowledge_base::KnowledgeBase;
+
+/// Default number of cached inference results
+const DEFAULT_CAPACITY: usize = 128;
+
+/// Parameters that determine an inference result
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct InferenceKey {
+    target: String,
+    min_confidence: u32,
+    max_depth: usize,
+    /// Selected rules, sorted (None = all rules)
+    rules: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    /// Graph version every entry was computed at
+    version: u64,
+    /// Logical clock for least-recently-used eviction
+    clock: u64,
+    entries: HashMap<InferenceKey, (u64, Vec<InferredRelation>, InferStats)>,
+}
+
+/// Bounded LRU cache of inference results for one knowledge base
+#[derive(Debug)]
+pub(crate) struct InferenceCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+impl InferenceCache {
+    /// Create a cache holding up to `capacity` results (0 disables caching)
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Create a cache sized by `MEMORY_INFERENCE_CACHE_SIZE`
+    pub(crate) fn from_env() -> Self {
+        let capacity = env::var("MEMORY_INFERENCE_CACHE_SIZE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_CAPACITY);
+        Self::new(capacity)
+    }
+
+    fn get(&self, version: u64, key: &InferenceKey) -> Option<(Vec<InferredRelation>, InferStats)> {
+        let mut state = self.state.lock().unwrap();
+        if state.version != version {
+            state.entries.clear();
+            state.version = version;
+            return None;
+        }
+        state.clock += 1;
+        let clock = state.clock;
+        state.entries.get_mut(key).map(|(used, relations, stats)| {
+            *used = clock;
+            (relations.clone(), stats.clone())
+        })
+    }
+
+    fn insert(
+        &self,
+        version: u64,
+        key: InferenceKey,
+        relations: &[InferredRelation],
+        stats: &InferStats,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        // Computed from a graph that has changed since
+        if version < state.version {
+            return;
+        }
+        if version > state.version {
+            state.entries.clear();
+            state.version = version;
+        }
+
+        if state.entries.len() >= self.capacity && !state.entries.contains_key(&key) {
+            if let Some(oldest) = state
+                .entries
+                .iter()
+                .min_by_key(|(_, (used, _, _))| *used)
+                .map(|(key, _)| key.clone())
+            {
+                state.entries.remove(&oldest);
+            }
+        }
+        state.clock += 1;
+        let clock = state.clock;
+        state
+            .entries
+            .insert(key, (clock, relations.to_vec(), stats.clone()));
+    }
+}
+
+/// Run inference for `target`, reusing a cached result if the graph is unchanged
+///
+/// Cached results are returned with `stats.cached` set.
+pub(crate) fn infer_cached(
+    kb: &KnowledgeBase,
+    target: &str,
+    min_confidence: f32,
+    max_depth: usize,
+    rules: Option<&[String]>,
+) -> McpResult<(Vec<InferredRelation>, InferStats)> {
+    let mut engine = InferenceEngine::with_max_depth(max_depth);
+    if let Some(rules) = rules {
+        engine.select_rules(rules)?;
+    }
+
+    let key = InferenceKey {
+        target: target.to_string(),
+        min_confidence: min_confidence.to_bits(),
+        max_depth,
+        rules: rules.map(|rules| {
+            let mut rules = rules.to_vec();
+            rules.sort_unstable();
+            rules.dedup();
+            rules
+        }),
+    };
+
+    // The version is read under the same lock as the graph it describes. Results
+    // are only cached for callers who see the whole graph.
+    let (version, graph) = {
+        let graph = kb.graph.read().unwrap();
+        let view = acl::view(&graph);
+        let version = match view {
+            Cow::Borrowed(_) => Some(kb.graph_version.load(Ordering::Acquire)),
+            Cow::Owned(_) => None,
+        };
+        if let Some((relations, mut stats)) =
+            version.and_then(|version| kb.inference_cache.get(version, &key))
+        {
+            stats.cached = true;
+            return Ok((relations, stats));
+        }
+        cost::check_infer(kb, &view, max_depth, &engine)?;
+        (version, view.into_owned())
+    };
+
+    let (relations, stats) = engine.infer(&graph, target, min_confidence);
+    if let Some(version) = version {
+        kb.inference_cache.insert(version, key, &relations, &stats);
+    }
+    Ok((relations, stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Entity, Relation};
+
+    #[test]
+    fn test_cache_invalidated_by_mutation() {
+        let path = format!("target/test_infer_cache_{}.jsonl", std::process::id());
+        let kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        let entities = ["A", "B", "C", "D"]
+            .iter()
+            .map(|n| Entity::new(n.to_string(), "Module".to_string()))
+            .collect();
+        kb.create_entities(entities).unwrap();
+        let depends = |from: &str, to: &str| {
+            Relation::new(from.to_string(), to.to_string(), "depends_on".to_string())
+        };
+        kb.create_relations(vec![depends("A", "B"), depends("B", "C")])
+            .unwrap();
+
+        let (first, stats) = infer_cached(&kb, "A", 0.5, 3, None).unwrap();
+        assert!(!stats.cached);
+        let (second, stats) = infer_cached(&kb, "A", 0.5, 3, None).unwrap();
+        assert!(stats.cached);
+        assert_eq!(first.len(), second.len());
+        // A different request is computed separately
+        assert!(!infer_cached(&kb, "A", 0.9, 3, None).unwrap().1.cached);
+
+        kb.create_relations(vec![depends("C", "D")]).unwrap();
+        let (after, stats) = infer_cached(&kb, "A", 0.5, 3, None).unwrap();
+        assert!(!stats.cached);
+        assert!(after.iter().any(|r| r.relation.to == "D"));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let cache = InferenceCache::new(2);
+        let key = |target: &str| InferenceKey {
+            target: target.to_string(),
+            min_confidence: 0,
+            max_depth: 1,
+            rules: None,
+        };
+        let stats = InferStats::default();
+        cache.insert(1, key("A"), &[], &stats);
+        cache.insert(1, key("B"), &[], &stats);
+        assert!(cache.get(1, &key("A")).is_some());
+        cache.insert(1, key("C"), &[], &stats);
+
+        assert!(cache.get(1, &key("A")).is_some());
+        assert!(cache.get(1, &key("B")).is_none());
+        assert!(cache.get(2, &key("A")).is_none());
+    }
+}
@@ -3,8 +3,11 @@
 //! This module provides the core inference engine that applies logical rules
 //! to discover hidden relations in the knowledge graph.
 
+pub(crate) mod cache;
 pub mod rules;
 
+use std::collections::HashSet;
+
 use crate::types::{InferStats, InferredRelation, KnowledgeGraph};
 
 /// Trait for inference rules
@@ -15,6 +18,11 @@ pub trait InferenceRule: Send + Sync {
     /// Get the name of this rule
     fn name(&self) -> &str;
 
+    /// One-line description shown when listing rules
+    fn description(&self) -> &str {
+        ""
+    }
+
     /// Apply the rule to infer relations for a target entity
     ///
     /// # Arguments
@@ -33,8 +41,14 @@ pub trait InferenceRule: Send + Sync {
 }
 
 /// The inference engine that manages and applies rules
+///
+/// Every registered rule is enabled until switched off with [`set_enabled`].
+///
+/// [`set_enabled`]: InferenceEngine::set_enabled
 pub struct InferenceEngine {
     rules: Vec<Box<dyn InferenceRule>>,
+    /// Names of rules skipped by `infer`
+    disabled: HashSet<String>,
 }
 
 impl InferenceEngine {
@@ -47,12 +61,16 @@ impl InferenceEngine {
     pub fn with_max_depth(max_depth: usize) -> Self {
         Self {
             rules: vec![Box::new(rules::TransitiveDependencyRule::new(max_depth))],
+            disabled: HashSet::new(),
         }
     }
 
     /// Create an empty inference engine (no rules)
     pub fn empty() -> Self {
-        Self { rules: Vec::new() }
+        Self {
+            rules: Vec::new(),
+            disabled: HashSet::new(),
+        }
     }
 
     /// Register a new inference rule
@@ -65,7 +83,63 @@ impl InferenceEngine {
         self.rules.len()
     }
 
-    /// Run all rules and collect inferred relations
+    /// Names of all registered rules, in application order
+    pub fn list_rules(&self) -> Vec<&str> {
+        self.rules.iter().map(|r| r.name()).collect()
+    }
+
+    /// Registered rules with their description and enabled flag
+    pub fn rules(&self) -> impl Iterator<Item = (&str, &str, bool)> {
+        self.rules
+            .iter()
+            .map(|r| (r.name(), r.description(), self.is_enabled(r.name())))
+    }
+
+    /// Check whether a rule is registered and enabled
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.rules.iter().any(|r| r.name() == name) && !self.disabled.contains(name)
+    }
+
+    /// Enable or disable a rule by name
+    ///
+    /// Returns false if no rule with that name is registered.
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        if !self.rules.iter().any(|r| r.name() == name) {
+            return false;
+        }
+        if enabled {
+            self.disabled.remove(name);
+        } else {
+            self.disabled.insert(name.to_string());
+        }
+        true
+    }
+
+    /// Enable exactly the named rules and disable all others
+    ///
+    /// Fails with the first unknown name, leaving the flags unchanged.
+    pub fn select_rules(&mut self, names: &[String]) -> Result<(), String> {
+        if let Some(unknown) = names
+            .iter()
+            .find(|n| !self.rules.iter().any(|r| r.name() == n.as_str()))
+        {
+            return Err(format!(
+                "Unknown inference rule '{}'. Available: {}",
+                unknown,
+                self.list_rules().join(", ")
+            ));
+        }
+
+        self.disabled = self
+            .rules
+            .iter()
+            .map(|r| r.name().to_string())
+            .filter(|n| !names.contains(n))
+            .collect();
+        Ok(())
+    }
+
+    /// Run all enabled rules and collect inferred relations
     ///
     /// # Arguments
     /// * `graph` - The knowledge graph to analyze
@@ -84,7 +158,7 @@ impl InferenceEngine {
         let mut total_stats = InferStats::default();
         let start_time = std::time::Instant::now();
 
-        for rule in &self.rules {
+        for rule in self.rules.iter().filter(|r| !self.disabled.contains(r.name())) {
             let (relations, stats) = rule.apply(graph, target, min_confidence);
             all_inferred.extend(relations);
 
@@ -121,6 +195,23 @@ mod tests {
         assert_eq!(engine.rule_count(), 0);
     }
 
+    #[test]
+    fn test_rule_selection() {
+        let mut engine = InferenceEngine::new();
+        assert_eq!(engine.list_rules(), vec!["TransitiveDependencyRule"]);
+        assert!(engine.is_enabled("TransitiveDependencyRule"));
+
+        assert!(engine.set_enabled("TransitiveDependencyRule", false));
+        assert!(!engine.is_enabled("TransitiveDependencyRule"));
+        assert!(!engine.set_enabled("NoSuchRule", true));
+
+        engine
+            .select_rules(&["TransitiveDependencyRule".to_string()])
+            .unwrap();
+        assert!(engine.is_enabled("TransitiveDependencyRule"));
+        assert!(engine.select_rules(&["NoSuchRule".to_string()]).is_err());
+    }
+
     #[test]
     fn test_infer_on_empty_graph() {
         let engine = InferenceEngine::new();
@@ -72,6 +72,10 @@ impl InferenceRule for TransitiveDependencyRule {
         "TransitiveDependencyRule"
     }
 
+    fn description(&self) -> &str {
+        "Follows outgoing relations up to max depth, decaying confidence per hop"
+    }
+
     fn apply(
         &self,
         graph: &KnowledgeGraph,
@@ -138,14 +142,17 @@ impl InferenceRule for TransitiveDependencyRule {
                 // If path length >= 3, we have a transitive relation (A -> B -> C)
                 if new_path.len() >= 3 {
                     // Create inferred relation from start to current end
+                    let now = crate::utils::current_timestamp();
                     let inferred_relation = Relation {
                         from: target.to_string(),
                         to: next_node.clone(),
                         relation_type: format!("inferred_{}", new_rel_types.first().unwrap_or(&"relation".to_string())),
                         created_by: "InferenceEngine".to_string(),
-                        created_at: crate::utils::current_timestamp(),
+                        created_at: now,
+                        updated_at: now,
                         valid_from: None,
                         valid_to: None,
+                        properties: Default::default(),
                     };
 
                     let explanation = Self::generate_explanation(&new_path, &new_rel_types);
@@ -254,6 +261,23 @@ mod tests {
         assert!(!inferred.iter().any(|i| i.relation.to == "A")); // No self-inference
     }
 
+    #[test]
+    fn test_self_loops_are_not_inferred() {
+        let mut graph = create_test_graph();
+        graph
+            .relations
+            .push(Relation::new("A".to_string(), "A".to_string(), "depends_on".to_string()));
+        graph
+            .relations
+            .push(Relation::new("B".to_string(), "B".to_string(), "depends_on".to_string()));
+        let rule = TransitiveDependencyRule::new(3);
+        let (inferred, _) = rule.apply(&graph, "A", 0.5);
+
+        // Loops add no paths: still only A -> C and A -> D
+        let targets: Vec<&str> = inferred.iter().map(|i| i.relation.to.as_str()).collect();
+        assert_eq!(targets, vec!["C", "D"]);
+    }
+
     #[test]
     fn test_confidence_threshold() {
         let graph = create_test_graph();
@@ -0,0 +1,251 @@
+//! Loading the legacy JSONL memory file
+//!
+//! The first line may be a `"type": "graph_metadata"` header holding the
+//! graph metadata; it is read by `load_metadata_from_file` and skipped when
+//! loading entities and relations.
+//!
+//! Lines that parse as neither an entity nor a relation are skipped, logged to
+//! stderr with their line number and the reason, and appended to
+//! `<memory_file>.quarantine` so they can be repaired by hand. Each quarantine
+//! record is a JSON object holding the line number, the reason and the raw
+//! line; the next write of the memory file drops the corrupt lines, so the
+//! quarantine file is the only copy left.
+//!
+//! Set `MEMORY_MAX_CORRUPT_PERCENT` to refuse loading a file when more than
+//! that percentage of its non-empty lines is unparseable, which guards against
+//! starting on a truncated file as if it were an empty graph. Without it,
+//! corrupt lines never block loading.
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::types::{Entity, GraphMetadata, KnowledgeGraph, McpResult, Relation};
+use crate::utils::time::current_timestamp;
+
+/// Too much of the memory file is unparseable to load it safely
+#[derive(Debug)]
+pub struct CorruptFileError {
+    pub path: String,
+    pub corrupt: usize,
+    pub total: usize,
+    pub max_percent: f64,
+}
+
+impl fmt::Display for CorruptFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} lines in {} are unparseable ({:.1}%, limit {}%); refusing to load it. \
+             Skipped lines were saved to {}",
+            self.corrupt,
+            self.total,
+            self.path,
+            self.corrupt as f64 * 100.0 / self.total as f64,
+            self.max_percent,
+            quarantine_path(&self.path)
+        )
+    }
+}
+
+impl Error for CorruptFileError {}
+
+/// A skipped line as written to the quarantine file
+#[derive(Debug, Serialize)]
+struct QuarantinedLine<'a> {
+    line: usize,
+    reason: String,
+    content: &'a str,
+    #[serde(rename = "quarantinedAt")]
+    quarantined_at: u64,
+}
+
+pub(super) enum Record {
+    Entity(Entity),
+    Relation(Relation),
+    Metadata,
+}
+
+/// Path of the quarantine file for a memory file
+pub fn quarantine_path(file_path: &str) -> String {
+    format!("{}.quarantine", file_path)
+}
+
+/// Read `MEMORY_MAX_CORRUPT_PERCENT` (0-100); None disables the check
+pub fn max_corrupt_percent_from_env() -> Option<f64> {
+    let value = env::var("MEMORY_MAX_CORRUPT_PERCENT").ok()?;
+    match value.trim().parse::<f64>() {
+        Ok(percent) if (0.0..=100.0).contains(&percent) => Some(percent),
+        _ => {
+            eprintln!(
+                "Warning: Ignoring MEMORY_MAX_CORRUPT_PERCENT={} (expected 0-100)",
+                value
+            );
+            None
+        }
+    }
+}
+
+/// Load the memory file, quarantining corrupt lines
+pub(super) fn load_graph_from_file(file_path: &str) -> McpResult<KnowledgeGraph> {
+    load_with_limit(file_path, max_corrupt_percent_from_env())
+}
+
+/// Load the memory file, failing if more than `max_percent` of lines are corrupt
+pub(super) fn load_with_limit(
+    file_path: &str,
+    max_percent: Option<f64>,
+) -> McpResult<KnowledgeGraph> {
+    if !Path::new(file_path).exists() {
+        return Ok(KnowledgeGraph::default());
+    }
+
+    let content = fs::read_to_string(file_path)?;
+    let mut graph = KnowledgeGraph::default();
+    let mut corrupt = Vec::new();
+    let mut total = 0;
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        total += 1;
+
+        match parse_line(line) {
+            Ok(Record::Entity(entity)) => graph.entities.push(entity),
+            Ok(Record::Relation(relation)) => graph.relations.push(relation),
+            Ok(Record::Metadata) => {}
+            Err(reason) => {
+                eprintln!(
+                    "Warning: Skipping line {} of {}: {}",
+                    index + 1,
+                    file_path,
+                    reason
+                );
+                corrupt.push(QuarantinedLine {
+                    line: index + 1,
+                    reason,
+                    content: line,
+                    quarantined_at: current_timestamp(),
+                });
+            }
+        }
+    }
+
+    if !corrupt.is_empty() {
+        if let Err(e) = quarantine(file_path, &corrupt) {
+            eprintln!("Warning: Failed to write quarantine file: {}", e);
+        }
+    }
+
+    if let Some(max_percent) = max_percent {
+        if total > 0 && corrupt.len() as f64 * 100.0 / total as f64 > max_percent {
+            return Err(Box::new(CorruptFileError {
+                path: file_path.to_string(),
+                corrupt: corrupt.len(),
+                total,
+                max_percent,
+            }));
+        }
+    }
+
+    Ok(graph)
+}
+
+/// Read the graph metadata header of the memory file
+///
+/// Files written before graph metadata existed have no header and yield
+/// empty metadata.
+pub(super) fn load_metadata_from_file(file_path: &str) -> McpResult<GraphMetadata> {
+    if !Path::new(file_path).exists() {
+        return Ok(GraphMetadata::default());
+    }
+
+    let content = fs::read_to_string(file_path)?;
+    Ok(content
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .and_then(|line| GraphMetadata::from_header_line(line.trim()))
+        .unwrap_or_default())
+}
+
+pub(super) fn parse_line(line: &str) -> Result<Record, String> {
+    if GraphMetadata::from_header_line(line).is_some() {
+        return Ok(Record::Metadata);
+    }
+
+    if let Ok(entity) = serde_json::from_str::<Entity>(line) {
+        if !entity.name.is_empty() && !entity.entity_type.is_empty() {
+            return Ok(Record::Entity(entity));
+        }
+    }
+
+    match serde_json::from_str::<Relation>(line) {
+        Ok(mut relation) if !relation.from.is_empty() && !relation.to.is_empty() => {
+            // Relations written before updatedAt existed were last touched at creation
+            if relation.updated_at == 0 {
+                relation.updated_at = relation.created_at;
+            }
+            Ok(Record::Relation(relation))
+        }
+        Ok(_) => Err("relation has an empty endpoint".to_string()),
+        Err(e) => match serde_json::from_str::<Value>(line) {
+            Ok(_) => Err(format!("not an entity or relation ({})", e)),
+            Err(e) => Err(format!("invalid JSON ({})", e)),
+        },
+    }
+}
+
+/// Append skipped lines to the quarantine file
+fn quarantine(file_path: &str, lines: &[QuarantinedLine]) -> McpResult<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(quarantine_path(file_path))?;
+
+    for line in lines {
+        writeln!(file, "{}", serde_json::to_string(line)?)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refuses_mostly_corrupt_file() {
+        let path = format!("target/test_load_corrupt_{}.jsonl", std::process::id());
+        fs::write(
+            &path,
+            "{\"name\":\"A\",\"entityType\":\"Module\"}\n{\"name\":\"B\",\"ent\nnot json\n",
+        )
+        .unwrap();
+
+        // 2 of 3 lines are corrupt
+        let err = load_with_limit(&path, Some(50.0)).unwrap_err();
+        assert!(err.is::<CorruptFileError>());
+        let graph = load_with_limit(&path, Some(70.0)).unwrap();
+        assert_eq!(graph.entities.len(), 1);
+        assert_eq!(load_with_limit(&path, None).unwrap().entities.len(), 1);
+
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(quarantine_path(&path));
+    }
+
+    #[test]
+    fn test_backfills_relation_updated_at() {
+        let line = "{\"from\":\"A\",\"to\":\"B\",\"relationType\":\"uses\",\"createdAt\":1700000000}";
+        match parse_line(line) {
+            Ok(Record::Relation(relation)) => assert_eq!(relation.updated_at, 1_700_000_000),
+            _ => panic!("expected a relation"),
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Graph-level metadata
+//!
+//! In legacy mode the metadata is the header line of `memory.jsonl`, written
+//! with every save of the file. In Event Sourcing mode it lives next to the
+//! event log in `data/graph_metadata.json`, so it survives snapshots and log
+//! rotation without being replayed. Graphs without metadata have neither, and
+//! read back as `GraphMetadata::default()`.
+
+use std::fs;
+
+use crate::event_store::EventStoreConfig;
+use crate::types::{GraphMetadata, McpResult};
+use crate::utils::atomic_write;
+use crate::utils::time::current_timestamp;
+
+use super::KnowledgeBase;
+
+/// Current graph metadata
+pub fn graph_metadata(kb: &KnowledgeBase) -> GraphMetadata {
+    kb.graph_metadata.read().unwrap().clone()
+}
+
+/// Replace the graph metadata and persist it (thread-safe)
+///
+/// Holds the graph read lock, so no mutation rewrites `memory.jsonl` in
+/// between, and the metadata write lock until the metadata is saved.
+/// `updatedBy`/`updatedAt` are stamped here; values passed in are ignored.
+/// Returns the metadata as stored.
+pub fn set_graph_metadata(
+    kb: &KnowledgeBase,
+    mut metadata: GraphMetadata,
+) -> McpResult<GraphMetadata> {
+    let graph = kb.graph.read().unwrap();
+    let mut current = kb.graph_metadata.write().unwrap();
+
+    metadata.updated_by = kb.acting_user();
+    metadata.updated_at = current_timestamp();
+
+    if !kb.scratch {
+        if let Some(ref event_store) = kb.event_store {
+            let config = event_store.lock().unwrap().config().clone();
+            save_sidecar(&config, &metadata)?;
+        } else {
+            kb.persist_with_metadata(&graph, &metadata)?;
+        }
+    }
+
+    *current = metadata.clone();
+    Ok(metadata)
+}
+
+/// Read `data/graph_metadata.json`, empty if it does not exist
+pub(super) fn load_sidecar(config: &EventStoreConfig) -> McpResult<GraphMetadata> {
+    let path = config.graph_metadata_path();
+    if !path.exists() {
+        return Ok(GraphMetadata::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Write `data/graph_metadata.json` atomically
+pub(super) fn save_sidecar(config: &EventStoreConfig, metadata: &GraphMetadata) -> McpResult<()> {
+    let path = config.graph_metadata_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    atomic_write(&path, &serde_json::to_string_pretty(metadata)?)?;
+    Ok(())
+}
+
+/// Metadata persisted for a knowledge base being constructed
+///
+/// Failures are logged and yield empty metadata, like a missing graph.
+pub(super) fn load_or_default(
+    memory_file_path: &str,
+    config: Option<&EventStoreConfig>,
+) -> GraphMetadata {
+    let loaded = match config {
+        Some(config) => load_sidecar(config),
+        None => super::load::load_metadata_from_file(memory_file_path),
+    };
+    loaded.unwrap_or_else(|e| {
+        eprintln!("Warning: Failed to load graph metadata: {}", e);
+        GraphMetadata::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Entity;
+
+    #[test]
+    fn test_metadata_round_trips_in_both_modes() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("memory.jsonl");
+        let path = path.to_string_lossy().to_string();
+
+        let kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        let mut metadata = GraphMetadata {
+            name: Some("Memory Graph".to_string()),
+            schema_version: Some("2".to_string()),
+            ..Default::default()
+        };
+        metadata
+            .annotations
+            .insert("owner".to_string(), "platform".to_string());
+        kb.set_graph_metadata(metadata).unwrap();
+        kb.create_entities(vec![Entity::new("A".to_string(), "Module".to_string())])
+            .unwrap();
+
+        // Header line first, entities still load
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("{\"type\":\"graph_metadata\""));
+        let reloaded = super::super::load::load_graph_from_file(&path).unwrap();
+        assert_eq!(reloaded.entities.len(), 1);
+        let loaded = load_or_default(&path, None);
+        assert_eq!(loaded.name.as_deref(), Some("Memory Graph"));
+        assert_eq!(loaded.updated_by, "tester");
+
+        let es_dir = temp_dir.path().join("es");
+        let kb = KnowledgeBase::for_testing_event_sourcing(&es_dir, "tester".to_string());
+        kb.create_entities(vec![Entity::new("B".to_string(), "Module".to_string())])
+            .unwrap();
+        kb.set_graph_metadata(loaded.clone()).unwrap();
+        kb.create_snapshot().unwrap();
+
+        let kb = KnowledgeBase::for_testing_event_sourcing(&es_dir, "tester".to_string());
+        assert_eq!(kb.graph_metadata().annotations, loaded.annotations);
+        assert_eq!(kb.graph_metadata().schema_version.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn test_missing_metadata_is_empty() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("memory.jsonl");
+        fs::write(&path, "{\"name\":\"A\",\"entityType\":\"Module\"}\n").unwrap();
+
+        let metadata = load_or_default(&path.to_string_lossy(), None);
+        assert!(metadata.is_empty());
+        let config = EventStoreConfig::with_data_dir(temp_dir.path());
+        assert!(load_or_default("", Some(&config)).is_empty());
+    }
+}
@@ -8,24 +8,75 @@
 //! The knowledge base now supports Event Sourcing mode where all mutations
 //! are recorded as immutable events. Set `MEMORY_EVENT_SOURCING=true` to enable.
 
+mod access;
+mod acl;
+mod backup;
+mod bulk;
+mod by_user;
+mod changes;
+mod cost;
 mod crud;
+mod dedup;
+mod expire;
+mod fingerprint;
+mod historize;
+mod import;
 pub mod inference;
+mod load;
+mod metadata;
+mod normalize;
+mod protect;
 mod query;
+mod resources;
+mod restore;
+mod rewrite;
+mod scratch;
+mod seed;
+mod snapshot;
+mod suggest;
 mod summarize;
+mod tail;
 mod temporal;
+mod transaction;
 mod traversal;
+mod user;
+mod validate;
+mod verify;
 
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::env;
 use std::fs;
 use std::path::Path;
-use std::sync::{Mutex, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::JoinHandle;
 
-use crate::event_store::{EventStore, EventStoreConfig, LogRotation, SnapshotManager};
+use crate::api::websocket::broadcast_event;
+use crate::api::websocket::events::GraphEvent;
+use crate::event_store::{ArchiveInfo, EventStore, EventStoreConfig, LogRotation, SnapshotManager};
 use crate::types::{
-    Entity, EventType, KnowledgeGraph, McpResult, Observation, ObservationDeletion, PathStep,
-    RelatedEntities, Relation, Summary, TraversalResult,
+    BackupMode, BulkReport, Caller, CompactView, Entity, EntityAttributes, EntityDiff, EntityTimeline,
+    EventType, GraphMetadata, GraphSchema, HistorizeReport, HistorizeRule, HotEntity,
+    ImportReport, InferStats, InferredRelation, KnowledgeGraph, McpResult, Observation,
+    ObservationChange, ObservationDeletion, ObservationOrder, ObservationReplacement,
+    ObservationReplacementResult, Op, OpResult, PathStep, PropertyFilter, QueryCost, RecentChange,
+    References, ResultResource, RelatedEntities, RelatedOptions, Relation, RelationNormalization,
+    RelationQuery, RelationSuggestion, RelationTypeChange, RestoreReport,
+    SearchOptions, SearchResult, Summary, TraversalPath, TraversalResult, UserField,
 };
 use crate::utils::time::get_current_user;
+use crate::utils::WriteRetry;
+use crate::validation::{
+    CardinalityConstraints, DedupMode, EntityTemplates, NamePolicy, ObservationLimits,
+    RelationAliases, ValidationWarning,
+};
+
+use access::AccessTracker;
+use changes::ChangeLog;
+use cost::QueryBudget;
+use inference::cache::InferenceCache;
+use resources::ResultLinks;
+use snapshot::SnapshotWorker;
 
 /// Knowledge base with in-memory cache for thread-safe operations
 /// Uses RwLock for better concurrent read performance (read-heavy workload)
@@ -41,14 +92,66 @@ pub struct KnowledgeBase {
     pub(crate) log_rotation: Option<LogRotation>,
     /// Whether Event Sourcing mode is enabled
     pub(crate) event_sourcing_enabled: bool,
+    /// In-memory read counters (not persisted)
+    pub(crate) access_tracker: AccessTracker,
+    /// Recent committed changes kept in memory for debugging
+    pub(crate) recent_changes: ChangeLog,
+    /// Large tool results stored for `resources/read`
+    pub(crate) result_links: ResultLinks,
+    /// Limits applied to incoming observations
+    pub(crate) observation_limits: ObservationLimits,
+    /// How add_observations detects duplicates
+    pub(crate) observation_dedup: DedupMode,
+    /// Functional relation types enforced on create
+    pub(crate) cardinality: CardinalityConstraints,
+    /// Default observation order for read_graph/open_nodes/search_nodes output
+    pub(crate) observation_order: ObservationOrder,
+    /// Relation type aliases applied on create and in query filters
+    pub(crate) relation_aliases: RelationAliases,
+    /// Trimming/case-folding and checks applied to new entity names
+    pub(crate) entity_names: NamePolicy,
+    /// Default observations added to new entities by type
+    pub(crate) entity_templates: EntityTemplates,
+    /// Background snapshot worker (see `spawn_snapshot_worker`)
+    pub(crate) snapshots: SnapshotWorker,
+    /// In-memory session copy: mutations are neither persisted nor broadcast
+    pub(crate) scratch: bool,
+    /// Bumped under the write lock by every mutation (see `version`)
+    pub(crate) graph_version: AtomicU64,
+    /// Inference results for the current graph version
+    pub(crate) inference_cache: InferenceCache,
+    /// Content hash and the graph version it was computed at
+    pub(crate) content_hash_cache: Mutex<Option<(u64, String)>>,
+    /// Visit budget for expensive queries (`MEMORY_MAX_QUERY_VISITS`)
+    pub(crate) query_budget: QueryBudget,
+    /// Graph-wide metadata, persisted apart from entities (see metadata.rs)
+    pub(crate) graph_metadata: RwLock<GraphMetadata>,
 }
 
 impl KnowledgeBase {
     /// Create a new knowledge base instance
     ///
+    /// See [`KnowledgeBase::try_new`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `try_new` fails; binaries should call `try_new` and report
+    /// the error instead.
+    pub fn new() -> Self {
+        Self::try_new().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new knowledge base instance, reporting load failures
+    ///
     /// If MEMORY_EVENT_SOURCING=true, uses Event Sourcing mode.
     /// Otherwise, uses legacy memory.jsonl mode.
-    pub fn new() -> Self {
+    ///
+    /// In legacy mode, fails if `MEMORY_MAX_CORRUPT_PERCENT` is set and more
+    /// than that share of the memory file's lines is unparseable.
+    ///
+    /// If the loaded graph has no entities and `MEMORY_SEED_PATH` is set, the
+    /// seed graph is imported (see [`KnowledgeBase::seed_if_empty`]).
+    pub fn try_new() -> McpResult<Self> {
         let current_dir = env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
         let default_memory_path = current_dir.join("memory.jsonl");
 
@@ -71,18 +174,26 @@ impl KnowledgeBase {
             .map(|v| v == "true" || v == "1")
             .unwrap_or(false);
 
-        if event_sourcing_enabled {
+        let kb = if event_sourcing_enabled {
             Self::new_with_event_sourcing(memory_file_path, current_user)
         } else {
-            Self::new_legacy(memory_file_path, current_user)
-        }
+            Self::new_legacy(memory_file_path, current_user)?
+        };
+        verify::verify_from_env(&kb);
+        seed::seed_from_env(&kb);
+        Ok(kb)
     }
 
     /// Create knowledge base in legacy mode (direct file writes)
-    fn new_legacy(memory_file_path: String, current_user: String) -> Self {
-        let graph = Self::load_graph_from_file(&memory_file_path).unwrap_or_default();
+    fn new_legacy(memory_file_path: String, current_user: String) -> McpResult<Self> {
+        let graph = match load::load_graph_from_file(&memory_file_path) {
+            Ok(graph) => graph,
+            Err(e) if e.is::<load::CorruptFileError>() => return Err(e),
+            Err(_) => KnowledgeGraph::default(),
+        };
+        let graph_metadata = metadata::load_or_default(&memory_file_path, None);
 
-        Self {
+        Ok(Self {
             memory_file_path,
             graph: RwLock::new(graph),
             current_user,
@@ -90,7 +201,24 @@ impl KnowledgeBase {
             snapshot_manager: None,
             log_rotation: None,
             event_sourcing_enabled: false,
-        }
+            access_tracker: AccessTracker::from_env(),
+            recent_changes: ChangeLog::from_env(),
+            result_links: ResultLinks::from_env(),
+            observation_limits: ObservationLimits::from_env(),
+            observation_dedup: DedupMode::from_env(),
+            cardinality: CardinalityConstraints::from_env(),
+            observation_order: ObservationOrder::from_env(),
+            relation_aliases: RelationAliases::from_env(),
+            entity_names: NamePolicy::from_env(),
+            entity_templates: EntityTemplates::from_env(),
+            snapshots: SnapshotWorker::default(),
+            scratch: false,
+            graph_version: AtomicU64::new(0),
+            inference_cache: InferenceCache::from_env(),
+            content_hash_cache: Mutex::new(None),
+            query_budget: QueryBudget::from_env(),
+            graph_metadata: RwLock::new(graph_metadata),
+        })
     }
 
     /// Create knowledge base with Event Sourcing enabled
@@ -101,9 +229,39 @@ impl KnowledgeBase {
             .map(|p| p.join("data"))
             .unwrap_or_else(|| std::path::PathBuf::from("data"));
 
-        let config = EventStoreConfig::with_data_dir(&data_dir);
+        let mut config = EventStoreConfig::with_data_dir(&data_dir);
+        if let Some(range) = env::var("MEMORY_EVENT_ID_RANGE")
+            .ok()
+            .and_then(|v| EventStoreConfig::parse_id_range(&v))
+        {
+            config.id_range = range;
+        }
+        config.id_block_size = env::var("MEMORY_EVENT_ID_BLOCK")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|size| *size > 0);
+        config.segment_size = env::var("MEMORY_SEGMENT_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|size| *size > 0);
+        if let Some(every) = env::var("MEMORY_REPLAY_PROGRESS_EVERY")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            config.replay_progress_every = every;
+        }
+        config.snapshot_interval_secs = env::var("MEMORY_SNAPSHOT_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok());
+        if let Some(max) = env::var("MEMORY_DELTA_SNAPSHOTS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            config.max_delta_chain = max;
+        }
         let mut event_store = EventStore::with_config(config.clone());
         let snapshot_manager = SnapshotManager::new(config.clone());
+        let graph_metadata = metadata::load_or_default(&memory_file_path, Some(&config));
         let log_rotation = LogRotation::new(config);
 
         // Initialize from snapshot + replay events
@@ -132,10 +290,31 @@ impl KnowledgeBase {
             snapshot_manager: Some(snapshot_manager),
             log_rotation: Some(log_rotation),
             event_sourcing_enabled: true,
+            access_tracker: AccessTracker::from_env(),
+            recent_changes: ChangeLog::from_env(),
+            result_links: ResultLinks::from_env(),
+            observation_limits: ObservationLimits::from_env(),
+            observation_dedup: DedupMode::from_env(),
+            cardinality: CardinalityConstraints::from_env(),
+            observation_order: ObservationOrder::from_env(),
+            relation_aliases: RelationAliases::from_env(),
+            entity_names: NamePolicy::from_env(),
+            entity_templates: EntityTemplates::from_env(),
+            snapshots: SnapshotWorker::default(),
+            scratch: false,
+            graph_version: AtomicU64::new(0),
+            inference_cache: InferenceCache::from_env(),
+            content_hash_cache: Mutex::new(None),
+            query_budget: QueryBudget::from_env(),
+            graph_metadata: RwLock::new(graph_metadata),
         }
     }
 
     /// Create a new knowledge base with custom file path
+    ///
+    /// # Panics
+    ///
+    /// Panics if the file cannot be loaded (see [`KnowledgeBase::try_new`]).
     pub fn with_file_path(file_path: String) -> Self {
         let current_user = get_current_user();
 
@@ -143,11 +322,13 @@ impl KnowledgeBase {
             .map(|v| v == "true" || v == "1")
             .unwrap_or(false);
 
-        if event_sourcing_enabled {
+        let kb = if event_sourcing_enabled {
             Self::new_with_event_sourcing(file_path, current_user)
         } else {
-            Self::new_legacy(file_path, current_user)
-        }
+            Self::new_legacy(file_path, current_user).unwrap_or_else(|e| panic!("{}", e))
+        };
+        seed::seed_from_env(&kb);
+        kb
     }
 
     /// Create a new knowledge base for testing with explicit parameters
@@ -161,6 +342,23 @@ impl KnowledgeBase {
             snapshot_manager: None,
             log_rotation: None,
             event_sourcing_enabled: false,
+            access_tracker: AccessTracker::new(true),
+            recent_changes: ChangeLog::new(256),
+            result_links: ResultLinks::new(0),
+            observation_limits: ObservationLimits::unlimited(),
+            observation_dedup: DedupMode::Exact,
+            cardinality: CardinalityConstraints::unconstrained(),
+            observation_order: ObservationOrder::OldestFirst,
+            relation_aliases: RelationAliases::none(),
+            entity_names: NamePolicy::default(),
+            entity_templates: EntityTemplates::none(),
+            snapshots: SnapshotWorker::default(),
+            scratch: false,
+            graph_version: AtomicU64::new(0),
+            inference_cache: InferenceCache::from_env(),
+            content_hash_cache: Mutex::new(None),
+            query_budget: QueryBudget::from_env(),
+            graph_metadata: RwLock::new(GraphMetadata::default()),
         }
     }
 
@@ -170,6 +368,7 @@ impl KnowledgeBase {
         let config = EventStoreConfig::with_data_dir(data_dir);
         let mut event_store = EventStore::with_config(config.clone());
         let snapshot_manager = SnapshotManager::new(config.clone());
+        let graph_metadata = metadata::load_or_default("", Some(&config));
         let log_rotation = LogRotation::new(config);
 
         let (entities, relations) = event_store.initialize().unwrap_or_default();
@@ -183,41 +382,26 @@ impl KnowledgeBase {
             snapshot_manager: Some(snapshot_manager),
             log_rotation: Some(log_rotation),
             event_sourcing_enabled: true,
+            access_tracker: AccessTracker::new(true),
+            recent_changes: ChangeLog::new(256),
+            result_links: ResultLinks::new(0),
+            observation_limits: ObservationLimits::unlimited(),
+            observation_dedup: DedupMode::Exact,
+            cardinality: CardinalityConstraints::unconstrained(),
+            observation_order: ObservationOrder::OldestFirst,
+            relation_aliases: RelationAliases::none(),
+            entity_names: NamePolicy::default(),
+            entity_templates: EntityTemplates::none(),
+            snapshots: SnapshotWorker::default(),
+            scratch: false,
+            graph_version: AtomicU64::new(0),
+            inference_cache: InferenceCache::from_env(),
+            content_hash_cache: Mutex::new(None),
+            query_budget: QueryBudget::from_env(),
+            graph_metadata: RwLock::new(graph_metadata),
         }
     }
 
-    /// Load graph from file (static helper for initialization)
-    fn load_graph_from_file(file_path: &str) -> McpResult<KnowledgeGraph> {
-        if !Path::new(file_path).exists() {
-            return Ok(KnowledgeGraph::default());
-        }
-
-        let content = fs::read_to_string(file_path)?;
-        let mut graph = KnowledgeGraph::default();
-
-        for line in content.lines() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
-            }
-
-            if let Ok(entity) = serde_json::from_str::<Entity>(line) {
-                if !entity.name.is_empty() && !entity.entity_type.is_empty() {
-                    graph.entities.push(entity);
-                    continue;
-                }
-            }
-
-            if let Ok(relation) = serde_json::from_str::<Relation>(line) {
-                if !relation.from.is_empty() && !relation.to.is_empty() {
-                    graph.relations.push(relation);
-                }
-            }
-        }
-
-        Ok(graph)
-    }
-
     /// Get a clone of the current graph (thread-safe read)
     /// Uses read lock - allows multiple concurrent readers
     pub(crate) fn load_graph(&self) -> McpResult<KnowledgeGraph> {
@@ -226,24 +410,27 @@ impl KnowledgeBase {
 
     /// Persist graph to file (internal helper, expects caller to hold write lock)
     pub(crate) fn persist_to_file(&self, graph: &KnowledgeGraph) -> McpResult<()> {
+        self.persist_with_metadata(graph, &self.graph_metadata.read().unwrap())
+    }
+
+    /// Persist graph to file with `metadata` as the header line (if any)
+    pub(crate) fn persist_with_metadata(
+        &self,
+        graph: &KnowledgeGraph,
+        metadata: &GraphMetadata,
+    ) -> McpResult<()> {
         // Ensure parent directory exists
         if let Some(parent) = Path::new(&self.memory_file_path).parent() {
             fs::create_dir_all(parent)?;
         }
 
-        let mut content = String::new();
-
-        for entity in &graph.entities {
-            content.push_str(&serde_json::to_string(entity)?);
-            content.push('\n');
-        }
-
-        for relation in &graph.relations {
-            content.push_str(&serde_json::to_string(relation)?);
-            content.push('\n');
-        }
-
-        fs::write(&self.memory_file_path, content)?;
+        let content = if metadata.is_empty() {
+            graph.to_jsonl()?
+        } else {
+            format!("{}\n{}", metadata.to_header_line()?, graph.to_jsonl()?)
+        };
+        let path = Path::new(&self.memory_file_path);
+        WriteRetry::from_env().run("write", path, || fs::write(path, &content))?;
         Ok(())
     }
 
@@ -252,6 +439,38 @@ impl KnowledgeBase {
         &self.current_user
     }
 
+    /// User stamped on writes: the [`KnowledgeBase::run_as`] override, else the current user
+    pub fn acting_user(&self) -> String {
+        user::acting_user(self)
+    }
+
+    /// Run `call` with writes attributed to `user` (`None` keeps the current user)
+    pub fn run_as<T>(user: Option<&str>, call: impl FnOnce() -> T) -> T {
+        user::run_as(user, call)
+    }
+
+    /// Run `call` as an authenticated caller (`None` keeps the current user)
+    ///
+    /// Used by the HTTP handlers: writes are attributed to the caller and
+    /// entity ACLs are checked against their permissions.
+    pub fn run_as_caller<T>(caller: Option<Caller>, call: impl FnOnce() -> T) -> T {
+        user::run_as_caller(caller, call)
+    }
+
+    /// Run `read` on the graph as the current caller may see it (see acl.rs)
+    ///
+    /// The read lock is held while `read` runs. Used by the REST endpoints,
+    /// which read the graph directly.
+    pub fn with_visible_graph<T>(&self, read: impl FnOnce(&KnowledgeGraph) -> T) -> T {
+        let graph = self.graph.read().unwrap();
+        read(&acl::view(&graph))
+    }
+
+    /// `event` as `caller` may see it, or `None` if it only concerns hidden entities
+    pub fn visible_event(&self, event: &GraphEvent, caller: &Caller) -> Option<GraphEvent> {
+        acl::event_visible(&self.graph.read().unwrap(), event, caller)
+    }
+
     /// Get the memory file path
     pub fn file_path(&self) -> &str {
         &self.memory_file_path
@@ -272,73 +491,33 @@ impl KnowledgeBase {
     ) -> McpResult<Option<u64>> {
         if let Some(ref event_store) = self.event_store {
             let mut store = event_store.lock().unwrap();
-            let event = store.create_and_append_event(event_type, self.current_user.clone(), data)?;
+            let event = store.create_and_append_event(event_type, self.acting_user(), data)?;
             Ok(Some(event.event_id))
         } else {
             Ok(None)
         }
     }
 
-    /// Check if a snapshot should be created and create it if so
-    pub(crate) fn maybe_create_snapshot(&self) -> McpResult<()> {
-        if let (Some(ref event_store), Some(ref snapshot_manager)) =
-            (&self.event_store, &self.snapshot_manager)
-        {
-            let store = event_store.lock().unwrap();
-
-            if store.should_snapshot() {
-                let graph = self.graph.read().unwrap();
-                let last_event_id = store.next_event_id().saturating_sub(1);
-                let config = store.config().clone();
-
-                // Drop the store lock before creating snapshot
-                drop(store);
-
-                snapshot_manager.create_snapshot_with_backup(
-                    last_event_id,
-                    &graph.entities,
-                    &graph.relations,
-                )?;
-
-                // Rotate event log to archive old events
-                if config.archive_old_events {
-                    if let Some(ref rotation) = self.log_rotation {
-                        if let Err(e) = rotation.rotate_after_snapshot(last_event_id) {
-                            eprintln!("Warning: Failed to rotate event log: {}", e);
-                        }
-                    }
-                }
-
-                // Update the store's snapshot counter
-                let mut store = event_store.lock().unwrap();
-                store.snapshot_created(last_event_id);
+    /// Rebuild the in-memory graph from the event log after a failed append
+    ///
+    /// Called with the write lock held, so readers never see changes whose
+    /// events did not make it to the log.
+    pub(crate) fn rebuild_from_log(&self, graph: &mut KnowledgeGraph) {
+        if let Some(ref event_store) = self.event_store {
+            match event_store.lock().unwrap().initialize() {
+                Ok((entities, relations)) => *graph = KnowledgeGraph { entities, relations },
+                Err(e) => eprintln!("Warning: Failed to rebuild graph from event log: {}", e),
             }
         }
-        Ok(())
     }
 
     /// Force create a snapshot (for graceful shutdown)
     /// Returns the path to the snapshot file if created, or None if Event Sourcing is disabled
     pub fn create_snapshot(&self) -> McpResult<Option<std::path::PathBuf>> {
-        if let (Some(ref event_store), Some(ref snapshot_manager)) =
-            (&self.event_store, &self.snapshot_manager)
-        {
-            let store = event_store.lock().unwrap();
-            let graph = self.graph.read().unwrap();
-            let last_event_id = store.next_event_id().saturating_sub(1);
-
-            if last_event_id > 0 {
-                drop(store);
-                snapshot_manager.create_snapshot_with_backup(
-                    last_event_id,
-                    &graph.entities,
-                    &graph.relations,
-                )?;
-
-                let mut store = event_store.lock().unwrap();
-                store.snapshot_created(last_event_id);
-
-                return Ok(Some(snapshot_manager.latest_path()));
+        if let Some(ref snapshot_manager) = self.snapshot_manager {
+            let _writing = self.snapshots.write_lock.lock().unwrap();
+            if let Some(meta) = snapshot::write_snapshot(self, true)? {
+                return Ok(Some(snapshot_manager.path_for(&meta)));
             }
         }
         Ok(None)
@@ -355,25 +534,103 @@ impl KnowledgeBase {
         }
     }
 
+    /// Re-read the graph from disk, discarding the in-memory state
+    ///
+    /// Picks up out-of-band edits to memory.jsonl (or the event log and
+    /// snapshot in Event Sourcing mode) without a restart. The new graph is
+    /// built while holding the write lock and swapped in whole, so readers see
+    /// either the old or the new graph. On error the old graph is kept.
+    ///
+    /// Clients are sent a `resync` event afterwards, as their view may be
+    /// stale. Returns the number of entities and relations loaded.
+    pub fn reload_from_disk(&self) -> McpResult<(usize, usize)> {
+        let mut graph = self.graph.write().unwrap();
+
+        let (reloaded, reloaded_metadata) = if let Some(ref event_store) = self.event_store {
+            let mut store = event_store.lock().unwrap();
+            let (entities, relations) = store.initialize()?;
+            let reloaded_metadata = metadata::load_sidecar(store.config())?;
+            (KnowledgeGraph { entities, relations }, reloaded_metadata)
+        } else {
+            (
+                load::load_graph_from_file(&self.memory_file_path)?,
+                load::load_metadata_from_file(&self.memory_file_path)?,
+            )
+        };
+
+        let counts = (reloaded.entities.len(), reloaded.relations.len());
+        *graph = reloaded;
+        *self.graph_metadata.write().unwrap() = reloaded_metadata;
+        self.graph_version.fetch_add(1, Ordering::Release);
+        drop(graph);
+
+        if !self.scratch {
+            broadcast_event(GraphEvent::Resync {
+                reason: "reload".to_string(),
+                user: Some(self.acting_user()),
+            });
+        }
+        Ok(counts)
+    }
+
     /// Manually rotate event log (archive old events)
     pub fn rotate_event_log(&self) -> McpResult<Option<std::path::PathBuf>> {
-        if let (Some(ref event_store), Some(ref rotation)) =
-            (&self.event_store, &self.log_rotation)
-        {
-            // Just need to drop the lock, don't need the config
-            let _store = event_store.lock().unwrap();
-            drop(_store);
+        if let (Some(ref event_store), Some(_)) = (&self.event_store, &self.log_rotation) {
+            // Hold the store lock so no event is appended while the log is rewritten
+            let mut store = event_store.lock().unwrap();
 
-            // Get last snapshot event ID from snapshot manager
+            // Rotate up to the last snapshot
             if let Some(ref snapshot_manager) = self.snapshot_manager {
                 if let Ok(Some(meta)) = snapshot_manager.load_meta() {
-                    return Ok(rotation.rotate_after_snapshot(meta.last_event_id)?);
+                    return Ok(match meta.log_position {
+                        Some(log_position) => store.rotate_to_position(log_position)?,
+                        None => store.rotate_after_snapshot(meta.last_event_id)?,
+                    });
                 }
             }
         }
         Ok(None)
     }
 
+    /// Canonical form of a relation type under the configured aliases
+    pub fn canonical_relation_type(&self, relation_type: &str) -> String {
+        self.relation_aliases.canonical(relation_type).to_string()
+    }
+
+    /// Server default for observation order in read output
+    pub fn observation_order(&self) -> ObservationOrder {
+        self.observation_order
+    }
+
+    /// Graph version, bumped by every mutation and reload
+    ///
+    /// Clients compare versions for equality: an unchanged version means the
+    /// graph has not changed since it was read. A bump does not guarantee a
+    /// change, as failed mutations bump it too. The version is kept in memory
+    /// only and restarts at 0 with the server.
+    ///
+    /// It is not an event ID: in Event Sourcing mode one mutation may append
+    /// many events (or none), so use `next_event_id` to position the log.
+    pub fn version(&self) -> u64 {
+        self.graph_version.load(Ordering::Acquire)
+    }
+
+    /// ID the next persisted event will get (None unless Event Sourcing is enabled)
+    pub fn next_event_id(&self) -> Option<u64> {
+        self.event_store
+            .as_ref()
+            .map(|store| store.lock().unwrap().next_event_id())
+    }
+
+    /// List archived event log segments (empty unless Event Sourcing is enabled)
+    pub fn archive_info(&self) -> McpResult<Vec<ArchiveInfo>> {
+        if let Some(ref rotation) = self.log_rotation {
+            Ok(rotation.list_archives()?)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
     /// Clean up old archive files, keeping only the most recent N
     pub fn cleanup_archives(&self, keep_count: usize) -> McpResult<usize> {
         if let Some(ref rotation) = self.log_rotation {
@@ -397,16 +654,58 @@ impl KnowledgeBase {
         crud::create_entities(self, entities)
     }
 
+    pub fn create_entities_with_warnings(
+        &self,
+        entities: Vec<Entity>,
+    ) -> McpResult<(Vec<Entity>, Vec<ValidationWarning>)> {
+        crud::create_entities_with_warnings(self, entities)
+    }
+
     pub fn create_relations(&self, relations: Vec<Relation>) -> McpResult<Vec<Relation>> {
         crud::create_relations(self, relations)
     }
 
+    pub fn create_relations_with_warnings(
+        &self,
+        relations: Vec<Relation>,
+    ) -> McpResult<(Vec<Relation>, Vec<ValidationWarning>)> {
+        crud::create_relations_with_warnings(self, relations)
+    }
+
     pub fn add_observations(&self, observations: Vec<Observation>) -> McpResult<Vec<Observation>> {
         crud::add_observations(self, observations)
     }
 
-    pub fn delete_entities(&self, entity_names: Vec<String>) -> McpResult<()> {
-        crud::delete_entities(self, entity_names)
+    pub fn add_observations_with_warnings(
+        &self,
+        observations: Vec<Observation>,
+    ) -> McpResult<(Vec<Observation>, Vec<ValidationWarning>)> {
+        crud::add_observations_with_warnings(self, observations)
+    }
+
+    /// Add observations to many entities, grouped by entity and resolved in one pass
+    pub fn add_observations_batch(
+        &self,
+        observations: Vec<Observation>,
+    ) -> McpResult<(Vec<Observation>, Vec<ValidationWarning>)> {
+        crud::add_observations_batch(self, observations)
+    }
+
+    pub fn replace_observations(
+        &self,
+        replacements: Vec<ObservationReplacement>,
+    ) -> McpResult<Vec<ObservationReplacementResult>> {
+        crud::replace_observations(self, replacements)
+    }
+
+    /// Delete entities, skipping protected ones; returns the names skipped
+    pub fn delete_entities(&self, entity_names: Vec<String>) -> McpResult<Vec<String>> {
+        crud::delete_entities(self, entity_names, false)
+    }
+
+    /// Delete entities, protected ones included
+    pub fn force_delete_entities(&self, entity_names: Vec<String>) -> McpResult<()> {
+        crud::delete_entities(self, entity_names, true).map(|_| ())
     }
 
     pub fn delete_observations(&self, deletions: Vec<ObservationDeletion>) -> McpResult<()> {
@@ -417,6 +716,87 @@ impl KnowledgeBase {
         crud::delete_relations(self, relations)
     }
 
+    pub fn delete_relations_by_filter(
+        &self,
+        from: Option<String>,
+        to: Option<String>,
+        relation_type: Option<String>,
+    ) -> McpResult<Vec<Relation>> {
+        crud::delete_relations_by_filter(self, from, to, relation_type)
+    }
+
+    // Observation and relation type rewrites (from rewrite.rs)
+    pub fn replace_in_observations(
+        &self,
+        find: &str,
+        replace: &str,
+        regex: bool,
+    ) -> McpResult<usize> {
+        rewrite::replace_in_observations(self, find, replace, regex)
+    }
+
+    pub fn preview_replace_in_observations(
+        &self,
+        find: &str,
+        replace: &str,
+        regex: bool,
+    ) -> McpResult<Vec<ObservationChange>> {
+        rewrite::preview_replace_in_observations(self, find, replace, regex)
+    }
+
+    pub fn rename_relation_type(&self, old: &str, new: &str) -> McpResult<usize> {
+        rewrite::rename_relation_type(self, old, new)
+    }
+
+    pub fn preview_rename_relation_type(
+        &self,
+        old: &str,
+        new: &str,
+    ) -> McpResult<Vec<RelationTypeChange>> {
+        rewrite::preview_rename_relation_type(self, old, new)
+    }
+
+    pub fn rename_entity_type(&self, old: &str, new: &str) -> McpResult<usize> {
+        rewrite::rename_entity_type(self, old, new)
+    }
+
+    pub fn preview_rename_entity_type(&self, old: &str, new: &str) -> McpResult<Vec<String>> {
+        rewrite::preview_rename_entity_type(self, old, new)
+    }
+
+    // Relation normalization (from normalize.rs)
+    /// Relation types `MEMORY_SYMMETRIC_RELATIONS` marks as symmetric
+    pub fn symmetric_relation_types() -> BTreeSet<String> {
+        normalize::symmetric_types_from_env()
+    }
+
+    pub fn normalize_relations(
+        &self,
+        symmetric: Option<&BTreeSet<String>>,
+    ) -> McpResult<Vec<RelationNormalization>> {
+        normalize::normalize_relations(self, symmetric)
+    }
+
+    pub fn preview_normalize_relations(
+        &self,
+        symmetric: Option<&BTreeSet<String>>,
+    ) -> Vec<RelationNormalization> {
+        normalize::preview_normalize_relations(self, symmetric)
+    }
+
+    // Transactions (from transaction.rs)
+    pub fn transaction(&self, ops: Vec<Op>) -> McpResult<Vec<OpResult>> {
+        transaction::transaction(self, ops).map(|(results, _)| results)
+    }
+
+    /// Same as `transaction`, also returning warnings about admitted items
+    pub fn transaction_with_warnings(
+        &self,
+        ops: Vec<Op>,
+    ) -> McpResult<(Vec<OpResult>, Vec<ValidationWarning>)> {
+        transaction::transaction(self, ops)
+    }
+
     // Query operations (from query.rs)
     pub fn read_graph(
         &self,
@@ -426,27 +806,60 @@ impl KnowledgeBase {
         query::read_graph(self, limit, offset)
     }
 
-    pub fn search_nodes(
-        &self,
-        query: &str,
-        limit: Option<usize>,
-        include_relations: bool,
-    ) -> McpResult<KnowledgeGraph> {
-        query::search_nodes(self, query, limit, include_relations)
+    /// Search entities, ranked by relevance (see query.rs)
+    ///
+    /// Limit, offset, relation inclusion and filters are set in `options`;
+    /// `SearchOptions::default()` returns every match with its relations.
+    pub fn search_nodes(&self, query: &str, options: &SearchOptions) -> McpResult<SearchResult> {
+        query::search_nodes(self, query, options)
     }
 
     pub fn open_nodes(&self, names: Vec<String>) -> McpResult<KnowledgeGraph> {
         query::open_nodes(self, names)
     }
 
+    pub fn open_nodes_paged(
+        &self,
+        names: Vec<String>,
+        order: ObservationOrder,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> McpResult<(KnowledgeGraph, BTreeMap<String, usize>)> {
+        query::open_nodes_paged(self, names, order, offset, limit)
+    }
+
+    pub fn entity_exists(&self, names: &[String]) -> HashMap<String, bool> {
+        query::entity_exists(self, names)
+    }
+
+    pub fn relation_exists(&self, triples: &[(String, String, String)]) -> Vec<bool> {
+        query::relation_exists(self, triples)
+    }
+
+    pub fn query_relations(&self, query: &RelationQuery) -> McpResult<Vec<Relation>> {
+        query::query_relations(self, query)
+    }
+
+    pub fn find_orphans(&self, entity_type: Option<String>) -> Vec<String> {
+        query::find_orphans(self, entity_type.as_deref())
+    }
+
+    pub fn infer_schema(&self) -> GraphSchema {
+        query::infer_schema(self)
+    }
+
     // Traversal operations (from traversal.rs)
+    /// Entities linked to `entity_name`, filtered, sorted and paged by `options`
     pub fn get_related(
         &self,
         entity_name: &str,
-        relation_type: Option<&str>,
-        direction: &str,
+        options: &RelatedOptions,
     ) -> McpResult<RelatedEntities> {
-        traversal::get_related(self, entity_name, relation_type, direction)
+        traversal::get_related(self, entity_name, options)
+    }
+
+    pub fn references_to(&self, entity_name: &str) -> McpResult<References> {
+        traversal::references_to(self, entity_name)
     }
 
     pub fn traverse(
@@ -455,7 +868,156 @@ impl KnowledgeBase {
         path: Vec<PathStep>,
         max_results: usize,
     ) -> McpResult<TraversalResult> {
-        traversal::traverse(self, start, path, max_results)
+        traversal::traverse(self, start, path, max_results, false)
+    }
+
+    /// `traverse`, with the relation each hop followed on every path
+    pub fn traverse_with_relation_details(
+        &self,
+        start: &str,
+        path: Vec<PathStep>,
+        max_results: usize,
+    ) -> McpResult<TraversalResult> {
+        traversal::traverse(self, start, path, max_results, true)
+    }
+
+    pub fn get_subgraph(&self, center: &str, depth: usize) -> McpResult<KnowledgeGraph> {
+        traversal::get_subgraph(self, center, depth)
+    }
+
+    pub fn random_walk(
+        &self,
+        start: Option<&str>,
+        steps: usize,
+        restart_prob: f32,
+        seed: u64,
+    ) -> Vec<String> {
+        traversal::random_walk(self, start, steps, restart_prob, seed)
+    }
+
+    // Query cost estimates (from cost.rs)
+    pub fn estimate_traverse(&self, path: &[PathStep], max_results: usize) -> QueryCost {
+        cost::estimate_traverse(self, path, max_results)
+    }
+
+    pub fn estimate_infer(
+        &self,
+        max_depth: usize,
+        rules: Option<&[String]>,
+    ) -> McpResult<QueryCost> {
+        cost::estimate_infer(self, max_depth, rules)
+    }
+
+    pub fn k_shortest_paths(
+        &self,
+        from: &str,
+        to: &str,
+        k: usize,
+        max_depth: usize,
+    ) -> McpResult<Vec<TraversalPath>> {
+        traversal::k_shortest_paths(self, from, to, k, max_depth)
+    }
+
+    // Import operations (from import.rs)
+    pub fn import_graph(&self, incoming: KnowledgeGraph) -> McpResult<ImportReport> {
+        import::import_graph(self, incoming).map(|(report, _)| report)
+    }
+
+    pub fn import_graph_with_warnings(
+        &self,
+        incoming: KnowledgeGraph,
+    ) -> McpResult<(ImportReport, Vec<ValidationWarning>)> {
+        import::import_graph(self, incoming)
+    }
+
+    // Chunked bulk creation (from bulk.rs)
+    /// Create entities then relations in committed chunks of `chunk_size`
+    ///
+    /// Progress is logged after each chunk. See [`BulkReport`] for how a
+    /// failure partway through is reported.
+    pub fn bulk_create(
+        &self,
+        entities: Vec<Entity>,
+        relations: Vec<Relation>,
+        chunk_size: usize,
+    ) -> McpResult<(BulkReport, Vec<ValidationWarning>)> {
+        bulk::bulk_create(self, entities, relations, chunk_size, |chunk, report| {
+            eprintln!(
+                "[Bulk] Chunk {}/{} committed: {} entities, {} relations created",
+                chunk.index + 1,
+                report.total_chunks,
+                chunk.entities_created,
+                chunk.relations_created
+            );
+            Ok(())
+        })
+    }
+
+    // Seed operations (from seed.rs)
+    /// Import the seed graph at `seed_path` if the graph has no entities
+    ///
+    /// Returns `None` if the graph already had entities or the file is missing.
+    pub fn seed_if_empty(&self, seed_path: &str) -> McpResult<Option<ImportReport>> {
+        seed::seed_if_empty(self, seed_path)
+    }
+
+    // Tail mode (from tail.rs)
+    pub fn apply_external_events(&self) -> McpResult<usize> {
+        tail::apply_external_events(self)
+    }
+
+    pub fn spawn_event_tail(kb: &Arc<Self>) -> Option<JoinHandle<()>> {
+        tail::spawn_event_tail(Arc::clone(kb))
+    }
+
+    // Inference (from inference/cache.rs)
+    /// Infer relations for `target`, cached until the next mutation
+    pub fn infer(
+        &self,
+        target: &str,
+        min_confidence: f32,
+        max_depth: usize,
+        rules: Option<&[String]>,
+    ) -> McpResult<(Vec<InferredRelation>, InferStats)> {
+        inference::cache::infer_cached(self, target, min_confidence, max_depth, rules)
+    }
+
+    // Session scratch graphs (from scratch.rs)
+    /// Private in-memory copy of the graph (see [`scratch::scratch_copy`])
+    pub fn scratch_copy(&self) -> KnowledgeBase {
+        scratch::scratch_copy(self)
+    }
+
+    /// Merge additions made in a scratch copy into this knowledge base
+    pub fn promote_from(
+        &self,
+        scratch: &KnowledgeBase,
+        entity_names: Option<&[String]>,
+    ) -> McpResult<(ImportReport, Vec<ValidationWarning>)> {
+        scratch::promote(self, scratch, entity_names)
+    }
+
+    // Background snapshots (from snapshot.rs)
+    pub fn spawn_snapshot_worker(kb: &Arc<Self>) -> Option<JoinHandle<()>> {
+        snapshot::spawn_snapshot_worker(kb)
+    }
+
+    // Duplicate detection (from dedup.rs)
+    pub fn find_duplicates(&self, threshold: f32) -> Vec<(String, String, f32)> {
+        dedup::find_duplicates(self, threshold)
+    }
+
+    pub fn diff_entities(&self, a: &str, b: &str) -> McpResult<EntityDiff> {
+        dedup::diff_entities(self, a, b)
+    }
+
+    // Relation suggestions (from suggest.rs)
+    pub fn suggest_relations(
+        &self,
+        name: &str,
+        top_n: usize,
+    ) -> McpResult<Vec<RelationSuggestion>> {
+        suggest::suggest_relations(self, name, top_n)
     }
 
     // Summarize operations (from summarize.rs)
@@ -465,7 +1027,33 @@ impl KnowledgeBase {
         entity_type: Option<String>,
         format: &str,
     ) -> McpResult<Summary> {
-        summarize::summarize(self, entity_names, entity_type, format)
+        summarize::summarize(self, entity_names, entity_type, format, &[])
+    }
+
+    /// Same as `summarize`, also counting stats by the `group_by` attribute keys
+    pub fn summarize_grouped(
+        &self,
+        entity_names: Option<Vec<String>>,
+        entity_type: Option<String>,
+        format: &str,
+        group_by: &[String],
+    ) -> McpResult<Summary> {
+        summarize::summarize(self, entity_names, entity_type, format, group_by)
+    }
+
+    /// Attributes parsed from `key: value` observations (see [`summarize::get_attributes`])
+    pub fn get_attributes(&self, name: &str) -> McpResult<EntityAttributes> {
+        summarize::get_attributes(self, name)
+    }
+
+    /// Compact read-only view of an entity's observations (see [`summarize::compact_view`])
+    pub fn compact_view(&self, name: &str, max_observations: usize) -> McpResult<CompactView> {
+        summarize::compact_view(self, name, max_observations)
+    }
+
+    /// An entity with its observations and relations as a Markdown section (see [`summarize::entity_to_markdown`])
+    pub fn entity_to_markdown(&self, name: &str) -> McpResult<String> {
+        summarize::entity_to_markdown(self, name)
     }
 
     // Temporal operations (from temporal.rs)
@@ -474,10 +1062,127 @@ impl KnowledgeBase {
         timestamp: Option<u64>,
         entity_name: Option<&str>,
     ) -> McpResult<Vec<Relation>> {
-        temporal::get_relations_at_time(self, timestamp, entity_name)
+        temporal::get_relations_at_time(self, timestamp, entity_name, &[])
+    }
+
+    /// Same as `get_relations_at_time`, keeping only relations whose properties match
+    pub fn get_relations_at_time_filtered(
+        &self,
+        timestamp: Option<u64>,
+        entity_name: Option<&str>,
+        properties: &[PropertyFilter],
+    ) -> McpResult<Vec<Relation>> {
+        temporal::get_relations_at_time(self, timestamp, entity_name, properties)
     }
 
     pub fn get_relation_history(&self, entity_name: &str) -> McpResult<Vec<Relation>> {
         temporal::get_relation_history(self, entity_name)
     }
+
+    pub fn entity_timeline(&self, entity_name: &str) -> McpResult<EntityTimeline> {
+        temporal::entity_timeline(self, entity_name)
+    }
+
+    // Relation historization (from historize.rs)
+    pub fn historize_relations(&self, rule: &HistorizeRule) -> McpResult<HistorizeReport> {
+        historize::historize_relations(self, rule)
+    }
+
+    pub fn preview_historize_relations(&self, rule: &HistorizeRule) -> McpResult<HistorizeReport> {
+        historize::preview_historize_relations(self, rule)
+    }
+
+    // Expired relation archiving (from expire.rs)
+    pub fn expired_relations(&self, before: u64) -> Vec<Relation> {
+        expire::expired_relations(self, before)
+    }
+
+    pub fn archive_expired_relations(&self, before: u64) -> McpResult<usize> {
+        expire::archive_expired_relations(self, before)
+    }
+
+    // Replay verification (from verify.rs)
+    pub fn verify_replay(&self) -> McpResult<Option<String>> {
+        verify::verify_replay(self)
+    }
+
+    // Validation operations (from validate.rs)
+    pub fn validate_all(&self) -> Vec<ValidationWarning> {
+        validate::validate_all(self)
+    }
+
+    // Access tracking (from access.rs)
+    pub fn hot_entities(&self, limit: usize) -> Vec<HotEntity> {
+        access::hot_entities(self, limit)
+    }
+
+    // Content hash (from fingerprint.rs)
+    /// Order-independent fingerprint of the graph content (see fingerprint.rs)
+    pub fn content_hash(&self) -> McpResult<String> {
+        fingerprint::content_hash(self)
+    }
+
+    // Recent changes (from changes.rs)
+    /// Latest committed changes, newest first, optionally only those naming `subject`
+    pub fn recent_changes(&self, limit: usize, subject: Option<&str>) -> Vec<RecentChange> {
+        changes::recent_changes(self, limit, subject)
+    }
+
+    // Attribution queries (from by_user.rs)
+    pub fn entities_by_user(&self, user: &str, field: UserField) -> Vec<Entity> {
+        by_user::entities_by_user(self, user, field)
+    }
+
+    /// Changes `user` made according to the event log (`None` without Event Sourcing)
+    pub fn events_by_user(&self, user: &str, limit: usize) -> McpResult<Option<Vec<RecentChange>>> {
+        by_user::events_by_user(self, user, limit)
+    }
+
+    // Resource links (from resources.rs)
+    /// Tool response for `text`, linked as a resource if over `MEMORY_RESULT_LINK_BYTES`
+    pub fn link_result(&self, tool: &str, text: String) -> serde_json::Value {
+        resources::link_result(self, tool, text)
+    }
+
+    pub fn list_resources(&self) -> Vec<ResultResource> {
+        resources::list_resources(self)
+    }
+
+    pub fn read_resource(&self, uri: &str) -> Option<(ResultResource, String)> {
+        resources::read_resource(self, uri)
+    }
+
+    // Deletion protection (from protect.rs)
+    pub fn protect_entities(&self, entity_names: &[String]) -> McpResult<Vec<String>> {
+        protect::set_protected(self, entity_names, true)
+    }
+
+    pub fn unprotect_entities(&self, entity_names: &[String]) -> McpResult<Vec<String>> {
+        protect::set_protected(self, entity_names, false)
+    }
+
+    // Graph metadata (from metadata.rs)
+    pub fn graph_metadata(&self) -> GraphMetadata {
+        metadata::graph_metadata(self)
+    }
+
+    /// Replace the graph-wide metadata, stamping who changed it and when
+    pub fn set_graph_metadata(&self, graph_metadata: GraphMetadata) -> McpResult<GraphMetadata> {
+        metadata::set_graph_metadata(self, graph_metadata)
+    }
+
+    // Backups (from backup.rs)
+    pub fn backup_mode(&self) -> BackupMode {
+        backup::backup_mode(self)
+    }
+
+    /// Write a one-file backup of the persisted state to `out`
+    pub fn write_backup(&self, out: &mut dyn std::io::Write) -> McpResult<BackupMode> {
+        backup::write_backup(self, out)
+    }
+
+    // Restores (from restore.rs)
+    pub fn restore_backup(&self, content: &str) -> McpResult<RestoreReport> {
+        restore::restore_backup(self, content)
+    }
 }
@@ -0,0 +1,288 @@
+//! Relation normalization: type case, exact duplicates and reciprocal pairs
+//!
+//! Inconsistent agents leave behind relations like `A Depends_On B` next to
+//! `A depends_on B`, or both `A relates_to B` and `B relates_to A`. A
+//! normalization pass lowercases every relation type and maps it through the
+//! relation aliases, drops relations whose normalized triple is already
+//! present and, if asked, drops the reverse copy of a symmetric relation.
+//! The first relation in graph order is the one kept. Running it again
+//! changes nothing.
+//!
+//! Symmetric types are read from `MEMORY_SYMMETRIC_RELATIONS`, a
+//! comma-separated list; without it only `relates_to` is symmetric.
+
+use std::collections::{BTreeSet, HashSet};
+use std::env;
+
+use serde_json::json;
+
+use crate::api::websocket::events::GraphEvent;
+use crate::types::{
+    EventType, KnowledgeGraph, McpResult, NormalizeAction, Relation, RelationNormalization,
+};
+use crate::utils::time::current_timestamp;
+
+use super::crud::{self, PendingEffects};
+use super::{acl, KnowledgeBase};
+
+/// Relation types treated as symmetric when `MEMORY_SYMMETRIC_RELATIONS` is unset
+const DEFAULT_SYMMETRIC_TYPES: &[&str] = &["relates_to"];
+
+/// Read the symmetric relation types from `MEMORY_SYMMETRIC_RELATIONS`
+pub fn symmetric_types_from_env() -> BTreeSet<String> {
+    match env::var("MEMORY_SYMMETRIC_RELATIONS") {
+        Ok(spec) => parse_symmetric_types(spec.split(',')),
+        Err(_) => parse_symmetric_types(DEFAULT_SYMMETRIC_TYPES.iter().copied()),
+    }
+}
+
+/// Lowercased, non-empty symmetric types
+pub fn parse_symmetric_types<'a>(types: impl IntoIterator<Item = &'a str>) -> BTreeSet<String> {
+    types
+        .into_iter()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Normalized form of a relation type: trimmed, lowercased, then aliased
+fn normalized_type(kb: &KnowledgeBase, relation_type: &str) -> String {
+    let lowered = relation_type.trim().to_lowercase();
+    kb.relation_aliases.canonical(&lowered).to_string()
+}
+
+/// Compute every change with the index of the relation it applies to
+fn collect_normalizations(
+    kb: &KnowledgeBase,
+    graph: &KnowledgeGraph,
+    symmetric: Option<&BTreeSet<String>>,
+) -> Vec<(usize, RelationNormalization)> {
+    let mut kept: HashSet<(&str, &str, String)> = HashSet::new();
+    let mut changes = Vec::new();
+
+    for (index, relation) in graph.relations.iter().enumerate() {
+        let normalized = normalized_type(kb, &relation.relation_type);
+        let from = relation.from.as_str();
+        let to = relation.to.as_str();
+
+        let action = if kept.contains(&(from, to, normalized.clone())) {
+            Some(NormalizeAction::Duplicate)
+        } else if from != to
+            && symmetric.is_some_and(|types| types.contains(&normalized))
+            && kept.contains(&(to, from, normalized.clone()))
+        {
+            Some(NormalizeAction::Reciprocal)
+        } else {
+            kept.insert((from, to, normalized.clone()));
+            (normalized != relation.relation_type).then_some(NormalizeAction::Retyped)
+        };
+
+        if let Some(action) = action {
+            changes.push((
+                index,
+                RelationNormalization {
+                    from: relation.from.clone(),
+                    to: relation.to.clone(),
+                    relation_type: relation.relation_type.clone(),
+                    normalized_type: normalized,
+                    action,
+                },
+            ));
+        }
+    }
+
+    changes
+}
+
+/// Preview a normalization pass (dry run)
+///
+/// With `symmetric`, reciprocal pairs of those types are collapsed too.
+pub fn preview_normalize_relations(
+    kb: &KnowledgeBase,
+    symmetric: Option<&BTreeSet<String>>,
+) -> Vec<RelationNormalization> {
+    let graph = kb.graph.read().unwrap();
+    collect_normalizations(kb, &graph, symmetric)
+        .into_iter()
+        .map(|(_, change)| change)
+        .collect()
+}
+
+/// Normalize relation types and drop redundant relations (thread-safe: holds write lock during entire operation)
+///
+/// Every relation changed is recorded as a `RelationDeleted` event for its
+/// old triple; retyped relations get a `RelationCreated` event keeping their
+/// validity, properties and creation time. A deleted triple may also name a
+/// relation that is kept as is (an exact duplicate), which is then recorded
+/// as created again so replay ends in the same state. Nothing changes if the
+/// current caller may not change an endpoint of a changed relation. Returns
+/// the changes.
+pub fn normalize_relations(
+    kb: &KnowledgeBase,
+    symmetric: Option<&BTreeSet<String>>,
+) -> McpResult<Vec<RelationNormalization>> {
+    crud::commit(kb, |graph, pending| {
+        let changes = collect_normalizations(kb, graph, symmetric);
+        let endpoints = changes
+            .iter()
+            .flat_map(|(_, c)| [c.from.as_str(), c.to.as_str()]);
+        acl::check_writable(graph, endpoints)?;
+        apply_normalizations(kb, graph, &changes, pending);
+        Ok(changes.into_iter().map(|(_, change)| change).collect())
+    })
+}
+
+fn apply_normalizations(
+    kb: &KnowledgeBase,
+    graph: &mut KnowledgeGraph,
+    changes: &[(usize, RelationNormalization)],
+    pending: &mut PendingEffects,
+) {
+    if changes.is_empty() {
+        return;
+    }
+    let now = current_timestamp();
+
+    let mut deleted: BTreeSet<(String, String, String)> = BTreeSet::new();
+    for (_, change) in changes {
+        let triple = (
+            change.from.clone(),
+            change.to.clone(),
+            change.relation_type.clone(),
+        );
+        if deleted.insert(triple) {
+            pending.event(
+                kb,
+                EventType::RelationDeleted,
+                json!({
+                    "from": change.from,
+                    "to": change.to,
+                    "relation_type": change.relation_type
+                }),
+            );
+            pending.broadcast(GraphEvent::RelationDeleted {
+                from: change.from.clone(),
+                to: change.to.clone(),
+                relation_type: change.relation_type.clone(),
+                user: Some(kb.acting_user()),
+            });
+        }
+    }
+
+    let mut relations = Vec::with_capacity(graph.relations.len());
+    let mut changes = changes.iter().peekable();
+    for (index, mut relation) in std::mem::take(&mut graph.relations).into_iter().enumerate() {
+        let change = changes.next_if(|(i, _)| *i == index).map(|(_, c)| c);
+        match change {
+            Some(change) if change.action != NormalizeAction::Retyped => continue,
+            Some(change) => {
+                relation.relation_type = change.normalized_type.clone();
+                relation.updated_at = now;
+                created_event(kb, &relation, pending);
+                pending.broadcast(GraphEvent::RelationCreated {
+                    payload: relation.clone(),
+                    user: Some(kb.acting_user()),
+                });
+            }
+            None => {
+                let triple = (
+                    relation.from.clone(),
+                    relation.to.clone(),
+                    relation.relation_type.clone(),
+                );
+                if deleted.contains(&triple) {
+                    created_event(kb, &relation, pending);
+                }
+            }
+        }
+        relations.push(relation);
+    }
+    graph.relations = relations;
+}
+
+fn created_event(kb: &KnowledgeBase, relation: &Relation, pending: &mut PendingEffects) {
+    let mut data = json!({
+        "from": relation.from,
+        "to": relation.to,
+        "relation_type": relation.relation_type,
+        "valid_from": relation.valid_from,
+        "valid_to": relation.valid_to,
+        "created_at": relation.created_at
+    });
+    if !relation.properties.is_empty() {
+        data["properties"] = json!(relation.properties);
+    }
+    pending.event(kb, EventType::RelationCreated, data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_store::EventStore;
+
+    fn relation(from: &str, to: &str, relation_type: &str) -> Relation {
+        Relation::new(from.to_string(), to.to_string(), relation_type.to_string())
+    }
+
+    #[test]
+    fn test_normalize_relations_replays_and_is_idempotent() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        kb.graph.write().unwrap().relations = vec![
+            relation("A", "B", "depends_on"),
+            relation("A", "B", "Depends_On"),
+            relation("A", "B", "depends_on"),
+            relation("B", "C", "relates_to"),
+            relation("C", "B", "relates_to"),
+            relation("C", "D", "Contains"),
+        ];
+        let symmetric = parse_symmetric_types(["relates_to"]);
+
+        let preview = kb.preview_normalize_relations(Some(&symmetric));
+        let actions: Vec<NormalizeAction> = preview.iter().map(|c| c.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                NormalizeAction::Duplicate,
+                NormalizeAction::Duplicate,
+                NormalizeAction::Reciprocal,
+                NormalizeAction::Retyped
+            ]
+        );
+
+        assert_eq!(kb.normalize_relations(Some(&symmetric)).unwrap(), preview);
+        let triples = |relations: &[Relation]| -> Vec<(String, String, String)> {
+            relations
+                .iter()
+                .map(|r| (r.from.clone(), r.to.clone(), r.relation_type.clone()))
+                .collect()
+        };
+        let expected = vec![
+            ("A".to_string(), "B".to_string(), "depends_on".to_string()),
+            ("B".to_string(), "C".to_string(), "relates_to".to_string()),
+            ("C".to_string(), "D".to_string(), "contains".to_string()),
+        ];
+        assert_eq!(triples(&kb.graph.read().unwrap().relations), expected);
+        assert!(kb.normalize_relations(Some(&symmetric)).unwrap().is_empty());
+
+        // Seed the replayed state with the original relations, as a snapshot would
+        let store = EventStore::with_config(crate::event_store::EventStoreConfig::with_data_dir(
+            temp_dir.path(),
+        ));
+        let mut entities = Vec::new();
+        let mut relations = vec![
+            relation("A", "B", "depends_on"),
+            relation("A", "B", "Depends_On"),
+            relation("A", "B", "depends_on"),
+            relation("B", "C", "relates_to"),
+            relation("C", "B", "relates_to"),
+            relation("C", "D", "Contains"),
+        ];
+        store
+            .replay_after(&mut entities, &mut relations, 0)
+            .unwrap();
+        let mut replayed = triples(&relations);
+        replayed.sort();
+        assert_eq!(replayed, expected);
+    }
+}
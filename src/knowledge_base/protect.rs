@@ -0,0 +1,131 @@
+//! Protection of critical entities against deletion
+//!
+//! A protected entity is skipped by `delete_entities` (and the transaction
+//! op) unless the deletion is forced. This guards project roots and
+//! conventions against runaway agents; it is not an access control.
+
+use std::collections::HashSet;
+
+use serde_json::json;
+
+use crate::api::websocket::events::GraphEvent;
+use crate::types::{EventType, McpResult};
+use crate::utils::time::current_timestamp;
+
+use super::acl;
+use super::crud;
+use super::KnowledgeBase;
+
+/// Set or clear the protected flag (thread-safe: holds write lock during entire operation)
+///
+/// Every name must exist. Each change is recorded as an `EntityUpdated`
+/// event; the names whose flag actually changed are returned.
+pub fn set_protected(
+    kb: &KnowledgeBase,
+    entity_names: &[String],
+    protected: bool,
+) -> McpResult<Vec<String>> {
+    crud::commit(kb, |graph, pending| {
+        let names: HashSet<&str> = entity_names
+            .iter()
+            .map(|name| graph.resolve_name(name))
+            .collect();
+        if let Some(missing) = names
+            .iter()
+            .find(|name| !graph.entities.iter().any(|e| e.name == **name))
+        {
+            return Err(format!("Entity '{}' not found", missing).into());
+        }
+        acl::check_writable(graph, names.iter().copied())?;
+        let names: HashSet<String> = names.into_iter().map(String::from).collect();
+
+        let now = current_timestamp();
+        let mut changed = Vec::new();
+        for entity in graph
+            .entities
+            .iter_mut()
+            .filter(|e| names.contains(&e.name) && e.protected != protected)
+        {
+            entity.protected = protected;
+            entity.updated_at = now;
+            entity.updated_by = kb.acting_user();
+
+            pending.event(
+                kb,
+                EventType::EntityUpdated,
+                json!({ "name": entity.name, "protected": protected }),
+            );
+            pending.broadcast(GraphEvent::EntityUpdated {
+                name: entity.name.clone(),
+                new_observations: Vec::new(),
+                user: Some(kb.acting_user()),
+            });
+            changed.push(entity.name.clone());
+        }
+
+        Ok(changed)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_store::EventStore;
+    use crate::types::{Entity, Op, OpResult};
+
+    #[test]
+    fn test_protected_entities_survive_deletes() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        kb.create_entities(vec![
+            Entity::new("Root".to_string(), "Project".to_string()),
+            Entity::new("Naming".to_string(), "Convention".to_string()),
+            Entity::new("Scratch".to_string(), "Module".to_string()),
+        ])
+        .unwrap();
+
+        let names = ["Root".to_string(), "Naming".to_string()];
+        assert_eq!(kb.protect_entities(&names).unwrap(), names);
+        assert!(kb.protect_entities(&names).unwrap().is_empty());
+        assert!(kb.protect_entities(&["Missing".to_string()]).is_err());
+
+        let skipped = kb
+            .delete_entities(vec!["Root".to_string(), "Scratch".to_string()])
+            .unwrap();
+        assert_eq!(skipped, vec!["Root"]);
+        let results = kb
+            .transaction(vec![Op::DeleteEntities {
+                entity_names: vec!["Naming".to_string()],
+                force: false,
+            }])
+            .unwrap();
+        assert!(matches!(
+            &results[0],
+            OpResult::DeleteEntities { skipped } if skipped == &["Naming"]
+        ));
+
+        // Replaying the log restores the flags
+        let events = kb
+            .event_store
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .load_events()
+            .unwrap();
+        let (mut entities, mut relations) = (Vec::new(), Vec::new());
+        for event in &events {
+            EventStore::apply_event(&mut entities, &mut relations, event).unwrap();
+        }
+        assert_eq!(entities.len(), 2);
+        assert!(entities.iter().all(|e| e.protected));
+
+        kb.unprotect_entities(&["Naming".to_string()]).unwrap();
+        assert!(kb
+            .delete_entities(vec!["Naming".to_string()])
+            .unwrap()
+            .is_empty());
+        kb.force_delete_entities(vec!["Root".to_string()]).unwrap();
+        assert!(kb.read_graph(None, None).unwrap().entities.is_empty());
+    }
+}
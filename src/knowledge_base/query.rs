@@ -1,11 +1,14 @@
 //! Query operations for the knowledge base
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use crate::search::{get_synonyms, matches_with_synonyms};
-use crate::types::{Entity, KnowledgeGraph, McpResult, Relation};
+use crate::search::{get_synonyms, relevance_score};
+use crate::types::{
+    Entity, GraphSchema, KnowledgeGraph, McpResult, ObservationOrder, PropertyFilter, Relation,
+    RelationQuery, SchemaEdge, ScoredEntity, SearchOptions, SearchResult,
+};
 
-use super::KnowledgeBase;
+use super::{acl, KnowledgeBase};
 
 /// Read graph with optional pagination
 pub fn read_graph(
@@ -13,7 +16,7 @@ pub fn read_graph(
     limit: Option<usize>,
     offset: Option<usize>,
 ) -> McpResult<KnowledgeGraph> {
-    let graph = kb.load_graph()?;
+    let graph = acl::visible(kb.load_graph()?);
 
     let offset = offset.unwrap_or(0);
 
@@ -34,62 +37,229 @@ pub fn read_graph(
     Ok(KnowledgeGraph { entities, relations })
 }
 
-/// Search nodes by query with synonym expansion, optional limit and relation inclusion
+/// Search nodes by query with synonym expansion, ranked by relevance
+///
+/// Filters and pagination come from `options`; `total` counts all matches
+/// before `offset` and `limit` are applied.
 pub fn search_nodes(
     kb: &KnowledgeBase,
     query: &str,
-    limit: Option<usize>,
-    include_relations: bool,
-) -> McpResult<KnowledgeGraph> {
-    let graph = kb.load_graph()?;
+    options: &SearchOptions,
+) -> McpResult<SearchResult> {
+    let graph = acl::visible(kb.load_graph()?);
 
     // Expand query with synonyms for semantic matching
     let search_terms = get_synonyms(query);
 
-    let mut matching_entities: Vec<Entity> = graph
+    let type_filter: Option<HashSet<String>> = options
+        .entity_types
+        .as_ref()
+        .map(|types| types.iter().map(|t| t.to_lowercase()).collect());
+
+    let mut matching_entities: Vec<ScoredEntity> = graph
         .entities
         .into_iter()
         .filter(|e| {
-            matches_with_synonyms(&e.name, &search_terms)
-                || matches_with_synonyms(&e.entity_type, &search_terms)
-                || e.observations
-                    .iter()
-                    .any(|o| matches_with_synonyms(o, &search_terms))
+            type_filter
+                .as_ref()
+                .map(|types| types.contains(&e.entity_type.to_lowercase()))
+                .unwrap_or(true)
+        })
+        .filter_map(|e| {
+            relevance_score(&e, query, &search_terms, options)
+                .map(|score| ScoredEntity { entity: e, score })
         })
         .collect();
 
-    // Apply limit if specified
-    if let Some(lim) = limit {
-        matching_entities.truncate(lim);
-    }
+    // Stable sort keeps graph order for equal scores
+    matching_entities.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    let total = matching_entities.len();
+    let page: Vec<ScoredEntity> = match options.limit {
+        Some(lim) => matching_entities
+            .into_iter()
+            .skip(options.offset)
+            .take(lim)
+            .collect(),
+        None => matching_entities.into_iter().skip(options.offset).collect(),
+    };
 
-    let matching_relations = if include_relations {
-        let entity_names: HashSet<String> =
-            matching_entities.iter().map(|e| e.name.clone()).collect();
+    let matching_relations = if options.include_relations {
+        let entity_names: HashSet<&str> = page.iter().map(|e| e.entity.name.as_str()).collect();
         graph
             .relations
             .into_iter()
-            .filter(|r| entity_names.contains(&r.from) || entity_names.contains(&r.to))
+            .filter(|r| {
+                entity_names.contains(r.from.as_str()) || entity_names.contains(r.to.as_str())
+            })
             .collect()
     } else {
         Vec::new()
     };
 
-    Ok(KnowledgeGraph {
-        entities: matching_entities,
+    kb.access_tracker
+        .record(page.iter().map(|e| e.entity.name.as_str()));
+
+    Ok(SearchResult {
+        entities: page,
         relations: matching_relations,
+        total,
     })
 }
 
-/// Open specific nodes by names
+/// Check which of the given entity names exist
+///
+/// Builds a name set once, so each lookup is O(1).
+pub fn entity_exists(kb: &KnowledgeBase, names: &[String]) -> HashMap<String, bool> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    let existing: HashSet<&str> = graph.entities.iter().map(|e| e.name.as_str()).collect();
+
+    names
+        .iter()
+        .map(|name| (name.clone(), existing.contains(name.as_str())))
+        .collect()
+}
+
+/// Names of entities that appear in no relation, optionally of one type only
+///
+/// Names are returned in graph order. The type compares case-insensitively.
+pub fn find_orphans(kb: &KnowledgeBase, entity_type: Option<&str>) -> Vec<String> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    let linked: HashSet<&str> = graph
+        .relations
+        .iter()
+        .flat_map(|r| [r.from.as_str(), r.to.as_str()])
+        .collect();
+
+    graph
+        .entities
+        .iter()
+        .filter(|e| entity_type.is_none_or(|t| e.entity_type.eq_ignore_ascii_case(t)))
+        .filter(|e| !linked.contains(e.name.as_str()))
+        .map(|e| e.name.clone())
+        .collect()
+}
+
+/// Infer the graph's schema: entity types and the type triples relations form
+///
+/// Relation types are counted under their canonical name. Triples are
+/// ordered by count, then by source, relation and target type.
+pub fn infer_schema(kb: &KnowledgeBase) -> GraphSchema {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    let mut schema = GraphSchema::default();
+
+    let mut type_of: HashMap<&str, &str> = HashMap::with_capacity(graph.entities.len());
+    for entity in &graph.entities {
+        type_of.insert(entity.name.as_str(), entity.entity_type.as_str());
+        *schema
+            .entity_types
+            .entry(entity.entity_type.clone())
+            .or_insert(0) += 1;
+    }
+
+    let mut triples: HashMap<(&str, &str, &str), usize> = HashMap::new();
+    for relation in &graph.relations {
+        match (
+            type_of.get(relation.from.as_str()),
+            type_of.get(relation.to.as_str()),
+        ) {
+            (Some(source), Some(target)) => {
+                let relation_type = kb.relation_aliases.canonical(&relation.relation_type);
+                *triples.entry((source, relation_type, target)).or_insert(0) += 1;
+            }
+            _ => schema.dangling_relations += 1,
+        }
+    }
+
+    schema.relations = triples
+        .into_iter()
+        .map(|((source, relation_type, target), count)| SchemaEdge {
+            source_type: source.to_string(),
+            relation_type: relation_type.to_string(),
+            target_type: target.to_string(),
+            count,
+        })
+        .collect();
+    schema.relations.sort_by(|a, b| {
+        b.count.cmp(&a.count).then_with(|| {
+            (&a.source_type, &a.relation_type, &a.target_type).cmp(&(
+                &b.source_type,
+                &b.relation_type,
+                &b.target_type,
+            ))
+        })
+    });
+    schema
+}
+
+/// Check whether each `(from, to, relation_type)` triple exists, in order
+pub fn relation_exists(kb: &KnowledgeBase, triples: &[(String, String, String)]) -> Vec<bool> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    let existing: HashSet<(&str, &str, &str)> = graph
+        .relations
+        .iter()
+        .map(|r| (r.from.as_str(), r.to.as_str(), r.relation_type.as_str()))
+        .collect();
+
+    triples
+        .iter()
+        .map(|(from, to, relation_type)| {
+            existing.contains(&(from.as_str(), to.as_str(), relation_type.as_str()))
+        })
+        .collect()
+}
+
+/// Relations matching endpoints, type and property predicates
+pub fn query_relations(kb: &KnowledgeBase, query: &RelationQuery) -> McpResult<Vec<Relation>> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    let matches = relation_matcher(kb, &graph, query);
+
+    Ok(graph
+        .relations
+        .iter()
+        .filter(|r| matches(r))
+        .cloned()
+        .collect())
+}
+
+/// Predicate for the relations of `graph` that match `query`
+pub(super) fn relation_matcher<'a>(
+    kb: &'a KnowledgeBase,
+    graph: &'a KnowledgeGraph,
+    query: &'a RelationQuery,
+) -> impl Fn(&Relation) -> bool + 'a {
+    let from = query.from.as_deref().map(|name| graph.resolve_name(name));
+    let to = query.to.as_deref().map(|name| graph.resolve_name(name));
+    let aliases = &kb.relation_aliases;
+    let relation_type = query.relation_type.as_deref().map(|rt| aliases.canonical(rt));
+
+    move |r| {
+        from.is_none_or(|f| r.from == f)
+            && to.is_none_or(|t| r.to == t)
+            && relation_type.is_none_or(|rt| aliases.canonical(&r.relation_type) == rt)
+            && PropertyFilter::all_match(&query.properties, &r.properties)
+    }
+}
+
+/// Open specific nodes by names (or ids)
 pub fn open_nodes(kb: &KnowledgeBase, names: Vec<String>) -> McpResult<KnowledgeGraph> {
-    let graph = kb.load_graph()?;
-    let name_set: HashSet<String> = names.into_iter().collect();
+    let graph = acl::visible(kb.load_graph()?);
+    let mut name_set: HashSet<String> = names.into_iter().collect();
+    let normalized: Vec<String> = name_set
+        .iter()
+        .map(|name| kb.entity_names.normalize(name).into_owned())
+        .collect();
+    name_set.extend(normalized);
 
     let matching_entities: Vec<Entity> = graph
         .entities
         .into_iter()
-        .filter(|e| name_set.contains(&e.name))
+        .filter(|e| name_set.contains(&e.name) || (!e.id.is_empty() && name_set.contains(&e.id)))
         .collect();
 
     let entity_names: HashSet<String> = matching_entities.iter().map(|e| e.name.clone()).collect();
@@ -100,8 +270,40 @@ pub fn open_nodes(kb: &KnowledgeBase, names: Vec<String>) -> McpResult<Knowledge
         .filter(|r| entity_names.contains(&r.from) && entity_names.contains(&r.to))
         .collect();
 
+    kb.access_tracker
+        .record(matching_entities.iter().map(|e| e.name.as_str()));
+
     Ok(KnowledgeGraph {
         entities: matching_entities,
         relations: matching_relations,
     })
 }
+
+/// Open nodes, keeping one page of each entity's observations
+///
+/// Observations are put in `order` before slicing, so `NewestFirst` with a
+/// limit returns the latest ones. Also returns each entity's total number of
+/// observations, keyed by name, so the client can page through the rest.
+pub fn open_nodes_paged(
+    kb: &KnowledgeBase,
+    names: Vec<String>,
+    order: ObservationOrder,
+    offset: usize,
+    limit: Option<usize>,
+) -> McpResult<(KnowledgeGraph, BTreeMap<String, usize>)> {
+    let mut graph = open_nodes(kb, names)?;
+    order.apply(&mut graph.entities);
+
+    let mut totals = BTreeMap::new();
+    for entity in &mut graph.entities {
+        totals.insert(entity.name.clone(), entity.observations.len());
+        let end = limit.map_or(usize::MAX, |limit| offset.saturating_add(limit));
+        entity.observations = entity
+            .observations
+            .drain(..)
+            .take(end)
+            .skip(offset)
+            .collect();
+    }
+    Ok((graph, totals))
+}
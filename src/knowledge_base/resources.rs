@@ -0,0 +1,228 @@
+//! Large tool results served as MCP resources
+//!
+//! A full `read_graph` can be megabytes of text inlined in one `tools/call`
+//! response. When `MEMORY_RESULT_LINK_BYTES` is set, `read_graph`,
+//! `open_nodes` and `search_nodes` results larger than that many bytes are
+//! stored here instead, and the tool answers with a short text item plus a
+//! `resource_link` content item:
+//!
+//! ```json
+//! { "type": "resource_link", "uri": "memory://results/7", "name": "read_graph result",
+//!   "mimeType": "application/json", "size": 1843022 }
+//! ```
+//!
+//! The client fetches the text with `resources/read` on that URI (the server
+//! advertises the `resources` capability). A stored result is the exact text
+//! the tool would have inlined, taken when the tool ran; it does not follow
+//! later changes to the graph. Only the last [`KEPT_RESULTS`] linked results
+//! are kept, in memory: older URIs, and all URIs after a restart, are not
+//! found and the tool has to be called again.
+//!
+//! A result belongs to the caller whose tool call stored it (see user.rs):
+//! it was filtered by that caller's entity ACLs, so `resources/list` and
+//! `resources/read` only show it to the same caller, and report it as not
+//! found to anyone else.
+
+use std::collections::VecDeque;
+use std::env;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use serde_json::{json, Value};
+
+use crate::types::ResultResource;
+
+use super::{user, KnowledgeBase};
+
+/// URI prefix of stored tool results
+const RESULT_URI_PREFIX: &str = "memory://results/";
+
+/// Linked results kept for `resources/read`
+const KEPT_RESULTS: usize = 16;
+
+/// A stored tool result
+#[derive(Debug)]
+struct StoredResult {
+    resource: ResultResource,
+    text: String,
+    /// Authenticated user of the tool call (`None` = no authenticated caller)
+    owner: Option<String>,
+}
+
+impl StoredResult {
+    /// Whether the current caller stored this result
+    fn is_own(&self) -> bool {
+        self.owner == user::caller().map(|caller| caller.user)
+    }
+}
+
+/// Size threshold and store for results returned as resource links
+#[derive(Debug)]
+pub(crate) struct ResultLinks {
+    /// Results larger than this many bytes are linked (0 = always inline)
+    threshold: usize,
+    next_id: AtomicU64,
+    results: Mutex<VecDeque<StoredResult>>,
+}
+
+impl ResultLinks {
+    /// Link results larger than `threshold` bytes (0 = disabled)
+    pub(crate) fn new(threshold: usize) -> Self {
+        Self {
+            threshold,
+            next_id: AtomicU64::new(1),
+            results: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Threshold from `MEMORY_RESULT_LINK_BYTES` (unset = disabled)
+    pub(crate) fn from_env() -> Self {
+        let threshold = env::var("MEMORY_RESULT_LINK_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        Self::new(threshold)
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.threshold > 0
+    }
+}
+
+/// Tool response for `text`: inline, or a link to the stored text if too large
+pub fn link_result(kb: &KnowledgeBase, tool: &str, text: String) -> Value {
+    let links = &kb.result_links;
+    if !links.is_enabled() || text.len() <= links.threshold {
+        return json!({
+            "content": [{
+                "type": "text",
+                "text": text
+            }]
+        });
+    }
+
+    let id = links.next_id.fetch_add(1, Ordering::Relaxed);
+    let resource = ResultResource {
+        uri: format!("{}{}", RESULT_URI_PREFIX, id),
+        name: format!("{} result", tool),
+        mime_type: "application/json".to_string(),
+        size: text.len(),
+    };
+    let summary = format!(
+        "Result is {} bytes; fetch it with resources/read on {}",
+        resource.size, resource.uri
+    );
+    let link = json!({
+        "type": "resource_link",
+        "uri": resource.uri,
+        "name": resource.name,
+        "mimeType": resource.mime_type,
+        "size": resource.size
+    });
+
+    let mut results = links.results.lock().unwrap();
+    results.push_back(StoredResult {
+        resource,
+        text,
+        owner: user::caller().map(|caller| caller.user),
+    });
+    if results.len() > KEPT_RESULTS {
+        results.pop_front();
+    }
+
+    json!({
+        "content": [
+            { "type": "text", "text": summary },
+            link
+        ]
+    })
+}
+
+/// The current caller's stored results, oldest first
+pub fn list_resources(kb: &KnowledgeBase) -> Vec<ResultResource> {
+    let results = kb.result_links.results.lock().unwrap();
+    results
+        .iter()
+        .filter(|r| r.is_own())
+        .map(|r| r.resource.clone())
+        .collect()
+}
+
+/// Stored text and resource for `uri`, if still kept and stored by the current caller
+pub fn read_resource(kb: &KnowledgeBase, uri: &str) -> Option<(ResultResource, String)> {
+    let results = kb.result_links.results.lock().unwrap();
+    results
+        .iter()
+        .find(|r| r.resource.uri == uri && r.is_own())
+        .map(|r| (r.resource.clone(), r.text.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{resources_read, RESOURCE_NOT_FOUND};
+
+    #[test]
+    fn test_large_results_are_linked() {
+        let path = format!("target/test_result_links_{}.jsonl", std::process::id());
+        let mut kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+
+        let inline = link_result(&kb, "read_graph", "x".repeat(100));
+        assert_eq!(inline["content"][0]["text"].as_str().unwrap().len(), 100);
+        assert!(list_resources(&kb).is_empty());
+
+        kb.result_links = ResultLinks::new(64);
+        let small = link_result(&kb, "read_graph", "{}".to_string());
+        assert_eq!(small["content"][0]["text"], "{}");
+
+        let linked = link_result(&kb, "read_graph", "y".repeat(100));
+        let link = &linked["content"][1];
+        assert_eq!(link["type"], "resource_link");
+        assert_eq!(link["size"], 100);
+        let uri = link["uri"].as_str().unwrap();
+        assert!(uri.starts_with(RESULT_URI_PREFIX));
+        let (resource, text) = read_resource(&kb, uri).unwrap();
+        assert_eq!(resource.name, "read_graph result");
+        assert_eq!(text, "y".repeat(100));
+        let read = resources_read(&kb, &json!({ "uri": uri })).unwrap();
+        assert_eq!(read["contents"][0]["text"], "y".repeat(100));
+
+        // Only the most recent results are kept
+        for _ in 0..KEPT_RESULTS {
+            link_result(&kb, "search_nodes", "z".repeat(100));
+        }
+        assert!(read_resource(&kb, uri).is_none());
+        let expired = resources_read(&kb, &json!({ "uri": uri })).unwrap_err();
+        assert_eq!(expired.code, RESOURCE_NOT_FOUND);
+        let kept = list_resources(&kb);
+        assert_eq!(kept.len(), KEPT_RESULTS);
+        assert!(kept.iter().all(|r| r.name == "search_nodes result"));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_linked_results_belong_to_their_caller() {
+        let path = format!("target/test_result_owners_{}.jsonl", std::process::id());
+        let mut kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        kb.result_links = ResultLinks::new(8);
+
+        let linked = KnowledgeBase::run_as(Some("alice"), || {
+            link_result(&kb, "read_graph", "a".repeat(100))
+        });
+        let uri = linked["content"][1]["uri"].as_str().unwrap();
+
+        KnowledgeBase::run_as(Some("alice"), || {
+            assert_eq!(list_resources(&kb).len(), 1);
+            assert_eq!(read_resource(&kb, uri).unwrap().1, "a".repeat(100));
+        });
+        KnowledgeBase::run_as(Some("bob"), || {
+            assert!(list_resources(&kb).is_empty());
+            let denied = resources_read(&kb, &json!({ "uri": uri })).unwrap_err();
+            assert_eq!(denied.code, RESOURCE_NOT_FOUND);
+        });
+        assert!(read_resource(&kb, uri).is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,299 @@
+//! Restoring a backup over the current state
+//!
+//! Accepts either backup format (see backup.rs): a legacy `memory.jsonl`, or
+//! an Event Sourcing backup starting with a snapshot metadata line. The file
+//! is validated in full before anything is touched: every line must be a
+//! metadata header, entity, relation or event, entity names must be unique,
+//! and the entity/relation counts of an Event Sourcing backup must match its
+//! metadata line, which catches truncated files.
+//!
+//! The current state is then written to `backups/pre-restore-<ts>.jsonl`
+//! (next to `memory.jsonl`, or in the data directory) and replaced:
+//!
+//! - Legacy mode rewrites `memory.jsonl`. Events in the backup are ignored.
+//! - Event Sourcing mode replaces the snapshots with one snapshot of the
+//!   restored state, the active log with the backup's events (the segments
+//!   are removed, archives are kept as they are) and the metadata sidecar,
+//!   then reinitializes the event store from them. The snapshot's log
+//!   position is the end of the restored log, as the backup's state already
+//!   reflects its events. A legacy file restored in this mode keeps the
+//!   current event IDs and starts an empty log.
+//!
+//! The graph write lock is held throughout, so no mutation interleaves.
+//! Clients are sent a `resync` event once the new graph is in place.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use crate::api::websocket::broadcast_event;
+use crate::api::websocket::events::GraphEvent;
+use crate::event_store::LogRotation;
+use crate::types::{
+    BackupMode, Event, GraphMetadata, InvalidParams, KnowledgeGraph, McpResult, RestoreReport,
+    SnapshotMeta,
+};
+use crate::utils::atomic_write_with;
+use crate::utils::time::current_timestamp;
+
+use super::backup::write_backup_of;
+use super::load::{parse_line, Record};
+use super::{metadata, KnowledgeBase};
+
+/// A validated backup file
+#[derive(Debug, Default)]
+pub(super) struct ParsedBackup {
+    meta: Option<SnapshotMeta>,
+    metadata: GraphMetadata,
+    graph: KnowledgeGraph,
+    events: Vec<Event>,
+}
+
+impl ParsedBackup {
+    fn mode(&self) -> BackupMode {
+        if self.meta.is_some() {
+            BackupMode::EventSourcing
+        } else {
+            BackupMode::Legacy
+        }
+    }
+}
+
+/// Parse and validate a backup file
+pub(super) fn parse_backup(content: &str) -> McpResult<ParsedBackup> {
+    let invalid = |line: usize, reason: String| InvalidParams(format!("Line {}: {}", line, reason));
+    let mut parsed = ParsedBackup::default();
+    let mut names = HashSet::new();
+    let mut first = true;
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let number = index + 1;
+
+        if std::mem::take(&mut first) && line.contains("\"snapshot_meta\"") {
+            let meta = SnapshotMeta::from_json_line(line)
+                .map_err(|e| invalid(number, format!("invalid snapshot metadata ({})", e)))?;
+            parsed.meta = Some(meta);
+            continue;
+        }
+        if let Some(metadata) = GraphMetadata::from_header_line(line) {
+            parsed.metadata = metadata;
+            continue;
+        }
+        if line.contains("\"eventType\"") {
+            let event = Event::from_json_line(line)
+                .map_err(|e| invalid(number, format!("invalid event ({})", e)))?;
+            parsed.events.push(event);
+            continue;
+        }
+
+        match parse_line(line).map_err(|reason| invalid(number, reason))? {
+            Record::Entity(entity) => {
+                if !names.insert(entity.name.clone()) {
+                    return Err(invalid(number, format!("duplicate entity '{}'", entity.name)).into());
+                }
+                parsed.graph.entities.push(entity);
+            }
+            Record::Relation(relation) => parsed.graph.relations.push(relation),
+            Record::Metadata => {}
+        }
+    }
+
+    if first {
+        return Err(InvalidParams("Backup is empty".to_string()).into());
+    }
+    if let Some(ref meta) = parsed.meta {
+        let counts = (parsed.graph.entities.len(), parsed.graph.relations.len());
+        if counts != (meta.entity_count, meta.relation_count) {
+            return Err(InvalidParams(format!(
+                "Backup holds {} entities and {} relations but its metadata says {} and {}; \
+                 the file may be truncated",
+                counts.0, counts.1, meta.entity_count, meta.relation_count
+            ))
+            .into());
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Replace the current state with a backup (thread-safe: holds write lock during entire operation)
+pub fn restore_backup(kb: &KnowledgeBase, content: &str) -> McpResult<RestoreReport> {
+    let parsed = parse_backup(content)?;
+    let mode = parsed.mode();
+
+    // Same order as snapshot writers, so a pending snapshot cannot interleave
+    let _writing = kb.snapshots.write_lock.lock().unwrap();
+    let mut graph = kb.graph.write().unwrap();
+
+    let safety_backup = safety_backup_path(kb);
+    write_safety_backup(kb, &graph, &safety_backup)?;
+
+    let ParsedBackup {
+        meta,
+        metadata: restored_metadata,
+        graph: restored,
+        events,
+    } = parsed;
+    let mut report = RestoreReport {
+        mode,
+        entities: restored.entities.len(),
+        relations: restored.relations.len(),
+        events: 0,
+        safety_backup: safety_backup.to_string_lossy().to_string(),
+    };
+
+    let restored = if let Some(ref event_store) = kb.event_store {
+        let mut store = event_store.lock().unwrap();
+        let config = store.config().clone();
+        let last_event_id = match meta {
+            Some(ref meta) => meta.last_event_id,
+            None => store.next_event_id().saturating_sub(1),
+        };
+        let events: Vec<Event> = if meta.is_some() { events } else { Vec::new() };
+        // The restored state already reflects every event of the backup
+        let rotation = LogRotation::new(config.clone());
+        let log_position = rotation.archived_lines()? + events.len() as u64;
+
+        if let Some(ref snapshot_manager) = kb.snapshot_manager {
+            snapshot_manager.clear_snapshots()?;
+            snapshot_manager.create_snapshot(
+                last_event_id,
+                Some(log_position),
+                &restored.entities,
+                &restored.relations,
+            )?;
+        }
+        atomic_write_with(config.events_path(), |file| {
+            for event in &events {
+                let json = serde_json::to_string(event)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+                writeln!(file, "{}", json)?;
+            }
+            Ok(())
+        })?;
+        for segment in rotation.list_segments()? {
+            fs::remove_file(segment)?;
+        }
+        metadata::save_sidecar(&config, &restored_metadata)?;
+
+        let (entities, relations) = store.initialize()?;
+        report.events = events.len();
+        KnowledgeGraph { entities, relations }
+    } else {
+        kb.persist_with_metadata(&restored, &restored_metadata)?;
+        restored
+    };
+
+    *graph = restored;
+    *kb.graph_metadata.write().unwrap() = restored_metadata;
+    kb.graph_version.fetch_add(1, Ordering::Release);
+    drop(graph);
+
+    broadcast_event(GraphEvent::Resync {
+        reason: "restore".to_string(),
+        user: Some(kb.acting_user()),
+    });
+    Ok(report)
+}
+
+/// Where the state replaced by a restore is saved
+fn safety_backup_path(kb: &KnowledgeBase) -> PathBuf {
+    let dir = match kb.event_store {
+        Some(ref event_store) => event_store.lock().unwrap().config().data_dir().to_path_buf(),
+        None => Path::new(&kb.memory_file_path)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+    };
+    dir.join("backups")
+        .join(format!("pre-restore-{}.jsonl", current_timestamp()))
+}
+
+fn write_safety_backup(kb: &KnowledgeBase, graph: &KnowledgeGraph, path: &Path) -> McpResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut out = BufWriter::new(File::create(path)?);
+    write_backup_of(kb, graph, &mut out)?;
+    out.flush()?;
+    out.get_ref().sync_all()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Entity, Relation};
+
+    fn entity(name: &str) -> Entity {
+        Entity::new(name.to_string(), "Module".to_string())
+    }
+
+    fn backup_of(kb: &KnowledgeBase) -> String {
+        let mut backup = Vec::new();
+        kb.write_backup(&mut backup).unwrap();
+        String::from_utf8(backup).unwrap()
+    }
+
+    #[test]
+    fn test_event_sourcing_restore_round_trips() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        kb.create_entities(vec![entity("Api"), entity("Auth")]).unwrap();
+        kb.create_relations(vec![Relation::new(
+            "Api".to_string(),
+            "Auth".to_string(),
+            "depends_on".to_string(),
+        )])
+        .unwrap();
+        let backup = backup_of(&kb);
+
+        kb.delete_entities(vec!["Auth".to_string()]).unwrap();
+        kb.create_entities(vec![entity("Billing")]).unwrap();
+
+        let report = kb.restore_backup(&backup).unwrap();
+        assert_eq!(report.mode, BackupMode::EventSourcing);
+        assert_eq!((report.entities, report.relations, report.events), (2, 1, 3));
+        let safety = fs::read_to_string(&report.safety_backup).unwrap();
+        assert!(safety.contains("\"name\":\"Billing\""));
+
+        let names = |kb: &KnowledgeBase| -> Vec<String> {
+            kb.graph.read().unwrap().entities.iter().map(|e| e.name.clone()).collect()
+        };
+        assert_eq!(names(&kb), vec!["Api", "Auth"]);
+        assert_eq!(kb.graph.read().unwrap().relations.len(), 1);
+
+        // The event store continues from the restored log
+        kb.create_entities(vec![entity("Cache")]).unwrap();
+        let reopened =
+            KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        assert_eq!(names(&reopened), vec!["Api", "Auth", "Cache"]);
+    }
+
+    #[test]
+    fn test_invalid_backup_is_rejected_untouched() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("memory.jsonl");
+        let kb = KnowledgeBase::for_testing(path.to_string_lossy().to_string(), "tester".to_string());
+        kb.create_entities(vec![entity("Api")]).unwrap();
+        let backup = backup_of(&kb);
+
+        let duplicated = format!("{}{}", backup, backup);
+        assert!(kb.restore_backup(&duplicated).is_err());
+        assert!(kb.restore_backup("not json").is_err());
+        assert!(kb.restore_backup("").is_err());
+        assert!(!temp_dir.path().join("backups").exists());
+
+        kb.create_entities(vec![entity("Auth")]).unwrap();
+        let report = kb.restore_backup(&backup).unwrap();
+        assert_eq!((report.mode, report.entities), (BackupMode::Legacy, 1));
+        assert_eq!(kb.graph.read().unwrap().entities.len(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), backup);
+    }
+}
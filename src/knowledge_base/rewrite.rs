@@ -0,0 +1,485 @@
+//! Graph-wide search-and-replace for observations, and entity and relation
+//! type renames
+
+use std::collections::HashSet;
+
+use regex::Regex;
+use serde_json::json;
+
+use crate::api::websocket::events::GraphEvent;
+use crate::types::{EventType, KnowledgeGraph, McpResult, ObservationChange, RelationTypeChange};
+use crate::utils::time::current_timestamp;
+
+use super::crud::{self, PendingEffects};
+use super::{acl, KnowledgeBase};
+
+/// Literal or regex pattern to rewrite
+enum Pattern {
+    Literal(String),
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn new(find: &str, regex: bool) -> McpResult<Self> {
+        if find.is_empty() {
+            return Err("Search pattern must not be empty".into());
+        }
+        if regex {
+            Ok(Self::Regex(Regex::new(find)?))
+        } else {
+            Ok(Self::Literal(find.to_string()))
+        }
+    }
+
+    /// Rewrite `text`, returning `None` when nothing matched
+    fn replace(&self, text: &str, replace: &str) -> Option<String> {
+        match self {
+            Self::Literal(find) => text
+                .contains(find.as_str())
+                .then(|| text.replace(find, replace)),
+            Self::Regex(re) => re
+                .is_match(text)
+                .then(|| re.replace_all(text, replace).into_owned()),
+        }
+    }
+}
+
+/// Compute every observation rewrite without touching the graph
+fn collect_changes(
+    graph: &KnowledgeGraph,
+    pattern: &Pattern,
+    replace: &str,
+) -> Vec<ObservationChange> {
+    graph
+        .entities
+        .iter()
+        .flat_map(|e| {
+            e.observations.iter().filter_map(move |o| {
+                pattern
+                    .replace(o, replace)
+                    .filter(|after| after != o)
+                    .map(|after| ObservationChange {
+                        entity_name: e.name.clone(),
+                        before: o.clone(),
+                        after,
+                    })
+            })
+        })
+        .collect()
+}
+
+/// Preview a graph-wide rewrite (dry run)
+pub fn preview_replace_in_observations(
+    kb: &KnowledgeBase,
+    find: &str,
+    replace: &str,
+    regex: bool,
+) -> McpResult<Vec<ObservationChange>> {
+    let pattern = Pattern::new(find, regex)?;
+    let graph = kb.graph.read().unwrap();
+    let changes = collect_changes(&graph, &pattern, replace);
+    check_changes_writable(&graph, &changes)?;
+    Ok(changes)
+}
+
+/// Rewrite matching text in all observations (thread-safe: holds write lock during entire operation)
+///
+/// `regex` switches `find` to a regular expression, in which case `replace`
+/// may reference capture groups (`$1`). Each rewrite is recorded as an
+/// `ObservationEdited` event keeping the old text, so the entity timeline
+/// shows what it changed from. Returns the number of observations changed.
+pub fn replace_in_observations(
+    kb: &KnowledgeBase,
+    find: &str,
+    replace: &str,
+    regex: bool,
+) -> McpResult<usize> {
+    let pattern = Pattern::new(find, regex)?;
+
+    crud::commit(kb, |graph, pending| {
+        let changes = collect_changes(graph, &pattern, replace);
+        check_changes_writable(graph, &changes)?;
+        apply_changes(kb, graph, &changes, pending);
+        Ok(changes.len())
+    })
+}
+
+/// Fail if the current caller may not change an entity a rewrite touches
+fn check_changes_writable(graph: &KnowledgeGraph, changes: &[ObservationChange]) -> McpResult<()> {
+    acl::check_writable(graph, changes.iter().map(|c| c.entity_name.as_str()))
+}
+
+fn apply_changes(
+    kb: &KnowledgeBase,
+    graph: &mut KnowledgeGraph,
+    changes: &[ObservationChange],
+    pending: &mut PendingEffects,
+) {
+    let now = current_timestamp();
+
+    for entity in graph.entities.iter_mut() {
+        let entity_changes: Vec<&ObservationChange> = changes
+            .iter()
+            .filter(|c| c.entity_name == entity.name)
+            .collect();
+        if entity_changes.is_empty() {
+            continue;
+        }
+
+        let mut new_observations = Vec::new();
+        for change in entity_changes {
+            pending.event(
+                kb,
+                EventType::ObservationEdited,
+                json!({
+                    "entity": entity.name,
+                    "before": change.before,
+                    "after": change.after
+                }),
+            );
+
+            // Rewriting into an existing observation just drops the old one
+            if entity.observations.contains(&change.after) {
+                entity.observations.retain(|o| o != &change.before);
+                continue;
+            }
+
+            if let Some(slot) = entity
+                .observations
+                .iter_mut()
+                .find(|o| **o == change.before)
+            {
+                *slot = change.after.clone();
+            }
+            new_observations.push(change.after.clone());
+        }
+
+        entity.updated_at = now;
+        entity.updated_by = kb.acting_user();
+
+        pending.broadcast(GraphEvent::EntityUpdated {
+            name: entity.name.clone(),
+            new_observations,
+            user: Some(kb.acting_user()),
+        });
+    }
+}
+
+/// Canonical target type for a rename, rejecting no-op and empty renames
+fn rename_target(kb: &KnowledgeBase, old: &str, new: &str) -> McpResult<String> {
+    let new = kb.relation_aliases.canonical(new.trim());
+    if old.is_empty() || new.is_empty() {
+        return Err("Relation types must not be empty".into());
+    }
+    if new == old {
+        return Err(format!("Relation type '{}' is already '{}'", old, new).into());
+    }
+    Ok(new.to_string())
+}
+
+/// Compute every relation retyping without touching the graph
+fn collect_renames(graph: &KnowledgeGraph, old: &str, new: &str) -> Vec<RelationTypeChange> {
+    let existing: HashSet<(&str, &str)> = graph
+        .relations
+        .iter()
+        .filter(|r| r.relation_type == new)
+        .map(|r| (r.from.as_str(), r.to.as_str()))
+        .collect();
+
+    graph
+        .relations
+        .iter()
+        .filter(|r| r.relation_type == old)
+        .map(|r| RelationTypeChange {
+            from: r.from.clone(),
+            to: r.to.clone(),
+            duplicate: existing.contains(&(r.from.as_str(), r.to.as_str())),
+        })
+        .collect()
+}
+
+/// Preview a relation type rename (dry run)
+pub fn preview_rename_relation_type(
+    kb: &KnowledgeBase,
+    old: &str,
+    new: &str,
+) -> McpResult<Vec<RelationTypeChange>> {
+    let new = rename_target(kb, old, new)?;
+    let graph = kb.graph.read().unwrap();
+    let changes = collect_renames(&graph, old, &new);
+    check_renames_writable(&graph, &changes)?;
+    Ok(changes)
+}
+
+/// Rename a relation type on every relation (thread-safe: holds write lock during entire operation)
+///
+/// `new` is normalized through the relation aliases, like a created relation.
+/// Each retyped relation is recorded as a `RelationDeleted` + `RelationCreated`
+/// pair keeping its validity, properties and creation time. A relation whose
+/// renamed triple already exists is only deleted. Returns the number of
+/// relations changed, dropped duplicates included.
+pub fn rename_relation_type(kb: &KnowledgeBase, old: &str, new: &str) -> McpResult<usize> {
+    let new = rename_target(kb, old, new)?;
+
+    crud::commit(kb, |graph, pending| {
+        let changes = collect_renames(graph, old, &new);
+        check_renames_writable(graph, &changes)?;
+        apply_renames(kb, graph, old, &new, &changes, pending);
+        Ok(changes.len())
+    })
+}
+
+/// Fail if the current caller may not change an endpoint of a retyped relation
+fn check_renames_writable(graph: &KnowledgeGraph, changes: &[RelationTypeChange]) -> McpResult<()> {
+    let endpoints = changes.iter().flat_map(|c| [c.from.as_str(), c.to.as_str()]);
+    acl::check_writable(graph, endpoints)
+}
+
+fn apply_renames(
+    kb: &KnowledgeBase,
+    graph: &mut KnowledgeGraph,
+    old: &str,
+    new: &str,
+    changes: &[RelationTypeChange],
+    pending: &mut PendingEffects,
+) {
+    let now = current_timestamp();
+    let duplicates: HashSet<(&str, &str)> = changes
+        .iter()
+        .filter(|c| c.duplicate)
+        .map(|c| (c.from.as_str(), c.to.as_str()))
+        .collect();
+
+    graph.relations.retain_mut(|relation| {
+        if relation.relation_type != old {
+            return true;
+        }
+
+        pending.event(
+            kb,
+            EventType::RelationDeleted,
+            json!({
+                "from": relation.from,
+                "to": relation.to,
+                "relation_type": relation.relation_type
+            }),
+        );
+        pending.broadcast(GraphEvent::RelationDeleted {
+            from: relation.from.clone(),
+            to: relation.to.clone(),
+            relation_type: relation.relation_type.clone(),
+            user: Some(kb.acting_user()),
+        });
+
+        // The renamed triple already exists: drop this copy
+        if duplicates.contains(&(relation.from.as_str(), relation.to.as_str())) {
+            return false;
+        }
+
+        relation.relation_type = new.to_string();
+        relation.updated_at = now;
+
+        let mut data = json!({
+            "from": relation.from,
+            "to": relation.to,
+            "relation_type": relation.relation_type,
+            "valid_from": relation.valid_from,
+            "valid_to": relation.valid_to,
+            "created_at": relation.created_at
+        });
+        if !relation.properties.is_empty() {
+            data["properties"] = json!(relation.properties);
+        }
+        pending.event(kb, EventType::RelationCreated, data);
+        pending.broadcast(GraphEvent::RelationCreated {
+            payload: relation.clone(),
+            user: Some(kb.acting_user()),
+        });
+        true
+    });
+}
+
+/// Validated target type for an entity type rename
+fn entity_rename_target<'a>(old: &str, new: &'a str) -> McpResult<&'a str> {
+    let new = new.trim();
+    if old.is_empty() || new.is_empty() {
+        return Err("Entity types must not be empty".into());
+    }
+    if new == old {
+        return Err(format!("Entity type '{}' is already '{}'", old, new).into());
+    }
+    Ok(new)
+}
+
+/// Preview an entity type rename (dry run): names of the entities to retype
+pub fn preview_rename_entity_type(
+    kb: &KnowledgeBase,
+    old: &str,
+    new: &str,
+) -> McpResult<Vec<String>> {
+    entity_rename_target(old, new)?;
+    let graph = kb.graph.read().unwrap();
+    let names: Vec<String> = graph
+        .entities
+        .iter()
+        .filter(|e| e.entity_type == old)
+        .map(|e| e.name.clone())
+        .collect();
+    acl::check_writable(&graph, names.iter().map(String::as_str))?;
+    Ok(names)
+}
+
+/// Rename an entity type on every entity (thread-safe: holds write lock during entire operation)
+///
+/// Each retyped entity is recorded as an `EntityUpdated` event carrying the
+/// new type. Returns the number of entities changed.
+pub fn rename_entity_type(kb: &KnowledgeBase, old: &str, new: &str) -> McpResult<usize> {
+    let new = entity_rename_target(old, new)?;
+
+    crud::commit(kb, |graph, pending| {
+        let retyped = graph.entities.iter().filter(|e| e.entity_type == old);
+        acl::check_writable(graph, retyped.map(|e| e.name.as_str()))?;
+
+        let now = current_timestamp();
+        let mut changed = 0;
+
+        for entity in graph.entities.iter_mut().filter(|e| e.entity_type == old) {
+            entity.entity_type = new.to_string();
+            entity.updated_at = now;
+            entity.updated_by = kb.acting_user();
+            changed += 1;
+
+            pending.event(
+                kb,
+                EventType::EntityUpdated,
+                json!({ "name": entity.name, "entity_type": entity.entity_type }),
+            );
+            pending.broadcast(GraphEvent::EntityUpdated {
+                name: entity.name.clone(),
+                new_observations: Vec::new(),
+                user: Some(kb.acting_user()),
+            });
+        }
+
+        Ok(changed)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_store::EventStore;
+    use crate::types::{Entity, Relation};
+
+    #[test]
+    fn test_rename_relation_type_replays() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        kb.create_entities(
+            ["Spec", "Api", "Guide"]
+                .map(|n| Entity::new(n.to_string(), "Document".to_string()))
+                .to_vec(),
+        )
+        .unwrap();
+        let mut dated = Relation::new(
+            "Spec".to_string(),
+            "Api".to_string(),
+            "relates_to".to_string(),
+        );
+        dated.valid_from = Some(100);
+        kb.create_relations(vec![
+            dated,
+            Relation::new(
+                "Spec".to_string(),
+                "Guide".to_string(),
+                "relates_to".to_string(),
+            ),
+            Relation::new(
+                "Spec".to_string(),
+                "Guide".to_string(),
+                "references".to_string(),
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            kb.rename_relation_type("relates_to", "references").unwrap(),
+            2
+        );
+        assert_eq!(
+            kb.rename_relation_type("relates_to", "references").unwrap(),
+            0
+        );
+
+        let events = kb
+            .event_store
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .load_events()
+            .unwrap();
+        let (mut entities, mut relations) = (Vec::new(), Vec::new());
+        for event in &events {
+            EventStore::apply_event(&mut entities, &mut relations, event).unwrap();
+        }
+        let triples = |relations: &[Relation]| {
+            let mut triples: Vec<_> = relations
+                .iter()
+                .map(|r| (r.to.clone(), r.relation_type.clone(), r.valid_from))
+                .collect();
+            triples.sort();
+            triples
+        };
+        let graph = kb.graph.read().unwrap();
+        assert_eq!(
+            triples(&graph.relations),
+            vec![
+                ("Api".to_string(), "references".to_string(), Some(100)),
+                ("Guide".to_string(), "references".to_string(), None),
+            ]
+        );
+        assert_eq!(triples(&relations), triples(&graph.relations));
+    }
+
+    #[test]
+    fn test_rename_entity_type_replays() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        kb.create_entities(vec![
+            Entity::new("Login fails".to_string(), "Ticket".to_string()),
+            Entity::new("Slow search".to_string(), "Ticket".to_string()),
+            Entity::new("Search".to_string(), "Module".to_string()),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            kb.preview_rename_entity_type("Ticket", "Issue").unwrap(),
+            vec!["Login fails", "Slow search"]
+        );
+        assert!(kb.rename_entity_type("Ticket", "Ticket").is_err());
+        assert_eq!(kb.rename_entity_type("Ticket", " Issue ").unwrap(), 2);
+
+        let events = kb
+            .event_store
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .load_events()
+            .unwrap();
+        let (mut entities, mut relations) = (Vec::new(), Vec::new());
+        for event in &events {
+            EventStore::apply_event(&mut entities, &mut relations, event).unwrap();
+        }
+        let types = |entities: &[Entity]| {
+            entities
+                .iter()
+                .map(|e| e.entity_type.clone())
+                .collect::<Vec<_>>()
+        };
+        let graph = kb.graph.read().unwrap();
+        assert_eq!(types(&graph.entities), vec!["Issue", "Issue", "Module"]);
+        assert_eq!(types(&entities), types(&graph.entities));
+    }
+}
@@ -0,0 +1,118 @@
+//! Session scratch graphs
+//!
+//! A scoped SSE session works on a private copy of the shared graph. The copy
+//! lives in memory only: its mutations are neither persisted nor broadcast, so
+//! other agents never see them. Promoting merges the session's additions (new
+//! entities, new observations and new relations) into the shared graph in one
+//! commit, with the usual events and broadcasts. Deletions made in the scratch
+//! graph are not promoted.
+
+use std::collections::HashSet;
+use std::sync::atomic::AtomicU64;
+use std::sync::{Mutex, RwLock};
+
+use crate::types::{Entity, ImportReport, KnowledgeGraph, McpResult, ObservationOrder};
+use crate::validation::{
+    CardinalityConstraints, DedupMode, EntityTemplates, NamePolicy, ObservationLimits,
+    RelationAliases, ValidationWarning,
+};
+
+use super::access::AccessTracker;
+use super::changes::ChangeLog;
+use super::inference::cache::InferenceCache;
+use super::resources::ResultLinks;
+use super::snapshot::SnapshotWorker;
+use super::{import, KnowledgeBase};
+
+/// In-memory copy of `shared` for a scoped session
+pub fn scratch_copy(shared: &KnowledgeBase) -> KnowledgeBase {
+    let graph = shared.graph.read().unwrap().clone();
+
+    KnowledgeBase {
+        memory_file_path: String::new(),
+        graph: RwLock::new(graph),
+        current_user: shared.current_user.clone(),
+        event_store: None,
+        snapshot_manager: None,
+        log_rotation: None,
+        event_sourcing_enabled: false,
+        access_tracker: AccessTracker::new(false),
+        recent_changes: ChangeLog::new(0),
+        result_links: ResultLinks::new(0),
+        observation_limits: ObservationLimits::from_env(),
+        observation_dedup: DedupMode::from_env(),
+        cardinality: CardinalityConstraints::from_env(),
+        observation_order: ObservationOrder::from_env(),
+        relation_aliases: RelationAliases::from_env(),
+        entity_names: NamePolicy::from_env(),
+        entity_templates: EntityTemplates::from_env(),
+        snapshots: SnapshotWorker::default(),
+        scratch: true,
+        graph_version: AtomicU64::new(0),
+        inference_cache: InferenceCache::from_env(),
+        content_hash_cache: Mutex::new(None),
+        query_budget: shared.query_budget,
+        graph_metadata: RwLock::new(shared.graph_metadata()),
+    }
+}
+
+/// Merge what `scratch` added on top of `shared` into `shared`
+///
+/// With `entity_names`, only those entities and relations touching them are
+/// promoted. Promotion goes through `import::import_graph`, so nothing is
+/// promoted if the current caller may not change an entity it names.
+pub fn promote(
+    shared: &KnowledgeBase,
+    scratch: &KnowledgeBase,
+    entity_names: Option<&[String]>,
+) -> McpResult<(ImportReport, Vec<ValidationWarning>)> {
+    let selected = |name: &str| entity_names.is_none_or(|names| names.iter().any(|n| n == name));
+
+    let incoming = {
+        let base = shared.graph.read().unwrap();
+        let graph = scratch.graph.read().unwrap();
+
+        let mut entities = Vec::new();
+        for entity in graph.entities.iter().filter(|e| selected(&e.name)) {
+            match base.entities.iter().find(|e| e.name == entity.name) {
+                None => entities.push(entity.clone()),
+                Some(known) => {
+                    let added: Vec<String> = entity
+                        .observations
+                        .iter()
+                        .filter(|o| !known.observations.contains(o))
+                        .cloned()
+                        .collect();
+                    if !added.is_empty() || known.entity_type != entity.entity_type {
+                        entities.push(Entity {
+                            observations: added,
+                            ..entity.clone()
+                        });
+                    }
+                }
+            }
+        }
+
+        let existing: HashSet<(&str, &str, &str)> = base
+            .relations
+            .iter()
+            .map(|r| (r.from.as_str(), r.to.as_str(), r.relation_type.as_str()))
+            .collect();
+        let relations = graph
+            .relations
+            .iter()
+            .filter(|r| selected(&r.from) || selected(&r.to))
+            .filter(|r| {
+                !existing.contains(&(r.from.as_str(), r.to.as_str(), r.relation_type.as_str()))
+            })
+            .cloned()
+            .collect();
+
+        KnowledgeGraph {
+            entities,
+            relations,
+        }
+    };
+
+    import::import_graph(shared, incoming)
+}
@@ -0,0 +1,48 @@
+//! Seed graph for an empty knowledge base
+//!
+//! `MEMORY_SEED_PATH` points to a JSONL file in the memory file format, e.g.
+//! shared entity types and conventions. When the knowledge base starts with no
+//! entities, the seed is imported through the normal import path, so it is
+//! persisted (and recorded as events in Event Sourcing mode). A graph that
+//! already has entities is never touched.
+
+use std::env;
+use std::path::Path;
+
+use crate::types::{ImportReport, McpResult};
+
+use super::{crud, import, load, KnowledgeBase};
+
+/// Import the seed at `seed_path` if the graph has no entities
+///
+/// Returns `None` when the graph was not empty or the seed file is missing.
+pub fn seed_if_empty(kb: &KnowledgeBase, seed_path: &str) -> McpResult<Option<ImportReport>> {
+    if !Path::new(seed_path).exists() || !kb.graph.read().unwrap().entities.is_empty() {
+        return Ok(None);
+    }
+
+    let seed = load::load_graph_from_file(seed_path)?;
+    crud::commit(kb, |graph, pending| {
+        // Re-checked under the write lock in case another writer got in first
+        if !graph.entities.is_empty() {
+            return Ok(None);
+        }
+        import::apply_import(kb, graph, seed, pending).map(Some)
+    })
+}
+
+/// Apply `MEMORY_SEED_PATH` at startup, logging the outcome
+pub fn seed_from_env(kb: &KnowledgeBase) {
+    let Ok(seed_path) = env::var("MEMORY_SEED_PATH") else {
+        return;
+    };
+
+    match seed_if_empty(kb, &seed_path) {
+        Ok(Some(report)) => eprintln!(
+            "[Seed] Loaded {} entities and {} relations from {}",
+            report.entities_created, report.relations_created, seed_path
+        ),
+        Ok(None) => {}
+        Err(e) => eprintln!("Warning: Failed to load seed graph {}: {}", seed_path, e),
+    }
+}
@@ -0,0 +1,325 @@
+//! Snapshots off the mutation path
+//!
+//! A mutation that crosses the snapshot threshold only signals the snapshot
+//! worker (see `spawn_snapshot_worker`) and returns. The worker copies the
+//! graph under the read lock, together with the log position it reflects,
+//! then writes the snapshot without holding any graph or store lock. Events
+//! appended while it writes stay in the active log and keep counting towards
+//! the next snapshot.
+//!
+//! Other writers' events (see tail.rs) are applied before the graph is
+//! copied, so the position covers every line before it, whichever ID range
+//! it came from.
+//!
+//! Without a running worker (tests, embedders that never spawn it),
+//! snapshots are written inline as before, and skipped while another
+//! snapshot is being written.
+
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::{self, JoinHandle};
+
+use crate::types::{Entity, McpResult, Relation, SnapshotMeta};
+
+use super::{tail, KnowledgeBase};
+
+/// How many times a snapshot applies other writers' events before giving up
+/// until the next request
+const CATCH_UP_ATTEMPTS: usize = 3;
+
+/// Snapshot worker handle and the lock serializing snapshot writes
+#[derive(Debug, Default)]
+pub(crate) struct SnapshotWorker {
+    /// Wakes the worker; set once the worker is running
+    signal: OnceLock<SyncSender<()>>,
+    /// Held while a snapshot is written, so two writers never interleave
+    pub(super) write_lock: Mutex<()>,
+}
+
+/// Graph state captured for a snapshot
+struct Capture {
+    last_event_id: u64,
+    log_position: u64,
+    entities: Vec<Entity>,
+    relations: Vec<Relation>,
+}
+
+/// Snapshot if due, on the worker when it runs and inline otherwise
+pub(super) fn request_snapshot(kb: &KnowledgeBase) -> McpResult<()> {
+    let Some(ref event_store) = kb.event_store else {
+        return Ok(());
+    };
+    if !event_store.lock().unwrap().should_snapshot() {
+        return Ok(());
+    }
+
+    match kb.snapshots.signal.get() {
+        // A full channel means a snapshot is already pending
+        Some(signal) => match signal.try_send(()) {
+            Ok(()) | Err(TrySendError::Full(())) => Ok(()),
+            Err(TrySendError::Disconnected(())) => snapshot_inline(kb),
+        },
+        None => snapshot_inline(kb),
+    }
+}
+
+/// Snapshot if due, unless a snapshot is being written already
+///
+/// That writer may be the caller: a snapshot applies other writers' events
+/// through the mutation path, which requests a snapshot in turn.
+fn snapshot_inline(kb: &KnowledgeBase) -> McpResult<()> {
+    let Ok(_writing) = kb.snapshots.write_lock.try_lock() else {
+        return Ok(());
+    };
+    write_snapshot(kb, false).map(|_| ())
+}
+
+/// Write a snapshot and rotate the log if the threshold has been reached
+pub(super) fn snapshot_if_due(kb: &KnowledgeBase) -> McpResult<()> {
+    let _writing = kb.snapshots.write_lock.lock().unwrap();
+    write_snapshot(kb, false).map(|_| ())
+}
+
+/// Capture the graph and write a snapshot; `write_lock` must be held
+///
+/// A forced snapshot is written whether due or not, as long as the log has
+/// any event, and leaves the log as it is. Otherwise the log is rotated up
+/// to the snapshot if `archive_old_events` is set.
+pub(super) fn write_snapshot(kb: &KnowledgeBase, forced: bool) -> McpResult<Option<SnapshotMeta>> {
+    let (Some(ref event_store), Some(ref snapshot_manager)) =
+        (&kb.event_store, &kb.snapshot_manager)
+    else {
+        return Ok(None);
+    };
+
+    let Some(capture) = capture(kb, forced)? else {
+        return Ok(None);
+    };
+
+    let meta = snapshot_manager.create_incremental_snapshot(
+        capture.last_event_id,
+        Some(capture.log_position),
+        &capture.entities,
+        &capture.relations,
+    )?;
+
+    // Appends hold the store lock, so none is lost while the log is rewritten
+    let mut store = event_store.lock().unwrap();
+    if !forced && store.config().archive_old_events {
+        if let Err(e) = store.rotate_to_position(capture.log_position) {
+            eprintln!("Warning: Failed to rotate event log: {}", e);
+        }
+    }
+    store.snapshot_created_at(capture.last_event_id, capture.log_position);
+    Ok(Some(meta))
+}
+
+/// Copy the graph and the log position it reflects, once every event other
+/// writers appended so far is applied
+fn capture(kb: &KnowledgeBase, forced: bool) -> McpResult<Option<Capture>> {
+    let Some(ref event_store) = kb.event_store else {
+        return Ok(None);
+    };
+
+    for _ in 0..CATCH_UP_ATTEMPTS {
+        {
+            // Graph first, then the store, the same order as the mutation path
+            let graph = kb.graph.read().unwrap();
+            let mut store = event_store.lock().unwrap();
+            if !forced && !store.should_snapshot() {
+                return Ok(None);
+            }
+            if store.catch_up_tail()? {
+                let last_event_id = store.next_event_id().saturating_sub(1);
+                if forced && last_event_id == 0 && store.log_position() == 0 {
+                    return Ok(None);
+                }
+                return Ok(Some(Capture {
+                    last_event_id,
+                    log_position: store.log_position(),
+                    entities: graph.entities.clone(),
+                    relations: graph.relations.clone(),
+                }));
+            }
+        }
+        tail::apply_external_events(kb)?;
+    }
+
+    eprintln!("Warning: Other writers kept appending events; snapshot postponed");
+    Ok(None)
+}
+
+/// Start the snapshot worker (Event Sourcing mode only)
+///
+/// The worker holds a weak reference and stops once the knowledge base is
+/// dropped. Only one worker is started per knowledge base.
+pub fn spawn_snapshot_worker(kb: &Arc<KnowledgeBase>) -> Option<JoinHandle<()>> {
+    kb.snapshot_manager.as_ref()?;
+
+    let (signal, requests) = mpsc::sync_channel(1);
+    kb.snapshots.signal.set(signal).ok()?;
+
+    let kb = Arc::downgrade(kb);
+    Some(thread::spawn(move || {
+        while requests.recv().is_ok() {
+            let Some(kb) = kb.upgrade() else {
+                break;
+            };
+            if let Err(e) = snapshot_if_due(&kb) {
+                eprintln!("[Snapshot] Failed to create snapshot: {}", e);
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_store::{EventStore, EventStoreConfig, LogRotation, SnapshotManager};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_mutations_do_not_wait_for_snapshot_writes() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = EventStoreConfig::with_data_dir(temp_dir.path());
+        config.snapshot_threshold = 5;
+
+        let mut kb =
+            KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        kb.event_store = Some(Mutex::new(EventStore::with_config(config.clone())));
+        kb.snapshot_manager = Some(SnapshotManager::new(config.clone()));
+        kb.log_rotation = Some(LogRotation::new(config));
+        let kb = Arc::new(kb);
+        assert!(spawn_snapshot_worker(&kb).is_some());
+        assert!(spawn_snapshot_worker(&kb).is_none());
+
+        // Stand in for a slow snapshot write that is still in progress
+        let writing = kb.snapshots.write_lock.lock().unwrap();
+        let (done, finished) = mpsc::channel();
+        let writer = Arc::clone(&kb);
+        thread::spawn(move || {
+            for i in 0..20 {
+                let entity = Entity::new(format!("E{}", i), "Module".to_string());
+                writer.create_entities(vec![entity]).unwrap();
+            }
+            done.send(()).unwrap();
+        });
+        finished
+            .recv_timeout(Duration::from_secs(10))
+            .expect("mutations blocked on the snapshot writer");
+        drop(writing);
+
+        // The worker catches up once the write lock is free
+        let store = kb.event_store.as_ref().unwrap();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while store.lock().unwrap().events_since_snapshot() >= 20 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let store = store.lock().unwrap();
+        assert!(store.events_since_snapshot() < 20);
+        assert!(kb.snapshot_manager.as_ref().unwrap().latest_path().exists());
+        let (entities, _) = EventStore::with_config(store.config().clone())
+            .initialize()
+            .unwrap();
+        assert_eq!(entities.len(), 20);
+    }
+
+    #[test]
+    fn test_delta_snapshots_match_full_replay() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = EventStoreConfig::with_data_dir(temp_dir.path());
+        config.max_delta_chain = 3;
+
+        let mut kb =
+            KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        kb.event_store = Some(Mutex::new(EventStore::with_config(config.clone())));
+        kb.snapshot_manager = Some(SnapshotManager::new(config.clone()));
+        let relation = |from: &str, to: &str| {
+            Relation::new(from.to_string(), to.to_string(), "uses".to_string())
+        };
+
+        kb.create_entities(
+            ["Api", "Auth", "Cache"]
+                .map(|n| Entity::new(n.to_string(), "Module".to_string()))
+                .to_vec(),
+        )
+        .unwrap();
+        kb.create_relations(vec![relation("Api", "Auth"), relation("Api", "Cache")])
+            .unwrap();
+        kb.create_snapshot().unwrap();
+
+        kb.add_observations(vec![crate::types::Observation {
+            entity_name: "Auth".to_string(),
+            contents: vec!["Issues JWTs".to_string()],
+        }])
+        .unwrap();
+        kb.delete_relations(vec![relation("Api", "Cache")]).unwrap();
+        kb.create_snapshot().unwrap();
+
+        // Deleted and recreated: the old relation must not come back
+        kb.delete_entities(vec!["Auth".to_string()]).unwrap();
+        kb.create_entities(vec![Entity::new("Auth".to_string(), "Service".to_string())])
+            .unwrap();
+        kb.create_snapshot().unwrap();
+
+        kb.delete_entities(vec!["Cache".to_string()]).unwrap();
+        kb.create_entities(vec![Entity::new("Db".to_string(), "Store".to_string())])
+            .unwrap();
+        kb.create_relations(vec![relation("Api", "Db")]).unwrap();
+        let delta = kb.create_snapshot().unwrap().unwrap();
+
+        let manager = SnapshotManager::new(config.clone());
+        assert_eq!(manager.delta_paths().unwrap().len(), 3);
+        assert_eq!(manager.delta_paths().unwrap().last(), Some(&delta));
+        let (meta, mut entities, mut relations) = manager.load_full().unwrap().unwrap();
+        assert!(meta.is_delta);
+
+        let store = EventStore::with_config(config.clone());
+        let (mut replayed_entities, mut replayed_relations, last_event_id) =
+            store.replay_all().unwrap();
+        assert_eq!(meta.last_event_id, last_event_id);
+
+        let entity_state = |entities: &mut Vec<Entity>| {
+            entities.sort_by(|a, b| a.name.cmp(&b.name));
+            entities
+                .iter()
+                .map(|e| {
+                    (
+                        e.name.clone(),
+                        e.entity_type.clone(),
+                        e.observations.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let relation_state = |relations: &mut Vec<Relation>| {
+            relations.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+            relations
+                .iter()
+                .map(|r| (r.from.clone(), r.to.clone(), r.relation_type.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            entity_state(&mut entities),
+            entity_state(&mut replayed_entities)
+        );
+        assert_eq!(
+            relation_state(&mut relations),
+            relation_state(&mut replayed_relations)
+        );
+        assert_eq!(
+            relation_state(&mut relations),
+            vec![("Api".to_string(), "Db".to_string(), "uses".to_string())]
+        );
+
+        // The chain is full, so the next snapshot is a full one again
+        kb.add_observations(vec![crate::types::Observation {
+            entity_name: "Db".to_string(),
+            contents: vec!["Postgres".to_string()],
+        }])
+        .unwrap();
+        assert_eq!(kb.create_snapshot().unwrap(), Some(manager.latest_path()));
+        assert!(manager.delta_paths().unwrap().is_empty());
+        assert!(!manager.load_meta().unwrap().unwrap().is_delta);
+    }
+}
@@ -0,0 +1,169 @@
+//! Relation suggestions from shared observation terms
+//!
+//! Entities whose observations mention the same distinctive terms are likely
+//! related even when no relation links them. Observations are split into
+//! lowercase alphanumeric terms of at least [`MIN_TERM_LEN`] characters, and
+//! each term is weighted by its inverse document frequency `ln(N / df)`,
+//! where `N` is the number of entities and `df` the number of entities using
+//! the term. A term every entity uses weighs nothing; the fewer entities use
+//! a term, the more sharing it counts.
+//!
+//! A candidate's score is the weight of the terms it shares with the target
+//! divided by the weight of all the target's terms, so it lies in `0.0..=1.0`.
+//! Entities already related to the target (either direction) are skipped.
+//! Suggestions are read-only: nothing is created.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::types::{McpResult, RelationSuggestion};
+
+use super::{acl, KnowledgeBase};
+
+/// Shortest term considered, so short words like "is" or "of" are ignored
+const MIN_TERM_LEN: usize = 3;
+
+/// Shared terms listed as evidence per suggestion, rarest first
+const MAX_EVIDENCE_TERMS: usize = 10;
+
+/// Distinct terms of an entity's observations
+fn terms(observations: &[String]) -> HashSet<String> {
+    observations
+        .iter()
+        .flat_map(|o| o.split(|c: char| !c.is_alphanumeric()))
+        .filter(|t| t.chars().count() >= MIN_TERM_LEN)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Up to `top_n` entities sharing distinctive observation terms with `name`
+///
+/// `name` may be a name or an id. Best score first; ties by name.
+pub fn suggest_relations(
+    kb: &KnowledgeBase,
+    name: &str,
+    top_n: usize,
+) -> McpResult<Vec<RelationSuggestion>> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    let target = graph
+        .resolve_entity(name)
+        .ok_or_else(|| format!("Entity not found: {}", name))?;
+
+    let entity_terms: Vec<HashSet<String>> = graph
+        .entities
+        .iter()
+        .map(|e| terms(&e.observations))
+        .collect();
+    let mut frequency: HashMap<&str, usize> = HashMap::new();
+    for term in entity_terms.iter().flatten() {
+        *frequency.entry(term.as_str()).or_default() += 1;
+    }
+    let count = graph.entities.len() as f32;
+    let weight = |term: &str| (count / frequency[term] as f32).ln();
+
+    let target_terms = terms(&target.observations);
+    let target_weight: f32 = target_terms.iter().map(|t| weight(t)).sum();
+    if target_weight <= 0.0 {
+        return Ok(Vec::new());
+    }
+
+    let mut related: HashSet<&str> = HashSet::from([target.name.as_str()]);
+    for relation in &graph.relations {
+        if relation.from == target.name {
+            related.insert(relation.to.as_str());
+        } else if relation.to == target.name {
+            related.insert(relation.from.as_str());
+        }
+    }
+
+    let mut suggestions: Vec<RelationSuggestion> = Vec::new();
+    for (entity, candidate_terms) in graph.entities.iter().zip(&entity_terms) {
+        if related.contains(entity.name.as_str()) {
+            continue;
+        }
+        let mut shared: Vec<(&String, f32)> = target_terms
+            .intersection(candidate_terms)
+            .map(|t| (t, weight(t)))
+            .filter(|(_, w)| *w > 0.0)
+            .collect();
+        if shared.is_empty() {
+            continue;
+        }
+        shared.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        let score = shared.iter().map(|(_, w)| w).sum::<f32>() / target_weight;
+        suggestions.push(RelationSuggestion {
+            entity: entity.name.clone(),
+            entity_type: entity.entity_type.clone(),
+            score,
+            shared_terms: shared
+                .into_iter()
+                .take(MAX_EVIDENCE_TERMS)
+                .map(|(t, _)| t.clone())
+                .collect(),
+        });
+    }
+
+    suggestions.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.entity.cmp(&b.entity))
+    });
+    suggestions.truncate(top_n);
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Entity, Relation};
+
+    #[test]
+    fn test_suggest_relations_weights_rare_terms() {
+        let path = format!("target/test_suggest_relations_{}.jsonl", std::process::id());
+        let kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        let module = |name: &str, observations: &[&str]| {
+            Entity::with_observations(
+                name.to_string(),
+                "Module".to_string(),
+                observations.iter().map(|o| o.to_string()).collect(),
+            )
+        };
+        kb.create_entities(vec![
+            module(
+                "Auth",
+                &["Written in Rust", "Issues JWT tokens via Keycloak"],
+            ),
+            module("Gateway", &["Written in Rust", "Validates JWT tokens"]),
+            module("Billing", &["Written in Rust", "Talks to Stripe"]),
+            module("Admin", &["Written in Go", "Signs in via Keycloak"]),
+            module("Docs", &["Static site"]),
+        ])
+        .unwrap();
+
+        let suggestions = kb.suggest_relations("Auth", 10).unwrap();
+        let names: Vec<&str> = suggestions.iter().map(|s| s.entity.as_str()).collect();
+        // Billing only shares terms most entities use; Docs shares nothing
+        assert_eq!(names, vec!["Gateway", "Admin", "Billing"]);
+        assert_eq!(suggestions[0].shared_terms[..2], ["jwt", "tokens"]);
+        assert!(suggestions[0].score > suggestions[2].score);
+        assert!(suggestions.iter().all(|s| s.score > 0.0 && s.score <= 1.0));
+        assert_eq!(suggestions[1].shared_terms, ["keycloak", "via", "written"]);
+
+        // Existing relations are not suggested again
+        kb.create_relations(vec![Relation::new(
+            "Gateway".to_string(),
+            "Auth".to_string(),
+            "uses".to_string(),
+        )])
+        .unwrap();
+        let suggestions = kb.suggest_relations("Auth", 1).unwrap();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].entity, "Admin");
+
+        assert!(kb.suggest_relations("Search", 10).is_err());
+        assert!(kb.suggest_relations("Docs", 10).unwrap().is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,19 +1,34 @@
 //! Summarize operations
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
 
-use crate::types::{Entity, EntityBrief, McpResult, Summary};
+use crate::types::{
+    parse_attribute, CompactView, ConnectedEntity, Entity, EntityAttributes, EntityBrief,
+    McpResult, Relation, Summary,
+};
+use crate::validation::normalize_observation;
 
-use super::KnowledgeBase;
+use super::{acl, KnowledgeBase};
+
+/// Number of entities listed in a summary's `top_connected`
+pub const TOP_CONNECTED_LIMIT: usize = 10;
 
 /// Summarize entities
+///
+/// In `stats` format, `group_by` lists attribute keys (see
+/// [`crate::types::parse_attribute`]) to count entities by, next to the
+/// built-in status and priority counts. Stats also count the relations
+/// touching at least one summarized entity by (canonical) type, and list the
+/// summarized entities with the highest degree.
 pub fn summarize(
     kb: &KnowledgeBase,
     entity_names: Option<Vec<String>>,
     entity_type: Option<String>,
     format: &str,
+    group_by: &[String],
 ) -> McpResult<Summary> {
-    let graph = kb.load_graph()?;
+    let graph = acl::visible(kb.load_graph()?);
 
     let entities: Vec<&Entity> = graph
         .entities
@@ -32,30 +47,17 @@ pub fn summarize(
     match format {
         "brief" => format_brief(&entities),
         "detailed" => format_detailed(&entities),
-        "stats" => format_stats(&entities),
+        "stats" => {
+            let mut summary = format_stats(&entities, group_by)?;
+            relation_stats(kb, &entities, &graph.relations, &mut summary);
+            Ok(summary)
+        }
         _ => format_brief(&entities),
     }
 }
 
 fn format_brief(entities: &[&Entity]) -> McpResult<Summary> {
-    let briefs: Vec<EntityBrief> = entities
-        .iter()
-        .map(|e| {
-            let brief = e
-                .observations
-                .first()
-                .cloned()
-                .unwrap_or_default()
-                .chars()
-                .take(100)
-                .collect::<String>();
-            EntityBrief {
-                name: e.name.clone(),
-                entity_type: e.entity_type.clone(),
-                brief,
-            }
-        })
-        .collect();
+    let briefs: Vec<EntityBrief> = entities.iter().map(|e| EntityBrief::of(e)).collect();
 
     Ok(Summary {
         total_entities: entities.len(),
@@ -84,7 +86,7 @@ fn format_detailed(entities: &[&Entity]) -> McpResult<Summary> {
     })
 }
 
-fn format_stats(entities: &[&Entity]) -> McpResult<Summary> {
+fn format_stats(entities: &[&Entity], group_by: &[String]) -> McpResult<Summary> {
     let mut by_status: HashMap<String, usize> = HashMap::new();
     let mut by_type: HashMap<String, usize> = HashMap::new();
     let mut by_priority: HashMap<String, usize> = HashMap::new();
@@ -92,14 +94,28 @@ fn format_stats(entities: &[&Entity]) -> McpResult<Summary> {
     for entity in entities {
         *by_type.entry(entity.entity_type.clone()).or_insert(0) += 1;
 
-        for obs in &entity.observations {
-            if obs.starts_with("Status:") {
-                let status = obs.trim_start_matches("Status:").trim().to_string();
-                *by_status.entry(status).or_insert(0) += 1;
+        for (key, value) in entity.observations.iter().filter_map(|obs| parse_attribute(obs)) {
+            if key.eq_ignore_ascii_case("status") {
+                *by_status.entry(value.to_string()).or_insert(0) += 1;
+            } else if key.eq_ignore_ascii_case("priority") {
+                *by_priority.entry(value.to_string()).or_insert(0) += 1;
             }
-            if obs.starts_with("Priority:") {
-                let priority = obs.trim_start_matches("Priority:").trim().to_string();
-                *by_priority.entry(priority).or_insert(0) += 1;
+        }
+    }
+
+    // One count per entity: its latest value for the key
+    let mut by_attribute: HashMap<String, HashMap<String, usize>> = HashMap::new();
+    for key in group_by {
+        let counts = by_attribute.entry(key.clone()).or_default();
+        for entity in entities {
+            let value = entity
+                .observations
+                .iter()
+                .rev()
+                .filter_map(|obs| parse_attribute(obs))
+                .find(|(k, _)| k.eq_ignore_ascii_case(key));
+            if let Some((_, value)) = value {
+                *counts.entry(value.to_string()).or_insert(0) += 1;
             }
         }
     }
@@ -118,5 +134,412 @@ fn format_stats(entities: &[&Entity]) -> McpResult<Summary> {
         } else {
             Some(by_priority)
         },
+        by_attribute: if by_attribute.is_empty() {
+            None
+        } else {
+            Some(by_attribute)
+        },
+        ..Default::default()
+    })
+}
+
+/// Fill in relation counts by type and the best connected entities
+fn relation_stats(
+    kb: &KnowledgeBase,
+    entities: &[&Entity],
+    relations: &[Relation],
+    summary: &mut Summary,
+) {
+    let mut degree: HashMap<&str, usize> = entities.iter().map(|e| (e.name.as_str(), 0)).collect();
+    let mut by_relation_type: HashMap<String, usize> = HashMap::new();
+
+    for relation in relations {
+        let mut touches = false;
+        for endpoint in [&relation.from, &relation.to] {
+            if let Some(count) = degree.get_mut(endpoint.as_str()) {
+                *count += 1;
+                touches = true;
+            }
+        }
+        if touches {
+            let relation_type = kb.relation_aliases.canonical(&relation.relation_type);
+            *by_relation_type
+                .entry(relation_type.to_string())
+                .or_insert(0) += 1;
+        }
+    }
+
+    let mut connected: Vec<ConnectedEntity> = entities
+        .iter()
+        .filter(|e| degree[e.name.as_str()] > 0)
+        .map(|e| ConnectedEntity {
+            name: e.name.clone(),
+            entity_type: e.entity_type.clone(),
+            degree: degree[e.name.as_str()],
+        })
+        .collect();
+    connected.sort_by(|a, b| b.degree.cmp(&a.degree).then_with(|| a.name.cmp(&b.name)));
+    connected.truncate(TOP_CONNECTED_LIMIT);
+
+    if !by_relation_type.is_empty() {
+        summary.by_relation_type = Some(by_relation_type);
+        summary.top_connected = Some(connected);
+    }
+}
+
+/// Attributes parsed from an entity's `key: value` observations
+pub fn get_attributes(kb: &KnowledgeBase, name: &str) -> McpResult<EntityAttributes> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    let entity = graph
+        .resolve_entity(name)
+        .ok_or_else(|| format!("Entity not found: {}", name))?;
+
+    let plain_observations = entity
+        .observations
+        .iter()
+        .filter(|obs| parse_attribute(obs).is_none())
+        .count();
+
+    kb.access_tracker.record(std::iter::once(entity.name.as_str()));
+
+    Ok(EntityAttributes {
+        name: entity.name.clone(),
+        entity_type: entity.entity_type.clone(),
+        attributes: entity.attributes(),
+        plain_observations,
+    })
+}
+
+/// Attribute keys (lowercased) for which only the latest observation is kept
+const LATEST_ONLY_KEYS: [&str; 2] = ["status", "priority"];
+
+/// Build a compact view of an entity's observations
+///
+/// Near-identical observations (equal after normalization) collapse to the
+/// latest one, and only the latest status/priority attribute is kept
+/// and always included. The remaining slots go to the observations adding the
+/// most words not already in the view, longer ones first on ties. The store
+/// is never modified.
+pub fn compact_view(
+    kb: &KnowledgeBase,
+    name: &str,
+    max_observations: usize,
+) -> McpResult<CompactView> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    let entity = graph
+        .entities
+        .iter()
+        .find(|e| e.name == name)
+        .ok_or_else(|| format!("Entity not found: {}", name))?;
+    let observations = &entity.observations;
+
+    #[derive(PartialEq, Eq, Hash)]
+    enum Key {
+        Attribute(String),
+        Observation(String),
+    }
+
+    // Latest observation index per attribute key or normalized observation
+    let mut latest: HashMap<Key, usize> = HashMap::new();
+    for (index, obs) in observations.iter().enumerate() {
+        let key = match parse_attribute(obs).map(|(key, _)| key.to_lowercase()) {
+            Some(key) if LATEST_ONLY_KEYS.contains(&key.as_str()) => Key::Attribute(key),
+            _ => Key::Observation(normalize_observation(obs)),
+        };
+        latest.insert(key, index);
+    }
+
+    let mut kept = Vec::new();
+    let mut candidates = Vec::new();
+    for (key, index) in latest {
+        if matches!(key, Key::Attribute(_)) {
+            kept.push(index);
+        } else {
+            candidates.push(index);
+        }
+    }
+    kept.sort_unstable();
+    kept.truncate(max_observations);
+    let mut seen: HashSet<String> = kept.iter().flat_map(|&i| words(&observations[i])).collect();
+
+    while kept.len() < max_observations && !candidates.is_empty() {
+        let (best, _) = candidates
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, &index)| {
+                let obs = &observations[index];
+                let novel = words(obs).filter(|w| !seen.contains(w)).count();
+                (novel, obs.len(), index)
+            })
+            .unwrap();
+        let index = candidates.swap_remove(best);
+        seen.extend(words(&observations[index]));
+        kept.push(index);
+    }
+    kept.sort_unstable();
+
+    kb.access_tracker.record(std::iter::once(name));
+
+    Ok(CompactView {
+        name: entity.name.clone(),
+        entity_type: entity.entity_type.clone(),
+        observations: kept.iter().map(|&i| observations[i].clone()).collect(),
+        total_observations: observations.len(),
+        omitted: observations.len() - kept.len(),
     })
 }
+
+/// Render an entity as a Markdown section
+///
+/// A `## name (type)` heading, the observations as a bullet list, then a
+/// `### Relationships` subsection listing outgoing and incoming relations
+/// by type with the neighbor names. Missing observations or relations are
+/// stated rather than left out. `name` may also be an entity id.
+pub fn entity_to_markdown(kb: &KnowledgeBase, name: &str) -> McpResult<String> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    let entity = graph
+        .resolve_entity(name)
+        .ok_or_else(|| format!("Entity not found: {}", name))?;
+
+    let mut outgoing: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    let mut incoming: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for relation in &graph.relations {
+        if relation.from == entity.name {
+            outgoing
+                .entry(relation.relation_type.as_str())
+                .or_default()
+                .push(relation.to.as_str());
+        }
+        if relation.to == entity.name {
+            incoming
+                .entry(relation.relation_type.as_str())
+                .or_default()
+                .push(relation.from.as_str());
+        }
+    }
+
+    let mut markdown = format!("## {} ({})\n\n", entity.name, entity.entity_type);
+    if entity.observations.is_empty() {
+        markdown.push_str("_No observations._\n");
+    }
+    for observation in &entity.observations {
+        // Continuation lines stay inside the bullet
+        let _ = writeln!(markdown, "- {}", observation.trim().replace('\n', "\n  "));
+    }
+
+    markdown.push_str("\n### Relationships\n");
+    if outgoing.is_empty() && incoming.is_empty() {
+        markdown.push_str("\n_No relationships._\n");
+    }
+    for (heading, groups) in [("Outgoing", &mut outgoing), ("Incoming", &mut incoming)] {
+        if groups.is_empty() {
+            continue;
+        }
+        let _ = write!(markdown, "\n**{}**\n\n", heading);
+        for (relation_type, neighbors) in groups.iter_mut() {
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            let _ = writeln!(markdown, "- {}: {}", relation_type, neighbors.join(", "));
+        }
+    }
+
+    kb.access_tracker.record(std::iter::once(entity.name.as_str()));
+    Ok(markdown)
+}
+
+/// Lowercased words of three or more characters
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3)
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_view() {
+        let path = format!("target/test_compact_view_{}.jsonl", std::process::id());
+        let kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        kb.create_entities(vec![Entity::with_observations(
+            "Deploy".to_string(),
+            "Task".to_string(),
+            vec![
+                "Status: todo".to_string(),
+                "Runs on the staging cluster".to_string(),
+                "runs on the  staging cluster".to_string(),
+                "Priority: low".to_string(),
+                "Owned by the platform team".to_string(),
+                "Status: done".to_string(),
+                "Uses blue-green rollout with automatic rollback".to_string(),
+            ],
+        )])
+        .unwrap();
+
+        let view = compact_view(&kb, "Deploy", 3).unwrap();
+        assert_eq!(view.total_observations, 7);
+        assert_eq!(view.omitted, 4);
+        // Latest status and priority are always kept, in stored order
+        assert_eq!(
+            view.observations,
+            vec![
+                "Priority: low",
+                "Status: done",
+                "Uses blue-green rollout with automatic rollback",
+            ]
+        );
+
+        // The duplicate collapses to its latest form
+        let view = compact_view(&kb, "Deploy", 10).unwrap();
+        assert_eq!(view.observations.len(), 5);
+        assert!(view
+            .observations
+            .contains(&"runs on the  staging cluster".to_string()));
+
+        // Nothing is removed from the store
+        assert_eq!(kb.graph.read().unwrap().entities[0].observations.len(), 7);
+        assert!(compact_view(&kb, "Missing", 3).is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_attributes_and_group_by() {
+        let path = format!("target/test_summarize_attributes_{}.jsonl", std::process::id());
+        let kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        let task = |name: &str, observations: &[&str]| {
+            Entity::with_observations(
+                name.to_string(),
+                "Task".to_string(),
+                observations.iter().map(|o| o.to_string()).collect(),
+            )
+        };
+        kb.create_entities(vec![
+            task("Deploy", &["Owner: alice", "Needs a freeze window", "owner: bob"]),
+            task("Migrate", &["Owner: alice", "DueDate: 2024-03-01"]),
+            task("Audit", &["No owner yet"]),
+        ])
+        .unwrap();
+
+        let attributes = get_attributes(&kb, "Deploy").unwrap();
+        assert_eq!(attributes.attributes.len(), 1);
+        assert_eq!(attributes.attributes["owner"], "bob");
+        assert_eq!(attributes.plain_observations, 1);
+        assert!(get_attributes(&kb, "Missing").is_err());
+
+        let group_by = vec!["Owner".to_string(), "DueDate".to_string()];
+        let summary = summarize(&kb, None, None, "stats", &group_by).unwrap();
+        let by_attribute = summary.by_attribute.unwrap();
+        assert_eq!(by_attribute["Owner"]["alice"], 1);
+        assert_eq!(by_attribute["Owner"]["bob"], 1);
+        assert_eq!(by_attribute["DueDate"]["2024-03-01"], 1);
+        assert!(summarize(&kb, None, None, "stats", &[])
+            .unwrap()
+            .by_attribute
+            .is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_stats_relation_breakdown() {
+        let path = format!(
+            "target/test_summarize_relations_{}.jsonl",
+            std::process::id()
+        );
+        let kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        kb.create_entities(vec![
+            Entity::new("Api".to_string(), "Module".to_string()),
+            Entity::new("Auth".to_string(), "Module".to_string()),
+            Entity::new("Crash".to_string(), "Bug".to_string()),
+            Entity::new("Typo".to_string(), "Bug".to_string()),
+        ])
+        .unwrap();
+        let relation = |from: &str, to: &str, relation_type: &str| {
+            Relation::new(from.to_string(), to.to_string(), relation_type.to_string())
+        };
+        kb.create_relations(vec![
+            relation("Api", "Auth", "depends_on"),
+            relation("Crash", "Auth", "affects"),
+            relation("Typo", "Api", "affects"),
+        ])
+        .unwrap();
+
+        let summary = summarize(&kb, None, None, "stats", &[]).unwrap();
+        let by_relation_type = summary.by_relation_type.unwrap();
+        assert_eq!(by_relation_type["affects"], 2);
+        assert_eq!(by_relation_type["depends_on"], 1);
+        let top: Vec<(String, usize)> = summary
+            .top_connected
+            .unwrap()
+            .into_iter()
+            .map(|c| (c.name, c.degree))
+            .collect();
+        assert_eq!(top[..2], [("Api".to_string(), 2), ("Auth".to_string(), 2)]);
+
+        // Only relations touching the summarized set count
+        let bugs = summarize(&kb, None, Some("Bug".to_string()), "stats", &[]).unwrap();
+        assert_eq!(
+            bugs.by_relation_type.unwrap(),
+            HashMap::from([("affects".to_string(), 2)])
+        );
+        assert_eq!(bugs.top_connected.unwrap().len(), 2);
+
+        let brief = summarize(&kb, None, None, "brief", &[]).unwrap();
+        assert!(brief.by_relation_type.is_none() && brief.top_connected.is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_entity_to_markdown() {
+        let path = format!("target/test_entity_markdown_{}.jsonl", std::process::id());
+        let kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        kb.create_entities(vec![
+            Entity::with_observations(
+                "Api".to_string(),
+                "Module".to_string(),
+                vec!["Serves REST\nand SSE".to_string(), "Status: active".to_string()],
+            ),
+            Entity::new("Auth".to_string(), "Module".to_string()),
+            Entity::new("Cache".to_string(), "Module".to_string()),
+            Entity::new("Web".to_string(), "Client".to_string()),
+        ])
+        .unwrap();
+        let relation = |from: &str, to: &str, relation_type: &str| {
+            Relation::new(from.to_string(), to.to_string(), relation_type.to_string())
+        };
+        kb.create_relations(vec![
+            relation("Api", "Cache", "depends_on"),
+            relation("Api", "Auth", "depends_on"),
+            relation("Web", "Api", "calls"),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            entity_to_markdown(&kb, "Api").unwrap(),
+            "## Api (Module)\n\n\
+             - Serves REST\n  and SSE\n\
+             - Status: active\n\n\
+             ### Relationships\n\n\
+             **Outgoing**\n\n\
+             - depends_on: Auth, Cache\n\n\
+             **Incoming**\n\n\
+             - calls: Web\n"
+        );
+
+        kb.create_entities(vec![Entity::new("Lonely".to_string(), "Note".to_string())])
+            .unwrap();
+        assert_eq!(
+            entity_to_markdown(&kb, "Lonely").unwrap(),
+            "## Lonely (Note)\n\n_No observations._\n\n### Relationships\n\n_No relationships._\n"
+        );
+        assert!(entity_to_markdown(&kb, "Missing").is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,280 @@
+//! Tail mode: pick up events appended to the log by other processes
+//!
+//! A sidecar may append events to `events.jsonl` while the server runs. Each
+//! process is the single writer for its own event ID range (see
+//! `EventStoreConfig::id_range`, set with `MEMORY_EVENT_ID_RANGE=START..END`),
+//! so IDs never collide; by default the server only assigns IDs below
+//! `EXTERNAL_ID_BASE`. The server polls the log, applies events outside its
+//! own range to the in-memory graph and broadcasts the changes.
+
+use std::env;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::api::websocket::events::GraphEvent;
+use crate::event_store::{EventStore, TailedEvent};
+use crate::types::{
+    EntityCreatedData, EntityDeletedData, EntityUpdatedData, Event, EventType, KnowledgeGraph,
+    McpResult, ObservationAddedData, ObservationEditedData, ObservationRemovedData,
+    RelationCreatedData, RelationDeletedData, RelationUpdatedData,
+};
+
+use super::crud;
+use super::KnowledgeBase;
+
+/// Default polling interval for tail mode
+const DEFAULT_TAIL_INTERVAL_MS: u64 = 1000;
+
+/// Apply events appended by other processes since the last call
+///
+/// Returns the number of events applied. Does nothing unless Event Sourcing
+/// is enabled. The log is consumed one event at a time as each is applied:
+/// if one fails, the events before it stay applied and it is read again on
+/// the next call.
+pub fn apply_external_events(kb: &KnowledgeBase) -> McpResult<usize> {
+    let Some(ref event_store) = kb.event_store else {
+        return Ok(0);
+    };
+
+    let (applied, failure) = crud::commit(kb, |graph, pending| {
+        let (tailed, end) = event_store.lock().unwrap().read_tail()?;
+        let mut applied = 0;
+        for TailedEvent { event, cursor } in tailed {
+            if let Err(e) = EventStore::apply_event(&mut graph.entities, &mut graph.relations, &event)
+            {
+                return Ok((applied, Some(e)));
+            }
+            event_store.lock().unwrap().consume_tail(cursor);
+            applied += 1;
+            if let Some(change) = graph_event(graph, &event) {
+                pending.broadcast(change);
+            }
+        }
+        event_store.lock().unwrap().consume_tail(end);
+        Ok((applied, None))
+    })?;
+
+    match failure {
+        Some(e) => Err(format!("Applied {} external events, then: {}", applied, e).into()),
+        None => Ok(applied),
+    }
+}
+
+/// Start polling the event log in the background if `MEMORY_EVENT_TAIL` is set
+///
+/// The interval defaults to one second and can be changed with
+/// `MEMORY_EVENT_TAIL_INTERVAL_MS`.
+pub fn spawn_event_tail(kb: Arc<KnowledgeBase>) -> Option<JoinHandle<()>> {
+    let enabled = env::var("MEMORY_EVENT_TAIL")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+    if !enabled || !kb.event_sourcing_enabled {
+        return None;
+    }
+
+    let interval = env::var("MEMORY_EVENT_TAIL_INTERVAL_MS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_TAIL_INTERVAL_MS);
+
+    Some(thread::spawn(move || loop {
+        thread::sleep(Duration::from_millis(interval));
+        match apply_external_events(&kb) {
+            Ok(0) => {}
+            Ok(n) => eprintln!("[Event Tail] Applied {} external events", n),
+            Err(e) => eprintln!("[Event Tail] Failed to apply external events: {}", e),
+        }
+    }))
+}
+
+/// Describe an applied event as a WebSocket change
+fn graph_event(graph: &KnowledgeGraph, event: &Event) -> Option<GraphEvent> {
+    let user = Some(event.user.clone());
+
+    let change = match event.event_type {
+        EventType::EntityCreated => {
+            let data: EntityCreatedData = event.parse_data().ok()?;
+            let entity = graph.entities.iter().find(|e| e.name == data.name)?;
+            GraphEvent::EntityCreated {
+                payload: entity.clone(),
+                user,
+            }
+        }
+        EventType::EntityUpdated => {
+            let data: EntityUpdatedData = event.parse_data().ok()?;
+            GraphEvent::EntityUpdated {
+                name: data.name,
+                new_observations: Vec::new(),
+                user,
+            }
+        }
+        EventType::EntityDeleted => {
+            let data: EntityDeletedData = event.parse_data().ok()?;
+            GraphEvent::EntityDeleted {
+                name: data.name,
+                user,
+            }
+        }
+        EventType::ObservationAdded => {
+            let data: ObservationAddedData = event.parse_data().ok()?;
+            GraphEvent::EntityUpdated {
+                name: data.entity,
+                new_observations: vec![data.observation],
+                user,
+            }
+        }
+        EventType::ObservationRemoved => {
+            let data: ObservationRemovedData = event.parse_data().ok()?;
+            GraphEvent::EntityUpdated {
+                name: data.entity,
+                new_observations: Vec::new(),
+                user,
+            }
+        }
+        EventType::ObservationEdited => {
+            let data: ObservationEditedData = event.parse_data().ok()?;
+            GraphEvent::EntityUpdated {
+                name: data.entity,
+                new_observations: vec![data.after],
+                user,
+            }
+        }
+        EventType::RelationCreated => {
+            let data: RelationCreatedData = event.parse_data().ok()?;
+            let relation = graph.relations.iter().find(|r| {
+                r.from == data.from && r.to == data.to && r.relation_type == data.relation_type
+            })?;
+            GraphEvent::RelationCreated {
+                payload: relation.clone(),
+                user,
+            }
+        }
+        EventType::RelationDeleted => {
+            let data: RelationDeletedData = event.parse_data().ok()?;
+            GraphEvent::RelationDeleted {
+                from: data.from,
+                to: data.to,
+                relation_type: data.relation_type,
+                user,
+            }
+        }
+        EventType::RelationUpdated => {
+            let data: RelationUpdatedData = event.parse_data().ok()?;
+            let relation = graph.relations.iter().find(|r| {
+                r.from == data.from && r.to == data.to && r.relation_type == data.relation_type
+            })?;
+            GraphEvent::RelationUpdated {
+                payload: relation.clone(),
+                user,
+            }
+        }
+    };
+
+    Some(change)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_store::{EventStoreConfig, EXTERNAL_ID_BASE};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_applies_events_from_other_writers() {
+        let temp_dir = TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "server".to_string());
+        kb.create_entities(vec![crate::types::Entity::new(
+            "Local".to_string(),
+            "Module".to_string(),
+        )])
+        .unwrap();
+
+        // A sidecar writing in its own reserved range
+        let mut config = EventStoreConfig::with_data_dir(temp_dir.path());
+        config.id_range = EXTERNAL_ID_BASE..EXTERNAL_ID_BASE + 1_000_000;
+        let mut sidecar = EventStore::with_config(config);
+        sidecar.initialize().unwrap();
+        sidecar
+            .create_and_append_event(
+                EventType::EntityCreated,
+                "sidecar".to_string(),
+                json!({"name": "Remote", "entity_type": "Module"}),
+            )
+            .unwrap();
+
+        assert_eq!(apply_external_events(&kb).unwrap(), 1);
+        let graph = kb.read_graph(None, None).unwrap();
+        assert!(graph.entities.iter().any(|e| e.name == "Remote"));
+        assert_eq!(graph.entities.len(), 2);
+
+        // Already consumed, and the server's own events are never re-applied
+        assert_eq!(apply_external_events(&kb).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_failed_event_is_read_again() {
+        let temp_dir = TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "server".to_string());
+
+        let mut config = EventStoreConfig::with_data_dir(temp_dir.path());
+        config.id_range = EXTERNAL_ID_BASE..EXTERNAL_ID_BASE + 1_000_000;
+        let mut sidecar = EventStore::with_config(config);
+        sidecar.initialize().unwrap();
+        let mut append = |data: serde_json::Value| {
+            sidecar
+                .create_and_append_event(EventType::EntityCreated, "sidecar".to_string(), data)
+                .unwrap()
+        };
+        append(json!({"name": "Remote", "entity_type": "Module"}));
+        let broken = append(json!({"entity_type": "Module"}));
+
+        assert!(apply_external_events(&kb).is_err());
+        assert_eq!(kb.graph.read().unwrap().entities.len(), 1);
+
+        // Only the event that failed is still pending
+        let event_store = kb.event_store.as_ref().unwrap();
+        let (pending, _) = event_store.lock().unwrap().read_tail().unwrap();
+        let ids: Vec<u64> = pending.iter().map(|t| t.event.event_id).collect();
+        assert_eq!(ids, vec![broken.event_id]);
+    }
+
+    #[test]
+    fn test_snapshot_covers_other_writers_events() {
+        let temp_dir = TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "server".to_string());
+        let entity = |name: &str| crate::types::Entity::new(name.to_string(), "Module".to_string());
+        kb.create_entities(vec![entity("Local")]).unwrap();
+
+        let mut config = EventStoreConfig::with_data_dir(temp_dir.path());
+        config.id_range = EXTERNAL_ID_BASE..EXTERNAL_ID_BASE + 1_000_000;
+        let mut sidecar = EventStore::with_config(config);
+        sidecar.initialize().unwrap();
+        sidecar
+            .create_and_append_event(
+                EventType::EntityCreated,
+                "sidecar".to_string(),
+                json!({"name": "Remote", "entity_type": "Module"}),
+            )
+            .unwrap();
+
+        // The pending event is applied before the graph is captured
+        kb.create_snapshot().unwrap();
+        assert_eq!(kb.graph.read().unwrap().entities.len(), 2);
+        assert!(kb.rotate_event_log().unwrap().is_some());
+
+        // Nothing is replayed or counted again after a restart
+        let reopened = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "server".to_string());
+        let event_store = reopened.event_store.as_ref().unwrap();
+        assert_eq!(event_store.lock().unwrap().events_since_snapshot(), 0);
+        assert!(event_store.lock().unwrap().load_events().unwrap().is_empty());
+        assert_eq!(reopened.graph.read().unwrap().entities.len(), 2);
+
+        reopened.create_entities(vec![entity("Later")]).unwrap();
+        let reopened = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "server".to_string());
+        let event_store = reopened.event_store.as_ref().unwrap();
+        assert_eq!(event_store.lock().unwrap().events_since_snapshot(), 1);
+        assert_eq!(reopened.graph.read().unwrap().entities.len(), 3);
+    }
+}
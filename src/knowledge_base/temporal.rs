@@ -1,22 +1,31 @@
 //! Temporal query operations
 
-use crate::types::{McpResult, Relation};
+use std::collections::BTreeMap;
+
+use serde_json::json;
+
+use crate::types::{EntityTimeline, Event, McpResult, PropertyFilter, Relation, TimelineEntry};
 use crate::utils::time::current_timestamp;
 
+use super::{acl, expire};
 use super::KnowledgeBase;
 
 /// Get relations valid at a specific point in time
+///
+/// Relations archived by `prune_expired` are included.
 pub fn get_relations_at_time(
     kb: &KnowledgeBase,
     timestamp: Option<u64>,
     entity_name: Option<&str>,
+    properties: &[PropertyFilter],
 ) -> McpResult<Vec<Relation>> {
-    let graph = kb.load_graph()?;
+    let graph = acl::visible(kb.load_graph()?);
     let check_time = timestamp.unwrap_or_else(current_timestamp);
 
     let relations: Vec<Relation> = graph
         .relations
         .into_iter()
+        .chain(expire::load_archived_relations(kb)?)
         .filter(|r| {
             // Filter by entity if specified
             if let Some(name) = entity_name {
@@ -36,7 +45,9 @@ pub fn get_relations_at_time(
                 None => true, // No end time means still valid
             };
 
-            valid_from_ok && valid_to_ok
+            valid_from_ok
+                && valid_to_ok
+                && PropertyFilter::all_match(properties, &r.properties)
         })
         .collect();
 
@@ -44,14 +55,229 @@ pub fn get_relations_at_time(
 }
 
 /// Get historical relations (including expired ones)
+///
+/// Relations in the graph come first, then those archived by `prune_expired`.
 pub fn get_relation_history(kb: &KnowledgeBase, entity_name: &str) -> McpResult<Vec<Relation>> {
-    let graph = kb.load_graph()?;
+    let graph = acl::visible(kb.load_graph()?);
 
     let relations: Vec<Relation> = graph
         .relations
         .into_iter()
+        .chain(expire::load_archived_relations(kb)?)
         .filter(|r| r.from == entity_name || r.to == entity_name)
         .collect();
 
     Ok(relations)
 }
+
+/// Build a chronological history of one entity
+///
+/// With Event Sourcing the timeline is assembled from every event touching the
+/// entity, archived ones included. Legacy mode only has the current state, so
+/// it falls back to the stored timestamps and lists what it cannot show.
+pub fn entity_timeline(kb: &KnowledgeBase, entity_name: &str) -> McpResult<EntityTimeline> {
+    if acl::hidden(&kb.graph.read().unwrap()).contains(entity_name) {
+        return Err(format!("Entity not found: {}", entity_name).into());
+    }
+    let timeline = match (&kb.event_store, &kb.log_rotation) {
+        (Some(event_store), Some(rotation)) => {
+            // Keyed by id: an event can be in both an archive and the active log
+            let mut events: BTreeMap<u64, Event> = BTreeMap::new();
+            for event in rotation.load_archived_events()? {
+                events.insert(event.event_id, event);
+            }
+            for event in event_store.lock().unwrap().load_events()? {
+                events.insert(event.event_id, event);
+            }
+
+            let mut entries: Vec<TimelineEntry> = events
+                .into_values()
+                .filter(|e| event_touches(e, entity_name))
+                .map(|e| TimelineEntry {
+                    timestamp: Some(e.timestamp),
+                    kind: e.event_type.to_string(),
+                    user: Some(e.user),
+                    event_id: Some(e.event_id),
+                    details: e.data,
+                })
+                .collect();
+            // Stable sort keeps event id order for equal timestamps
+            entries.sort_by_key(|e| e.timestamp);
+
+            EntityTimeline {
+                entity: entity_name.to_string(),
+                source: "event_log".to_string(),
+                entries,
+                unavailable: Vec::new(),
+            }
+        }
+        _ => legacy_timeline(kb, entity_name),
+    };
+
+    if timeline.entries.is_empty() {
+        return Err(format!("Entity not found: {}", entity_name).into());
+    }
+    Ok(timeline)
+}
+
+/// Check whether an event's payload refers to the entity
+pub(super) fn event_touches(event: &Event, entity_name: &str) -> bool {
+    ["name", "entity", "from", "to"]
+        .iter()
+        .any(|field| event.data.get(field).and_then(|v| v.as_str()) == Some(entity_name))
+}
+
+/// Timeline from the current state, for legacy (non event sourced) storage
+fn legacy_timeline(kb: &KnowledgeBase, entity_name: &str) -> EntityTimeline {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    let known = |ts: u64| (ts > 0).then_some(ts as i64);
+    let mut entries = Vec::new();
+
+    if let Some(entity) = graph.entities.iter().find(|e| e.name == entity_name) {
+        entries.push(TimelineEntry {
+            timestamp: known(entity.created_at),
+            kind: "entity_created".to_string(),
+            user: Some(entity.created_by.clone()).filter(|u| !u.is_empty()),
+            event_id: None,
+            details: json!({ "name": entity.name, "entity_type": entity.entity_type }),
+        });
+        if entity.updated_at > entity.created_at {
+            entries.push(TimelineEntry {
+                timestamp: known(entity.updated_at),
+                kind: "entity_updated".to_string(),
+                user: Some(entity.updated_by.clone()).filter(|u| !u.is_empty()),
+                event_id: None,
+                details: json!({ "name": entity.name }),
+            });
+        }
+        for observation in &entity.observations {
+            entries.push(TimelineEntry {
+                timestamp: None,
+                kind: "observation_added".to_string(),
+                user: None,
+                event_id: None,
+                details: json!({ "entity": entity.name, "observation": observation }),
+            });
+        }
+    }
+
+    for relation in graph
+        .relations
+        .iter()
+        .filter(|r| r.from == entity_name || r.to == entity_name)
+    {
+        entries.push(TimelineEntry {
+            timestamp: known(relation.created_at),
+            kind: "relation_created".to_string(),
+            user: Some(relation.created_by.clone()).filter(|u| !u.is_empty()),
+            event_id: None,
+            details: json!({
+                "from": relation.from,
+                "to": relation.to,
+                "relation_type": relation.relation_type
+            }),
+        });
+    }
+
+    // Dated entries first (oldest first), undated ones keep their stored order
+    entries.sort_by_key(|e| (e.timestamp.is_none(), e.timestamp));
+
+    EntityTimeline {
+        entity: entity_name.to_string(),
+        source: "current_state".to_string(),
+        entries,
+        unavailable: vec![
+            "observation timestamps and authors".to_string(),
+            "intermediate updates (only the latest is recorded)".to_string(),
+            "deleted observations and relations".to_string(),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Entity, Observation};
+    use tempfile::TempDir;
+
+    fn populate(kb: &KnowledgeBase) {
+        kb.create_entities(vec![
+            Entity::new("Auth".to_string(), "Module".to_string()),
+            Entity::new("Api".to_string(), "Module".to_string()),
+        ])
+        .unwrap();
+        kb.add_observations(vec![Observation::new(
+            "Auth".to_string(),
+            vec!["Uses JWT".to_string()],
+        )])
+        .unwrap();
+        kb.create_relations(vec![Relation::new(
+            "Api".to_string(),
+            "Auth".to_string(),
+            "depends_on".to_string(),
+        )])
+        .unwrap();
+    }
+
+    #[test]
+    fn test_timeline_from_event_log() {
+        let temp_dir = TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        populate(&kb);
+
+        let timeline = entity_timeline(&kb, "Auth").unwrap();
+        assert_eq!(timeline.source, "event_log");
+        let kinds: Vec<&str> = timeline.entries.iter().map(|e| e.kind.as_str()).collect();
+        assert_eq!(
+            kinds,
+            vec!["entity_created", "observation_added", "relation_created"]
+        );
+        assert!(timeline.entries.iter().all(|e| e.event_id.is_some()));
+        assert!(timeline.unavailable.is_empty());
+        assert!(entity_timeline(&kb, "Missing").is_err());
+    }
+
+    #[test]
+    fn test_timeline_shows_observation_edits() {
+        let temp_dir = TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        populate(&kb);
+        kb.add_observations(vec![Observation::new(
+            "Auth".to_string(),
+            vec!["Rate limited".to_string()],
+        )])
+        .unwrap();
+        assert_eq!(kb.replace_in_observations("JWT", "OAuth2", false).unwrap(), 1);
+
+        let timeline = entity_timeline(&kb, "Auth").unwrap();
+        let edit = timeline.entries.last().unwrap();
+        assert_eq!(edit.kind, "observation_edited");
+        assert_eq!(edit.details["before"], "Uses JWT");
+        assert_eq!(edit.details["after"], "Uses OAuth2");
+
+        // Replay keeps the edited observation in place
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        let graph = kb.read_graph(None, None).unwrap();
+        let auth = graph.entities.iter().find(|e| e.name == "Auth").unwrap();
+        assert_eq!(auth.observations, vec!["Uses OAuth2", "Rate limited"]);
+    }
+
+    #[test]
+    fn test_timeline_from_current_state() {
+        let path = format!("target/test_timeline_{}.jsonl", std::process::id());
+        let kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        populate(&kb);
+
+        let timeline = entity_timeline(&kb, "Auth").unwrap();
+        assert_eq!(timeline.source, "current_state");
+        assert_eq!(timeline.entries.len(), 3);
+        assert_eq!(timeline.entries[0].kind, "entity_created");
+        // Observations carry no timestamp in legacy mode
+        assert_eq!(timeline.entries[2].kind, "observation_added");
+        assert!(timeline.entries[2].timestamp.is_none());
+        assert!(!timeline.unavailable.is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,377 @@
+//! Atomic multi-operation transactions
+//!
+//! Operations are applied in order to a clone of the graph. The clone is only
+//! swapped in, persisted and its events appended once every operation has
+//! passed its checks; otherwise the live graph and event log are untouched.
+
+use std::collections::HashSet;
+
+use crate::types::{KnowledgeGraph, McpResult, Op, OpResult};
+use crate::validation::{ValidationWarning, ValidationWarningKind};
+
+use super::acl;
+use super::crud::{self, PendingEffects};
+use super::KnowledgeBase;
+
+/// Apply all operations atomically (thread-safe: holds write lock during entire operation)
+///
+/// Unlike the single-shot CRUD methods, which silently skip invalid items, a
+/// transaction fails when an operation references something that does not
+/// exist (or creates something that already does), or when an item would be
+/// dropped by the name policy, a cardinality constraint or an observation
+/// limit, so a partially valid edit is never committed. Relation endpoints may
+/// be given by id or alias, as with `create_relations`. Warnings about items
+/// that were kept, such as truncated observations, are returned.
+pub fn transaction(
+    kb: &KnowledgeBase,
+    ops: Vec<Op>,
+) -> McpResult<(Vec<OpResult>, Vec<ValidationWarning>)> {
+    crud::commit_with_warnings(kb, |graph, pending| {
+        // Effects queued in `pending` are discarded if any check fails
+        let mut working = graph.clone();
+        let mut results = Vec::with_capacity(ops.len());
+
+        for (index, mut op) in ops.into_iter().enumerate() {
+            let aborted = |e: String| format!("Transaction aborted at operation {}: {}", index, e);
+            normalize_op(kb, &working, &mut op);
+            check_op(&working, &op).map_err(aborted)?;
+
+            let submitted = submitted_items(&op);
+            let warned = pending.warnings().len();
+            let result = apply_op(kb, &mut working, op, pending);
+            check_applied(&result, submitted, &pending.warnings()[warned..]).map_err(aborted)?;
+            results.push(result);
+        }
+
+        *graph = working;
+        Ok(results)
+    })
+}
+
+/// Rewrite names in `op` to the form the apply step stores them in
+///
+/// Relation endpoints given by id or alias are resolved and relation types
+/// mapped through the aliases, so the checks below see the relations that
+/// would actually be created or deleted.
+fn normalize_op(kb: &KnowledgeBase, graph: &KnowledgeGraph, op: &mut Op) {
+    match op {
+        Op::CreateEntities { entities } => {
+            for entity in entities {
+                entity.name = kb.entity_names.normalize(&entity.name).into_owned();
+            }
+        }
+        Op::CreateRelations { relations } | Op::DeleteRelations { relations } => {
+            crud::resolve_relation_endpoints(kb, graph, relations);
+            if !kb.relation_aliases.is_empty() {
+                for relation in relations.iter_mut() {
+                    relation.relation_type = kb.relation_aliases.normalize(&relation.relation_type);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Number of items an operation creates, if every one of them is admitted
+fn submitted_items(op: &Op) -> Option<usize> {
+    match op {
+        Op::CreateEntities { entities } => Some(entities.len()),
+        Op::CreateRelations { relations } => Some(relations.len()),
+        _ => None,
+    }
+}
+
+/// Fail if applying an operation dropped any of its items
+fn check_applied(
+    result: &OpResult,
+    submitted: Option<usize>,
+    warnings: &[ValidationWarning],
+) -> Result<(), String> {
+    let describe = |w: &ValidationWarning| format!("'{}' was dropped: {}", w.subject, w.message);
+    let rejected = warnings.iter().find(|w| {
+        matches!(
+            w.kind,
+            ValidationWarningKind::EntityNameRejected | ValidationWarningKind::ObservationRejected
+        )
+    });
+    if let Some(warning) = rejected {
+        return Err(describe(warning));
+    }
+
+    let created = match result {
+        OpResult::CreateEntities { created } => created.len(),
+        OpResult::CreateRelations { created } => created.len(),
+        _ => return Ok(()),
+    };
+    match submitted {
+        Some(submitted) if created < submitted => Err(warnings
+            .iter()
+            .find(|w| w.kind == ValidationWarningKind::CardinalityViolation)
+            .map(describe)
+            .unwrap_or_else(|| format!("only {} of {} items would be created", created, submitted))),
+        _ => Ok(()),
+    }
+}
+
+/// Verify an operation against the working graph before applying it
+fn check_op(graph: &KnowledgeGraph, op: &Op) -> Result<(), String> {
+    let entity_names: HashSet<&str> = graph.entities.iter().map(|e| e.name.as_str()).collect();
+    let require_entity = |name: &str| {
+        if !entity_names.contains(name) {
+            return Err(format!("Entity '{}' not found", name));
+        }
+        acl::check_writable(graph, [name]).map_err(|e| e.to_string())
+    };
+
+    match op {
+        Op::CreateEntities { entities } => {
+            let mut seen = HashSet::new();
+            for entity in entities {
+                if entity_names.contains(entity.name.as_str()) || !seen.insert(&entity.name) {
+                    return Err(format!("Entity '{}' already exists", entity.name));
+                }
+            }
+        }
+        Op::CreateRelations { relations } => {
+            let mut seen = HashSet::new();
+            for relation in relations {
+                require_entity(&relation.from)?;
+                require_entity(&relation.to)?;
+                if relation_exists(graph, &relation.from, &relation.to, &relation.relation_type)
+                    || !seen.insert((&relation.from, &relation.to, &relation.relation_type))
+                {
+                    return Err(format!(
+                        "Relation '{} -[{}]-> {}' already exists",
+                        relation.from, relation.relation_type, relation.to
+                    ));
+                }
+            }
+        }
+        Op::AddObservations { observations } => {
+            for obs in observations {
+                require_entity(&obs.entity_name)?;
+            }
+        }
+        Op::ReplaceObservations { replacements } => {
+            for replacement in replacements {
+                require_entity(&replacement.entity_name)?;
+            }
+        }
+        Op::DeleteEntities { entity_names, .. } => {
+            for name in entity_names {
+                require_entity(name)?;
+            }
+        }
+        Op::DeleteObservations { deletions } => {
+            for deletion in deletions {
+                require_entity(&deletion.entity_name)?;
+            }
+        }
+        Op::DeleteRelations { relations } => {
+            acl::check_writable(graph, crud::relation_endpoints(relations))
+                .map_err(|e| e.to_string())?;
+            for relation in relations {
+                if !relation_exists(graph, &relation.from, &relation.to, &relation.relation_type) {
+                    return Err(format!(
+                        "Relation '{} -[{}]-> {}' not found",
+                        relation.from, relation.relation_type, relation.to
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn relation_exists(graph: &KnowledgeGraph, from: &str, to: &str, relation_type: &str) -> bool {
+    graph
+        .relations
+        .iter()
+        .any(|r| r.from == from && r.to == to && r.relation_type == relation_type)
+}
+
+/// Apply a checked operation to the working graph
+fn apply_op(
+    kb: &KnowledgeBase,
+    graph: &mut KnowledgeGraph,
+    op: Op,
+    pending: &mut PendingEffects,
+) -> OpResult {
+    match op {
+        Op::CreateEntities { entities } => OpResult::CreateEntities {
+            created: crud::apply_create_entities(kb, graph, entities, pending),
+        },
+        Op::CreateRelations { relations } => OpResult::CreateRelations {
+            created: crud::apply_create_relations(kb, graph, relations, pending),
+        },
+        Op::AddObservations { observations } => OpResult::AddObservations {
+            added: crud::apply_add_observations(kb, graph, observations, pending),
+        },
+        Op::ReplaceObservations { replacements } => OpResult::ReplaceObservations {
+            replaced: crud::apply_replace_observations(kb, graph, replacements, pending),
+        },
+        Op::DeleteEntities {
+            entity_names,
+            force,
+        } => OpResult::DeleteEntities {
+            skipped: crud::apply_delete_entities(kb, graph, entity_names, force, pending),
+        },
+        Op::DeleteObservations { deletions } => {
+            crud::apply_delete_observations(kb, graph, deletions, pending);
+            OpResult::DeleteObservations
+        }
+        Op::DeleteRelations { relations } => {
+            crud::apply_delete_relations(kb, graph, relations, pending);
+            OpResult::DeleteRelations
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::types::{Entity, Relation};
+
+    fn test_dir(name: &str) -> PathBuf {
+        PathBuf::from(format!(
+            "target/test_transaction_{}_{}",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn test_events_appended_only_on_commit() {
+        let dir = test_dir("events");
+        let _ = fs::remove_dir_all(&dir);
+        let kb = KnowledgeBase::for_testing_event_sourcing(&dir, "tester".to_string());
+
+        // Second op references a missing entity, so nothing is committed
+        let result = kb.transaction(vec![
+            Op::CreateEntities {
+                entities: vec![Entity::new("A".to_string(), "Module".to_string())],
+            },
+            Op::CreateRelations {
+                relations: vec![Relation::new(
+                    "A".to_string(),
+                    "Missing".to_string(),
+                    "depends_on".to_string(),
+                )],
+            },
+        ]);
+        assert!(result.is_err());
+        assert!(kb.graph.read().unwrap().entities.is_empty());
+        {
+            let store = kb.event_store.as_ref().unwrap().lock().unwrap();
+            assert_eq!(store.next_event_id(), 1);
+        }
+
+        // Relation to an entity created earlier in the same transaction succeeds
+        let results = kb
+            .transaction(vec![
+                Op::CreateEntities {
+                    entities: vec![
+                        Entity::new("A".to_string(), "Module".to_string()),
+                        Entity::new("B".to_string(), "Module".to_string()),
+                    ],
+                },
+                Op::CreateRelations {
+                    relations: vec![Relation::new(
+                        "A".to_string(),
+                        "B".to_string(),
+                        "depends_on".to_string(),
+                    )],
+                },
+            ])
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        {
+            let store = kb.event_store.as_ref().unwrap().lock().unwrap();
+            assert_eq!(store.next_event_id(), 4);
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_dropped_items_abort_the_transaction() {
+        use crate::types::Observation;
+        use crate::validation::{CardinalityConstraints, CardinalityMode, ObservationLimits};
+
+        let path = format!("target/test_transaction_drops_{}.jsonl", std::process::id());
+        let mut kb = KnowledgeBase::for_testing(path.clone(), "tester".to_string());
+        kb.cardinality = CardinalityConstraints::unconstrained()
+            .with_functional("reports_to", CardinalityMode::Reject);
+        kb.observation_limits = ObservationLimits {
+            max_len: Some(20),
+            max_per_entity: Some(1),
+            ..Default::default()
+        };
+        let created = kb
+            .create_entities(vec![
+                Entity::new("Alice".to_string(), "Person".to_string()),
+                Entity::new("Bob".to_string(), "Person".to_string()),
+            ])
+            .unwrap();
+        let bob_id = created[1].id.clone();
+        let relation = |from: &str, to: &str| {
+            Relation::new(from.to_string(), to.to_string(), "reports_to".to_string())
+        };
+        let observe = |contents: Vec<&str>| Op::AddObservations {
+            observations: vec![Observation::new(
+                "Alice".to_string(),
+                contents.into_iter().map(String::from).collect(),
+            )],
+        };
+        let snapshot = |kb: &KnowledgeBase| serde_json::to_value(&*kb.graph.read().unwrap());
+        let before = snapshot(&kb).unwrap();
+
+        // An endpoint given by id resolves like in create_relations
+        let err = kb
+            .transaction(vec![Op::CreateRelations {
+                relations: vec![relation("Alice", &bob_id), relation("Alice", "Bob")],
+            }])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("operation 0") && err.contains("already exists"), "{}", err);
+
+        // The second relation breaks the functional constraint
+        let err = kb
+            .transaction(vec![Op::CreateRelations {
+                relations: vec![relation("Alice", &bob_id), relation("Alice", "Alice")],
+            }])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("allows one relation per entity"), "{}", err);
+
+        // The second observation is over the per-entity limit
+        let err = kb
+            .transaction(vec![observe(vec!["first", "second"])])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("was dropped"), "{}", err);
+        assert_eq!(snapshot(&kb).unwrap(), before);
+
+        // Truncation keeps the item, so it only warns
+        let (results, warnings) = kb
+            .transaction_with_warnings(vec![
+                Op::CreateRelations {
+                    relations: vec![relation("Alice", &bob_id)],
+                },
+                observe(vec!["a fairly long observation"]),
+            ])
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(warnings.len(), 1);
+        let graph = kb.graph.read().unwrap();
+        assert_eq!(graph.relations[0].to, "Bob");
+        assert_eq!(graph.entities[0].observations[0].chars().count(), 20);
+        drop(graph);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,21 +1,38 @@
 //! Graph traversal operations
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use crate::types::{
-    Entity, McpResult, PathStep, RelatedEntities, RelatedEntity, TraversalPath, TraversalResult,
+    max_traverse_depth, Entity, InvalidParams, KnowledgeGraph, McpResult, PathStep,
+    PropertyFilter, ReferenceGroup, References, RelatedEntities, RelatedEntity,
+    RelatedOptions, RelatedSort, Relation, TraversalPath, TraversalResult, MAX_K_PATHS, MAX_PATH_DEPTH,
+    MAX_STEP_HOPS,
 };
 
+use crate::validation::RelationAliases;
+
+use super::{acl, cost};
 use super::KnowledgeBase;
 
 /// Get related entities
+///
+/// With `collapse_reciprocal` and direction `"both"`, an entity linked by the
+/// same relation type in both directions is listed once as `"bidirectional"`.
+/// A self-loop lists the entity itself once, in the queried direction.
+/// Results are sorted by `page.sort` before `offset` and `limit` are applied;
+/// `total` counts them all.
 pub fn get_related(
     kb: &KnowledgeBase,
     entity_name: &str,
-    relation_type: Option<&str>,
-    direction: &str,
+    options: &RelatedOptions,
 ) -> McpResult<RelatedEntities> {
-    let graph = kb.load_graph()?;
+    let graph = acl::visible(kb.load_graph()?);
+    let entity_name = graph.resolve_name(entity_name).to_string();
+    let entity_name = entity_name.as_str();
+    let aliases = &kb.relation_aliases;
+    let relation_type = options.relation_type.as_deref().map(|rt| aliases.normalize(rt));
+    let direction = options.direction.as_str();
+    let page = &options.page;
     let mut related = Vec::new();
 
     for relation in &graph.relations {
@@ -30,96 +47,254 @@ pub fn get_related(
             continue;
         }
 
-        if let Some(rt) = relation_type {
-            if relation.relation_type != rt {
+        if let Some(ref rt) = relation_type {
+            if aliases.canonical(&relation.relation_type) != rt {
                 continue;
             }
         }
 
+        if !PropertyFilter::all_match(&options.properties, &relation.properties) {
+            continue;
+        }
+
         let target_name = if relation.from == entity_name {
             &relation.to
         } else {
             &relation.from
         };
 
+        // A self-loop is both outgoing and incoming: it is listed once, in the
+        // queried direction ("bidirectional" for "both")
+        let relation_direction = if relation.from == relation.to {
+            match direction {
+                "both" => "bidirectional",
+                queried => queried,
+            }
+        } else if relation.from == entity_name {
+            "outgoing"
+        } else {
+            "incoming"
+        };
+
         if let Some(entity) = graph.entities.iter().find(|e| e.name == *target_name) {
             related.push(RelatedEntity {
                 relation_type: relation.relation_type.clone(),
-                direction: if relation.from == entity_name {
-                    "outgoing".to_string()
-                } else {
-                    "incoming".to_string()
-                },
+                direction: relation_direction.to_string(),
                 entity: entity.clone(),
             });
         }
     }
 
+    if options.collapse_reciprocal && direction == "both" {
+        related = collapse_reciprocal_relations(related);
+    }
+
+    // Stable sorts, so ties keep graph order
+    match page.sort {
+        RelatedSort::Graph => {}
+        RelatedSort::Name => related.sort_by(|a, b| a.entity.name.cmp(&b.entity.name)),
+        RelatedSort::EntityType => related.sort_by(|a, b| {
+            (&a.entity.entity_type, &a.entity.name).cmp(&(&b.entity.entity_type, &b.entity.name))
+        }),
+        RelatedSort::RelationType => related.sort_by(|a, b| {
+            (&a.relation_type, &a.entity.name).cmp(&(&b.relation_type, &b.entity.name))
+        }),
+    }
+    let total = related.len();
+    let related: Vec<RelatedEntity> = related
+        .into_iter()
+        .skip(page.offset)
+        .take(page.limit.unwrap_or(usize::MAX))
+        .collect();
+
+    kb.access_tracker
+        .record(related.iter().map(|r| r.entity.name.as_str()));
+
     Ok(RelatedEntities {
         entity: entity_name.to_string(),
         relations: related,
+        total,
+    })
+}
+
+/// Aggregate incoming relations by relation type and source entity type
+///
+/// Groups are ordered by size, largest first. References from entities that
+/// no longer exist are ignored, as in `get_related`.
+pub fn references_to(kb: &KnowledgeBase, entity_name: &str) -> McpResult<References> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    let entity_name = graph.resolve_name(entity_name);
+
+    let types: HashMap<&str, &str> = graph
+        .entities
+        .iter()
+        .map(|e| (e.name.as_str(), e.entity_type.as_str()))
+        .collect();
+    if !types.contains_key(entity_name) {
+        return Err(format!("Entity not found: {}", entity_name).into());
+    }
+
+    let mut grouped: BTreeMap<(&str, &str), Vec<String>> = BTreeMap::new();
+    for relation in graph.relations.iter().filter(|r| r.to == entity_name) {
+        if let Some(source_type) = types.get(relation.from.as_str()) {
+            let sources = grouped
+                .entry((relation.relation_type.as_str(), source_type))
+                .or_default();
+            if !sources.contains(&relation.from) {
+                sources.push(relation.from.clone());
+            }
+        }
+    }
+
+    let mut groups: Vec<ReferenceGroup> = grouped
+        .into_iter()
+        .map(|((relation_type, source_type), sources)| ReferenceGroup {
+            relation_type: relation_type.to_string(),
+            source_type: source_type.to_string(),
+            count: sources.len(),
+            sources,
+        })
+        .collect();
+    // Stable sort keeps the (relation type, source type) order among ties
+    groups.sort_by_key(|g| std::cmp::Reverse(g.count));
+
+    Ok(References {
+        entity: entity_name.to_string(),
+        total: groups.iter().map(|g| g.count).sum(),
+        groups,
     })
 }
 
+/// Merge outgoing/incoming pairs of the same relation type into one entry
+fn collapse_reciprocal_relations(related: Vec<RelatedEntity>) -> Vec<RelatedEntity> {
+    let mut collapsed: Vec<RelatedEntity> = Vec::with_capacity(related.len());
+    let mut index: HashMap<(String, String), usize> = HashMap::new();
+
+    for item in related {
+        let key = (item.entity.name.clone(), item.relation_type.clone());
+        match index.get(&key) {
+            Some(&i) if collapsed[i].direction != item.direction => {
+                collapsed[i].direction = "bidirectional".to_string();
+            }
+            Some(_) => {}
+            None => {
+                index.insert(key, collapsed.len());
+                collapsed.push(item);
+            }
+        }
+    }
+
+    collapsed
+}
+
 /// Traverse graph following path pattern
+///
+/// A step with a hop range expands like a Cypher variable-length pattern. While
+/// repeating, a path never revisits a node it already reached in that step.
+/// A self-loop is one hop back to the same node (in any step direction), so
+/// it only extends paths of single-hop steps.
+///
+/// Paths longer than [`max_traverse_depth`] steps or hops are refused before
+/// anything runs.
+///
+/// Paths carry the index of each relation followed, so with
+/// `include_relation_details` every path also lists those relations: parallel
+/// relations of one type between the same nodes yield one path each.
 pub fn traverse(
     kb: &KnowledgeBase,
     start: &str,
-    path: Vec<PathStep>,
+    mut path: Vec<PathStep>,
     max_results: usize,
+    include_relation_details: bool,
 ) -> McpResult<TraversalResult> {
-    let graph = kb.load_graph()?;
+    let graph = acl::visible(kb.load_graph()?);
+    let start = graph.resolve_name(start).to_string();
+    let start = start.as_str();
+    let aliases = &kb.relation_aliases;
+    for step in &mut path {
+        // Steps built in code skip the check done when deserializing
+        step.direction = PathStep::parse_direction(&step.direction)
+            .ok_or_else(|| InvalidParams(PathStep::unknown_direction(&step.direction)))?
+            .to_string();
+        if !aliases.is_empty() {
+            step.relation_type = aliases.normalize(&step.relation_type);
+        }
+    }
+    check_depth(&path, max_traverse_depth())?;
+    cost::check_traverse(kb, &graph, &path, max_results)?;
 
-    // Track paths: (current_node, path_so_far, relations_so_far)
-    let mut current_paths: Vec<(String, Vec<String>, Vec<String>)> =
+    // Track paths: (current_node, path_so_far, relation_indices_so_far)
+    let mut current_paths: Vec<(String, Vec<String>, Vec<usize>)> =
         vec![(start.to_string(), vec![start.to_string()], vec![])];
 
     for step in &path {
+        let (min_hops, max_hops) = step.hop_range();
+        if min_hops > max_hops {
+            return Err(format!(
+                "Invalid hop range for '{}': minHops {} exceeds maxHops {}",
+                step.relation_type, min_hops, max_hops
+            )
+            .into());
+        }
+        if max_hops > MAX_STEP_HOPS {
+            return Err(format!("maxHops cannot exceed {}", MAX_STEP_HOPS).into());
+        }
+
+        let repeating = max_hops > 1;
         let mut next_paths = Vec::new();
 
-        for (node, nodes_path, rels_path) in &current_paths {
-            // Find related entities for this step
-            for relation in &graph.relations {
-                let (matches, target_name) = match step.direction.as_str() {
-                    "out" => {
-                        if relation.from == *node && relation.relation_type == step.relation_type {
-                            (true, &relation.to)
-                        } else {
-                            (false, &relation.to)
-                        }
-                    }
-                    "in" => {
-                        if relation.to == *node && relation.relation_type == step.relation_type {
-                            (true, &relation.from)
-                        } else {
-                            (false, &relation.from)
-                        }
-                    }
-                    _ => (false, &relation.to),
-                };
+        if min_hops == 0 {
+            next_paths.extend(
+                current_paths
+                    .iter()
+                    .filter(|(node, _, _)| matches_target_type(&graph, step, node))
+                    .cloned(),
+            );
+        }
 
-                if !matches {
-                    continue;
-                }
+        // Each frontier entry remembers where this step began in its node list
+        let mut frontier: Vec<(String, Vec<String>, Vec<usize>, usize)> = current_paths
+            .into_iter()
+            .map(|(node, nodes, rels)| {
+                let step_start = nodes.len() - 1;
+                (node, nodes, rels, step_start)
+            })
+            .collect();
 
-                // Check target type if specified
-                if let Some(ref target_type) = step.target_type {
-                    if let Some(entity) = graph.entities.iter().find(|e| e.name == *target_name) {
-                        if &entity.entity_type != target_type {
-                            continue;
-                        }
-                    } else {
+        for hop in 1..=max_hops {
+            let mut expanded = Vec::new();
+
+            for (node, nodes_path, rels_path, step_start) in &frontier {
+                for (index, target_name) in step_targets(&graph.relations, aliases, step, node) {
+                    if repeating && nodes_path[*step_start..].contains(target_name) {
                         continue;
                     }
+
+                    let mut new_nodes = nodes_path.clone();
+                    new_nodes.push(target_name.clone());
+                    let mut new_rels = rels_path.clone();
+                    new_rels.push(index);
+
+                    expanded.push((target_name.clone(), new_nodes, new_rels, *step_start));
                 }
+            }
 
-                let mut new_nodes = nodes_path.clone();
-                new_nodes.push(target_name.clone());
-                let mut new_rels = rels_path.clone();
-                new_rels.push(step.relation_type.clone());
+            if hop >= min_hops {
+                next_paths.extend(
+                    expanded
+                        .iter()
+                        .filter(|(node, _, _, _)| matches_target_type(&graph, step, node))
+                        .map(|(node, nodes, rels, _)| (node.clone(), nodes.clone(), rels.clone())),
+                );
+            }
 
-                next_paths.push((target_name.clone(), new_nodes, new_rels));
+            if next_paths.len() >= max_results || expanded.is_empty() {
+                break;
             }
+
+            expanded.truncate(max_results);
+            frontier = expanded;
         }
 
         if next_paths.len() > max_results {
@@ -135,9 +310,13 @@ pub fn traverse(
 
     for (end_node, nodes, rels) in current_paths {
         end_node_names.insert(end_node);
+        let followed = || rels.iter().map(|&i| &graph.relations[i]);
         paths.push(TraversalPath {
             nodes,
-            relations: rels,
+            relations: followed()
+                .map(|r| aliases.canonical(&r.relation_type).to_string())
+                .collect(),
+            relation_details: include_relation_details.then(|| followed().cloned().collect()),
         });
     }
 
@@ -154,3 +333,356 @@ pub fn traverse(
         end_nodes,
     })
 }
+
+/// Refuse a path with more steps or hops than `max_depth`
+fn check_depth(path: &[PathStep], max_depth: usize) -> McpResult<()> {
+    let hops = PathStep::path_hops(path);
+    if path.len() > max_depth || hops > max_depth {
+        return Err(Box::new(InvalidParams(format!(
+            "Path too long: {} steps, up to {} hops; at most {} allowed (MEMORY_MAX_TRAVERSE_DEPTH)",
+            path.len(),
+            hops,
+            max_depth
+        ))));
+    }
+    Ok(())
+}
+
+/// Neighbours of `node` reachable through one hop of `step`, with the index
+/// of the relation leading there
+fn step_targets<'a>(
+    relations: &'a [Relation],
+    aliases: &'a RelationAliases,
+    step: &'a PathStep,
+    node: &'a str,
+) -> impl Iterator<Item = (usize, &'a String)> + 'a {
+    relations
+        .iter()
+        .enumerate()
+        .filter(move |(_, r)| aliases.canonical(&r.relation_type) == step.relation_type)
+        .filter_map(move |(i, r)| match step.direction.as_str() {
+            "out" if r.from == node => Some((i, &r.to)),
+            "in" if r.to == node => Some((i, &r.from)),
+            "both" if r.from == node => Some((i, &r.to)),
+            "both" if r.to == node => Some((i, &r.from)),
+            _ => None,
+        })
+}
+
+/// Check the step's optional target type against an entity
+fn matches_target_type(graph: &KnowledgeGraph, step: &PathStep, name: &str) -> bool {
+    match step.target_type {
+        Some(ref target_type) => graph
+            .entities
+            .iter()
+            .any(|e| e.name == name && &e.entity_type == target_type),
+        None => true,
+    }
+}
+
+/// Collect everything within `depth` hops of `center`, following relations
+/// in both directions
+///
+/// The result holds the reached entities and every relation between them, so
+/// it is a self-contained graph.
+pub fn get_subgraph(kb: &KnowledgeBase, center: &str, depth: usize) -> McpResult<KnowledgeGraph> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+
+    if !graph.entities.iter().any(|e| e.name == center) {
+        return Err(format!("Entity not found: {}", center).into());
+    }
+
+    let mut reached: HashSet<&str> = HashSet::from([center]);
+    let mut frontier: HashSet<&str> = HashSet::from([center]);
+
+    for _ in 0..depth {
+        let mut next = HashSet::new();
+        for relation in &graph.relations {
+            for (here, there) in [
+                (&relation.from, &relation.to),
+                (&relation.to, &relation.from),
+            ] {
+                if frontier.contains(here.as_str()) && reached.insert(there.as_str()) {
+                    next.insert(there.as_str());
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+
+    let entities: Vec<Entity> = graph
+        .entities
+        .iter()
+        .filter(|e| reached.contains(e.name.as_str()))
+        .cloned()
+        .collect();
+    let relations: Vec<Relation> = graph
+        .relations
+        .iter()
+        .filter(|r| reached.contains(r.from.as_str()) && reached.contains(r.to.as_str()))
+        .cloned()
+        .collect();
+
+    Ok(KnowledgeGraph {
+        entities,
+        relations,
+    })
+}
+
+/// Seeded random walk over outgoing relations
+///
+/// Starts at `start` (or a seed-chosen entity when `None`). At each step the
+/// walk jumps back to the start with probability `restart_prob`, or when the
+/// current node has no outgoing relations. The same graph and seed always
+/// produce the same walk. Returns `steps + 1` names including the start, or
+/// nothing if the start entity does not exist.
+pub fn random_walk(
+    kb: &KnowledgeBase,
+    start: Option<&str>,
+    steps: usize,
+    restart_prob: f32,
+    seed: u64,
+) -> Vec<String> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    let mut rng = SplitMix64::new(seed);
+
+    let start = match start {
+        Some(name) if graph.entities.iter().any(|e| e.name == name) => name.to_string(),
+        Some(_) => return Vec::new(),
+        None if graph.entities.is_empty() => return Vec::new(),
+        None => graph.entities[rng.below(graph.entities.len())].name.clone(),
+    };
+
+    let mut neighbors: HashMap<&str, Vec<&str>> = HashMap::new();
+    for relation in &graph.relations {
+        neighbors
+            .entry(relation.from.as_str())
+            .or_default()
+            .push(relation.to.as_str());
+    }
+
+    let restart_prob = f64::from(restart_prob.clamp(0.0, 1.0));
+    let mut walk = Vec::with_capacity(steps + 1);
+    walk.push(start.clone());
+    let mut current = start.as_str();
+
+    for _ in 0..steps {
+        let next = match neighbors.get(current) {
+            Some(options) if rng.next_f64() >= restart_prob => options[rng.below(options.len())],
+            _ => start.as_str(),
+        };
+        walk.push(next.to_string());
+        current = next;
+    }
+
+    walk
+}
+
+/// Path as entity indices and the relation indices between them
+#[derive(Clone, PartialEq)]
+struct IndexPath {
+    nodes: Vec<usize>,
+    edges: Vec<usize>,
+}
+
+/// Up to `k` shortest simple paths between two entities, shortest first
+///
+/// Relations are followed in either direction. Paths are found with Yen's
+/// algorithm over breadth-first searches: each new path deviates from an
+/// earlier one at some node, so alternatives through other routes (or other
+/// relations between the same entities) are listed once each and no path
+/// visits an entity twice. `k` is capped at [`MAX_K_PATHS`] and `max_depth`
+/// at [`MAX_PATH_DEPTH`] hops. Returns no paths when the entities are not
+/// connected within `max_depth`.
+pub fn k_shortest_paths(
+    kb: &KnowledgeBase,
+    from: &str,
+    to: &str,
+    k: usize,
+    max_depth: usize,
+) -> McpResult<Vec<TraversalPath>> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    let index: HashMap<&str, usize> = graph
+        .entities
+        .iter()
+        .enumerate()
+        .map(|(i, e)| (e.name.as_str(), i))
+        .collect();
+    let endpoint = |name: &str| {
+        let name = graph.resolve_name(name);
+        index
+            .get(name)
+            .copied()
+            .ok_or_else(|| format!("Entity not found: {}", name))
+    };
+    let (source, target) = (endpoint(from)?, endpoint(to)?);
+    let (k, max_depth) = (k.min(MAX_K_PATHS), max_depth.min(MAX_PATH_DEPTH));
+
+    let mut adjacency: Vec<Vec<(usize, usize)>> = vec![Vec::new(); graph.entities.len()];
+    for (edge, relation) in graph.relations.iter().enumerate() {
+        if let (Some(&a), Some(&b)) = (
+            index.get(relation.from.as_str()),
+            index.get(relation.to.as_str()),
+        ) {
+            adjacency[a].push((b, edge));
+            if a != b {
+                adjacency[b].push((a, edge));
+            }
+        }
+    }
+
+    let mut found: Vec<IndexPath> = Vec::new();
+    if k > 0 {
+        if let Some(first) = shortest_path(
+            &adjacency,
+            source,
+            target,
+            &HashSet::new(),
+            &HashSet::new(),
+            max_depth,
+        ) {
+            found.push(first);
+        }
+    }
+
+    let mut candidates: Vec<IndexPath> = Vec::new();
+    while !found.is_empty() && found.len() < k {
+        let last = found[found.len() - 1].clone();
+        for i in 0..last.edges.len() {
+            let (root_nodes, root_edges) = (&last.nodes[..=i], &last.edges[..i]);
+
+            // Leave the root by a relation no earlier path with this root used
+            let banned_edges: HashSet<usize> = found
+                .iter()
+                .filter(|p| {
+                    p.edges.len() > i && p.nodes[..=i] == *root_nodes && p.edges[..i] == *root_edges
+                })
+                .map(|p| p.edges[i])
+                .collect();
+            let banned_nodes: HashSet<usize> = root_nodes[..i].iter().copied().collect();
+
+            let Some(spur) = shortest_path(
+                &adjacency,
+                last.nodes[i],
+                target,
+                &banned_nodes,
+                &banned_edges,
+                max_depth - i,
+            ) else {
+                continue;
+            };
+            let mut path = IndexPath {
+                nodes: root_nodes[..i].to_vec(),
+                edges: root_edges.to_vec(),
+            };
+            path.nodes.extend(spur.nodes);
+            path.edges.extend(spur.edges);
+            if !found.contains(&path) && !candidates.contains(&path) {
+                candidates.push(path);
+            }
+        }
+
+        // Shortest candidate next; ties keep discovery order
+        let Some(best) = (0..candidates.len()).min_by_key(|&i| (candidates[i].edges.len(), i))
+        else {
+            break;
+        };
+        found.push(candidates.remove(best));
+    }
+
+    Ok(found
+        .into_iter()
+        .map(|path| {
+            TraversalPath::new(
+                path.nodes
+                    .iter()
+                    .map(|&n| graph.entities[n].name.clone())
+                    .collect(),
+                path.edges
+                    .iter()
+                    .map(|&e| graph.relations[e].relation_type.clone())
+                    .collect(),
+            )
+        })
+        .collect())
+}
+
+/// Breadth-first shortest path avoiding banned entities and relations
+fn shortest_path(
+    adjacency: &[Vec<(usize, usize)>],
+    source: usize,
+    target: usize,
+    banned_nodes: &HashSet<usize>,
+    banned_edges: &HashSet<usize>,
+    max_depth: usize,
+) -> Option<IndexPath> {
+    // Entity -> (previous entity, relation), for every entity reached
+    let mut parent: HashMap<usize, Option<(usize, usize)>> = HashMap::from([(source, None)]);
+    let mut frontier = vec![source];
+
+    for _ in 0..=max_depth {
+        if parent.contains_key(&target) {
+            let (mut nodes, mut edges) = (vec![target], Vec::new());
+            let mut current = target;
+            while let Some(Some((previous, edge))) = parent.get(&current) {
+                nodes.push(*previous);
+                edges.push(*edge);
+                current = *previous;
+            }
+            nodes.reverse();
+            edges.reverse();
+            return Some(IndexPath { nodes, edges });
+        }
+
+        let mut next = Vec::new();
+        for &node in &frontier {
+            for &(neighbor, edge) in &adjacency[node] {
+                if banned_edges.contains(&edge) || banned_nodes.contains(&neighbor) {
+                    continue;
+                }
+                if let std::collections::hash_map::Entry::Vacant(slot) = parent.entry(neighbor) {
+                    slot.insert(Some((node, edge)));
+                    next.push(neighbor);
+                }
+            }
+        }
+        if next.is_empty() {
+            return None;
+        }
+        frontier = next;
+    }
+    None
+}
+
+/// Small deterministic PRNG so walks are reproducible from a seed
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform float in `[0, 1)`
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform index in `0..n` (`n` must be non-zero)
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
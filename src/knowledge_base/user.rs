@@ -0,0 +1,97 @@
+//! Per-call user attribution
+//!
+//! `current_user` is detected once at startup (git config or environment).
+//! In HTTP mode many authenticated users share one knowledge base, so each
+//! tool call runs inside [`run_as`], which overrides the user on the current
+//! thread until the call returns. Writes made meanwhile record that user in
+//! `createdBy`/`updatedBy`, event metadata and broadcasts. Without an
+//! override the startup user is used.
+//!
+//! The override is the authenticated [`Caller`], permissions included, so the
+//! entity access-control lists in `acl.rs` can be checked against it. HTTP
+//! requests without a token run as [`Caller::anonymous`]: they satisfy no ACL
+//! and their writes are attributed to the startup user.
+//!
+//! Mutations run synchronously on the calling thread, so a thread-local
+//! override cannot leak into another request.
+
+use std::cell::RefCell;
+
+use crate::types::Caller;
+
+use super::KnowledgeBase;
+
+thread_local! {
+    static ACTING_USER: RefCell<Option<Caller>> = const { RefCell::new(None) };
+}
+
+/// Restores the previous override when the call returns or panics
+struct Restore(Option<Caller>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        ACTING_USER.with(|user| *user.borrow_mut() = self.0.take());
+    }
+}
+
+/// Run `call` with writes attributed to `user` (`None` keeps the current user)
+///
+/// The user holds no permissions, so only ACLs naming them are satisfied.
+pub fn run_as<T>(user: Option<&str>, call: impl FnOnce() -> T) -> T {
+    let caller = user.map(|user| Caller::new(user.to_string(), Vec::new()));
+    run_as_caller(caller, call)
+}
+
+/// Run `call` as the authenticated `caller` (`None` keeps the current user)
+pub fn run_as_caller<T>(caller: Option<Caller>, call: impl FnOnce() -> T) -> T {
+    let Some(caller) = caller else {
+        return call();
+    };
+    let previous = ACTING_USER.with(|current| current.replace(Some(caller)));
+    let _restore = Restore(previous);
+    call()
+}
+
+/// Authenticated caller of the current call, if any
+pub fn caller() -> Option<Caller> {
+    ACTING_USER.with(|caller| caller.borrow().clone())
+}
+
+/// User to stamp on writes: the authenticated override, else the startup user
+pub fn acting_user(kb: &KnowledgeBase) -> String {
+    ACTING_USER
+        .with(|caller| {
+            let caller = caller.borrow();
+            caller.as_ref().filter(|c| c.authenticated).map(|c| c.user.clone())
+        })
+        .unwrap_or_else(|| kb.current_user.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Entity;
+
+    #[test]
+    fn test_run_as_attributes_writes() {
+        let path = format!("target/test_run_as_{}.jsonl", std::process::id());
+        let kb = KnowledgeBase::for_testing(path.clone(), "server".to_string());
+
+        run_as(Some("alice"), || {
+            kb.create_entities(vec![Entity::new("Auth".to_string(), "Module".to_string())])
+                .unwrap();
+            // Overrides nest and restore the outer user
+            run_as(Some("bob"), || assert_eq!(acting_user(&kb), "bob"));
+            assert_eq!(acting_user(&kb), "alice");
+        });
+        kb.create_entities(vec![Entity::new("Billing".to_string(), "Module".to_string())])
+            .unwrap();
+
+        let graph = kb.read_graph(None, None).unwrap();
+        assert_eq!(graph.entities[0].created_by, "alice");
+        assert_eq!(graph.entities[1].created_by, "server");
+        assert_eq!(run_as(None, || acting_user(&kb)), "server");
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
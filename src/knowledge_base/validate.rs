@@ -0,0 +1,85 @@
+//! Graph-wide validation
+
+use std::collections::HashSet;
+
+use crate::validation::{
+    validate_entity_type, validate_relation_type, ValidationWarning, ValidationWarningKind,
+};
+
+use super::{acl, KnowledgeBase};
+
+/// Scan the whole graph and collect every validation warning
+pub fn validate_all(kb: &KnowledgeBase) -> Vec<ValidationWarning> {
+    let graph = kb.graph.read().unwrap();
+    let graph = acl::view(&graph);
+    let mut warnings = Vec::new();
+
+    for entity in &graph.entities {
+        if entity.name.is_empty() {
+            warnings.push(ValidationWarning::new(
+                ValidationWarningKind::MissingField,
+                entity.name.clone(),
+                format!("Entity of type '{}' has an empty name", entity.entity_type),
+            ));
+        }
+
+        if entity.entity_type.is_empty() {
+            warnings.push(ValidationWarning::new(
+                ValidationWarningKind::MissingField,
+                entity.name.clone(),
+                format!("Entity '{}' has an empty entityType", entity.name),
+            ));
+        } else if let Some(message) = validate_entity_type(&entity.entity_type) {
+            warnings.push(ValidationWarning::new(
+                ValidationWarningKind::NonStandardEntityType,
+                entity.name.clone(),
+                message,
+            ));
+        }
+    }
+
+    let entity_names: HashSet<&str> = graph.entities.iter().map(|e| e.name.as_str()).collect();
+
+    for relation in &graph.relations {
+        let subject = format!(
+            "{} -[{}]-> {}",
+            relation.from, relation.relation_type, relation.to
+        );
+
+        for (field, value) in [
+            ("from", &relation.from),
+            ("to", &relation.to),
+            ("relationType", &relation.relation_type),
+        ] {
+            if value.is_empty() {
+                warnings.push(ValidationWarning::new(
+                    ValidationWarningKind::MissingField,
+                    subject.clone(),
+                    format!("Relation has an empty '{}'", field),
+                ));
+            }
+        }
+
+        if !relation.relation_type.is_empty() {
+            if let Some(message) = validate_relation_type(&relation.relation_type) {
+                warnings.push(ValidationWarning::new(
+                    ValidationWarningKind::NonStandardRelationType,
+                    subject.clone(),
+                    message,
+                ));
+            }
+        }
+
+        for endpoint in [&relation.from, &relation.to] {
+            if !endpoint.is_empty() && !entity_names.contains(endpoint.as_str()) {
+                warnings.push(ValidationWarning::new(
+                    ValidationWarningKind::DanglingRelation,
+                    subject.clone(),
+                    format!("Relation references missing entity '{}'", endpoint),
+                ));
+            }
+        }
+    }
+
+    warnings
+}
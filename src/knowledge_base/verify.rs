@@ -0,0 +1,315 @@
+//! Replay verification (Event Sourcing mode)
+//!
+//! With `MEMORY_VERIFY_ON_START=true` the graph loaded at startup (snapshot
+//! plus the events after it) is checked against a replay of the whole event
+//! history from scratch, archived events included. Any divergence means a
+//! corrupt snapshot or a bug in `EventStore::apply_event`; the first differing
+//! entity or relation is logged. The check reads the full history, so it is
+//! off by default.
+//!
+//! Timestamps and authorship are not compared: live mutations stamp them at
+//! apply time, replays take them from the event.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::env;
+
+use crate::event_store::EventStore;
+use crate::types::{Entity, KnowledgeGraph, McpResult, Relation};
+
+use super::KnowledgeBase;
+
+/// Replay every event from scratch and compare with the loaded graph
+///
+/// Returns a description of the first difference, or `None` if the graphs
+/// match (or Event Sourcing is disabled).
+pub fn verify_replay(kb: &KnowledgeBase) -> McpResult<Option<String>> {
+    let (Some(event_store), Some(rotation)) = (&kb.event_store, &kb.log_rotation) else {
+        return Ok(None);
+    };
+
+    // Archives first, then the log files in the order `initialize` reads
+    // them. An event can be in both an archive and the active log if a
+    // rotation was interrupted; its first copy is kept.
+    let mut events = rotation.load_archived_events()?;
+    let snapshot_meta = {
+        let store = event_store.lock().unwrap();
+        events.extend(store.load_events()?);
+        store.load_snapshot_meta()?
+    };
+    let mut seen = HashSet::new();
+    events.retain(|event| seen.insert(event.event_id));
+
+    if let Some(meta) = snapshot_meta {
+        let last_event_id = meta.last_event_id;
+        let unavailable = match meta.log_position {
+            // Every archived line has to be there, whichever writer it came from
+            Some(_) => {
+                let archived: usize = rotation.list_archives()?.iter().map(|a| a.event_count).sum();
+                (archived as u64) < rotation.archived_lines()?
+            }
+            None => events
+                .iter()
+                .map(|event| event.event_id)
+                .min()
+                .is_none_or(|first| first > last_event_id),
+        };
+        if unavailable {
+            return Err(format!(
+                "events up to {} are no longer available (archives pruned or disabled)",
+                last_event_id
+            )
+            .into());
+        }
+    }
+
+    let mut replayed = KnowledgeGraph::default();
+    for event in &events {
+        EventStore::apply_event(&mut replayed.entities, &mut replayed.relations, event)?;
+    }
+
+    let loaded = kb.graph.read().unwrap();
+    Ok(first_difference(&loaded, &replayed))
+}
+
+/// Run `verify_replay` at startup if `MEMORY_VERIFY_ON_START` is set, logging the outcome
+pub fn verify_from_env(kb: &KnowledgeBase) {
+    let enabled = env::var("MEMORY_VERIFY_ON_START")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+    if !enabled || kb.event_store.is_none() {
+        return;
+    }
+
+    match verify_replay(kb) {
+        Ok(None) => eprintln!("[Verify] Loaded graph matches a full replay of the event log"),
+        Ok(Some(difference)) => eprintln!(
+            "Warning: Loaded graph diverges from a full replay of the event log: {}",
+            difference
+        ),
+        Err(e) => eprintln!("Warning: Could not verify event log replay: {}", e),
+    }
+}
+
+/// First difference between two graphs, entities before relations, by name
+fn first_difference(loaded: &KnowledgeGraph, replayed: &KnowledgeGraph) -> Option<String> {
+    let loaded_entities = entities_by_name(&loaded.entities);
+    let replayed_entities = entities_by_name(&replayed.entities);
+    let names: BTreeSet<&str> = loaded_entities
+        .keys()
+        .chain(replayed_entities.keys())
+        .copied()
+        .collect();
+    for name in names {
+        let difference = match (loaded_entities.get(name), replayed_entities.get(name)) {
+            (Some(_), None) => Some("only in the loaded graph".to_string()),
+            (None, Some(_)) => Some("missing from the loaded graph".to_string()),
+            (Some(a), Some(b)) => entity_difference(a, b),
+            (None, None) => None,
+        };
+        if let Some(difference) = difference {
+            return Some(format!("entity '{}': {}", name, difference));
+        }
+    }
+
+    let loaded_relations = relations_by_key(&loaded.relations);
+    let replayed_relations = relations_by_key(&replayed.relations);
+    let keys: BTreeSet<_> = loaded_relations
+        .keys()
+        .chain(replayed_relations.keys())
+        .copied()
+        .collect();
+    for key in keys {
+        let difference = match (loaded_relations.get(&key), replayed_relations.get(&key)) {
+            (Some(_), None) => Some("only in the loaded graph"),
+            (None, Some(_)) => Some("missing from the loaded graph"),
+            (Some(a), Some(b)) if (a.valid_from, a.valid_to) != (b.valid_from, b.valid_to) => {
+                Some("validity differs")
+            }
+            (Some(a), Some(b)) if a.properties != b.properties => Some("properties differ"),
+            _ => None,
+        };
+        if let Some(difference) = difference {
+            let (from, relation_type, to) = key;
+            return Some(format!(
+                "relation {} -[{}]-> {}: {}",
+                from, relation_type, to, difference
+            ));
+        }
+    }
+
+    None
+}
+
+fn entity_difference(loaded: &Entity, replayed: &Entity) -> Option<String> {
+    if loaded.entity_type != replayed.entity_type {
+        return Some(format!(
+            "type '{}' vs '{}' after replay",
+            loaded.entity_type, replayed.entity_type
+        ));
+    }
+    if loaded.id != replayed.id {
+        return Some(format!(
+            "id '{}' vs '{}' after replay",
+            loaded.id, replayed.id
+        ));
+    }
+    if loaded.observations != replayed.observations {
+        let position = loaded
+            .observations
+            .iter()
+            .zip(&replayed.observations)
+            .position(|(a, b)| a != b)
+            .unwrap_or(loaded.observations.len().min(replayed.observations.len()));
+        return Some(format!(
+            "observations differ at position {} ({} vs {} after replay)",
+            position,
+            loaded.observations.len(),
+            replayed.observations.len()
+        ));
+    }
+    None
+}
+
+fn entities_by_name(entities: &[Entity]) -> BTreeMap<&str, &Entity> {
+    entities.iter().map(|e| (e.name.as_str(), e)).collect()
+}
+
+fn relations_by_key(relations: &[Relation]) -> BTreeMap<(&str, &str, &str), &Relation> {
+    relations
+        .iter()
+        .map(|r| {
+            (
+                (r.from.as_str(), r.relation_type.as_str(), r.to.as_str()),
+                r,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_store::EventStoreConfig;
+    use crate::types::{Event, EventType, Observation};
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_verify_replay_detects_divergence() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        kb.create_entities(vec![
+            Entity::new("Api".to_string(), "Module".to_string()),
+            Entity::new("Auth".to_string(), "Module".to_string()),
+        ])
+        .unwrap();
+        kb.create_relations(vec![Relation::new(
+            "Api".to_string(),
+            "Auth".to_string(),
+            "uses".to_string(),
+        )])
+        .unwrap();
+        kb.add_observations(vec![Observation {
+            entity_name: "Auth".to_string(),
+            contents: vec!["Issues JWTs".to_string()],
+        }])
+        .unwrap();
+        kb.create_snapshot().unwrap();
+        assert_eq!(verify_replay(&kb).unwrap(), None);
+
+        // Drift the in-memory graph away from the log
+        kb.graph.write().unwrap().entities[1].observations.clear();
+        let difference = verify_replay(&kb).unwrap().unwrap();
+        assert!(
+            difference.starts_with("entity 'Auth': observations"),
+            "{}",
+            difference
+        );
+
+        kb.graph.write().unwrap().entities[1].observations = vec!["Issues JWTs".to_string()];
+        kb.graph.write().unwrap().relations.clear();
+        let difference = verify_replay(&kb).unwrap().unwrap();
+        assert_eq!(
+            difference,
+            "relation Api -[uses]-> Auth: missing from the loaded graph"
+        );
+    }
+
+    #[test]
+    fn test_verify_replays_in_log_order() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut store = EventStore::with_config(EventStoreConfig::with_data_dir(temp_dir.path()));
+        // Another writer's block can put lower IDs after higher ones
+        store
+            .append_event(Event::new(
+                EventType::EntityCreated,
+                10,
+                "a".to_string(),
+                json!({"name": "Cache", "entity_type": "Module"}),
+            ))
+            .unwrap();
+        store
+            .append_event(Event::new(
+                EventType::EntityDeleted,
+                5,
+                "b".to_string(),
+                json!({"name": "Cache"}),
+            ))
+            .unwrap();
+
+        let kb = KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "tester".to_string());
+        assert!(kb.graph.read().unwrap().entities.is_empty());
+        assert_eq!(verify_replay(&kb).unwrap(), None);
+    }
+
+    #[test]
+    fn test_block_writers_snapshot_and_rotate() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = EventStoreConfig {
+            id_block_size: Some(2),
+            ..EventStoreConfig::with_data_dir(temp_dir.path())
+        };
+        let mut kb =
+            KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "server".to_string());
+        kb.event_store = Some(Mutex::new(EventStore::with_config(config.clone())));
+        let mut sidecar = EventStore::with_config(config);
+        let mut remote = |name: &str| {
+            sidecar
+                .create_and_append_event(
+                    EventType::EntityCreated,
+                    "sidecar".to_string(),
+                    json!({"name": name, "entity_type": "Module"}),
+                )
+                .unwrap()
+                .event_id
+        };
+        let local = |kb: &KnowledgeBase, name: &str| {
+            kb.create_entities(vec![Entity::new(name.to_string(), "Module".to_string())])
+                .unwrap();
+        };
+
+        // The sidecar reserves IDs 1..3, the server 3..5
+        assert_eq!(remote("Remote"), 1);
+        local(&kb, "Api");
+        local(&kb, "Auth");
+        kb.create_snapshot().unwrap();
+        assert!(kb.rotate_event_log().unwrap().is_some());
+
+        // A lower ID than the snapshot's lands after it in the log
+        assert_eq!(remote("Late"), 2);
+
+        let reopened =
+            KnowledgeBase::for_testing_event_sourcing(temp_dir.path(), "server".to_string());
+        let mut names: Vec<String> = reopened
+            .graph
+            .read()
+            .unwrap()
+            .entities
+            .iter()
+            .map(|e| e.name.clone())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["Api", "Auth", "Late", "Remote"]);
+        assert_eq!(verify_replay(&reopened).unwrap(), None);
+    }
+}
@@ -5,7 +5,7 @@
 //!
 //! # Features
 //!
-//! - **16 MCP Tools**: Full CRUD, query, temporal, and inference operations
+//! - **55 MCP Tools**: Full CRUD, query, temporal, and inference operations
 //! - **Thread-Safe**: Production-ready with RwLock-based concurrency
 //! - **Semantic Search**: Built-in synonym matching
 //! - **Time Travel**: Query historical state with validFrom/validTo
@@ -17,7 +17,7 @@
 //! - `types`: Core data structures (Entity, Relation, KnowledgeGraph)
 //! - `protocol`: MCP and JSON-RPC protocol types
 //! - `knowledge_base`: Core data engine with CRUD, queries, and inference
-//! - `tools`: 16 MCP tool implementations
+//! - `tools`: 55 MCP tool implementations
 //! - `search`: Semantic search with synonym expansion
 //! - `validation`: Entity and relation type validation
 //! - `utils`: Utility functions (timestamps, etc.)
@@ -38,6 +38,29 @@
 //!     server.run().unwrap();
 //! }
 //! ```
+//!
+//! # Embedding
+//!
+//! When `memory-graph` is used as a library, [`KnowledgeBase`] is the API:
+//! its methods take and return the types in [`types`]. The tools are thin
+//! MCP adapters over those methods that render their result as JSON text.
+//! To reuse a tool's argument handling anyway, [`tools::execute_typed`] runs
+//! it and parses the result back:
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use memory_graph::KnowledgeBase;
+//! use memory_graph::tools::{execute_typed, TraverseTool};
+//! use memory_graph::types::TraversalResult;
+//!
+//! let kb = Arc::new(KnowledgeBase::new());
+//! let result: TraversalResult = execute_typed(
+//!     &TraverseTool::new(kb),
+//!     serde_json::json!({ "startNode": "Api", "path": [{ "relationType": "depends_on" }] }),
+//! )
+//! .unwrap();
+//! println!("{} end nodes", result.end_nodes.len());
+//! ```
 
 pub mod api;
 pub mod event_store;
@@ -63,9 +86,11 @@ pub use knowledge_base::KnowledgeBase;
 pub use protocol::{McpTool, ServerInfo, Tool};
 pub use server::McpServer;
 pub use types::{
-    Entity, EntityBrief, Event, EventData, EventSource, EventType, InferResult, InferStats,
-    InferredRelation, KnowledgeGraph, McpResult, Observation, ObservationDeletion, PathStep,
-    RelatedEntities, RelatedEntity, Relation, SnapshotMeta, Summary, TraversalPath, TraversalResult,
+    Entity, EntityBrief, Event, EventData, EventSource, EventType, HotEntity, InferResult,
+    InferStats, InferredRelation, KnowledgeGraph, McpResult, Observation, ObservationChange,
+    ObservationDeletion, ObservationReplacement, ObservationReplacementResult, Op, OpResult,
+    PathStep, RelatedEntities, RelatedEntity, Relation, ScoredEntity, SearchField, SearchOptions,
+    SearchResult, SnapshotMeta, Summary, TraversalPath, TraversalResult,
 };
 
 /// Library version
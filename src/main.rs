@@ -34,8 +34,11 @@ use std::env;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use memory_graph::api::websocket::{init_broadcaster, state::AppState};
-use memory_graph::api::http::create_router_with_auth;
+use memory_graph::api::websocket::{
+    backpressure::BackpressureConfig, init_broadcaster_with_sequence, state::AppState,
+    SequenceCounter,
+};
+use memory_graph::api::http::{api_prefix_from_env, create_router_with_auth, startup_router};
 use memory_graph::api::sse::JwtAuth;
 use memory_graph::knowledge_base::KnowledgeBase;
 use memory_graph::protocol::ServerInfo;
@@ -113,6 +116,77 @@ ENVIRONMENT VARIABLES:
     MEMORY_SERVER_MODE       Override server mode (stdio, http, both)
     MEMORY_FILE_PATH         Path to memory.jsonl file
     MEMORY_EVENT_SOURCING    Enable event sourcing (true/false)
+    MEMORY_SUPPRESS_VALIDATION_WARNINGS
+                             Leave validation warnings out of tool responses (true/false)
+    MEMORY_ACCESS_TRACKING   Count entity reads for hot_entities (default: true)
+    MEMORY_ACCESS_TRACKING   Count entity reads for hot_entities (default: true)
+    MEMORY_MAX_OBSERVATION_LEN
+                             Longest observation in characters (default: no limit)
+    MEMORY_MAX_OBSERVATIONS_PER_ENTITY
+                             Most observations one entity may hold (default: no limit)
+    MEMORY_OBSERVATION_LIMIT_MODE
+                             Observations over the length limit: truncate (default) or reject
+    MEMORY_EVENT_TAIL        Apply events other processes append to the event log (true/false)
+    MEMORY_EVENT_ID_RANGE    Event IDs this process may assign, as START..END
+    MEMORY_EVENT_TAIL        Apply events other processes append to the event log (true/false)
+    MEMORY_EVENT_TAIL_INTERVAL_MS
+                             How often the event log is polled in tail mode (default: 1000)
+    MEMORY_EVENT_ID_RANGE    Event IDs this process may assign, as START..END
+    MEMORY_SNAPSHOT_INTERVAL_SECS
+                             Also snapshot when this many seconds passed with new events (default: off)
+    MEMORY_WS_DEBOUNCE_MS    Delay before WebSocket change batches are sent (1-10000, default: 50)
+    MEMORY_WS_MAX_BATCH      Most changes sent in one WebSocket batch (1-10000, default: 100)
+    MEMORY_WS_DEBOUNCE_MS    Delay before WebSocket change batches are sent (1-10000, default: 50)
+    MEMORY_WS_MAX_BATCH      Most changes sent in one WebSocket batch (1-10000, default: 100)
+    MEMORY_FUNCTIONAL_RELATIONS
+                             Relation types allowed once per source entity, as type[:reject|replace],...
+    MEMORY_OBSERVATION_DEDUP Observation duplicate check: exact (default) or normalized
+    MEMORY_OBSERVATION_DEDUP Observation duplicate check: exact (default) or normalized
+    MEMORY_OBSERVATION_ORDER Observation order in read output: oldest (default) or newest
+    MEMORY_OBSERVATION_ORDER Observation order in read output: oldest (default) or newest
+    MEMORY_MAX_CORRUPT_PERCENT
+                             Refuse to load when more than this percent of lines are corrupt (0-100)
+    MEMORY_RELATION_ALIASES  Relation type aliases, as alias=canonical,...
+    MEMORY_RELATION_ALIASES  Relation type aliases, as alias=canonical,...
+    MEMORY_SEED_PATH         JSONL graph imported when the knowledge base starts empty
+    MEMORY_SEED_PATH         JSONL graph imported when the knowledge base starts empty
+    MEMORY_ENTITY_NAME_POLICY
+                             Entity names: normalize (default, trim and reject empty) or verbatim
+    MEMORY_ENTITY_NAME_CASE_FOLD
+                             Also lowercase entity names (true/false)
+    MEMORY_MAX_ENTITY_NAME_LEN
+                             Longest entity name in characters (default: no limit)
+    MEMORY_INFERENCE_CACHE_SIZE
+                             Inference results cached per graph version (default: 128)
+    MEMORY_API_PREFIX        Base path for all HTTP routes (e.g. /memory)
+    MEMORY_VERIFY_ON_START   Check the loaded graph against a full event replay (true/false)
+    MEMORY_DELTA_SNAPSHOTS   Delta snapshots to write between full snapshots (default: 0)
+    MEMORY_EVENT_ID_BLOCK    Reserve event IDs in blocks of this size shared with other writers
+    MEMORY_SEGMENT_BYTES     Roll events.jsonl into a numbered segment at this size (default: off)
+    MEMORY_MAX_QUERY_VISITS  Refuse traverse/infer calls estimated to visit more nodes than this
+    MEMORY_WS_CLIENT_BUFFER  Unread messages buffered per WebSocket/SSE client (16-65536, default: 1024)
+    MEMORY_WS_CLIENT_BUFFER  Unread messages buffered per WebSocket/SSE client (16-65536, default: 1024)
+    MEMORY_WS_SLOW_CLIENT_POLICY
+                             Clients lagging past the replay history: drop-oldest (default) or disconnect
+    MEMORY_DEFAULT_DIRECTION Direction of traverse steps that leave it out: out (default), in or both
+    MEMORY_WRITE_RETRIES     Retries for file writes that fail while the file is in use (default: 3)
+    MEMORY_ENTITY_TEMPLATES  JSON file of default observations per entity type
+    MEMORY_DUPLICATE_IDS     Reused stdio request ids: allow, warn (default) or reject
+    MEMORY_RECENT_CHANGES    Recent changes kept in memory for recent_changes / GET /api/changes (default: 256, 0 = off)
+    MEMORY_RESULT_LINK_BYTES Return read results over this size as memory:// resource links
+    MEMORY_COMPACT_OUTPUT    Compact instead of pretty JSON in tool responses (true/false; per call: "compact")
+    MEMORY_SYMMETRIC_RELATIONS
+                             Relation types normalize_relations treats as symmetric (default: relates_to)
+    MEMORY_REPLAY_PROGRESS_EVERY
+                             Report event replay progress every this many events (default: 10000)
+    MEMORY_SSE_SESSION_IDLE_SECS
+                             Close SSE sessions idle this long (default: 1800, 0 = never)
+    MEMORY_MAX_TRAVERSE_DEPTH
+                             Most steps or hops in one traverse path (default: 10)
+    MEMORY_SYNONYM_MATCH     Synonym group matching: token (default) or substring
+    MEMORY_SYNONYM_MATCH     Synonym group matching: token (default) or substring
+    MEMORY_ATTRIBUTE_PREFIX  Prefix of key=value attribute observations (default: @)
+    MEMORY_LEGACY_ATTRIBUTES Also read Key: value observations as attributes (default: true)
 
 EXAMPLES:
     # Run as MCP server for AI Agents
@@ -138,10 +212,20 @@ fn main() -> McpResult<()> {
     }
 }
 
+/// Load the knowledge base, exiting with a message if its data can't be read
+fn load_knowledge_base() -> KnowledgeBase {
+    KnowledgeBase::try_new().unwrap_or_else(|e| {
+        eprintln!("[Memory Server] Failed to load knowledge base: {}", e);
+        std::process::exit(1);
+    })
+}
+
 /// Run in stdio mode (MCP for AI Agents)
 fn run_stdio_mode() -> McpResult<()> {
-    let kb = Arc::new(KnowledgeBase::new());
+    let kb = Arc::new(load_knowledge_base());
     let kb_for_shutdown = Arc::clone(&kb);
+    KnowledgeBase::spawn_event_tail(&kb);
+    KnowledgeBase::spawn_snapshot_worker(&kb);
 
     setup_shutdown_handler(kb_for_shutdown);
 
@@ -193,14 +277,57 @@ fn run_both_modes() -> McpResult<()> {
 
 /// Run the HTTP server with WebSocket support
 async fn run_http_server() -> McpResult<()> {
-    // Create SINGLE knowledge base - shared by both SSE/MCP and REST/WebSocket
-    let kb = Arc::new(KnowledgeBase::new());
-
-    // Initialize global broadcaster for WebSocket events
-    init_broadcaster(1024);
+    // Bind first so /health and /ready answer while a large event log replays
+    let addr = std::net::SocketAddr::from(([0, 0, 0, 0], 3030));
+    let base = api_prefix_from_env();
+    let std_listener = std::net::TcpListener::bind(addr)
+        .map_err(|e| format!("Failed to bind to {}: {}", addr, e))?;
+    std_listener.set_nonblocking(true)?;
+    let startup_listener = tokio::net::TcpListener::from_std(std_listener.try_clone()?)?;
+    let (loaded_tx, loaded_rx) = tokio::sync::oneshot::channel::<()>();
+    let startup_app = startup_router(&base);
+    let startup_server = tokio::spawn(async move {
+        axum::serve(startup_listener, startup_app)
+            .with_graceful_shutdown(async {
+                let _ = loaded_rx.await;
+            })
+            .await
+    });
+    eprintln!("[HTTP Server] Loading graph, readiness at http://{}{}/ready", addr, base);
 
-    // Create AppState for WebSocket/REST using the same KB
-    let state = Arc::new(AppState::new(Arc::clone(&kb)));
+    // Create SINGLE knowledge base - shared by both SSE/MCP and REST/WebSocket
+    let kb = Arc::new(
+        tokio::task::spawn_blocking(load_knowledge_base)
+            .await
+            .map_err(|e| format!("Failed to load knowledge base: {}", e))?,
+    );
+    let _ = loaded_tx.send(());
+    let _ = startup_server.await;
+
+    // One persisted counter numbers every WebSocket/SSE message, so sequence
+    // ids keep increasing across restarts
+    let sequence = Arc::new(SequenceCounter::for_knowledge_base(&kb));
+
+    // Initialize global broadcaster for WebSocket events; its channel buffers
+    // MEMORY_WS_CLIENT_BUFFER messages per client
+    let backpressure = BackpressureConfig::from_env();
+    let broadcaster =
+        init_broadcaster_with_sequence(backpressure.client_buffer, Arc::clone(&sequence));
+
+    // Pick up events appended by other processes (MEMORY_EVENT_TAIL=true)
+    KnowledgeBase::spawn_event_tail(&kb);
+
+    // Write snapshots off the request path
+    KnowledgeBase::spawn_snapshot_worker(&kb);
+
+    // Create AppState for WebSocket/REST using the same KB, streaming the
+    // broadcaster's channel so lagging clients can resume from its history
+    let state = Arc::new(AppState::with_sender(
+        Arc::clone(&kb),
+        sequence,
+        broadcaster.sender(),
+        backpressure.policy,
+    ));
 
     // Initialize JWT authentication if configured
     let (jwt_auth, require_auth) = match JwtAuth::from_env() {
@@ -220,16 +347,16 @@ async fn run_http_server() -> McpResult<()> {
     // Create router with JWT auth - both SSE and REST/WS use the same kb
     let app = create_router_with_auth(state, Arc::clone(&kb), jwt_auth, require_auth);
 
-    // Bind to port 3030
-    let addr = std::net::SocketAddr::from(([0, 0, 0, 0], 3030));
-    eprintln!("[HTTP Server] Listening on http://{}", addr);
-    eprintln!("[HTTP Server] WebSocket endpoint: ws://{}/ws", addr);
-    eprintln!("[HTTP Server] MCP SSE endpoint: http://{}/mcp/sse", addr);
-    eprintln!("[HTTP Server] Auth endpoints: POST /auth/token, POST /auth/refresh, GET /auth/me");
-    eprintln!("[HTTP Server] Health check: http://{}/health", addr);
+    eprintln!("[HTTP Server] Listening on http://{}{}", addr, base);
+    eprintln!("[HTTP Server] WebSocket endpoint: ws://{}{}/ws", addr, base);
+    eprintln!("[HTTP Server] MCP SSE endpoint: http://{}{}/mcp/sse", addr, base);
+    eprintln!(
+        "[HTTP Server] Auth endpoints: POST {0}/auth/token, POST {0}/auth/refresh, GET {0}/auth/me",
+        base
+    );
+    eprintln!("[HTTP Server] Health check: http://{}{}/health", addr, base);
 
-    let listener = tokio::net::TcpListener::bind(addr).await
-        .map_err(|e| format!("Failed to bind to {}: {}", addr, e))?;
+    let listener = tokio::net::TcpListener::from_std(std_listener)?;
 
     axum::serve(listener, app).await
         .map_err(|e| format!("Server error: {}", e))?;
@@ -3,6 +3,8 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::types::InvalidParams;
+
 /// JSON-RPC 2.0 Request
 #[derive(Deserialize, Debug, Clone)]
 pub struct JsonRpcRequest {
@@ -22,6 +24,14 @@ impl JsonRpcRequest {
     pub fn is_notification(&self) -> bool {
         self.id.is_none()
     }
+
+    /// Check that the id is a string, a number or null, as JSON-RPC 2.0 requires
+    pub fn has_valid_id(&self) -> bool {
+        matches!(
+            self.id,
+            None | Some(Value::Null | Value::String(_) | Value::Number(_))
+        )
+    }
 }
 
 /// JSON-RPC 2.0 Success Response
@@ -134,4 +144,21 @@ impl ErrorObject {
             data,
         }
     }
+
+    /// Error object for a failed tool call
+    ///
+    /// [`InvalidParams`] errors become `-32602 Invalid params`, anything else
+    /// `-32603 Tool execution error`.
+    pub fn tool_error(error: &(dyn std::error::Error + 'static)) -> Self {
+        let (code, message) = if error.is::<InvalidParams>() {
+            (-32602, "Invalid params")
+        } else {
+            (-32603, "Tool execution error")
+        };
+        Self::new(
+            code,
+            message.to_string(),
+            Some(serde_json::json!({"details": error.to_string()})),
+        )
+    }
 }
@@ -25,6 +25,53 @@ impl McpTool {
     }
 }
 
+/// Whether a tool only reads the graph or may change it
+///
+/// Serialized as `"read"` / `"write"`, which are also the JWT permissions a
+/// caller needs to use the tool.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolAccess {
+    Read,
+    Write,
+}
+
+impl ToolAccess {
+    /// Permission required to call a tool with this access
+    pub fn permission(&self) -> &'static str {
+        match self {
+            ToolAccess::Read => "read",
+            ToolAccess::Write => "write",
+        }
+    }
+}
+
+/// Tool definition enriched with its access classification
+#[derive(Serialize, Debug, Clone)]
+pub struct ToolDescriptor {
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "inputSchema")]
+    pub input_schema: Value,
+    pub access: ToolAccess,
+    pub permission: &'static str,
+}
+
+impl ToolDescriptor {
+    /// Describe a tool from its definition and access
+    pub fn of(tool: &dyn Tool) -> Self {
+        let definition = tool.definition();
+        let access = tool.access();
+        Self {
+            name: definition.name,
+            description: definition.description,
+            input_schema: definition.input_schema,
+            access,
+            permission: access.permission(),
+        }
+    }
+}
+
 /// Server information for MCP handshake
 #[derive(Clone, Debug)]
 pub struct ServerInfo {
@@ -58,6 +105,14 @@ pub trait Tool: Send + Sync {
     /// Execute the tool with the given parameters
     fn execute(&self, params: Value) -> McpResult<Value>;
 
+    /// Whether the tool reads or writes the graph
+    ///
+    /// Defaults to `Write`, so a tool is only advertised as read-only when it
+    /// says so.
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Write
+    }
+
     /// Get the tool name (convenience method)
     fn name(&self) -> String {
         self.definition().name
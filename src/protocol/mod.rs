@@ -6,4 +6,4 @@ mod jsonrpc;
 mod mcp;
 
 pub use jsonrpc::{ErrorObject, JsonRpcError, JsonRpcRequest, JsonRpcResponse};
-pub use mcp::{McpTool, ServerInfo, Tool};
+pub use mcp::{McpTool, ServerInfo, Tool, ToolAccess, ToolDescriptor};
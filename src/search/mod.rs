@@ -1,7 +1,13 @@
 //! Semantic search with synonym matching
 //!
-//! This module provides semantic search capabilities through synonym expansion.
+//! This module provides semantic search capabilities through synonym expansion
+//! and relevance scoring.
 
+mod score;
 mod synonyms;
 
-pub use synonyms::{get_synonyms, matches_with_synonyms, SYNONYM_GROUPS};
+pub use score::relevance_score;
+pub use synonyms::{
+    get_synonyms, get_synonyms_with, matches_with_synonyms, synonym_match, SynonymMatch,
+    SYNONYM_GROUPS,
+};
@@ -0,0 +1,129 @@
+//! Relevance scoring for search results
+
+use crate::types::{Entity, SearchField, SearchOptions};
+
+use super::matches_with_synonyms;
+
+/// Weight for a match in the entity name
+const NAME_WEIGHT: f64 = 3.0;
+/// Extra weight when the name equals the query exactly
+const EXACT_NAME_BONUS: f64 = 2.0;
+/// Weight for a match in the entity type
+const TYPE_WEIGHT: f64 = 1.5;
+/// Weight per matching observation
+const OBSERVATION_WEIGHT: f64 = 1.0;
+/// Matches through a synonym only count for this fraction of the weight
+const SYNONYM_FACTOR: f64 = 0.5;
+
+/// Score how well an entity matches a query
+///
+/// `search_terms` is the synonym expansion of `query`. Returns `None` when no
+/// searched field matches.
+pub fn relevance_score(
+    entity: &Entity,
+    query: &str,
+    search_terms: &[String],
+    options: &SearchOptions,
+) -> Option<f64> {
+    let query_lower = query.to_lowercase();
+
+    let field_score = |text: &str, weight: f64| -> f64 {
+        if text.to_lowercase().contains(&query_lower) {
+            weight
+        } else if matches_with_synonyms(text, search_terms) {
+            weight * SYNONYM_FACTOR
+        } else {
+            0.0
+        }
+    };
+
+    let mut score = 0.0;
+
+    if options.searches(SearchField::Name) {
+        score += field_score(&entity.name, NAME_WEIGHT);
+        if entity.name.to_lowercase() == query_lower {
+            score += EXACT_NAME_BONUS;
+        }
+    }
+
+    if options.searches(SearchField::EntityType) {
+        score += field_score(&entity.entity_type, TYPE_WEIGHT);
+    }
+
+    if options.searches(SearchField::Observations) {
+        score += entity
+            .observations
+            .iter()
+            .map(|o| field_score(o, OBSERVATION_WEIGHT))
+            .sum::<f64>();
+    }
+
+    if score > 0.0 {
+        Some(score)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::get_synonyms;
+
+    fn entity(name: &str, entity_type: &str, observations: &[&str]) -> Entity {
+        Entity::with_observations(
+            name.to_string(),
+            entity_type.to_string(),
+            observations.iter().map(|s| s.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_exact_name_ranks_above_observation() {
+        let terms = get_synonyms("auth");
+        let options = SearchOptions::default();
+
+        let by_name = relevance_score(&entity("auth", "Module", &[]), "auth", &terms, &options);
+        let by_obs = relevance_score(
+            &entity("Login", "Feature", &["Uses auth"]),
+            "auth",
+            &terms,
+            &options,
+        );
+
+        assert!(by_name.unwrap() > by_obs.unwrap());
+    }
+
+    #[test]
+    fn test_synonym_scores_lower_than_literal() {
+        let terms = get_synonyms("developer");
+        let options = SearchOptions::default();
+
+        let literal = relevance_score(
+            &entity("A", "Person", &["developer"]),
+            "developer",
+            &terms,
+            &options,
+        );
+        let synonym = relevance_score(
+            &entity("B", "Person", &["coder"]),
+            "developer",
+            &terms,
+            &options,
+        );
+
+        assert!(literal.unwrap() > synonym.unwrap());
+    }
+
+    #[test]
+    fn test_field_filter() {
+        let terms = get_synonyms("engineer");
+        let options = SearchOptions {
+            fields: Some(vec![SearchField::Name]),
+            ..Default::default()
+        };
+
+        let e = entity("Alice", "Person", &["Software Engineer"]);
+        assert!(relevance_score(&e, "engineer", &terms, &options).is_none());
+    }
+}
@@ -1,4 +1,19 @@
 //! Synonym dictionary for semantic search
+//!
+//! A query expands to every group that one of its words belongs to. How a
+//! group word is found in the query is set by `MEMORY_SYNONYM_MATCH`:
+//!
+//! - `token` (default): the group word, or phrase, appears in the query as
+//!   whole tokens, so "db" expands to the database group and "dev" only to
+//!   the developer group, not to "devops".
+//! - `substring`: the group word appears anywhere in the query text, so
+//!   "redisson" still pulls in the cache group through "redis".
+//!
+//! Both are case-insensitive. A query never matches a longer group word it
+//! is merely part of.
+
+use std::env;
+use std::sync::OnceLock;
 
 /// Synonym groups - words in same group are considered semantically similar
 pub const SYNONYM_GROUPS: &[&[&str]] = &[
@@ -52,15 +67,55 @@ pub const SYNONYM_GROUPS: &[&[&str]] = &[
     &["cache", "caching", "redis", "memcached"],
 ];
 
+/// How group words are found in a query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SynonymMatch {
+    /// The word appears as whole tokens of the query
+    #[default]
+    Token,
+    /// The word appears anywhere in the query
+    Substring,
+}
+
+impl SynonymMatch {
+    /// Parse a `MEMORY_SYNONYM_MATCH` value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "token" => Some(SynonymMatch::Token),
+            "substring" => Some(SynonymMatch::Substring),
+            _ => None,
+        }
+    }
+}
+
+/// Matching mode from `MEMORY_SYNONYM_MATCH`, read once
+pub fn synonym_match() -> SynonymMatch {
+    static MODE: OnceLock<SynonymMatch> = OnceLock::new();
+    *MODE.get_or_init(|| {
+        env::var("MEMORY_SYNONYM_MATCH")
+            .ok()
+            .and_then(|v| SynonymMatch::parse(&v))
+            .unwrap_or_default()
+    })
+}
+
 /// Get all synonyms for a query term
 pub fn get_synonyms(query: &str) -> Vec<String> {
+    get_synonyms_with(query, synonym_match())
+}
+
+/// Get all synonyms for a query term, finding group words with `mode`
+pub fn get_synonyms_with(query: &str, mode: SynonymMatch) -> Vec<String> {
     let query_lower = query.to_lowercase();
+    let query_tokens = tokens(&query_lower);
     let mut synonyms = vec![query_lower.clone()];
 
     for group in SYNONYM_GROUPS {
-        if group.iter().any(|&word| {
-            word == query_lower || query_lower.contains(word) || word.contains(&query_lower)
-        }) {
+        let matched = group.iter().any(|&word| match mode {
+            SynonymMatch::Token => contains_tokens(&query_tokens, &tokens(word)),
+            SynonymMatch::Substring => query_lower.contains(word),
+        });
+        if matched {
             for &word in *group {
                 if !synonyms.contains(&word.to_string()) {
                     synonyms.push(word.to_string());
@@ -72,6 +127,18 @@ pub fn get_synonyms(query: &str) -> Vec<String> {
     synonyms
 }
 
+/// Words of `text`, split at anything but letters, digits and hyphens
+fn tokens(text: &str) -> Vec<&str> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '-'))
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Whether `phrase` occurs as consecutive tokens of `tokens`
+fn contains_tokens(tokens: &[&str], phrase: &[&str]) -> bool {
+    !phrase.is_empty() && tokens.windows(phrase.len()).any(|window| window == phrase)
+}
+
 /// Check if text matches any of the search terms (including synonyms)
 pub fn matches_with_synonyms(text: &str, search_terms: &[String]) -> bool {
     let text_lower = text.to_lowercase();
@@ -105,4 +172,43 @@ mod tests {
         assert!(matches_with_synonyms("Software Engineer position", &terms));
         assert!(!matches_with_synonyms("I am a doctor", &terms));
     }
+
+    #[test]
+    fn test_token_match_expands_whole_words_only() {
+        let expands = |query: &str, word: &str| {
+            get_synonyms_with(query, SynonymMatch::Token).contains(&word.to_string())
+        };
+        assert!(expands("db", "database"));
+        assert!(expands("DB", "storage"));
+        assert!(expands("senior software engineer", "programmer"));
+        assert!(expands("on hold", "blocked"));
+        assert!(expands("front-end work", "frontend"));
+
+        // Part of a longer group word, or a group word inside a longer token
+        assert!(!expands("dev", "devops"));
+        assert!(!expands("test", "unittest"));
+        assert!(!expands("redisson", "cache"));
+        assert!(!expands("hold", "blocked"));
+        assert_eq!(get_synonyms_with("a", SynonymMatch::Token), vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn test_substring_match_looks_inside_the_query_only() {
+        let expands = |query: &str, word: &str| {
+            get_synonyms_with(query, SynonymMatch::Substring).contains(&word.to_string())
+        };
+        assert!(expands("redisson", "cache"));
+        assert!(expands("developers", "coder"));
+
+        // A short query no longer pulls in every group word containing it
+        assert!(!expands("dev", "devops"));
+        assert_eq!(get_synonyms_with("a", SynonymMatch::Substring), vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_synonym_match() {
+        assert_eq!(SynonymMatch::parse(" Substring "), Some(SynonymMatch::Substring));
+        assert_eq!(SynonymMatch::parse("token"), Some(SynonymMatch::Token));
+        assert_eq!(SynonymMatch::parse("fuzzy"), None);
+    }
 }
@@ -4,7 +4,14 @@
 //! Most handlers are implemented directly in McpServer, but this module
 //! can be extended for custom handlers.
 
-use serde_json::Value;
+use std::collections::{HashSet, VecDeque};
+use std::env;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{ErrorObject, Tool, ToolDescriptor};
+use crate::tools::{execute_tool, track};
 
 /// Extract tool arguments from params
 pub fn extract_arguments(params: &Value) -> Value {
@@ -36,3 +43,362 @@ pub fn error_response(message: String) -> Value {
         "isError": true
     })
 }
+
+/// Version of the [`tool_catalog`] layout, bumped on incompatible changes
+pub const TOOL_CATALOG_VERSION: u32 = 1;
+
+/// Build the enriched tool catalog (`server/capabilities`, `GET /api/tools`)
+///
+/// ```json
+/// {
+///   "catalogVersion": 1,
+///   "tools": [
+///     {
+///       "name": "read_graph",
+///       "description": "...",
+///       "inputSchema": { ... },
+///       "access": "read",
+///       "permission": "read"
+///     }
+///   ]
+/// }
+/// ```
+///
+/// `access` is `read` for tools that never change the graph and `write`
+/// otherwise; `permission` is the JWT permission needed to call the tool.
+/// Tools are sorted by name so the output is stable.
+pub fn tool_catalog<'a>(tools: impl IntoIterator<Item = &'a dyn Tool>) -> Value {
+    let mut descriptors: Vec<ToolDescriptor> = tools.into_iter().map(ToolDescriptor::of).collect();
+    descriptors.sort_by(|a, b| a.name.cmp(&b.name));
+
+    json!({
+        "catalogVersion": TOOL_CATALOG_VERSION,
+        "tools": descriptors
+    })
+}
+
+/// JSON-RPC error code for an unknown `resources/read` URI
+pub const RESOURCE_NOT_FOUND: i32 = -32002;
+
+/// Build the `resources/list` result: the stored large tool results
+///
+/// See [`KnowledgeBase::link_result`] for when results are stored.
+pub fn resources_list(kb: &KnowledgeBase) -> Value {
+    json!({ "resources": kb.list_resources() })
+}
+
+/// Build the `resources/read` result for `{"uri": "memory://results/<id>"}`
+///
+/// ```json
+/// { "contents": [{ "uri": "...", "mimeType": "application/json", "text": "..." }] }
+/// ```
+///
+/// Fails with [`RESOURCE_NOT_FOUND`] once the result is no longer kept, or
+/// if another caller stored it.
+pub fn resources_read(kb: &KnowledgeBase, params: &Value) -> Result<Value, ErrorObject> {
+    let uri = params.get("uri").and_then(|v| v.as_str()).ok_or_else(|| {
+        ErrorObject::new(
+            -32602,
+            "Invalid params".to_string(),
+            Some(json!({"details": "Missing uri"})),
+        )
+    })?;
+    let (resource, text) = kb.read_resource(uri).ok_or_else(|| {
+        ErrorObject::new(
+            RESOURCE_NOT_FOUND,
+            "Resource not found".to_string(),
+            Some(json!({"uri": uri})),
+        )
+    })?;
+    Ok(json!({
+        "contents": [{
+            "uri": resource.uri,
+            "mimeType": resource.mime_type,
+            "text": text
+        }]
+    }))
+}
+
+/// Execute an ordered list of tool calls (`tools/call_batch`)
+///
+/// Params: `{"calls": [{"name", "arguments"}, ...], "continueOnError": false}`.
+///
+/// Calls run sequentially in the given order, so a later call can rely on the
+/// effects of an earlier one (e.g. `create_entities` then `create_relations`).
+/// The batch is **not atomic**: each call commits on its own, nothing is rolled
+/// back when a later call fails, and other clients may write between calls.
+/// By default execution stops at the first failure; with `continueOnError`
+/// the remaining calls still run.
+///
+/// Returns an error message only when the batch itself is malformed.
+pub fn execute_batch<'a, F>(params: &Value, lookup: F) -> Result<Value, String>
+where
+    F: Fn(&str) -> Option<&'a dyn Tool>,
+{
+    let calls = params
+        .get("calls")
+        .and_then(|v| v.as_array())
+        .ok_or("Missing calls array")?;
+    let continue_on_error = params
+        .get("continueOnError")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let mut results = Vec::with_capacity(calls.len());
+    let mut failed = 0;
+
+    for (index, call) in calls.iter().enumerate() {
+        let name = extract_tool_name(call).unwrap_or_default();
+
+        let outcome = match lookup(name) {
+            Some(tool) => track(name, || execute_tool(tool, extract_arguments(call)))
+                .map_err(|e| ErrorObject::tool_error(e.as_ref())),
+            None => Err(ErrorObject::new(
+                -32602,
+                "Unknown tool".to_string(),
+                Some(json!({"tool": name})),
+            )),
+        };
+
+        match outcome {
+            Ok(result) => results.push(json!({
+                "index": index,
+                "name": name,
+                "ok": true,
+                "result": result
+            })),
+            Err(error) => {
+                failed += 1;
+                results.push(json!({
+                    "index": index,
+                    "name": name,
+                    "ok": false,
+                    "error": error
+                }));
+                if !continue_on_error {
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok(json!({
+        "results": results,
+        "executed": results.len(),
+        "failed": failed,
+        "skipped": calls.len() - results.len()
+    }))
+}
+
+/// What to do when a client reuses a request id within a session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateIdPolicy {
+    /// Accept the request silently
+    Allow,
+    /// Accept the request and log a warning (default)
+    Warn,
+    /// Refuse the request with `-32600 Invalid Request`
+    Reject,
+}
+
+impl DuplicateIdPolicy {
+    /// Read the policy from `MEMORY_DUPLICATE_IDS` (`allow`, `warn` or `reject`)
+    pub fn from_env() -> Self {
+        match env::var("MEMORY_DUPLICATE_IDS").as_deref() {
+            Ok("allow") | Ok("off") => Self::Allow,
+            Ok("reject") | Ok("error") => Self::Reject,
+            _ => Self::Warn,
+        }
+    }
+}
+
+/// Recently seen request ids of one stdio session
+///
+/// Only the last `window` non-null ids are remembered, so a long session
+/// keeps bounded memory and ids may be reused once they fall out of it.
+/// `1` and `"1"` are different ids.
+#[derive(Debug)]
+pub struct RequestIdTracker {
+    policy: DuplicateIdPolicy,
+    window: usize,
+    seen: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl RequestIdTracker {
+    /// Number of ids remembered by default
+    pub const DEFAULT_WINDOW: usize = 1024;
+
+    /// Create a tracker remembering the last `window` ids
+    pub fn new(policy: DuplicateIdPolicy, window: usize) -> Self {
+        Self {
+            policy,
+            window,
+            seen: HashSet::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Create a tracker with the policy from `MEMORY_DUPLICATE_IDS`
+    pub fn from_env() -> Self {
+        Self::new(DuplicateIdPolicy::from_env(), Self::DEFAULT_WINDOW)
+    }
+
+    /// Record `id`, returning an error message if the request must be refused
+    pub fn check(&mut self, id: &Value) -> Result<(), String> {
+        if id.is_null() || self.policy == DuplicateIdPolicy::Allow || self.window == 0 {
+            return Ok(());
+        }
+
+        let key = id.to_string();
+        if self.seen.contains(&key) {
+            return match self.policy {
+                DuplicateIdPolicy::Reject => Err(format!(
+                    "Duplicate request id {} (MEMORY_DUPLICATE_IDS=reject)",
+                    key
+                )),
+                _ => {
+                    eprintln!("[Server] Warning: duplicate request id {}", key);
+                    Ok(())
+                }
+            };
+        }
+
+        if self.order.len() == self.window {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        self.seen.insert(key.clone());
+        self.order.push_back(key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::McpTool;
+    use crate::types::{InvalidParams, McpResult};
+
+    struct EchoTool;
+
+    impl Tool for EchoTool {
+        fn definition(&self) -> McpTool {
+            McpTool::new("echo".to_string(), String::new(), json!({}))
+        }
+
+        fn execute(&self, params: Value) -> McpResult<Value> {
+            if params.get("fail").is_some() {
+                return Err("boom".into());
+            }
+            if params.get("invalid").is_some() {
+                return Err(InvalidParams("bad argument".to_string()).into());
+            }
+            Ok(params)
+        }
+    }
+
+    fn lookup(name: &str) -> Option<&'static dyn Tool> {
+        (name == "echo").then_some(&EchoTool as &dyn Tool)
+    }
+
+    #[test]
+    fn test_batch_runs_in_order() {
+        let params = json!({
+            "calls": [
+                {"name": "echo", "arguments": {"n": 1}},
+                {"name": "echo", "arguments": {"n": 2}}
+            ]
+        });
+
+        let result = execute_batch(&params, lookup).unwrap();
+        assert_eq!(result["executed"], 2);
+        assert_eq!(result["failed"], 0);
+        assert_eq!(result["results"][1]["result"]["n"], 2);
+    }
+
+    #[test]
+    fn test_batch_stops_on_error() {
+        let params = json!({
+            "calls": [
+                {"name": "echo", "arguments": {"fail": true}},
+                {"name": "echo", "arguments": {}}
+            ]
+        });
+
+        let result = execute_batch(&params, lookup).unwrap();
+        assert_eq!(result["executed"], 1);
+        assert_eq!(result["skipped"], 1);
+        assert_eq!(result["results"][0]["ok"], false);
+    }
+
+    #[test]
+    fn test_batch_continue_on_error() {
+        let params = json!({
+            "calls": [
+                {"name": "missing"},
+                {"name": "echo", "arguments": {}}
+            ],
+            "continueOnError": true
+        });
+
+        let result = execute_batch(&params, lookup).unwrap();
+        assert_eq!(result["executed"], 2);
+        assert_eq!(result["failed"], 1);
+        assert_eq!(result["results"][0]["error"]["code"], -32602);
+        assert_eq!(result["results"][1]["ok"], true);
+    }
+
+    #[test]
+    fn test_batch_error_codes() {
+        let params = json!({
+            "calls": [
+                {"name": "echo", "arguments": {"invalid": true}},
+                {"name": "echo", "arguments": {"fail": true}}
+            ],
+            "continueOnError": true
+        });
+
+        // Argument errors are invalid params, other failures execution errors
+        let result = execute_batch(&params, lookup).unwrap();
+        assert_eq!(result["results"][0]["error"]["code"], -32602);
+        assert_eq!(result["results"][0]["error"]["data"]["details"], "bad argument");
+        assert_eq!(result["results"][1]["error"]["code"], -32603);
+    }
+
+    #[test]
+    fn test_batch_requires_calls() {
+        assert!(execute_batch(&json!({}), lookup).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_ids_within_window() {
+        let mut ids = RequestIdTracker::new(DuplicateIdPolicy::Reject, 2);
+        assert!(ids.check(&json!(1)).is_ok());
+        assert!(ids.check(&json!("1")).is_ok());
+        assert!(ids.check(&json!(1)).is_err());
+        assert!(ids.check(&Value::Null).is_ok());
+        assert!(ids.check(&Value::Null).is_ok());
+
+        // 1 falls out of the window once two newer ids are seen
+        assert!(ids.check(&json!(2)).is_ok());
+        assert!(ids.check(&json!(1)).is_ok());
+        assert!(ids.check(&json!(2)).is_err());
+
+        // Warn (the default) logs but accepts
+        let mut ids = RequestIdTracker::new(DuplicateIdPolicy::Warn, 8);
+        assert!(ids.check(&json!(7)).is_ok());
+        assert!(ids.check(&json!(7)).is_ok());
+    }
+
+    #[test]
+    fn test_tool_catalog_classifies_access() {
+        let catalog = tool_catalog([&EchoTool as &dyn Tool]);
+        assert_eq!(catalog["catalogVersion"], TOOL_CATALOG_VERSION);
+        assert_eq!(catalog["tools"][0]["name"], "echo");
+        // Tools are write unless they declare otherwise
+        assert_eq!(catalog["tools"][0]["access"], "write");
+        assert_eq!(catalog["tools"][0]["permission"], "write");
+    }
+}
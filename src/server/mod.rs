@@ -6,12 +6,15 @@ mod handlers;
 
 use std::collections::HashMap;
 use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::sync::Arc;
 
 use serde_json::{json, Value};
 
+use crate::knowledge_base::KnowledgeBase;
 use crate::protocol::{
-    JsonRpcError, JsonRpcRequest, JsonRpcResponse, McpTool, ServerInfo, Tool,
+    ErrorObject, JsonRpcError, JsonRpcRequest, JsonRpcResponse, McpTool, ServerInfo, Tool,
 };
+use crate::tools::{execute_tool, track};
 use crate::types::McpResult;
 
 pub use handlers::*;
@@ -22,6 +25,9 @@ pub struct McpServer {
     tools: HashMap<String, Box<dyn Tool>>,
     reader: BufReader<io::Stdin>,
     writer: BufWriter<io::Stdout>,
+    request_ids: RequestIdTracker,
+    /// Knowledge base whose stored tool results are served as resources
+    resources: Option<Arc<KnowledgeBase>>,
 }
 
 impl McpServer {
@@ -32,6 +38,8 @@ impl McpServer {
             tools: HashMap::new(),
             reader: BufReader::new(io::stdin()),
             writer: BufWriter::new(io::stdout()),
+            request_ids: RequestIdTracker::from_env(),
+            resources: None,
         }
     }
 
@@ -42,6 +50,8 @@ impl McpServer {
             tools: HashMap::new(),
             reader: BufReader::new(io::stdin()),
             writer: BufWriter::new(io::stdout()),
+            request_ids: RequestIdTracker::from_env(),
+            resources: None,
         }
     }
 
@@ -52,6 +62,12 @@ impl McpServer {
         self
     }
 
+    /// Serve the large tool results stored by `kb` via `resources/list` / `resources/read`
+    pub fn serve_resources(&mut self, kb: Arc<KnowledgeBase>) -> &mut Self {
+        self.resources = Some(kb);
+        self
+    }
+
     /// Get the number of registered tools
     pub fn tool_count(&self) -> usize {
         self.tools.len()
@@ -85,6 +101,16 @@ impl McpServer {
             }
         };
 
+        if !request.has_valid_id() {
+            self.send_error_response(
+                Value::Null,
+                -32600,
+                "Invalid Request",
+                Some(json!({"details": "id must be a string, a number or null"})),
+            )?;
+            return Ok(());
+        }
+
         if request.jsonrpc != "2.0" {
             self.send_error_response(
                 request.id.unwrap_or(Value::Null),
@@ -97,11 +123,25 @@ impl McpServer {
 
         let id = request.id.clone().unwrap_or(Value::Null);
 
+        if let Err(details) = self.request_ids.check(&id) {
+            self.send_error_response(
+                id,
+                -32600,
+                "Invalid Request",
+                Some(json!({"details": details})),
+            )?;
+            return Ok(());
+        }
+
         match request.method.as_str() {
             "initialize" => self.handle_initialize(id, request.params),
             "notifications/initialized" => Ok(()), // Notification, no response
             "tools/list" => self.handle_tools_list(id),
             "tools/call" => self.handle_tool_call(id, request.params),
+            "tools/call_batch" => self.handle_tool_call_batch(id, request.params),
+            "server/capabilities" => self.handle_capabilities(id),
+            "resources/list" => self.handle_resources_list(id),
+            "resources/read" => self.handle_resources_read(id, request.params),
             "ping" => self.send_success_response(id, json!({})),
             _ => self.send_error_response(
                 id,
@@ -114,7 +154,7 @@ impl McpServer {
 
     /// Handle initialize request
     fn handle_initialize(&mut self, id: Value, _params: Option<Value>) -> McpResult<()> {
-        let result = json!({
+        let mut result = json!({
             "protocolVersion": "2024-11-05",
             "capabilities": {
                 "tools": {}
@@ -124,6 +164,9 @@ impl McpServer {
                 "version": self.server_info.version
             }
         });
+        if self.resources.is_some() {
+            result["capabilities"]["resources"] = json!({});
+        }
         self.send_success_response(id, result)
     }
 
@@ -134,6 +177,38 @@ impl McpServer {
         self.send_success_response(id, result)
     }
 
+    /// Handle server/capabilities request (tool catalog with access classes)
+    fn handle_capabilities(&mut self, id: Value) -> McpResult<()> {
+        let result = tool_catalog(self.tools.values().map(|t| t.as_ref()));
+        self.send_success_response(id, result)
+    }
+
+    /// Handle resources/list request
+    fn handle_resources_list(&mut self, id: Value) -> McpResult<()> {
+        let result = match self.resources.as_deref() {
+            Some(kb) => resources_list(kb),
+            None => json!({ "resources": [] }),
+        };
+        self.send_success_response(id, result)
+    }
+
+    /// Handle resources/read request
+    fn handle_resources_read(&mut self, id: Value, params: Option<Value>) -> McpResult<()> {
+        let params = params.unwrap_or(json!({}));
+        let result = match self.resources.as_deref() {
+            Some(kb) => resources_read(kb, &params),
+            None => Err(ErrorObject::new(
+                RESOURCE_NOT_FOUND,
+                "Resource not found".to_string(),
+                params.get("uri").map(|uri| json!({"uri": uri})),
+            )),
+        };
+        match result {
+            Ok(result) => self.send_success_response(id, result),
+            Err(e) => self.send_error_response(id, e.code, &e.message, e.data),
+        }
+    }
+
     /// Handle tools/call request
     fn handle_tool_call(&mut self, id: Value, params: Option<Value>) -> McpResult<()> {
         let params = params.ok_or("Missing parameters")?;
@@ -157,13 +232,27 @@ impl McpServer {
 
         let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
 
-        match tool.execute(arguments) {
+        match track(tool_name, || execute_tool(tool.as_ref(), arguments)) {
+            Ok(result) => self.send_success_response(id, result),
+            Err(e) => {
+                let error = ErrorObject::tool_error(e.as_ref());
+                self.send_error_response(id, error.code, &error.message, error.data)
+            }
+        }
+    }
+
+    /// Handle tools/call_batch request (sequential, non-atomic)
+    fn handle_tool_call_batch(&mut self, id: Value, params: Option<Value>) -> McpResult<()> {
+        let params = params.unwrap_or(json!({}));
+        let tools = &self.tools;
+
+        match execute_batch(&params, |name| tools.get(name).map(|t| t.as_ref())) {
             Ok(result) => self.send_success_response(id, result),
             Err(e) => self.send_error_response(
                 id,
-                -32603,
-                "Tool execution error",
-                Some(json!({"details": e.to_string()})),
+                -32602,
+                "Invalid params",
+                Some(json!({"details": e})),
             ),
         }
     }
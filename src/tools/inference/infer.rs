@@ -6,9 +6,9 @@ use std::sync::Arc;
 
 use serde_json::{json, Value};
 
-use crate::knowledge_base::inference::InferenceEngine;
 use crate::knowledge_base::KnowledgeBase;
-use crate::protocol::{McpTool, Tool};
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
 use crate::types::{InferResult, McpResult};
 
 /// Tool for inferring hidden relations from the knowledge graph
@@ -50,6 +50,11 @@ impl Tool for InferTool {
                         "maximum": 5,
                         "default": 3,
                         "description": "Maximum traversal depth (1-5). Higher values find more distant relations but take longer."
+                    },
+                    "rules": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Names of the rules to apply (see list_inference_rules). Default: all rules."
                     }
                 },
                 "required": ["entityName"]
@@ -57,6 +62,10 @@ impl Tool for InferTool {
         }
     }
 
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
     fn execute(&self, params: Value) -> McpResult<Value> {
         let entity_name = params
             .get("entityName")
@@ -77,14 +86,14 @@ impl Tool for InferTool {
             .unwrap_or(3)
             .clamp(1, 5);
 
-        // Get full graph for inference (no pagination)
-        let graph = self.kb.read_graph(None, None)?;
-
-        // Create inference engine with specified depth
-        let engine = InferenceEngine::with_max_depth(max_depth);
+        let rules: Option<Vec<String>> = params
+            .get("rules")
+            .map(|rules| serde_json::from_value(rules.clone()))
+            .transpose()?;
 
-        // Run inference
-        let (inferred_relations, stats) = engine.infer(&graph, entity_name, min_confidence);
+        // Run inference (reused while the graph is unchanged)
+        let (inferred_relations, stats) =
+            self.kb.infer(entity_name, min_confidence, max_depth, rules.as_deref())?;
 
         // Build result
         let result = InferResult {
@@ -112,7 +121,7 @@ impl Tool for InferTool {
             json!({
                 "content": [{
                     "type": "text",
-                    "text": serde_json::to_string_pretty(&result)?
+                    "text": to_text(&result)?
                 }]
             })
         };
@@ -0,0 +1,58 @@
+//! List inference rules tool
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::inference::InferenceEngine;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for listing the rules the `infer` tool can apply
+pub struct ListInferenceRulesTool;
+
+impl ListInferenceRulesTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ListInferenceRulesTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tool for ListInferenceRulesTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "list_inference_rules".to_string(),
+            description: "List the inference rules available to the infer tool, with descriptions and whether each is enabled by default. Pass rule names to infer's 'rules' parameter to apply only those.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {},
+                "required": []
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, _params: Value) -> McpResult<Value> {
+        let engine = InferenceEngine::new();
+        let rules: Vec<Value> = engine
+            .rules()
+            .map(|(name, description, enabled)| {
+                json!({ "name": name, "description": description, "enabled": enabled })
+            })
+            .collect();
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&json!({ "rules": rules }))?
+            }]
+        }))
+    }
+}
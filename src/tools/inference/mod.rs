@@ -3,5 +3,7 @@
 //! This module contains tools for runtime inference using the knowledge graph.
 
 mod infer;
+mod list_rules;
 
 pub use infer::InferTool;
+pub use list_rules::ListInferenceRulesTool;
@@ -1,13 +1,17 @@
 //! Add observations tool
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use serde_json::{json, Value};
 
 use crate::knowledge_base::KnowledgeBase;
 use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
 use crate::types::{McpResult, Observation};
 
+use super::response_with_warnings;
+
 /// Tool for adding new observations to existing entities
 pub struct AddObservationsTool {
     kb: Arc<KnowledgeBase>,
@@ -52,12 +56,16 @@ impl Tool for AddObservationsTool {
     fn execute(&self, params: Value) -> McpResult<Value> {
         let observations: Vec<Observation> =
             serde_json::from_value(params.get("observations").cloned().unwrap_or(json!([])))?;
-        let added = self.kb.add_observations(observations)?;
-        Ok(json!({
-            "content": [{
-                "type": "text",
-                "text": serde_json::to_string_pretty(&added)?
-            }]
-        }))
+        // Several entities: group them and resolve all names in one pass
+        let entities: HashSet<&str> = observations
+            .iter()
+            .map(|o| o.entity_name.as_str())
+            .collect();
+        let (added, warnings) = if entities.len() > 1 {
+            self.kb.add_observations_batch(observations)?
+        } else {
+            self.kb.add_observations_with_warnings(observations)?
+        };
+        response_with_warnings(to_text(&added)?, warnings)
     }
 }
@@ -0,0 +1,67 @@
+//! Bulk create tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use super::response_with_warnings;
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
+use crate::types::{Entity, McpResult, Relation};
+
+/// Tool for creating large batches of entities and relations in chunks
+pub struct BulkCreateTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl BulkCreateTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for BulkCreateTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "bulk_create".to_string(),
+            description: "Create a large batch of entities and relations, committing chunkSize items at a time so other clients are not blocked and committed chunks are kept if a later one fails. Entities are created before relations. Existing entities and duplicate relations are skipped, as with create_entities and create_relations, so a failed import can be re-run. Returns per-chunk progress.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "entities": {
+                        "type": "array",
+                        "items": { "type": "object" },
+                        "description": "Entities to create, as for create_entities"
+                    },
+                    "relations": {
+                        "type": "array",
+                        "items": { "type": "object" },
+                        "description": "Relations to create, as for create_relations"
+                    },
+                    "chunkSize": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "default": 500,
+                        "description": "Entities or relations committed per chunk"
+                    }
+                },
+                "required": []
+            }),
+        }
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let entities: Vec<Entity> =
+            serde_json::from_value(params.get("entities").cloned().unwrap_or(json!([])))?;
+        let relations: Vec<Relation> =
+            serde_json::from_value(params.get("relations").cloned().unwrap_or(json!([])))?;
+        let chunk_size = params
+            .get("chunkSize")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(500) as usize;
+
+        let (report, warnings) = self.kb.bulk_create(entities, relations, chunk_size)?;
+        response_with_warnings(to_text(&report)?, warnings)
+    }
+}
@@ -6,8 +6,11 @@ use serde_json::{json, Value};
 
 use crate::knowledge_base::KnowledgeBase;
 use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
 use crate::types::{Entity, McpResult};
-use crate::validation::validate_entity_type;
+use crate::validation::{validate_entity_type, ValidationWarning, ValidationWarningKind};
+
+use super::{reject_if_strict, response_with_warnings, strict_schema};
 
 /// Tool for creating multiple new entities in the knowledge graph
 pub struct CreateEntitiesTool {
@@ -41,11 +44,15 @@ impl Tool for CreateEntitiesTool {
                                     "description": "Initial observations about the entity"
                                 },
                                 "createdBy": { "type": "string", "description": "Who created this entity (auto-filled from git/env if not provided)" },
-                                "updatedBy": { "type": "string", "description": "Who last updated this entity (auto-filled from git/env if not provided)" }
+                                "updatedBy": { "type": "string", "description": "Who last updated this entity (auto-filled from git/env if not provided)" },
+                                "id": { "type": "string", "description": "Id usable in place of the name (generated if omitted or already taken)" },
+                                "protected": { "type": "boolean", "description": "Protect the entity from deletion (see protect_entities)" },
+                                "acl": { "type": "array", "items": { "type": "string" }, "description": "Usernames or permissions allowed to see and change the entity over HTTP; requests without a token satisfy none (default: everyone)" }
                             },
                             "required": ["name", "entityType"]
                         }
-                    }
+                    },
+                    "strict": strict_schema()
                 },
                 "required": ["entities"]
             }),
@@ -57,28 +64,76 @@ impl Tool for CreateEntitiesTool {
             serde_json::from_value(params.get("entities").cloned().unwrap_or(json!([])))?;
 
         // Collect warnings for non-standard types
-        let warnings: Vec<String> = entities
+        let mut warnings: Vec<ValidationWarning> = entities
             .iter()
-            .filter_map(|e| validate_entity_type(&e.entity_type))
+            .filter_map(|e| {
+                validate_entity_type(&e.entity_type).map(|message| {
+                    ValidationWarning::new(
+                        ValidationWarningKind::NonStandardEntityType,
+                        e.name.clone(),
+                        message,
+                    )
+                })
+            })
             .collect();
 
-        let created = self.kb.create_entities(entities)?;
+        reject_if_strict(&params, &warnings)?;
+
+        let (created, limit_warnings) = self.kb.create_entities_with_warnings(entities)?;
+        warnings.extend(limit_warnings);
+
+        response_with_warnings(to_text(&created)?, warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::CreateRelationsTool;
+
+    #[test]
+    fn test_strict_rejects_non_standard_types() {
+        let _env = super::super::tests::SUPPRESS_WARNINGS_ENV
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let path = format!("target/test_create_strict_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(
+            path.clone(),
+            "tester".to_string(),
+        ));
+        let tool = CreateEntitiesTool::new(Arc::clone(&kb));
+        let params = |strict: bool| {
+            json!({
+                "entities": [
+                    { "name": "Payments", "entityType": "Module" },
+                    { "name": "Standup", "entityType": "Ritual" }
+                ],
+                "strict": strict
+            })
+        };
+
+        let err = tool.execute(params(true)).unwrap_err().to_string();
+        assert!(err.contains("Standup") && err.contains("Ritual"), "{}", err);
+        assert!(kb.read_graph(None, None).unwrap().entities.is_empty());
+
+        // Lenient calls still create everything and warn
+        let response = tool.execute(params(false)).unwrap();
+        assert_eq!(kb.read_graph(None, None).unwrap().entities.len(), 2);
+        assert!(response["content"][1]["text"]
+            .as_str()
+            .unwrap()
+            .contains("non_standard_entity_type"));
 
-        let response = if warnings.is_empty() {
-            serde_json::to_string_pretty(&created)?
-        } else {
-            format!(
-                "{}\n\n{}",
-                serde_json::to_string_pretty(&created)?,
-                warnings.join("\n")
-            )
+        let relations = CreateRelationsTool::new(Arc::clone(&kb));
+        let relation = |relation_type: &str| {
+            json!({
+                "relations": [{ "from": "Standup", "to": "Payments", "relationType": relation_type }],
+                "strict": true
+            })
         };
+        assert!(relations.execute(relation("discusses")).is_err());
+        assert!(relations.execute(relation("depends_on")).is_ok());
 
-        Ok(json!({
-            "content": [{
-                "type": "text",
-                "text": response
-            }]
-        }))
+        let _ = std::fs::remove_file(&path);
     }
 }
@@ -6,8 +6,11 @@ use serde_json::{json, Value};
 
 use crate::knowledge_base::KnowledgeBase;
 use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
 use crate::types::{McpResult, Relation};
-use crate::validation::validate_relation_type;
+use crate::validation::{validate_relation_type, ValidationWarning, ValidationWarningKind};
+
+use super::{reject_if_strict, response_with_warnings, strict_schema};
 
 /// Tool for creating multiple new relations between entities
 pub struct CreateRelationsTool {
@@ -34,16 +37,18 @@ impl Tool for CreateRelationsTool {
                         "items": {
                             "type": "object",
                             "properties": {
-                                "from": { "type": "string", "description": "The source entity name" },
-                                "to": { "type": "string", "description": "The target entity name" },
+                                "from": { "type": "string", "description": "The source entity name or id" },
+                                "to": { "type": "string", "description": "The target entity name or id" },
                                 "relationType": { "type": "string", "description": "The type of relation" },
                                 "createdBy": { "type": "string", "description": "Who created this relation (auto-filled from git/env if not provided)" },
                                 "validFrom": { "type": "integer", "description": "Unix timestamp when relation becomes valid" },
-                                "validTo": { "type": "integer", "description": "Unix timestamp when relation expires" }
+                                "validTo": { "type": "integer", "description": "Unix timestamp when relation expires" },
+                                "properties": { "type": "object", "description": "Arbitrary metadata (e.g. {\"critical\": true, \"weight\": 0.8}), queryable with propertyFilter" }
                             },
                             "required": ["from", "to", "relationType"]
                         }
-                    }
+                    },
+                    "strict": strict_schema()
                 },
                 "required": ["relations"]
             }),
@@ -54,29 +59,26 @@ impl Tool for CreateRelationsTool {
         let relations: Vec<Relation> =
             serde_json::from_value(params.get("relations").cloned().unwrap_or(json!([])))?;
 
-        // Collect warnings for non-standard relation types
-        let warnings: Vec<String> = relations
+        // Collect warnings for non-standard relation types (after alias resolution)
+        let mut warnings: Vec<ValidationWarning> = relations
             .iter()
-            .filter_map(|r| validate_relation_type(&r.relation_type))
+            .filter_map(|r| {
+                let relation_type = self.kb.canonical_relation_type(&r.relation_type);
+                validate_relation_type(&relation_type).map(|message| {
+                    ValidationWarning::new(
+                        ValidationWarningKind::NonStandardRelationType,
+                        format!("{} -[{}]-> {}", r.from, r.relation_type, r.to),
+                        message,
+                    )
+                })
+            })
             .collect();
 
-        let created = self.kb.create_relations(relations)?;
+        reject_if_strict(&params, &warnings)?;
 
-        let response = if warnings.is_empty() {
-            serde_json::to_string_pretty(&created)?
-        } else {
-            format!(
-                "{}\n\n{}",
-                serde_json::to_string_pretty(&created)?,
-                warnings.join("\n")
-            )
-        };
+        let (created, cardinality_warnings) = self.kb.create_relations_with_warnings(relations)?;
+        warnings.extend(cardinality_warnings);
 
-        Ok(json!({
-            "content": [{
-                "type": "text",
-                "text": response
-            }]
-        }))
+        response_with_warnings(to_text(&created)?, warnings)
     }
 }
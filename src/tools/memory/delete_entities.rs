@@ -23,7 +23,7 @@ impl Tool for DeleteEntitiesTool {
     fn definition(&self) -> McpTool {
         McpTool {
             name: "delete_entities".to_string(),
-            description: "Delete multiple entities from the knowledge graph".to_string(),
+            description: "Delete multiple entities from the knowledge graph. Protected entities are skipped and reported unless force is true.".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
@@ -31,6 +31,11 @@ impl Tool for DeleteEntitiesTool {
                         "type": "array",
                         "items": { "type": "string" },
                         "description": "An array of entity names to delete"
+                    },
+                    "force": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Also delete protected entities"
                     }
                 },
                 "required": ["entityNames"]
@@ -41,11 +46,29 @@ impl Tool for DeleteEntitiesTool {
     fn execute(&self, params: Value) -> McpResult<Value> {
         let entity_names: Vec<String> =
             serde_json::from_value(params.get("entityNames").cloned().unwrap_or(json!([])))?;
-        self.kb.delete_entities(entity_names)?;
+        let force = params
+            .get("force")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let text = if force {
+            self.kb.force_delete_entities(entity_names)?;
+            "Entities deleted successfully".to_string()
+        } else {
+            let skipped = self.kb.delete_entities(entity_names)?;
+            if skipped.is_empty() {
+                "Entities deleted successfully".to_string()
+            } else {
+                format!(
+                    "Entities deleted; skipped protected entities (use force: true to delete them): {}",
+                    skipped.join(", ")
+                )
+            }
+        };
         Ok(json!({
             "content": [{
                 "type": "text",
-                "text": "Entities deleted successfully"
+                "text": text
             }]
         }))
     }
@@ -0,0 +1,73 @@
+//! Delete relations by filter tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for deleting all relations matching a from/to/type filter
+pub struct DeleteRelationsByFilterTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl DeleteRelationsByFilterTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for DeleteRelationsByFilterTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "delete_relations_by_filter".to_string(),
+            description: "Delete all relations matching the given filters (e.g. every 'depends_on' relation, or every relation from an entity). Filters are combined with AND; at least one is required. Requires confirm: true.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "from": { "type": "string", "description": "Only relations from this entity" },
+                    "to": { "type": "string", "description": "Only relations to this entity" },
+                    "relationType": { "type": "string", "description": "Only relations of this type" },
+                    "confirm": {
+                        "type": "boolean",
+                        "description": "Must be true to perform the deletion"
+                    }
+                },
+                "required": ["confirm"]
+            }),
+        }
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let confirm = params
+            .get("confirm")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !confirm {
+            return Err("Refusing to delete relations without confirm: true".into());
+        }
+
+        let get = |key: &str| params.get(key).and_then(|v| v.as_str()).map(String::from);
+        let deleted =
+            self.kb
+                .delete_relations_by_filter(get("from"), get("to"), get("relationType"))?;
+
+        let triples: Vec<Value> = deleted
+            .iter()
+            .map(|r| json!({ "from": r.from, "to": r.to, "relationType": r.relation_type }))
+            .collect();
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&json!({
+                    "deleted": deleted.len(),
+                    "relations": triples
+                }))?
+            }]
+        }))
+    }
+}
@@ -0,0 +1,130 @@
+//! Graph metadata tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Map, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
+use crate::types::{GraphMetadata, InvalidParams, McpResult};
+
+/// Tool for reading and updating graph-wide metadata
+pub struct GraphMetadataTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl GraphMetadataTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+/// Apply a string-or-null parameter to an optional field
+fn update_field(params: &Value, key: &str, field: &mut Option<String>) -> McpResult<bool> {
+    match params.get(key) {
+        None => Ok(false),
+        Some(Value::Null) => Ok(field.take().is_some()),
+        Some(Value::String(value)) => {
+            *field = Some(value.clone());
+            Ok(true)
+        }
+        Some(_) => Err(Box::new(InvalidParams(format!(
+            "'{}' must be a string or null",
+            key
+        )))),
+    }
+}
+
+/// Merge an annotations object: strings set a key, null removes it
+fn update_annotations(
+    annotations: &Map<String, Value>,
+    metadata: &mut GraphMetadata,
+) -> McpResult<()> {
+    for (key, value) in annotations {
+        match value {
+            Value::Null => {
+                metadata.annotations.remove(key);
+            }
+            Value::String(value) => {
+                metadata.annotations.insert(key.clone(), value.clone());
+            }
+            _ => {
+                return Err(Box::new(InvalidParams(format!(
+                    "annotation '{}' must be a string or null",
+                    key
+                ))))
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Tool for GraphMetadataTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "graph_metadata".to_string(),
+            description: "Get or set document-level metadata for the whole graph (project name, description, schema version, last-reviewed date, free-form annotations), kept apart from domain entities. Call without arguments to read it. Given fields are set, null clears a field; annotations are merged key by key (null removes a key).".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": ["string", "null"],
+                        "description": "Project or graph name"
+                    },
+                    "description": {
+                        "type": ["string", "null"],
+                        "description": "What the graph is about"
+                    },
+                    "schemaVersion": {
+                        "type": ["string", "null"],
+                        "description": "Version of the conventions the graph follows"
+                    },
+                    "lastReviewed": {
+                        "type": ["string", "null"],
+                        "description": "Date the graph was last reviewed (e.g. 2026-10-01)"
+                    },
+                    "annotations": {
+                        "type": "object",
+                        "additionalProperties": { "type": ["string", "null"] },
+                        "description": "Key/value annotations to set (null removes a key)"
+                    }
+                },
+                "required": []
+            }),
+        }
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let mut metadata = self.kb.graph_metadata();
+
+        let mut changed = false;
+        changed |= update_field(&params, "name", &mut metadata.name)?;
+        changed |= update_field(&params, "description", &mut metadata.description)?;
+        changed |= update_field(&params, "schemaVersion", &mut metadata.schema_version)?;
+        changed |= update_field(&params, "lastReviewed", &mut metadata.last_reviewed)?;
+        match params.get("annotations") {
+            None | Some(Value::Null) => {}
+            Some(Value::Object(annotations)) => {
+                update_annotations(annotations, &mut metadata)?;
+                changed = true;
+            }
+            Some(_) => {
+                return Err(Box::new(InvalidParams(
+                    "'annotations' must be an object".to_string(),
+                )))
+            }
+        }
+
+        if changed {
+            metadata = self.kb.set_graph_metadata(metadata)?;
+        }
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&metadata)?
+            }]
+        }))
+    }
+}
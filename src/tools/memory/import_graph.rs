@@ -0,0 +1,52 @@
+//! Import graph tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use super::response_with_warnings;
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
+use crate::types::{KnowledgeGraph, McpResult};
+
+/// Tool for merging an external graph (e.g. an `export_subgraph` result)
+pub struct ImportGraphTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl ImportGraphTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for ImportGraphTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "import_graph".to_string(),
+            description: "Merge an external knowledge graph into this one in a single call. New entities are created, observations of existing entities are merged, and duplicate relations are skipped. Entities whose type differs from the stored one are reported as conflicts and left unchanged.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "graph": {
+                        "type": "object",
+                        "description": "Graph document with 'entities' and 'relations' arrays, as returned by read_graph or export_subgraph",
+                        "properties": {
+                            "entities": { "type": "array", "items": { "type": "object" } },
+                            "relations": { "type": "array", "items": { "type": "object" } }
+                        }
+                    }
+                },
+                "required": ["graph"]
+            }),
+        }
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let graph: KnowledgeGraph =
+            serde_json::from_value(params.get("graph").cloned().ok_or("Missing graph")?)?;
+        let (report, warnings) = self.kb.import_graph_with_warnings(graph)?;
+        response_with_warnings(to_text(&report)?, warnings)
+    }
+}
@@ -1,23 +1,234 @@
 //! Memory tools for CRUD operations
 //!
-//! This module contains 9 tools for managing entities, relations, and observations.
+//! This module contains 22 tools for managing entities, relations, and observations.
 
 mod add_observations;
+mod bulk_create;
 mod create_entities;
 mod create_relations;
 mod delete_entities;
 mod delete_observations;
 mod delete_relations;
+mod delete_relations_by_filter;
+mod graph_metadata;
+mod import_graph;
+mod normalize_relations;
 mod open_nodes;
+mod protect_entities;
 mod read_graph;
+mod reload;
+mod rename_entity_type;
+mod rename_relation_type;
+mod replace_in_observations;
+mod replace_observations;
 mod search_nodes;
+mod transaction;
+mod unprotect_entities;
 
 pub use add_observations::AddObservationsTool;
+pub use bulk_create::BulkCreateTool;
 pub use create_entities::CreateEntitiesTool;
 pub use create_relations::CreateRelationsTool;
 pub use delete_entities::DeleteEntitiesTool;
 pub use delete_observations::DeleteObservationsTool;
 pub use delete_relations::DeleteRelationsTool;
+pub use delete_relations_by_filter::DeleteRelationsByFilterTool;
+pub use graph_metadata::GraphMetadataTool;
+pub use import_graph::ImportGraphTool;
+pub use normalize_relations::NormalizeRelationsTool;
 pub use open_nodes::OpenNodesTool;
+pub use protect_entities::ProtectEntitiesTool;
 pub use read_graph::ReadGraphTool;
+pub use reload::ReloadTool;
+pub use rename_entity_type::RenameEntityTypeTool;
+pub use rename_relation_type::RenameRelationTypeTool;
+pub use replace_in_observations::ReplaceInObservationsTool;
+pub use replace_observations::ReplaceObservationsTool;
 pub use search_nodes::SearchNodesTool;
+pub use transaction::TransactionTool;
+pub use unprotect_entities::UnprotectEntitiesTool;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::tools::to_text;
+use crate::types::{McpResult, ObservationOrder, Projection};
+use crate::utils::add_iso_timestamps;
+use crate::validation::{warnings_suppressed, ValidationWarning};
+
+/// Build a tool response with validation warnings in a separate content item
+///
+/// The first content item is always the plain JSON body so clients can parse it
+/// directly. Warnings follow as a structured JSON item unless suppressed via
+/// `MEMORY_SUPPRESS_VALIDATION_WARNINGS`.
+pub(crate) fn response_with_warnings(
+    body: String,
+    warnings: Vec<ValidationWarning>,
+) -> McpResult<Value> {
+    let mut content = vec![json!({
+        "type": "text",
+        "text": body
+    })];
+
+    if !warnings.is_empty() && !warnings_suppressed() {
+        content.push(json!({
+            "type": "text",
+            "text": to_text(&json!({ "warnings": warnings }))?
+        }));
+    }
+
+    Ok(json!({ "content": content }))
+}
+
+/// Schema of the `strict` parameter of the create tools
+pub(crate) fn strict_schema() -> Value {
+    json!({
+        "type": "boolean",
+        "default": false,
+        "description": "Reject the whole call with an error instead of warning about non-standard types. MEMORY_SUPPRESS_VALIDATION_WARNINGS does not relax this."
+    })
+}
+
+/// Turn type warnings into an error when the call set `strict: true`
+///
+/// Checked before anything is written, so a strict call either creates
+/// everything or nothing. `MEMORY_SUPPRESS_VALIDATION_WARNINGS` only hides
+/// warnings in responses; it does not affect strict calls.
+pub(crate) fn reject_if_strict(params: &Value, warnings: &[ValidationWarning]) -> McpResult<()> {
+    let strict = params
+        .get("strict")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if !strict || warnings.is_empty() {
+        return Ok(());
+    }
+
+    let problems: Vec<String> = warnings
+        .iter()
+        .map(|w| format!("{}: {}", w.subject, w.message))
+        .collect();
+    Err(format!("Strict mode: {}", problems.join("; ")).into())
+}
+
+/// Resolve the `observationOrder` parameter, falling back to the server default
+pub(crate) fn observation_order(kb: &KnowledgeBase, params: &Value) -> McpResult<ObservationOrder> {
+    match params.get("observationOrder").and_then(|v| v.as_str()) {
+        Some(value) => ObservationOrder::parse(value).ok_or_else(|| {
+            format!(
+                "Invalid observationOrder '{}': expected 'oldest' or 'newest'",
+                value
+            )
+            .into()
+        }),
+        None => Ok(kb.observation_order()),
+    }
+}
+
+/// Resolve the `projection` parameter (default: full entities)
+pub(crate) fn projection(params: &Value) -> McpResult<Projection> {
+    match params.get("projection").and_then(|v| v.as_str()) {
+        Some(value) => Projection::parse(value).ok_or_else(|| {
+            format!(
+                "Invalid projection '{}': expected 'names', 'brief' or 'full'",
+                value
+            )
+            .into()
+        }),
+        None => Ok(Projection::Full),
+    }
+}
+
+/// Schema of the `projection` parameter of the read tools
+pub(crate) fn projection_schema() -> Value {
+    json!({
+        "type": "string",
+        "enum": ["names", "brief", "full"],
+        "default": "full",
+        "description": "How much of each entity to return: names only, name + type + first observation (brief), or everything (full). names/brief save tokens when scanning large graphs."
+    })
+}
+
+/// Response text for `body` with its entities shaped by `projection`
+pub(crate) fn projected_text(
+    body: &impl Serialize,
+    projection: Projection,
+    params: &Value,
+) -> McpResult<String> {
+    if projection == Projection::Full {
+        return output_text(body, params);
+    }
+    let mut body = serde_json::to_value(body)?;
+    projection.apply(&mut body);
+    output_text(&body, params)
+}
+
+/// Schema of the `isoTimestamps` parameter of the read tools
+pub(crate) fn iso_timestamps_schema() -> Value {
+    json!({
+        "type": "boolean",
+        "default": false,
+        "description": "Also return each timestamp as an ISO 8601 string (createdAtIso, updatedAtIso, validFromIso, validToIso)"
+    })
+}
+
+/// JSON text for a response body (see `to_text`), with ISO 8601 timestamps if the call set `isoTimestamps`
+pub(crate) fn output_text(body: &impl Serialize, params: &Value) -> McpResult<String> {
+    let iso = params
+        .get("isoTimestamps")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if !iso {
+        return Ok(to_text(body)?);
+    }
+    let mut body = serde_json::to_value(body)?;
+    add_iso_timestamps(&mut body);
+    Ok(to_text(&body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::validation::ValidationWarningKind;
+
+    /// Held by tests that read or set `MEMORY_SUPPRESS_VALIDATION_WARNINGS`
+    pub(super) static SUPPRESS_WARNINGS_ENV: Mutex<()> = Mutex::new(());
+
+    fn warning() -> ValidationWarning {
+        ValidationWarning::new(
+            ValidationWarningKind::NonStandardEntityType,
+            "Standup".to_string(),
+            "Non-standard entityType 'Ritual'".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_response_includes_warnings() {
+        let _env = SUPPRESS_WARNINGS_ENV.lock().unwrap_or_else(|e| e.into_inner());
+        let response = response_with_warnings("[]".to_string(), vec![warning()]).unwrap();
+
+        assert_eq!(response["content"][0]["text"], "[]");
+        let warnings: Value =
+            serde_json::from_str(response["content"][1]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(warnings["warnings"][0]["subject"], "Standup");
+
+        let response = response_with_warnings("[]".to_string(), Vec::new()).unwrap();
+        assert_eq!(response["content"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_env_switch_suppresses_warnings() {
+        let _env = SUPPRESS_WARNINGS_ENV.lock().unwrap_or_else(|e| e.into_inner());
+        std::env::set_var("MEMORY_SUPPRESS_VALIDATION_WARNINGS", "true");
+        let suppressed = warnings_suppressed();
+        let response = response_with_warnings("[]".to_string(), vec![warning()]);
+        std::env::remove_var("MEMORY_SUPPRESS_VALIDATION_WARNINGS");
+
+        assert!(suppressed);
+        let response = response.unwrap();
+        assert_eq!(response["content"].as_array().unwrap().len(), 1);
+        assert_eq!(response["content"][0]["text"], "[]");
+    }
+}
@@ -0,0 +1,97 @@
+//! Normalize relations tool
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
+use crate::types::{McpResult, NormalizeAction};
+
+/// Tool for cleaning up relation type case, duplicates and reciprocal pairs
+pub struct NormalizeRelationsTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl NormalizeRelationsTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for NormalizeRelationsTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "normalize_relations".to_string(),
+            description: "Clean up relations across the graph: lowercase relation types (and apply relation aliases), drop relations that duplicate another after that, and with collapseReciprocal drop the reverse copy of symmetric relations (A relates_to B plus B relates_to A). The first relation in graph order is kept. Safe to run repeatedly. Use dryRun to preview the changes first.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "collapseReciprocal": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Also drop the reverse copy of relations whose type is symmetric"
+                    },
+                    "symmetricTypes": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Relation types treated as symmetric (default: MEMORY_SYMMETRIC_RELATIONS, or relates_to)"
+                    },
+                    "dryRun": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Report the changes without applying them"
+                    }
+                },
+                "required": []
+            }),
+        }
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let collapse_reciprocal = params
+            .get("collapseReciprocal")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let dry_run = params
+            .get("dryRun")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let symmetric: Option<BTreeSet<String>> = collapse_reciprocal.then(|| {
+            match params.get("symmetricTypes").and_then(|v| v.as_array()) {
+                Some(types) => types
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|t| t.trim().to_lowercase())
+                    .filter(|t| !t.is_empty())
+                    .collect(),
+                None => KnowledgeBase::symmetric_relation_types(),
+            }
+        });
+
+        let changes = if dry_run {
+            self.kb.preview_normalize_relations(symmetric.as_ref())
+        } else {
+            self.kb.normalize_relations(symmetric.as_ref())?
+        };
+        let count = |action: NormalizeAction| changes.iter().filter(|c| c.action == action).count();
+
+        let result = json!({
+            "dryRun": dry_run,
+            "retyped": count(NormalizeAction::Retyped),
+            "duplicates": count(NormalizeAction::Duplicate),
+            "reciprocals": count(NormalizeAction::Reciprocal),
+            "symmetricTypes": symmetric,
+            "changes": changes
+        });
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&result)?
+            }]
+        }))
+    }
+}
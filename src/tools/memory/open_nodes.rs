@@ -5,9 +5,11 @@ use std::sync::Arc;
 use serde_json::{json, Value};
 
 use crate::knowledge_base::KnowledgeBase;
-use crate::protocol::{McpTool, Tool};
+use crate::protocol::{McpTool, Tool, ToolAccess};
 use crate::types::McpResult;
 
+use super::{iso_timestamps_schema, observation_order, output_text};
+
 /// Tool for opening specific nodes by their names
 pub struct OpenNodesTool {
     kb: Arc<KnowledgeBase>,
@@ -23,30 +25,166 @@ impl Tool for OpenNodesTool {
     fn definition(&self) -> McpTool {
         McpTool {
             name: "open_nodes".to_string(),
-            description: "Open specific nodes in the knowledge graph by their names".to_string(),
+            description: "Open specific nodes in the knowledge graph by their names. For entities with many observations, use observationLimit/observationOffset to return one page of each entity's observations; the response then includes observationTotals with each entity's full count.".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
                     "names": {
                         "type": "array",
                         "items": { "type": "string" },
-                        "description": "An array of entity names to retrieve"
-                    }
+                        "description": "An array of entity names or ids to retrieve"
+                    },
+                    "observationOrder": {
+                        "type": "string",
+                        "enum": ["oldest", "newest"],
+                        "description": "Order of observations in the output (default: oldest first, or the server setting)"
+                    },
+                    "observationLimit": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "description": "Maximum observations returned per entity (default: all)"
+                    },
+                    "observationOffset": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "default": 0,
+                        "description": "Observations to skip per entity, counted in observationOrder"
+                    },
+                    "isoTimestamps": iso_timestamps_schema()
                 },
                 "required": ["names"]
             }),
         }
     }
 
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
     fn execute(&self, params: Value) -> McpResult<Value> {
         let names: Vec<String> =
             serde_json::from_value(params.get("names").cloned().unwrap_or(json!([])))?;
-        let graph = self.kb.open_nodes(names)?;
-        Ok(json!({
-            "content": [{
-                "type": "text",
-                "text": serde_json::to_string_pretty(&graph)?
-            }]
-        }))
+        let order = observation_order(&self.kb, &params)?;
+        let limit = params
+            .get("observationLimit")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize);
+        let offset = params.get("observationOffset").and_then(|v| v.as_u64());
+
+        let text = if limit.is_none() && offset.is_none() {
+            let mut graph = self.kb.open_nodes(names)?;
+            order.apply(&mut graph.entities);
+            output_text(&graph, &params)?
+        } else {
+            let offset = offset.unwrap_or(0) as usize;
+            let (graph, totals) = self.kb.open_nodes_paged(names, order, offset, limit)?;
+            let page = json!({
+                "entities": graph.entities,
+                "relations": graph.relations,
+                "observationOffset": offset,
+                "observationTotals": totals
+            });
+            output_text(&page, &params)?
+        };
+
+        Ok(self.kb.link_result("open_nodes", text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Entity;
+
+    #[test]
+    fn test_observation_order_flag() {
+        let path = format!("target/test_open_nodes_order_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(
+            path.clone(),
+            "tester".to_string(),
+        ));
+        kb.create_entities(vec![Entity::with_observations(
+            "Deploy".to_string(),
+            "Task".to_string(),
+            vec![
+                "queued".to_string(),
+                "running".to_string(),
+                "done".to_string(),
+            ],
+        )])
+        .unwrap();
+        let tool = OpenNodesTool::new(kb);
+
+        let observations = |params: Value| -> Vec<String> {
+            let response = tool.execute(params).unwrap();
+            let text = response["content"][0]["text"].as_str().unwrap();
+            let graph: Value = serde_json::from_str(text).unwrap();
+            serde_json::from_value(graph["entities"][0]["observations"].clone()).unwrap()
+        };
+
+        assert_eq!(
+            observations(json!({ "names": ["Deploy"] })),
+            vec!["queued", "running", "done"]
+        );
+        assert_eq!(
+            observations(json!({ "names": ["Deploy"], "observationOrder": "newest" })),
+            vec!["done", "running", "queued"]
+        );
+        assert!(tool
+            .execute(json!({ "names": ["Deploy"], "observationOrder": "sideways" }))
+            .is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_observation_pages() {
+        let path = format!("target/test_open_nodes_pages_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(
+            path.clone(),
+            "tester".to_string(),
+        ));
+        kb.create_entities(vec![Entity::with_observations(
+            "Log".to_string(),
+            "Service".to_string(),
+            (0..10).map(|i| format!("line {}", i)).collect(),
+        )])
+        .unwrap();
+        let tool = OpenNodesTool::new(kb);
+
+        let open = |params: Value| -> Value {
+            let response = tool.execute(params).unwrap();
+            serde_json::from_str(response["content"][0]["text"].as_str().unwrap()).unwrap()
+        };
+
+        let page = open(json!({ "names": ["Log"], "observationLimit": 3, "observationOffset": 8 }));
+        assert_eq!(
+            page["entities"][0]["observations"],
+            json!(["line 8", "line 9"])
+        );
+        assert_eq!(page["observationTotals"]["Log"], 10);
+
+        let page = open(json!({
+            "names": ["Log"],
+            "observationLimit": 2,
+            "observationOrder": "newest"
+        }));
+        assert_eq!(
+            page["entities"][0]["observations"],
+            json!(["line 9", "line 8"])
+        );
+
+        // Without paging the response is unchanged
+        let full = open(json!({ "names": ["Log"] }));
+        assert!(full.get("observationTotals").is_none());
+        assert_eq!(
+            full["entities"][0]["observations"]
+                .as_array()
+                .unwrap()
+                .len(),
+            10
+        );
+
+        let _ = std::fs::remove_file(&path);
     }
 }
@@ -0,0 +1,53 @@
+//! Protect entities tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for protecting entities from deletion
+pub struct ProtectEntitiesTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl ProtectEntitiesTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for ProtectEntitiesTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "protect_entities".to_string(),
+            description: "Protect entities (e.g. project roots, conventions) from deletion: delete_entities skips them unless called with force: true. A safety net against accidental deletes, not access control.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "entityNames": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Names (or ids) of the entities"
+                    }
+                },
+                "required": ["entityNames"]
+            }),
+        }
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let entity_names: Vec<String> =
+            serde_json::from_value(params.get("entityNames").cloned().unwrap_or(json!([])))?;
+        let changed = self.kb.protect_entities(&entity_names)?;
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&json!({ "changed": changed }))?
+            }]
+        }))
+    }
+}
@@ -5,9 +5,13 @@ use std::sync::Arc;
 use serde_json::{json, Value};
 
 use crate::knowledge_base::KnowledgeBase;
-use crate::protocol::{McpTool, Tool};
+use crate::protocol::{McpTool, Tool, ToolAccess};
 use crate::types::McpResult;
 
+use super::{
+    iso_timestamps_schema, observation_order, projected_text, projection, projection_schema,
+};
+
 /// Tool for reading the knowledge graph with optional pagination
 pub struct ReadGraphTool {
     kb: Arc<KnowledgeBase>,
@@ -34,13 +38,24 @@ impl Tool for ReadGraphTool {
                     "offset": {
                         "type": "integer",
                         "description": "Number of entities to skip (for pagination)"
-                    }
+                    },
+                    "observationOrder": {
+                        "type": "string",
+                        "enum": ["oldest", "newest"],
+                        "description": "Order of observations in the output (default: oldest first, or the server setting)"
+                    },
+                    "isoTimestamps": iso_timestamps_schema(),
+                    "projection": projection_schema()
                 },
                 "required": []
             }),
         }
     }
 
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
     fn execute(&self, params: Value) -> McpResult<Value> {
         let limit = params
             .get("limit")
@@ -50,7 +65,10 @@ impl Tool for ReadGraphTool {
             .get("offset")
             .and_then(|v| v.as_u64())
             .map(|v| v as usize);
-        let graph = self.kb.read_graph(limit, offset)?;
+        let order = observation_order(&self.kb, &params)?;
+        let projection = projection(&params)?;
+        let mut graph = self.kb.read_graph(limit, offset)?;
+        order.apply(&mut graph.entities);
 
         let total_msg = if limit.is_some() || offset.is_some() {
             format!(" (showing {} entities)", graph.entities.len())
@@ -58,11 +76,11 @@ impl Tool for ReadGraphTool {
             String::new()
         };
 
-        Ok(json!({
-            "content": [{
-                "type": "text",
-                "text": format!("{}{}", serde_json::to_string_pretty(&graph)?, total_msg)
-            }]
-        }))
+        let text = format!(
+            "{}{}",
+            projected_text(&graph, projection, &params)?,
+            total_msg
+        );
+        Ok(self.kb.link_result("read_graph", text))
     }
 }
@@ -0,0 +1,51 @@
+//! Reload tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for re-reading the graph from disk after out-of-band edits
+///
+/// Only served over stdio; HTTP clients use the admin-only
+/// `POST /api/graph/reload` instead.
+pub struct ReloadTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl ReloadTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for ReloadTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "reload_graph".to_string(),
+            description: "Discard the in-memory graph and re-read it from disk (memory.jsonl, or snapshot plus event log with Event Sourcing). Use after editing the storage files directly, instead of restarting the server.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {},
+                "required": []
+            }),
+        }
+    }
+
+    fn execute(&self, _params: Value) -> McpResult<Value> {
+        let (entities, relations) = self.kb.reload_from_disk()?;
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&json!({
+                    "entities": entities,
+                    "relations": relations
+                }))?
+            }]
+        }))
+    }
+}
@@ -0,0 +1,83 @@
+//! Rename entity type tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use super::{reject_if_strict, response_with_warnings, strict_schema};
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
+use crate::types::McpResult;
+use crate::validation::{validate_entity_type, ValidationWarning, ValidationWarningKind};
+
+/// Tool for renaming an entity type across the whole graph
+pub struct RenameEntityTypeTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl RenameEntityTypeTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for RenameEntityTypeTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "rename_entity_type".to_string(),
+            description: "Rename an entity type on every entity of the graph (e.g. 'Ticket' to 'Issue'). Warns when the new type is non-standard. Use dryRun to preview the entities affected first.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "oldType": { "type": "string", "description": "Entity type to rename" },
+                    "newType": { "type": "string", "description": "New entity type" },
+                    "dryRun": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Report matching entities without changing anything"
+                    },
+                    "strict": strict_schema()
+                },
+                "required": ["oldType", "newType"]
+            }),
+        }
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let old_type = params
+            .get("oldType")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing oldType")?;
+        let new_type = params
+            .get("newType")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing newType")?;
+        let dry_run = params
+            .get("dryRun")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let warnings: Vec<ValidationWarning> = validate_entity_type(new_type.trim())
+            .map(|message| {
+                ValidationWarning::new(
+                    ValidationWarningKind::NonStandardEntityType,
+                    new_type.trim().to_string(),
+                    message,
+                )
+            })
+            .into_iter()
+            .collect();
+        reject_if_strict(&params, &warnings)?;
+
+        let result = if dry_run {
+            let entities = self.kb.preview_rename_entity_type(old_type, new_type)?;
+            json!({ "dryRun": true, "matches": entities.len(), "entities": entities })
+        } else {
+            let changed = self.kb.rename_entity_type(old_type, new_type)?;
+            json!({ "dryRun": false, "changed": changed })
+        };
+
+        response_with_warnings(to_text(&result)?, warnings)
+    }
+}
@@ -0,0 +1,79 @@
+//! Rename relation type tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for renaming a relation type across the whole graph
+pub struct RenameRelationTypeTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl RenameRelationTypeTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for RenameRelationTypeTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "rename_relation_type".to_string(),
+            description: "Rename a relation type on every relation of the graph (e.g. 'relates_to' to 'references'). Relations whose renamed triple already exists are dropped as duplicates. Use dryRun to preview the changes first.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "oldType": { "type": "string", "description": "Relation type to rename" },
+                    "newType": { "type": "string", "description": "New relation type" },
+                    "dryRun": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Report matching relations without changing anything"
+                    }
+                },
+                "required": ["oldType", "newType"]
+            }),
+        }
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let old_type = params
+            .get("oldType")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing oldType")?;
+        let new_type = params
+            .get("newType")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing newType")?;
+        let dry_run = params
+            .get("dryRun")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let result = if dry_run {
+            let changes = self.kb.preview_rename_relation_type(old_type, new_type)?;
+            let duplicates = changes.iter().filter(|c| c.duplicate).count();
+            json!({
+                "dryRun": true,
+                "matches": changes.len(),
+                "duplicates": duplicates,
+                "changes": changes
+            })
+        } else {
+            let changed = self.kb.rename_relation_type(old_type, new_type)?;
+            json!({ "dryRun": false, "changed": changed })
+        };
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&result)?
+            }]
+        }))
+    }
+}
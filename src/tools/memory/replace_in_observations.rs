@@ -0,0 +1,84 @@
+//! Replace in observations tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for rewriting text across all observations in the graph
+pub struct ReplaceInObservationsTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl ReplaceInObservationsTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for ReplaceInObservationsTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "replace_in_observations".to_string(),
+            description: "Search-and-replace text in every observation of the graph (e.g. rename 'OAuth1' to 'OAuth2' everywhere). Use dryRun to preview the changes first.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "find": { "type": "string", "description": "Text (or regex) to search for" },
+                    "replace": { "type": "string", "description": "Replacement text; with regex, $1 etc. refer to capture groups" },
+                    "regex": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Treat 'find' as a regular expression"
+                    },
+                    "dryRun": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Report matching observations without changing anything"
+                    }
+                },
+                "required": ["find", "replace"]
+            }),
+        }
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let find = params
+            .get("find")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing find")?;
+        let replace = params
+            .get("replace")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing replace")?;
+        let regex = params
+            .get("regex")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let dry_run = params
+            .get("dryRun")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let result = if dry_run {
+            let changes = self
+                .kb
+                .preview_replace_in_observations(find, replace, regex)?;
+            json!({ "dryRun": true, "matches": changes.len(), "changes": changes })
+        } else {
+            let changed = self.kb.replace_in_observations(find, replace, regex)?;
+            json!({ "dryRun": false, "changed": changed })
+        };
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&result)?
+            }]
+        }))
+    }
+}
@@ -0,0 +1,67 @@
+//! Replace observations tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
+use crate::types::{McpResult, ObservationReplacement};
+
+/// Tool for replacing observations on existing entities
+pub struct ReplaceObservationsTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl ReplaceObservationsTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for ReplaceObservationsTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "replace_observations".to_string(),
+            description: "Replace observations on existing entities. Without replacePrefix the entire observation set is replaced; with replacePrefix (e.g. 'Status:') only observations starting with it are replaced, so status-like observations update instead of accumulating.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "replacements": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "entityName": { "type": "string", "description": "The name of the entity" },
+                                "contents": {
+                                    "type": "array",
+                                    "items": { "type": "string" },
+                                    "description": "New observation contents"
+                                },
+                                "replacePrefix": {
+                                    "type": "string",
+                                    "description": "Only replace existing observations starting with this prefix"
+                                }
+                            },
+                            "required": ["entityName", "contents"]
+                        }
+                    }
+                },
+                "required": ["replacements"]
+            }),
+        }
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let replacements: Vec<ObservationReplacement> =
+            serde_json::from_value(params.get("replacements").cloned().unwrap_or(json!([])))?;
+        let replaced = self.kb.replace_observations(replacements)?;
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&replaced)?
+            }]
+        }))
+    }
+}
@@ -5,8 +5,12 @@ use std::sync::Arc;
 use serde_json::{json, Value};
 
 use crate::knowledge_base::KnowledgeBase;
-use crate::protocol::{McpTool, Tool};
-use crate::types::McpResult;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::types::{McpResult, SearchField, SearchOptions};
+
+use super::{
+    iso_timestamps_schema, observation_order, projected_text, projection, projection_schema,
+};
 
 /// Tool for searching nodes in the knowledge graph with semantic matching
 pub struct SearchNodesTool {
@@ -24,7 +28,7 @@ impl Tool for SearchNodesTool {
         McpTool {
             name: "search_nodes".to_string(),
             description:
-                "Search for nodes in the knowledge graph. Returns matching entities with optional relations."
+                "Search for nodes in the knowledge graph. Returns matching entities ranked by relevance score, with optional relations and pagination. Set groupByType to bucket the results by entity type."
                     .to_string(),
             input_schema: json!({
                 "type": "object",
@@ -37,9 +41,34 @@ impl Tool for SearchNodesTool {
                         "type": "integer",
                         "description": "Maximum number of entities to return (default: no limit)"
                     },
+                    "offset": {
+                        "type": "integer",
+                        "description": "Number of ranked entities to skip (default: 0)"
+                    },
                     "includeRelations": {
                         "type": "boolean",
                         "description": "Whether to include relations connected to matching entities (default: true)"
+                    },
+                    "fields": {
+                        "type": "array",
+                        "items": { "type": "string", "enum": ["name", "entityType", "observations"] },
+                        "description": "Only match against these fields (default: all)"
+                    },
+                    "entityTypes": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Only return entities of these types"
+                    },
+                    "observationOrder": {
+                        "type": "string",
+                        "enum": ["oldest", "newest"],
+                        "description": "Order of observations in the output (default: oldest first, or the server setting)"
+                    },
+                    "isoTimestamps": iso_timestamps_schema(),
+                    "projection": projection_schema(),
+                    "groupByType": {
+                        "type": "boolean",
+                        "description": "Return the page as groups of one entity type each, with per-type counts (default: false)"
                     }
                 },
                 "required": ["query"]
@@ -47,23 +76,117 @@ impl Tool for SearchNodesTool {
         }
     }
 
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
     fn execute(&self, params: Value) -> McpResult<Value> {
         let query = params.get("query").and_then(|v| v.as_str()).unwrap_or("");
         let limit = params
             .get("limit")
             .and_then(|v| v.as_u64())
             .map(|v| v as usize);
+        let offset = params
+            .get("offset")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(0);
         let include_relations = params
             .get("includeRelations")
             .and_then(|v| v.as_bool())
             .unwrap_or(true);
+        let fields = params
+            .get("fields")
+            .and_then(|v| v.as_array())
+            .map(|arr| SearchField::parse_list(arr.iter().filter_map(|v| v.as_str())))
+            .transpose()?;
+        let entity_types = params
+            .get("entityTypes")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            });
+
+        let options = SearchOptions {
+            limit,
+            offset,
+            include_relations,
+            fields,
+            entity_types,
+        };
+
+        let group_by_type = params
+            .get("groupByType")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let order = observation_order(&self.kb, &params)?;
+        let projection = projection(&params)?;
+        let mut result = self.kb.search_nodes(query, &options)?;
+        order.apply(result.entities.iter_mut().map(|e| &mut e.entity));
+        let text = if group_by_type {
+            projected_text(&result.group_by_type(), projection, &params)?
+        } else {
+            projected_text(&result, projection, &params)?
+        };
+        Ok(self.kb.link_result("search_nodes", text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Entity;
+
+    #[test]
+    fn test_group_by_type() {
+        let path = format!("target/test_search_group_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(
+            path.clone(),
+            "tester".to_string(),
+        ));
+        kb.create_entities(vec![
+            Entity::new("Auth login bug".to_string(), "Bug".to_string()),
+            Entity::new("Auth".to_string(), "Module".to_string()),
+            Entity::new("Auth token bug".to_string(), "Bug".to_string()),
+        ])
+        .unwrap();
+        let tool = SearchNodesTool::new(kb);
+
+        let search = |params: Value| -> Value {
+            let response = tool.execute(params).unwrap();
+            serde_json::from_str(response["content"][0]["text"].as_str().unwrap()).unwrap()
+        };
+
+        let flat = search(json!({ "query": "auth" }));
+        assert_eq!(flat["entities"].as_array().unwrap().len(), 3);
+        assert!(flat.get("groups").is_none());
+
+        let grouped = search(json!({ "query": "auth", "groupByType": true }));
+        let groups = grouped["groups"].as_array().unwrap();
+        let mut counts: Vec<(&str, u64)> = groups
+            .iter()
+            .map(|g| (g["entityType"].as_str().unwrap(), g["count"].as_u64().unwrap()))
+            .collect();
+        counts.sort_unstable();
+        assert_eq!(counts, vec![("Bug", 2), ("Module", 1)]);
+        assert_eq!(grouped["total"], 3);
+
+        let names = search(json!({ "query": "auth", "projection": "names" }));
+        let names = names["entities"].as_array().unwrap();
+        assert!(names.iter().all(|e| e.is_string()));
+        let brief = search(json!({
+            "query": "auth",
+            "projection": "brief",
+            "groupByType": true
+        }));
+        let entity = &brief["groups"][0]["entities"][0];
+        assert!(entity.get("observations").is_none());
+        assert!(entity["entityType"].is_string() && entity["score"].is_number());
+        assert!(tool.execute(json!({ "query": "auth", "projection": "all" })).is_err());
 
-        let graph = self.kb.search_nodes(query, limit, include_relations)?;
-        Ok(json!({
-            "content": [{
-                "type": "text",
-                "text": serde_json::to_string_pretty(&graph)?
-            }]
-        }))
+        let _ = std::fs::remove_file(&path);
     }
 }
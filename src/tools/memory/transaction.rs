@@ -0,0 +1,105 @@
+//! Transaction tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
+use crate::types::{McpResult, Op};
+
+use super::response_with_warnings;
+
+/// Tool for applying several mutations atomically
+pub struct TransactionTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl TransactionTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for TransactionTool {
+    fn definition(&self) -> McpTool {
+        let entity = json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "entityType": { "type": "string" },
+                "observations": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["name", "entityType"]
+        });
+        let relation = json!({
+            "type": "object",
+            "properties": {
+                "from": { "type": "string" },
+                "to": { "type": "string" },
+                "relationType": { "type": "string" }
+            },
+            "required": ["from", "to", "relationType"]
+        });
+        let observation = json!({
+            "type": "object",
+            "properties": {
+                "entityName": { "type": "string" },
+                "contents": { "type": "array", "items": { "type": "string" } },
+                "replacePrefix": { "type": "string" },
+                "observations": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["entityName"]
+        });
+
+        McpTool {
+            name: "transaction".to_string(),
+            description: "Apply multiple mutations atomically: either every operation succeeds or nothing is changed. Operations run in order and fail if they reference missing entities/relations, create existing ones, or would drop an item (rejected name, cardinality conflict, observation limit).".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "ops": {
+                        "type": "array",
+                        "description": "Ordered operations, each tagged with 'op'",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "op": {
+                                    "type": "string",
+                                    "enum": [
+                                        "create_entities",
+                                        "create_relations",
+                                        "add_observations",
+                                        "replace_observations",
+                                        "delete_entities",
+                                        "delete_observations",
+                                        "delete_relations"
+                                    ]
+                                },
+                                "entities": { "type": "array", "items": entity },
+                                "relations": { "type": "array", "items": relation },
+                                "observations": { "type": "array", "items": observation },
+                                "replacements": { "type": "array", "items": observation },
+                                "deletions": { "type": "array", "items": observation },
+                                "entityNames": { "type": "array", "items": { "type": "string" } },
+                                "force": {
+                                    "type": "boolean",
+                                    "description": "delete_entities: also delete protected entities"
+                                }
+                            },
+                            "required": ["op"]
+                        }
+                    }
+                },
+                "required": ["ops"]
+            }),
+        }
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let ops: Vec<Op> = serde_json::from_value(params.get("ops").cloned().unwrap_or(json!([])))?;
+        let (results, warnings) = self.kb.transaction_with_warnings(ops)?;
+        response_with_warnings(to_text(&results)?, warnings)
+    }
+}
@@ -0,0 +1,55 @@
+//! Unprotect entities tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for removing deletion protection from entities
+pub struct UnprotectEntitiesTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl UnprotectEntitiesTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for UnprotectEntitiesTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "unprotect_entities".to_string(),
+            description:
+                "Remove deletion protection from entities, so delete_entities removes them again."
+                    .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "entityNames": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Names (or ids) of the entities"
+                    }
+                },
+                "required": ["entityNames"]
+            }),
+        }
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let entity_names: Vec<String> =
+            serde_json::from_value(params.get("entityNames").cloned().unwrap_or(json!([])))?;
+        let changed = self.kb.unprotect_entities(&entity_names)?;
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&json!({ "changed": changed }))?
+            }]
+        }))
+    }
+}
@@ -1,80 +1,183 @@
 //! MCP Tools implementation
 //!
-//! This module contains all 16 MCP tools organized by category:
-//! - Memory tools (9): CRUD operations
-//! - Query tools (3): Graph traversal and search
-//! - Temporal tools (3): Time-based queries
-//! - Inference tools (1): Graph reasoning
+//! This module contains all 55 MCP tools organized by category:
+//! - Memory tools (22): CRUD operations, bulk imports and rewrites, relation normalization, deletion protection, reload and graph metadata
+//! - Query tools (25): Traversal, search, validation, sampling, lookups, export, references, compaction, relation queries, attributes, orphans, k shortest paths, schema, cost estimates, entity diffs, recent changes, content hashes, relation suggestions, per-user changes, tool usage statistics, entity Markdown export
+//! - Temporal tools (6): Time-based queries, timelines, relation historization and pruning of expired relations
+//! - Inference tools (2): Graph reasoning and rule listing
 
 pub mod inference;
 pub mod memory;
 pub mod query;
 pub mod temporal;
 
+mod output;
+mod stats;
+
 use std::sync::Arc;
 
 use crate::knowledge_base::KnowledgeBase;
 use crate::protocol::Tool;
 use crate::server::McpServer;
 
+pub use output::{execute_tool, execute_typed, to_text};
+pub use stats::{tool_stats, track, ToolStats, ToolUsage, LATENCY_SAMPLES};
+
 // Re-export all tools for convenience
-pub use inference::InferTool;
+pub use inference::{InferTool, ListInferenceRulesTool};
 pub use memory::{
-    AddObservationsTool, CreateEntitiesTool, CreateRelationsTool, DeleteEntitiesTool,
-    DeleteObservationsTool, DeleteRelationsTool, OpenNodesTool, ReadGraphTool, SearchNodesTool,
+    AddObservationsTool, BulkCreateTool, CreateEntitiesTool, CreateRelationsTool,
+    DeleteEntitiesTool, DeleteObservationsTool, DeleteRelationsByFilterTool, DeleteRelationsTool,
+    GraphMetadataTool, ImportGraphTool, NormalizeRelationsTool, OpenNodesTool,
+    ProtectEntitiesTool, ReadGraphTool, ReloadTool, RenameEntityTypeTool, RenameRelationTypeTool,
+    ReplaceInObservationsTool, ReplaceObservationsTool, SearchNodesTool, TransactionTool,
+    UnprotectEntitiesTool,
+};
+pub use query::{
+    AttributesTool, ByUserTool, CompactViewTool, DiffEntitiesTool, EntityExistsTool,
+    EntityMarkdownTool, EstimateCostTool, ExportSubgraphTool, FindDuplicatesTool, FindOrphansTool,
+    GetRelatedTool, GraphHashTool, GraphSchemaTool, HotEntitiesTool, KPathsTool, QueryRelationsTool,
+    RandomWalkTool, RecentChangesTool, RelationExistsTool, SuggestRelationsTool, SummarizeTool,
+    ToolStatsTool, TraverseTool, ValidateGraphTool, WhatLinksHereTool,
+};
+pub use temporal::{
+    GetCurrentTimeTool, GetRelationHistoryTool, GetRelationsAtTimeTool, GetTimelineTool,
+    HistorizeRelationsTool, PruneExpiredTool,
 };
-pub use query::{GetRelatedTool, SummarizeTool, TraverseTool};
-pub use temporal::{GetCurrentTimeTool, GetRelationHistoryTool, GetRelationsAtTimeTool};
 
 /// Register all tools with the MCP server
+///
+/// Also serves the large results the read tools link to as resources.
 pub fn register_all_tools(server: &mut McpServer, kb: Arc<KnowledgeBase>) {
-    // Memory tools (9)
+    server.serve_resources(kb.clone());
+
+    // Memory tools (22)
     server.register_tool(Box::new(CreateEntitiesTool::new(kb.clone())));
     server.register_tool(Box::new(CreateRelationsTool::new(kb.clone())));
     server.register_tool(Box::new(AddObservationsTool::new(kb.clone())));
+    server.register_tool(Box::new(ReplaceObservationsTool::new(kb.clone())));
+    server.register_tool(Box::new(ReplaceInObservationsTool::new(kb.clone())));
+    server.register_tool(Box::new(RenameRelationTypeTool::new(kb.clone())));
+    server.register_tool(Box::new(RenameEntityTypeTool::new(kb.clone())));
+    server.register_tool(Box::new(NormalizeRelationsTool::new(kb.clone())));
     server.register_tool(Box::new(DeleteEntitiesTool::new(kb.clone())));
+    server.register_tool(Box::new(ProtectEntitiesTool::new(kb.clone())));
+    server.register_tool(Box::new(UnprotectEntitiesTool::new(kb.clone())));
     server.register_tool(Box::new(DeleteObservationsTool::new(kb.clone())));
     server.register_tool(Box::new(DeleteRelationsTool::new(kb.clone())));
+    server.register_tool(Box::new(DeleteRelationsByFilterTool::new(kb.clone())));
     server.register_tool(Box::new(ReadGraphTool::new(kb.clone())));
     server.register_tool(Box::new(SearchNodesTool::new(kb.clone())));
     server.register_tool(Box::new(OpenNodesTool::new(kb.clone())));
+    server.register_tool(Box::new(TransactionTool::new(kb.clone())));
+    server.register_tool(Box::new(ImportGraphTool::new(kb.clone())));
+    server.register_tool(Box::new(BulkCreateTool::new(kb.clone())));
+    server.register_tool(Box::new(ReloadTool::new(kb.clone())));
+    server.register_tool(Box::new(GraphMetadataTool::new(kb.clone())));
 
-    // Query tools (3)
+    // Query tools (25)
     server.register_tool(Box::new(GetRelatedTool::new(kb.clone())));
     server.register_tool(Box::new(TraverseTool::new(kb.clone())));
     server.register_tool(Box::new(SummarizeTool::new(kb.clone())));
+    server.register_tool(Box::new(ValidateGraphTool::new(kb.clone())));
+    server.register_tool(Box::new(HotEntitiesTool::new(kb.clone())));
+    server.register_tool(Box::new(RecentChangesTool::new(kb.clone())));
+    server.register_tool(Box::new(ByUserTool::new(kb.clone())));
+    server.register_tool(Box::new(GraphHashTool::new(kb.clone())));
+    server.register_tool(Box::new(RandomWalkTool::new(kb.clone())));
+    server.register_tool(Box::new(FindDuplicatesTool::new(kb.clone())));
+    server.register_tool(Box::new(DiffEntitiesTool::new(kb.clone())));
+    server.register_tool(Box::new(SuggestRelationsTool::new(kb.clone())));
+    server.register_tool(Box::new(EntityExistsTool::new(kb.clone())));
+    server.register_tool(Box::new(RelationExistsTool::new(kb.clone())));
+    server.register_tool(Box::new(ExportSubgraphTool::new(kb.clone())));
+    server.register_tool(Box::new(WhatLinksHereTool::new(kb.clone())));
+    server.register_tool(Box::new(CompactViewTool::new(kb.clone())));
+    server.register_tool(Box::new(EntityMarkdownTool::new(kb.clone())));
+    server.register_tool(Box::new(QueryRelationsTool::new(kb.clone())));
+    server.register_tool(Box::new(AttributesTool::new(kb.clone())));
+    server.register_tool(Box::new(FindOrphansTool::new(kb.clone())));
+    server.register_tool(Box::new(KPathsTool::new(kb.clone())));
+    server.register_tool(Box::new(GraphSchemaTool::new(kb.clone())));
+    server.register_tool(Box::new(EstimateCostTool::new(kb.clone())));
+    server.register_tool(Box::new(ToolStatsTool::new()));
 
-    // Temporal tools (3)
+    // Temporal tools (6)
     server.register_tool(Box::new(GetRelationsAtTimeTool::new(kb.clone())));
     server.register_tool(Box::new(GetRelationHistoryTool::new(kb.clone())));
     server.register_tool(Box::new(GetCurrentTimeTool::new()));
+    server.register_tool(Box::new(GetTimelineTool::new(kb.clone())));
+    server.register_tool(Box::new(HistorizeRelationsTool::new(kb.clone())));
+    server.register_tool(Box::new(PruneExpiredTool::new(kb.clone())));
 
-    // Inference tools (1)
+    // Inference tools (2)
     server.register_tool(Box::new(InferTool::new(kb.clone())));
+    server.register_tool(Box::new(ListInferenceRulesTool::new()));
 }
 
 /// Get all tools as Arc<dyn Tool> for SSE state
+///
+/// `reload_graph` is left out: over HTTP, reloading is admin-only and goes
+/// through `POST /api/graph/reload`.
 pub fn get_all_tools(kb: Arc<KnowledgeBase>) -> Vec<Arc<dyn Tool>> {
     vec![
-        // Memory tools (9)
+        // Memory tools (21)
         Arc::new(CreateEntitiesTool::new(kb.clone())) as Arc<dyn Tool>,
         Arc::new(CreateRelationsTool::new(kb.clone())),
         Arc::new(AddObservationsTool::new(kb.clone())),
+        Arc::new(ReplaceObservationsTool::new(kb.clone())),
+        Arc::new(ReplaceInObservationsTool::new(kb.clone())),
+        Arc::new(RenameRelationTypeTool::new(kb.clone())),
+        Arc::new(RenameEntityTypeTool::new(kb.clone())),
+        Arc::new(NormalizeRelationsTool::new(kb.clone())),
         Arc::new(DeleteEntitiesTool::new(kb.clone())),
+        Arc::new(ProtectEntitiesTool::new(kb.clone())),
+        Arc::new(UnprotectEntitiesTool::new(kb.clone())),
         Arc::new(DeleteObservationsTool::new(kb.clone())),
         Arc::new(DeleteRelationsTool::new(kb.clone())),
+        Arc::new(DeleteRelationsByFilterTool::new(kb.clone())),
         Arc::new(ReadGraphTool::new(kb.clone())),
         Arc::new(SearchNodesTool::new(kb.clone())),
         Arc::new(OpenNodesTool::new(kb.clone())),
-        // Query tools (3)
+        Arc::new(TransactionTool::new(kb.clone())),
+        Arc::new(ImportGraphTool::new(kb.clone())),
+        Arc::new(BulkCreateTool::new(kb.clone())),
+        Arc::new(GraphMetadataTool::new(kb.clone())),
+        // Query tools (25)
         Arc::new(GetRelatedTool::new(kb.clone())),
         Arc::new(TraverseTool::new(kb.clone())),
         Arc::new(SummarizeTool::new(kb.clone())),
-        // Temporal tools (3)
+        Arc::new(ValidateGraphTool::new(kb.clone())),
+        Arc::new(HotEntitiesTool::new(kb.clone())),
+        Arc::new(RecentChangesTool::new(kb.clone())),
+        Arc::new(ByUserTool::new(kb.clone())),
+        Arc::new(GraphHashTool::new(kb.clone())),
+        Arc::new(RandomWalkTool::new(kb.clone())),
+        Arc::new(FindDuplicatesTool::new(kb.clone())),
+        Arc::new(DiffEntitiesTool::new(kb.clone())),
+        Arc::new(SuggestRelationsTool::new(kb.clone())),
+        Arc::new(EntityExistsTool::new(kb.clone())),
+        Arc::new(RelationExistsTool::new(kb.clone())),
+        Arc::new(ExportSubgraphTool::new(kb.clone())),
+        Arc::new(WhatLinksHereTool::new(kb.clone())),
+        Arc::new(CompactViewTool::new(kb.clone())),
+        Arc::new(EntityMarkdownTool::new(kb.clone())),
+        Arc::new(QueryRelationsTool::new(kb.clone())),
+        Arc::new(AttributesTool::new(kb.clone())),
+        Arc::new(FindOrphansTool::new(kb.clone())),
+        Arc::new(KPathsTool::new(kb.clone())),
+        Arc::new(GraphSchemaTool::new(kb.clone())),
+        Arc::new(EstimateCostTool::new(kb.clone())),
+        Arc::new(ToolStatsTool::new()),
+        // Temporal tools (6)
         Arc::new(GetRelationsAtTimeTool::new(kb.clone())),
         Arc::new(GetRelationHistoryTool::new(kb.clone())),
         Arc::new(GetCurrentTimeTool::new()),
-        // Inference tools (1)
+        Arc::new(GetTimelineTool::new(kb.clone())),
+        Arc::new(HistorizeRelationsTool::new(kb.clone())),
+        Arc::new(PruneExpiredTool::new(kb.clone())),
+        // Inference tools (2)
         Arc::new(InferTool::new(kb.clone())),
+        Arc::new(ListInferenceRulesTool::new()),
     ]
 }
@@ -0,0 +1,176 @@
+//! Output format of tool responses
+//!
+//! Tools serialize their response bodies with [`to_text`]. Pretty JSON (the
+//! default) is easy to read while debugging, but roughly doubles the token
+//! count for LLM clients; `MEMORY_COMPACT_OUTPUT=true` switches every tool to
+//! compact JSON. A single call can override the server setting with a
+//! boolean `compact` argument, applied by [`execute_tool`] around the call.
+//! Tools ignore the argument otherwise.
+//!
+//! Library users who call tools directly can use [`execute_typed`] to get
+//! the response body back as a typed value instead of MCP content text.
+
+use std::cell::Cell;
+use std::env;
+use std::sync::OnceLock;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::protocol::Tool;
+use crate::types::McpResult;
+
+/// Server default from `MEMORY_COMPACT_OUTPUT`, read once
+static COMPACT_DEFAULT: OnceLock<bool> = OnceLock::new();
+
+thread_local! {
+    /// Per-call override set by `execute_tool`
+    static COMPACT: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// Restores the previous override when the call returns or panics
+struct Restore(Option<bool>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        COMPACT.with(|compact| compact.set(self.0));
+    }
+}
+
+fn compact_default() -> bool {
+    *COMPACT_DEFAULT.get_or_init(|| {
+        env::var("MEMORY_COMPACT_OUTPUT")
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false)
+    })
+}
+
+/// Whether responses of the current call are compact
+pub fn is_compact() -> bool {
+    COMPACT.with(Cell::get).unwrap_or_else(compact_default)
+}
+
+/// Run `call` with compact output on or off (`None` keeps the server setting)
+pub fn with_compact<T>(compact: Option<bool>, call: impl FnOnce() -> T) -> T {
+    let Some(compact) = compact else {
+        return call();
+    };
+    let previous = COMPACT.with(|current| current.replace(Some(compact)));
+    let _restore = Restore(previous);
+    call()
+}
+
+/// JSON text of a tool response body, pretty or compact per the current call
+pub fn to_text(body: &impl Serialize) -> serde_json::Result<String> {
+    if is_compact() {
+        serde_json::to_string(body)
+    } else {
+        serde_json::to_string_pretty(body)
+    }
+}
+
+/// Execute `tool`, honouring a `compact` argument
+pub fn execute_tool(tool: &dyn Tool, arguments: Value) -> McpResult<Value> {
+    let compact = arguments.get("compact").and_then(Value::as_bool);
+    with_compact(compact, || tool.execute(arguments))
+}
+
+/// Execute `tool` and deserialize its response body into `T`
+///
+/// Tools wrap their result as JSON text in MCP `content`; this parses it
+/// back, e.g. into `TraversalResult` for `traverse`, or `Value` for tools
+/// whose response has no dedicated type. Results linked as resources (see
+/// `MEMORY_RESULT_LINK_BYTES`) are not inlined: the error names the URI to
+/// pass to `KnowledgeBase::read_resource`.
+pub fn execute_typed<T: DeserializeOwned>(tool: &dyn Tool, arguments: Value) -> McpResult<T> {
+    let response = with_compact(Some(true), || tool.execute(arguments))?;
+    let content = response["content"].as_array().cloned().unwrap_or_default();
+
+    if let Some(link) = content.iter().find(|c| c["type"] == "resource_link") {
+        return Err(format!(
+            "Result was linked as {}; read it with KnowledgeBase::read_resource",
+            link["uri"].as_str().unwrap_or_default()
+        )
+        .into());
+    }
+    let text = content
+        .iter()
+        .find_map(|c| c["text"].as_str())
+        .ok_or("Tool response has no text content")?;
+    Ok(serde_json::from_str(text)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::knowledge_base::KnowledgeBase;
+    use crate::tools::GraphHashTool;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_compact_argument_overrides_output() {
+        let path = format!("target/test_compact_output_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(
+            path.clone(),
+            "tester".to_string(),
+        ));
+        let tool = GraphHashTool::new(kb);
+        let text = |arguments: Value| {
+            let result = execute_tool(&tool, arguments).unwrap();
+            result["content"][0]["text"].as_str().unwrap().to_string()
+        };
+
+        let compact = text(serde_json::json!({ "compact": true }));
+        assert!(!compact.contains('\n'));
+        let pretty = text(serde_json::json!({ "compact": false }));
+        assert!(pretty.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<Value>(&compact).unwrap(),
+            serde_json::from_str::<Value>(&pretty).unwrap()
+        );
+
+        // The override ends with the call
+        assert!(with_compact(Some(true), is_compact));
+        assert!(!with_compact(Some(false), is_compact));
+        assert_eq!(is_compact(), compact_default());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_execute_typed_parses_the_response_body() {
+        use crate::tools::TraverseTool;
+        use crate::types::{Entity, Relation, TraversalResult};
+
+        let path = format!("target/test_execute_typed_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(
+            path.clone(),
+            "tester".to_string(),
+        ));
+        kb.create_entities(vec![
+            Entity::new("Api".to_string(), "Module".to_string()),
+            Entity::new("Auth".to_string(), "Module".to_string()),
+        ])
+        .unwrap();
+        kb.create_relations(vec![Relation::new(
+            "Api".to_string(),
+            "Auth".to_string(),
+            "depends_on".to_string(),
+        )])
+        .unwrap();
+
+        let result: TraversalResult = execute_typed(
+            &TraverseTool::new(kb),
+            serde_json::json!({
+                "startNode": "Api",
+                "path": [{ "relationType": "depends_on", "direction": "out" }]
+            }),
+        )
+        .unwrap();
+        assert_eq!(result.start_node, "Api");
+        assert_eq!(result.end_nodes[0].name, "Auth");
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,59 @@
+//! Entity attributes tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for reading an entity's `@key=value` and `key: value` observations as attributes
+pub struct AttributesTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl AttributesTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for AttributesTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "get_attributes".to_string(),
+            description: "Get an entity's structured attributes, parsed from '@key=value' observations such as '@owner=alice', and (unless MEMORY_LEGACY_ATTRIBUTES=false) 'Key: value' observations such as 'DueDate: 2024-03-01'. The latest observation wins for repeated keys; other observations are counted as plain.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "entityName": {
+                        "type": "string",
+                        "description": "Name or id of the entity"
+                    }
+                },
+                "required": ["entityName"]
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let entity_name = params
+            .get("entityName")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing entityName")?;
+
+        let attributes = self.kb.get_attributes(entity_name)?;
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&attributes)?
+            }]
+        }))
+    }
+}
@@ -0,0 +1,108 @@
+//! By user tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::{McpResult, UserField};
+
+/// Tool for listing what a specific user created or changed
+pub struct ByUserTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl ByUserTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for ByUserTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "by_user".to_string(),
+            description: "Show what a user created or changed: entities whose createdBy or updatedBy is the user. With Event Sourcing, also lists the user's changes from the event log, newest first, including deletions and relation changes that no longer show in the graph.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "user": {
+                        "type": "string",
+                        "description": "Username as recorded in createdBy / updatedBy"
+                    },
+                    "field": {
+                        "type": "string",
+                        "enum": ["createdBy", "updatedBy"],
+                        "default": "updatedBy",
+                        "description": "Entity field to match the user against"
+                    },
+                    "includeEvents": {
+                        "type": "boolean",
+                        "default": true,
+                        "description": "Also list the user's changes from the event log (Event Sourcing mode only)"
+                    },
+                    "eventLimit": {
+                        "type": "integer",
+                        "default": 100,
+                        "description": "Maximum number of event log changes to return"
+                    }
+                },
+                "required": ["user"]
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let user = params
+            .get("user")
+            .and_then(|v| v.as_str())
+            .filter(|u| !u.is_empty())
+            .ok_or("Missing user")?;
+        let field = match params.get("field").and_then(|v| v.as_str()) {
+            Some(value) => UserField::parse(value).ok_or_else(|| {
+                format!(
+                    "Invalid field '{}': expected 'createdBy' or 'updatedBy'",
+                    value
+                )
+            })?,
+            None => UserField::default(),
+        };
+        let include_events = params
+            .get("includeEvents")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+        let event_limit = params
+            .get("eventLimit")
+            .and_then(|v| v.as_u64())
+            .map_or(100, |v| v as usize);
+
+        let entities = self.kb.entities_by_user(user, field);
+        let mut result = json!({
+            "user": user,
+            "field": match field {
+                UserField::CreatedBy => "createdBy",
+                UserField::UpdatedBy => "updatedBy",
+            },
+            "count": entities.len(),
+            "entities": entities
+        });
+        if include_events {
+            if let Some(events) = self.kb.events_by_user(user, event_limit)? {
+                result["events"] = json!(events);
+            }
+        }
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&result)?
+            }]
+        }))
+    }
+}
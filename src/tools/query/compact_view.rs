@@ -0,0 +1,69 @@
+//! Compact observations tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for reading a token-saving view of an entity's observations
+pub struct CompactViewTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl CompactViewTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for CompactViewTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "compact_observations".to_string(),
+            description: "Get a compact view of an entity's observations for building context: near-identical observations are merged, only the latest status/priority attribute ('@status=done' or 'Status: done') is kept, and the rest are capped to the most informative ones. Read-only; nothing is deleted from the graph.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "entityName": {
+                        "type": "string",
+                        "description": "Entity to compact"
+                    },
+                    "maxObservations": {
+                        "type": "integer",
+                        "default": 10,
+                        "description": "Maximum number of observations to return"
+                    }
+                },
+                "required": ["entityName"]
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let entity_name = params
+            .get("entityName")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing entityName")?;
+        let max_observations = params
+            .get("maxObservations")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(10);
+
+        let view = self.kb.compact_view(entity_name, max_observations)?;
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&view)?
+            }]
+        }))
+    }
+}
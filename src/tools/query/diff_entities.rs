@@ -0,0 +1,62 @@
+//! Diff entities tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for comparing two entities before merging them
+pub struct DiffEntitiesTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl DiffEntitiesTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for DiffEntitiesTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "diff_entities".to_string(),
+            description: "Compare two entities, e.g. a pair reported by find_duplicates: whether their types match, and which observations and relations (type, direction, other entity) they share or have alone. Use it to decide whether merging would lose or combine anything; nothing is changed.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "a": { "type": "string", "description": "First entity (name or id)" },
+                    "b": { "type": "string", "description": "Second entity (name or id)" }
+                },
+                "required": ["a", "b"]
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let a = params
+            .get("a")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing a")?;
+        let b = params
+            .get("b")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing b")?;
+
+        let diff = self.kb.diff_entities(a, b)?;
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&diff)?
+            }]
+        }))
+    }
+}
@@ -0,0 +1,57 @@
+//! Entity exists tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for checking whether entities exist without returning their contents
+pub struct EntityExistsTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl EntityExistsTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for EntityExistsTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "entity_exists".to_string(),
+            description: "Check whether entities exist by name. Returns a name to boolean map without entity payloads; cheaper than open_nodes when you only need to know if something exists.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "names": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Entity names to check"
+                    }
+                },
+                "required": ["names"]
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let names: Vec<String> =
+            serde_json::from_value(params.get("names").cloned().unwrap_or(json!([])))?;
+        let exists = self.kb.entity_exists(&names);
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&exists)?
+            }]
+        }))
+    }
+}
@@ -0,0 +1,58 @@
+//! Entity Markdown tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::types::McpResult;
+
+/// Tool for rendering one entity as a Markdown section
+pub struct EntityMarkdownTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl EntityMarkdownTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for EntityMarkdownTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "entity_markdown".to_string(),
+            description: "Render one entity as a Markdown section for documentation: a heading with its name and type, its observations as a bullet list, and a Relationships subsection listing outgoing and incoming relations by type with the neighbor names. Returns raw Markdown, not JSON.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "entityName": {
+                        "type": "string",
+                        "description": "Entity to render (name or id)"
+                    }
+                },
+                "required": ["entityName"]
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let entity_name = params
+            .get("entityName")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing entityName")?;
+
+        let markdown = self.kb.entity_to_markdown(entity_name)?;
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": markdown
+            }]
+        }))
+    }
+}
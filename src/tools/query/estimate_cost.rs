@@ -0,0 +1,108 @@
+//! Query cost estimate tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::{InvalidParams, McpResult, PathStep};
+
+/// Tool for estimating how many nodes a traverse or infer call would visit
+pub struct EstimateCostTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl EstimateCostTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for EstimateCostTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "estimate_cost".to_string(),
+            description: "Estimate how many nodes a traverse or infer call would visit, from the graph size and the requested depth and breadth, without running it. Reports whether the call fits the server's visit budget; calls over it are refused.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "operation": {
+                        "type": "string",
+                        "enum": ["traverse", "infer"],
+                        "description": "Operation to estimate"
+                    },
+                    "path": {
+                        "type": "array",
+                        "items": { "type": "object" },
+                        "description": "traverse: path pattern, as for the traverse tool"
+                    },
+                    "maxResults": {
+                        "type": "integer",
+                        "default": 50,
+                        "description": "traverse: maximum number of results"
+                    },
+                    "maxDepth": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": 5,
+                        "default": 3,
+                        "description": "infer: maximum traversal depth"
+                    },
+                    "rules": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "infer: names of the rules to apply (default: all rules)"
+                    }
+                },
+                "required": ["operation"]
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let operation = params
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing operation")?;
+
+        let cost = match operation {
+            "traverse" => {
+                let path: Vec<PathStep> =
+                    serde_json::from_value(params.get("path").cloned().unwrap_or(json!([])))
+                        .map_err(|e| InvalidParams(format!("Invalid path: {}", e)))?;
+                let max_results = params
+                    .get("maxResults")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(50) as usize;
+                self.kb.estimate_traverse(&path, max_results)
+            }
+            "infer" => {
+                let max_depth = params
+                    .get("maxDepth")
+                    .and_then(|v| v.as_u64())
+                    .map(|v| v as usize)
+                    .unwrap_or(3)
+                    .clamp(1, 5);
+                let rules: Option<Vec<String>> = params
+                    .get("rules")
+                    .map(|rules| serde_json::from_value(rules.clone()))
+                    .transpose()?;
+                self.kb.estimate_infer(max_depth, rules.as_deref())?
+            }
+            other => return Err(format!("Unknown operation: {}", other).into()),
+        };
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&cost)?
+            }]
+        }))
+    }
+}
@@ -0,0 +1,88 @@
+//! Export subgraph tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for exporting the neighborhood of an entity as a standalone graph
+pub struct ExportSubgraphTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl ExportSubgraphTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for ExportSubgraphTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "export_subgraph".to_string(),
+            description: "Export every entity within N hops of a center entity (following relations in both directions) plus the relations between them, as a self-contained graph. The jsonl format matches memory.jsonl, so the export can be loaded by another server or session.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "center": {
+                        "type": "string",
+                        "description": "Entity at the center of the subgraph"
+                    },
+                    "depth": {
+                        "type": "integer",
+                        "default": 2,
+                        "description": "Maximum number of hops from the center (max 5)"
+                    },
+                    "format": {
+                        "type": "string",
+                        "enum": ["json", "jsonl"],
+                        "default": "json",
+                        "description": "Output as a JSON graph document or as memory.jsonl lines"
+                    }
+                },
+                "required": ["center"]
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let center = params
+            .get("center")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing center")?;
+
+        let depth = params
+            .get("depth")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(2)
+            .min(5);
+
+        let format = params
+            .get("format")
+            .and_then(|v| v.as_str())
+            .unwrap_or("json");
+
+        let subgraph = self.kb.get_subgraph(center, depth)?;
+        let text = match format {
+            "jsonl" => subgraph.to_jsonl()?,
+            "json" => to_text(&subgraph)?,
+            other => return Err(format!("Unknown format: {}", other).into()),
+        };
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": text
+            }]
+        }))
+    }
+}
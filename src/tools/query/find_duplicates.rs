@@ -0,0 +1,71 @@
+//! Find duplicates tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for suggesting entities that are likely duplicates of each other
+pub struct FindDuplicatesTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl FindDuplicatesTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for FindDuplicatesTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "find_duplicates".to_string(),
+            description: "Suggest pairs of entities that are likely duplicates: same entity type and a similar name, or highly overlapping observations or neighbors. Only entities sharing a type and name prefix are compared. Returns candidate pairs with a similarity score; nothing is changed.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "threshold": {
+                        "type": "number",
+                        "default": 0.8,
+                        "description": "Minimum similarity (0.0 - 1.0) for a pair to be reported"
+                    }
+                },
+                "required": []
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let threshold = params
+            .get("threshold")
+            .and_then(|v| v.as_f64())
+            .map(|v| v as f32)
+            .unwrap_or(0.8)
+            .clamp(0.0, 1.0);
+
+        let candidates: Vec<Value> = self
+            .kb
+            .find_duplicates(threshold)
+            .into_iter()
+            .map(|(a, b, score)| json!({ "a": a, "b": b, "score": score }))
+            .collect();
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&json!({
+                    "count": candidates.len(),
+                    "candidates": candidates
+                }))?
+            }]
+        }))
+    }
+}
@@ -0,0 +1,62 @@
+//! Find orphans tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for listing entities without any relation
+pub struct FindOrphansTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl FindOrphansTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for FindOrphansTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "find_orphans".to_string(),
+            description: "List entities with no incoming or outgoing relations. Orphans are often incomplete or stale; nothing is changed.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "entityType": {
+                        "type": "string",
+                        "description": "Only list orphans of this type (e.g. Bug)"
+                    }
+                },
+                "required": []
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let entity_type = params
+            .get("entityType")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+
+        let orphans = self.kb.find_orphans(entity_type);
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&json!({
+                    "count": orphans.len(),
+                    "orphans": orphans
+                }))?
+            }]
+        }))
+    }
+}
@@ -5,8 +5,11 @@ use std::sync::Arc;
 use serde_json::{json, Value};
 
 use crate::knowledge_base::KnowledgeBase;
-use crate::protocol::{McpTool, Tool};
-use crate::types::McpResult;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::{McpResult, RelatedOptions, RelatedPage, RelatedSort};
+
+use super::{property_filter_schema, property_filters};
 
 /// Tool for getting entities related to a specific entity
 pub struct GetRelatedTool {
@@ -29,7 +32,7 @@ impl Tool for GetRelatedTool {
                 "properties": {
                     "entityName": {
                         "type": "string",
-                        "description": "Name of the entity to find relations for"
+                        "description": "Name or id of the entity to find relations for"
                     },
                     "relationType": {
                         "type": "string",
@@ -40,6 +43,27 @@ impl Tool for GetRelatedTool {
                         "enum": ["outgoing", "incoming", "both"],
                         "default": "both",
                         "description": "Direction of relations"
+                    },
+                    "collapseReciprocal": {
+                        "type": "boolean",
+                        "default": true,
+                        "description": "For direction 'both', list entities related in both directions by the same relation type once, marked 'bidirectional'. Set false for the expanded form."
+                    },
+                    "propertyFilter": property_filter_schema(),
+                    "sort": {
+                        "type": "string",
+                        "enum": ["graph", "name", "entityType", "relationType"],
+                        "default": "graph",
+                        "description": "Order of results: graph order, or by related entity name, entity type or relation type. Applied before offset/limit."
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum number of related entities to return (default: all)"
+                    },
+                    "offset": {
+                        "type": "integer",
+                        "default": 0,
+                        "description": "Number of sorted related entities to skip. 'total' in the result counts them all."
                     }
                 },
                 "required": ["entityName"]
@@ -47,6 +71,10 @@ impl Tool for GetRelatedTool {
         }
     }
 
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
     fn execute(&self, params: Value) -> McpResult<Value> {
         let entity_name = params
             .get("entityName")
@@ -57,12 +85,41 @@ impl Tool for GetRelatedTool {
             .get("direction")
             .and_then(|v| v.as_str())
             .unwrap_or("both");
+        let collapse_reciprocal = params
+            .get("collapseReciprocal")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+        let properties = property_filters(&params)?;
+        let sort = match params.get("sort").and_then(|v| v.as_str()) {
+            Some(sort) => {
+                RelatedSort::parse(sort).ok_or_else(|| format!("Unknown sort '{}'", sort))?
+            }
+            None => RelatedSort::default(),
+        };
+        let page = RelatedPage {
+            sort,
+            offset: params
+                .get("offset")
+                .and_then(|v| v.as_u64())
+                .map_or(0, |v| v as usize),
+            limit: params
+                .get("limit")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize),
+        };
 
-        let related = self.kb.get_related(entity_name, relation_type, direction)?;
+        let options = RelatedOptions {
+            relation_type: relation_type.map(String::from),
+            direction: direction.to_string(),
+            collapse_reciprocal,
+            properties,
+            page,
+        };
+        let related = self.kb.get_related(entity_name, &options)?;
         Ok(json!({
             "content": [{
                 "type": "text",
-                "text": serde_json::to_string_pretty(&related)?
+                "text": to_text(&related)?
             }]
         }))
     }
@@ -0,0 +1,53 @@
+//! Graph content hash tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for fingerprinting the graph content
+pub struct GraphHashTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl GraphHashTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for GraphHashTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "graph_hash".to_string(),
+            description: "Get a fingerprint of the whole graph content: the same for any two graphs with identical entities, relations and observations, whatever order they were added in. Timestamps and authors are not part of it. Compare hashes to check that replicas or caches are in sync.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {},
+                "required": []
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, _params: Value) -> McpResult<Value> {
+        let graph_version = self.kb.version();
+        let result = json!({
+            "hash": self.kb.content_hash()?,
+            "graphVersion": graph_version
+        });
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&result)?
+            }]
+        }))
+    }
+}
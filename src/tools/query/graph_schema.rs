@@ -0,0 +1,49 @@
+//! Graph schema tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for inferring entity types and how relation types connect them
+pub struct GraphSchemaTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl GraphSchemaTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for GraphSchemaTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "get_graph_schema".to_string(),
+            description: "Infer the graph's de-facto schema: entity types with counts, and which relation types connect which entity-type pairs (e.g. Bug -affects-> Module), most frequent first. Useful before querying a graph you did not build.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {},
+                "required": []
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, _params: Value) -> McpResult<Value> {
+        let schema = self.kb.infer_schema();
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&schema)?
+            }]
+        }))
+    }
+}
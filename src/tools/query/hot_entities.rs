@@ -0,0 +1,61 @@
+//! Hot entities tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for listing the most frequently read entities since startup
+pub struct HotEntitiesTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl HotEntitiesTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for HotEntitiesTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "hot_entities".to_string(),
+            description: "List the most accessed entities (returned by open_nodes, search_nodes or get_related) since the server started. Useful for prioritizing context; counts are in-memory and reset on restart.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "limit": {
+                        "type": "integer",
+                        "default": 10,
+                        "description": "Maximum number of entities to return"
+                    }
+                },
+                "required": []
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let limit = params
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(10);
+
+        let hot = self.kb.hot_entities(limit);
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&hot)?
+            }]
+        }))
+    }
+}
@@ -0,0 +1,82 @@
+//! K shortest paths tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::{McpResult, MAX_K_PATHS, MAX_PATH_DEPTH};
+
+/// Tool for listing alternative paths between two entities
+pub struct KPathsTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl KPathsTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for KPathsTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "k_shortest_paths".to_string(),
+            description: "Find up to k shortest paths between two entities, shortest first, following relations in either direction. Each path visits an entity at most once; alternatives show how else two entities are connected. Returns no paths if they are not connected within maxDepth.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "string",
+                        "description": "Start entity name or id"
+                    },
+                    "to": {
+                        "type": "string",
+                        "description": "End entity name or id"
+                    },
+                    "k": {
+                        "type": "integer",
+                        "description": format!("Maximum number of paths (default: 3, max: {})", MAX_K_PATHS),
+                        "default": 3
+                    },
+                    "maxDepth": {
+                        "type": "integer",
+                        "description": format!("Maximum path length in hops (default: 6, max: {})", MAX_PATH_DEPTH),
+                        "default": 6
+                    }
+                },
+                "required": ["from", "to"]
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let from = params
+            .get("from")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing from")?;
+        let to = params
+            .get("to")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing to")?;
+        let k = params.get("k").and_then(|v| v.as_u64()).unwrap_or(3) as usize;
+        let max_depth = params.get("maxDepth").and_then(|v| v.as_u64()).unwrap_or(6) as usize;
+
+        let paths = self.kb.k_shortest_paths(from, to, k, max_depth)?;
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&json!({
+                    "count": paths.len(),
+                    "paths": paths
+                }))?
+            }]
+        }))
+    }
+}
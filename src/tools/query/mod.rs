@@ -1,11 +1,88 @@
 //! Query tools for graph traversal and search
 //!
-//! This module contains 3 tools for advanced graph operations.
+//! This module contains 25 tools for advanced graph operations.
 
+mod attributes;
+mod by_user;
+mod compact_view;
+mod diff_entities;
+mod entity_exists;
+mod entity_markdown;
+mod estimate_cost;
+mod export_subgraph;
+mod find_duplicates;
+mod find_orphans;
 mod get_related;
+mod graph_hash;
+mod graph_schema;
+mod hot_entities;
+mod k_paths;
+mod query_relations;
+mod random_walk;
+mod recent_changes;
+mod relation_exists;
+mod suggest_relations;
 mod summarize;
+mod tool_stats;
 mod traverse;
+mod validate_graph;
+mod what_links_here;
 
+pub use attributes::AttributesTool;
+pub use by_user::ByUserTool;
+pub use compact_view::CompactViewTool;
+pub use diff_entities::DiffEntitiesTool;
+pub use entity_exists::EntityExistsTool;
+pub use entity_markdown::EntityMarkdownTool;
+pub use estimate_cost::EstimateCostTool;
+pub use export_subgraph::ExportSubgraphTool;
+pub use find_duplicates::FindDuplicatesTool;
+pub use find_orphans::FindOrphansTool;
 pub use get_related::GetRelatedTool;
+pub use graph_hash::GraphHashTool;
+pub use graph_schema::GraphSchemaTool;
+pub use hot_entities::HotEntitiesTool;
+pub use k_paths::KPathsTool;
+pub use query_relations::QueryRelationsTool;
+pub use random_walk::RandomWalkTool;
+pub use recent_changes::RecentChangesTool;
+pub use relation_exists::RelationExistsTool;
+pub use suggest_relations::SuggestRelationsTool;
 pub use summarize::SummarizeTool;
+pub use tool_stats::ToolStatsTool;
 pub use traverse::TraverseTool;
+pub use validate_graph::ValidateGraphTool;
+pub use what_links_here::WhatLinksHereTool;
+
+use serde_json::{json, Value};
+
+use crate::types::{McpResult, PropertyFilter};
+
+/// Schema of the `propertyFilter` parameter shared by relation queries
+pub(crate) fn property_filter_schema() -> Value {
+    json!({
+        "description": "Filter relations by properties: an object of key/value pairs that must be equal (e.g. {\"critical\": true}), or a list of {key, op, value} predicates with op one of eq, ne, gt, gte, lt, lte, exists, missing. Numbers compare numerically, strings lexicographically; values of different types never compare equal.",
+        "oneOf": [
+            { "type": "object" },
+            {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "key": { "type": "string" },
+                        "op": { "type": "string", "enum": ["eq", "ne", "gt", "gte", "lt", "lte", "exists", "missing"], "default": "eq" },
+                        "value": {}
+                    },
+                    "required": ["key"]
+                }
+            }
+        ]
+    })
+}
+
+/// Parse the `propertyFilter` parameter (no filters when absent)
+pub(crate) fn property_filters(params: &Value) -> McpResult<Vec<PropertyFilter>> {
+    Ok(PropertyFilter::parse_list(
+        params.get("propertyFilter").unwrap_or(&Value::Null),
+    )?)
+}
@@ -0,0 +1,145 @@
+//! Query relations tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::{McpResult, RelationQuery};
+
+use super::{property_filter_schema, property_filters};
+
+/// Tool for finding relations by endpoints, type and properties
+pub struct QueryRelationsTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl QueryRelationsTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for QueryRelationsTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "query_relations".to_string(),
+            description: "Find relations by source, target, type and property predicates, e.g. all depends_on relations where critical=true. Every given filter must match.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "string",
+                        "description": "Source entity name or id"
+                    },
+                    "to": {
+                        "type": "string",
+                        "description": "Target entity name or id"
+                    },
+                    "relationType": {
+                        "type": "string",
+                        "description": "Relation type"
+                    },
+                    "propertyFilter": property_filter_schema()
+                },
+                "required": []
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let text = |key: &str| params.get(key).and_then(|v| v.as_str()).map(String::from);
+        let query = RelationQuery {
+            from: text("from"),
+            to: text("to"),
+            relation_type: text("relationType"),
+            properties: property_filters(&params)?,
+        };
+
+        let relations = self.kb.query_relations(&query)?;
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&json!({
+                    "total": relations.len(),
+                    "relations": relations
+                }))?
+            }]
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Entity, RelatedOptions, Relation};
+
+    #[test]
+    fn test_query_relations_by_property() {
+        let path = format!("target/test_query_relations_{}.jsonl", std::process::id());
+        let kb = Arc::new(KnowledgeBase::for_testing(
+            path.clone(),
+            "tester".to_string(),
+        ));
+        kb.create_entities(
+            ["Api", "Auth", "Cache"]
+                .map(|n| Entity::new(n.to_string(), "Module".to_string()))
+                .to_vec(),
+        )
+        .unwrap();
+        let relation = |to: &str, properties: Value| Relation {
+            properties: serde_json::from_value(properties).unwrap(),
+            ..Relation::new("Api".to_string(), to.to_string(), "depends_on".to_string())
+        };
+        kb.create_relations(vec![
+            relation("Auth", json!({"critical": true, "weight": 0.9})),
+            relation("Cache", json!({"critical": false, "weight": 0.2})),
+        ])
+        .unwrap();
+
+        let tool = QueryRelationsTool::new(Arc::clone(&kb));
+        let query = |params: Value| -> Value {
+            let result = tool.execute(params).unwrap();
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap()
+        };
+
+        let critical = query(json!({
+            "relationType": "depends_on",
+            "propertyFilter": {"critical": true}
+        }));
+        assert_eq!(critical["total"], 1);
+        assert_eq!(critical["relations"][0]["to"], "Auth");
+        assert_eq!(critical["relations"][0]["properties"]["weight"], 0.9);
+
+        let light = query(json!({
+            "from": "Api",
+            "propertyFilter": [{"key": "weight", "op": "<", "value": 0.5}]
+        }));
+        assert_eq!(light["total"], 1);
+        assert_eq!(light["relations"][0]["to"], "Cache");
+
+        let related = kb
+            .get_related(
+                "Api",
+                &RelatedOptions {
+                    direction: "outgoing".to_string(),
+                    properties: property_filters(&json!({
+                        "propertyFilter": [{"key": "weight", "op": "gte", "value": 0.5}]
+                    }))
+                    .unwrap(),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(related.relations.len(), 1);
+        assert!(tool.execute(json!({"propertyFilter": "critical"})).is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,103 @@
+//! Random walk tool
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Upper bound on walk length to keep responses small
+const MAX_STEPS: usize = 1000;
+
+/// Tool for sampling a large graph with a seeded random walk
+pub struct RandomWalkTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl RandomWalkTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for RandomWalkTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "random_walk".to_string(),
+            description: "Sample a representative set of entities by walking outgoing relations at random. The walk restarts from the start entity with probability restartProb or at dead-ends. The same seed always yields the same walk. Useful for getting context from a graph too large to read whole.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "start": {
+                        "type": "string",
+                        "description": "Entity to start from (default: chosen from the seed)"
+                    },
+                    "steps": {
+                        "type": "integer",
+                        "default": 20,
+                        "description": "Number of steps to take (max 1000)"
+                    },
+                    "restartProb": {
+                        "type": "number",
+                        "default": 0.15,
+                        "description": "Probability of jumping back to the start at each step (0.0 - 1.0)"
+                    },
+                    "seed": {
+                        "type": "integer",
+                        "default": 0,
+                        "description": "Seed for reproducible walks"
+                    }
+                },
+                "required": []
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let start = params.get("start").and_then(|v| v.as_str());
+
+        let steps = params
+            .get("steps")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(20)
+            .min(MAX_STEPS);
+
+        let restart_prob = params
+            .get("restartProb")
+            .and_then(|v| v.as_f64())
+            .map(|v| v as f32)
+            .unwrap_or(0.15)
+            .clamp(0.0, 1.0);
+
+        let seed = params.get("seed").and_then(|v| v.as_u64()).unwrap_or(0);
+
+        let walk = self.kb.random_walk(start, steps, restart_prob, seed);
+        if walk.is_empty() {
+            if let Some(name) = start {
+                return Err(format!("Entity not found: {}", name).into());
+            }
+        }
+
+        let mut seen = HashSet::new();
+        let distinct: Vec<&String> = walk.iter().filter(|n| seen.insert(n.as_str())).collect();
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&json!({
+                    "walk": walk,
+                    "distinct": distinct
+                }))?
+            }]
+        }))
+    }
+}
@@ -0,0 +1,66 @@
+//! Recent changes tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for listing the latest mutations kept in memory
+pub struct RecentChangesTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl RecentChangesTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for RecentChangesTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "recent_changes".to_string(),
+            description: "List the latest changes to the graph (type, entity or relation, user, time), newest first. A lightweight audit trail for debugging 'who changed this': it is kept in memory only, holds a bounded number of changes (MEMORY_RECENT_CHANGES) and is lost on restart.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "limit": {
+                        "type": "integer",
+                        "default": 50,
+                        "description": "Maximum number of changes to return"
+                    },
+                    "entity": {
+                        "type": "string",
+                        "description": "Only changes to this entity or its relations"
+                    }
+                },
+                "required": []
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let limit = params
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(50);
+        let entity = params.get("entity").and_then(|v| v.as_str());
+
+        let changes = self.kb.recent_changes(limit, entity);
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&changes)?
+            }]
+        }))
+    }
+}
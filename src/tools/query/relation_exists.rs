@@ -0,0 +1,80 @@
+//! Relation exists tool
+
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// A relation to look up, identified by its endpoints and type
+#[derive(Deserialize)]
+struct RelationKey {
+    from: String,
+    to: String,
+    #[serde(rename = "relationType")]
+    relation_type: String,
+}
+
+/// Tool for checking whether relations exist without returning their contents
+pub struct RelationExistsTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl RelationExistsTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for RelationExistsTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "relation_exists".to_string(),
+            description: "Check whether relations exist. Returns one boolean per requested relation, in the same order.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "relations": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "from": { "type": "string" },
+                                "to": { "type": "string" },
+                                "relationType": { "type": "string" }
+                            },
+                            "required": ["from", "to", "relationType"]
+                        },
+                        "description": "Relations to check"
+                    }
+                },
+                "required": ["relations"]
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let keys: Vec<RelationKey> =
+            serde_json::from_value(params.get("relations").cloned().unwrap_or(json!([])))?;
+        let triples: Vec<(String, String, String)> = keys
+            .into_iter()
+            .map(|k| (k.from, k.to, k.relation_type))
+            .collect();
+
+        let exists = self.kb.relation_exists(&triples);
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&exists)?
+            }]
+        }))
+    }
+}
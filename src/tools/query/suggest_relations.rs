@@ -0,0 +1,73 @@
+//! Suggest relations tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for suggesting relations from observation terms entities share
+pub struct SuggestRelationsTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl SuggestRelationsTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for SuggestRelationsTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "suggest_relations".to_string(),
+            description: "Suggest entities that are probably related to an entity because their observations share distinctive terms (weighted by how rare each term is across the graph). Entities already related to it are left out. Returns candidates with a score (0.0 - 1.0) and the shared terms as evidence; nothing is created. Use it to densify a sparse graph, then create_relations for the ones that hold.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "entityName": {
+                        "type": "string",
+                        "description": "Name or id of the entity to find candidates for"
+                    },
+                    "topN": {
+                        "type": "integer",
+                        "default": 10,
+                        "description": "Maximum number of suggestions"
+                    }
+                },
+                "required": ["entityName"]
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let entity_name = params
+            .get("entityName")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing entityName")?;
+        let top_n = params
+            .get("topN")
+            .and_then(|v| v.as_u64())
+            .map_or(10, |v| v as usize);
+
+        let suggestions = self.kb.suggest_relations(entity_name, top_n)?;
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&json!({
+                    "entity": entity_name,
+                    "count": suggestions.len(),
+                    "suggestions": suggestions
+                }))?
+            }]
+        }))
+    }
+}
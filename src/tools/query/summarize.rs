@@ -5,7 +5,8 @@ use std::sync::Arc;
 use serde_json::{json, Value};
 
 use crate::knowledge_base::KnowledgeBase;
-use crate::protocol::{McpTool, Tool};
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
 use crate::types::McpResult;
 
 /// Tool for getting a condensed summary of entities
@@ -40,7 +41,12 @@ impl Tool for SummarizeTool {
                         "type": "string",
                         "enum": ["brief", "detailed", "stats"],
                         "default": "brief",
-                        "description": "Output format: brief (first observation), detailed (all observations), stats (statistics)"
+                        "description": "Output format: brief (first observation), detailed (all observations), stats (counts by type, status, priority and relation type, plus the most connected entities)"
+                    },
+                    "groupBy": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "For stats: attribute keys to count entities by, read from '@key=value' or 'Key: value' observations (e.g. [\"Owner\"])"
                     }
                 },
                 "required": []
@@ -48,6 +54,10 @@ impl Tool for SummarizeTool {
         }
     }
 
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
     fn execute(&self, params: Value) -> McpResult<Value> {
         let entity_names: Option<Vec<String>> = params
             .get("entityNames")
@@ -63,11 +73,18 @@ impl Tool for SummarizeTool {
             .and_then(|v| v.as_str())
             .unwrap_or("brief");
 
-        let summary = self.kb.summarize(entity_names, entity_type, format)?;
+        let group_by: Vec<String> = params
+            .get("groupBy")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+
+        let summary = self
+            .kb
+            .summarize_grouped(entity_names, entity_type, format, &group_by)?;
         Ok(json!({
             "content": [{
                 "type": "text",
-                "text": serde_json::to_string_pretty(&summary)?
+                "text": to_text(&summary)?
             }]
         }))
     }
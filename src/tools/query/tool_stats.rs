@@ -0,0 +1,66 @@
+//! Tool usage statistics tool
+
+use serde_json::{json, Value};
+
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::{to_text, tool_stats};
+use crate::types::McpResult;
+
+/// Tool for reporting how often each tool was called and how it fared
+pub struct ToolStatsTool;
+
+impl ToolStatsTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ToolStatsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tool for ToolStatsTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "tool_stats".to_string(),
+            description: "Get usage statistics of the tools on this server since it started: invocations, successes, errors, error rate and latency (total, average, p50 and p99 over recent calls) per tool, most used first. Tools never called are not listed. Counters reset on restart.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "tool": {
+                        "type": "string",
+                        "description": "Only report this tool"
+                    }
+                },
+                "required": []
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let only = params.get("tool").and_then(|v| v.as_str());
+        let tools: Vec<_> = tool_stats()
+            .snapshot()
+            .into_iter()
+            .filter(|usage| only.is_none_or(|name| usage.name == name))
+            .collect();
+
+        let result = json!({
+            "invocations": tools.iter().map(|t| t.invocations).sum::<u64>(),
+            "errors": tools.iter().map(|t| t.errors).sum::<u64>(),
+            "tools": tools
+        });
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&result)?
+            }]
+        }))
+    }
+}
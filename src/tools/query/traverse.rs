@@ -5,8 +5,9 @@ use std::sync::Arc;
 use serde_json::{json, Value};
 
 use crate::knowledge_base::KnowledgeBase;
-use crate::protocol::{McpTool, Tool};
-use crate::types::{McpResult, PathStep};
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::{InvalidParams, McpResult, PathStep};
 
 /// Tool for traversing the graph following a path pattern
 pub struct TraverseTool {
@@ -23,14 +24,13 @@ impl Tool for TraverseTool {
     fn definition(&self) -> McpTool {
         McpTool {
             name: "traverse".to_string(),
-            description: "Traverse the graph following a path pattern for multi-hop queries"
-                .to_string(),
+            description: "Traverse the graph following a path pattern for multi-hop queries. A step with minHops/maxHops matches a variable number of hops, like a Cypher variable-length pattern. A path may have at most 10 steps and 10 hops in total (counting each step's maxHops), unless the server sets MEMORY_MAX_TRAVERSE_DEPTH.".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
                     "startNode": {
                         "type": "string",
-                        "description": "Starting entity name"
+                        "description": "Starting entity name or id"
                     },
                     "path": {
                         "type": "array",
@@ -43,15 +43,25 @@ impl Tool for TraverseTool {
                                 },
                                 "direction": {
                                     "type": "string",
-                                    "enum": ["out", "in"],
-                                    "description": "Direction: out (outgoing) or in (incoming)"
+                                    "enum": ["out", "in", "both", "outgoing", "incoming"],
+                                    "description": "Direction: out (alias outgoing), in (alias incoming) or both. Default: out, unless the server sets MEMORY_DEFAULT_DIRECTION"
                                 },
                                 "targetType": {
                                     "type": "string",
                                     "description": "Filter by target entity type (optional)"
+                                },
+                                "minHops": {
+                                    "type": "integer",
+                                    "minimum": 0,
+                                    "description": "Minimum repetitions of this step (default: 1)"
+                                },
+                                "maxHops": {
+                                    "type": "integer",
+                                    "maximum": 10,
+                                    "description": "Maximum repetitions of this step (default: minHops or 1)"
                                 }
                             },
-                            "required": ["relationType", "direction"]
+                            "required": ["relationType"]
                         },
                         "description": "Path pattern to follow"
                     },
@@ -59,6 +69,11 @@ impl Tool for TraverseTool {
                         "type": "integer",
                         "default": 50,
                         "description": "Maximum number of results"
+                    },
+                    "includeRelationDetails": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Add relationDetails to each path: the relation each hop followed, with its properties, validity and timestamps. Parallel relations of one type between the same nodes then show up as separate paths"
                     }
                 },
                 "required": ["startNode", "path"]
@@ -66,6 +81,10 @@ impl Tool for TraverseTool {
         }
     }
 
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
     fn execute(&self, params: Value) -> McpResult<Value> {
         let start_node = params
             .get("startNode")
@@ -73,18 +92,29 @@ impl Tool for TraverseTool {
             .ok_or("Missing startNode")?;
 
         let path: Vec<PathStep> =
-            serde_json::from_value(params.get("path").cloned().unwrap_or(json!([])))?;
+            serde_json::from_value(params.get("path").cloned().unwrap_or(json!([])))
+                .map_err(|e| InvalidParams(format!("Invalid path: {}", e)))?;
 
         let max_results = params
             .get("maxResults")
             .and_then(|v| v.as_u64())
             .unwrap_or(50) as usize;
 
-        let result = self.kb.traverse(start_node, path, max_results)?;
+        let include_relation_details = params
+            .get("includeRelationDetails")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let result = if include_relation_details {
+            self.kb
+                .traverse_with_relation_details(start_node, path, max_results)?
+        } else {
+            self.kb.traverse(start_node, path, max_results)?
+        };
         Ok(json!({
             "content": [{
                 "type": "text",
-                "text": serde_json::to_string_pretty(&result)?
+                "text": to_text(&result)?
             }]
         }))
     }
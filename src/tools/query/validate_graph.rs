@@ -0,0 +1,77 @@
+//! Validate graph tool
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+use crate::validation::{ValidationWarning, ValidationWarningKind};
+
+/// Tool for scanning the whole graph and reporting all validation warnings
+pub struct ValidateGraphTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl ValidateGraphTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for ValidateGraphTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "validate_graph".to_string(),
+            description: "Scan the entire knowledge graph and report all validation warnings (non-standard types, missing fields, dangling relations) grouped by kind. Useful for cleaning up after bulk imports.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {},
+                "required": []
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, _params: Value) -> McpResult<Value> {
+        let warnings = self.kb.validate_all();
+        let total = warnings.len();
+
+        // Group by kind so the report is actionable
+        let mut by_kind: BTreeMap<ValidationWarningKind, Vec<ValidationWarning>> = BTreeMap::new();
+        for warning in warnings {
+            by_kind.entry(warning.kind).or_default().push(warning);
+        }
+
+        let groups: serde_json::Map<String, Value> = by_kind
+            .into_iter()
+            .map(|(kind, items)| {
+                let key = serde_json::to_value(kind)?
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string();
+                let items: Vec<Value> = items
+                    .into_iter()
+                    .map(|w| json!({ "subject": w.subject, "message": w.message }))
+                    .collect();
+                Ok((key, json!({ "count": items.len(), "items": items })))
+            })
+            .collect::<McpResult<_>>()?;
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&json!({
+                    "totalWarnings": total,
+                    "byKind": groups
+                }))?
+            }]
+        }))
+    }
+}
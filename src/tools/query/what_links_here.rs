@@ -0,0 +1,59 @@
+//! What links here tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for impact analysis: who references an entity, aggregated by type
+pub struct WhatLinksHereTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl WhatLinksHereTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for WhatLinksHereTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "what_links_here".to_string(),
+            description: "List everything that references an entity through incoming relations, grouped by relation type and source entity type with counts. Useful for impact analysis, e.g. what depends on a module.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "entityName": {
+                        "type": "string",
+                        "description": "Name or id of the referenced entity"
+                    }
+                },
+                "required": ["entityName"]
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let entity_name = params
+            .get("entityName")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing entityName")?;
+
+        let references = self.kb.references_to(entity_name)?;
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&references)?
+            }]
+        }))
+    }
+}
@@ -0,0 +1,186 @@
+//! Per-tool usage metrics
+//!
+//! Every `tools/call` (and each call of a `tools/call_batch`) is counted by
+//! tool name, over stdio and HTTP alike: invocations, successes, errors and
+//! latency. p50/p99 latencies are computed from the last
+//! [`LATENCY_SAMPLES`] calls of each tool. Counters live in memory only and
+//! start from zero on restart. Served by `GET /api/tool-stats` and the
+//! `tool_stats` tool.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Latency samples kept per tool for percentiles
+pub const LATENCY_SAMPLES: usize = 1024;
+
+/// Counters of one tool
+#[derive(Debug, Default)]
+struct ToolCounters {
+    invocations: AtomicU64,
+    successes: AtomicU64,
+    errors: AtomicU64,
+    total_latency_micros: AtomicU64,
+    recent_latencies: Mutex<VecDeque<u64>>,
+}
+
+/// Usage of one tool since the server started
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolUsage {
+    pub name: String,
+    pub invocations: u64,
+    pub successes: u64,
+    pub errors: u64,
+    /// Errors / invocations
+    pub error_rate: f64,
+    pub total_latency_ms: f64,
+    pub avg_latency_ms: f64,
+    pub p50_latency_ms: f64,
+    pub p99_latency_ms: f64,
+}
+
+/// Usage counters of all tools, keyed by tool name
+#[derive(Debug, Default)]
+pub struct ToolStats {
+    tools: RwLock<HashMap<String, Arc<ToolCounters>>>,
+}
+
+/// Tool usage of this process
+pub fn tool_stats() -> &'static ToolStats {
+    static TOOL_STATS: OnceLock<ToolStats> = OnceLock::new();
+    TOOL_STATS.get_or_init(ToolStats::default)
+}
+
+/// Run a call of tool `name`, recording its outcome and latency
+pub fn track<T, E>(name: &str, call: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    let started = Instant::now();
+    let result = call();
+    tool_stats().record(name, result.is_ok(), started.elapsed());
+    result
+}
+
+impl ToolStats {
+    fn counters(&self, name: &str) -> Arc<ToolCounters> {
+        if let Some(counters) = self.tools.read().unwrap().get(name) {
+            return Arc::clone(counters);
+        }
+        let mut tools = self.tools.write().unwrap();
+        Arc::clone(tools.entry(name.to_string()).or_default())
+    }
+
+    /// Record one call of tool `name`
+    pub fn record(&self, name: &str, ok: bool, latency: Duration) {
+        let counters = self.counters(name);
+        let micros = latency.as_micros().min(u64::MAX as u128) as u64;
+
+        counters.invocations.fetch_add(1, Ordering::Relaxed);
+        if ok {
+            counters.successes.fetch_add(1, Ordering::Relaxed);
+        } else {
+            counters.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        counters
+            .total_latency_micros
+            .fetch_add(micros, Ordering::Relaxed);
+
+        let mut recent = counters.recent_latencies.lock().unwrap();
+        if recent.len() == LATENCY_SAMPLES {
+            recent.pop_front();
+        }
+        recent.push_back(micros);
+    }
+
+    /// Usage of every tool called so far, most invoked first
+    pub fn snapshot(&self) -> Vec<ToolUsage> {
+        let tools = self.tools.read().unwrap();
+        let mut usage: Vec<ToolUsage> = tools
+            .iter()
+            .map(|(name, counters)| usage_of(name, counters))
+            .collect();
+        usage.sort_by(|a, b| {
+            b.invocations
+                .cmp(&a.invocations)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        usage
+    }
+}
+
+fn usage_of(name: &str, counters: &ToolCounters) -> ToolUsage {
+    let invocations = counters.invocations.load(Ordering::Relaxed);
+    let errors = counters.errors.load(Ordering::Relaxed);
+    let total_micros = counters.total_latency_micros.load(Ordering::Relaxed);
+
+    let mut recent: Vec<u64> = counters
+        .recent_latencies
+        .lock()
+        .unwrap()
+        .iter()
+        .copied()
+        .collect();
+    recent.sort_unstable();
+
+    let ratio = |part: u64| {
+        if invocations == 0 {
+            0.0
+        } else {
+            part as f64 / invocations as f64
+        }
+    };
+
+    ToolUsage {
+        name: name.to_string(),
+        invocations,
+        successes: counters.successes.load(Ordering::Relaxed),
+        errors,
+        error_rate: ratio(errors),
+        total_latency_ms: millis(total_micros),
+        avg_latency_ms: ratio(total_micros) / 1000.0,
+        p50_latency_ms: millis(percentile(&recent, 50)),
+        p99_latency_ms: millis(percentile(&recent, 99)),
+    }
+}
+
+/// Nearest-rank percentile of sorted samples (0 when empty)
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn millis(micros: u64) -> f64 {
+    micros as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_counts_and_percentiles() {
+        let stats = ToolStats::default();
+        for ms in 1..=100 {
+            stats.record("traverse", ms != 100, Duration::from_millis(ms));
+        }
+        stats.record("read_graph", true, Duration::from_millis(5));
+
+        let usage = stats.snapshot();
+        assert_eq!(usage.len(), 2);
+        let traverse = &usage[0];
+        assert_eq!(traverse.name, "traverse");
+        assert_eq!(traverse.invocations, 100);
+        assert_eq!(traverse.successes, 99);
+        assert_eq!(traverse.errors, 1);
+        assert_eq!(traverse.error_rate, 0.01);
+        assert_eq!(traverse.p50_latency_ms, 50.0);
+        assert_eq!(traverse.p99_latency_ms, 99.0);
+        assert_eq!(traverse.avg_latency_ms, 50.5);
+        assert_eq!(usage[1].p99_latency_ms, 5.0);
+    }
+}
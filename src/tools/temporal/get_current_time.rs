@@ -2,7 +2,8 @@
 
 use serde_json::{json, Value};
 
-use crate::protocol::{McpTool, Tool};
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
 use crate::types::McpResult;
 use crate::utils::time::get_current_time;
 
@@ -34,12 +35,16 @@ impl Tool for GetCurrentTimeTool {
         }
     }
 
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
     fn execute(&self, _params: Value) -> McpResult<Value> {
         let time_info = get_current_time();
         Ok(json!({
             "content": [{
                 "type": "text",
-                "text": serde_json::to_string_pretty(&time_info)?
+                "text": to_text(&time_info)?
             }]
         }))
     }
@@ -0,0 +1,59 @@
+//! Get entity timeline tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
+use crate::types::McpResult;
+
+/// Tool for showing how an entity evolved over time
+pub struct GetTimelineTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl GetTimelineTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for GetTimelineTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "get_entity_timeline".to_string(),
+            description: "Get a chronological timeline of an entity: creation, observations added, removed and edited (observation_edited keeps the text before and after), relation changes and updates. Complete with Event Sourcing; in legacy mode it is rebuilt from stored timestamps and 'unavailable' lists what is missing.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "entityName": {
+                        "type": "string",
+                        "description": "The name of the entity to get the timeline for"
+                    }
+                },
+                "required": ["entityName"]
+            }),
+        }
+    }
+
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let entity_name = params
+            .get("entityName")
+            .and_then(|v| v.as_str())
+            .ok_or("entityName is required")?;
+
+        let timeline = self.kb.entity_timeline(entity_name)?;
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&timeline)?
+            }]
+        }))
+    }
+}
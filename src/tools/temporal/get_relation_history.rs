@@ -5,7 +5,8 @@ use std::sync::Arc;
 use serde_json::{json, Value};
 
 use crate::knowledge_base::KnowledgeBase;
-use crate::protocol::{McpTool, Tool};
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::to_text;
 use crate::types::McpResult;
 use crate::utils::time::current_timestamp;
 
@@ -38,6 +39,10 @@ impl Tool for GetRelationHistoryTool {
         }
     }
 
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
     fn execute(&self, params: Value) -> McpResult<Value> {
         let entity_name = params
             .get("entityName")
@@ -72,7 +77,7 @@ impl Tool for GetRelationHistoryTool {
         Ok(json!({
             "content": [{
                 "type": "text",
-                "text": serde_json::to_string_pretty(&json!({
+                "text": to_text(&json!({
                     "entity": entity_name,
                     "currentTime": current_time,
                     "relations": annotated
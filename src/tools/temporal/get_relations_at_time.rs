@@ -5,7 +5,9 @@ use std::sync::Arc;
 use serde_json::{json, Value};
 
 use crate::knowledge_base::KnowledgeBase;
-use crate::protocol::{McpTool, Tool};
+use crate::protocol::{McpTool, Tool, ToolAccess};
+use crate::tools::query::{property_filter_schema, property_filters};
+use crate::tools::to_text;
 use crate::types::McpResult;
 use crate::utils::time::current_timestamp;
 
@@ -35,23 +37,32 @@ impl Tool for GetRelationsAtTimeTool {
                     "entityName": {
                         "type": "string",
                         "description": "Optional: filter relations involving this entity"
-                    }
+                    },
+                    "propertyFilter": property_filter_schema()
                 },
                 "required": []
             }),
         }
     }
 
+    fn access(&self) -> ToolAccess {
+        ToolAccess::Read
+    }
+
     fn execute(&self, params: Value) -> McpResult<Value> {
         let timestamp = params.get("timestamp").and_then(|v| v.as_u64());
         let entity_name = params.get("entityName").and_then(|v| v.as_str());
 
-        let relations = self.kb.get_relations_at_time(timestamp, entity_name)?;
+        let properties = property_filters(&params)?;
+
+        let relations = self
+            .kb
+            .get_relations_at_time_filtered(timestamp, entity_name, &properties)?;
 
         Ok(json!({
             "content": [{
                 "type": "text",
-                "text": serde_json::to_string_pretty(&json!({
+                "text": to_text(&json!({
                     "queryTime": timestamp.unwrap_or_else(current_timestamp),
                     "relations": relations
                 }))?
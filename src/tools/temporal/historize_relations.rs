@@ -0,0 +1,107 @@
+//! Historize relations tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool};
+use crate::tools::query::{property_filter_schema, property_filters};
+use crate::tools::to_text;
+use crate::types::{HistorizeRule, McpResult, RelationQuery, TimeSource};
+
+/// Tool for bulk-setting relation validity windows
+pub struct HistorizeRelationsTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl HistorizeRelationsTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for HistorizeRelationsTool {
+    fn definition(&self) -> McpTool {
+        let time_source = |bound: &str| {
+            json!({
+                "type": "string",
+                "description": format!("Where {} comes from: relation_created_at, source_created_at, target_created_at, endpoints_created_at (later of both), or source_attribute:KEY / target_attribute:KEY for a 'KEY: value' observation holding Unix seconds or a YYYY-MM-DD date", bound)
+            })
+        };
+
+        McpTool {
+            name: "historize_relations".to_string(),
+            description: "Set validFrom/validTo on every matching relation in one pass, derived from creation times or dated observations, so the graph can be queried with get_relations_at_time. Bounds already set are kept unless overwrite is true. Use dryRun to preview the changes first.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "string",
+                        "description": "Only relations from this entity (name or id)"
+                    },
+                    "to": {
+                        "type": "string",
+                        "description": "Only relations to this entity (name or id)"
+                    },
+                    "relationType": {
+                        "type": "string",
+                        "description": "Only relations of this type"
+                    },
+                    "propertyFilter": property_filter_schema(),
+                    "validFrom": time_source("validFrom"),
+                    "validTo": time_source("validTo"),
+                    "overwrite": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Replace bounds that are already set"
+                    },
+                    "dryRun": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Report the new windows without changing anything"
+                    }
+                },
+                "required": []
+            }),
+        }
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let text = |key: &str| params.get(key).and_then(|v| v.as_str()).map(String::from);
+        let time_source = |key: &str| text(key).as_deref().map(TimeSource::parse).transpose();
+        let flag = |key: &str| params.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let rule = HistorizeRule {
+            query: RelationQuery {
+                from: text("from"),
+                to: text("to"),
+                relation_type: text("relationType"),
+                properties: property_filters(&params)?,
+            },
+            valid_from: time_source("validFrom")?,
+            valid_to: time_source("validTo")?,
+            overwrite: flag("overwrite"),
+        };
+        let dry_run = flag("dryRun");
+
+        let report = if dry_run {
+            self.kb.preview_historize_relations(&rule)?
+        } else {
+            self.kb.historize_relations(&rule)?
+        };
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&json!({
+                    "dryRun": dry_run,
+                    "matched": report.matched,
+                    "changed": report.changes.len(),
+                    "changes": report.changes,
+                    "skipped": report.skipped
+                }))?
+            }]
+        }))
+    }
+}
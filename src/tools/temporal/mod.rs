@@ -1,11 +1,17 @@
 //! Temporal tools for time-based queries
 //!
-//! This module contains 3 tools for temporal operations.
+//! This module contains 6 tools for temporal operations.
 
 mod get_current_time;
+mod get_entity_timeline;
 mod get_relation_history;
 mod get_relations_at_time;
+mod historize_relations;
+mod prune_expired;
 
 pub use get_current_time::GetCurrentTimeTool;
+pub use get_entity_timeline::GetTimelineTool;
 pub use get_relation_history::GetRelationHistoryTool;
 pub use get_relations_at_time::GetRelationsAtTimeTool;
+pub use historize_relations::HistorizeRelationsTool;
+pub use prune_expired::PruneExpiredTool;
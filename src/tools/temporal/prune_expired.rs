@@ -0,0 +1,80 @@
+//! Prune expired relations tool
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::knowledge_base::KnowledgeBase;
+use crate::protocol::{McpTool, Tool};
+use crate::tools::to_text;
+use crate::types::McpResult;
+use crate::utils::time::current_timestamp;
+
+/// Tool for moving expired temporal relations out of the active graph
+pub struct PruneExpiredTool {
+    kb: Arc<KnowledgeBase>,
+}
+
+impl PruneExpiredTool {
+    pub fn new(kb: Arc<KnowledgeBase>) -> Self {
+        Self { kb }
+    }
+}
+
+impl Tool for PruneExpiredTool {
+    fn definition(&self) -> McpTool {
+        McpTool {
+            name: "prune_expired".to_string(),
+            description: "Archive relations whose validTo is before a cutoff (default: now), removing them from the active graph so queries no longer have to skip them. Archived relations are still returned by get_relation_history and get_relations_at_time. Use dryRun to list them first.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "before": {
+                        "type": "integer",
+                        "description": "Unix timestamp; relations with validTo before it are archived. If not provided, uses current time."
+                    },
+                    "dryRun": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "List the expired relations without archiving them"
+                    }
+                },
+                "required": []
+            }),
+        }
+    }
+
+    fn execute(&self, params: Value) -> McpResult<Value> {
+        let before = params
+            .get("before")
+            .and_then(|v| v.as_u64())
+            .unwrap_or_else(current_timestamp);
+        let dry_run = params
+            .get("dryRun")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let result = if dry_run {
+            let expired = self.kb.expired_relations(before);
+            json!({
+                "before": before,
+                "dryRun": true,
+                "count": expired.len(),
+                "relations": expired
+            })
+        } else {
+            json!({
+                "before": before,
+                "dryRun": false,
+                "archived": self.kb.archive_expired_relations(before)?
+            })
+        };
+
+        Ok(json!({
+            "content": [{
+                "type": "text",
+                "text": to_text(&result)?
+            }]
+        }))
+    }
+}
@@ -0,0 +1,16 @@
+//! Entity access tracking types
+
+use serde::{Deserialize, Serialize};
+
+/// Entity ranked by how often it has been read since startup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HotEntity {
+    pub name: String,
+    #[serde(rename = "entityType")]
+    pub entity_type: String,
+    #[serde(rename = "accessCount")]
+    pub access_count: u64,
+    /// Unix timestamp (seconds) of the most recent read
+    #[serde(rename = "lastAccessed")]
+    pub last_accessed: u64,
+}
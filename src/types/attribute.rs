@@ -0,0 +1,189 @@
+//! Structured attributes parsed from observations
+//!
+//! Observations in the reserved namespace, `@key=value` (e.g. `@status=done`),
+//! are always read as attributes. So are legacy `key: value` observations such
+//! as `Owner: alice` or `DueDate: 2024-03-01`, unless
+//! `MEMORY_LEGACY_ATTRIBUTES=false`: free text like "Priority: investigate
+//! later" is then no longer mistaken for a priority. The namespace prefix is
+//! `@` unless `MEMORY_ATTRIBUTE_PREFIX` sets another. Observations are stored
+//! as written either way.
+//!
+//! A key starts with a letter, is at most [`MAX_ATTRIBUTE_KEY_LEN`]
+//! characters and three words of letters, digits, `_` or `-`, and needs a
+//! non-empty value. Anything else (`10:30 standup`, `https://...`, free text)
+//! stays a plain observation.
+//!
+//! Keys compare case-insensitively; when a key repeats, the latest
+//! observation wins, as with `Status:` in `compact_view`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+/// Longest key accepted as an attribute
+pub const MAX_ATTRIBUTE_KEY_LEN: usize = 32;
+
+/// Default prefix of namespaced attributes
+pub const DEFAULT_ATTRIBUTE_PREFIX: &str = "@";
+
+/// Which observations are read as attributes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeSyntax {
+    /// Prefix of `<prefix>key=value` observations
+    pub prefix: String,
+    /// Also read `key: value` observations
+    pub legacy: bool,
+}
+
+impl Default for AttributeSyntax {
+    fn default() -> Self {
+        Self {
+            prefix: DEFAULT_ATTRIBUTE_PREFIX.to_string(),
+            legacy: true,
+        }
+    }
+}
+
+impl AttributeSyntax {
+    /// Read `MEMORY_ATTRIBUTE_PREFIX` and `MEMORY_LEGACY_ATTRIBUTES`
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| env::var(key).ok())
+    }
+
+    /// Read the settings through `lookup` (for tests)
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+        let prefix = lookup("MEMORY_ATTRIBUTE_PREFIX")
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .unwrap_or(defaults.prefix);
+        let legacy = lookup("MEMORY_LEGACY_ATTRIBUTES")
+            .map(|v| !matches!(v.trim().to_lowercase().as_str(), "0" | "false" | "no"))
+            .unwrap_or(defaults.legacy);
+        Self { prefix, legacy }
+    }
+
+    /// Split an attribute observation, or `None` for a plain observation
+    pub fn parse<'a>(&self, observation: &'a str) -> Option<(&'a str, &'a str)> {
+        let trimmed = observation.trim();
+        if let Some(rest) = trimmed.strip_prefix(self.prefix.as_str()) {
+            if let Some((key, value)) = rest.split_once('=') {
+                return valid_attribute(key.trim(), value.trim());
+            }
+        }
+        if !self.legacy {
+            return None;
+        }
+        let (key, value) = observation.split_once(':')?;
+        let (key, value) = (key.trim(), value.trim());
+        if value.starts_with("//") {
+            return None;
+        }
+        valid_attribute(key, value)
+    }
+}
+
+fn valid_attribute<'a>(key: &'a str, value: &'a str) -> Option<(&'a str, &'a str)> {
+    let valid_key = key.chars().next().is_some_and(char::is_alphabetic)
+        && key.chars().count() <= MAX_ATTRIBUTE_KEY_LEN
+        && key.split_whitespace().count() <= 3
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == ' ' || c == '_' || c == '-');
+    (valid_key && !value.is_empty()).then_some((key, value))
+}
+
+/// Attribute syntax of this process, read from the environment once
+pub fn attribute_syntax() -> &'static AttributeSyntax {
+    static SYNTAX: OnceLock<AttributeSyntax> = OnceLock::new();
+    SYNTAX.get_or_init(AttributeSyntax::from_env)
+}
+
+/// Split an attribute observation, or `None` for a plain observation
+pub fn parse_attribute(observation: &str) -> Option<(&str, &str)> {
+    attribute_syntax().parse(observation)
+}
+
+/// Attributes found in a list of observations, the latest value per key
+pub fn collect_attributes<'a>(
+    observations: impl IntoIterator<Item = &'a String>,
+) -> BTreeMap<String, String> {
+    // Lowercased key -> (key as last written, value)
+    let mut latest: HashMap<String, (&str, &str)> = HashMap::new();
+    for (key, value) in observations.into_iter().filter_map(|o| parse_attribute(o)) {
+        latest.insert(key.to_lowercase(), (key, value));
+    }
+    latest
+        .into_values()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Attributes of one entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityAttributes {
+    pub name: String,
+    #[serde(rename = "entityType")]
+    pub entity_type: String,
+    pub attributes: BTreeMap<String, String>,
+    /// Observations that are not `key: value` pairs
+    #[serde(rename = "plainObservations")]
+    pub plain_observations: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_attribute() {
+        assert_eq!(parse_attribute("Owner: alice"), Some(("Owner", "alice")));
+        assert_eq!(
+            parse_attribute("Due Date:2024-03-01"),
+            Some(("Due Date", "2024-03-01"))
+        );
+        assert_eq!(
+            parse_attribute("Note: see: docs"),
+            Some(("Note", "see: docs"))
+        );
+        assert_eq!(parse_attribute("10:30 standup"), None);
+        assert_eq!(parse_attribute("https://example.com"), None);
+        assert_eq!(parse_attribute("Owner:"), None);
+        assert_eq!(parse_attribute("this sentence is far too long: no"), None);
+        assert_eq!(parse_attribute("plain observation"), None);
+    }
+
+    #[test]
+    fn test_latest_value_wins() {
+        let observations = vec![
+            "Status: todo".to_string(),
+            "Owner: alice".to_string(),
+            "status: done".to_string(),
+        ];
+        let attributes = collect_attributes(&observations);
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(attributes["status"], "done");
+        assert_eq!(attributes["Owner"], "alice");
+    }
+
+    #[test]
+    fn test_namespaced_attributes() {
+        let syntax = AttributeSyntax::default();
+        assert_eq!(syntax.parse("@status=done"), Some(("status", "done")));
+        assert_eq!(syntax.parse(" @due date = 2024-03-01"), Some(("due date", "2024-03-01")));
+        assert_eq!(syntax.parse("@url=https://example.com"), Some(("url", "https://example.com")));
+        assert_eq!(syntax.parse("@status="), None);
+        assert_eq!(syntax.parse("Priority: investigate later"), Some(("Priority", "investigate later")));
+
+        let strict = AttributeSyntax::from_lookup(|key| match key {
+            "MEMORY_LEGACY_ATTRIBUTES" => Some("false".to_string()),
+            "MEMORY_ATTRIBUTE_PREFIX" => Some("meta.".to_string()),
+            _ => None,
+        });
+        assert_eq!(strict.parse("meta.priority=high"), Some(("priority", "high")));
+        assert_eq!(strict.parse("Priority: investigate later"), None);
+        assert_eq!(strict.parse("@status=done"), None);
+    }
+}
@@ -0,0 +1,37 @@
+//! Backup types
+
+use serde::Serialize;
+
+/// What a backup file holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupMode {
+    /// The legacy `memory.jsonl` file
+    Legacy,
+    /// A full snapshot followed by the event log
+    EventSourcing,
+}
+
+impl BackupMode {
+    /// Name used in headers and file names
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackupMode::Legacy => "legacy",
+            BackupMode::EventSourcing => "event_sourcing",
+        }
+    }
+}
+
+/// Outcome of restoring a backup
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    /// Format of the restored file
+    pub mode: BackupMode,
+    pub entities: usize,
+    pub relations: usize,
+    /// Events written to the active log (0 in legacy mode)
+    pub events: usize,
+    /// Backup of the state that was replaced
+    pub safety_backup: String,
+}
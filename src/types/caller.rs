@@ -0,0 +1,32 @@
+//! Caller identity types
+
+/// User a tool call runs as, with the permissions of their token
+///
+/// HTTP requests without a valid token run as [`Caller::anonymous`], which
+/// holds no permissions and satisfies no entity ACL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Caller {
+    pub user: String,
+    pub permissions: Vec<String>,
+    /// Whether the caller presented a valid token
+    pub authenticated: bool,
+}
+
+impl Caller {
+    pub fn new(user: String, permissions: Vec<String>) -> Self {
+        Self {
+            user,
+            permissions,
+            authenticated: true,
+        }
+    }
+
+    /// Caller of an HTTP request without a token
+    pub fn anonymous() -> Self {
+        Self {
+            user: "anonymous".to_string(),
+            permissions: Vec::new(),
+            authenticated: false,
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! Recent change types
+
+use serde::{Deserialize, Serialize};
+
+use super::EventType;
+
+/// One mutation kept in the in-memory recent changes buffer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentChange {
+    /// Kind of change, named like the matching event type
+    #[serde(rename = "type")]
+    pub change_type: EventType,
+    /// Entity name, or `from -[type]-> to` for relations
+    pub subject: String,
+    pub user: String,
+    /// Unix timestamp (seconds) of the commit
+    pub timestamp: u64,
+}
@@ -0,0 +1,18 @@
+//! Query cost estimates
+
+use serde::{Deserialize, Serialize};
+
+/// Rough cost of a query, computed before running it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryCost {
+    /// `traverse` or `infer`
+    pub operation: String,
+    /// Approximate number of nodes the query would visit
+    #[serde(rename = "estimatedVisits")]
+    pub estimated_visits: u64,
+    /// Configured visit budget (`MEMORY_MAX_QUERY_VISITS`), if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget: Option<u64>,
+    /// Whether the query would be allowed to run
+    pub allowed: bool,
+}
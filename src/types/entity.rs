@@ -1,8 +1,12 @@
 //! Entity types for the knowledge graph
 
+use std::collections::BTreeMap;
+use std::env;
+
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 
-use super::{default_user, is_default_user, is_zero};
+use super::{collect_attributes, default_user, is_default_user, is_false, is_zero};
 
 /// Entity in the knowledge graph
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +32,18 @@ pub struct Entity {
     pub created_at: u64,
     #[serde(rename = "updatedAt", default, skip_serializing_if = "is_zero")]
     pub updated_at: u64,
+    /// Id assigned at create time, accepted wherever an entity name is. It is
+    /// an alias only: relations keep referencing the name. Empty for entities
+    /// created before ids were introduced.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    /// Protected entities are skipped by deletions unless they are forced
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub protected: bool,
+    /// Usernames or permissions allowed to see and change the entity over
+    /// HTTP, where requests without a token satisfy none; empty means public
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub acl: Vec<String>,
 }
 
 impl Entity {
@@ -41,6 +57,9 @@ impl Entity {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
         }
     }
 
@@ -54,7 +73,144 @@ impl Entity {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
+        }
+    }
+
+    /// `key: value` observations as attributes (see [`collect_attributes`])
+    pub fn attributes(&self) -> BTreeMap<String, String> {
+        collect_attributes(&self.observations)
+    }
+}
+
+/// Order in which observations are returned to clients
+///
+/// Observations are always stored in insertion order; this only affects output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ObservationOrder {
+    /// Insertion order (the default)
+    #[default]
+    OldestFirst,
+    /// Reverse insertion order, for status and log style entities
+    NewestFirst,
+}
+
+impl ObservationOrder {
+    /// Read the server default from `MEMORY_OBSERVATION_ORDER` (`oldest` or `newest`)
+    pub fn from_env() -> Self {
+        env::var("MEMORY_OBSERVATION_ORDER")
+            .ok()
+            .and_then(|v| Self::parse(&v))
+            .unwrap_or_default()
+    }
+
+    /// Parse `oldest` or `newest` (case-insensitive)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "oldest" => Some(ObservationOrder::OldestFirst),
+            "newest" => Some(ObservationOrder::NewestFirst),
+            _ => None,
+        }
+    }
+
+    /// Reorder the observations of each entity in place
+    pub fn apply<'a>(&self, entities: impl IntoIterator<Item = &'a mut Entity>) {
+        if *self == ObservationOrder::NewestFirst {
+            for entity in entities {
+                entity.observations.reverse();
+            }
+        }
+    }
+}
+
+/// Attribution field of an entity to match a user against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UserField {
+    /// The user who created the entity
+    CreatedBy,
+    /// The user who last changed the entity (the default)
+    #[default]
+    UpdatedBy,
+}
+
+impl UserField {
+    /// Parse `createdBy` or `updatedBy` (snake case accepted)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "createdBy" | "created_by" => Some(UserField::CreatedBy),
+            "updatedBy" | "updated_by" => Some(UserField::UpdatedBy),
+            _ => None,
+        }
+    }
+
+    /// The entity's value of this field
+    pub fn of<'a>(&self, entity: &'a Entity) -> &'a str {
+        match self {
+            UserField::CreatedBy => &entity.created_by,
+            UserField::UpdatedBy => &entity.updated_by,
+        }
+    }
+}
+
+/// How much of each entity the read tools return
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Projection {
+    /// Entity names only
+    Names,
+    /// Name, type and a short brief (see [`EntityBrief::of`])
+    Brief,
+    /// The whole entity (the default)
+    #[default]
+    Full,
+}
+
+impl Projection {
+    /// Parse `names`, `brief` or `full` (case-insensitive)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "names" | "name" => Some(Projection::Names),
+            "brief" => Some(Projection::Brief),
+            "full" => Some(Projection::Full),
+            _ => None,
+        }
+    }
+
+    /// Shape the entities of a serialized response body in place
+    ///
+    /// Applies to the `entities` list of the body and of each of its
+    /// `groups`. A `score` next to an entity is kept in the brief form and
+    /// dropped with names only.
+    pub fn apply(&self, body: &mut Value) {
+        if *self == Projection::Full {
+            return;
+        }
+        if let Some(entities) = body.get_mut("entities").and_then(|v| v.as_array_mut()) {
+            entities.iter_mut().for_each(|entity| self.shape(entity));
         }
+        if let Some(groups) = body.get_mut("groups").and_then(|v| v.as_array_mut()) {
+            for group in groups {
+                self.apply(group);
+            }
+        }
+    }
+
+    fn shape(&self, entity: &mut Value) {
+        let Ok(parsed) = Entity::deserialize(&*entity) else {
+            return;
+        };
+        let score = entity.get("score").cloned();
+        *entity = match self {
+            Projection::Names => Value::String(parsed.name),
+            _ => {
+                let mut brief = json!(EntityBrief::of(&parsed));
+                if let Some(score) = score {
+                    brief["score"] = score;
+                }
+                brief
+            }
+        };
     }
 }
 
@@ -66,3 +222,55 @@ pub struct EntityBrief {
     pub entity_type: String,
     pub brief: String,
 }
+
+impl EntityBrief {
+    /// Name, type and the first observation cut to 100 characters
+    pub fn of(entity: &Entity) -> Self {
+        Self {
+            name: entity.name.clone(),
+            entity_type: entity.entity_type.clone(),
+            brief: entity
+                .observations
+                .first()
+                .map(|o| o.chars().take(100).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Items two entities share and the items unique to each
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SetDiff<T> {
+    pub common: Vec<T>,
+    #[serde(rename = "onlyA")]
+    pub only_a: Vec<T>,
+    #[serde(rename = "onlyB")]
+    pub only_b: Vec<T>,
+}
+
+/// A relation seen from one entity: its type, direction and other endpoint
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct NeighborEdge {
+    #[serde(rename = "relationType")]
+    pub relation_type: String,
+    /// `outgoing` or `incoming`
+    pub direction: String,
+    pub entity: String,
+}
+
+/// Structured comparison of two entities, for deciding whether to merge them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityDiff {
+    pub a: String,
+    pub b: String,
+    #[serde(rename = "typeA")]
+    pub type_a: String,
+    #[serde(rename = "typeB")]
+    pub type_b: String,
+    #[serde(rename = "sameType")]
+    pub same_type: bool,
+    /// Duplicate score as reported by `find_duplicates` (0.0 - 1.0)
+    pub similarity: f32,
+    pub observations: SetDiff<String>,
+    pub relations: SetDiff<NeighborEdge>,
+}
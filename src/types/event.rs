@@ -5,6 +5,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::{is_false, Properties};
+
 /// Event types that can occur in the system
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -19,10 +21,14 @@ pub enum EventType {
     ObservationAdded,
     /// An observation was removed from an entity
     ObservationRemoved,
+    /// An observation's text was changed in place
+    ObservationEdited,
     /// A new relation was created between entities
     RelationCreated,
     /// A relation was deleted
     RelationDeleted,
+    /// A relation's validity window was changed
+    RelationUpdated,
 }
 
 impl std::fmt::Display for EventType {
@@ -33,8 +39,10 @@ impl std::fmt::Display for EventType {
             EventType::EntityDeleted => write!(f, "entity_deleted"),
             EventType::ObservationAdded => write!(f, "observation_added"),
             EventType::ObservationRemoved => write!(f, "observation_removed"),
+            EventType::ObservationEdited => write!(f, "observation_edited"),
             EventType::RelationCreated => write!(f, "relation_created"),
             EventType::RelationDeleted => write!(f, "relation_deleted"),
+            EventType::RelationUpdated => write!(f, "relation_updated"),
         }
     }
 }
@@ -63,6 +71,15 @@ pub struct EntityCreatedData {
     pub entity_type: String,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub observations: Vec<String>,
+    /// Entity id alias (absent in events written before ids existed)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    /// Whether the entity was created protected from deletion
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub protected: bool,
+    /// Access-control list the entity was created with (empty = public)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub acl: Vec<String>,
 }
 
 /// Data payload for EntityUpdated event
@@ -71,6 +88,9 @@ pub struct EntityUpdatedData {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub entity_type: Option<String>,
+    /// New protection flag (absent when unchanged)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protected: Option<bool>,
 }
 
 /// Data payload for EntityDeleted event
@@ -95,6 +115,18 @@ pub struct ObservationRemovedData {
     pub observation: String,
 }
 
+/// Data payload for ObservationEdited event
+///
+/// Replaces `before` with `after` at the same position. If `after` is
+/// already present, `before` is just removed; if `before` is gone, `after`
+/// is added.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObservationEditedData {
+    pub entity: String,
+    pub before: String,
+    pub after: String,
+}
+
 /// Data payload for RelationCreated event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RelationCreatedData {
@@ -105,6 +137,11 @@ pub struct RelationCreatedData {
     pub valid_from: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub valid_to: Option<i64>,
+    /// Creation time recorded by the writer (older events fall back to the event timestamp)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Properties::is_empty")]
+    pub properties: Properties,
 }
 
 /// Data payload for RelationDeleted event
@@ -115,6 +152,20 @@ pub struct RelationDeletedData {
     pub relation_type: String,
 }
 
+/// Data payload for RelationUpdated event
+///
+/// Only the bounds present are changed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationUpdatedData {
+    pub from: String,
+    pub to: String,
+    pub relation_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_to: Option<i64>,
+}
+
 /// Event data - typed payload for each event type
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -124,8 +175,10 @@ pub enum EventData {
     EntityDeleted(EntityDeletedData),
     ObservationAdded(ObservationAddedData),
     ObservationRemoved(ObservationRemovedData),
+    ObservationEdited(ObservationEditedData),
     RelationCreated(RelationCreatedData),
     RelationDeleted(RelationDeletedData),
+    RelationUpdated(RelationUpdatedData),
 }
 
 /// An immutable event in the event log
@@ -253,6 +306,22 @@ pub struct SnapshotMeta {
     /// Version of snapshot format (for future migrations)
     #[serde(default = "default_version")]
     pub version: u32,
+
+    /// Whether this snapshot only records changes since `base_event_id`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_delta: bool,
+
+    /// `last_event_id` of the snapshot a delta applies on top of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_event_id: Option<u64>,
+
+    /// Number of log lines this snapshot reflects, archived ones included
+    ///
+    /// Replay resumes after them. Other writers' events interleave with ours
+    /// in the log, so an event ID cannot mark where the snapshot ends;
+    /// snapshots without a position fall back to `last_event_id`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_position: Option<u64>,
 }
 
 fn default_version() -> u32 {
@@ -269,6 +338,32 @@ impl SnapshotMeta {
             entity_count,
             relation_count,
             version: 1,
+            is_delta: false,
+            base_event_id: None,
+            log_position: None,
+        }
+    }
+
+    /// Set the log position the snapshot reflects
+    pub fn with_log_position(mut self, log_position: Option<u64>) -> Self {
+        self.log_position = log_position;
+        self
+    }
+
+    /// Create metadata for a delta snapshot on top of `base_event_id`
+    ///
+    /// The counts are the entities and relations written to the delta, not
+    /// the size of the graph.
+    pub fn delta(
+        base_event_id: u64,
+        last_event_id: u64,
+        entity_count: usize,
+        relation_count: usize,
+    ) -> Self {
+        Self {
+            is_delta: true,
+            base_event_id: Some(base_event_id),
+            ..Self::new(last_event_id, entity_count, relation_count)
         }
     }
 
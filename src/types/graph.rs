@@ -29,6 +29,23 @@ impl KnowledgeGraph {
         self.entities.is_empty() && self.relations.is_empty()
     }
 
+    /// Find an entity by name, or by id if no entity has that name
+    pub fn resolve_entity(&self, key: &str) -> Option<&Entity> {
+        self.entities.iter().find(|e| e.name == key).or_else(|| {
+            self.entities
+                .iter()
+                .find(|e| !e.id.is_empty() && e.id == key)
+        })
+    }
+
+    /// Name of the entity addressed by `key` (a name or an id)
+    ///
+    /// Returns `key` itself when no entity matches, so callers keep their
+    /// usual "not found" handling.
+    pub fn resolve_name<'a>(&'a self, key: &'a str) -> &'a str {
+        self.resolve_entity(key).map_or(key, |e| e.name.as_str())
+    }
+
     /// Get the number of entities
     pub fn entity_count(&self) -> usize {
         self.entities.len()
@@ -38,4 +55,23 @@ impl KnowledgeGraph {
     pub fn relation_count(&self) -> usize {
         self.relations.len()
     }
+
+    /// Serialize as JSONL (one entity or relation per line, entities first)
+    ///
+    /// This is the `memory.jsonl` format, so the output can be loaded back.
+    pub fn to_jsonl(&self) -> serde_json::Result<String> {
+        let mut content = String::new();
+
+        for entity in &self.entities {
+            content.push_str(&serde_json::to_string(entity)?);
+            content.push('\n');
+        }
+
+        for relation in &self.relations {
+            content.push_str(&serde_json::to_string(relation)?);
+            content.push('\n');
+        }
+
+        Ok(content)
+    }
 }
@@ -0,0 +1,164 @@
+//! Rules for bulk-setting relation validity windows
+//!
+//! A historize rule picks relations with the same filters as
+//! `query_relations` and derives `validFrom` and/or `validTo` for each from
+//! a time source:
+//! - `relation_created_at`: when the relation was recorded
+//! - `source_created_at` / `target_created_at`: when an endpoint was created
+//! - `endpoints_created_at`: the later of the two (a relation cannot hold
+//!   before both sides exist)
+//! - `source_attribute:KEY` / `target_attribute:KEY`: a `KEY: value`
+//!   observation of an endpoint holding Unix seconds or a `YYYY-MM-DD` date
+//!
+//! Bounds that are already set are kept unless the rule overwrites them.
+
+use serde::{Deserialize, Serialize};
+
+use super::{collect_attributes, Entity, Relation, RelationQuery};
+use crate::utils::time::parse_timestamp;
+
+/// Where a validity bound is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeSource {
+    RelationCreatedAt,
+    SourceCreatedAt,
+    TargetCreatedAt,
+    EndpointsCreatedAt,
+    SourceAttribute(String),
+    TargetAttribute(String),
+}
+
+impl TimeSource {
+    /// Parse a time source such as `source_created_at` or `target_attribute:StartDate`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let source = match value.split_once(':') {
+            Some(("source_attribute", key)) if !key.trim().is_empty() => {
+                Self::SourceAttribute(key.trim().to_string())
+            }
+            Some(("target_attribute", key)) if !key.trim().is_empty() => {
+                Self::TargetAttribute(key.trim().to_string())
+            }
+            Some(_) => return Err(format!("Invalid time source: {}", value)),
+            None => match value {
+                "relation_created_at" => Self::RelationCreatedAt,
+                "source_created_at" => Self::SourceCreatedAt,
+                "target_created_at" => Self::TargetCreatedAt,
+                "endpoints_created_at" => Self::EndpointsCreatedAt,
+                _ => return Err(format!("Invalid time source: {}", value)),
+            },
+        };
+        Ok(source)
+    }
+
+    /// Resolve the time for a relation, or describe why it has none
+    pub fn resolve(
+        &self,
+        relation: &Relation,
+        source: &Entity,
+        target: &Entity,
+    ) -> Result<u64, String> {
+        let known = |ts: u64, what: &str| {
+            if ts > 0 {
+                Ok(ts)
+            } else {
+                Err(format!("no creation time recorded for {}", what))
+            }
+        };
+        let attribute = |entity: &Entity, key: &str| {
+            let value = collect_attributes(&entity.observations)
+                .into_iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v)
+                .ok_or_else(|| format!("no '{}' attribute on '{}'", key, entity.name))?;
+            parse_timestamp(&value).ok_or_else(|| {
+                format!(
+                    "'{}' of '{}' is not a timestamp or date: {}",
+                    key, entity.name, value
+                )
+            })
+        };
+
+        match self {
+            Self::RelationCreatedAt => known(relation.created_at, "the relation"),
+            Self::SourceCreatedAt => known(source.created_at, &format!("'{}'", source.name)),
+            Self::TargetCreatedAt => known(target.created_at, &format!("'{}'", target.name)),
+            Self::EndpointsCreatedAt => {
+                Ok(known(source.created_at, &format!("'{}'", source.name))?
+                    .max(known(target.created_at, &format!("'{}'", target.name))?))
+            }
+            Self::SourceAttribute(key) => attribute(source, key),
+            Self::TargetAttribute(key) => attribute(target, key),
+        }
+    }
+}
+
+/// Which relations to historize and where their bounds come from
+#[derive(Debug, Clone, Default)]
+pub struct HistorizeRule {
+    /// Relations the rule applies to
+    pub query: RelationQuery,
+    pub valid_from: Option<TimeSource>,
+    pub valid_to: Option<TimeSource>,
+    /// Replace bounds that are already set
+    pub overwrite: bool,
+}
+
+/// New validity window of one relation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidityChange {
+    pub from: String,
+    pub to: String,
+    #[serde(rename = "relationType")]
+    pub relation_type: String,
+    #[serde(rename = "validFrom", skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<u64>,
+    #[serde(rename = "validTo", skip_serializing_if = "Option::is_none")]
+    pub valid_to: Option<u64>,
+    #[serde(rename = "previousValidFrom", skip_serializing_if = "Option::is_none")]
+    pub previous_valid_from: Option<u64>,
+    #[serde(rename = "previousValidTo", skip_serializing_if = "Option::is_none")]
+    pub previous_valid_to: Option<u64>,
+}
+
+/// Outcome of `historize_relations`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistorizeReport {
+    /// Relations matching the rule's filters
+    pub matched: usize,
+    /// Relations whose window changes
+    pub changes: Vec<ValidityChange>,
+    /// Matched relations left alone, with the reason
+    pub skipped: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_source_resolution() {
+        assert_eq!(
+            TimeSource::parse("target_attribute: StartDate"),
+            Ok(TimeSource::TargetAttribute("StartDate".to_string()))
+        );
+        assert!(TimeSource::parse("created_at").is_err());
+        assert!(TimeSource::parse("source_attribute:").is_err());
+
+        let mut source = Entity::new("Api".to_string(), "Module".to_string());
+        source.created_at = 100;
+        let mut target = Entity::new("Auth".to_string(), "Module".to_string());
+        target.created_at = 200;
+        target.observations = vec!["StartDate: 2024-03-01".to_string()];
+        let relation = Relation::new("Api".to_string(), "Auth".to_string(), "uses".to_string());
+
+        let resolve = |value: &str| {
+            TimeSource::parse(value)
+                .unwrap()
+                .resolve(&relation, &source, &target)
+        };
+        assert_eq!(resolve("endpoints_created_at"), Ok(200));
+        assert_eq!(resolve("target_attribute:startdate"), Ok(1709251200));
+        assert!(resolve("relation_created_at").is_err());
+        assert!(resolve("source_attribute:StartDate").is_err());
+    }
+}
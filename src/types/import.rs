@@ -0,0 +1,75 @@
+//! Graph import types
+
+use serde::{Deserialize, Serialize};
+
+/// Entity whose imported type differs from the one already stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityTypeConflict {
+    pub name: String,
+    #[serde(rename = "existingType")]
+    pub existing_type: String,
+    #[serde(rename = "importedType")]
+    pub imported_type: String,
+}
+
+/// Outcome of merging an external graph into the knowledge base
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// Entities that did not exist before
+    #[serde(rename = "entitiesCreated")]
+    pub entities_created: usize,
+    /// Existing entities that gained new observations
+    #[serde(rename = "entitiesUpdated")]
+    pub entities_updated: usize,
+    /// Existing entities with nothing new to add
+    #[serde(rename = "entitiesSkipped")]
+    pub entities_skipped: usize,
+    #[serde(rename = "relationsCreated")]
+    pub relations_created: usize,
+    /// Relations that already existed or reference missing entities
+    #[serde(rename = "relationsSkipped")]
+    pub relations_skipped: usize,
+    /// Entities left untouched because their type did not match
+    pub conflicts: Vec<EntityTypeConflict>,
+}
+
+/// One committed chunk of a bulk import
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkChunk {
+    /// Position of the chunk, starting at 0
+    pub index: usize,
+    /// Entities and relations submitted in this chunk
+    pub submitted: usize,
+    #[serde(rename = "entitiesCreated")]
+    pub entities_created: usize,
+    #[serde(rename = "relationsCreated")]
+    pub relations_created: usize,
+}
+
+/// Outcome of a chunked bulk import
+///
+/// Chunks listed in `chunks` are committed; when `error` is set, the chunk
+/// at `failedChunk` and every later one were not applied.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkReport {
+    #[serde(rename = "chunkSize")]
+    pub chunk_size: usize,
+    #[serde(rename = "totalChunks")]
+    pub total_chunks: usize,
+    pub chunks: Vec<BulkChunk>,
+    #[serde(rename = "entitiesCreated")]
+    pub entities_created: usize,
+    #[serde(rename = "relationsCreated")]
+    pub relations_created: usize,
+    #[serde(rename = "failedChunk", skip_serializing_if = "Option::is_none")]
+    pub failed_chunk: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BulkReport {
+    /// Whether every chunk was committed
+    pub fn is_complete(&self) -> bool {
+        self.error.is_none() && self.chunks.len() == self.total_chunks
+    }
+}
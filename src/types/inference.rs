@@ -35,6 +35,9 @@ pub struct InferStats {
     /// Execution time in milliseconds
     #[serde(rename = "executionTimeMs")]
     pub execution_time_ms: u64,
+    /// Result was served from the inference cache
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
 }
 
 /// Result of an inference operation
@@ -0,0 +1,87 @@
+//! Graph-level metadata type
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::is_zero;
+
+/// Value of the `type` field marking the metadata header line
+pub const GRAPH_METADATA_TYPE: &str = "graph_metadata";
+
+/// Document-level information about the graph as a whole
+///
+/// Kept apart from the domain entities: it is not searchable, takes no part
+/// in relations and is not returned by `read_graph`. Every field is optional,
+/// so a graph without metadata is simply `GraphMetadata::default()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphMetadata {
+    /// Project or graph name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Version of the entity/relation conventions the graph follows
+    #[serde(
+        rename = "schemaVersion",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub schema_version: Option<String>,
+    /// Date the graph was last reviewed, as given by the caller
+    #[serde(
+        rename = "lastReviewed",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_reviewed: Option<String>,
+    /// Free-form key/value annotations
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+    #[serde(
+        rename = "updatedBy",
+        default,
+        skip_serializing_if = "String::is_empty"
+    )]
+    pub updated_by: String,
+    #[serde(rename = "updatedAt", default, skip_serializing_if = "is_zero")]
+    pub updated_at: u64,
+}
+
+/// Metadata as written on the first line of `memory.jsonl`
+#[derive(Serialize, Deserialize)]
+struct HeaderLine {
+    #[serde(rename = "type")]
+    line_type: String,
+    #[serde(flatten)]
+    metadata: GraphMetadata,
+}
+
+impl GraphMetadata {
+    /// Whether no field is set (such metadata is not written out)
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.description.is_none()
+            && self.schema_version.is_none()
+            && self.last_reviewed.is_none()
+            && self.annotations.is_empty()
+    }
+
+    /// Serialize as a JSONL header line tagged `"type": "graph_metadata"`
+    pub fn to_header_line(&self) -> serde_json::Result<String> {
+        serde_json::to_string(&HeaderLine {
+            line_type: GRAPH_METADATA_TYPE.to_string(),
+            metadata: self.clone(),
+        })
+    }
+
+    /// Parse a header line written by [`GraphMetadata::to_header_line`]
+    ///
+    /// Returns `None` for any other line, entities and relations included.
+    pub fn from_header_line(line: &str) -> Option<Self> {
+        serde_json::from_str::<HeaderLine>(line)
+            .ok()
+            .filter(|header| header.line_type == GRAPH_METADATA_TYPE)
+            .map(|header| header.metadata)
+    }
+}
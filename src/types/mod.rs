@@ -2,31 +2,92 @@
 //!
 //! This module contains all the core data structures used throughout the application.
 
+mod access;
+mod attribute;
+mod backup;
+mod caller;
+mod change;
+mod cost;
 mod entity;
 mod event;
 mod graph;
+mod historize;
+mod import;
 mod inference;
+mod metadata;
 mod observation;
+mod property;
 mod relation;
+mod resource;
+mod search;
 mod summary;
+mod timeline;
+mod transaction;
 mod traversal;
 
-pub use entity::{Entity, EntityBrief};
+pub use access::HotEntity;
+pub use attribute::{
+    attribute_syntax, collect_attributes, parse_attribute, AttributeSyntax, EntityAttributes,
+    DEFAULT_ATTRIBUTE_PREFIX, MAX_ATTRIBUTE_KEY_LEN,
+};
+pub use caller::Caller;
+pub use change::RecentChange;
+pub use cost::QueryCost;
+pub use entity::{
+    Entity, EntityBrief, EntityDiff, NeighborEdge, ObservationOrder, Projection, SetDiff,
+    UserField,
+};
 pub use event::{
     EntityCreatedData, EntityDeletedData, EntityUpdatedData, Event, EventData, EventSource,
-    EventType, ObservationAddedData, ObservationRemovedData, RelationCreatedData,
-    RelationDeletedData, SnapshotMeta,
+    EventType, ObservationAddedData, ObservationEditedData, ObservationRemovedData,
+    RelationCreatedData, RelationDeletedData, RelationUpdatedData, SnapshotMeta,
 };
 pub use graph::KnowledgeGraph;
+pub use historize::{HistorizeReport, HistorizeRule, TimeSource, ValidityChange};
+pub use import::{BulkChunk, BulkReport, EntityTypeConflict, ImportReport};
 pub use inference::{InferResult, InferStats, InferredRelation};
-pub use observation::{Observation, ObservationDeletion};
-pub use relation::{RelatedEntities, RelatedEntity, Relation};
-pub use summary::Summary;
-pub use traversal::{PathStep, TraversalPath, TraversalResult};
+pub use backup::{BackupMode, RestoreReport};
+pub use metadata::{GraphMetadata, GRAPH_METADATA_TYPE};
+pub use observation::{
+    Observation, ObservationChange, ObservationDeletion, ObservationReplacement,
+    ObservationReplacementResult,
+};
+pub use property::{Properties, PropertyFilter, PropertyOp};
+pub use relation::{
+    NormalizeAction, ReferenceGroup, References, RelatedEntities, RelatedEntity, RelatedOptions, RelatedPage,
+    RelatedSort, Relation, RelationNormalization, RelationQuery, RelationSuggestion,
+    RelationTypeChange,
+};
+pub use resource::ResultResource;
+pub use search::{
+    GroupedSearchResult, ScoredEntity, SearchField, SearchGroup, SearchOptions, SearchResult,
+};
+pub use summary::{CompactView, ConnectedEntity, GraphSchema, SchemaEdge, Summary};
+pub use timeline::{EntityTimeline, TimelineEntry};
+pub use transaction::{Op, OpResult};
+pub use traversal::{
+    max_traverse_depth, PathStep, TraversalPath, TraversalResult, DEFAULT_MAX_TRAVERSE_DEPTH,
+    MAX_K_PATHS, MAX_PATH_DEPTH, MAX_STEP_HOPS,
+};
 
 /// Result type for MCP operations
 pub type McpResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
+/// Error for tool arguments the caller got wrong
+///
+/// Reported as JSON-RPC `-32602 Invalid params` rather than the generic
+/// `-32603 Tool execution error`.
+#[derive(Debug)]
+pub struct InvalidParams(pub String);
+
+impl std::fmt::Display for InvalidParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for InvalidParams {}
+
 /// Default user for serde deserialization
 pub fn default_user() -> String {
     "system".to_string()
@@ -41,3 +102,8 @@ pub fn is_default_user(val: &str) -> bool {
 pub fn is_zero(val: &u64) -> bool {
     *val == 0
 }
+
+/// Check if flag is unset (for skip_serializing_if)
+pub fn is_false(val: &bool) -> bool {
+    !*val
+}
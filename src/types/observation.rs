@@ -37,3 +37,59 @@ impl ObservationDeletion {
         }
     }
 }
+
+/// Observation replacement request
+///
+/// Without `replace_prefix` the entity's entire observation set is replaced by
+/// `contents`. With a prefix, only existing observations starting with it are
+/// removed before `contents` are added (e.g. `"Status:"`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObservationReplacement {
+    #[serde(rename = "entityName")]
+    pub entity_name: String,
+    pub contents: Vec<String>,
+    #[serde(
+        rename = "replacePrefix",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub replace_prefix: Option<String>,
+}
+
+impl ObservationReplacement {
+    /// Create a request replacing all observations of an entity
+    pub fn new(entity_name: String, contents: Vec<String>) -> Self {
+        Self {
+            entity_name,
+            contents,
+            replace_prefix: None,
+        }
+    }
+
+    /// Create a request replacing only observations starting with `prefix`
+    pub fn with_prefix(entity_name: String, contents: Vec<String>, prefix: String) -> Self {
+        Self {
+            entity_name,
+            contents,
+            replace_prefix: Some(prefix),
+        }
+    }
+}
+
+/// Outcome of an observation replacement for a single entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObservationReplacementResult {
+    #[serde(rename = "entityName")]
+    pub entity_name: String,
+    pub removed: Vec<String>,
+    pub added: Vec<String>,
+}
+
+/// A single observation rewrite produced by graph-wide search-and-replace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObservationChange {
+    #[serde(rename = "entityName")]
+    pub entity_name: String,
+    pub before: String,
+    pub after: String,
+}
@@ -0,0 +1,152 @@
+//! Relation properties and property filters
+//!
+//! Relations may carry a `properties` map of JSON values. Filters compare one
+//! property against a value:
+//! - Numbers compare numerically (`1` equals `1.0`)
+//! - Strings compare exactly and order lexicographically
+//! - Other values (booleans, null, arrays, objects) only support `eq`/`ne`
+//! - Values of different JSON types never compare: `eq` and the ordering
+//!   operators are false, `ne` is true (a string `"true"` is not `true`)
+//!
+//! A filter on a missing property only matches with `op: "missing"`.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Property map attached to a relation
+pub type Properties = BTreeMap<String, Value>;
+
+/// Comparison applied by a property filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PropertyOp {
+    #[default]
+    #[serde(alias = "=", alias = "==")]
+    Eq,
+    #[serde(alias = "!=")]
+    Ne,
+    #[serde(alias = ">")]
+    Gt,
+    #[serde(alias = ">=")]
+    Gte,
+    #[serde(alias = "<")]
+    Lt,
+    #[serde(alias = "<=")]
+    Lte,
+    /// The property is set, whatever its value
+    Exists,
+    /// The property is not set
+    Missing,
+}
+
+/// Predicate over one relation property
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyFilter {
+    pub key: String,
+    #[serde(default)]
+    pub op: PropertyOp,
+    #[serde(default)]
+    pub value: Value,
+}
+
+impl PropertyFilter {
+    /// Equality filter on `key`
+    pub fn eq(key: impl Into<String>, value: Value) -> Self {
+        Self {
+            key: key.into(),
+            op: PropertyOp::Eq,
+            value,
+        }
+    }
+
+    /// Parse filters given as a list of `{key, op, value}` predicates or as an
+    /// object of `key: value` equality pairs
+    pub fn parse_list(value: &Value) -> Result<Vec<Self>, String> {
+        match value {
+            Value::Null => Ok(Vec::new()),
+            Value::Object(pairs) => Ok(pairs
+                .iter()
+                .map(|(key, value)| Self::eq(key.clone(), value.clone()))
+                .collect()),
+            Value::Array(_) => serde_json::from_value(value.clone())
+                .map_err(|e| format!("Invalid property filter: {}", e)),
+            _ => Err("Property filters must be an object or an array".to_string()),
+        }
+    }
+
+    /// Whether the filter holds for a property map
+    pub fn matches(&self, properties: &Properties) -> bool {
+        let Some(actual) = properties.get(&self.key) else {
+            return self.op == PropertyOp::Missing;
+        };
+        let ordering = compare(actual, &self.value);
+        match self.op {
+            PropertyOp::Eq => ordering == Some(Ordering::Equal) || actual == &self.value,
+            PropertyOp::Ne => ordering != Some(Ordering::Equal) && actual != &self.value,
+            PropertyOp::Gt => ordering == Some(Ordering::Greater),
+            PropertyOp::Gte => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+            PropertyOp::Lt => ordering == Some(Ordering::Less),
+            PropertyOp::Lte => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+            PropertyOp::Exists => true,
+            PropertyOp::Missing => false,
+        }
+    }
+
+    /// Whether every filter holds (true for no filters)
+    pub fn all_match(filters: &[Self], properties: &Properties) -> bool {
+        filters.iter().all(|f| f.matches(properties))
+    }
+}
+
+/// Order two numbers or two strings; other values are unordered
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn props(value: Value) -> Properties {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_property_comparisons() {
+        let p = props(json!({"critical": true, "weight": 2, "owner": "bob"}));
+        let filter = |value: Value| -> PropertyFilter { serde_json::from_value(value).unwrap() };
+
+        assert!(filter(json!({"key": "critical", "value": true})).matches(&p));
+        assert!(!filter(json!({"key": "critical", "value": "true"})).matches(&p));
+        assert!(filter(json!({"key": "critical", "op": "!=", "value": "true"})).matches(&p));
+        assert!(filter(json!({"key": "weight", "value": 2.0})).matches(&p));
+        assert!(filter(json!({"key": "weight", "op": ">=", "value": 1.5})).matches(&p));
+        assert!(!filter(json!({"key": "weight", "op": "gt", "value": "1"})).matches(&p));
+        assert!(filter(json!({"key": "owner", "op": "<", "value": "carol"})).matches(&p));
+        assert!(filter(json!({"key": "owner", "op": "exists"})).matches(&p));
+        assert!(!filter(json!({"key": "team", "op": "ne", "value": 1})).matches(&p));
+        assert!(filter(json!({"key": "team", "op": "missing"})).matches(&p));
+    }
+
+    #[test]
+    fn test_parse_filter_list() {
+        let filters = PropertyFilter::parse_list(&json!({"critical": true})).unwrap();
+        assert_eq!(filters, vec![PropertyFilter::eq("critical", json!(true))]);
+
+        let filters =
+            PropertyFilter::parse_list(&json!([{"key": "weight", "op": "<=", "value": 3}]))
+                .unwrap();
+        assert_eq!(filters[0].op, PropertyOp::Lte);
+
+        assert!(PropertyFilter::parse_list(&json!([{"key": "w", "op": "~"}])).is_err());
+        assert!(PropertyFilter::parse_list(&json!("critical")).is_err());
+    }
+}
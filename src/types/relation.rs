@@ -2,7 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::{default_user, is_default_user, is_zero, Entity};
+use super::{default_user, is_default_user, is_zero, Entity, Properties, PropertyFilter};
 
 /// Relation between entities with temporal validity
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,10 +19,15 @@ pub struct Relation {
     pub created_by: String,
     #[serde(rename = "createdAt", default, skip_serializing_if = "is_zero")]
     pub created_at: u64,
+    #[serde(rename = "updatedAt", default, skip_serializing_if = "is_zero")]
+    pub updated_at: u64,
     #[serde(rename = "validFrom", default, skip_serializing_if = "Option::is_none")]
     pub valid_from: Option<u64>,
     #[serde(rename = "validTo", default, skip_serializing_if = "Option::is_none")]
     pub valid_to: Option<u64>,
+    /// Arbitrary metadata, queryable with property filters
+    #[serde(default, skip_serializing_if = "Properties::is_empty")]
+    pub properties: Properties,
 }
 
 impl Relation {
@@ -34,8 +39,10 @@ impl Relation {
             relation_type,
             created_by: String::new(),
             created_at: 0,
+            updated_at: 0,
             valid_from: None,
             valid_to: None,
+            properties: Properties::new(),
         }
     }
 
@@ -53,12 +60,27 @@ impl Relation {
             relation_type,
             created_by: String::new(),
             created_at: 0,
+            updated_at: 0,
             valid_from,
             valid_to,
+            properties: Properties::new(),
         }
     }
 }
 
+/// Relation query for `query_relations`; unset fields match every relation
+#[derive(Debug, Clone, Default)]
+pub struct RelationQuery {
+    /// Source entity name or id
+    pub from: Option<String>,
+    /// Target entity name or id
+    pub to: Option<String>,
+    /// Relation type (or an alias of it)
+    pub relation_type: Option<String>,
+    /// Predicates that must all hold for the relation's properties
+    pub properties: Vec<PropertyFilter>,
+}
+
 /// Related entity with relation info
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RelatedEntity {
@@ -68,9 +90,143 @@ pub struct RelatedEntity {
     pub entity: Entity,
 }
 
+/// Order of `get_related` results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RelatedSort {
+    /// Graph (relation insertion) order, the default
+    #[default]
+    Graph,
+    /// Neighbor entity name
+    Name,
+    /// Neighbor entity type, then name
+    EntityType,
+    /// Relation type, then neighbor name
+    RelationType,
+}
+
+impl RelatedSort {
+    /// Parse a sort key as used in tool params
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "graph" => Some(Self::Graph),
+            "name" => Some(Self::Name),
+            "entityType" | "entity_type" | "type" => Some(Self::EntityType),
+            "relationType" | "relation_type" => Some(Self::RelationType),
+            _ => None,
+        }
+    }
+}
+
+/// Sorting and pagination for `get_related`; the default returns everything
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RelatedPage {
+    pub sort: RelatedSort,
+    /// Number of sorted results to skip
+    pub offset: usize,
+    /// Maximum number of results to return (None = no limit)
+    pub limit: Option<usize>,
+}
+
+/// Filters, sorting and pagination for `get_related`
+///
+/// The default lists every neighbor in both directions, in graph order.
+#[derive(Debug, Clone)]
+pub struct RelatedOptions {
+    /// Only follow relations of this type (aliases resolve)
+    pub relation_type: Option<String>,
+    /// `"outgoing"`, `"incoming"` or `"both"`
+    pub direction: String,
+    /// List a neighbor linked both ways by one relation type once, as `"bidirectional"`
+    pub collapse_reciprocal: bool,
+    /// Only follow relations whose properties match all of these
+    pub properties: Vec<PropertyFilter>,
+    pub page: RelatedPage,
+}
+
+impl Default for RelatedOptions {
+    fn default() -> Self {
+        Self {
+            relation_type: None,
+            direction: "both".to_string(),
+            collapse_reciprocal: false,
+            properties: Vec::new(),
+            page: RelatedPage::default(),
+        }
+    }
+}
+
 /// Result of get_related query
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RelatedEntities {
     pub entity: String,
     pub relations: Vec<RelatedEntity>,
+    /// Number of related entities before pagination
+    pub total: usize,
+}
+
+/// Entity suggested as related, from observation terms shared with the target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationSuggestion {
+    pub entity: String,
+    #[serde(rename = "entityType")]
+    pub entity_type: String,
+    /// Share of the target's term weight found in this entity (0.0 - 1.0)
+    pub score: f32,
+    /// Shared terms, rarest first
+    #[serde(rename = "sharedTerms")]
+    pub shared_terms: Vec<String>,
+}
+
+/// Incoming references sharing a relation type and source entity type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferenceGroup {
+    #[serde(rename = "relationType")]
+    pub relation_type: String,
+    #[serde(rename = "sourceType")]
+    pub source_type: String,
+    pub count: usize,
+    pub sources: Vec<String>,
+}
+
+/// Result of references_to query ("what links here")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct References {
+    pub entity: String,
+    pub total: usize,
+    pub groups: Vec<ReferenceGroup>,
+}
+
+/// A single relation retyped by `rename_relation_type`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelationTypeChange {
+    pub from: String,
+    pub to: String,
+    /// The renamed triple already existed, so this relation is dropped
+    pub duplicate: bool,
+}
+
+/// What `normalize_relations` does to a relation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NormalizeAction {
+    /// The type is rewritten to its normalized form
+    Retyped,
+    /// Another relation has the same normalized triple, so this one is dropped
+    Duplicate,
+    /// The reverse relation of a symmetric type exists, so this one is dropped
+    Reciprocal,
+}
+
+/// A single relation changed by `normalize_relations`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelationNormalization {
+    pub from: String,
+    pub to: String,
+    /// Type before normalization
+    #[serde(rename = "relationType")]
+    pub relation_type: String,
+    /// Normalized type
+    #[serde(rename = "normalizedType")]
+    pub normalized_type: String,
+    pub action: NormalizeAction,
 }
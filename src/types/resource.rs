@@ -0,0 +1,16 @@
+//! Resource types
+
+use serde::{Deserialize, Serialize};
+
+/// A tool result stored for `resources/read`, as listed by `resources/list`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResultResource {
+    /// `memory://results/<id>`
+    pub uri: String,
+    /// Tool that produced the result, e.g. `read_graph result`
+    pub name: String,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+    /// Size of the stored text in bytes
+    pub size: usize,
+}
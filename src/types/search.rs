@@ -0,0 +1,140 @@
+//! Search request and result types
+
+use serde::{Deserialize, Serialize};
+
+use super::{Entity, Relation};
+
+/// Entity field that a search query can be matched against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchField {
+    #[serde(rename = "name")]
+    Name,
+    #[serde(rename = "entityType")]
+    EntityType,
+    #[serde(rename = "observations")]
+    Observations,
+}
+
+impl SearchField {
+    /// Parse a field name as used in tool params and query strings
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "name" => Some(Self::Name),
+            "entityType" | "entity_type" | "type" => Some(Self::EntityType),
+            "observations" | "observation" => Some(Self::Observations),
+            _ => None,
+        }
+    }
+
+    /// Parse a list of field names, failing on the first unknown one
+    pub fn parse_list<'a>(values: impl IntoIterator<Item = &'a str>) -> Result<Vec<Self>, String> {
+        values
+            .into_iter()
+            .map(|v| Self::parse(v).ok_or_else(|| format!("Unknown search field '{}'", v.trim())))
+            .collect()
+    }
+}
+
+/// Options shared by every search surface (MCP tool, REST)
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+    /// Maximum number of entities to return (None = no limit)
+    pub limit: Option<usize>,
+    /// Number of ranked entities to skip
+    pub offset: usize,
+    /// Include relations connected to the returned entities
+    pub include_relations: bool,
+    /// Restrict matching to these fields (None = all fields)
+    pub fields: Option<Vec<SearchField>>,
+    /// Only return entities of these types (case-insensitive)
+    pub entity_types: Option<Vec<String>>,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            limit: None,
+            offset: 0,
+            include_relations: true,
+            fields: None,
+            entity_types: None,
+        }
+    }
+}
+
+impl SearchOptions {
+    /// Check whether a field should be searched
+    pub fn searches(&self, field: SearchField) -> bool {
+        self.fields
+            .as_ref()
+            .map(|fields| fields.contains(&field))
+            .unwrap_or(true)
+    }
+}
+
+/// Entity with its relevance score for a query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoredEntity {
+    #[serde(flatten)]
+    pub entity: Entity,
+    pub score: f64,
+}
+
+/// Paginated, ranked search result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    /// Matching entities ordered by descending score
+    pub entities: Vec<ScoredEntity>,
+    pub relations: Vec<Relation>,
+    /// Total number of matches before pagination
+    pub total: usize,
+}
+
+/// Search matches of one entity type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchGroup {
+    #[serde(rename = "entityType")]
+    pub entity_type: String,
+    /// Number of entities in this group
+    pub count: usize,
+    pub entities: Vec<ScoredEntity>,
+}
+
+/// Search result bucketed by entity type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupedSearchResult {
+    /// Groups ordered by their best-ranked entity
+    pub groups: Vec<SearchGroup>,
+    pub relations: Vec<Relation>,
+    /// Total number of matches before pagination
+    pub total: usize,
+}
+
+impl SearchResult {
+    /// Bucket the returned page by entity type, keeping rank order within groups
+    pub fn group_by_type(self) -> GroupedSearchResult {
+        let mut groups: Vec<SearchGroup> = Vec::new();
+        for scored in self.entities {
+            match groups
+                .iter_mut()
+                .find(|g| g.entity_type == scored.entity.entity_type)
+            {
+                Some(group) => group.entities.push(scored),
+                None => groups.push(SearchGroup {
+                    entity_type: scored.entity.entity_type.clone(),
+                    count: 0,
+                    entities: vec![scored],
+                }),
+            }
+        }
+        for group in &mut groups {
+            group.count = group.entities.len();
+        }
+
+        GroupedSearchResult {
+            groups,
+            relations: self.relations,
+            total: self.total,
+        }
+    }
+}
@@ -19,6 +19,24 @@ pub struct Summary {
     pub by_type: Option<HashMap<String, usize>>,
     #[serde(rename = "byPriority", skip_serializing_if = "Option::is_none")]
     pub by_priority: Option<HashMap<String, usize>>,
+    /// Entity counts per value of each requested attribute key
+    #[serde(rename = "byAttribute", skip_serializing_if = "Option::is_none")]
+    pub by_attribute: Option<HashMap<String, HashMap<String, usize>>>,
+    /// Counts of relations touching the summarized entities, by type
+    #[serde(rename = "byRelationType", skip_serializing_if = "Option::is_none")]
+    pub by_relation_type: Option<HashMap<String, usize>>,
+    /// Summarized entities with the most relations, highest degree first
+    #[serde(rename = "topConnected", skip_serializing_if = "Option::is_none")]
+    pub top_connected: Option<Vec<ConnectedEntity>>,
+}
+
+/// Entity and its number of relations (in either direction)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectedEntity {
+    pub name: String,
+    #[serde(rename = "entityType")]
+    pub entity_type: String,
+    pub degree: usize,
 }
 
 impl Summary {
@@ -52,6 +70,47 @@ impl Summary {
             by_status,
             by_type: Some(by_type),
             by_priority,
+            ..Default::default()
         }
     }
 }
+
+/// De-facto schema of a graph, inferred from its contents
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphSchema {
+    /// Entity count per entity type
+    #[serde(rename = "entityTypes")]
+    pub entity_types: HashMap<String, usize>,
+    /// Observed (source type, relation type, target type) triples, most frequent first
+    pub relations: Vec<SchemaEdge>,
+    /// Relations skipped because an endpoint does not exist
+    #[serde(rename = "danglingRelations")]
+    pub dangling_relations: usize,
+}
+
+/// How often a relation type connects two entity types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaEdge {
+    #[serde(rename = "sourceType")]
+    pub source_type: String,
+    #[serde(rename = "relationType")]
+    pub relation_type: String,
+    #[serde(rename = "targetType")]
+    pub target_type: String,
+    pub count: usize,
+}
+
+/// Compacted read-only view of an entity's observations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactView {
+    pub name: String,
+    #[serde(rename = "entityType")]
+    pub entity_type: String,
+    /// Kept observations, in their stored order
+    pub observations: Vec<String>,
+    /// Number of observations stored on the entity
+    #[serde(rename = "totalObservations")]
+    pub total_observations: usize,
+    /// Number of stored observations left out of this view
+    pub omitted: usize,
+}
@@ -0,0 +1,31 @@
+//! Entity timeline types
+
+use serde::{Deserialize, Serialize};
+
+/// One step in an entity's history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    /// Unix timestamp, or None when the storage mode does not record one
+    pub timestamp: Option<i64>,
+    /// What happened, using event type names (`entity_created`, `observation_added`, ...)
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(rename = "eventId", skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<u64>,
+    /// Event payload, or the relevant part of the current state
+    pub details: serde_json::Value,
+}
+
+/// Chronological history of one entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityTimeline {
+    pub entity: String,
+    /// `event_log` in Event Sourcing mode, `current_state` in legacy mode
+    pub source: String,
+    /// Dated entries first, oldest to newest, then undated ones
+    pub entries: Vec<TimelineEntry>,
+    /// History that this storage mode cannot reconstruct
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unavailable: Vec<String>,
+}
@@ -0,0 +1,66 @@
+//! Transaction operation types
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Entity, Observation, ObservationDeletion, ObservationReplacement, ObservationReplacementResult,
+    Relation,
+};
+
+/// A single mutation inside a transaction
+///
+/// Serialized with an `op` tag, e.g. `{"op": "create_entities", "entities": [...]}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Op {
+    CreateEntities {
+        entities: Vec<Entity>,
+    },
+    CreateRelations {
+        relations: Vec<Relation>,
+    },
+    AddObservations {
+        observations: Vec<Observation>,
+    },
+    ReplaceObservations {
+        replacements: Vec<ObservationReplacement>,
+    },
+    DeleteEntities {
+        #[serde(rename = "entityNames")]
+        entity_names: Vec<String>,
+        /// Also delete protected entities
+        #[serde(default)]
+        force: bool,
+    },
+    DeleteObservations {
+        deletions: Vec<ObservationDeletion>,
+    },
+    DeleteRelations {
+        relations: Vec<Relation>,
+    },
+}
+
+/// Result of a committed operation, in the same order as the submitted ops
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum OpResult {
+    CreateEntities {
+        created: Vec<Entity>,
+    },
+    CreateRelations {
+        created: Vec<Relation>,
+    },
+    AddObservations {
+        added: Vec<Observation>,
+    },
+    ReplaceObservations {
+        replaced: Vec<ObservationReplacementResult>,
+    },
+    DeleteEntities {
+        /// Protected entities left in place
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        skipped: Vec<String>,
+    },
+    DeleteObservations,
+    DeleteRelations,
+}
@@ -1,17 +1,66 @@
 //! Graph traversal types
 
-use serde::{Deserialize, Serialize};
+use std::env;
+use std::sync::OnceLock;
 
-use super::Entity;
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+use super::{Entity, Relation};
+
+/// Upper bound on `maxHops` for a single variable-length step
+pub const MAX_STEP_HOPS: usize = 10;
+
+/// Upper bound on the number of paths `k_shortest_paths` returns
+pub const MAX_K_PATHS: usize = 20;
+
+/// Upper bound on the length in hops of a `k_shortest_paths` path
+pub const MAX_PATH_DEPTH: usize = 10;
+
+/// Default bound on the length of a `traverse` path
+pub const DEFAULT_MAX_TRAVERSE_DEPTH: usize = 10;
+
+/// Server default from `MEMORY_DEFAULT_DIRECTION`, read once
+static DEFAULT_DIRECTION: OnceLock<&'static str> = OnceLock::new();
+
+/// Server bound from `MEMORY_MAX_TRAVERSE_DEPTH`, read once
+static MAX_TRAVERSE_DEPTH: OnceLock<usize> = OnceLock::new();
+
+/// Longest `traverse` path allowed: `MEMORY_MAX_TRAVERSE_DEPTH`, or
+/// [`DEFAULT_MAX_TRAVERSE_DEPTH`]
+///
+/// Both the number of steps and the total hops (each step counting its
+/// `maxHops`) must stay within it.
+pub fn max_traverse_depth() -> usize {
+    *MAX_TRAVERSE_DEPTH.get_or_init(|| {
+        env::var("MEMORY_MAX_TRAVERSE_DEPTH")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|&v| v > 0)
+            .unwrap_or(DEFAULT_MAX_TRAVERSE_DEPTH)
+    })
+}
 
 /// Path step for traverse query
+///
+/// `direction` is `"out"`, `"in"` or `"both"`; `"outgoing"` and `"incoming"`
+/// are accepted as in `get_related`, and anything else fails to deserialize.
+/// A step without one uses [`PathStep::default_direction`]. Without
+/// `minHops`/`maxHops` the step matches exactly one hop.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PathStep {
     #[serde(rename = "relationType")]
     pub relation_type: String,
+    #[serde(
+        default = "default_direction",
+        deserialize_with = "deserialize_direction"
+    )]
     pub direction: String,
     #[serde(rename = "targetType")]
     pub target_type: Option<String>,
+    #[serde(rename = "minHops", default, skip_serializing_if = "Option::is_none")]
+    pub min_hops: Option<usize>,
+    #[serde(rename = "maxHops", default, skip_serializing_if = "Option::is_none")]
+    pub max_hops: Option<usize>,
 }
 
 impl PathStep {
@@ -21,6 +70,8 @@ impl PathStep {
             relation_type,
             direction,
             target_type: None,
+            min_hops: None,
+            max_hops: None,
         }
     }
 
@@ -34,21 +85,100 @@ impl PathStep {
             relation_type,
             direction,
             target_type: Some(target_type),
+            min_hops: None,
+            max_hops: None,
         }
     }
+
+    /// Repeat this step between `min_hops` and `max_hops` times
+    pub fn with_hops(mut self, min_hops: usize, max_hops: usize) -> Self {
+        self.min_hops = Some(min_hops);
+        self.max_hops = Some(max_hops);
+        self
+    }
+
+    /// Canonical form of a direction: `out`, `in` or `both`
+    ///
+    /// `outgoing` and `incoming` are aliases; case is ignored.
+    pub fn parse_direction(value: &str) -> Option<&'static str> {
+        match value.trim().to_lowercase().as_str() {
+            "out" | "outgoing" => Some("out"),
+            "in" | "incoming" => Some("in"),
+            "both" => Some("both"),
+            _ => None,
+        }
+    }
+
+    /// Direction of steps that leave it out: `MEMORY_DEFAULT_DIRECTION`, or `out`
+    pub fn default_direction() -> &'static str {
+        DEFAULT_DIRECTION.get_or_init(|| {
+            env::var("MEMORY_DEFAULT_DIRECTION")
+                .ok()
+                .and_then(|v| Self::parse_direction(&v))
+                .unwrap_or("out")
+        })
+    }
+
+    /// Error for a direction [`PathStep::parse_direction`] does not know
+    pub fn unknown_direction(value: &str) -> String {
+        format!(
+            "Unknown direction '{}': expected out, in or both (or outgoing / incoming)",
+            value
+        )
+    }
+
+    /// Inclusive hop range; a missing bound defaults to the other one, or 1
+    pub fn hop_range(&self) -> (usize, usize) {
+        match (self.min_hops, self.max_hops) {
+            (None, None) => (1, 1),
+            (Some(min), None) => (min, min.max(1)),
+            (None, Some(max)) => (1.min(max), max),
+            (Some(min), Some(max)) => (min, max),
+        }
+    }
+
+    /// Most hops a whole path can take
+    pub fn path_hops(path: &[PathStep]) -> usize {
+        path.iter().map(|step| step.hop_range().1).sum()
+    }
+}
+
+fn default_direction() -> String {
+    PathStep::default_direction().to_string()
+}
+
+fn deserialize_direction<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    PathStep::parse_direction(&value)
+        .map(String::from)
+        .ok_or_else(|| de::Error::custom(PathStep::unknown_direction(&value)))
 }
 
 /// Single path in traversal result
+///
+/// `relations` holds the type of each hop. When asked for, `relationDetails`
+/// holds the relation instance each hop followed, with its properties and
+/// validity, in the same order.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TraversalPath {
     pub nodes: Vec<String>,
     pub relations: Vec<String>,
+    #[serde(
+        rename = "relationDetails",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub relation_details: Option<Vec<Relation>>,
 }
 
 impl TraversalPath {
     /// Create a new traversal path
     pub fn new(nodes: Vec<String>, relations: Vec<String>) -> Self {
-        Self { nodes, relations }
+        Self {
+            nodes,
+            relations,
+            relation_details: None,
+        }
     }
 }
 
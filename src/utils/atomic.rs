@@ -13,10 +13,20 @@
 //! - The old version (if crash before rename)
 //! - The new version (if rename completed)
 //! - Never a partial/corrupted state
+//!
+//! # Retries
+//!
+//! On platforms with exclusive file locks (Windows), a write or rename can
+//! fail while another process briefly holds the file open. Such transient
+//! failures are retried with a doubling backoff, `MEMORY_WRITE_RETRIES` times
+//! (default 3), before the error is returned.
 
+use std::env;
 use std::fs::{self, File};
 use std::io::{self, Write};
 use std::path::Path;
+use std::thread;
+use std::time::Duration;
 
 /// Result type for atomic operations
 pub type AtomicResult<T> = Result<T, AtomicError>;
@@ -45,6 +55,83 @@ impl From<io::Error> for AtomicError {
     }
 }
 
+/// Bounded retry with backoff for transient write failures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteRetry {
+    /// Retries after the first attempt
+    pub retries: u32,
+    /// Delay before the first retry, doubled for each further retry
+    pub backoff: Duration,
+}
+
+impl Default for WriteRetry {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            backoff: Duration::from_millis(10),
+        }
+    }
+}
+
+impl WriteRetry {
+    /// Read the retry count from `MEMORY_WRITE_RETRIES` (default 3, 0 disables)
+    pub fn from_env() -> Self {
+        let mut retry = Self::default();
+        if let Some(retries) = env::var("MEMORY_WRITE_RETRIES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            retry.retries = retries;
+        }
+        retry
+    }
+
+    /// Run `op`, retrying transient failures and logging each retry to stderr
+    pub fn run<T>(
+        &self,
+        action: &str,
+        path: &Path,
+        mut op: impl FnMut() -> io::Result<T>,
+    ) -> io::Result<T> {
+        let mut delay = self.backoff;
+        let mut attempt = 0;
+        loop {
+            match op() {
+                Err(e) if attempt < self.retries && is_transient(&e) => {
+                    attempt += 1;
+                    eprintln!(
+                        "[Persistence] {} {} failed ({}), retry {}/{} in {:?}",
+                        action,
+                        path.display(),
+                        e,
+                        attempt,
+                        self.retries,
+                        delay
+                    );
+                    thread::sleep(delay);
+                    delay *= 2;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Whether an I/O error may clear up if the operation is retried
+///
+/// Covers files held open by another process: access denied while in use,
+/// and the Windows sharing (32) and lock (33) violations.
+fn is_transient(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::PermissionDenied
+            | io::ErrorKind::ResourceBusy
+            | io::ErrorKind::Interrupted
+            | io::ErrorKind::WouldBlock
+            | io::ErrorKind::TimedOut
+    ) || (cfg!(windows) && matches!(e.raw_os_error(), Some(32 | 33)))
+}
+
 /// Atomically write content to a file
 ///
 /// This function:
@@ -71,15 +158,17 @@ pub fn atomic_write<P: AsRef<Path>>(path: P, content: &str) -> AtomicResult<()>
         fs::create_dir_all(parent)?;
     }
 
-    // Write to temp file
-    let mut file = File::create(&temp_path)?;
-    file.write_all(content.as_bytes())?;
+    let retry = WriteRetry::from_env();
 
-    // Sync to disk (ensure data is durable)
-    file.sync_all()?;
+    // Write to temp file and sync to disk (ensure data is durable)
+    retry.run("write", &temp_path, || {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()
+    })?;
 
     // Atomic rename
-    fs::rename(&temp_path, path)?;
+    retry.run("rename", path, || fs::rename(&temp_path, path))?;
 
     Ok(())
 }
@@ -116,15 +205,19 @@ where
         fs::create_dir_all(parent)?;
     }
 
-    // Write to temp file using the provided function
-    let mut file = File::create(&temp_path)?;
+    let retry = WriteRetry::from_env();
+
+    // Write to temp file using the provided function (not retried: it may
+    // only be called once)
+    let mut file = retry.run("create", &temp_path, || File::create(&temp_path))?;
     write_fn(&mut file)?;
 
     // Sync to disk
     file.sync_all()?;
+    drop(file);
 
     // Atomic rename
-    fs::rename(&temp_path, path)?;
+    retry.run("rename", path, || fs::rename(&temp_path, path))?;
 
     Ok(())
 }
@@ -246,6 +339,49 @@ mod tests {
         assert_eq!(content, "nested content");
     }
 
+    #[test]
+    fn test_write_retry_recovers_from_transient_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test.txt");
+        let retry = WriteRetry {
+            retries: 2,
+            backoff: std::time::Duration::from_millis(1),
+        };
+
+        // Locked by another process for the first two attempts
+        let mut attempts = 0;
+        retry
+            .run("write", &path, || {
+                attempts += 1;
+                if attempts <= 2 {
+                    return Err(io::Error::new(io::ErrorKind::PermissionDenied, "in use"));
+                }
+                fs::write(&path, "written")
+            })
+            .unwrap();
+        assert_eq!(attempts, 3);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "written");
+
+        // Still locked after every retry
+        let mut attempts = 0;
+        let err = retry
+            .run("write", &path, || -> io::Result<()> {
+                attempts += 1;
+                Err(io::Error::new(io::ErrorKind::PermissionDenied, "in use"))
+            })
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(attempts, 3);
+
+        // Permanent failures are not retried
+        let mut attempts = 0;
+        let _ = retry.run("write", &path, || -> io::Result<()> {
+            attempts += 1;
+            Err(io::Error::new(io::ErrorKind::NotFound, "gone"))
+        });
+        assert_eq!(attempts, 1);
+    }
+
     #[test]
     fn test_safe_rename_with_backup() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,44 @@
+//! Entity id aliases
+//!
+//! An id is a second key for an entity: tools that take an entity name also
+//! accept its id and resolve it to the name. Relations, events and the memory
+//! file still reference entities by name, so ids do not make renames safe and
+//! display names must stay unique.
+//!
+//! Ids are a 64-bit FNV-1a hash of the entity's name, type and creation time
+//! plus a salt, rendered as 16 hex digits. They are computed once at create
+//! time and stored with the entity (and in its `EntityCreated` event), so the
+//! hash only has to be unique, not reproducible across versions.
+
+use super::hash::Fnv1a;
+
+/// Compute an entity id; bump `salt` to get a different id for the same input
+pub fn entity_id(name: &str, entity_type: &str, created_at: u64, salt: u64) -> String {
+    let mut hash = Fnv1a::new();
+    let fields = [
+        name.as_bytes(),
+        &[0],
+        entity_type.as_bytes(),
+        &[0],
+        &created_at.to_le_bytes(),
+        &salt.to_le_bytes(),
+    ];
+    for field in fields {
+        hash.write(field);
+    }
+    hash.to_hex()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entity_id() {
+        let id = entity_id("Auth", "Module", 1_700_000_000, 0);
+        assert_eq!(id.len(), 16);
+        assert_eq!(id, entity_id("Auth", "Module", 1_700_000_000, 0));
+        assert_ne!(id, entity_id("Auth", "Module", 1_700_000_000, 1));
+        assert_ne!(id, entity_id("Auth", "Service", 1_700_000_000, 0));
+    }
+}
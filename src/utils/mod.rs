@@ -1,9 +1,19 @@
 //! Utility functions and helpers
 //!
-//! This module contains timestamp utilities and other helper functions.
+//! This module contains timestamp utilities, entity id generation, stable
+//! hashing and other helper functions.
 
 pub mod atomic;
+pub mod hash;
+pub mod id;
 pub mod time;
 
-pub use atomic::{atomic_write, atomic_write_with, cleanup_temp_files, safe_rename, AtomicResult};
-pub use time::{current_timestamp, days_to_ymd, get_current_time, get_month_name, get_weekday};
+pub use atomic::{
+    atomic_write, atomic_write_with, cleanup_temp_files, safe_rename, AtomicResult, WriteRetry,
+};
+pub use hash::Fnv1a;
+pub use id::entity_id;
+pub use time::{
+    add_iso_timestamps, current_timestamp, days_to_ymd, format_iso8601, get_current_time,
+    get_month_name, get_weekday, parse_timestamp, ymd_to_days, TIMESTAMP_FIELDS,
+};
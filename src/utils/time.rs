@@ -53,11 +53,7 @@ pub fn get_current_time() -> Value {
     // Calculate year, month, day
     let (year, month, day) = days_to_ymd(days);
 
-    // Format ISO 8601
-    let iso8601 = format!(
-        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
-        year, month, day, hours, minutes, seconds
-    );
+    let iso8601 = format_iso8601(timestamp);
 
     // Format readable
     let weekday = get_weekday(days);
@@ -84,6 +80,44 @@ pub fn get_current_time() -> Value {
     })
 }
 
+/// Format a Unix timestamp in seconds as ISO 8601 UTC (`2024-03-01T10:00:00Z`)
+pub fn format_iso8601(timestamp: u64) -> String {
+    let secs = timestamp as i64;
+    let (year, month, day) = days_to_ymd(secs / 86400);
+    let remaining = secs % 86400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        remaining / 3600,
+        (remaining % 3600) / 60,
+        remaining % 60
+    )
+}
+
+/// Timestamp fields of entities and relations, as serialized
+pub const TIMESTAMP_FIELDS: [&str; 4] = ["createdAt", "updatedAt", "validFrom", "validTo"];
+
+/// Add an ISO 8601 copy (`createdAtIso`, ...) next to every timestamp field in `value`
+///
+/// Walks nested objects and arrays, so it works on any response body holding
+/// entities or relations. The Unix-second fields are left in place.
+pub fn add_iso_timestamps(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for field in TIMESTAMP_FIELDS {
+                if let Some(timestamp) = map.get(field).and_then(Value::as_u64) {
+                    map.insert(format!("{}Iso", field), json!(format_iso8601(timestamp)));
+                }
+            }
+            map.values_mut().for_each(add_iso_timestamps);
+        }
+        Value::Array(items) => items.iter_mut().for_each(add_iso_timestamps),
+        _ => {}
+    }
+}
+
 /// Convert days since epoch to year/month/day
 pub fn days_to_ymd(days: i64) -> (i64, u32, u32) {
     // Algorithm to convert days since epoch to year/month/day
@@ -102,6 +136,43 @@ pub fn days_to_ymd(days: i64) -> (i64, u32, u32) {
     (year, month, day)
 }
 
+/// Convert year/month/day to days since epoch (inverse of `days_to_ymd`)
+pub fn ymd_to_days(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146097 + doe - 719468
+}
+
+/// Parse a Unix timestamp in seconds or a `YYYY-MM-DD` date (midnight UTC)
+///
+/// Anything after the date (`2024-03-01T10:00`) is ignored.
+pub fn parse_timestamp(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(seconds);
+    }
+
+    let date = value.get(..10)?;
+    let mut parts = date.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    if date.len() != 10 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let days = ymd_to_days(year, month, day);
+    // Reject dates that roll over into the next month (2024-02-30)
+    if days_to_ymd(days) != (year, month, day) || days < 0 {
+        return None;
+    }
+    Some(days as u64 * 86400)
+}
+
 /// Get weekday name from days since epoch
 pub fn get_weekday(days: i64) -> &'static str {
     match (days + 4) % 7 {
@@ -0,0 +1,100 @@
+//! Relation type aliases
+//!
+//! Agents spell the same relation type in different ways (`depends_on`,
+//! `dependsOn`, `depends on`). `MEMORY_RELATION_ALIASES` maps aliases to a
+//! canonical type as a comma-separated list of `alias=canonical` pairs:
+//!
+//! ```text
+//! MEMORY_RELATION_ALIASES=dependsOn=depends_on,depends on=depends_on
+//! ```
+//!
+//! Aliases match case-insensitively. New relations are stored under the
+//! canonical type, and relation-type filters in queries match every alias of
+//! the type they name, including relations stored before the alias was added.
+//! Each rewrite is logged to stderr. Without the variable nothing is rewritten.
+
+use std::collections::HashMap;
+use std::env;
+
+/// Configured relation type aliases
+#[derive(Debug, Clone, Default)]
+pub struct RelationAliases {
+    /// Lowercased alias -> canonical type
+    aliases: HashMap<String, String>,
+}
+
+impl RelationAliases {
+    /// No aliases (the default)
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Read aliases from `MEMORY_RELATION_ALIASES`
+    pub fn from_env() -> Self {
+        env::var("MEMORY_RELATION_ALIASES")
+            .map(|spec| Self::parse(&spec))
+            .unwrap_or_default()
+    }
+
+    /// Parse an `alias=canonical,...` list; malformed entries are ignored
+    pub fn parse(spec: &str) -> Self {
+        spec.split(',')
+            .filter_map(|entry| entry.split_once('='))
+            .fold(Self::default(), |aliases, (alias, canonical)| {
+                aliases.with_alias(alias, canonical)
+            })
+    }
+
+    /// Map `alias` to `canonical`
+    pub fn with_alias(mut self, alias: &str, canonical: &str) -> Self {
+        let (alias, canonical) = (alias.trim(), canonical.trim());
+        if !alias.is_empty() && !canonical.is_empty() {
+            self.aliases
+                .insert(alias.to_lowercase(), canonical.to_string());
+        }
+        self
+    }
+
+    /// Canonical form of a relation type (the type itself if it is no alias)
+    pub fn canonical<'a>(&'a self, relation_type: &'a str) -> &'a str {
+        if self.aliases.is_empty() {
+            return relation_type;
+        }
+        self.aliases
+            .get(&relation_type.trim().to_lowercase())
+            .map(String::as_str)
+            .unwrap_or(relation_type)
+    }
+
+    /// Rewrite a relation type to its canonical form, logging any change
+    pub fn normalize(&self, relation_type: &str) -> String {
+        let canonical = self.canonical(relation_type);
+        if canonical != relation_type {
+            eprintln!(
+                "[Relation Aliases] Normalized relation type '{}' to '{}'",
+                relation_type, canonical
+            );
+        }
+        canonical.to_string()
+    }
+
+    /// Whether any alias is configured
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_aliases() {
+        let aliases = RelationAliases::parse("dependsOn=depends_on, depends on = depends_on,bogus");
+        assert_eq!(aliases.canonical("dependsOn"), "depends_on");
+        assert_eq!(aliases.canonical("DEPENDSON"), "depends_on");
+        assert_eq!(aliases.canonical("depends on"), "depends_on");
+        assert_eq!(aliases.canonical("implements"), "implements");
+        assert!(RelationAliases::parse("").is_empty());
+    }
+}
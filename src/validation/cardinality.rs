@@ -0,0 +1,107 @@
+//! Relation cardinality constraints
+//!
+//! A functional relation type allows at most one outgoing relation of that
+//! type per entity (e.g. one `assigned_to`). Constraints are read from
+//! `MEMORY_FUNCTIONAL_RELATIONS`, a comma-separated list of `type` or
+//! `type:mode` entries where mode is `reject` (default) or `replace`:
+//!
+//! ```text
+//! MEMORY_FUNCTIONAL_RELATIONS=assigned_to:replace,reports_to
+//! ```
+//!
+//! Relation types that are not listed stay many-to-many.
+
+use std::collections::HashMap;
+use std::env;
+
+/// What to do when a new relation would break a functional constraint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CardinalityMode {
+    /// Keep the existing relation and drop the new one
+    #[default]
+    Reject,
+    /// Delete the existing relation and create the new one
+    Replace,
+}
+
+/// Configured cardinality constraints, keyed by relation type
+#[derive(Debug, Clone, Default)]
+pub struct CardinalityConstraints {
+    functional: HashMap<String, CardinalityMode>,
+}
+
+impl CardinalityConstraints {
+    /// No constraints (the default)
+    pub fn unconstrained() -> Self {
+        Self::default()
+    }
+
+    /// Read constraints from `MEMORY_FUNCTIONAL_RELATIONS`
+    pub fn from_env() -> Self {
+        env::var("MEMORY_FUNCTIONAL_RELATIONS")
+            .map(|spec| Self::parse(&spec))
+            .unwrap_or_default()
+    }
+
+    /// Parse a `type[:mode],...` list; unknown modes fall back to `reject`
+    pub fn parse(spec: &str) -> Self {
+        let functional = spec
+            .split(',')
+            .filter_map(|entry| {
+                let (relation_type, mode) = match entry.split_once(':') {
+                    Some((t, m)) if m.trim().eq_ignore_ascii_case("replace") => {
+                        (t, CardinalityMode::Replace)
+                    }
+                    Some((t, _)) => (t, CardinalityMode::Reject),
+                    None => (entry, CardinalityMode::Reject),
+                };
+                let relation_type = relation_type.trim();
+                (!relation_type.is_empty()).then(|| (relation_type.to_string(), mode))
+            })
+            .collect();
+
+        Self { functional }
+    }
+
+    /// Mark a relation type as functional
+    pub fn with_functional(mut self, relation_type: &str, mode: CardinalityMode) -> Self {
+        self.functional.insert(relation_type.to_string(), mode);
+        self
+    }
+
+    /// Constraint mode for a relation type, or `None` if unconstrained
+    pub fn functional_mode(&self, relation_type: &str) -> Option<CardinalityMode> {
+        self.functional.get(relation_type).copied()
+    }
+
+    /// Whether any relation type is constrained
+    pub fn is_empty(&self) -> bool {
+        self.functional.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_functional_relations() {
+        let constraints = CardinalityConstraints::parse("assigned_to:replace, reports_to,,x:bogus");
+        assert_eq!(
+            constraints.functional_mode("assigned_to"),
+            Some(CardinalityMode::Replace)
+        );
+        assert_eq!(
+            constraints.functional_mode("reports_to"),
+            Some(CardinalityMode::Reject)
+        );
+        assert_eq!(constraints.functional_mode("x"), Some(CardinalityMode::Reject));
+        assert_eq!(constraints.functional_mode("depends_on"), None);
+    }
+
+    #[test]
+    fn test_unconstrained_by_default() {
+        assert!(CardinalityConstraints::unconstrained().is_empty());
+        assert!(CardinalityConstraints::parse("").is_empty());
+    }
+}
@@ -0,0 +1,187 @@
+//! Observation size limits
+//!
+//! Protects the store from accidental dumps (e.g. pasted log output). All
+//! limits are disabled by default:
+//! - `MEMORY_MAX_OBSERVATION_LEN`: maximum characters per observation
+//! - `MEMORY_MAX_OBSERVATIONS_PER_ENTITY`: maximum observations per entity
+//! - `MEMORY_OBSERVATION_LIMIT_MODE`: `truncate` (default) or `reject` for
+//!   observations over the length limit
+
+use std::env;
+
+use super::{ValidationWarning, ValidationWarningKind};
+
+/// What to do with an observation longer than the limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LimitMode {
+    #[default]
+    Truncate,
+    Reject,
+}
+
+/// Configured observation limits
+#[derive(Debug, Clone, Default)]
+pub struct ObservationLimits {
+    pub max_len: Option<usize>,
+    pub max_per_entity: Option<usize>,
+    pub mode: LimitMode,
+}
+
+impl ObservationLimits {
+    /// No limits (the default)
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Read limits from the environment
+    pub fn from_env() -> Self {
+        let parse = |key: &str| {
+            env::var(key)
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .filter(|&v| v > 0)
+        };
+
+        let mode = match env::var("MEMORY_OBSERVATION_LIMIT_MODE") {
+            Ok(v) if v.eq_ignore_ascii_case("reject") => LimitMode::Reject,
+            _ => LimitMode::Truncate,
+        };
+
+        Self {
+            max_len: parse("MEMORY_MAX_OBSERVATION_LEN"),
+            max_per_entity: parse("MEMORY_MAX_OBSERVATIONS_PER_ENTITY"),
+            mode,
+        }
+    }
+
+    /// Check one observation about to be added to an entity
+    ///
+    /// `current_count` is the number of observations the entity already holds.
+    /// Returns the content to store (possibly truncated) or `None` if it was
+    /// rejected, plus a warning describing what happened.
+    pub fn admit(
+        &self,
+        entity_name: &str,
+        current_count: usize,
+        content: &str,
+    ) -> (Option<String>, Option<ValidationWarning>) {
+        if let Some(max) = self.max_per_entity {
+            if current_count >= max {
+                return (
+                    None,
+                    Some(ValidationWarning::new(
+                        ValidationWarningKind::ObservationRejected,
+                        entity_name.to_string(),
+                        format!(
+                            "Entity already has {} observations (max {}); dropped '{}'",
+                            current_count,
+                            max,
+                            preview(content)
+                        ),
+                    )),
+                );
+            }
+        }
+
+        if let Some(max) = self.max_len {
+            let len = content.chars().count();
+            if len > max {
+                return match self.mode {
+                    LimitMode::Truncate => (
+                        Some(content.chars().take(max).collect()),
+                        Some(ValidationWarning::new(
+                            ValidationWarningKind::ObservationTruncated,
+                            entity_name.to_string(),
+                            format!(
+                                "Observation truncated from {} to {} characters: '{}'",
+                                len,
+                                max,
+                                preview(content)
+                            ),
+                        )),
+                    ),
+                    LimitMode::Reject => (
+                        None,
+                        Some(ValidationWarning::new(
+                            ValidationWarningKind::ObservationRejected,
+                            entity_name.to_string(),
+                            format!(
+                                "Observation of {} characters exceeds limit of {}: '{}'",
+                                len,
+                                max,
+                                preview(content)
+                            ),
+                        )),
+                    ),
+                };
+            }
+        }
+
+        (Some(content.to_string()), None)
+    }
+}
+
+/// Short prefix of an observation for warning messages
+fn preview(content: &str) -> String {
+    const PREVIEW_LEN: usize = 40;
+    if content.chars().count() > PREVIEW_LEN {
+        format!(
+            "{}...",
+            content.chars().take(PREVIEW_LEN).collect::<String>()
+        )
+    } else {
+        content.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlimited_admits_everything() {
+        let limits = ObservationLimits::unlimited();
+        let (content, warning) = limits.admit("A", 1000, &"x".repeat(10_000));
+        assert_eq!(content.unwrap().len(), 10_000);
+        assert!(warning.is_none());
+    }
+
+    #[test]
+    fn test_truncate_long_observation() {
+        let limits = ObservationLimits {
+            max_len: Some(5),
+            ..Default::default()
+        };
+        let (content, warning) = limits.admit("A", 0, "abcdefgh");
+        assert_eq!(content.unwrap(), "abcde");
+        assert_eq!(
+            warning.unwrap().kind,
+            ValidationWarningKind::ObservationTruncated
+        );
+    }
+
+    #[test]
+    fn test_reject_long_observation() {
+        let limits = ObservationLimits {
+            max_len: Some(5),
+            mode: LimitMode::Reject,
+            ..Default::default()
+        };
+        let (content, warning) = limits.admit("A", 0, "abcdefgh");
+        assert!(content.is_none());
+        assert_eq!(
+            warning.unwrap().kind,
+            ValidationWarningKind::ObservationRejected
+        );
+    }
+
+    #[test]
+    fn test_per_entity_cap() {
+        let limits = ObservationLimits {
+            max_per_entity: Some(2),
+            ..Default::default()
+        };
+        assert!(limits.admit("A", 1, "ok").0.is_some());
+        assert!(limits.admit("A", 2, "too many").0.is_none());
+    }
+}
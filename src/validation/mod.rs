@@ -1,9 +1,27 @@
 //! Type validation for entities and relations
 //!
-//! This module provides soft validation for standard entity and relation types.
+//! This module provides soft validation for standard entity and relation types,
+//! plus optional observation size limits, observation deduplication rules,
+//! relation cardinality constraints, relation type aliases, the entity name
+//! policy and per-type entity templates.
 
+mod aliases;
+mod cardinality;
+mod limits;
+mod names;
+mod normalize;
+mod templates;
 mod types;
+mod warning;
 
+pub use aliases::RelationAliases;
+pub use cardinality::{CardinalityConstraints, CardinalityMode};
+pub use limits::{LimitMode, ObservationLimits};
+pub use names::{NameMode, NamePolicy};
+pub use normalize::{normalize_observation, DedupMode};
+pub use templates::EntityTemplates;
 pub use types::{
-    validate_entity_type, validate_relation_type, STANDARD_ENTITY_TYPES, STANDARD_RELATION_TYPES,
+    validate_entity_type, validate_relation_type, warnings_suppressed, STANDARD_ENTITY_TYPES,
+    STANDARD_RELATION_TYPES,
 };
+pub use warning::{ValidationWarning, ValidationWarningKind};
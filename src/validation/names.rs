@@ -0,0 +1,151 @@
+//! Entity name policy
+//!
+//! Names that differ only in surrounding whitespace (`" Alice "` vs `"Alice"`)
+//! are a common source of "entity not found" errors. By default new entity
+//! names are trimmed and empty or whitespace-only names are rejected:
+//! - `MEMORY_ENTITY_NAME_POLICY`: `normalize` (default) or `verbatim` to store
+//!   names exactly as given, with no checks
+//! - `MEMORY_ENTITY_NAME_CASE_FOLD`: `true` to also lowercase names
+//! - `MEMORY_MAX_ENTITY_NAME_LEN`: reject names longer than this many
+//!   characters (after normalization)
+//!
+//! Every normalized or rejected name is reported as a warning.
+
+use std::borrow::Cow;
+use std::env;
+
+use super::{ValidationWarning, ValidationWarningKind};
+
+/// How entity names are checked on create
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NameMode {
+    /// Trim (and optionally case-fold) names; reject empty ones
+    #[default]
+    Normalize,
+    /// Accept names exactly as given
+    Verbatim,
+}
+
+/// Configured entity name policy
+#[derive(Debug, Clone, Default)]
+pub struct NamePolicy {
+    pub mode: NameMode,
+    pub case_fold: bool,
+    pub max_len: Option<usize>,
+}
+
+impl NamePolicy {
+    /// Accept every name unchanged
+    pub fn verbatim() -> Self {
+        Self {
+            mode: NameMode::Verbatim,
+            ..Default::default()
+        }
+    }
+
+    /// Read the policy from the environment
+    pub fn from_env() -> Self {
+        let mode = match env::var("MEMORY_ENTITY_NAME_POLICY") {
+            Ok(v) if v.eq_ignore_ascii_case("verbatim") => NameMode::Verbatim,
+            _ => NameMode::Normalize,
+        };
+        let case_fold = env::var("MEMORY_ENTITY_NAME_CASE_FOLD")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+        let max_len = env::var("MEMORY_MAX_ENTITY_NAME_LEN")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|&v| v > 0);
+
+        Self {
+            mode,
+            case_fold,
+            max_len: if mode == NameMode::Verbatim { None } else { max_len },
+        }
+    }
+
+    /// Normalized form of a name, used for lookups as well as on create
+    pub fn normalize<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        match self.mode {
+            NameMode::Verbatim => Cow::Borrowed(name),
+            NameMode::Normalize if self.case_fold => Cow::Owned(name.trim().to_lowercase()),
+            NameMode::Normalize => Cow::Borrowed(name.trim()),
+        }
+    }
+
+    /// Check the name of an entity about to be created
+    ///
+    /// Returns the name to store, or `None` if it was rejected, plus a
+    /// warning describing what happened.
+    pub fn admit(&self, name: &str) -> (Option<String>, Option<ValidationWarning>) {
+        if self.mode == NameMode::Verbatim {
+            return (Some(name.to_string()), None);
+        }
+
+        let normalized = self.normalize(name);
+        if normalized.is_empty() {
+            return (
+                None,
+                Some(ValidationWarning::new(
+                    ValidationWarningKind::EntityNameRejected,
+                    name.to_string(),
+                    "Entity name is empty or whitespace-only".to_string(),
+                )),
+            );
+        }
+
+        if let Some(max) = self.max_len {
+            let len = normalized.chars().count();
+            if len > max {
+                return (
+                    None,
+                    Some(ValidationWarning::new(
+                        ValidationWarningKind::EntityNameRejected,
+                        name.to_string(),
+                        format!("Entity name of {} characters exceeds limit of {}", len, max),
+                    )),
+                );
+            }
+        }
+
+        let warning = (normalized != name).then(|| {
+            ValidationWarning::new(
+                ValidationWarningKind::EntityNameNormalized,
+                normalized.to_string(),
+                format!("Entity name '{}' stored as '{}'", name, normalized),
+            )
+        });
+        (Some(normalized.into_owned()), warning)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_names() {
+        let policy = NamePolicy::default();
+        let (name, warning) = policy.admit("  Alice ");
+        assert_eq!(name.as_deref(), Some("Alice"));
+        assert_eq!(warning.unwrap().kind, ValidationWarningKind::EntityNameNormalized);
+        assert!(policy.admit("Alice").1.is_none());
+        assert!(policy.admit(" \t ").0.is_none());
+
+        let strict = NamePolicy {
+            case_fold: true,
+            max_len: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(strict.admit(" ALICE ").0.as_deref(), Some("alice"));
+        assert!(strict.admit("Alexandra").0.is_none());
+    }
+
+    #[test]
+    fn test_verbatim_keeps_names() {
+        let policy = NamePolicy::verbatim();
+        assert_eq!(policy.admit(" Alice ").0.as_deref(), Some(" Alice "));
+        assert_eq!(policy.admit("").0.as_deref(), Some(""));
+        assert_eq!(policy.normalize(" Alice "), " Alice ");
+    }
+}
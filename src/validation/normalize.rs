@@ -0,0 +1,88 @@
+//! Observation deduplication
+//!
+//! By default an observation is a duplicate only if it matches an existing one
+//! exactly. With `MEMORY_OBSERVATION_DEDUP=normalized`, observations are
+//! compared after normalization:
+//! - leading and trailing whitespace is trimmed
+//! - runs of internal whitespace collapse to a single space
+//! - whitespace next to ASCII punctuation is dropped, so `Status: done` and
+//!   `Status:done` match
+//! - comparison is case-insensitive
+//!
+//! The stored text is never rewritten; normalization only decides whether an
+//! observation is new.
+
+use std::borrow::Cow;
+use std::env;
+
+/// How observations are compared when checking for duplicates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DedupMode {
+    /// Byte-for-byte equality
+    #[default]
+    Exact,
+    /// Equality after [`normalize_observation`]
+    Normalized,
+}
+
+impl DedupMode {
+    /// Read the mode from `MEMORY_OBSERVATION_DEDUP` (`exact` or `normalized`)
+    pub fn from_env() -> Self {
+        match env::var("MEMORY_OBSERVATION_DEDUP") {
+            Ok(v) if v.eq_ignore_ascii_case("normalized") => DedupMode::Normalized,
+            _ => DedupMode::Exact,
+        }
+    }
+
+    /// Key under which an observation is compared
+    pub fn key<'a>(&self, content: &'a str) -> Cow<'a, str> {
+        match self {
+            DedupMode::Exact => Cow::Borrowed(content),
+            DedupMode::Normalized => Cow::Owned(normalize_observation(content)),
+        }
+    }
+}
+
+/// Normalize an observation for duplicate detection
+pub fn normalize_observation(content: &str) -> String {
+    let mut normalized = String::with_capacity(content.len());
+    let mut pending_space = false;
+
+    for c in content.trim().chars() {
+        if c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+
+        let after_punct = normalized.chars().last().is_some_and(|p| p.is_ascii_punctuation());
+        if pending_space && !c.is_ascii_punctuation() && !after_punct {
+            normalized.push(' ');
+        }
+        pending_space = false;
+        normalized.extend(c.to_lowercase());
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_observation() {
+        assert_eq!(normalize_observation("  Status:   Done \t"), "status:done");
+        assert_eq!(
+            normalize_observation("Status:done"),
+            normalize_observation("status : DONE")
+        );
+        assert_eq!(normalize_observation("uses  OAuth2"), "uses oauth2");
+        assert_ne!(normalize_observation("a b"), normalize_observation("ab"));
+    }
+
+    #[test]
+    fn test_exact_mode_keeps_content() {
+        assert_eq!(DedupMode::Exact.key(" Done "), " Done ");
+        assert_eq!(DedupMode::Normalized.key(" Done "), "done");
+    }
+}
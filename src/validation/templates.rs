@@ -0,0 +1,160 @@
+//! Default observations per entity type
+//!
+//! Structured entity types usually carry the same attributes, e.g. every
+//! `Bug` has a `Status:` and a `Priority:`. `MEMORY_ENTITY_TEMPLATES` points
+//! to a JSON file mapping entity types to default observations:
+//!
+//! ```json
+//! {
+//!   "Bug": ["Status: todo", "Priority: medium"],
+//!   "Feature": ["Status: planned"]
+//! }
+//! ```
+//!
+//! When an entity of a listed type is created, each default is appended unless
+//! the entity already has it: a `key: value` default is skipped when any
+//! observation sets the same key (case-insensitively), so `Priority: high`
+//! overrides `Priority: medium`; any other default is skipped when the exact
+//! observation is present. Each added observation is reported back to the
+//! caller. Without the variable no defaults are added.
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+
+use crate::types::parse_attribute;
+
+use super::{ValidationWarning, ValidationWarningKind};
+
+/// Configured default observations, keyed by entity type
+#[derive(Debug, Clone, Default)]
+pub struct EntityTemplates {
+    defaults: HashMap<String, Vec<String>>,
+}
+
+impl EntityTemplates {
+    /// No templates (the default)
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Load templates from the file named by `MEMORY_ENTITY_TEMPLATES`
+    ///
+    /// A missing or malformed file is logged and leaves templates off.
+    pub fn from_env() -> Self {
+        let Ok(path) = env::var("MEMORY_ENTITY_TEMPLATES") else {
+            return Self::none();
+        };
+        match fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| Self::parse(&json))
+        {
+            Ok(templates) => templates,
+            Err(e) => {
+                eprintln!("Warning: Failed to load entity templates {}: {}", path, e);
+                Self::none()
+            }
+        }
+    }
+
+    /// Parse a `{"EntityType": ["observation", ...], ...}` JSON object
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let defaults: HashMap<String, Vec<String>> =
+            serde_json::from_str(json).map_err(|e| e.to_string())?;
+        Ok(defaults
+            .into_iter()
+            .fold(Self::default(), |templates, (entity_type, observations)| {
+                templates.with_template(&entity_type, observations)
+            }))
+    }
+
+    /// Set the default observations for an entity type
+    pub fn with_template(mut self, entity_type: &str, observations: Vec<String>) -> Self {
+        let observations: Vec<String> = observations
+            .into_iter()
+            .map(|o| o.trim().to_string())
+            .filter(|o| !o.is_empty())
+            .collect();
+        if !entity_type.trim().is_empty() && !observations.is_empty() {
+            self.defaults
+                .insert(entity_type.trim().to_string(), observations);
+        }
+        self
+    }
+
+    /// Whether any entity type has a template
+    pub fn is_empty(&self) -> bool {
+        self.defaults.is_empty()
+    }
+
+    /// Append the defaults `observations` lacks for `entity_type`
+    ///
+    /// Returns one warning per added observation, naming the entity.
+    pub fn apply(
+        &self,
+        entity_name: &str,
+        entity_type: &str,
+        observations: &mut Vec<String>,
+    ) -> Vec<ValidationWarning> {
+        let Some(defaults) = self.defaults.get(entity_type) else {
+            return Vec::new();
+        };
+
+        let mut added = Vec::new();
+        for default in defaults {
+            let present = match parse_attribute(default) {
+                Some((key, _)) => observations
+                    .iter()
+                    .any(|o| parse_attribute(o).is_some_and(|(k, _)| k.eq_ignore_ascii_case(key))),
+                None => observations.contains(default),
+            };
+            if !present {
+                observations.push(default.clone());
+                added.push(ValidationWarning::new(
+                    ValidationWarningKind::TemplateDefaultAdded,
+                    entity_name.to_string(),
+                    format!(
+                        "Added default observation '{}' from the {} template",
+                        default, entity_type
+                    ),
+                ));
+            }
+        }
+        added
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_defaults_yield_to_provided_keys() {
+        let templates = EntityTemplates::parse(
+            r#"{"Bug": ["Status: todo", "Priority: medium", "Needs triage"], "Note": []}"#,
+        )
+        .unwrap();
+
+        let mut observations = vec!["priority: high".to_string(), "Crashes on save".to_string()];
+        let added = templates.apply("Crash", "Bug", &mut observations);
+        assert_eq!(
+            observations,
+            vec![
+                "priority: high",
+                "Crashes on save",
+                "Status: todo",
+                "Needs triage"
+            ]
+        );
+        assert_eq!(added.len(), 2);
+        assert!(added[0].message.contains("'Status: todo'"));
+
+        let mut untouched = vec!["Status: done".to_string()];
+        assert!(templates.apply("Doc", "Note", &mut untouched).is_empty());
+        assert!(templates.apply("Doc", "Feature", &mut untouched).is_empty());
+        assert_eq!(untouched, vec!["Status: done"]);
+
+        assert!(EntityTemplates::parse("[1, 2]").is_err());
+        assert!(EntityTemplates::none().is_empty());
+    }
+}
@@ -31,6 +31,15 @@ pub const STANDARD_RELATION_TYPES: &[&str] = &[
     "requires",
 ];
 
+/// Check whether validation warnings should be left out of tool responses
+///
+/// Controlled by `MEMORY_SUPPRESS_VALIDATION_WARNINGS=true`.
+pub fn warnings_suppressed() -> bool {
+    std::env::var("MEMORY_SUPPRESS_VALIDATION_WARNINGS")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false)
+}
+
 /// Check if entity type is standard, return warning if not
 pub fn validate_entity_type(entity_type: &str) -> Option<String> {
     if STANDARD_ENTITY_TYPES
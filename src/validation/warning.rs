@@ -0,0 +1,49 @@
+//! Validation warning types for graph-wide reports
+
+use serde::{Deserialize, Serialize};
+
+/// Category of a validation warning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationWarningKind {
+    /// Entity uses a type outside the standard set
+    NonStandardEntityType,
+    /// Relation uses a type outside the standard set
+    NonStandardRelationType,
+    /// A required field (name, entityType, from, to, relationType) is empty
+    MissingField,
+    /// Relation points to an entity that does not exist
+    DanglingRelation,
+    /// Observation was shortened to the configured maximum length
+    ObservationTruncated,
+    /// Observation was dropped because it exceeded a configured limit
+    ObservationRejected,
+    /// Relation broke a functional (at most one per entity) constraint
+    CardinalityViolation,
+    /// Entity name was trimmed or case-folded before being stored
+    EntityNameNormalized,
+    /// Entity was not created because its name broke the name policy
+    EntityNameRejected,
+    /// Default observation was added from the entity type's template
+    TemplateDefaultAdded,
+}
+
+/// A single validation finding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationWarning {
+    pub kind: ValidationWarningKind,
+    /// Entity name or relation descriptor (`from -[type]-> to`) the warning refers to
+    pub subject: String,
+    pub message: String,
+}
+
+impl ValidationWarning {
+    /// Create a new validation warning
+    pub fn new(kind: ValidationWarningKind, subject: String, message: String) -> Self {
+        Self {
+            kind,
+            subject,
+            message,
+        }
+    }
+}
@@ -10,7 +10,6 @@ use std::fs;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use memory_graph::event_store::{EventStore, EventStoreConfig, MigrationTool, SnapshotManager};
-use memory_graph::knowledge_base::KnowledgeBase;
 use memory_graph::types::{Entity, EventType, Relation};
 
 static TEST_COUNTER: AtomicU64 = AtomicU64::new(0);
@@ -83,6 +82,9 @@ fn test_snapshot_creation_and_loading() {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
         },
         Entity {
             name: "Bob".to_string(),
@@ -92,6 +94,9 @@ fn test_snapshot_creation_and_loading() {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
         },
     ];
 
@@ -165,6 +170,9 @@ fn test_state_recovery_from_snapshot_and_events() {
                 updated_by: String::new(),
                 created_at: 0,
                 updated_at: 0,
+                id: String::new(),
+                protected: false,
+                acl: Vec::new(),
             },
             Entity {
                 name: "Bob".to_string(),
@@ -174,6 +182,9 @@ fn test_state_recovery_from_snapshot_and_events() {
                 updated_by: String::new(),
                 created_at: 0,
                 updated_at: 0,
+                id: String::new(),
+                protected: false,
+                acl: Vec::new(),
             },
         ];
         snapshot_manager.create_snapshot_with_backup(2, &entities, &[]).unwrap();
@@ -202,7 +213,7 @@ fn test_state_recovery_from_snapshot_and_events() {
 
     // Simulate restart - initialize from snapshot + replay
     {
-        let mut store = EventStore::with_config(config.clone());
+        let store = EventStore::with_config(config.clone());
         let snapshot_manager = SnapshotManager::new(config.clone());
 
         // Load snapshot
@@ -292,6 +303,9 @@ fn test_backup_snapshot_recovery() {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
         },
     ];
 
@@ -304,6 +318,9 @@ fn test_backup_snapshot_recovery() {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
         },
         Entity {
             name: "Bob".to_string(),
@@ -313,6 +330,9 @@ fn test_backup_snapshot_recovery() {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
         },
     ];
 
@@ -5,8 +5,19 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::thread;
 
+use serde_json::json;
+
 use memory_graph::knowledge_base::KnowledgeBase;
-use memory_graph::types::{Entity, Observation, Relation};
+use memory_graph::protocol::Tool;
+use memory_graph::tools::{
+    DeleteEntitiesTool, EstimateCostTool, GetRelatedTool, HistorizeRelationsTool,
+    ProtectEntitiesTool, RenameEntityTypeTool, RenameRelationTypeTool, TraverseTool,
+};
+use memory_graph::types::{
+    Entity, InvalidParams, KnowledgeGraph, Observation, ObservationReplacement, Op, OpResult,
+    PathStep, RelatedOptions, RelatedPage, RelatedSort, Relation, SearchField, SearchOptions,
+};
+use memory_graph::validation::ValidationWarningKind;
 
 static TEST_COUNTER: AtomicU64 = AtomicU64::new(0);
 
@@ -40,6 +51,9 @@ fn test_create_entities() {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
         },
         Entity {
             name: "Bob".to_string(),
@@ -49,6 +63,9 @@ fn test_create_entities() {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
         },
     ];
 
@@ -75,6 +92,9 @@ fn test_create_relations() {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
         },
         Entity {
             name: "Bob".to_string(),
@@ -84,6 +104,9 @@ fn test_create_relations() {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
         },
     ];
     kb.create_entities(entities).unwrap();
@@ -95,8 +118,10 @@ fn test_create_relations() {
         relation_type: "knows".to_string(),
         created_by: String::new(),
         created_at: 0,
+        updated_at: 0,
         valid_from: None,
         valid_to: None,
+        properties: Default::default(),
     }];
 
     let created = kb.create_relations(relations).unwrap();
@@ -121,6 +146,9 @@ fn test_search_nodes() {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
         },
         Entity {
             name: "Bob".to_string(),
@@ -130,17 +158,20 @@ fn test_search_nodes() {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
         },
     ];
     kb.create_entities(entities).unwrap();
 
-    let result = kb.search_nodes("Alice", None, true).unwrap();
+    let result = kb.search_nodes("Alice", &SearchOptions::default()).unwrap();
     assert_eq!(result.entities.len(), 1);
-    assert_eq!(result.entities[0].name, "Alice");
+    assert_eq!(result.entities[0].entity.name, "Alice");
 
-    let result = kb.search_nodes("Engineer", None, true).unwrap();
+    let result = kb.search_nodes("Engineer", &SearchOptions::default()).unwrap();
     assert_eq!(result.entities.len(), 1);
-    assert_eq!(result.entities[0].name, "Alice");
+    assert_eq!(result.entities[0].entity.name, "Alice");
 
     cleanup(&temp_file);
 }
@@ -158,6 +189,9 @@ fn test_delete_entities() {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
         },
         Entity {
             name: "Bob".to_string(),
@@ -167,6 +201,9 @@ fn test_delete_entities() {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
         },
     ];
     kb.create_entities(entities).unwrap();
@@ -199,12 +236,15 @@ fn test_concurrent_access() {
                 updated_by: String::new(),
                 created_at: 0,
                 updated_at: 0,
+                id: String::new(),
+                protected: false,
+                acl: Vec::new(),
             };
             kb_clone.create_entities(vec![entity]).unwrap();
 
             // Each agent also reads the graph
             let graph = kb_clone.read_graph(None, None).unwrap();
-            assert!(graph.entities.len() >= 1);
+            assert!(!graph.entities.is_empty());
 
             // Each agent adds an observation
             let obs = Observation {
@@ -228,7 +268,7 @@ fn test_concurrent_access() {
     // Verify all entities have observations
     for entity in &graph.entities {
         assert!(
-            entity.observations.len() >= 1,
+            !entity.observations.is_empty(),
             "Entity should have observations"
         );
     }
@@ -250,6 +290,9 @@ fn test_concurrent_read_write() {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
         };
         kb.create_entities(vec![entity]).unwrap();
     }
@@ -263,7 +306,7 @@ fn test_concurrent_read_write() {
             for _ in 0..100 {
                 let graph = kb_clone.read_graph(None, None).unwrap();
                 assert!(graph.entities.len() >= 5);
-                let _ = kb_clone.search_nodes("Entity", None, true);
+                let _ = kb_clone.search_nodes("Entity", &SearchOptions::default());
             }
         });
         handles.push(handle);
@@ -312,16 +355,19 @@ fn test_semantic_search_synonyms() {
         updated_by: String::new(),
         created_at: 0,
         updated_at: 0,
+        id: String::new(),
+        protected: false,
+        acl: Vec::new(),
     }];
     kb.create_entities(entities).unwrap();
 
     // Search with synonym "coder" should find "developer"
-    let result = kb.search_nodes("coder", None, true).unwrap();
+    let result = kb.search_nodes("coder", &SearchOptions::default()).unwrap();
     assert_eq!(result.entities.len(), 1);
-    assert_eq!(result.entities[0].name, "Alice");
+    assert_eq!(result.entities[0].entity.name, "Alice");
 
     // Search with synonym "programmer" should also find "developer"
-    let result = kb.search_nodes("programmer", None, true).unwrap();
+    let result = kb.search_nodes("programmer", &SearchOptions::default()).unwrap();
     assert_eq!(result.entities.len(), 1);
 
     cleanup(&temp_file);
@@ -341,6 +387,9 @@ fn test_pagination() {
             updated_by: String::new(),
             created_at: 0,
             updated_at: 0,
+            id: String::new(),
+            protected: false,
+            acl: Vec::new(),
         };
         kb.create_entities(vec![entity]).unwrap();
     }
@@ -359,3 +408,1340 @@ fn test_pagination() {
 
     cleanup(&temp_file);
 }
+
+#[test]
+fn test_validate_all() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![
+        Entity::new("Auth".to_string(), "Module".to_string()),
+        Entity::new("Widget".to_string(), "Gadget".to_string()),
+    ])
+    .unwrap();
+    kb.create_relations(vec![Relation::new(
+        "Widget".to_string(),
+        "Auth".to_string(),
+        "plugs_into".to_string(),
+    )])
+    .unwrap();
+
+    let warnings = kb.validate_all();
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings
+        .iter()
+        .any(|w| w.kind == ValidationWarningKind::NonStandardEntityType && w.subject == "Widget"));
+    assert!(warnings
+        .iter()
+        .any(|w| w.kind == ValidationWarningKind::NonStandardRelationType));
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_replace_observations() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![Entity::with_observations(
+        "Task".to_string(),
+        "Task".to_string(),
+        vec!["Status: todo".to_string(), "Owner: alice".to_string()],
+    )])
+    .unwrap();
+
+    // Prefix replacement only touches matching observations
+    let results = kb
+        .replace_observations(vec![ObservationReplacement::with_prefix(
+            "Task".to_string(),
+            vec!["Status: done".to_string()],
+            "Status:".to_string(),
+        )])
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].removed, vec!["Status: todo"]);
+    assert_eq!(results[0].added, vec!["Status: done"]);
+
+    let graph = kb.open_nodes(vec!["Task".to_string()]).unwrap();
+    assert_eq!(
+        graph.entities[0].observations,
+        vec!["Owner: alice", "Status: done"]
+    );
+
+    // Full replacement swaps the entire set
+    kb.replace_observations(vec![ObservationReplacement::new(
+        "Task".to_string(),
+        vec!["Archived".to_string()],
+    )])
+    .unwrap();
+
+    let graph = kb.open_nodes(vec!["Task".to_string()]).unwrap();
+    assert_eq!(graph.entities[0].observations, vec!["Archived"]);
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_search_ranking_and_filters() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![
+        Entity::with_observations(
+            "Login".to_string(),
+            "Feature".to_string(),
+            vec!["Depends on auth".to_string()],
+        ),
+        Entity::new("auth".to_string(), "Module".to_string()),
+        Entity::new("AuthService".to_string(), "Component".to_string()),
+    ])
+    .unwrap();
+
+    // Exact name match ranks first, observation-only match last
+    let result = kb.search_nodes("auth", &SearchOptions::default()).unwrap();
+    assert_eq!(result.total, 3);
+    assert_eq!(result.entities[0].entity.name, "auth");
+    assert_eq!(result.entities[2].entity.name, "Login");
+    assert!(result.entities[0].score > result.entities[2].score);
+
+    // Pagination keeps the total
+    let options = SearchOptions {
+        limit: Some(1),
+        offset: 1,
+        ..Default::default()
+    };
+    let result = kb.search_nodes("auth", &options).unwrap();
+    assert_eq!(result.total, 3);
+    assert_eq!(result.entities.len(), 1);
+    assert_eq!(result.entities[0].entity.name, "AuthService");
+
+    // Field and type filters
+    let options = SearchOptions {
+        fields: Some(vec![SearchField::Name]),
+        ..Default::default()
+    };
+    assert_eq!(kb.search_nodes("auth", &options).unwrap().total, 2);
+
+    let options = SearchOptions {
+        entity_types: Some(vec!["feature".to_string()]),
+        ..Default::default()
+    };
+    let result = kb.search_nodes("auth", &options).unwrap();
+    assert_eq!(result.total, 1);
+    assert_eq!(result.entities[0].entity.name, "Login");
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_hot_entities() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![
+        Entity::new("Auth".to_string(), "Module".to_string()),
+        Entity::new("Billing".to_string(), "Module".to_string()),
+        Entity::new("Unused".to_string(), "Module".to_string()),
+    ])
+    .unwrap();
+
+    kb.open_nodes(vec!["Auth".to_string(), "Billing".to_string()])
+        .unwrap();
+    kb.search_nodes("Auth", &SearchOptions::default()).unwrap();
+
+    let hot = kb.hot_entities(10);
+    assert_eq!(hot.len(), 2);
+    assert_eq!(hot[0].name, "Auth");
+    assert_eq!(hot[0].access_count, 2);
+    assert_eq!(hot[1].name, "Billing");
+
+    // Deleted entities drop out of the ranking
+    kb.delete_entities(vec!["Auth".to_string()]).unwrap();
+    let hot = kb.hot_entities(10);
+    assert_eq!(hot.len(), 1);
+    assert_eq!(hot[0].name, "Billing");
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_transaction_rolls_back_on_error() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![Entity::new("Auth".to_string(), "Module".to_string())])
+        .unwrap();
+
+    let result = kb.transaction(vec![
+        Op::AddObservations {
+            observations: vec![Observation::new(
+                "Auth".to_string(),
+                vec!["Uses JWT".to_string()],
+            )],
+        },
+        Op::DeleteEntities {
+            entity_names: vec!["Missing".to_string()],
+            force: false,
+        },
+    ]);
+    assert!(result.is_err());
+
+    // Neither the in-memory graph nor the file saw the first operation
+    let graph = kb.read_graph(None, None).unwrap();
+    assert!(graph.entities[0].observations.is_empty());
+    let reloaded = KnowledgeBase::with_file_path(temp_file.clone());
+    let graph = reloaded.read_graph(None, None).unwrap();
+    assert!(graph.entities[0].observations.is_empty());
+
+    let results = kb
+        .transaction(vec![Op::AddObservations {
+            observations: vec![Observation::new(
+                "Auth".to_string(),
+                vec!["Uses JWT".to_string()],
+            )],
+        }])
+        .unwrap();
+    assert!(matches!(&results[0], OpResult::AddObservations { added } if added.len() == 1));
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_get_related_collapses_reciprocal() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![
+        Entity::new("Alice".to_string(), "Person".to_string()),
+        Entity::new("Bob".to_string(), "Person".to_string()),
+    ])
+    .unwrap();
+    kb.create_relations(vec![
+        Relation::new("Alice".to_string(), "Bob".to_string(), "knows".to_string()),
+        Relation::new("Bob".to_string(), "Alice".to_string(), "knows".to_string()),
+    ])
+    .unwrap();
+
+    let related = kb
+        .get_related(
+            "Alice",
+            &RelatedOptions {
+                collapse_reciprocal: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(related.relations.len(), 1);
+    assert_eq!(related.relations[0].direction, "bidirectional");
+
+    let related = kb
+        .get_related("Alice", &RelatedOptions::default())
+        .unwrap();
+    assert_eq!(related.relations.len(), 2);
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_get_related_sorts_before_paging() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![
+        Entity::new("Hub".to_string(), "Service".to_string()),
+        Entity::new("Cache".to_string(), "Store".to_string()),
+        Entity::new("Auth".to_string(), "Service".to_string()),
+        Entity::new("Billing".to_string(), "Service".to_string()),
+    ])
+    .unwrap();
+    kb.create_relations(vec![
+        Relation::new("Hub".to_string(), "Cache".to_string(), "uses".to_string()),
+        Relation::new("Hub".to_string(), "Auth".to_string(), "uses".to_string()),
+        Relation::new("Billing".to_string(), "Hub".to_string(), "calls".to_string()),
+    ])
+    .unwrap();
+
+    let names = |page: RelatedPage| -> (Vec<String>, usize) {
+        let options = RelatedOptions {
+            collapse_reciprocal: true,
+            page,
+            ..Default::default()
+        };
+        let related = kb.get_related("Hub", &options).unwrap();
+        let names = related.relations.into_iter().map(|r| r.entity.name).collect();
+        (names, related.total)
+    };
+
+    let (all, total) = names(RelatedPage::default());
+    assert_eq!(all, vec!["Cache", "Auth", "Billing"]);
+    assert_eq!(total, 3);
+    let page = RelatedPage {
+        sort: RelatedSort::Name,
+        offset: 1,
+        limit: Some(1),
+    };
+    assert_eq!(names(page), (vec!["Billing".to_string()], 3));
+    let page = RelatedPage {
+        sort: RelatedSort::EntityType,
+        limit: Some(2),
+        ..Default::default()
+    };
+    assert_eq!(names(page).0, vec!["Auth", "Billing"]);
+    let page = RelatedPage {
+        sort: RelatedSort::RelationType,
+        ..Default::default()
+    };
+    assert_eq!(names(page).0, vec!["Billing", "Auth", "Cache"]);
+
+    let tool = GetRelatedTool::new(kb);
+    let result = tool
+        .execute(json!({"entityName": "Hub", "sort": "name", "offset": 2}))
+        .unwrap();
+    let text = result["content"][0]["text"].as_str().unwrap();
+    let page: serde_json::Value = serde_json::from_str(text).unwrap();
+    assert_eq!(page["total"], 3);
+    assert_eq!(page["relations"][0]["entity"]["name"], "Cache");
+    assert!(tool.execute(json!({"entityName": "Hub", "sort": "age"})).is_err());
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_delete_relations_by_filter() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![
+        Entity::new("A".to_string(), "Module".to_string()),
+        Entity::new("B".to_string(), "Module".to_string()),
+        Entity::new("C".to_string(), "Module".to_string()),
+    ])
+    .unwrap();
+    kb.create_relations(vec![
+        Relation::new("A".to_string(), "B".to_string(), "depends_on".to_string()),
+        Relation::new("A".to_string(), "C".to_string(), "depends_on".to_string()),
+        Relation::new("B".to_string(), "C".to_string(), "relates_to".to_string()),
+    ])
+    .unwrap();
+
+    assert!(kb.delete_relations_by_filter(None, None, None).is_err());
+
+    let deleted = kb
+        .delete_relations_by_filter(Some("A".to_string()), None, Some("depends_on".to_string()))
+        .unwrap();
+    assert_eq!(deleted.len(), 2);
+
+    let graph = kb.read_graph(None, None).unwrap();
+    assert_eq!(graph.relations.len(), 1);
+    assert_eq!(graph.relations[0].relation_type, "relates_to");
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_replace_in_observations() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![
+        Entity::with_observations(
+            "Auth".to_string(),
+            "Module".to_string(),
+            vec!["Uses OAuth1".to_string(), "Owner: alice".to_string()],
+        ),
+        Entity::with_observations(
+            "Login".to_string(),
+            "Feature".to_string(),
+            vec!["Migrating from OAuth1".to_string()],
+        ),
+    ])
+    .unwrap();
+
+    // Dry run reports matches without changing anything
+    let preview = kb
+        .preview_replace_in_observations("OAuth1", "OAuth2", false)
+        .unwrap();
+    assert_eq!(preview.len(), 2);
+    let graph = kb.open_nodes(vec!["Auth".to_string()]).unwrap();
+    assert_eq!(graph.entities[0].observations[0], "Uses OAuth1");
+
+    let changed = kb.replace_in_observations("OAuth1", "OAuth2", false).unwrap();
+    assert_eq!(changed, 2);
+    let graph = kb.open_nodes(vec!["Auth".to_string()]).unwrap();
+    assert_eq!(
+        graph.entities[0].observations,
+        vec!["Uses OAuth2", "Owner: alice"]
+    );
+
+    // Regex with capture groups
+    let changed = kb
+        .replace_in_observations(r"Owner: (\w+)", "Maintainer: $1", true)
+        .unwrap();
+    assert_eq!(changed, 1);
+    let graph = kb.open_nodes(vec!["Auth".to_string()]).unwrap();
+    assert_eq!(graph.entities[0].observations[1], "Maintainer: alice");
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_rename_relation_type_drops_duplicates() {
+    let (kb, temp_file) = setup_test_kb();
+    kb.create_entities(vec![
+        Entity::new("Spec".to_string(), "Document".to_string()),
+        Entity::new("Api".to_string(), "Document".to_string()),
+        Entity::new("Guide".to_string(), "Document".to_string()),
+    ])
+    .unwrap();
+    kb.create_relations(vec![
+        Relation::new(
+            "Spec".to_string(),
+            "Api".to_string(),
+            "relates_to".to_string(),
+        ),
+        Relation::new(
+            "Spec".to_string(),
+            "Guide".to_string(),
+            "relates_to".to_string(),
+        ),
+        Relation::new(
+            "Spec".to_string(),
+            "Guide".to_string(),
+            "references".to_string(),
+        ),
+    ])
+    .unwrap();
+
+    let tool = RenameRelationTypeTool::new(Arc::clone(&kb));
+    let run = |params: serde_json::Value| -> serde_json::Value {
+        let result = tool.execute(params).unwrap();
+        serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap()
+    };
+
+    let preview = run(json!({"oldType": "relates_to", "newType": "references", "dryRun": true}));
+    assert_eq!(preview["matches"], 2);
+    assert_eq!(preview["duplicates"], 1);
+    assert_eq!(kb.read_graph(None, None).unwrap().relations.len(), 3);
+
+    let applied = run(json!({"oldType": "relates_to", "newType": "references"}));
+    assert_eq!(applied["changed"], 2);
+    let graph = kb.read_graph(None, None).unwrap();
+    assert_eq!(graph.relations.len(), 2);
+    assert!(graph
+        .relations
+        .iter()
+        .all(|r| r.relation_type == "references"));
+    assert!(tool
+        .execute(json!({"oldType": "references", "newType": "references"}))
+        .is_err());
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_rename_entity_type_tool() {
+    let (kb, temp_file) = setup_test_kb();
+    kb.create_entities(vec![
+        Entity::new("Login fails".to_string(), "Ticket".to_string()),
+        Entity::new("Auth".to_string(), "Module".to_string()),
+    ])
+    .unwrap();
+
+    let tool = RenameEntityTypeTool::new(Arc::clone(&kb));
+    let params = json!({"oldType": "Ticket", "newType": "Issue", "strict": true});
+    assert!(tool.execute(params).is_err());
+
+    let response = tool
+        .execute(json!({"oldType": "Ticket", "newType": "Issue"}))
+        .unwrap();
+    let result: serde_json::Value =
+        serde_json::from_str(response["content"][0]["text"].as_str().unwrap()).unwrap();
+    assert_eq!(result["changed"], 1);
+    assert!(response["content"][1]["text"]
+        .as_str()
+        .unwrap()
+        .contains("non_standard_entity_type"));
+
+    let graph = kb.open_nodes(vec!["Login fails".to_string()]).unwrap();
+    assert_eq!(graph.entities[0].entity_type, "Issue");
+    let response = tool
+        .execute(json!({"oldType": "Issue", "newType": "Bug", "dryRun": true}))
+        .unwrap();
+    let result: serde_json::Value =
+        serde_json::from_str(response["content"][0]["text"].as_str().unwrap()).unwrap();
+    assert_eq!(result["entities"], json!(["Login fails"]));
+    assert_eq!(response["content"].as_array().unwrap().len(), 1);
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_delete_entities_tool_skips_protected() {
+    let (kb, temp_file) = setup_test_kb();
+    kb.create_entities(vec![
+        Entity::new("Root".to_string(), "Project".to_string()),
+        Entity::new("Scratch".to_string(), "Module".to_string()),
+    ])
+    .unwrap();
+    ProtectEntitiesTool::new(Arc::clone(&kb))
+        .execute(json!({"entityNames": ["Root"]}))
+        .unwrap();
+
+    // The flag is persisted with the entity
+    let content = std::fs::read_to_string(&temp_file).unwrap();
+    assert!(content.contains("\"protected\":true"));
+    assert_eq!(content.matches("protected").count(), 1);
+
+    let tool = DeleteEntitiesTool::new(Arc::clone(&kb));
+    let result = tool
+        .execute(json!({"entityNames": ["Root", "Scratch"]}))
+        .unwrap();
+    assert!(result["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .contains("skipped protected entities (use force: true to delete them): Root"));
+    let graph = kb.read_graph(None, None).unwrap();
+    assert_eq!(graph.entities.len(), 1);
+
+    tool.execute(json!({"entityNames": ["Root"], "force": true}))
+        .unwrap();
+    assert!(kb.read_graph(None, None).unwrap().entities.is_empty());
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_random_walk_is_seeded() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![
+        Entity::new("A".to_string(), "Module".to_string()),
+        Entity::new("B".to_string(), "Module".to_string()),
+        Entity::new("C".to_string(), "Module".to_string()),
+        Entity::new("D".to_string(), "Module".to_string()),
+    ])
+    .unwrap();
+    kb.create_relations(vec![
+        Relation::new("A".to_string(), "B".to_string(), "depends_on".to_string()),
+        Relation::new("A".to_string(), "C".to_string(), "depends_on".to_string()),
+        Relation::new("B".to_string(), "D".to_string(), "depends_on".to_string()),
+    ])
+    .unwrap();
+
+    let walk = kb.random_walk(Some("A"), 50, 0.2, 42);
+    assert_eq!(walk.len(), 51);
+    assert_eq!(walk[0], "A");
+    assert_eq!(walk, kb.random_walk(Some("A"), 50, 0.2, 42));
+
+    // Every move follows a relation or restarts at the start node
+    for pair in walk.windows(2) {
+        let (from, to) = (pair[0].as_str(), pair[1].as_str());
+        let follows = matches!((from, to), ("A", "B") | ("A", "C") | ("B", "D"));
+        assert!(follows || to == "A", "unexpected step {} -> {}", from, to);
+    }
+
+    // A dead-end start restarts onto itself every step
+    assert!(kb.random_walk(Some("D"), 3, 0.0, 1).iter().all(|n| n == "D"));
+    assert!(kb.random_walk(Some("Missing"), 5, 0.0, 1).is_empty());
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_find_duplicates() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![
+        Entity::new("UserService".to_string(), "Module".to_string()),
+        Entity::new("User-Service".to_string(), "Module".to_string()),
+        Entity::new("UserStore".to_string(), "Module".to_string()),
+        Entity::new("UserServices".to_string(), "Person".to_string()),
+        Entity::with_observations(
+            "Postgres".to_string(),
+            "Technology".to_string(),
+            vec!["Primary database".to_string()],
+        ),
+        Entity::with_observations(
+            "PG".to_string(),
+            "Technology".to_string(),
+            vec!["Primary database".to_string()],
+        ),
+    ])
+    .unwrap();
+
+    let pairs = kb.find_duplicates(0.8);
+    let names: Vec<(&str, &str)> = pairs
+        .iter()
+        .map(|(a, b, _)| (a.as_str(), b.as_str()))
+        .collect();
+
+    assert_eq!(names, vec![("UserService", "User-Service")]);
+    assert_eq!(pairs[0].2, 1.0);
+
+    // Different name prefixes are never compared, even with identical observations
+    assert!(!names.contains(&("Postgres", "PG")));
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_entity_and_relation_exists() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![
+        Entity::new("A".to_string(), "Module".to_string()),
+        Entity::new("B".to_string(), "Module".to_string()),
+    ])
+    .unwrap();
+    kb.create_relations(vec![Relation::new(
+        "A".to_string(),
+        "B".to_string(),
+        "depends_on".to_string(),
+    )])
+    .unwrap();
+
+    let exists = kb.entity_exists(&["A".to_string(), "Missing".to_string()]);
+    assert_eq!(exists.get("A"), Some(&true));
+    assert_eq!(exists.get("Missing"), Some(&false));
+
+    let exists = kb.relation_exists(&[
+        ("A".to_string(), "B".to_string(), "depends_on".to_string()),
+        ("B".to_string(), "A".to_string(), "depends_on".to_string()),
+    ]);
+    assert_eq!(exists, vec![true, false]);
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_export_subgraph_round_trips() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![
+        Entity::new("A".to_string(), "Module".to_string()),
+        Entity::new("B".to_string(), "Module".to_string()),
+        Entity::new("C".to_string(), "Module".to_string()),
+        Entity::new("D".to_string(), "Module".to_string()),
+    ])
+    .unwrap();
+    kb.create_relations(vec![
+        Relation::new("A".to_string(), "B".to_string(), "depends_on".to_string()),
+        Relation::new("B".to_string(), "C".to_string(), "depends_on".to_string()),
+        Relation::new("C".to_string(), "D".to_string(), "depends_on".to_string()),
+    ])
+    .unwrap();
+
+    let subgraph = kb.get_subgraph("B", 1).unwrap();
+    let mut names: Vec<&str> = subgraph.entities.iter().map(|e| e.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, vec!["A", "B", "C"]);
+    assert_eq!(subgraph.relations.len(), 2);
+    assert!(kb.get_subgraph("Missing", 1).is_err());
+
+    // The JSONL export loads as a memory file
+    let (_, export_file) = setup_test_kb();
+    std::fs::write(&export_file, subgraph.to_jsonl().unwrap()).unwrap();
+    let imported = KnowledgeBase::with_file_path(export_file.clone());
+    let graph = imported.read_graph(None, None).unwrap();
+    assert_eq!(graph.entities.len(), 3);
+    assert_eq!(graph.relations.len(), 2);
+
+    cleanup(&export_file);
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_import_graph_merges() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![
+        Entity::with_observations(
+            "Alice".to_string(),
+            "Person".to_string(),
+            vec!["Engineer".to_string()],
+        ),
+        Entity::with_observations(
+            "Bob".to_string(),
+            "Person".to_string(),
+            vec!["Designer".to_string()],
+        ),
+        Entity::new("Mercury".to_string(), "Project".to_string()),
+    ])
+    .unwrap();
+    kb.create_relations(vec![Relation::new(
+        "Alice".to_string(),
+        "Mercury".to_string(),
+        "works_on".to_string(),
+    )])
+    .unwrap();
+
+    let incoming = KnowledgeGraph {
+        entities: vec![
+            Entity::with_observations(
+                "Alice".to_string(),
+                "Person".to_string(),
+                vec!["Engineer".to_string(), "Speaks Rust".to_string()],
+            ),
+            Entity::with_observations(
+                "Bob".to_string(),
+                "Person".to_string(),
+                vec!["Designer".to_string()],
+            ),
+            Entity::new("Mercury".to_string(), "Module".to_string()),
+            Entity::new("Carol".to_string(), "Person".to_string()),
+        ],
+        relations: vec![
+            Relation::new(
+                "Alice".to_string(),
+                "Mercury".to_string(),
+                "works_on".to_string(),
+            ),
+            Relation::new(
+                "Carol".to_string(),
+                "Mercury".to_string(),
+                "works_on".to_string(),
+            ),
+            Relation::new(
+                "Carol".to_string(),
+                "Mercury".to_string(),
+                "works_on".to_string(),
+            ),
+        ],
+    };
+
+    let report = kb.import_graph(incoming).unwrap();
+    assert_eq!(report.entities_created, 1);
+    assert_eq!(report.entities_updated, 1);
+    assert_eq!(report.entities_skipped, 1);
+    assert_eq!(report.relations_created, 1);
+    assert_eq!(report.relations_skipped, 2);
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].name, "Mercury");
+    assert_eq!(report.conflicts[0].existing_type, "Project");
+
+    let graph = kb.read_graph(None, None).unwrap();
+    assert_eq!(graph.entities.len(), 4);
+    assert_eq!(graph.relations.len(), 2);
+    let alice = graph.entities.iter().find(|e| e.name == "Alice").unwrap();
+    assert_eq!(alice.observations, vec!["Engineer", "Speaks Rust"]);
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_traverse_variable_length_and_both() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![
+        Entity::new("A".to_string(), "Module".to_string()),
+        Entity::new("B".to_string(), "Module".to_string()),
+        Entity::new("C".to_string(), "Module".to_string()),
+        Entity::new("D".to_string(), "Service".to_string()),
+    ])
+    .unwrap();
+    kb.create_relations(vec![
+        Relation::new("A".to_string(), "B".to_string(), "depends_on".to_string()),
+        Relation::new("B".to_string(), "C".to_string(), "depends_on".to_string()),
+        Relation::new("C".to_string(), "D".to_string(), "depends_on".to_string()),
+        // Cycle back to the start must not loop forever
+        Relation::new("D".to_string(), "A".to_string(), "depends_on".to_string()),
+    ])
+    .unwrap();
+
+    // Single-hop steps are unchanged
+    let step = PathStep::new("depends_on".to_string(), "out".to_string());
+    let result = kb.traverse("A", vec![step.clone()], 50).unwrap();
+    assert_eq!(result.paths.len(), 1);
+    assert_eq!(result.paths[0].nodes, vec!["A", "B"]);
+
+    let result = kb.traverse("A", vec![step.with_hops(1, 3)], 50).unwrap();
+    let ends: Vec<&str> = result
+        .paths
+        .iter()
+        .map(|p| p.nodes.last().unwrap().as_str())
+        .collect();
+    assert_eq!(ends, vec!["B", "C", "D"]);
+
+    // The cycle guard stops at the start node even with a large range
+    let step = PathStep::new("depends_on".to_string(), "out".to_string()).with_hops(1, 10);
+    let result = kb.traverse("A", vec![step], 50).unwrap();
+    assert_eq!(result.paths.len(), 3);
+
+    let step = PathStep::with_target_type(
+        "depends_on".to_string(),
+        "both".to_string(),
+        "Service".to_string(),
+    );
+    let result = kb.traverse("A", vec![step], 50).unwrap();
+    assert_eq!(result.paths.len(), 1);
+    assert_eq!(result.paths[0].nodes, vec!["A", "D"]);
+
+    // The global cap applies across repetitions
+    let step = PathStep::new("depends_on".to_string(), "both".to_string()).with_hops(1, 3);
+    let result = kb.traverse("A", vec![step], 2).unwrap();
+    assert_eq!(result.paths.len(), 2);
+
+    let step = PathStep::new("depends_on".to_string(), "out".to_string()).with_hops(3, 1);
+    assert!(kb.traverse("A", vec![step], 50).is_err());
+
+    // Paths longer than MEMORY_MAX_TRAVERSE_DEPTH (10 by default) are refused
+    let step = PathStep::new("depends_on".to_string(), "both".to_string());
+    let err = kb.traverse("A", vec![step.clone(); 11], 50).unwrap_err();
+    assert!(err.is::<InvalidParams>());
+    let long = vec![step.clone().with_hops(1, 6), step.with_hops(1, 5)];
+    assert!(kb.traverse("A", long, 50).unwrap_err().is::<InvalidParams>());
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_self_loop_relations() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![
+        Entity::new("Worker".to_string(), "Service".to_string()),
+        Entity::new("Queue".to_string(), "Service".to_string()),
+    ])
+    .unwrap();
+    let created = kb
+        .create_relations(vec![
+            Relation::new("Worker".to_string(), "Worker".to_string(), "retries".to_string()),
+            Relation::new("Worker".to_string(), "Queue".to_string(), "retries".to_string()),
+        ])
+        .unwrap();
+    assert_eq!(created.len(), 2);
+
+    // The entity is its own neighbour once, in the queried direction
+    for (direction, collapse, expected) in [
+        ("outgoing", false, vec![("Worker", "outgoing"), ("Queue", "outgoing")]),
+        ("incoming", false, vec![("Worker", "incoming")]),
+        ("both", true, vec![("Worker", "bidirectional"), ("Queue", "outgoing")]),
+        ("both", false, vec![("Worker", "bidirectional"), ("Queue", "outgoing")]),
+    ] {
+        let options = RelatedOptions {
+            direction: direction.to_string(),
+            collapse_reciprocal: collapse,
+            ..Default::default()
+        };
+        let related = kb.get_related("Worker", &options).unwrap();
+        let found: Vec<(&str, &str)> = related
+            .relations
+            .iter()
+            .map(|r| (r.entity.name.as_str(), r.direction.as_str()))
+            .collect();
+        assert_eq!(found, expected, "direction {}", direction);
+    }
+
+    // A single hop follows the loop once; repeating steps do not revisit it
+    for direction in ["out", "in", "both"] {
+        let step = PathStep::new("retries".to_string(), direction.to_string());
+        let result = kb.traverse("Worker", vec![step], 50).unwrap();
+        let loops = result
+            .paths
+            .iter()
+            .filter(|p| p.nodes == ["Worker", "Worker"])
+            .count();
+        assert_eq!(loops, 1, "direction {}", direction);
+    }
+    let step = PathStep::new("retries".to_string(), "out".to_string()).with_hops(1, 3);
+    let result = kb.traverse("Worker", vec![step], 50).unwrap();
+    assert_eq!(result.paths.len(), 1);
+    assert_eq!(result.paths[0].nodes, vec!["Worker", "Queue"]);
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_traverse_direction_validation() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![
+        Entity::new("Api".to_string(), "Module".to_string()),
+        Entity::new("Auth".to_string(), "Module".to_string()),
+    ])
+    .unwrap();
+    kb.create_relations(vec![Relation::new(
+        "Api".to_string(),
+        "Auth".to_string(),
+        "depends_on".to_string(),
+    )])
+    .unwrap();
+
+    let tool = TraverseTool::new(Arc::clone(&kb));
+    let end_nodes = |start: &str, step: serde_json::Value| -> Vec<String> {
+        let result = tool
+            .execute(json!({"startNode": start, "path": [step]}))
+            .unwrap();
+        let text = result["content"][0]["text"].as_str().unwrap();
+        let result: serde_json::Value = serde_json::from_str(text).unwrap();
+        result["endNodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["name"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    // Valid, aliased and default directions
+    assert_eq!(
+        end_nodes("Api", json!({"relationType": "depends_on", "direction": "out"})),
+        ["Auth"]
+    );
+    assert_eq!(
+        end_nodes("Api", json!({"relationType": "depends_on", "direction": "outgoing"})),
+        ["Auth"]
+    );
+    assert_eq!(
+        end_nodes("Auth", json!({"relationType": "depends_on", "direction": "Incoming"})),
+        ["Api"]
+    );
+    assert_eq!(end_nodes("Api", json!({"relationType": "depends_on"})), ["Auth"]);
+
+    // A typo is an invalid params error, not an empty result
+    let err = tool
+        .execute(json!({
+            "startNode": "Api",
+            "path": [{"relationType": "depends_on", "direction": "sideways"}]
+        }))
+        .unwrap_err();
+    assert!(err.is::<InvalidParams>());
+    assert!(err.to_string().contains("Unknown direction 'sideways'"));
+
+    let step = PathStep::new("depends_on".to_string(), "sideways".to_string());
+    assert!(kb.traverse("Api", vec![step], 50).unwrap_err().is::<InvalidParams>());
+    let step = PathStep::new("depends_on".to_string(), "outgoing".to_string());
+    assert_eq!(kb.traverse("Api", vec![step], 50).unwrap().end_nodes.len(), 1);
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_references_to_groups_by_type() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![
+        Entity::new("Auth".to_string(), "Module".to_string()),
+        Entity::new("Api".to_string(), "Module".to_string()),
+        Entity::new("Web".to_string(), "Module".to_string()),
+        Entity::new("Alice".to_string(), "Person".to_string()),
+    ])
+    .unwrap();
+    kb.create_relations(vec![
+        Relation::new("Api".to_string(), "Auth".to_string(), "depends_on".to_string()),
+        Relation::new("Web".to_string(), "Auth".to_string(), "depends_on".to_string()),
+        Relation::new("Alice".to_string(), "Auth".to_string(), "maintains".to_string()),
+        Relation::new("Auth".to_string(), "Api".to_string(), "depends_on".to_string()),
+    ])
+    .unwrap();
+
+    let refs = kb.references_to("Auth").unwrap();
+    assert_eq!(refs.total, 3);
+    assert_eq!(refs.groups.len(), 2);
+    assert_eq!(refs.groups[0].relation_type, "depends_on");
+    assert_eq!(refs.groups[0].source_type, "Module");
+    assert_eq!(refs.groups[0].sources, vec!["Api", "Web"]);
+    assert_eq!(refs.groups[1].source_type, "Person");
+    assert!(kb.references_to("Missing").is_err());
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_reload_from_disk_picks_up_external_edits() {
+    let (kb, temp_file) = setup_test_kb();
+
+    kb.create_entities(vec![Entity::new("Old".to_string(), "Module".to_string())])
+        .unwrap();
+
+    // Replace the file behind the knowledge base's back
+    let external = KnowledgeGraph {
+        entities: vec![
+            Entity::new("New".to_string(), "Module".to_string()),
+            Entity::new("Other".to_string(), "Module".to_string()),
+        ],
+        relations: vec![Relation::new(
+            "New".to_string(),
+            "Other".to_string(),
+            "depends_on".to_string(),
+        )],
+    };
+    fs::write(&temp_file, external.to_jsonl().unwrap()).unwrap();
+
+    assert_eq!(kb.reload_from_disk().unwrap(), (2, 1));
+    let graph = kb.read_graph(None, None).unwrap();
+    assert!(graph.entities.iter().all(|e| e.name != "Old"));
+    assert_eq!(graph.relations.len(), 1);
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_traverse_relation_details() {
+    let id = TEST_COUNTER.fetch_add(1, Ordering::SeqCst);
+    let temp_file = format!("test_memory_{}_{}.jsonl", std::process::id(), id);
+    // Two relations of the same type between the same nodes
+    fs::write(
+        &temp_file,
+        concat!(
+            "{\"name\":\"Api\",\"entityType\":\"Module\",\"observations\":[]}\n",
+            "{\"name\":\"Auth\",\"entityType\":\"Module\",\"observations\":[]}\n",
+            "{\"from\":\"Api\",\"to\":\"Auth\",\"relationType\":\"depends_on\",\"properties\":{\"version\":1}}\n",
+            "{\"from\":\"Api\",\"to\":\"Auth\",\"relationType\":\"depends_on\",\"properties\":{\"version\":2}}\n",
+        ),
+    )
+    .unwrap();
+    let kb = Arc::new(KnowledgeBase::with_file_path(temp_file.clone()));
+    let tool = TraverseTool::new(Arc::clone(&kb));
+    let traverse = |details: bool| -> serde_json::Value {
+        let result = tool
+            .execute(json!({
+                "startNode": "Api",
+                "path": [{"relationType": "depends_on"}],
+                "includeRelationDetails": details
+            }))
+            .unwrap();
+        serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap()
+    };
+
+    let result = traverse(false);
+    assert!(result["paths"][0].get("relationDetails").is_none());
+
+    let result = traverse(true);
+    let versions: Vec<i64> = result["paths"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| {
+            assert_eq!(p["relations"], json!(["depends_on"]));
+            p["relationDetails"][0]["properties"]["version"].as_i64().unwrap()
+        })
+        .collect();
+    assert_eq!(versions, vec![1, 2]);
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_load_quarantines_corrupt_lines() {
+    let id = TEST_COUNTER.fetch_add(1, Ordering::SeqCst);
+    let temp_file = format!("test_memory_{}_{}.jsonl", std::process::id(), id);
+    let quarantine_file = format!("{}.quarantine", temp_file);
+    fs::write(
+        &temp_file,
+        concat!(
+            "{\"name\":\"Auth\",\"entityType\":\"Module\",\"observations\":[]}\n",
+            "{\"name\":\"Api\",\"entityType\":\"Mod\n",
+            "{\"name\":\"Api\",\"entityType\":\"Module\",\"observations\":[]}\n",
+            "{\"from\":\"Api\",\"to\":\"Auth\",\"relationType\":\"depends_on\"}\n",
+            "{\"unexpected\":true}\n",
+        ),
+    )
+    .unwrap();
+
+    let kb = KnowledgeBase::with_file_path(temp_file.clone());
+    let graph = kb.read_graph(None, None).unwrap();
+    assert_eq!(graph.entities.len(), 2);
+    assert_eq!(graph.relations.len(), 1);
+
+    // Both corrupt lines are kept with their line numbers
+    let quarantined: Vec<serde_json::Value> = fs::read_to_string(&quarantine_file)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(quarantined.len(), 2);
+    assert_eq!(quarantined[0]["line"], 2);
+    assert_eq!(quarantined[0]["content"], "{\"name\":\"Api\",\"entityType\":\"Mod");
+    assert_eq!(quarantined[1]["line"], 5);
+
+    cleanup(&temp_file);
+    cleanup(&quarantine_file);
+}
+
+#[test]
+fn test_seed_loaded_only_into_empty_graph() {
+    let (kb, temp_file) = setup_test_kb();
+    let seed_file = format!("{}.seed", temp_file);
+    let seed = KnowledgeGraph {
+        entities: vec![
+            Entity::with_observations(
+                "Conventions".to_string(),
+                "Reference".to_string(),
+                vec!["Use snake_case relation types".to_string()],
+            ),
+            Entity::new("Module".to_string(), "EntityType".to_string()),
+        ],
+        relations: vec![Relation::new(
+            "Conventions".to_string(),
+            "Module".to_string(),
+            "describes".to_string(),
+        )],
+    };
+    fs::write(&seed_file, seed.to_jsonl().unwrap()).unwrap();
+
+    // Empty graph: seed is imported and persisted
+    let report = kb.seed_if_empty(&seed_file).unwrap().unwrap();
+    assert_eq!(report.entities_created, 2);
+    assert_eq!(report.relations_created, 1);
+    let reloaded = KnowledgeBase::with_file_path(temp_file.clone());
+    assert_eq!(reloaded.read_graph(None, None).unwrap().entities.len(), 2);
+
+    // Non-empty graph: left untouched
+    let (other, other_file) = setup_test_kb();
+    other
+        .create_entities(vec![Entity::new("Existing".to_string(), "Module".to_string())])
+        .unwrap();
+    assert!(other.seed_if_empty(&seed_file).unwrap().is_none());
+    let graph = other.read_graph(None, None).unwrap();
+    assert_eq!(graph.entities.len(), 1);
+    assert_eq!(graph.entities[0].name, "Existing");
+
+    // A missing seed file is not an error
+    assert!(kb.seed_if_empty("missing_seed.jsonl").unwrap().is_none());
+
+    cleanup(&temp_file);
+    cleanup(&other_file);
+    cleanup(&seed_file);
+}
+
+#[test]
+fn test_entities_addressable_by_id() {
+    let (kb, temp_file) = setup_test_kb();
+    let created = kb
+        .create_entities(vec![
+            Entity::new("Api".to_string(), "Module".to_string()),
+            Entity::new("Auth".to_string(), "Module".to_string()),
+        ])
+        .unwrap();
+    let (api_id, auth_id) = (created[0].id.clone(), created[1].id.clone());
+    assert!(!api_id.is_empty());
+    assert_ne!(api_id, auth_id);
+
+    kb.create_relations(vec![Relation::new(
+        api_id.clone(),
+        auth_id.clone(),
+        "depends_on".to_string(),
+    )])
+    .unwrap();
+
+    // Ids and names address the same entity
+    let opened = kb.open_nodes(vec![api_id.clone(), "Auth".to_string()]).unwrap();
+    assert_eq!(opened.entities.len(), 2);
+    assert_eq!(opened.relations.len(), 1);
+    assert_eq!(opened.relations[0].from, "Api");
+    let related = kb
+        .get_related(
+            &auth_id,
+            &RelatedOptions {
+                direction: "incoming".to_string(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(related.relations.len(), 1);
+
+    // Ids are persisted with the entity
+    let reloaded = KnowledgeBase::with_file_path(temp_file.clone());
+    let graph = reloaded.open_nodes(vec![auth_id.clone()]).unwrap();
+    assert_eq!(graph.entities[0].name, "Auth");
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_find_orphans() {
+    let (kb, temp_file) = setup_test_kb();
+    kb.create_entities(vec![
+        Entity::new("Api".to_string(), "Module".to_string()),
+        Entity::new("Auth".to_string(), "Module".to_string()),
+        Entity::new("Cache".to_string(), "Module".to_string()),
+        Entity::new("Crash on login".to_string(), "Bug".to_string()),
+    ])
+    .unwrap();
+    kb.create_relations(vec![Relation::new(
+        "Api".to_string(),
+        "Auth".to_string(),
+        "depends_on".to_string(),
+    )])
+    .unwrap();
+
+    assert_eq!(kb.find_orphans(None), vec!["Cache", "Crash on login"]);
+    assert_eq!(kb.find_orphans(Some("bug".to_string())), vec!["Crash on login"]);
+
+    kb.delete_entities(vec!["Api".to_string()]).unwrap();
+    assert_eq!(kb.find_orphans(Some("Module".to_string())), vec!["Auth", "Cache"]);
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_k_shortest_paths() {
+    let (kb, temp_file) = setup_test_kb();
+    kb.create_entities(
+        ["Api", "Auth", "Cache", "Db", "Docs"]
+            .iter()
+            .map(|n| Entity::new(n.to_string(), "Module".to_string()))
+            .collect(),
+    )
+    .unwrap();
+    let relation = |from: &str, to: &str, relation_type: &str| {
+        Relation::new(from.to_string(), to.to_string(), relation_type.to_string())
+    };
+    kb.create_relations(vec![
+        relation("Api", "Db", "writes"),
+        relation("Api", "Auth", "uses"),
+        relation("Auth", "Db", "reads"),
+        relation("Cache", "Api", "serves"),
+        relation("Cache", "Db", "mirrors"),
+    ])
+    .unwrap();
+
+    let paths = kb.k_shortest_paths("Api", "Db", 5, 6).unwrap();
+    let nodes: Vec<Vec<&str>> = paths
+        .iter()
+        .map(|p| p.nodes.iter().map(String::as_str).collect())
+        .collect();
+    assert_eq!(
+        nodes,
+        vec![
+            vec!["Api", "Db"],
+            vec!["Api", "Auth", "Db"],
+            vec!["Api", "Cache", "Db"],
+        ]
+    );
+    assert_eq!(paths[2].relations, vec!["serves", "mirrors"]);
+
+    // k and maxDepth bound the result
+    assert_eq!(kb.k_shortest_paths("Api", "Db", 2, 6).unwrap().len(), 2);
+    assert_eq!(kb.k_shortest_paths("Api", "Db", 5, 1).unwrap().len(), 1);
+
+    assert!(kb.k_shortest_paths("Api", "Docs", 3, 6).unwrap().is_empty());
+    assert!(kb.k_shortest_paths("Api", "Missing", 3, 6).is_err());
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_infer_schema() {
+    let (kb, temp_file) = setup_test_kb();
+    kb.create_entities(vec![
+        Entity::new("Api".to_string(), "Module".to_string()),
+        Entity::new("Auth".to_string(), "Module".to_string()),
+        Entity::new("Crash".to_string(), "Bug".to_string()),
+        Entity::new("Typo".to_string(), "Bug".to_string()),
+    ])
+    .unwrap();
+    let relation = |from: &str, to: &str, relation_type: &str| {
+        Relation::new(from.to_string(), to.to_string(), relation_type.to_string())
+    };
+    kb.create_relations(vec![
+        relation("Crash", "Auth", "affects"),
+        relation("Typo", "Api", "affects"),
+        relation("Api", "Auth", "depends_on"),
+    ])
+    .unwrap();
+
+    let schema = kb.infer_schema();
+    assert_eq!(schema.entity_types["Module"], 2);
+    assert_eq!(schema.entity_types["Bug"], 2);
+    let triples: Vec<(&str, &str, &str, usize)> = schema
+        .relations
+        .iter()
+        .map(|e| {
+            (
+                e.source_type.as_str(),
+                e.relation_type.as_str(),
+                e.target_type.as_str(),
+                e.count,
+            )
+        })
+        .collect();
+    assert_eq!(
+        triples,
+        vec![
+            ("Bug", "affects", "Module", 2),
+            ("Module", "depends_on", "Module", 1),
+        ]
+    );
+    assert_eq!(schema.dangling_relations, 0);
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_historize_relations_tool() {
+    let (kb, temp_file) = setup_test_kb();
+    kb.create_entities(vec![
+        Entity::with_observations(
+            "Alice".to_string(),
+            "Person".to_string(),
+            vec!["Joined: 2021-06-01".to_string()],
+        ),
+        Entity::new("Acme".to_string(), "Company".to_string()),
+    ])
+    .unwrap();
+    kb.create_relations(vec![Relation::new(
+        "Alice".to_string(),
+        "Acme".to_string(),
+        "works_at".to_string(),
+    )])
+    .unwrap();
+
+    let tool = HistorizeRelationsTool::new(Arc::clone(&kb));
+    let run = |params: serde_json::Value| -> serde_json::Value {
+        let result = tool.execute(params).unwrap();
+        serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap()
+    };
+
+    let preview = run(json!({
+        "relationType": "works_at",
+        "validFrom": "source_attribute:Joined",
+        "dryRun": true
+    }));
+    assert_eq!(preview["changed"], 1);
+    assert_eq!(preview["changes"][0]["validFrom"], 1622505600);
+    assert_eq!(kb.get_relations_at_time(Some(1), None).unwrap().len(), 1);
+
+    let applied = run(json!({"relationType": "works_at", "validFrom": "source_attribute:Joined"}));
+    assert_eq!(applied["changed"], 1);
+    assert!(kb.get_relations_at_time(Some(1), None).unwrap().is_empty());
+
+    // Persisted and idempotent
+    let reloaded = KnowledgeBase::with_file_path(temp_file.clone());
+    let graph = reloaded.read_graph(None, None).unwrap();
+    assert_eq!(graph.relations[0].valid_from, Some(1622505600));
+    assert_eq!(
+        run(json!({"validFrom": "source_attribute:Joined"}))["changed"],
+        0
+    );
+    assert!(tool.execute(json!({"validFrom": "created_at"})).is_err());
+    assert!(tool.execute(json!({"relationType": "works_at"})).is_err());
+
+    cleanup(&temp_file);
+}
+
+#[test]
+fn test_estimate_cost_tool() {
+    let (kb, temp_file) = setup_test_kb();
+    kb.create_entities(vec![
+        Entity::new("Api".to_string(), "Module".to_string()),
+        Entity::new("Auth".to_string(), "Module".to_string()),
+    ])
+    .unwrap();
+    kb.create_relations(vec![Relation::new(
+        "Api".to_string(),
+        "Auth".to_string(),
+        "depends_on".to_string(),
+    )])
+    .unwrap();
+
+    let tool = EstimateCostTool::new(Arc::clone(&kb));
+    let estimate = |params: serde_json::Value| -> serde_json::Value {
+        let result = tool.execute(params).unwrap();
+        serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap()
+    };
+
+    let traverse = estimate(json!({
+        "operation": "traverse",
+        "path": [{"relationType": "depends_on", "direction": "both"}]
+    }));
+    assert_eq!(traverse["operation"], "traverse");
+    assert_eq!(traverse["estimatedVisits"], 1);
+    assert_eq!(traverse["allowed"], true);
+    let infer = estimate(json!({"operation": "infer", "maxDepth": 5}));
+    assert!(infer["estimatedVisits"].as_u64().unwrap() <= 2);
+    assert!(tool.execute(json!({"operation": "search"})).is_err());
+
+    cleanup(&temp_file);
+}
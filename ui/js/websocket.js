@@ -130,6 +130,11 @@ class MemoryGraphWS {
                 this.emit('relationCreated', data.payload);
                 break;
 
+            case 'relation_updated':
+                console.log('🕒 Relation updated:', data.payload?.from, '→', data.payload?.to);
+                this.emit('relationUpdated', data.payload);
+                break;
+
             case 'relation_deleted':
                 console.log('✂️ Relation deleted:', data.from, '→', data.to);
                 this.emit('relationDeleted', {
@@ -143,6 +148,12 @@ class MemoryGraphWS {
                 this.handleBatchUpdate(data.events || data.payload);
                 break;
 
+            case 'resync':
+                // The whole graph was replaced on the server (reload, restore)
+                console.log('🔄 Graph replaced on server:', data.reason);
+                this.emit('fullRefreshNeeded', { reason: data.reason });
+                break;
+
             case 'pong':
                 // Heartbeat response received
                 break;